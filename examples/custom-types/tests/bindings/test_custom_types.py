@@ -1,3 +1,4 @@
+import dataclasses
 from custom_types import *
 
 # Get the custom types and check their data
@@ -8,6 +9,5 @@ assert val.url.path == '/'
 assert val.handle == 123
 
 # Change some data and ensure that the round-trip works
-val.url = val.url._replace(netloc='new.example.com')
-val.handle = 456
+val = dataclasses.replace(val, url=val.url._replace(netloc='new.example.com'), handle=456)
 assert val == get_custom_types_demo(val)
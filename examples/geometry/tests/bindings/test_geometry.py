@@ -8,3 +8,14 @@ assert gradient(ln2) == 1
 
 assert intersection(ln1, ln2) == Point(0, 0)
 assert intersection(ln1, ln1) is None
+
+# `frozen_records` is `false` in `uniffi.toml`, so the fields can be assigned to, and the records
+# can't be hashed.
+ln1.start = Point(0, 1)
+assert gradient(ln1) == 1
+assert ln1 == Line(Point(0, 1), Point(1, 2))
+try:
+    hash(ln1)
+    raise AssertionError("mutable records shouldn't be hashable")
+except TypeError:
+    pass
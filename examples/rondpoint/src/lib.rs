@@ -270,6 +270,10 @@ impl Optionneur {
     fn sinon_enum(&self, value: Enumeration) -> Enumeration {
         value
     }
+
+    fn sinon_dictionnaire(&self, value: OptionneurDictionnaire) -> OptionneurDictionnaire {
+        value
+    }
 }
 
 pub struct OptionneurDictionnaire {
@@ -287,6 +291,7 @@ pub struct OptionneurDictionnaire {
     string_var: String,
     list_var: Vec<String>,
     enumeration_var: Enumeration,
+    enumeration_avec_donnees_var: EnumerationAvecDonnees,
     dictionnaire_var: Option<minusculeMAJUSCULEEnum>,
 }
 
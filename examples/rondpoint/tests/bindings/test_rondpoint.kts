@@ -181,6 +181,9 @@ assert(op.sinonF64() == 42.1)
 // enums
 assert(op.sinonEnum() == Enumeration.TROIS)
 
+// records
+assert(op.sinonDictionnaire() == OptionneurDictionnaire())
+
 // Step 2. Convince ourselves that if we pass something else, then that changes the output.
 //         We have shown something coming out of the sinon methods, but without eyeballing the Rust
 //         we can't be sure that the arguments will change the return value.
@@ -239,6 +242,7 @@ val explicite = OptionneurDictionnaire(
     stringVar = "default",
     listVar = listOf(),
     enumerationVar = Enumeration.DEUX,
+    enumerationAvecDonneesVar = EnumerationAvecDonnees.Zero,
     dictionnaireVar = null
 )
 assert(defaultes == explicite)
@@ -1,5 +1,6 @@
 import sys
 import ctypes
+import pickle
 from rondpoint import *
 
 dico = Dictionnaire(Enumeration.DEUX, True, 0, 123456789)
@@ -7,6 +8,10 @@ copyDico = copie_dictionnaire(dico)
 assert dico == copyDico
 
 assert copie_enumeration(Enumeration.DEUX) == Enumeration.DEUX
+# `enum_class` is set to `StrEnum` in `uniffi.toml`, so the values are the variant names.
+assert Enumeration.DEUX == "Deux"
+assert str(Enumeration.DEUX) == "Deux"
+assert Enumeration("Trois") is Enumeration.TROIS
 assert copie_enumerations([Enumeration.UN, Enumeration.DEUX]) == [Enumeration.UN, Enumeration.DEUX]
 assert copie_carte({
     "0": EnumerationAvecDonnees.ZERO(),
@@ -23,6 +28,16 @@ assert switcheroo(False) is True
 assert EnumerationAvecDonnees.ZERO() != EnumerationAvecDonnees.UN(1)
 assert EnumerationAvecDonnees.UN(1) == EnumerationAvecDonnees.UN(1)
 assert EnumerationAvecDonnees.UN(1) != EnumerationAvecDonnees.UN(2)
+assert EnumerationAvecDonnees.ZERO() != 0
+assert repr(EnumerationAvecDonnees.DEUX(2, "deux")) == "EnumerationAvecDonnees.DEUX(premier=2, second='deux')"
+
+# Records and enums are hashed by value.
+assert hash(dico) == hash(copyDico)
+assert len({EnumerationAvecDonnees.UN(1), EnumerationAvecDonnees.UN(1), EnumerationAvecDonnees.ZERO()}) == 2
+
+# Records and enums can be pickled, for example to be sent to another process.
+for value in [dico, Enumeration.DEUX, EnumerationAvecDonnees.ZERO(), EnumerationAvecDonnees.DEUX(2, "deux")]:
+    assert pickle.loads(pickle.dumps(value)) == value
 
 # Test the roundtrip across the FFI.
 # This shows that the values we send come back in exactly the same state as we sent them.
@@ -144,3 +159,12 @@ affirmEnchaine(
   st.to_string_double,
   rustyFloatToStr,
 )
+
+# Default arguments
+op = Optionneur()
+assert op.sinon_string() == "default"
+assert op.sinon_enum() == Enumeration.TROIS
+assert op.sinon_dictionnaire() == OptionneurDictionnaire()
+assert op.sinon_dictionnaire(OptionneurDictionnaire(i8_var=1)).i8_var == 1
+assert OptionneurDictionnaire().enumeration_var == Enumeration.DEUX
+assert OptionneurDictionnaire().enumeration_avec_donnees_var == EnumerationAvecDonnees.ZERO()
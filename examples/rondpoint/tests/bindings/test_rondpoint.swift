@@ -224,6 +224,7 @@ let explicite = OptionneurDictionnaire(
     stringVar: "default",
     listVar: [],
     enumerationVar: .deux,
+    enumerationAvecDonneesVar: .zero,
     dictionnaireVar: nil
 )  
 
@@ -81,3 +81,16 @@ TodoList().use { todo2 ->
 todo.destroy()
 todo.destroy()
 
+
+// Objects can be substituted through their generated interface, e.g. with fakes in unit tests.
+class FakeTodoList(real: TodoList) : TodoListInterface by real {
+    override fun getFirst() = "fake"
+}
+
+fun firstItem(list: TodoListInterface) = list.getFirst()
+
+TodoList().use { real ->
+    real.addItem("real")
+    assert(firstItem(real) == "real")
+    assert(firstItem(FakeTodoList(real)) == "fake")
+}
@@ -2,6 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use std::sync::Arc;
+
 trait ForeignGetters {
     fn get_bool(&self, v: bool, argument_two: bool) -> Result<bool, SimpleError>;
     fn get_string(&self, v: String, arg2: bool) -> Result<String, SimpleError>;
@@ -117,4 +119,62 @@ impl RustStringifier {
     }
 }
 
+#[derive(Debug)]
+pub struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    fn new(value: u32) -> Self {
+        Counter { value }
+    }
+
+    fn get_value(&self) -> u32 {
+        self.value
+    }
+}
+
+trait CounterFactory {
+    fn make_counter(&self, value: u32) -> Arc<Counter>;
+    fn pass_through(&self, counter: Arc<Counter>) -> Arc<Counter>;
+    fn make_counters(&self, count: u32) -> Vec<Arc<Counter>>;
+}
+
+#[derive(Debug, Default)]
+pub struct RustCounterReader;
+
+impl RustCounterReader {
+    fn new() -> Self {
+        RustCounterReader
+    }
+
+    fn read_new(&self, factory: Box<dyn CounterFactory>, value: u32) -> u32 {
+        factory.make_counter(value).get_value()
+    }
+
+    fn read_passed_through(&self, factory: Box<dyn CounterFactory>, value: u32) -> u32 {
+        let counter = Arc::new(Counter::new(value));
+        let returned = factory.pass_through(Arc::clone(&counter));
+        assert!(Arc::ptr_eq(&counter, &returned));
+        returned.get_value()
+    }
+
+    fn sum_new(&self, factory: Box<dyn CounterFactory>, count: u32) -> u32 {
+        factory
+            .make_counters(count)
+            .iter()
+            .map(|counter| counter.get_value())
+            .sum()
+    }
+}
+
+// Callback functions are received as boxed closures, which any `impl Fn` argument accepts.
+fn filter_strings(values: Vec<String>, filter: impl Fn(String) -> bool) -> Vec<String> {
+    values.into_iter().filter(|v| filter(v.clone())).collect()
+}
+
+fn apply_twice(transform: IntTransform, value: i32) -> Result<i32, SimpleError> {
+    transform(transform(value)?)
+}
+
 include!(concat!(env!("OUT_DIR"), "/callbacks.uniffi.rs"));
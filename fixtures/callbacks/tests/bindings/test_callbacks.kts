@@ -3,6 +3,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 import uniffi.fixture_callbacks.*
+import java.lang.ref.WeakReference
 
 // A bit more systematic in testing, but this time in English.
 //
@@ -117,3 +118,73 @@ listOf(1, 2).forEach { v ->
     assert(expected == observed) { "callback is sent on construction: $expected != $observed" }
 }
 rustStringifier.destroy()
+
+// The same callback object can be held by Rust several times, and stays usable until Rust frees
+// all of them.
+val firstStringifier = RustStringifier(kotlinStringifier)
+val secondStringifier = RustStringifier(kotlinStringifier)
+firstStringifier.destroy()
+assert(secondStringifier.fromSimpleType(3) == "kotlin: 3")
+secondStringifier.destroy()
+
+// Kotlin registers its callbacks in the first slot, and the first callback passed to Rust above got
+// the first handle, which is 0 with that slot. The slots of the handle map are freed when Rust drops
+// the callbacks, which releases them, and the callbacks passed afterwards are still called.
+class NumberedStringifier(val number: Int): StoredForeignStringifier {
+    override fun fromSimpleType(value: Int): String = "$number: $value"
+    override fun fromComplexType(values: List<Double?>?): String = "$number: $values"
+}
+
+fun storeAndDrop(number: Int): WeakReference<NumberedStringifier> {
+    val stringifier = NumberedStringifier(number)
+    val rustStringifier = RustStringifier(stringifier)
+    assert(rustStringifier.fromSimpleType(number) == "$number: $number")
+    rustStringifier.destroy()
+    return WeakReference(stringifier)
+}
+
+val dropped = (0 until 300).map(::storeAndDrop)
+for (attempt in 0 until 50) {
+    if (dropped.all { it.get() == null }) break
+    System.gc()
+    Thread.sleep(10)
+}
+assert(dropped.all { it.get() == null }) { "the callbacks dropped by Rust are still referenced" }
+
+// 3. Return Rust objects from the callbacks, whether they were created by Kotlin or by Rust.
+
+class KotlinCounterFactory: CounterFactory {
+    override fun makeCounter(value: UInt): Counter = Counter(value)
+    override fun passThrough(counter: Counter): Counter = counter
+    override fun makeCounters(count: UInt): List<Counter> = (1u..count).map { Counter(it) }
+}
+
+val counterReader = RustCounterReader()
+val counterFactory = KotlinCounterFactory()
+assert(counterReader.readNew(counterFactory, 42u) == 42u)
+assert(counterReader.readPassedThrough(counterFactory, 7u) == 7u)
+assert(counterReader.sumNew(counterFactory, 4u) == 10u)
+counterReader.destroy()
+
+// 4. Pass closures to Rust for its callback functions.
+
+assert(filterStrings(listOf("a", "bb", "ccc")) { it.length > 1 } == listOf("bb", "ccc"))
+assert(applyTwice({ it * 3 }, 2) == 18)
+try {
+    applyTwice({ throw SimpleException.BadArgument("bad argument") }, 2)
+    throw RuntimeException("Expected SimpleException.BadArgument")
+} catch (e: SimpleException.BadArgument) {
+    // It's okay!
+}
+
+// The callbacks can call back into Rust, which calls them again, on the same thread.
+assert(applyTwice({ value -> if (value < 10) applyTwice({ it + 1 }, value) else value }, 1) == 5)
+
+// Calling each other endlessly fails once the calls are nested too deeply.
+fun endless(value: Int): Int = applyTwice(::endless, value)
+try {
+    endless(1)
+    throw RuntimeException("Expected SimpleException.UnexpectedException")
+} catch (e: SimpleException.UnexpectedException) {
+    // It's okay!
+}
@@ -3,8 +3,18 @@
 # file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 from fixture_callbacks import *
+from concurrent.futures import ThreadPoolExecutor
+import textwrap
 import unittest
 
+try:
+    import _interpreters as interpreters
+except ImportError:
+    try:
+        import _xxsubinterpreters as interpreters
+    except ImportError:
+        interpreters = None
+
 # A bit more systematic in testing, but this time in English.
 #
 # 1. Pass in the callback as arguments.
@@ -82,6 +92,67 @@ class ForeignGettersTest(unittest.TestCase):
         self.assertEqual(rust_getters.get_string_optional_callback(PythonGetters(), "TestString", False), "TestString")
         self.assertEqual(rust_getters.get_string_optional_callback(None, "TestString", False), None)
 
+    def test_callbacks_from_threads(self):
+        # The GIL is released while Rust runs, and reacquired for calling back into Python, so
+        # many threads can go through Rust and back concurrently.
+        callback = PythonGetters()
+        with ThreadPoolExecutor(max_workers=8) as executor:
+            values = [f"value{i}" for i in range(100)]
+            observed = list(executor.map(lambda v: rust_getters.get_string(callback, v, True), values))
+        self.assertEqual(observed, [callback.get_string(v, True) for v in values])
+
+    @unittest.skipIf(interpreters is None, "sub-interpreters aren't supported by this Python")
+    def test_callbacks_from_sub_interpreters(self):
+        # Each sub-interpreter imports its own copy of the module, which registers its own callbacks
+        # with Rust. Rust must call back into the interpreter which passed the object.
+        script = textwrap.dedent("""
+            from fixture_callbacks import ForeignGetters, RustGetters
+
+            class SubGetters(ForeignGetters):
+                def get_string(self, v, arg2):
+                    return "sub: " + v
+
+            rust_getters = RustGetters()
+            for i in range(50):
+                assert rust_getters.get_string(SubGetters(), str(i), False) == "sub: " + str(i)
+        """)
+
+        def run_in_sub_interpreter(_):
+            interp = interpreters.create()
+            try:
+                interpreters.run_string(interp, script)
+            finally:
+                interpreters.destroy(interp)
+
+        callback = PythonGetters()
+        with ThreadPoolExecutor(max_workers=4) as executor:
+            sub_interpreters = [executor.submit(run_in_sub_interpreter, i) for i in range(4)]
+            for i in range(50):
+                self.assertEqual(rust_getters.get_string(callback, str(i), False), str(i))
+            for future in sub_interpreters:
+                future.result()
+
+    @unittest.skipIf(interpreters is None, "sub-interpreters aren't supported by this Python")
+    def test_sub_interpreters_free_their_callbacks(self):
+        # An interpreter unregisters its callbacks when it exits, so more interpreters than there
+        # are slots can use them one after the other.
+        script = textwrap.dedent("""
+            from fixture_callbacks import ForeignGetters, RustGetters
+
+            class SubGetters(ForeignGetters):
+                def get_string(self, v, arg2):
+                    return "sub: " + v
+
+            assert RustGetters().get_string(SubGetters(), "a", False) == "sub: a"
+        """)
+        for _ in range(300):
+            interp = interpreters.create()
+            try:
+                interpreters.run_string(interp, script)
+            finally:
+                interpreters.destroy(interp)
+        self.assertEqual(rust_getters.get_string(PythonGetters(), "main", False), "main")
+
 # 2. Pass the callback in as a constructor argument, to be stored on the Object struct.
 # This is crucial if we want to configure a system at startup,
 # then use it without passing callbacks all the time.
@@ -116,4 +187,51 @@ class TestCallbackErrors(unittest.TestCase):
             rust_getters.get_option(callback, "unexpected-error", True)
         self.assertEqual(cm.exception.reason, repr(ValueError("unexpected value")))
 
+# 3. Return Rust objects from the callbacks, whether they were created by Python or by Rust.
+# The counters made by Python are freed as soon as the callbacks return.
+
+class PythonCounterFactory(CounterFactory):
+    def make_counter(self, value):
+        return Counter(value)
+
+    def pass_through(self, counter):
+        return counter
+
+    def make_counters(self, count):
+        return [Counter(value) for value in range(1, count + 1)]
+
+class TestCallbackObjects(unittest.TestCase):
+    def test_returned_objects(self):
+        counter_reader = RustCounterReader()
+        counter_factory = PythonCounterFactory()
+        self.assertEqual(counter_reader.read_new(counter_factory, 42), 42)
+        self.assertEqual(counter_reader.read_passed_through(counter_factory, 7), 7)
+        self.assertEqual(counter_reader.sum_new(counter_factory, 4), 10)
+
+class TestCallbackFunctions(unittest.TestCase):
+    def test_closures(self):
+        self.assertEqual(filter_strings(["a", "bb", "ccc"], lambda value: len(value) > 1), ["bb", "ccc"])
+        self.assertEqual(apply_twice(lambda value: value * 3, 2), 18)
+
+    def test_errors(self):
+        def fail(value):
+            raise SimpleError.BadArgument()
+
+        with self.assertRaises(SimpleError.BadArgument):
+            apply_twice(fail, 2)
+
+    def test_reentrancy(self):
+        # The callbacks can call back into Rust, which calls them again, on the same thread.
+        def nested(value):
+            return apply_twice(lambda value: value + 1, value) if value < 10 else value
+
+        self.assertEqual(apply_twice(nested, 1), 5)
+
+        # Calling each other endlessly fails once the calls are nested too deeply.
+        def endless(value):
+            return apply_twice(endless, value)
+
+        with self.assertRaises(SimpleError.UnexpectedError):
+            endless(1)
+
 unittest.main()
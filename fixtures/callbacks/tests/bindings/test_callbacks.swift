@@ -92,6 +92,27 @@ do {
         assert(expected == observed, "callback is sent on construction: \(expected) != \(observed)")
     }
 
+    // Swift registers its callbacks in the first slot, whose handles are the ones of the handle
+    // map. Its slots are freed when Rust drops the callbacks, which releases them, and the callbacks
+    // passed afterwards are still called.
+    class NumberedStringifier: StoredForeignStringifier {
+        let number: Int32
+        init(_ number: Int32) { self.number = number }
+        func fromSimpleType(value: Int32) -> String { "\(number): \(value)" }
+        func fromComplexType(values: [Double?]?) -> String { "\(number): \(String(describing: values))" }
+    }
+
+    for number in Int32(0)..<300 {
+        weak var dropped: NumberedStringifier?
+        do {
+            let stringifier = NumberedStringifier(number)
+            dropped = stringifier
+            let rustStringifier = RustStringifier(callback: stringifier)
+            assert(rustStringifier.fromSimpleType(value: number) == "\(number): \(number)")
+        }
+        assert(dropped == nil, "the callback dropped by Rust is still referenced")
+    }
+
 
     // 3. Error handling
     do {
@@ -124,3 +145,48 @@ do {
         assert(reason == String(describing: SomeOtherError()))
     }
 }
+
+// 3. Return Rust objects from the callbacks, whether they were created by Swift or by Rust.
+// The counters made by Swift are released as soon as the callbacks return.
+class SwiftCounterFactory: CounterFactory {
+    func makeCounter(value: UInt32) -> Counter { Counter(value: value) }
+    func passThrough(counter: Counter) -> Counter { counter }
+    func makeCounters(count: UInt32) -> [Counter] { (1...count).map { Counter(value: $0) } }
+}
+
+do {
+    let counterReader = RustCounterReader()
+    let counterFactory = SwiftCounterFactory()
+    assert(counterReader.readNew(factory: counterFactory, value: 42) == 42)
+    assert(counterReader.readPassedThrough(factory: counterFactory, value: 7) == 7)
+    assert(counterReader.sumNew(factory: counterFactory, count: 4) == 10)
+}
+
+// 4. Pass closures to Rust for its callback functions.
+do {
+    assert(filterStrings(values: ["a", "bb", "ccc"], filter: { $0.count > 1 }) == ["bb", "ccc"])
+    assert(try! applyTwice(transform: { $0 * 3 }, value: 2) == 18)
+    do {
+        _ = try applyTwice(transform: { _ in throw SimpleError.BadArgument(message: "Bad argument") }, value: 2)
+        fatalError("Should have thrown")
+    } catch SimpleError.BadArgument {
+        // It's okay!
+    }
+
+    // The callbacks can call back into Rust, which calls them again, on the same thread.
+    let nested = try! applyTwice(transform: { value in
+        value < 10 ? try applyTwice(transform: { $0 + 1 }, value: value) : value
+    }, value: 1)
+    assert(nested == 5)
+
+    // Calling each other endlessly fails once the calls are nested too deeply.
+    func endless(_ value: Int32) throws -> Int32 {
+        try applyTwice(transform: endless, value: value)
+    }
+    do {
+        _ = try endless(1)
+        fatalError("Should have thrown")
+    } catch SimpleError.UnexpectedError {
+        // It's okay!
+    }
+}
@@ -0,0 +1,79 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct Numbers {
+    u8_value: u8,
+    i8_value: i8,
+    u16_value: u16,
+    i16_value: i16,
+    u32_value: u32,
+    i32_value: i32,
+    u64_value: u64,
+    i64_value: i64,
+    f32_value: f32,
+    f64_value: f64,
+    bool_value: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Point,
+    Circle {
+        radius: f64,
+    },
+    Rectangle {
+        width: u32,
+        height: u32,
+        label: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Holder {
+    name: Option<String>,
+    shapes: Vec<Shape>,
+    counts: HashMap<String, i64>,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShapeError {
+    #[error("The shape is empty")]
+    Empty,
+    #[error("The shape is unknown")]
+    Unknown,
+}
+
+fn echo_numbers(numbers: Numbers) -> Numbers {
+    numbers
+}
+
+fn echo_strings(strings: Vec<String>) -> Vec<String> {
+    strings
+}
+
+fn echo_u64s(values: Vec<u64>) -> Vec<u64> {
+    values
+}
+
+fn echo_shape(shape: Shape) -> Shape {
+    shape
+}
+
+fn echo_holder(holder: Holder) -> Holder {
+    holder
+}
+
+fn parse_shape(value: String) -> Result<Shape, ShapeError> {
+    match value.as_str() {
+        "" => Err(ShapeError::Empty),
+        "point" => Ok(Shape::Point),
+        _ => Err(ShapeError::Unknown),
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/compact_encoding.uniffi.rs"));
@@ -0,0 +1,53 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.compact_encoding.*
+
+// The extremes of each width take the most bytes as varints.
+for (numbers in listOf(
+    Numbers(0u, 0, 0u, 0, 0u, 0, 0uL, 0L, 0.0f, 0.0, false),
+    Numbers(UByte.MAX_VALUE, Byte.MIN_VALUE, UShort.MAX_VALUE, Short.MIN_VALUE, UInt.MAX_VALUE, Int.MIN_VALUE, ULong.MAX_VALUE, Long.MIN_VALUE, 1.5f, -2.25, true),
+    Numbers(127u, Byte.MAX_VALUE, 128u, Short.MAX_VALUE, 16384u, Int.MAX_VALUE, 9223372036854775808uL, Long.MAX_VALUE, -1.5f, 1e300, false),
+    Numbers(1u, -1, 300u, -300, 70000u, -70000, 34359738368uL, -34359738368L, 3.0f, 0.1, true),
+)) {
+    assert(echoNumbers(numbers) == numbers)
+}
+
+// The strings share their length when they all have the same one.
+for (strings in listOf(
+    listOf(),
+    listOf(""),
+    listOf("", ""),
+    listOf("ab", "cd", "ef"),
+    listOf("ab", "c"),
+    listOf("", "a"),
+    listOf("🦀", "ab", "漢"),
+    listOf("x".repeat(200), "y".repeat(200)),
+)) {
+    assert(echoStrings(strings) == strings)
+}
+assert(echoU64s(listOf(0uL, 1uL, 127uL, 128uL, ULong.MAX_VALUE)) == listOf(0uL, 1uL, 127uL, 128uL, ULong.MAX_VALUE))
+
+for (shape in listOf(Shape.Point, Shape.Circle(2.5), Shape.Rectangle(300u, 0u, "wide"))) {
+    assert(echoShape(shape) == shape)
+}
+
+var holder = Holder(null, listOf(), mapOf(), listOf())
+assert(echoHolder(holder) == holder)
+holder = Holder("shapes", listOf(Shape.Point, Shape.Rectangle(1u, 2u, "")), mapOf("a" to -1L, "b" to 1099511627776L), listOf("red", "tan"))
+assert(echoHolder(holder) == holder)
+
+assert(parseShape("point") == Shape.Point)
+try {
+    parseShape("")
+    throw RuntimeException("Should have thrown an Empty error!")
+} catch (e: ShapeException.Empty) {
+    // It's okay!
+}
+try {
+    parseShape("hexagon")
+    throw RuntimeException("Should have thrown an Unknown error!")
+} catch (e: ShapeException.Unknown) {
+    // It's okay!
+}
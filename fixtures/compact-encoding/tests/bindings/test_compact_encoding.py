@@ -0,0 +1,39 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+from compact_encoding import *
+
+# The extremes of each width take the most bytes as varints.
+for numbers in [
+    Numbers(0, 0, 0, 0, 0, 0, 0, 0, 0.0, 0.0, False),
+    Numbers(255, -128, 65535, -32768, 4294967295, -2147483648, 18446744073709551615, -9223372036854775808, 1.5, -2.25, True),
+    Numbers(127, 127, 128, 32767, 16384, 2147483647, 2**63, 9223372036854775807, -1.5, 1e300, False),
+    Numbers(1, -1, 300, -300, 70000, -70000, 2**35, -(2**35), 3.0, 0.1, True),
+]:
+    assert echo_numbers(numbers) == numbers
+
+# The strings share their length when they all have the same one.
+for strings in [[], [""], ["", ""], ["ab", "cd", "ef"], ["ab", "c"], ["", "a"], ["🦀", "ab", "漢"], ["x" * 200, "y" * 200]]:
+    assert echo_strings(strings) == strings
+assert echo_u64s([0, 1, 127, 128, 2**64 - 1]) == [0, 1, 127, 128, 2**64 - 1]
+
+for shape in [Shape.POINT(), Shape.CIRCLE(2.5), Shape.RECTANGLE(300, 0, "wide")]:
+    assert echo_shape(shape) == shape
+
+holder = Holder(None, [], {}, [])
+assert echo_holder(holder) == holder
+holder = Holder("shapes", [Shape.POINT(), Shape.RECTANGLE(1, 2, "")], {"a": -1, "b": 2**40}, ["red", "tan"])
+assert echo_holder(holder) == holder
+
+assert parse_shape("point") == Shape.POINT()
+try:
+    parse_shape("")
+    raise RuntimeError("Should have thrown an Empty error!")
+except ShapeError.Empty:
+    pass
+try:
+    parse_shape("hexagon")
+    raise RuntimeError("Should have thrown an Unknown error!")
+except ShapeError.Unknown:
+    pass
@@ -0,0 +1,51 @@
+# frozen_string_literal: true
+
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+require 'test/unit'
+require 'compact_encoding'
+
+class TestCompactEncoding < Test::Unit::TestCase
+  # The extremes of each width take the most bytes as varints.
+  def test_numbers_round_trip
+    [
+      CompactEncoding::Numbers.new(0, 0, 0, 0, 0, 0, 0, 0, 0.0, 0.0, false),
+      CompactEncoding::Numbers.new(255, -128, 65_535, -32_768, 4_294_967_295, -2_147_483_648, 2**64 - 1, -2**63, 1.5, -2.25, true),
+      CompactEncoding::Numbers.new(127, 127, 128, 32_767, 16_384, 2_147_483_647, 2**63, 2**63 - 1, -1.5, 1e300, false),
+      CompactEncoding::Numbers.new(1, -1, 300, -300, 70_000, -70_000, 2**35, -2**35, 3.0, 0.1, true)
+    ].each do |numbers|
+      assert_equal CompactEncoding.echo_numbers(numbers), numbers
+    end
+  end
+
+  # The strings share their length when they all have the same one.
+  def test_strings_round_trip
+    [[], [''], ['', ''], %w[ab cd ef], %w[ab c], ['', 'a'], %w[🦀 ab 漢], ['x' * 200, 'y' * 200]].each do |strings|
+      assert_equal CompactEncoding.echo_strings(strings), strings
+    end
+    assert_equal CompactEncoding.echo_u64s([0, 1, 127, 128, 2**64 - 1]), [0, 1, 127, 128, 2**64 - 1]
+  end
+
+  def test_enums_and_records_round_trip
+    [CompactEncoding::Shape::POINT.new, CompactEncoding::Shape::CIRCLE.new(2.5), CompactEncoding::Shape::RECTANGLE.new(300, 0, 'wide')].each do |shape|
+      assert_equal CompactEncoding.echo_shape(shape), shape
+    end
+
+    holder = CompactEncoding::Holder.new(nil, [], {}, [])
+    assert_equal CompactEncoding.echo_holder(holder), holder
+    holder = CompactEncoding::Holder.new('shapes', [CompactEncoding::Shape::POINT.new, CompactEncoding::Shape::RECTANGLE.new(1, 2, '')], { 'a' => -1, 'b' => 2**40 }, %w[red tan])
+    assert_equal CompactEncoding.echo_holder(holder), holder
+  end
+
+  def test_errors_round_trip
+    assert_equal CompactEncoding.parse_shape('point'), CompactEncoding::Shape::POINT.new
+    assert_raise CompactEncoding::ShapeError::Empty do
+      CompactEncoding.parse_shape('')
+    end
+    assert_raise CompactEncoding::ShapeError::Unknown do
+      CompactEncoding.parse_shape('hexagon')
+    end
+  end
+end
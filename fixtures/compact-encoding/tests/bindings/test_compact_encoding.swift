@@ -0,0 +1,47 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import compact_encoding
+
+// The extremes of each width take the most bytes as varints.
+for numbers in [
+    Numbers(u8Value: 0, i8Value: 0, u16Value: 0, i16Value: 0, u32Value: 0, i32Value: 0, u64Value: 0, i64Value: 0, f32Value: 0.0, f64Value: 0.0, boolValue: false),
+    Numbers(u8Value: .max, i8Value: .min, u16Value: .max, i16Value: .min, u32Value: .max, i32Value: .min, u64Value: .max, i64Value: .min, f32Value: 1.5, f64Value: -2.25, boolValue: true),
+    Numbers(u8Value: 127, i8Value: .max, u16Value: 128, i16Value: .max, u32Value: 16384, i32Value: .max, u64Value: 1 << 63, i64Value: .max, f32Value: -1.5, f64Value: 1e300, boolValue: false),
+    Numbers(u8Value: 1, i8Value: -1, u16Value: 300, i16Value: -300, u32Value: 70000, i32Value: -70000, u64Value: 1 << 35, i64Value: -(1 << 35), f32Value: 3.0, f64Value: 0.1, boolValue: true),
+] {
+    assert(echoNumbers(numbers: numbers) == numbers)
+}
+
+// The strings share their length when they all have the same one.
+for strings in [
+    [], [""], ["", ""], ["ab", "cd", "ef"], ["ab", "c"], ["", "a"], ["🦀", "ab", "漢"],
+    [String(repeating: "x", count: 200), String(repeating: "y", count: 200)],
+] {
+    assert(echoStrings(strings: strings) == strings)
+}
+assert(echoU64s(values: [0, 1, 127, 128, .max]) == [0, 1, 127, 128, .max])
+
+for shape in [Shape.point, Shape.circle(radius: 2.5), Shape.rectangle(width: 300, height: 0, label: "wide")] {
+    assert(echoShape(shape: shape) == shape)
+}
+
+var holder = Holder(name: nil, shapes: [], counts: [:], tags: [])
+assert(echoHolder(holder: holder) == holder)
+holder = Holder(name: "shapes", shapes: [.point, .rectangle(width: 1, height: 2, label: "")], counts: ["a": -1, "b": 1 << 40], tags: ["red", "tan"])
+assert(echoHolder(holder: holder) == holder)
+
+assert(try! parseShape(value: "point") == .point)
+do {
+    _ = try parseShape(value: "")
+    fatalError("Should have thrown an Empty error!")
+} catch ShapeError.Empty {
+    // It's okay!
+}
+do {
+    _ = try parseShape(value: "hexagon")
+    fatalError("Should have thrown an Unknown error!")
+} catch ShapeError.Unknown {
+    // It's okay!
+}
@@ -0,0 +1,6 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_compact_encoding.kts",
+    "tests/bindings/test_compact_encoding.swift",
+    "tests/bindings/test_compact_encoding.py",
+    "tests/bindings/test_compact_encoding.rb",
+);
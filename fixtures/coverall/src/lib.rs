@@ -7,7 +7,12 @@ use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::SystemTime;
 
+use num_bigint::BigInt;
 use once_cell::sync::Lazy;
+use rust_decimal::Decimal;
+use uniffi::ErrorContext;
+use url::Url;
+use uuid::Uuid;
 
 static NUM_ALIVE: Lazy<RwLock<u64>> = Lazy::new(|| RwLock::new(0));
 
@@ -41,6 +46,20 @@ pub enum ComplexError {
     PermissionDenied { reason: String },
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("The configuration is unreadable")]
+    Unreadable(#[source] ConfigLineError),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid line {line}")]
+pub struct ConfigLineError {
+    line: usize,
+    #[source]
+    source: std::num::ParseIntError,
+}
+
 #[derive(Debug, Clone)]
 pub struct SimpleDict {
     text: String,
@@ -71,6 +90,13 @@ pub struct DictWithDefaults {
     integer: u64,
 }
 
+#[derive(Debug, Clone)]
+pub struct DictWithDefaultsFirst {
+    name: String,
+    tags: Vec<String>,
+    integer: u64,
+}
+
 #[derive(Debug, Clone)]
 pub enum MaybeSimpleDict {
     Yeah { d: SimpleDict },
@@ -129,6 +155,23 @@ fn get_num_alive() -> u64 {
     *NUM_ALIVE.read().unwrap()
 }
 
+fn count_coveralls(coveralls: Vec<Arc<Coveralls>>) -> u64 {
+    coveralls.len() as u64
+}
+
+fn log_at_every_level(message: String) {
+    log::error!("{message}");
+    log::warn!("{message}");
+    log::info!("{message}");
+    log::debug!("{message}");
+    log::trace!("{message}");
+}
+
+fn reverse_bytes(mut input: Vec<u8>) -> Vec<u8> {
+    input.reverse();
+    input
+}
+
 type Result<T, E = CoverallError> = std::result::Result<T, E>;
 type ComplexResult<T, E = ComplexError> = std::result::Result<T, E>;
 
@@ -137,6 +180,20 @@ fn println(text: String) -> Result<()> {
     Ok(())
 }
 
+fn sum_config(text: String) -> Result<u32, ConfigError> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.trim().parse::<u32>().map_err(|source| {
+                ConfigError::Unreadable(ConfigLineError {
+                    line: index + 1,
+                    source,
+                })
+            })
+        })
+        .sum()
+}
+
 #[derive(Debug)]
 pub struct Coveralls {
     name: String,
@@ -169,6 +226,22 @@ impl Coveralls {
         panic!("{message}");
     }
 
+    fn default_name() -> String {
+        "Nameless coveralls".to_string()
+    }
+
+    fn with_default_name() -> Arc<Self> {
+        Arc::new(Self::new(Self::default_name()))
+    }
+
+    fn check_holes(holes: u8) -> Result<()> {
+        if holes > 3 {
+            Err(CoverallError::TooManyHoles)
+        } else {
+            Ok(())
+        }
+    }
+
     fn get_name(&self) -> String {
         self.name.clone()
     }
@@ -194,7 +267,9 @@ impl Coveralls {
     }
 
     fn maybe_throw_complex(&self, input: i8) -> ComplexResult<bool> {
-        match input {
+        // The context is raised with the errors and the panics.
+        uniffi::add_error_context("input", input);
+        let result = match input {
             0 => Ok(true),
             1 => Err(ComplexError::OsError {
                 code: 10,
@@ -204,7 +279,8 @@ impl Coveralls {
                 reason: "Forbidden".to_owned(),
             }),
             _ => panic!("Invalid input"),
-        }
+        };
+        result.error_context("coveralls", &self.name)
     }
 
     fn panic(&self, message: String) {
@@ -296,7 +372,7 @@ pub struct Repair {
     patch: Arc<Patch>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Color {
     Red,
     Blue,
@@ -350,4 +426,140 @@ impl ThreadsafeCounter {
     }
 }
 
+pub trait Getters: Send + Sync {
+    fn get_bool(&self, v: bool, arg2: bool) -> bool;
+    fn get_string(&self, v: String, arg2: bool) -> Result<String>;
+
+    fn strong_count(self: Arc<Self>) -> u64 {
+        Arc::strong_count(&self) as u64
+    }
+}
+
+struct RustGetters;
+
+impl Getters for RustGetters {
+    fn get_bool(&self, v: bool, arg2: bool) -> bool {
+        v ^ arg2
+    }
+
+    fn get_string(&self, v: String, arg2: bool) -> Result<String> {
+        if v == "too-many-holes" {
+            Err(CoverallError::TooManyHoles)
+        } else if arg2 {
+            Ok(v.to_uppercase())
+        } else {
+            Ok(v)
+        }
+    }
+}
+
+struct InvertedGetters;
+
+impl Getters for InvertedGetters {
+    fn get_bool(&self, v: bool, arg2: bool) -> bool {
+        !(v ^ arg2)
+    }
+
+    fn get_string(&self, v: String, arg2: bool) -> Result<String> {
+        RustGetters
+            .get_string(v, arg2)
+            .map(|s| s.chars().rev().collect())
+    }
+}
+
+fn make_rust_getters(inverted: bool) -> Arc<dyn Getters> {
+    if inverted {
+        Arc::new(InvertedGetters)
+    } else {
+        Arc::new(RustGetters)
+    }
+}
+
+fn make_all_rust_getters() -> Vec<Arc<dyn Getters>> {
+    vec![make_rust_getters(false), make_rust_getters(true)]
+}
+
+fn get_bool_with(getters: Arc<dyn Getters>, v: bool, arg2: bool) -> bool {
+    getters.get_bool(v, arg2)
+}
+
+fn multiply_bigints(a: BigInt, b: BigInt) -> BigInt {
+    a * b
+}
+
+fn add_decimals(a: Decimal, b: Decimal) -> Decimal {
+    a + b
+}
+
+fn get_url_host(address: Url) -> Option<String> {
+    address.host_str().map(str::to_string)
+}
+
+fn set_url_path(mut address: Url, path: String) -> Url {
+    address.set_path(&path);
+    address
+}
+
+fn format_uuid(id: Uuid) -> String {
+    id.hyphenated().to_string()
+}
+
+fn parse_uuid(text: String) -> Option<Uuid> {
+    Uuid::parse_str(&text).ok()
+}
+
+fn echo_int_keyed_dict(dict: HashMap<i64, String>) -> HashMap<i64, String> {
+    dict
+}
+
+fn echo_enum_keyed_dict(dict: HashMap<Color, u32>) -> HashMap<Color, u32> {
+    dict
+}
+
+pub struct Stack<T> {
+    items: Mutex<Vec<T>>,
+}
+
+impl<T: Clone> Stack<T> {
+    fn new() -> Self {
+        Self {
+            items: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn push(&self, value: T) {
+        self.items.lock().unwrap().push(value);
+    }
+
+    fn pop(&self) -> Option<T> {
+        self.items.lock().unwrap().pop()
+    }
+
+    fn items(&self) -> Vec<T> {
+        self.items.lock().unwrap().clone()
+    }
+}
+
+type StringStack = Stack<String>;
+type CountStack = Stack<u32>;
+
+pub struct Countdown {
+    remaining: Mutex<u32>,
+}
+
+impl Countdown {
+    fn new(start: u32) -> Self {
+        Self {
+            remaining: Mutex::new(start),
+        }
+    }
+
+    fn next(&self) -> Option<u32> {
+        let mut remaining = self.remaining.lock().unwrap();
+        let current = *remaining;
+        *remaining = current.checked_sub(1)?;
+        Some(current)
+    }
+}
+
 include!(concat!(env!("OUT_DIR"), "/coverall.uniffi.rs"));
@@ -104,6 +104,23 @@ Coveralls("test_return_objects").use { coveralls ->
 // Destroying `coveralls` will kill both.
 assert(getNumAlive() == 0UL);
 
+// Test static methods
+
+assert(Coveralls.defaultName() == "Nameless coveralls")
+Coveralls.withDefaultName().use { coveralls ->
+    assert(getNumAlive() == 1UL)
+    assert(coveralls.getName() == "Nameless coveralls")
+}
+assert(getNumAlive() == 0UL);
+
+Coveralls.checkHoles(3u)
+try {
+    Coveralls.checkHoles(4u)
+    throw RuntimeException("Expected method to throw exception")
+} catch(e: CoverallException.TooManyHoles) {
+    // It's okay!
+}
+
 Coveralls("test_simple_errors").use { coveralls ->
     try {
         coveralls.maybeThrow(true)
@@ -141,6 +158,7 @@ Coveralls("test_complex_errors").use { coveralls ->
         assert(e.toString() == "uniffi.coverall.ComplexException\$OsException: code=10, extendedCode=20") {
             "Unexpected ComplexError.OsError.toString() value: ${e.toString()}"
         }
+        assert(e.errorContext == mapOf("input" to "1", "coveralls" to "test_complex_errors"))
     }
 
     try {
@@ -157,10 +175,25 @@ Coveralls("test_complex_errors").use { coveralls ->
         coveralls.maybeThrowComplex(3)
         throw RuntimeException("Expected method to throw exception")
     } catch(e: InternalException) {
-        // Expected result
+        assert(e.errorContext == mapOf("input" to "3"))
     }
 }
 
+assert(sumConfig("1\n2") == 3u)
+try {
+    sumConfig("1\nx")
+    throw RuntimeException("Expected method to throw exception")
+} catch(e: ConfigException.Unreadable) {
+    // The sources of the Rust error are the causes of the exception, and follow its message.
+    assert(e.message == "The configuration is unreadable: Invalid line 2: invalid digit found in string") {
+        "Unexpected ConfigError.Unreadable message: ${e.message}"
+    }
+    val source = e.cause as ErrorSourceException
+    assert(source.message == "Invalid line 2")
+    assert(source.cause!!.message == "invalid digit found in string")
+    assert(source.cause!!.cause == null)
+}
+
 Coveralls("test_interfaces_in_dicts").use { coveralls ->
     coveralls.addPatch(Patch(Color.RED))
     coveralls.addRepair(
@@ -216,3 +249,139 @@ d = DictWithDefaults(name = "this", category = "that", integer = 42UL)
 assert(d.name == "this")
 assert(d.category == "that")
 assert(d.integer == 42UL)
+
+// Objects which are never destroyed are freed once they have been garbage collected.
+assert(getNumAlive() == 0UL)
+for (i in 1..100) {
+    Coveralls("test_garbage_collection_$i")
+}
+assert(getNumAlive() == 100UL)
+// The cleaner runs on a background thread, so wait for it to catch up.
+for (attempt in 1..50) {
+    if (getNumAlive() == 0UL) {
+        break
+    }
+    System.gc()
+    Thread.sleep(100)
+}
+assert(getNumAlive() == 0UL) { "Objects were not freed: ${getNumAlive()} still alive" }
+
+// `sequence<u8>` values, with and without copying them.
+assert(reverseBytes(listOf(1u, 2u, 3u)) == listOf<UByte>(3u, 2u, 1u))
+val input = RustByteBuffer.allocate(3)
+input.buffer.put(byteArrayOf(1, 2, 3))
+reverseBytesDirect(input).use { output ->
+    val bytes = ByteArray(3)
+    output.buffer.get(bytes)
+    assert(bytes.contentEquals(byteArrayOf(3, 2, 1)))
+}
+try {
+    input.buffer
+    throw RuntimeException("Should have thrown, as the buffer was passed to Rust")
+} catch (e: IllegalStateException) {
+    // It's okay!
+}
+
+// Rust implementations of the `Getters` trait.
+makeRustGetters(false).use { getters ->
+    makeRustGetters(true).use { inverted ->
+        assert(getters.getBool(true, false))
+        assert(!inverted.getBool(true, false))
+        assert(getters.getString("hello", true) == "HELLO")
+        assert(inverted.getString("hello", false) == "olleh")
+        try {
+            inverted.getString("too-many-holes", false)
+            throw RuntimeException("Should have thrown a TooManyHoles exception!")
+        } catch (e: CoverallException.TooManyHoles) {
+            // It's okay!
+        }
+
+        assert(getBoolWith(inverted, true, true))
+        assert(getters.strongCount() == 2UL)
+    }
+}
+assert(makeAllRustGetters().map { it.use { g -> g.getBool(false, false) } } == listOf(false, true))
+
+// Instances of the generic `Stack` interface.
+StringStack().use { strings ->
+    strings.push("one")
+    strings.push("two")
+    assert(strings.items() == listOf("one", "two"))
+    assert(strings.pop() == "two")
+}
+CountStack().use { counts ->
+    assert(counts.pop() == null)
+    counts.push(1u)
+    assert(counts.items() == listOf(1u))
+}
+
+// Big integers are passed whatever their size.
+assert(multiplyBigints(java.math.BigInteger("340282366920938463463374607431768211456"), java.math.BigInteger("-2")) == java.math.BigInteger("-680564733841876926926749214863536422912"))
+
+// Decimals are passed without losing precision, even with an exponent.
+assert(addDecimals(java.math.BigDecimal("0.1"), java.math.BigDecimal("0.2")) == java.math.BigDecimal("0.3"))
+assert(addDecimals(java.math.BigDecimal("12345678901234567890.12"), java.math.BigDecimal("1E+3")) == java.math.BigDecimal("12345678901234568890.12"))
+
+// URLs are `URI`s, which Rust checks when lifting them.
+assert(getUrlHost(java.net.URI("https://example.com/a")) == "example.com")
+assert(getUrlHost(java.net.URI("mailto:coverall@example.com")) == null)
+assert(setUrlPath(java.net.URI("https://example.com/a?q=1"), "b c") == java.net.URI("https://example.com/b%20c?q=1"))
+try {
+    getUrlHost(java.net.URI("example.com"))
+    throw RuntimeException("Should have thrown an InternalException!")
+} catch (e: InternalException) {
+    // It's okay!
+}
+
+// UUIDs keep their byte order.
+val uuidText = "a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"
+assert(formatUuid(java.util.UUID.fromString(uuidText)) == uuidText)
+assert(parseUuid(uuidText) == java.util.UUID.fromString(uuidText))
+assert(parseUuid("not-a-uuid") == null)
+
+// Maps can have other keys than strings, and their entries may come back in any order.
+val ints = mapOf(-1L to "minus one", 0L to "zero", (1L shl 40) to "big")
+assert(echoIntKeyedDict(ints) == ints)
+val colors = mapOf(Color.RED to 1u, Color.GREEN to 2u, Color.BLUE to 3u)
+assert(echoEnumKeyedDict(colors) == colors)
+
+// Constants declared in the namespace.
+assert(MAX_ALIVE == 100u)
+assert(MIN_OFFSET == -16L)
+assert(RATIO == 0.5)
+assert(!VERBOSE)
+assert(GREETING == "Hello, coverall!")
+assert(TIMEOUT == java.time.Duration.ofMillis(1500))
+// `$` doesn't start a string template in constants.
+assert(PRICE_TEMPLATE == "\${item} costs \$5")
+assert(LONG_MIN == Long.MIN_VALUE)
+
+// `log_forwarder` is set in `uniffi.toml`. The scaffolding logs the calls into Rust as well, which
+// we ignore.
+val records = mutableListOf<Pair<RustLogLevel, String>>()
+installLogForwarder(RustLogLevel.DEBUG) { level, target, message ->
+    if (target == "uniffi_coverall" && !message.contains("log_at_every_level")) {
+        records.add(level to message)
+    }
+}
+logAtEveryLevel("hello")
+assert(records == listOf(
+    RustLogLevel.ERROR to "hello",
+    RustLogLevel.WARN to "hello",
+    RustLogLevel.INFO to "hello",
+    RustLogLevel.DEBUG to "hello",
+))
+
+// Outside of Android, the records go to `java.util.logging` by default.
+val julMessages = mutableListOf<String>()
+val julLogger = java.util.logging.Logger.getLogger("uniffi_coverall")
+julLogger.addHandler(object : java.util.logging.Handler() {
+    override fun publish(record: java.util.logging.LogRecord) {
+        julMessages.add("${record.level} ${record.message}")
+    }
+    override fun flush() {}
+    override fun close() {}
+})
+installLogForwarder(RustLogLevel.WARN)
+logAtEveryLevel("default")
+assert(julMessages == listOf("SEVERE default", "WARNING default"))
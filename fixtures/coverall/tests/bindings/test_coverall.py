@@ -2,8 +2,15 @@
 # License, v. 2.0. If a copy of the MPL was not distributed with this
 # file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+import dataclasses
+import logging
+import pickle
 import unittest
-from datetime import datetime, timezone
+from concurrent.futures import ThreadPoolExecutor
+from datetime import datetime, timedelta, timezone
+from decimal import Decimal
+from urllib.parse import urlsplit
+from uuid import UUID
 from coverall import *
 
 class TestCoverall(unittest.TestCase):
@@ -85,13 +92,64 @@ class TestCoverall(unittest.TestCase):
         coveralls = None
         self.assertEqual(get_num_alive(), 0)
 
+    def test_static_methods(self):
+        self.assertEqual(Coveralls.default_name(), "Nameless coveralls")
+        coveralls = Coveralls.with_default_name()
+        self.assertEqual(get_num_alive(), 1)
+        self.assertEqual(coveralls.get_name(), "Nameless coveralls")
+        coveralls = None
+        self.assertEqual(get_num_alive(), 0)
+
+        Coveralls.check_holes(3)
+        with self.assertRaises(CoverallError.TooManyHoles):
+            Coveralls.check_holes(4)
+
+    def test_context_manager(self):
+        self.assertEqual(get_num_alive(), 0)
+        with Coveralls("c1") as coveralls:
+            self.assertEqual(get_num_alive(), 1)
+            self.assertEqual(coveralls.get_name(), "c1")
+        # The Rust object is freed on exit, even though `coveralls` is still referenced.
+        self.assertEqual(get_num_alive(), 0)
+
+        with self.assertRaises(CoverallError.TooManyHoles):
+            with Coveralls("c2") as coveralls:
+                coveralls.maybe_throw(True)
+        self.assertEqual(get_num_alive(), 0)
+
+    def test_cloned_handles_released_on_failed_write(self):
+        coveralls = Coveralls("c1")
+        self.assertEqual(count_coveralls([coveralls, coveralls]), 2)
+        self.assertEqual(coveralls.strong_count(), 2)
+        # The handle cloned for the first item is released when the second fails to write.
+        with self.assertRaises(TypeError):
+            count_coveralls([coveralls, "not a coveralls"])
+        self.assertEqual(coveralls.strong_count(), 2)
+        coveralls = None
+        self.assertEqual(get_num_alive(), 0)
+
+    def test_destroy(self):
+        self.assertEqual(get_num_alive(), 0)
+        coveralls = Coveralls("c1")
+        self.assertEqual(get_num_alive(), 1)
+        coveralls.destroy()
+        self.assertEqual(get_num_alive(), 0)
+        # Destroying it again does nothing, and using it fails cleanly.
+        coveralls.destroy()
+        with self.assertRaisesRegex(ValueError, "already been destroyed"):
+            coveralls.get_name()
+        with self.assertRaisesRegex(ValueError, "already been destroyed"):
+            Coveralls("c2").take_other(coveralls)
+        self.assertEqual(get_num_alive(), 0)
+
 
     def test_simple_errors(self):
         coveralls = Coveralls("test_errors")
         self.assertEqual(coveralls.get_name(), "test_errors")
 
-        with self.assertRaisesRegex(CoverallError.TooManyHoles, "The coverall has too many holes"):
+        with self.assertRaisesRegex(CoverallError.TooManyHoles, "The coverall has too many holes") as cm:
             coveralls.maybe_throw(True)
+        self.assertEqual(cm.exception.error_context, {})
 
         with self.assertRaises(CoverallError.TooManyHoles):
             coveralls.maybe_throw_into(True)
@@ -111,6 +169,7 @@ class TestCoverall(unittest.TestCase):
         self.assertEqual(cm.exception.code, 10)
         self.assertEqual(cm.exception.extended_code, 20)
         self.assertEqual(str(cm.exception), "ComplexError.OsError(code=10, extended_code=20)")
+        self.assertEqual(cm.exception.error_context, {"input": "1", "coveralls": "test_complex_errors"})
 
         with self.assertRaises(ComplexError.PermissionDenied) as cm:
             coveralls.maybe_throw_complex(2)
@@ -120,6 +179,14 @@ class TestCoverall(unittest.TestCase):
         # Test panics, which should cause InternalError to be raised
         with self.assertRaises(InternalError) as cm:
             coveralls.maybe_throw_complex(3)
+        self.assertEqual(cm.exception.error_context, {"input": "3"})
+
+    def test_error_sources(self):
+        self.assertEqual(sum_config("1\n2"), 3)
+        # The sources sent with the error are skipped.
+        with self.assertRaisesRegex(ConfigError.Unreadable, "The configuration is unreadable") as cm:
+            sum_config("1\nx")
+        self.assertEqual(cm.exception.error_context, {})
 
     def test_self_by_arc(self):
         coveralls = Coveralls("test_self_by_arc")
@@ -200,6 +267,122 @@ class TestCoverall(unittest.TestCase):
         self.assertEqual("that", d.category)
         self.assertEqual(42, d.integer)
 
+    def test_dict_dataclass(self):
+        """ This does not call Rust code. """
+
+        d = DictWithDefaults(name="this")
+        self.assertEqual(d, DictWithDefaults(name="this"))
+        self.assertNotEqual(d, DictWithDefaults(name="that"))
+        self.assertEqual(hash(d), hash(DictWithDefaults(name="this")))
+        self.assertEqual(repr(d), "DictWithDefaults(name='this', category=None, integer=31)")
+
+        # Records are frozen unless `frozen_records` is `false` in `uniffi.toml`.
+        with self.assertRaises(dataclasses.FrozenInstanceError):
+            d.name = "that"
+        self.assertEqual(dataclasses.replace(d, integer=42).integer, 42)
+
+    def test_dict_defaults_before_required_field(self):
+        d = DictWithDefaultsFirst(integer=42)
+        # The fields after the first one with a default value and the first one without are
+        # passed by keyword.
+        self.assertEqual(d, DictWithDefaultsFirst("default-value", [], integer=42))
+        self.assertEqual(DictWithDefaultsFirst(name="this", integer=1).name, "this")
+        # The default list isn't shared between records.
+        self.assertIsNot(d.tags, DictWithDefaultsFirst(integer=42).tags)
+        with self.assertRaises(TypeError):
+            DictWithDefaultsFirst()
+        with self.assertRaises(dataclasses.FrozenInstanceError):
+            d.integer = 1
+
+    def test_pickle(self):
+        d = DictWithDefaults(name="this")
+        self.assertEqual(pickle.loads(pickle.dumps(d)), d)
+
+        e = pickle.loads(pickle.dumps(CoverallError.TooManyHoles("too many")))
+        self.assertIsInstance(e, CoverallError.TooManyHoles)
+        self.assertEqual(str(e), str(CoverallError.TooManyHoles("too many")))
+
+        e = pickle.loads(pickle.dumps(ComplexError.OsError(code=10, extended_code=20)))
+        self.assertIsInstance(e, ComplexError.OsError)
+        self.assertEqual(e.code, 10)
+        self.assertEqual(e.extended_code, 20)
+
+        coveralls = Coveralls("test_pickle")
+        with self.assertRaisesRegex(TypeError, "can't be pickled"):
+            pickle.dumps(coveralls)
+
+    def test_repr(self):
+        coveralls = Coveralls("test_repr")
+        self.assertRegex(repr(coveralls), r"^<Coveralls handle=0x[0-9a-f]+>$")
+        with coveralls:
+            pass
+        self.assertEqual(repr(coveralls), "<Coveralls handle=None>")
+
+        self.assertEqual(
+            repr(ComplexError.PermissionDenied(reason="Forbidden")),
+            "ComplexError.PermissionDenied(reason='Forbidden')",
+        )
+
+    def test_gil_released(self):
+        # One thread busy-waits in Rust for some time, while a second thread repeatedly
+        # increments the counter if the object is still busy, until the first one is done. If the
+        # GIL were held during Rust calls, the second thread could only run once the first one is
+        # done.
+        counter = ThreadsafeCounter()
+        with ThreadPoolExecutor(max_workers=2) as executor:
+            # 300 ms should be long enough for the other thread to be scheduled, but not so long
+            # as to annoy the user with a slow test.
+            busy_waiting = executor.submit(counter.busy_wait, 300)
+
+            def increment():
+                count = 0
+                while not busy_waiting.done():
+                    count = counter.increment_if_busy()
+                return count
+
+            incrementing = executor.submit(increment)
+            busy_waiting.result()
+            self.assertGreater(incrementing.result(), 0)
+
+    def test_log_forwarder(self):
+        # `log_forwarder` is set in `uniffi.toml`.
+        records = []
+
+        class Handler(logging.Handler):
+            def emit(self, record):
+                records.append(record)
+
+        logger = logging.getLogger("coverall")
+        handler = Handler()
+        logger.addHandler(handler)
+        logger.setLevel(logging.DEBUG)
+        try:
+            install_log_forwarder()
+            log_at_every_level("hello")
+            # Forwarding can be turned off again.
+            install_log_forwarder(logging.CRITICAL)
+            log_at_every_level("not forwarded")
+        finally:
+            logger.removeHandler(handler)
+            logger.setLevel(logging.NOTSET)
+
+        # The scaffolding logs the calls into Rust as well, which we ignore.
+        records = [record for record in records if record.rust_target == "uniffi_coverall" and "log_at_every_level" not in record.getMessage()]
+        self.assertEqual(
+            [(record.levelno, record.getMessage()) for record in records],
+            [(logging.ERROR, "hello"), (logging.WARNING, "hello"), (logging.INFO, "hello"), (logging.DEBUG, "hello")],
+        )
+
+    def test_iterator(self):
+        self.assertEqual(list(Countdown(3)), [3, 2, 1])
+
+        countdown = Countdown(2)
+        self.assertIs(iter(countdown), countdown)
+        self.assertEqual(next(countdown), 2)
+        self.assertEqual([n for n in countdown], [1])
+        with self.assertRaises(StopIteration):
+            next(countdown)
+
     def test_dict_with_non_string_keys(self):
         coveralls = Coveralls("test_dict")
 
@@ -212,5 +395,79 @@ class TestCoverall(unittest.TestCase):
         dict3 = coveralls.get_dict3(key=31, value=42)
         assert dict3[31] == 42
 
+    def test_bytes(self):
+        self.assertEqual(reverse_bytes([1, 2, 3]), [3, 2, 1])
+
+    def test_rust_getters(self):
+        getters = make_rust_getters(False)
+        inverted = make_rust_getters(True)
+        self.assertIsInstance(inverted, Getters)
+        self.assertTrue(getters.get_bool(True, False))
+        self.assertFalse(inverted.get_bool(True, False))
+        self.assertEqual(getters.get_string("hello", True), "HELLO")
+        self.assertEqual(inverted.get_string("hello", False), "olleh")
+        with self.assertRaises(CoverallError.TooManyHoles):
+            inverted.get_string("too-many-holes", False)
+
+        self.assertTrue(get_bool_with(inverted, True, True))
+        self.assertEqual([g.get_bool(False, False) for g in make_all_rust_getters()], [False, True])
+        # The foreign-language object holds one reference, the call another.
+        self.assertEqual(getters.strong_count(), 2)
+
+    def test_generic_interfaces(self):
+        strings = StringStack()
+        strings.push("one")
+        strings.push("two")
+        self.assertEqual(strings.items(), ["one", "two"])
+        self.assertEqual(strings.pop(), "two")
+
+        counts = CountStack()
+        self.assertIsNone(counts.pop())
+        counts.push(1)
+        self.assertEqual(counts.items(), [1])
+        self.assertNotIsInstance(counts, StringStack)
+
+    def test_bigints(self):
+        self.assertEqual(multiply_bigints(2**128, -2), -(2**129))
+        self.assertEqual(multiply_bigints(0, 7), 0)
+
+    def test_decimals(self):
+        self.assertEqual(add_decimals(Decimal("0.1"), Decimal("0.2")), Decimal("0.3"))
+        # Exponents are passed in plain notation, and no precision is lost.
+        self.assertEqual(
+            add_decimals(Decimal("12345678901234567890.12"), Decimal("1E+3")),
+            Decimal("12345678901234568890.12"),
+        )
+        self.assertEqual(add_decimals(Decimal("-1.50"), 1), Decimal("-0.50"))
+
+    def test_urls(self):
+        self.assertEqual(get_url_host("https://example.com/a"), "example.com")
+        self.assertEqual(get_url_host(urlsplit("mailto:coverall@example.com")), None)
+        self.assertEqual(set_url_path("https://example.com/a?q=1", "b c"), "https://example.com/b%20c?q=1")
+        # Rust checks the URLs when lifting them.
+        with self.assertRaisesRegex(InternalError, "is not a valid URL: relative URL without a base"):
+            get_url_host("example.com")
+
+    def test_uuids(self):
+        text = "a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"
+        self.assertEqual(format_uuid(UUID(text)), text)
+        self.assertEqual(parse_uuid(text), UUID(text))
+        self.assertIsNone(parse_uuid("not-a-uuid"))
+
+    def test_dicts_with_other_keys(self):
+        # The entries may come back in any order, which dicts don't care about.
+        ints = {-1: "minus one", 0: "zero", 2**40: "big"}
+        self.assertEqual(echo_int_keyed_dict(ints), ints)
+        colors = {Color.RED: 1, Color.GREEN: 2, Color.BLUE: 3}
+        self.assertEqual(echo_enum_keyed_dict(colors), colors)
+
+    def test_constants(self):
+        self.assertEqual(MAX_ALIVE, 100)
+        self.assertEqual(MIN_OFFSET, -16)
+        self.assertEqual(RATIO, 0.5)
+        self.assertFalse(VERBOSE)
+        self.assertEqual(GREETING, "Hello, coverall!")
+        self.assertEqual(TIMEOUT, timedelta(seconds=1, milliseconds=500))
+
 if __name__=='__main__':
     unittest.main()
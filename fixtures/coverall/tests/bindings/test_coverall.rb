@@ -94,6 +94,30 @@ class TestCoverall < Test::Unit::TestCase
     assert_equal 2, Coverall.get_num_alive
   end
 
+  def test_static_methods
+    assert_equal Coverall::Coveralls.default_name, 'Nameless coveralls'
+    coveralls = Coverall::Coveralls.with_default_name
+    assert_equal coveralls.get_name, 'Nameless coveralls'
+
+    Coverall::Coveralls.check_holes 3
+    assert_raise Coverall::CoverallError::TooManyHoles do
+      Coverall::Coveralls.check_holes 4
+    end
+  end
+
+  def test_destroy
+    assert_equal Coverall.get_num_alive, 0
+    coveralls = Coverall::Coveralls.new 'c1'
+    assert_equal Coverall.get_num_alive, 1
+    coveralls.destroy
+    assert_equal Coverall.get_num_alive, 0
+    # Destroying it again does nothing, and using it fails cleanly.
+    coveralls.destroy
+    assert_raise_message /already been destroyed/ do
+      coveralls.get_name
+    end
+  end
+
   def test_simple_errors
     coveralls = Coverall::Coveralls.new 'test_simple_errors'
     assert_equal coveralls.get_name, 'test_simple_errors'
@@ -107,6 +131,12 @@ class TestCoverall < Test::Unit::TestCase
       coveralls.maybe_throw_into true
     end
 
+    # The variants can be rescued as the error they belong to.
+    err = assert_raise Coverall::CoverallError do
+      coveralls.maybe_throw true
+    end
+    assert_kind_of Coverall::CoverallError::TooManyHoles, err
+
     err = assert_raise Coverall::InternalError do
       coveralls.panic 'expected panic: oh no'
     end
@@ -127,6 +157,7 @@ class TestCoverall < Test::Unit::TestCase
       assert_equal err.code, 10
       assert_equal err.extended_code, 20
       assert_equal err.to_s, 'Coverall::ComplexError::OsError(code=10, extended_code=20)'
+      assert_equal err.error_context, { 'input' => '1', 'coveralls' => 'test_complex_errors' }
     else
       raise 'should have thrown'
     end
@@ -140,9 +171,26 @@ class TestCoverall < Test::Unit::TestCase
       raise 'should have thrown'
     end
 
-    assert_raise Coverall::InternalError do
+    err = assert_raise Coverall::ComplexError do
+      coveralls.maybe_throw_complex(1)
+    end
+    assert_equal err, Coverall::ComplexError::OsError.new(10, 20)
+    assert_not_equal err, Coverall::ComplexError::OsError.new(10, 21)
+    assert_not_equal err, Coverall::ComplexError::PermissionDenied.new('Forbidden')
+
+    err = assert_raise Coverall::InternalError do
       coveralls.maybe_throw_complex(3)
     end
+    assert_equal err.error_context, { 'input' => '3' }
+  end
+
+  def test_error_sources
+    assert_equal Coverall.sum_config("1\n2"), 3
+    # The sources sent with the error are skipped.
+    err = assert_raise Coverall::ConfigError::Unreadable do
+      Coverall.sum_config("1\nx")
+    end
+    assert_equal err.message, 'The configuration is unreadable'
   end
 
   def test_self_by_arc
@@ -234,5 +282,123 @@ class TestCoverall < Test::Unit::TestCase
     end
   end
 
+  def test_async_variants
+    # `async_variants` is set in `uniffi.toml`.
+    assert_equal Coverall.create_some_dict_async.text, 'text'
+
+    coveralls = Coverall::Coveralls.new 'test_async_variants'
+    assert_equal coveralls.get_name_async, 'test_async_variants'
+    assert_raise Coverall::CoverallError::TooManyHoles do
+      coveralls.maybe_throw_async true
+    end
+
+    # The GVL is released while Rust runs, so this thread keeps incrementing the counter while
+    # the other one waits for Rust to stop being busy.
+    counter = Coverall::ThreadsafeCounter.new
+    busy_waiting = Thread.new { counter.busy_wait_async 300 }
+    count = 0
+    deadline = Time.now + 0.3
+    count = counter.increment_if_busy while count.zero? && Time.now < deadline
+    busy_waiting.join
+    assert_operator count, :>, 0
+  end
+
+  def test_ractors
+    # `ractor_safe` is set in `uniffi.toml`.
+    omit 'Ractors need Ruby 3' unless defined?(Ractor)
+
+    ractors = 4.times.map do |i|
+      Ractor.new(i) do |i|
+        coveralls = Coverall::Coveralls.new "ractor #{i}"
+        error = begin
+          coveralls.maybe_throw true
+        rescue Coverall::CoverallError => err
+          err.class
+        end
+        [coveralls.get_name, Coverall.reverse_bytes([i, 1, 2]), error]
+      end
+    end
+    ractors.each_with_index do |ractor, i|
+      assert_equal ractor.take, ["ractor #{i}", [2, 1, i], Coverall::CoverallError::TooManyHoles]
+    end
+
+    GC.start
+    assert_equal Coverall.get_num_alive, 0
+  end
+
+  def test_rust_getters
+    getters = Coverall.make_rust_getters false
+    inverted = Coverall.make_rust_getters true
+    assert_kind_of Coverall::Getters, inverted
+    assert_true getters.get_bool(true, false)
+    assert_false inverted.get_bool(true, false)
+    assert_equal getters.get_string('hello', true), 'HELLO'
+    assert_equal inverted.get_string('hello', false), 'olleh'
+    assert_raise Coverall::CoverallError::TooManyHoles do
+      inverted.get_string 'too-many-holes', false
+    end
+
+    assert_true Coverall.get_bool_with(inverted, true, true)
+    assert_equal(Coverall.make_all_rust_getters.map { |g| g.get_bool(false, false) }, [false, true])
+    assert_equal getters.strong_count, 2
+  end
+
+  def test_generic_interfaces
+    strings = Coverall::StringStack.new
+    strings.push 'one'
+    strings.push 'two'
+    assert_equal strings.items, %w[one two]
+    assert_equal strings.pop, 'two'
+
+    counts = Coverall::CountStack.new
+    assert_nil counts.pop
+    counts.push 1
+    assert_equal counts.items, [1]
+  end
+
+  def test_bigints
+    assert_equal Coverall.multiply_bigints(2**128, -2), -(2**129)
+  end
+
+  def test_decimals
+    assert_equal Coverall.add_decimals(BigDecimal('0.1'), BigDecimal('0.2')), BigDecimal('0.3')
+    assert_equal Coverall.add_decimals(BigDecimal('12345678901234567890.12'), BigDecimal('1E+3')),
+                 BigDecimal('12345678901234568890.12')
+  end
+
+  def test_urls
+    assert_equal Coverall.get_url_host('https://example.com/a'), 'example.com'
+    assert_nil Coverall.get_url_host('mailto:coverall@example.com')
+    assert_equal Coverall.set_url_path('https://example.com/a?q=1', 'b c'), 'https://example.com/b%20c?q=1'
+    assert_raise_message(/is not a valid URL: relative URL without a base/) do
+      Coverall.get_url_host 'example.com'
+    end
+  end
 
+  def test_uuids
+    text = 'a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8'
+    assert_equal Coverall.format_uuid(text), text
+    assert_equal Coverall.parse_uuid(text), text
+    assert_nil Coverall.parse_uuid('not-a-uuid')
+    assert_raise ArgumentError do
+      Coverall.format_uuid 'not-a-uuid'
+    end
+  end
+
+  def test_dicts_with_other_keys
+    # The entries may come back in any order, which hashes don't care about.
+    ints = { -1 => 'minus one', 0 => 'zero', 2**40 => 'big' }
+    assert_equal Coverall.echo_int_keyed_dict(ints), ints
+    colors = { Coverall::Color::RED => 1, Coverall::Color::GREEN => 2, Coverall::Color::BLUE => 3 }
+    assert_equal Coverall.echo_enum_keyed_dict(colors), colors
+  end
+
+  def test_constants
+    assert_equal Coverall::MAX_ALIVE, 100
+    assert_equal Coverall::MIN_OFFSET, -16
+    assert_in_delta Coverall::RATIO, 0.5
+    assert_false Coverall::VERBOSE
+    assert_equal Coverall::GREETING, 'Hello, coverall!'
+    assert_equal Coverall::TIMEOUT, Time.at(1, 500_000_000, :nanosecond, in: '+00:00').utc
+  end
 end
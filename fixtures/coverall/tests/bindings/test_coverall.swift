@@ -117,6 +117,7 @@ do {
             fatalError("wrong error variant: \(e)")
         }
         assert(String(describing: e) == "OsError(code: 10, extendedCode: 20)", "Unexpected ComplexError.OsError description: \(e)")
+        assert(e.errorContext == ["input": "1", "coveralls": "test_complex_errors"])
     }
 
     do {
@@ -140,6 +141,17 @@ do {
 
 }
 
+// Test the errors sent with their sources, which are skipped
+do {
+    assert(try! sumConfig(text: "1\n2") == 3)
+    do {
+        let _ = try sumConfig(text: "1\nx")
+        fatalError("should have thrown")
+    } catch ConfigError.Unreadable(let message) {
+        assert(message == "The configuration is unreadable")
+    }
+}
+
 // Swift GC is deterministic, `coveralls` is freed when it goes out of scope.
 assert(getNumAlive() == 0);
 
@@ -168,6 +180,41 @@ do {
 // Dropping `coveralls` will kill both.
 assert(getNumAlive() == 0)
 
+// Test static methods
+assert(Coveralls.defaultName() == "Nameless coveralls")
+do {
+    let coveralls = Coveralls.withDefaultName()
+    assert(getNumAlive() == 1)
+    assert(coveralls.getName() == "Nameless coveralls")
+}
+assert(getNumAlive() == 0)
+
+// Destroy the Rust object explicitly, rather than when the Swift object is deinitialized.
+do {
+    let coveralls = Coveralls(name: "c1")
+    assert(getNumAlive() == 1)
+    coveralls.destroy()
+    assert(getNumAlive() == 0)
+    // Destroying it again does nothing.
+    coveralls.destroy()
+    // The methods which throw throw afterwards.
+    do {
+        _ = try coveralls.maybeThrow(shouldThrow: false)
+        fatalError("Should have thrown")
+    } catch {
+        assert(error.localizedDescription == "Coveralls object has already been destroyed")
+    }
+}
+assert(getNumAlive() == 0)
+
+try! Coveralls.checkHoles(holes: 3)
+do {
+    try Coveralls.checkHoles(holes: 4)
+    fatalError("Should have thrown")
+} catch CoverallError.TooManyHoles {
+    // It's okay!
+}
+
 // Test a dict with defaults
 // This does not call Rust code.
 do {
@@ -202,3 +249,105 @@ do {
     coveralls.addRepair(repair: Repair(when: Date.init(), patch: Patch(color: Color.blue)))
     assert(coveralls.getRepairs().count == 2)
 }
+
+// Rust implementations of the `Getters` trait.
+do {
+    let getters = makeRustGetters(inverted: false)
+    let inverted = makeRustGetters(inverted: true)
+    assert(getters.getBool(v: true, arg2: false))
+    assert(!inverted.getBool(v: true, arg2: false))
+    assert(try! getters.getString(v: "hello", arg2: true) == "HELLO")
+    assert(try! inverted.getString(v: "hello", arg2: false) == "olleh")
+    do {
+        _ = try inverted.getString(v: "too-many-holes", arg2: false)
+        fatalError("Should have thrown")
+    } catch CoverallError.TooManyHoles {
+        // It's okay!
+    }
+
+    assert(getBoolWith(getters: inverted, v: true, arg2: true))
+    assert(makeAllRustGetters().map { $0.getBool(v: false, arg2: false) } == [false, true])
+    assert(getters.strongCount() == 2)
+}
+
+// Instances of the generic `Stack` interface.
+do {
+    let strings = StringStack()
+    strings.push(value: "one")
+    strings.push(value: "two")
+    assert(strings.items() == ["one", "two"])
+    assert(strings.pop() == "two")
+
+    let counts = CountStack()
+    assert(counts.pop() == nil)
+    counts.push(value: 1)
+    assert(counts.items() == [1])
+}
+
+// Big integers are strings of decimal digits.
+do {
+    assert(multiplyBigints(a: "340282366920938463463374607431768211456", b: "-2") == "-680564733841876926926749214863536422912")
+}
+
+// Decimals are passed without losing precision.
+do {
+    assert(addDecimals(a: Decimal(string: "0.1")!, b: Decimal(string: "0.2")!) == Decimal(string: "0.3")!)
+    assert(addDecimals(a: Decimal(string: "12345678901234567890.12")!, b: 1000) == Decimal(string: "12345678901234568890.12")!)
+}
+
+// URLs are passed as their absolute string.
+do {
+    assert(getUrlHost(address: URL(string: "https://example.com/a")!) == "example.com")
+    assert(getUrlHost(address: URL(string: "mailto:coverall@example.com")!) == nil)
+    assert(setUrlPath(address: URL(string: "https://example.com/a?q=1")!, path: "b c") == URL(string: "https://example.com/b%20c?q=1")!)
+}
+
+// UUIDs keep their byte order.
+do {
+    let text = "A1A2A3A4-B1B2-C1C2-D1D2-D3D4D5D6D7D8"
+    assert(formatUuid(id: UUID(uuidString: text)!) == text.lowercased())
+    assert(parseUuid(text: text) == UUID(uuidString: text)!)
+    assert(parseUuid(text: "not-a-uuid") == nil)
+}
+
+// Maps can have other keys than strings, and their entries may come back in any order.
+do {
+    let ints: [Int64: String] = [-1: "minus one", 0: "zero", 1 << 40: "big"]
+    assert(echoIntKeyedDict(dict: ints) == ints)
+    let colors: [Color: UInt32] = [.red: 1, .green: 2, .blue: 3]
+    assert(echoEnumKeyedDict(dict: colors) == colors)
+}
+
+// Constants declared in the namespace.
+do {
+    assert(maxAlive == 100)
+    assert(minOffset == -16)
+    assert(ratio == 0.5)
+    assert(!verbose)
+    assert(greeting == "Hello, coverall!")
+    assert(timeout == 1.5)
+}
+
+// `log_forwarder` is set in `uniffi.toml`. The scaffolding logs the calls into Rust as well, which
+// we ignore.
+do {
+    var records: [String] = []
+    let installed = installLogForwarder(maxLevel: .debug) { level, target, message in
+        if target == "uniffi_coverall" && !message.contains("log_at_every_level") {
+            records.append("\(level) \(message)")
+        }
+    }
+    assert(installed)
+    logAtEveryLevel(message: "hello")
+    assert(records == ["error hello", "warn hello", "info hello", "debug hello"])
+
+    // Only the records up to the new level are forwarded after installing the forwarder again.
+    records = []
+    installLogForwarder(maxLevel: .warn) { level, target, message in
+        if target == "uniffi_coverall" {
+            records.append("\(level) \(message)")
+        }
+    }
+    logAtEveryLevel(message: "hello")
+    assert(records == ["error hello", "warn hello"])
+}
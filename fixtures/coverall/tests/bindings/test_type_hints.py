@@ -0,0 +1,104 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import os
+import tempfile
+import typing
+import unittest
+import coverall
+from coverall import *
+
+# Code using the bindings correctly, which the type checker must accept.
+WELL_TYPED = """
+import typing
+from coverall import *
+
+coveralls: Coveralls = Coveralls("c1")
+name: str = coveralls.get_name()
+other: typing.Optional[Coveralls] = coveralls.get_other()
+count: int = count_coveralls((coveralls, coveralls))
+repairs: typing.List[Repair] = coveralls.get_repairs()
+maybe_text: typing.Optional[str] = create_some_dict().maybe_text
+keyed: typing.Dict[Color, int] = echo_enum_keyed_dict({Color.RED: 1})
+defaults = DictWithDefaultsFirst(tags=["a"], integer=1)
+"""
+
+# Code using the bindings wrongly, which the type checker must reject.
+ILL_TYPED = """
+from coverall import *
+
+count_coveralls(["not a coveralls"])
+name: int = Coveralls("c1").get_name()
+"""
+
+class TestTypeHints(unittest.TestCase):
+    def test_functions(self):
+        self.assertEqual(typing.get_type_hints(get_num_alive), {"return": int})
+        self.assertEqual(
+            typing.get_type_hints(count_coveralls),
+            {"coveralls": typing.Sequence[Coveralls], "return": int},
+        )
+        self.assertEqual(
+            typing.get_type_hints(echo_enum_keyed_dict),
+            {"dict": typing.Mapping[Color, int], "return": typing.Dict[Color, int]},
+        )
+        self.assertEqual(
+            typing.get_type_hints(parse_uuid)["return"],
+            typing.Optional[typing.get_type_hints(format_uuid)["id"]],
+        )
+
+    def test_methods(self):
+        self.assertEqual(
+            typing.get_type_hints(Coveralls.__init__),
+            {"name": str, "return": type(None)},
+        )
+        self.assertEqual(
+            typing.get_type_hints(Coveralls.take_other),
+            {"other": typing.Optional[Coveralls], "return": type(None)},
+        )
+        self.assertEqual(
+            typing.get_type_hints(Coveralls.get_dict),
+            {"key": str, "value": int, "return": typing.Dict[str, int]},
+        )
+        self.assertEqual(typing.get_type_hints(Coveralls.fallible_new)["return"], Coveralls)
+
+    def test_records(self):
+        hints = typing.get_type_hints(SimpleDict)
+        self.assertEqual(hints["text"], str)
+        self.assertEqual(hints["maybe_text"], typing.Optional[str])
+        self.assertEqual(hints["coveralls"], typing.Optional[Coveralls])
+        self.assertEqual(
+            typing.get_type_hints(DictWithDefaultsFirst.__init__),
+            {"name": str, "tags": typing.Sequence[str], "integer": int, "return": type(None)},
+        )
+
+    def test_py_typed(self):
+        self.assertTrue(os.path.exists(os.path.join(os.path.dirname(coverall.__file__), "py.typed")))
+
+    def check_types(self, source):
+        try:
+            from mypy import api
+        except ImportError:
+            self.skipTest("mypy isn't installed")
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            path = os.path.join(tmp_dir, "client.py")
+            with open(path, "w") as f:
+                f.write(source)
+            # Only the errors of the client code are reported, not those of the bindings.
+            os.environ["MYPYPATH"] = os.path.dirname(coverall.__file__)
+            stdout, _, status = api.run(["--follow-imports=silent", "--no-incremental", path])
+        return stdout, status
+
+    def test_mypy_accepts_well_typed_code(self):
+        stdout, status = self.check_types(WELL_TYPED)
+        self.assertEqual(status, 0, stdout)
+
+    def test_mypy_rejects_ill_typed_code(self):
+        stdout, status = self.check_types(ILL_TYPED)
+        self.assertNotEqual(status, 0, stdout)
+        self.assertIn('"count_coveralls" has incompatible type', stdout)
+        self.assertIn('Incompatible types in assignment (expression has type "str", variable has type "int")', stdout)
+
+if __name__=='__main__':
+    unittest.main()
@@ -1,5 +1,6 @@
 uniffi_macros::build_foreign_language_testcases!(
     "tests/bindings/test_coverall.py",
+    "tests/bindings/test_type_hints.py",
     "tests/bindings/test_coverall.kts",
     "tests/bindings/test_coverall.rb",
     "tests/bindings/test_coverall.swift",
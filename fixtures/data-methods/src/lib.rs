@@ -0,0 +1,67 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+#[derive(Debug, Clone)]
+pub struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl Point {
+    fn distance_to(&self, other: Point) -> f64 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+
+    fn scaled(&self, factor: f64) -> Point {
+        Point {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShapeError {
+    #[error("The shape has a negative size")]
+    NegativeSize,
+}
+
+impl Shape {
+    fn area(&self) -> Result<f64, ShapeError> {
+        match *self {
+            Shape::Circle { radius } if radius >= 0.0 => Ok(std::f64::consts::PI * radius * radius),
+            Shape::Rectangle { width, height } if width >= 0.0 && height >= 0.0 => {
+                Ok(width * height)
+            }
+            _ => Err(ShapeError::NegativeSize),
+        }
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/data_methods.uniffi.rs"));
@@ -0,0 +1,20 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+from data_methods import *
+
+origin = Point(0.0, 0.0)
+assert origin.distance_to(Point(3.0, 4.0)) == 5.0
+assert Point(1.0, 2.0).scaled(2.0) == Point(2.0, 4.0)
+
+assert Direction.NORTH.opposite() == Direction.SOUTH
+assert Direction.WEST.opposite().opposite() == Direction.WEST
+
+assert Shape.RECTANGLE(2.0, 3.0).area() == 6.0
+try:
+    Shape.CIRCLE(-1.0).area()
+    raise RuntimeError("Should have thrown a NegativeSize exception!")
+except ShapeError.NegativeSize:
+    # It's okay!
+    pass
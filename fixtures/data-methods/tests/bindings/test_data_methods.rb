@@ -0,0 +1,16 @@
+# frozen_string_literal: true
+
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+require 'test/unit'
+require 'data_methods'
+
+include Test::Unit::Assertions
+include DataMethods
+
+# Only the methods of records are supported by the Ruby bindings.
+origin = Point.new(0.0, 0.0)
+assert_equal 5.0, origin.distance_to(Point.new(3.0, 4.0))
+assert_equal Point.new(2.0, 4.0), Point.new(1.0, 2.0).scaled(2.0)
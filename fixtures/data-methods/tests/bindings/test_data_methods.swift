@@ -0,0 +1,20 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import data_methods
+
+let origin = Point(x: 0.0, y: 0.0)
+assert(origin.distanceTo(other: Point(x: 3.0, y: 4.0)) == 5.0)
+assert(Point(x: 1.0, y: 2.0).scaled(factor: 2.0) == Point(x: 2.0, y: 4.0))
+
+assert(Direction.north.opposite() == .south)
+assert(Direction.west.opposite().opposite() == .west)
+
+assert(try! Shape.rectangle(width: 2.0, height: 3.0).area() == 6.0)
+do {
+    let _ = try Shape.circle(radius: -1.0).area()
+    fatalError("Should have thrown a NegativeSize error!")
+} catch ShapeError.NegativeSize {
+    // It's okay!
+}
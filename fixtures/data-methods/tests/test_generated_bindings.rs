@@ -0,0 +1,6 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_data_methods.kts",
+    "tests/bindings/test_data_methods.swift",
+    "tests/bindings/test_data_methods.py",
+    "tests/bindings/test_data_methods.rb",
+);
@@ -17,7 +17,8 @@ pub enum InternalError {
 }
 
 fn get_guid(guid: Option<Guid>) -> Guid {
-    // This function doesn't return a Result, so all conversion errors are panics
+    // This function doesn't return a Result, but conversion errors which are a `GuidError`
+    // are raised anyway, as the `Guid` is declared with `[Custom, Throws=GuidError]`.
     match guid {
         Some(guid) => {
             assert!(
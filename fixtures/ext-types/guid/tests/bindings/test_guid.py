@@ -3,7 +3,7 @@
 # file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 import unittest
-from ext_types_guid import *
+from ext_types.ext_types_guid import *
 
 class TestCallback(GuidCallback):
     def run(self, guid):
@@ -22,10 +22,11 @@ class TestGuid(unittest.TestCase):
         self.assertEqual(helper.maybe_guid, None)
 
     def test_get_guid_errors(self):
-        # This is testing `get_guid` which never returns a result, so everything
-        # is InternalError representing a panic.
+        # This is testing `get_guid` which never returns a result, so only the
+        # `GuidError` of the `Guid` is raised, and everything else is InternalError
+        # representing a panic.
         # The fixture hard-codes some Guid strings to return specific errors.
-        with self.assertRaisesRegex(InternalError, "Failed to convert arg 'value': The Guid is too short"):
+        with self.assertRaises(GuidError.TooShort):
             get_guid("")
 
         with self.assertRaisesRegex(InternalError, "Failed to convert arg 'value': Something unexpected went wrong"):
@@ -1,6 +1,6 @@
 use custom_types::Handle;
 use ext_types_guid::Guid;
-use uniffi_one::UniffiOneType;
+use uniffi_one::{UniffiOneCallback, UniffiOneType};
 use url::Url;
 
 pub struct CombinedType {
@@ -50,4 +50,8 @@ fn get_combined_type(existing: Option<CombinedType>) -> CombinedType {
     })
 }
 
+fn call_uniffi_one_callback(callback: Box<dyn UniffiOneCallback>, value: UniffiOneType) -> String {
+    callback.on_sval(value.sval)
+}
+
 include!(concat!(env!("OUT_DIR"), "/ext-types-lib.uniffi.rs"));
@@ -3,7 +3,7 @@
 # file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 import unittest
-from imported_types_lib import *
+from ext_types.imported_types_lib import *
 
 class TestIt(unittest.TestCase):
     def test_it(self):
@@ -2,4 +2,8 @@ pub struct UniffiOneType {
     pub sval: String,
 }
 
+pub trait UniffiOneCallback {
+    fn on_sval(&self, sval: String) -> String;
+}
+
 include!(concat!(env!("OUT_DIR"), "/uniffi-one.uniffi.rs"));
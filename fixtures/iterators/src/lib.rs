@@ -0,0 +1,50 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::sync::Arc;
+
+// This iterator never ends, so it can't be returned as a `Vec`.
+fn count_from(start: u32) -> impl Iterator<Item = u32> {
+    start..
+}
+
+pub struct Word {
+    position: u32,
+    text: String,
+}
+
+pub struct Text {
+    contents: String,
+}
+
+impl Text {
+    fn new(contents: String) -> Self {
+        Self { contents }
+    }
+
+    // The iterator holds the `Arc`, as it can't borrow the text it outlives.
+    fn words(self: Arc<Self>) -> impl Iterator<Item = Word> {
+        let mut offset = 0;
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            let rest = self.contents[offset..].trim_start();
+            if rest.is_empty() {
+                return None;
+            }
+            let start = self.contents.len() - rest.len();
+            let end = rest
+                .find(char::is_whitespace)
+                .map_or(self.contents.len(), |i| start + i);
+            let word = Word {
+                position,
+                text: self.contents[start..end].to_string(),
+            };
+            offset = end;
+            position += 1;
+            Some(word)
+        })
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/iterators.uniffi.rs"));
@@ -0,0 +1,30 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.iterators.*
+
+// The items are produced lazily, so an endless iterator can be consumed.
+countFrom(1u).use { numbers ->
+    assert(numbers.asSequence().take(3).toList() == listOf(1u, 2u, 3u))
+    assert(numbers.next() == 4u)
+}
+
+Text(" lazy  iterators ").use { text ->
+    text.words().use { words ->
+        assert(words.asSequence().toList() == listOf(Word(0u, "lazy"), Word(1u, "iterators")))
+        assert(!words.hasNext())
+    }
+}
+
+Text("").use { text ->
+    text.words().use { words ->
+        assert(!words.hasNext())
+        try {
+            words.next()
+            throw RuntimeException("Should have thrown at the end of the iterator")
+        } catch (e: NoSuchElementException) {
+            // It's okay!
+        }
+    }
+}
@@ -0,0 +1,18 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+import itertools
+
+from iterators import *
+
+# The items are produced lazily, so an endless iterator can be consumed.
+numbers = count_from(1)
+assert list(itertools.islice(numbers, 3)) == [1, 2, 3]
+assert next(numbers) == 4
+
+words = Text(" lazy  iterators ").words()
+assert list(words) == [Word(0, "lazy"), Word(1, "iterators")]
+assert next(words, None) is None
+
+assert list(Text("").words()) == []
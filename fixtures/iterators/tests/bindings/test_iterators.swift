@@ -0,0 +1,16 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import iterators
+
+// The items are produced lazily, so an endless iterator can be consumed.
+let numbers = countFrom(start: 1)
+assert(Array(numbers.prefix(3)) == [1, 2, 3])
+assert(numbers.next() == 4)
+
+let words = Text(contents: " lazy  iterators ").words()
+assert(Array(words) == [Word(position: 0, text: "lazy"), Word(position: 1, text: "iterators")])
+assert(words.next() == nil)
+
+assert(Array(Text(contents: "").words()).isEmpty)
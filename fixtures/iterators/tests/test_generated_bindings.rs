@@ -0,0 +1,5 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_iterators.kts",
+    "tests/bindings/test_iterators.swift",
+    "tests/bindings/test_iterators.py",
+);
@@ -0,0 +1,59 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsonError {
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+}
+
+fn parse_json(text: String) -> Result<Value, JsonError> {
+    Ok(serde_json::from_str(&text)?)
+}
+
+fn stringify_json(value: Value) -> String {
+    value.to_string()
+}
+
+fn get_field(value: Value, key: String) -> Value {
+    value.get(&key).cloned().unwrap_or(Value::Null)
+}
+
+fn maybe_json(some: bool) -> Option<Value> {
+    some.then(|| serde_json::json!({"some": true}))
+}
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    id: String,
+    payload: Value,
+}
+
+pub struct Store {
+    documents: Mutex<HashMap<String, Document>>,
+}
+
+impl Store {
+    fn new() -> Self {
+        Self {
+            documents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn put(&self, document: Document) {
+        let mut documents = self.documents.lock().unwrap();
+        documents.insert(document.id.clone(), document);
+    }
+
+    fn get(&self, id: String) -> Option<Document> {
+        self.documents.lock().unwrap().get(&id).cloned()
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/json_values.uniffi.rs"));
@@ -0,0 +1,41 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import kotlinx.serialization.json.*
+import uniffi.json_values.*
+
+// JSON values are `JsonElement`s.
+val parsed = parseJson("""{"name": "uniffi", "tags": ["ffi", 1, 2.5, null], "ok": true}""")
+assert(parsed == buildJsonObject {
+    put("name", "uniffi")
+    putJsonArray("tags") {
+        add("ffi")
+        add(1)
+        add(2.5)
+        add(JsonNull)
+    }
+    put("ok", true)
+})
+assert(parseJson("42") == JsonPrimitive(42))
+assert(stringifyJson(buildJsonObject { putJsonObject("nested") { putJsonArray("list") {} } }) == """{"nested":{"list":[]}}""")
+assert(getField(buildJsonObject { put("a", "b") }, "a") == JsonPrimitive("b"))
+assert(getField(buildJsonObject { put("a", "b") }, "missing") == JsonNull)
+
+try {
+    parseJson("{")
+    throw RuntimeException("Should have thrown an InvalidJson exception!")
+} catch (e: JsonException.InvalidJson) {
+    // It's okay!
+}
+
+assert(maybeJson(true) == buildJsonObject { put("some", true) })
+assert(maybeJson(false) == null)
+
+// JSON values can be nested in records.
+Store().use { store ->
+    val document = Document("one", buildJsonObject { put("count", 1) })
+    store.put(document)
+    assert(store.get("one") == document)
+    assert(store.get("two") == null)
+}
@@ -0,0 +1,41 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+import math
+from json_values import *
+
+# JSON values are dicts, lists and scalars.
+assert parse_json('{"name": "uniffi", "tags": ["ffi", 1, 2.5, null], "ok": true}') == {
+    "name": "uniffi",
+    "tags": ["ffi", 1, 2.5, None],
+    "ok": True,
+}
+assert parse_json("42") == 42
+assert stringify_json({"nested": {"list": []}}) == '{"nested":{"list":[]}}'
+assert get_field({"a": "b"}, "a") == "b"
+assert get_field({"a": "b"}, "missing") is None
+
+try:
+    parse_json("{")
+    assert(not("Should have thrown an InvalidJson exception!"))
+except JsonError.InvalidJson:
+    # It's okay!
+    pass
+
+# NaN isn't valid JSON.
+try:
+    stringify_json(math.nan)
+    assert(not("Should have thrown a ValueError exception!"))
+except ValueError:
+    # It's okay!
+    pass
+
+assert maybe_json(True) == {"some": True}
+assert maybe_json(False) is None
+
+# JSON values can be nested in records.
+store = Store()
+store.put(Document(id="one", payload={"count": 1}))
+assert store.get("one") == Document(id="one", payload={"count": 1})
+assert store.get("two") is None
@@ -0,0 +1,37 @@
+# frozen_string_literal: true
+
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+require 'test/unit'
+require 'json_values'
+
+class TestJsonValues < Test::Unit::TestCase
+  def test_json_values_are_hashes_arrays_and_scalars
+    parsed = JsonValues.parse_json '{"name": "uniffi", "tags": ["ffi", 1, 2.5, null], "ok": true}'
+    assert_equal parsed, { 'name' => 'uniffi', 'tags' => ['ffi', 1, 2.5, nil], 'ok' => true }
+    assert_equal JsonValues.parse_json('42'), 42
+    assert_equal JsonValues.stringify_json({ 'nested' => { 'list' => [] } }), '{"nested":{"list":[]}}'
+    assert_equal JsonValues.get_field({ 'a' => 'b' }, 'a'), 'b'
+    assert_nil JsonValues.get_field({ 'a' => 'b' }, 'missing')
+  end
+
+  def test_invalid_json_is_an_error
+    assert_raise JsonValues::JsonError::InvalidJson do
+      JsonValues.parse_json '{'
+    end
+  end
+
+  def test_optional_json_values
+    assert_equal JsonValues.maybe_json(true), { 'some' => true }
+    assert_nil JsonValues.maybe_json(false)
+  end
+
+  def test_json_values_in_records
+    store = JsonValues::Store.new
+    store.put JsonValues::Document.new('one', { 'count' => 1 })
+    assert_equal store.get('one').payload, { 'count' => 1 }
+    assert_nil store.get('two')
+  end
+end
@@ -0,0 +1,34 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import json_values
+
+// JSON values are `JsonValue`s.
+let parsed = try! parseJson(text: "{\"name\": \"uniffi\", \"tags\": [\"ffi\", 1, 2.5, null], \"ok\": true}")
+assert(parsed == .object([
+    "name": .string("uniffi"),
+    "tags": .array([.string("ffi"), .integer(1), .double(2.5), .null]),
+    "ok": .bool(true),
+]))
+assert(try! parseJson(text: "42") == .integer(42))
+assert(stringifyJson(value: .object(["nested": .object(["list": .array([])])])) == "{\"nested\":{\"list\":[]}}")
+assert(getField(value: .object(["a": .string("b")]), key: "a") == .string("b"))
+assert(getField(value: .object(["a": .string("b")]), key: "missing") == .null)
+
+do {
+    let _ = try parseJson(text: "{")
+    fatalError("Should have thrown an InvalidJson exception!")
+} catch JsonError.InvalidJson {
+    // It's okay!
+}
+
+assert(maybeJson(some: true) == .object(["some": .bool(true)]))
+assert(maybeJson(some: false) == nil)
+
+// JSON values can be nested in records.
+let store = Store()
+let document = Document(id: "one", payload: .object(["count": .integer(1)]))
+store.put(document: document)
+assert(store.get(id: "one") == document)
+assert(store.get(id: "two") == nil)
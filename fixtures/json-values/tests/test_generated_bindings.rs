@@ -0,0 +1,6 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_json_values.kts",
+    "tests/bindings/test_json_values.swift",
+    "tests/bindings/test_json_values.py",
+    "tests/bindings/test_json_values.rb",
+);
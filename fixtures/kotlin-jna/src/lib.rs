@@ -0,0 +1,26 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+#[derive(Debug, Clone)]
+pub struct Point {
+    x: f64,
+    y: f64,
+}
+
+fn add(left: u32, right: u32) -> u64 {
+    left as u64 + right as u64
+}
+
+fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    Point {
+        x: (a.x + b.x) / 2.0,
+        y: (a.y + b.y) / 2.0,
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/kotlin_jna.uniffi.rs"));
@@ -0,0 +1,35 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.kotlin_jna.Point as JnaPoint
+import uniffi.kotlin_jna.add as addJna
+import uniffi.kotlin_jna.greet as greetJna
+import uniffi.kotlin_jna.midpoint as midpointJna
+import uniffi.kotlin_jni.Point as JniPoint
+import uniffi.kotlin_jni.add as addJni
+import uniffi.kotlin_jni.greet as greetJni
+import uniffi.kotlin_jni.midpoint as midpointJni
+
+// Compare calling the same functions through JNI, with this component, and through JNA, with the
+// `kotlin-jna` fixture. The average time of a call is printed in nanoseconds.
+fun timeCalls(iterations: Int, call: () -> Unit): Double {
+    // Let the JIT compile the calls first.
+    repeat(iterations) { call() }
+    val start = System.nanoTime()
+    repeat(iterations) { call() }
+    return (System.nanoTime() - start).toDouble() / iterations
+}
+
+val iterations = 1_000_000
+val (jniA, jniB) = JniPoint(0.0, 0.0) to JniPoint(2.0, 4.0)
+val (jnaA, jnaB) = JnaPoint(0.0, 0.0) to JnaPoint(2.0, 4.0)
+for ((name, jni, jna) in listOf(
+    Triple("add", { addJni(1u, 2u); Unit }, { addJna(1u, 2u); Unit }),
+    Triple("greet", { greetJni("JNI"); Unit }, { greetJna("JNA"); Unit }),
+    Triple("midpoint", { midpointJni(jniA, jniB); Unit }, { midpointJna(jnaA, jnaB); Unit }),
+)) {
+    val jniTime = timeCalls(iterations, jni)
+    val jnaTime = timeCalls(iterations, jna)
+    println("%-8s: JNI %8.1f ns, JNA %8.1f ns, %5.1fx faster".format(name, jniTime, jnaTime, jnaTime / jniTime))
+}
@@ -0,0 +1,23 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi::deps::anyhow::Result;
+
+// Runs the benchmark script with `cargo bench -p uniffi-fixture-kotlin-jni`, unless `kts` is listed
+// in `UNIFFI_TESTS_DISABLE_EXTENSIONS` like for the tests.
+fn main() -> Result<()> {
+    let disabled = std::env::var("UNIFFI_TESTS_DISABLE_EXTENSIONS").unwrap_or_default();
+    if disabled.split(',').any(|ext| ext == "kts") {
+        return Ok(());
+    }
+    let script_file = format!(
+        "{}/benches/bench_kotlin_jni.kts",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    uniffi_bindgen::bindings::kotlin::run_test(
+        env!("CARGO_TARGET_TMPDIR"),
+        env!("CARGO_PKG_NAME"),
+        &script_file,
+    )
+}
@@ -0,0 +1,72 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::sync::Mutex;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArithmeticError {
+    #[error("division by zero")]
+    DivisionByZero,
+}
+
+#[derive(Debug, Clone)]
+pub struct Point {
+    x: f64,
+    y: f64,
+}
+
+pub trait Greeter {
+    fn greeting(&self, name: String) -> String;
+}
+
+fn add(left: u32, right: u32) -> u64 {
+    left as u64 + right as u64
+}
+
+fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+}
+
+fn checked_div(dividend: i32, divisor: i32) -> Result<i32, ArithmeticError> {
+    dividend
+        .checked_div(divisor)
+        .ok_or(ArithmeticError::DivisionByZero)
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    Point {
+        x: (a.x + b.x) / 2.0,
+        y: (a.y + b.y) / 2.0,
+    }
+}
+
+fn call_back(greeter: Box<dyn Greeter>, name: String) -> String {
+    greeter.greeting(name)
+}
+
+#[derive(Debug)]
+pub struct Counter {
+    history: Mutex<Vec<u32>>,
+}
+
+impl Counter {
+    fn new(start: u32) -> Self {
+        Self {
+            history: Mutex::new(vec![start]),
+        }
+    }
+
+    fn increment(&self) -> u32 {
+        let mut history = self.history.lock().unwrap();
+        let next = history.last().unwrap() + 1;
+        history.push(next);
+        next
+    }
+
+    fn history(&self) -> Vec<u32> {
+        self.history.lock().unwrap().clone()
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/kotlin_jni.uniffi.rs"));
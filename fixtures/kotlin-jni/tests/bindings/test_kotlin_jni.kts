@@ -0,0 +1,29 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.kotlin_jni.*
+
+assert(add(4_000_000_000u, 4_000_000_000u) == 8_000_000_000uL)
+assert(greet("JNI") == "Hello, JNI!")
+
+assert(checkedDiv(7, 2) == 3)
+try {
+    checkedDiv(1, 0)
+    throw RuntimeException("Should have thrown a DivisionByZero exception!")
+} catch (e: ArithmeticException.DivisionByZero) {
+    // It's okay!
+}
+
+assert(midpoint(Point(0.0, 0.0), Point(2.0, 4.0)) == Point(1.0, 2.0))
+
+Counter(41u).use { counter ->
+    assert(counter.increment() == 42u)
+    assert(counter.increment() == 43u)
+    assert(counter.history() == listOf(41u, 42u, 43u))
+}
+
+class KotlinGreeter : Greeter {
+    override fun greeting(name: String) = "Hi, $name!"
+}
+assert(callBack(KotlinGreeter(), "Kotlin") == "Hi, Kotlin!")
@@ -0,0 +1,35 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Empty,
+}
+
+fn scale(shape: Shape, factor: f64) -> Shape {
+    match shape {
+        Shape::Circle { radius } => Shape::Circle {
+            radius: radius * factor,
+        },
+        Shape::Rectangle { width, height } => Shape::Rectangle {
+            width: width * factor,
+            height: height * factor,
+        },
+        Shape::Empty => Shape::Empty,
+    }
+}
+
+fn describe(shape: Shape) -> String {
+    match shape {
+        Shape::Circle { radius } => format!("circle of radius {radius}"),
+        Shape::Rectangle { width, height } => format!("{width}x{height} rectangle"),
+        Shape::Empty => "nothing".to_string(),
+    }
+}
+
+include!(concat!(
+    env!("OUT_DIR"),
+    "/kotlin_sealed_interfaces.uniffi.rs"
+));
@@ -0,0 +1,27 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.kotlin_sealed_interfaces.*
+
+// The variants are matched exhaustively, without an `else` branch.
+fun area(shape: Shape) = when (shape) {
+    is Shape.Circle -> 3.0 * shape.radius * shape.radius
+    is Shape.Rectangle -> shape.width * shape.height
+    Shape.Empty -> 0.0
+}
+
+assert(scale(Shape.Circle(1.0), 2.0) == Shape.Circle(2.0))
+assert(scale(Shape.Rectangle(1.0, 2.0), 3.0) == Shape.Rectangle(3.0, 6.0))
+assert(scale(Shape.Empty, 2.0) == Shape.Empty)
+assert(area(Shape.Rectangle(2.0, 3.0)) == 6.0)
+assert(area(Shape.Empty) == 0.0)
+
+assert(describe(Shape.Circle(1.5)) == "circle of radius 1.5")
+assert(describe(Shape.Empty) == "nothing")
+
+// The variants without data are data objects.
+assert(Shape.Empty.toString() == "Empty")
+
+// The variants are data classes, so they can be copied.
+assert(Shape.Rectangle(1.0, 2.0).copy(height = 4.0) == Shape.Rectangle(1.0, 4.0))
@@ -0,0 +1,3 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_kotlin_sealed_interfaces.kts",
+);
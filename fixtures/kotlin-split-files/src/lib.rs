@@ -0,0 +1,60 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::sync::Mutex;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CounterError {
+    #[error("the name of the counter is empty")]
+    EmptyName,
+}
+
+pub struct Point {
+    x: f64,
+    y: f64,
+}
+
+pub enum Direction {
+    Up,
+    Down,
+}
+
+pub struct Counter {
+    name: String,
+    value: Mutex<u32>,
+}
+
+impl Counter {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn step(&self, direction: Direction) -> u32 {
+        let mut value = self.value.lock().unwrap();
+        *value = match direction {
+            Direction::Up => *value + 1,
+            Direction::Down => value.saturating_sub(1),
+        };
+        *value
+    }
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    Point {
+        x: (a.x + b.x) / 2.0,
+        y: (a.y + b.y) / 2.0,
+    }
+}
+
+fn new_counter(name: String, start: u32) -> Result<std::sync::Arc<Counter>, CounterError> {
+    if name.is_empty() {
+        return Err(CounterError::EmptyName);
+    }
+    Ok(std::sync::Arc::new(Counter {
+        name,
+        value: Mutex::new(start),
+    }))
+}
+
+include!(concat!(env!("OUT_DIR"), "/kotlin_split_files.uniffi.rs"));
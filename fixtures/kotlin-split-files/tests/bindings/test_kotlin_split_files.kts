@@ -0,0 +1,21 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.kotlin_split_files.*
+
+// The bindings are split over several files, compiled together.
+assert(midpoint(Point(0.0, 0.0), Point(2.0, 4.0)) == Point(1.0, 2.0))
+
+newCounter("steps", 1u).use { counter ->
+    assert(counter.name() == "steps")
+    assert(counter.step(Direction.UP) == 2u)
+    assert(counter.step(Direction.DOWN) == 1u)
+}
+
+try {
+    newCounter("", 0u)
+    throw RuntimeException("Should have thrown an EmptyName exception!")
+} catch (e: CounterException.EmptyName) {
+    // It's okay!
+}
@@ -0,0 +1,40 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub struct Guid(String);
+
+impl UniffiCustomTypeConverter for Guid {
+    type Builtin = String;
+
+    fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
+        Ok(Guid(val))
+    }
+
+    fn from_custom(obj: Self) -> Self::Builtin {
+        obj.0
+    }
+}
+
+pub struct Tagged {
+    name: String,
+    guid: Guid,
+}
+
+fn new_guid(seed: u64) -> Guid {
+    Guid(format!("guid-{seed}"))
+}
+
+fn guid_prefix(guid: Guid) -> String {
+    guid.0.split('-').next().unwrap_or_default().to_string()
+}
+
+fn new_guids(count: u32) -> Vec<Guid> {
+    (0..count as u64).map(new_guid).collect()
+}
+
+fn tag(name: String, guid: Guid) -> Tagged {
+    Tagged { name, guid }
+}
+
+include!(concat!(env!("OUT_DIR"), "/kotlin_value_classes.uniffi.rs"));
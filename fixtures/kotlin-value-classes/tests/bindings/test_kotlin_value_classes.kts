@@ -0,0 +1,20 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.kotlin_value_classes.*
+
+// The custom type is a value class wrapping the string, rather than an alias of `String`.
+val guid: Guid = newGuid(42uL)
+assert(guid.value == "guid-42")
+assert(guid == Guid("guid-42"))
+assert(guidPrefix(Guid("abc-def")) == "abc")
+
+// It's distinct from its builtin type at runtime too, once boxed.
+val boxed: Any = guid
+assert(boxed !is String)
+assert(boxed is Guid)
+
+// It can be nested in sequences and records.
+assert(newGuids(2u) == listOf(Guid("guid-0"), Guid("guid-1")))
+assert(tag("answer", guid) == Tagged("answer", Guid("guid-42")))
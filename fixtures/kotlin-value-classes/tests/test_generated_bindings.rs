@@ -0,0 +1 @@
+uniffi_macros::build_foreign_language_testcases!("tests/bindings/test_kotlin_value_classes.kts",);
@@ -2,10 +2,14 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use std::collections::{BTreeSet, HashSet};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+/// A record holding a single number.
 #[derive(uniffi::Record)]
 pub struct One {
+    /// The number.
     inner: i32,
 }
 
@@ -21,21 +25,42 @@ pub struct NestedRecord {
     user_type_in_builtin_generic: Option<Two>,
 }
 
+#[derive(uniffi::Record)]
+pub struct Span {
+    bounds: (u32, u32),
+}
+
+#[derive(uniffi::Record)]
+pub struct Distance {
+    // Known as `meters` in the bindings.
+    #[uniffi(rename = "meters")]
+    m: f64,
+}
+
 #[derive(uniffi::Record)]
 pub struct Three {
     obj: Arc<Object>,
 }
 
+/// An object with a single method.
 #[derive(uniffi::Object)]
 pub struct Object;
 
 #[uniffi::export]
 impl Object {
+    /// Whether the object is heavy.
+    ///
+    /// Nobody knows for sure.
     fn is_heavy(&self) -> MaybeBool {
         MaybeBool::Uncertain
     }
 }
 
+/// Make a `One` holding `inner`.
+///
+/// # Arguments
+///
+/// * `inner` - The number to hold.
 #[uniffi::export]
 fn make_one(inner: i32) -> One {
     One { inner }
@@ -51,11 +76,91 @@ fn make_object() -> Arc<Object> {
     Arc::new(Object)
 }
 
+#[uniffi::export]
+fn span_length(span: Span) -> u32 {
+    span.bounds.1 - span.bounds.0
+}
+
+#[uniffi::export]
+fn double_distance(distance: Distance) -> Distance {
+    Distance {
+        m: distance.m * 2.0,
+    }
+}
+
+/// Return the quotient and the remainder of `dividend` divided by `divisor`.
+#[uniffi::export]
+fn divide(dividend: u32, divisor: u32) -> (u32, u32) {
+    (dividend / divisor, dividend % divisor)
+}
+
+fn swap_pair(pair: (u32, String)) -> (String, u32) {
+    (pair.1, pair.0)
+}
+
+fn unique_words(words: Vec<String>) -> HashSet<String> {
+    words.into_iter().collect()
+}
+
+// Characters with a multi-character uppercase, like `ß`, are kept as they are.
+fn to_upper(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("The input is empty")]
+    Empty,
+    #[error("The input isn't a number")]
+    Invalid,
+}
+
+pub struct Attempt {
+    count: u32,
+    outcome: Result<u32, ParseError>,
+}
+
+// Each input is parsed on its own, so that a bad one doesn't fail the others.
+fn parse_numbers(inputs: Vec<String>) -> Vec<Result<u32, ParseError>> {
+    inputs
+        .iter()
+        .map(|input| match input.as_str() {
+            "" => Err(ParseError::Empty),
+            input => input.parse().map_err(|_| ParseError::Invalid),
+        })
+        .collect()
+}
+
+// A failed attempt succeeds on retry, with the number of attempts it took.
+fn retry(attempt: Attempt) -> Attempt {
+    let count = attempt.count + 1;
+    Attempt {
+        count,
+        outcome: attempt.outcome.or(Ok(count)),
+    }
+}
+
+#[uniffi::export]
+fn char_code(c: char) -> u32 {
+    c.into()
+}
+
+#[uniffi::export]
+fn sort_set(set: HashSet<u32>) -> BTreeSet<u32> {
+    set.into_iter().collect()
+}
+
+/// A boolean which can also be uncertain.
 #[derive(uniffi::Enum)]
 pub enum MaybeBool {
     True,
     False,
-    Uncertain,
+    /// Neither true nor false.
+    Uncertain = 10,
 }
 
 #[uniffi::export]
@@ -63,8 +168,59 @@ fn enum_identity(value: MaybeBool) -> MaybeBool {
     value
 }
 
+pub trait Shape: Send + Sync {
+    fn area(&self) -> f64;
+}
+
+struct Square {
+    side: f64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+pub trait Greeter {
+    fn greet(&self, name: String) -> String;
+}
+
+#[uniffi::export]
+fn make_square(side: f64) -> Arc<dyn Shape> {
+    Arc::new(Square { side })
+}
+
+#[uniffi::export]
+fn total_area(first: Arc<dyn Shape>, second: Arc<dyn Shape>) -> f64 {
+    first.area() + second.area()
+}
+
+#[uniffi::export]
+fn greet_twice(greeter: Box<dyn Greeter>, name: String) -> String {
+    format!("{} {}", greeter.greet(name.clone()), greeter.greet(name))
+}
+
+static INIT_CALLS: AtomicU32 = AtomicU32::new(0);
+
+#[uniffi::init]
+fn count_init() {
+    INIT_CALLS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[uniffi::shutdown]
+fn shutdown() {}
+
+/// The number of times the init hook ran, once whatever the bindings.
+#[uniffi::export]
+fn init_calls() -> u32 {
+    INIT_CALLS.load(Ordering::SeqCst)
+}
+
 include!(concat!(env!("OUT_DIR"), "/proc-macro.uniffi.rs"));
 
 mod uniffi_types {
-    pub use crate::{MaybeBool, NestedRecord, Object, One, Three, Two};
+    pub use crate::{
+        Distance, Greeter, MaybeBool, NestedRecord, Object, One, Shape, Span, Three, Two,
+    };
 }
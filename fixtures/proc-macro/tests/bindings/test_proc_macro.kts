@@ -15,5 +15,56 @@ assert(obj.isHeavy() == MaybeBool.UNCERTAIN)
 
 assert(enumIdentity(MaybeBool.TRUE) == MaybeBool.TRUE)
 
+// `value` is the Rust discriminant, not the position of the variant.
+assert(MaybeBool.FALSE.value == 1L)
+assert(MaybeBool.UNCERTAIN.value == 10L)
+assert(MaybeBool.fromValue(10L) == MaybeBool.UNCERTAIN)
+assert(enumIdentity(MaybeBool.UNCERTAIN) == MaybeBool.UNCERTAIN)
+
+// Tuples, from the proc-macros and from the UDL.
+assert(divide(7u, 2u) == Pair(3u, 1u))
+assert(spanLength(Span(Pair(2u, 5u))) == 3u)
+assert(swapPair(Pair(1u, "one")) == Pair("one", 1u))
+
+// Renamed fields, from the proc-macros.
+assert(doubleDistance(Distance(1.5)).meters == 3.0)
+
+// Sets, from the proc-macros and from the UDL.
+assert(uniqueWords(listOf("a", "b", "a")) == setOf("a", "b"))
+// The items of a `BTreeSet` come in order.
+assert(sortSet(setOf(3u, 1u, 2u)).toList() == listOf(1u, 2u, 3u))
+
+// Chars, from the proc-macros and from the UDL.
+assert(toUpper('é') == 'É')
+assert(charCode('a') == 97u)
+try {
+    charCode('\uD800')
+    throw RuntimeException("Should have thrown an IllegalArgumentException")
+} catch (e: IllegalArgumentException) {
+    // Surrogates aren't Unicode scalar values.
+}
+
+// Results, from the UDL.
+val parsed = parseNumbers(listOf("12", "", "twelve"))
+assert(parsed[0] == Result.success(12u))
+assert(parsed[1].exceptionOrNull() is ParseException.Empty)
+assert(parsed[2].exceptionOrNull() is ParseException.Invalid)
+assert(retry(Attempt(1u, Result.failure(ParseException.Invalid("twelve")))) == Attempt(2u, Result.success(2u)))
+assert(retry(Attempt(1u, Result.success(5u))) == Attempt(2u, Result.success(5u)))
+
 // just make sure this works / doesn't crash
 val three = Three(obj)
+
+// Trait objects, from the proc-macros.
+val square = makeSquare(2.0)
+assert(square.area() == 4.0)
+assert(totalArea(square, makeSquare(3.0)) == 13.0)
+
+class KotlinGreeter : Greeter {
+    override fun greet(name: String): String = "Hello $name!"
+}
+
+assert(greetTwice(KotlinGreeter(), "Ferris") == "Hello Ferris! Hello Ferris!")
+
+// The init hook ran when the library was loaded.
+assert(initCalls() == 1u)
@@ -2,8 +2,19 @@
 # License, v. 2.0. If a copy of the MPL was not distributed with this
 # file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+import importlib
+import inspect
+import uniffi_proc_macro
 from uniffi_proc_macro import *
 
+# The FFI symbols start with the `ffi_prefix` of `uniffi.toml`.
+ffi_names = [name for name in dir(uniffi_proc_macro._UniFFILib) if "proc_macro" in name]
+assert ffi_names
+assert all(
+    name.startswith(("fixture_", "ffi_fixture_", "_uniffi_fixture_", "uniffi_fixture_"))
+    for name in ffi_names
+)
+
 one = make_one(123)
 assert one.inner == 123
 
@@ -15,5 +26,76 @@ assert obj.is_heavy() == MaybeBool.UNCERTAIN
 
 assert enum_identity(MaybeBool.TRUE) == MaybeBool.TRUE
 
+# `enum_class` is set to `IntEnum` in `uniffi.toml`, so the values are the Rust discriminants.
+assert MaybeBool.TRUE == 0
+assert MaybeBool.UNCERTAIN == 10
+assert MaybeBool(10) is MaybeBool.UNCERTAIN
+
+# Tuples, from the proc-macros and from the UDL.
+assert divide(7, 2) == (3, 1)
+assert span_length(Span((2, 5))) == 3
+assert swap_pair((1, "one")) == ("one", 1)
+
+# Renamed fields, from the proc-macros.
+assert double_distance(Distance(1.5)).meters == 3.0
+
+# Sets, from the proc-macros and from the UDL.
+assert unique_words(["a", "b", "a"]) == {"a", "b"}
+assert sort_set({3, 1, 2}) == {1, 2, 3}
+# Any iterable can be passed, duplicates are dropped.
+assert sort_set([2, 2, 1]) == {1, 2}
+
+# Chars, from the proc-macros and from the UDL.
+assert to_upper("é") == "É"
+assert char_code("🦀") == 0x1F980
+try:
+    char_code("ab")
+    raise AssertionError("Should have raised a TypeError")
+except TypeError:
+    pass
+try:
+    char_code("\ud800")
+    raise AssertionError("Should have raised a ValueError")
+except ValueError:
+    pass
+
+# Results, from the UDL.
+parsed = parse_numbers(["12", "", "twelve"])
+assert parsed[0] == Ok(12)
+assert isinstance(parsed[1], Err) and isinstance(parsed[1].error, ParseError.Empty)
+assert isinstance(parsed[2], Err) and isinstance(parsed[2].error, ParseError.Invalid)
+assert retry(Attempt(1, Err(ParseError.Invalid("twelve")))) == Attempt(2, Ok(2))
+assert retry(Attempt(1, Ok(5))) == Attempt(2, Ok(5))
+try:
+    retry(Attempt(1, 5))
+    raise AssertionError("Should have raised a TypeError")
+except TypeError:
+    pass
+
 # just make sure this works / doesn't crash
 three = Three(obj)
+
+# The Rust documentation is rendered as docstrings.
+assert "uniffi_proc_macro" in uniffi_proc_macro.__doc__
+assert inspect.getdoc(One) == "A record holding a single number.\n\nAttributes:\n    inner: The number."
+assert inspect.getdoc(Object) == "An object with a single method."
+assert inspect.getdoc(Object.is_heavy) == "Whether the object is heavy.\n\nNobody knows for sure."
+assert inspect.getdoc(make_one) == "Make a `One` holding `inner`.\n\nArgs:\n    inner (int): The number to hold."
+assert inspect.getdoc(MaybeBool) == "A boolean which can also be uncertain.\n\nAttributes:\n    UNCERTAIN: Neither true nor false."
+assert take_two.__doc__ is None
+
+# Trait objects, from the proc-macros.
+square = make_square(2.0)
+assert square.area() == 4.0
+assert total_area(square, make_square(3.0)) == 13.0
+
+class PythonGreeter(Greeter):
+    def greet(self, name):
+        return f"Hello {name}!"
+
+assert greet_twice(PythonGreeter(), "Ferris") == "Hello Ferris! Hello Ferris!"
+
+# The init hook ran when the module was loaded, and only then even if it is loaded again.
+assert init_calls() == 1
+importlib.reload(uniffi_proc_macro)
+assert uniffi_proc_macro.init_calls() == 1
@@ -15,5 +15,43 @@ assert(obj.isHeavy() == .uncertain)
 
 assert(enumIdentity(value: .true) == .true)
 
+// Tuples, from the proc-macros and from the UDL.
+assert(divide(dividend: 7, divisor: 2) == (3, 1))
+assert(spanLength(span: Span(bounds: (2, 5))) == 3)
+assert(swapPair(pair: (1, "one")) == ("one", 1))
+
+// Renamed fields, from the proc-macros.
+assert(doubleDistance(distance: Distance(meters: 1.5)).meters == 3.0)
+
+// Sets, from the proc-macros and from the UDL.
+assert(uniqueWords(words: ["a", "b", "a"]) == ["a", "b"])
+assert(sortSet(set: [3, 1, 2]) == [1, 2, 3])
+
+// Chars, from the proc-macros and from the UDL.
+assert(toUpper(c: "é") == "É")
+assert(charCode(c: "🦀") == 0x1F980)
+
+// Results, from the UDL.
+let parsed = parseNumbers(inputs: ["12", "", "twelve"])
+assert(try! parsed[0].get() == 12)
+guard case .failure(.Empty) = parsed[1] else { fatalError("Should have failed with ParseError.Empty") }
+guard case .failure(.Invalid) = parsed[2] else { fatalError("Should have failed with ParseError.Invalid") }
+assert(retry(attempt: Attempt(count: 1, outcome: .failure(.Invalid(message: "twelve")))) == Attempt(count: 2, outcome: .success(2)))
+assert(retry(attempt: Attempt(count: 1, outcome: .success(5))) == Attempt(count: 2, outcome: .success(5)))
+
 // just make sure this works / doesn't crash
 let three = Three(obj: obj)
+
+// Trait objects, from the proc-macros.
+let square = makeSquare(side: 2.0)
+assert(square.area() == 4.0)
+assert(totalArea(first: square, second: makeSquare(side: 3.0)) == 13.0)
+
+class SwiftGreeter: Greeter {
+    func greet(name: String) -> String { "Hello \(name)!" }
+}
+
+assert(greetTwice(greeter: SwiftGreeter(), name: "Ferris") == "Hello Ferris! Hello Ferris!")
+
+// The init hook ran before the first call into the library.
+assert(initCalls() == 1)
@@ -0,0 +1,67 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi::deps::anyhow::Result;
+use uniffi_testing::UniFFITestHelper;
+
+// KDoc isn't kept in the compiled classes, so this checks the generated source rather than running
+// a script against it.
+#[test]
+fn kdoc_from_rust_documentation() -> Result<()> {
+    let test_helper = UniFFITestHelper::new(env!("CARGO_PKG_NAME"))?;
+    let out_dir = test_helper.create_out_dir(env!("CARGO_TARGET_TMPDIR"), "kdoc")?;
+    let source = test_helper.get_main_compile_source()?;
+    uniffi_bindgen::generate_bindings(
+        &source.udl_path,
+        source.config_path.as_deref(),
+        vec!["kotlin"],
+        Some(&out_dir),
+        Some(&test_helper.cdylib_path()?),
+        None,
+        false,
+    )?;
+    let kotlin =
+        std::fs::read_to_string(out_dir.join("uniffi/fixture/proc_macro/uniffi_proc_macro.kt"))?;
+
+    assert!(kotlin.contains(concat!(
+        "/**\n",
+        " * A record holding a single number.\n",
+        " */\n",
+        "data class One (\n",
+        "    /**\n",
+        "     * The number.\n",
+        "     */\n",
+        "    var `inner`: Int\n",
+    )));
+    assert!(kotlin.contains(concat!(
+        "/**\n",
+        " * An object with a single method.\n",
+        " */\n",
+        "class Object(\n",
+    )));
+    assert!(kotlin.contains(concat!(
+        "    /**\n",
+        "     * Whether the object is heavy.\n",
+        "     *\n",
+        "     * Nobody knows for sure.\n",
+        "     */\n",
+    )));
+    assert!(kotlin.contains(concat!(
+        "    /**\n",
+        "     * Neither true nor false.\n",
+        "     */\n",
+        "    UNCERTAIN(10L);\n",
+    )));
+    assert!(kotlin.contains(concat!(
+        "/**\n",
+        " * Make a `One` holding `inner`.\n",
+        " *\n",
+        " * # Arguments\n",
+        " *\n",
+        " * * `inner` - The number to hold.\n",
+        " */\n",
+        "fun `makeOne`(",
+    )));
+    Ok(())
+}
@@ -0,0 +1,22 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub struct Frame {
+    data: Vec<u8>,
+}
+
+fn reverse_bytes(mut input: Vec<u8>) -> Vec<u8> {
+    input.reverse();
+    input
+}
+
+fn sum_bytes(input: Vec<u8>) -> u64 {
+    input.into_iter().map(u64::from).sum()
+}
+
+fn make_frame(data: Vec<u8>) -> Frame {
+    Frame { data }
+}
+
+include!(concat!(env!("OUT_DIR"), "/memoryview_bytes.uniffi.rs"));
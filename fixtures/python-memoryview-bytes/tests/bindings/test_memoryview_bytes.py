@@ -0,0 +1,33 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import array
+import gc
+from memoryview_bytes import *
+
+# Returned bytes are a view over the memory of the Rust buffer.
+result = reverse_bytes(b"abc")
+assert isinstance(result, memoryview)
+assert bytes(result) == b"cba"
+assert bytes(reverse_bytes(b"")) == b""
+
+# A slice of the view keeps the buffer alive.
+view = reverse_bytes(b"abc")[1:]
+gc.collect()
+assert bytes(view) == b"ba"
+
+# Any object implementing the buffer protocol can be passed, as well as sequences of ints.
+assert bytes(reverse_bytes(bytearray(b"abc"))) == b"cba"
+assert bytes(reverse_bytes(memoryview(b"xabcx")[1:4])) == b"cba"
+assert bytes(reverse_bytes(memoryview(b"abcdef")[::2])) == b"eca"
+assert bytes(reverse_bytes(result)) == b"abc"
+assert bytes(reverse_bytes([1, 2, 3])) == b"\x03\x02\x01"
+# Buffers of larger items are passed as their raw bytes.
+assert sum_bytes(array.array("H", [0x0101, 0x0101])) == 4
+
+# Bytes nested in other types are memoryviews too.
+frame = make_frame(b"data")
+assert isinstance(frame.data, memoryview)
+assert bytes(frame.data) == b"data"
+assert bytes(make_frame(bytearray(b"more")).data) == b"more"
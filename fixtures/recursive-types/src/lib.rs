@@ -0,0 +1,51 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// The fields through which the types contain themselves are boxed.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Num { value: i64 },
+    Neg { value: Box<Expr> },
+    Add { left: Box<Expr>, right: Box<Expr> },
+    Mul { left: Box<Expr>, right: Box<Expr> },
+}
+
+#[derive(Debug, Clone)]
+pub struct Chain {
+    value: i32,
+    next: Option<Box<Chain>>,
+}
+
+fn evaluate(expr: Expr) -> i64 {
+    match expr {
+        Expr::Num { value } => value,
+        Expr::Neg { value } => -evaluate(*value),
+        Expr::Add { left, right } => evaluate(*left) + evaluate(*right),
+        Expr::Mul { left, right } => evaluate(*left) * evaluate(*right),
+    }
+}
+
+fn negate(expr: Expr) -> Expr {
+    match expr {
+        Expr::Neg { value } => *value,
+        expr => Expr::Neg {
+            value: Box::new(expr),
+        },
+    }
+}
+
+fn chain_from(values: Vec<i32>) -> Option<Chain> {
+    values.into_iter().rev().fold(None, |next, value| {
+        Some(Chain {
+            value,
+            next: next.map(Box::new),
+        })
+    })
+}
+
+fn sum_chain(chain: Chain) -> i32 {
+    chain.value + chain.next.map_or(0, |next| sum_chain(*next))
+}
+
+include!(concat!(env!("OUT_DIR"), "/recursive_types.uniffi.rs"));
@@ -0,0 +1,16 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.recursive_types.*
+
+// (2 + 3) * -4
+val expr = Expr.Mul(Expr.Add(Expr.Num(2L), Expr.Num(3L)), Expr.Neg(Expr.Num(4L)))
+assert(evaluate(expr) == -20L)
+assert(negate(expr) == Expr.Neg(expr))
+assert(negate(Expr.Neg(expr)) == expr)
+
+val chain = chainFrom(listOf(1, 2, 3))!!
+assert(chain == Chain(1, Chain(2, Chain(3, null))))
+assert(sumChain(chain) == 6)
+assert(chainFrom(listOf()) == null)
@@ -0,0 +1,16 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+from recursive_types import *
+
+# (2 + 3) * -4
+expr = Expr.MUL(Expr.ADD(Expr.NUM(2), Expr.NUM(3)), Expr.NEG(Expr.NUM(4)))
+assert evaluate(expr) == -20
+assert evaluate(negate(expr)) == 20
+assert evaluate(negate(negate(expr))) == -20
+
+chain = chain_from([1, 2, 3])
+assert chain == Chain(1, Chain(2, Chain(3, None)))
+assert sum_chain(chain) == 6
+assert chain_from([]) is None
@@ -0,0 +1,17 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import recursive_types
+
+// (2 + 3) * -4
+let expr = Expr.mul(left: .add(left: .num(value: 2), right: .num(value: 3)), right: .neg(value: .num(value: 4)))
+assert(evaluate(expr: expr) == -20)
+assert(negate(expr: expr) == .neg(value: expr))
+assert(negate(expr: .neg(value: expr)) == expr)
+
+// Recursive records are classes.
+let chain = chainFrom(values: [1, 2, 3])!
+assert(chain == Chain(value: 1, next: Chain(value: 2, next: Chain(value: 3, next: nil))))
+assert(sumChain(chain: chain) == 6)
+assert(chainFrom(values: []) == nil)
@@ -0,0 +1,5 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_recursive_types.kts",
+    "tests/bindings/test_recursive_types.swift",
+    "tests/bindings/test_recursive_types.py",
+);
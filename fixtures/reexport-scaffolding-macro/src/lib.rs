@@ -1,12 +1,12 @@
 uniffi_fixture_callbacks::uniffi_reexport_scaffolding!();
 uniffi_coverall::uniffi_reexport_scaffolding!();
+uniffi_iterators::uniffi_reexport_scaffolding!();
 
 #[cfg(test)]
 mod tests {
     use cargo_metadata::Message;
     use libloading::{Library, Symbol};
     use std::ffi::CString;
-    use std::os::raw::c_void;
     use std::process::{Command, Stdio};
     use uniffi::{FfiConverter, ForeignCallback, RustBuffer, RustCallStatus};
     use uniffi_bindgen::ComponentInterface;
@@ -141,19 +141,18 @@ mod tests {
                 .ffi_func()
                 .name(),
         );
-        let coveralls_new: Symbol<
-            unsafe extern "C" fn(RustBuffer, &mut RustCallStatus) -> *const c_void,
-        > = get_symbol(
-            &library,
-            object_def.primary_constructor().unwrap().ffi_func().name(),
-        );
+        let coveralls_new: Symbol<unsafe extern "C" fn(RustBuffer, &mut RustCallStatus) -> u64> =
+            get_symbol(
+                &library,
+                object_def.primary_constructor().unwrap().ffi_func().name(),
+            );
         let coveralls_get_name: Symbol<
-            unsafe extern "C" fn(*const c_void, &mut RustCallStatus) -> RustBuffer,
+            unsafe extern "C" fn(u64, &mut RustCallStatus) -> RustBuffer,
         > = get_symbol(
             &library,
             object_def.get_method("get_name").ffi_func().name(),
         );
-        let coveralls_free: Symbol<unsafe extern "C" fn(*const c_void, &mut RustCallStatus) -> ()> =
+        let coveralls_free: Symbol<unsafe extern "C" fn(u64, &mut RustCallStatus) -> ()> =
             get_symbol(&library, object_def.ffi_object_free().name());
 
         let num_alive = unsafe { get_num_alive(&mut call_status) };
@@ -177,5 +176,69 @@ mod tests {
         let num_alive = unsafe { get_num_alive(&mut call_status) };
         assert_eq!(call_status.code, 0);
         assert_eq!(num_alive, 0);
+
+        // The stale handle is rejected rather than used.
+        unsafe { coveralls_get_name(obj_id, &mut call_status) };
+        assert_eq!(call_status.code, 2);
+    }
+
+    #[test]
+    fn test_iterator_handles() {
+        let mut call_status = RustCallStatus::default();
+        let library = load_library();
+        let iterators_ci =
+            ComponentInterface::from_webidl(include_str!("../../iterators/src/iterators.udl"))
+                .unwrap();
+        let count_from_def = iterators_ci.get_function_definition("count_from").unwrap();
+        let iterator_type = count_from_def.return_type().unwrap();
+
+        let count_from: Symbol<unsafe extern "C" fn(u32, &mut RustCallStatus) -> u64> =
+            get_symbol(&library, count_from_def.ffi_func().name());
+        let iterator_next: Symbol<unsafe extern "C" fn(u64, &mut RustCallStatus) -> RustBuffer> =
+            get_symbol(
+                &library,
+                iterators_ci.ffi_iterator_next(iterator_type).name(),
+            );
+        let iterator_free: Symbol<unsafe extern "C" fn(u64, &mut RustCallStatus) -> ()> =
+            get_symbol(
+                &library,
+                iterators_ci.ffi_iterator_free(iterator_type).name(),
+            );
+
+        let handle = unsafe { count_from(1, &mut call_status) };
+        assert_eq!(call_status.code, 0);
+
+        let item = unsafe { iterator_next(handle, &mut call_status) };
+        assert_eq!(call_status.code, 0);
+        assert_eq!(Option::<u32>::try_lift(item).unwrap(), Some(1));
+
+        unsafe { iterator_free(handle, &mut call_status) };
+        assert_eq!(call_status.code, 0);
+
+        // Freeing the iterator twice, or advancing it once freed, is rejected rather than using
+        // the freed iterator.
+        unsafe { iterator_free(handle, &mut call_status) };
+        assert_eq!(call_status.code, 2);
+        let mut call_status = RustCallStatus::default();
+        unsafe { iterator_next(handle, &mut call_status) };
+        assert_eq!(call_status.code, 2);
+    }
+
+    #[test]
+    fn test_contract_versions() {
+        let library = load_library();
+        let coveralls_ci =
+            ComponentInterface::from_webidl(include_str!("../../coverall/src/coverall.udl"))
+                .unwrap();
+        let callbacks_ci =
+            ComponentInterface::from_webidl(include_str!("../../callbacks/src/callbacks.udl"))
+                .unwrap();
+
+        // Each component of the library checks its own contract version.
+        for ci in [coveralls_ci, callbacks_ci] {
+            let contract_version: Symbol<unsafe extern "C" fn() -> u32> =
+                get_symbol(&library, &ci.ffi_contract_version_name());
+            assert_eq!(unsafe { contract_version() }, ci.contract_version());
+        }
     }
 }
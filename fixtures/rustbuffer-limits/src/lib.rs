@@ -0,0 +1,23 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// No string can be larger than 1 MiB, and the strings alive at once can't add up to more than 4 MiB.
+#[uniffi::init]
+fn set_limits() {
+    uniffi::set_rustbuffer_limits(uniffi::RustBufferLimits {
+        max_buffer_size: 1024 * 1024,
+        max_total_size: 4 * 1024 * 1024,
+    })
+    .unwrap();
+}
+
+fn repeat(text: String, count: u32) -> String {
+    text.repeat(count as usize)
+}
+
+fn length(text: String) -> u32 {
+    text.len() as u32
+}
+
+include!(concat!(env!("OUT_DIR"), "/rustbuffer_limits.uniffi.rs"));
@@ -0,0 +1,33 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.uniffi_rustbuffer_limits.*
+
+assert(repeat("a", 1000u) == "a".repeat(1000))
+assert(length("a".repeat(1000)) == 1000u)
+
+// The strings returned by Rust are checked before they're handed over.
+val rejected = rustBufferStats().rejected
+try {
+    repeat("a", 2u * 1024u * 1024u)
+    throw RuntimeException("should have thrown a PayloadTooLargeException")
+} catch (e: PayloadTooLargeException) {
+    assert(e.size >= 2L * 1024 * 1024)
+    assert(e.limit == 1024L * 1024)
+}
+assert(rustBufferStats().rejected == rejected + 1)
+
+// The strings passed to Rust are checked before they're allocated.
+try {
+    length("a".repeat(2 * 1024 * 1024))
+    throw RuntimeException("should have thrown a PayloadTooLargeException")
+} catch (e: PayloadTooLargeException) {
+    assert(e.limit == 1024L * 1024)
+}
+
+// The buffers returned to Rust aren't live anymore.
+val stats = rustBufferStats()
+assert(stats.liveBuffers == 0L)
+assert(stats.liveBytes == 0L)
+assert(stats.rejected == rejected + 2)
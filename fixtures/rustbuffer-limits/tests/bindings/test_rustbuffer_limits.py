@@ -0,0 +1,40 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+from uniffi_rustbuffer_limits import *
+
+assert repeat("a", 1000) == "a" * 1000
+assert length("a" * 1000) == 1000
+
+# The strings returned by Rust are checked before they're handed over.
+rejected = rustbuffer_stats().rejected
+try:
+    repeat("a", 2 * 1024 * 1024)
+    raise AssertionError("should have raised a PayloadTooLargeError")
+except PayloadTooLargeError as err:
+    assert err.size >= 2 * 1024 * 1024
+    assert err.limit == 1024 * 1024
+    assert str(err).startswith("payload too large")
+assert rustbuffer_stats().rejected == rejected + 1
+
+# The strings passed to Rust are checked before they're allocated.
+try:
+    length("a" * 2 * 1024 * 1024)
+    raise AssertionError("should have raised a PayloadTooLargeError")
+except PayloadTooLargeError as err:
+    assert err.limit == 1024 * 1024
+
+# It's still an internal error.
+try:
+    repeat("a", 2 * 1024 * 1024)
+    raise AssertionError("should have raised an InternalError")
+except InternalError:
+    pass
+
+# The buffers returned to Rust aren't live anymore.
+stats = rustbuffer_stats()
+assert stats.live_buffers == 0
+assert stats.live_bytes == 0
+assert stats.peak_bytes >= 1000
+assert stats.rejected == rejected + 3
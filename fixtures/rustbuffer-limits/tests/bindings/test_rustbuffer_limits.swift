@@ -0,0 +1,16 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi_rustbuffer_limits
+
+assert(repeat(text: "a", count: 1000) == String(repeating: "a", count: 1000))
+assert(length(text: String(repeating: "a", count: 1000)) == 1000)
+
+// The functions which don't throw can't report the limits as an error, so they stop the process,
+// like panics: this only checks the counters.
+let stats = rustBufferStats()
+assert(stats.liveBuffers == 0)
+assert(stats.liveBytes == 0)
+assert(stats.peakBytes >= 1000)
+assert(stats.rejected == 0)
@@ -0,0 +1,5 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_rustbuffer_limits.kts",
+    "tests/bindings/test_rustbuffer_limits.swift",
+    "tests/bindings/test_rustbuffer_limits.py",
+);
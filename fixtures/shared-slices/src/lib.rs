@@ -0,0 +1,33 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::sync::Arc;
+use uniffi::SharedSlice;
+
+fn zeroes(len: u32) -> SharedSlice {
+    vec![0; len as usize].into()
+}
+
+// The slices returned by `contents()` share the bytes of the dataset, which counts them.
+pub struct Dataset {
+    bytes: Arc<[u8]>,
+}
+
+impl Dataset {
+    fn new(len: u32) -> Self {
+        Self {
+            bytes: (0..len).map(|i| i as u8).collect(),
+        }
+    }
+
+    fn contents(&self) -> SharedSlice {
+        SharedSlice::new(self.bytes.clone())
+    }
+
+    fn live_slices(&self) -> u32 {
+        Arc::strong_count(&self.bytes) as u32 - 1
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/shared_slices.uniffi.rs"));
@@ -0,0 +1,28 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.shared_slices.*
+
+// 256 MiB of zeroes, read in place rather than copied.
+zeroes(256 * 1024 * 1024).use { slice ->
+    assert(slice.buffer.capacity() == 256 * 1024 * 1024)
+    assert(slice.buffer.get(slice.buffer.capacity() - 1) == 0.toByte())
+    assert(slice.buffer.isReadOnly())
+}
+
+assert(zeroes(0).use { it.buffer.capacity() } == 0)
+
+Dataset(300).use { dataset ->
+    assert(dataset.liveSlices() == 0)
+
+    val contents = dataset.contents()
+    assert(dataset.liveSlices() == 1)
+    assert(contents.buffer.get(2) == 2.toByte())
+    assert(contents.buffer.get(299) == (299 % 256).toByte())
+
+    // Releasing the slice drops it on the Rust side, and can be done more than once.
+    contents.release()
+    contents.release()
+    assert(dataset.liveSlices() == 0)
+}
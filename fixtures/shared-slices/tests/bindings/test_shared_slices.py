@@ -0,0 +1,55 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+import struct
+
+from shared_slices import *
+
+# 256 MiB of zeroes, read in place rather than copied.
+with zeroes(256 * 1024 * 1024) as zero_slice:
+    assert len(zero_slice) == 256 * 1024 * 1024
+    assert zero_slice.view[-1] == 0
+    assert zero_slice.view.readonly
+
+with zeroes(0) as empty_slice:
+    assert len(empty_slice.view) == 0
+
+# The view is released along with the slice, rather than reading freed memory.
+try:
+    zero_slice.view[0]
+    raise AssertionError("the view should be released")
+except ValueError:
+    pass
+
+dataset = Dataset(300)
+assert dataset.live_slices() == 0
+
+contents = dataset.contents()
+assert dataset.live_slices() == 1
+assert contents.view[:3].tolist() == [0, 1, 2]
+assert contents.view[299] == 299 % 256
+try:
+    contents.view[0] = 1
+    raise AssertionError("the view should be read-only")
+except TypeError:
+    pass
+
+# The slice can't be released while its view is still exported, and releasing it can be retried.
+items = struct.iter_unpack("B", contents.view)
+try:
+    contents.release()
+    raise AssertionError("the exported view should not be released")
+except BufferError:
+    pass
+assert dataset.live_slices() == 1
+del items
+
+# Releasing the slice drops it on the Rust side, and can be done more than once.
+contents.release()
+contents.release()
+assert dataset.live_slices() == 0
+
+# Slices are also released when garbage collected.
+dataset.contents()
+assert dataset.live_slices() == 0
@@ -0,0 +1,36 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import shared_slices
+
+// 256 MiB of zeroes, read in place rather than copied.
+do {
+    let slice = zeroes(len: 256 * 1024 * 1024)
+    assert(slice.data.count == 256 * 1024 * 1024)
+    assert(slice.data.last == 0)
+    slice.release()
+}
+
+assert(zeroes(len: 0).data.isEmpty)
+
+let dataset = Dataset(len: 300)
+assert(dataset.liveSlices() == 0)
+
+do {
+    let contents = dataset.contents()
+    assert(dataset.liveSlices() == 1)
+    assert(Array(contents.data.prefix(3)) == [0, 1, 2])
+    assert(contents.data[299] == UInt8(299 % 256))
+
+    // Releasing the slice drops it on the Rust side, and can be done more than once.
+    contents.release()
+    contents.release()
+    assert(dataset.liveSlices() == 0)
+}
+
+// Slices are also released when deinitialized.
+do {
+    _ = dataset.contents()
+}
+assert(dataset.liveSlices() == 0)
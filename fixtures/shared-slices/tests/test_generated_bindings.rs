@@ -0,0 +1,5 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_shared_slices.kts",
+    "tests/bindings/test_shared_slices.swift",
+    "tests/bindings/test_shared_slices.py",
+);
@@ -4,12 +4,16 @@
 
 use std::time::{Duration, SystemTime};
 
+use chrono::{DateTime, FixedOffset, Utc};
+
 #[derive(Debug, thiserror::Error)]
 pub enum ChronologicalError {
     #[error("Time overflow on an operation with {a:?} and {b:?}")]
     TimeOverflow { a: SystemTime, b: Duration },
     #[error("Time difference error {a:?} is before {b:?}")]
     TimeDiffError { a: SystemTime, b: SystemTime },
+    #[error("{seconds} seconds is not a valid UTC offset")]
+    InvalidUtcOffset { seconds: i32 },
 }
 
 fn add(a: SystemTime, b: Duration) -> Result<SystemTime> {
@@ -46,6 +50,20 @@ fn set_seconds_before_unix_epoch(seconds: u64) -> Result<SystemTime> {
         .ok_or(ChronologicalError::TimeOverflow { a, b })
 }
 
+fn with_utc_offset(a: SystemTime, seconds: i32) -> Result<DateTime<FixedOffset>> {
+    let offset =
+        FixedOffset::east_opt(seconds).ok_or(ChronologicalError::InvalidUtcOffset { seconds })?;
+    Ok(DateTime::<Utc>::from(a).with_timezone(&offset))
+}
+
+fn to_timestamp(a: DateTime<FixedOffset>) -> SystemTime {
+    a.into()
+}
+
+fn get_utc_offset(a: DateTime<FixedOffset>) -> i32 {
+    a.offset().local_minus_utc()
+}
+
 type Result<T, E = ChronologicalError> = std::result::Result<T, E>;
 
 include!(concat!(env!("OUT_DIR"), "/chronological.uniffi.rs"));
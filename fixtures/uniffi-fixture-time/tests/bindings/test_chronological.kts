@@ -2,6 +2,8 @@ import uniffi.chronological.*;
 import java.time.Duration
 import java.time.Instant
 import java.time.DateTimeException
+import java.time.OffsetDateTime
+import java.time.ZoneOffset
 
 // Test passing timestamp and duration while returning timestamp
 assert(add(Instant.ofEpochSecond(100, 100), Duration.ofSeconds(1, 1))
@@ -50,3 +52,19 @@ assert(kotlinAfter.isAfter(rustNow))
 assert(optional(Instant.MAX, Duration.ofSeconds(0)))
 assert(optional(null, Duration.ofSeconds(0)) == false)
 assert(optional(Instant.MAX, null) == false)
+
+// Test that offset date-times keep their UTC offset
+val preEpoch = Instant.parse("1955-11-05T00:06:00.283001Z")
+val shifted = withUtcOffset(preEpoch, 3600)
+assert(shifted.toInstant().equals(preEpoch))
+assert(shifted.offset.equals(ZoneOffset.ofHours(1)))
+assert(shifted.hour == 1)
+assert(toTimestamp(OffsetDateTime.parse("1955-11-05T01:06:00.283001+01:00")).equals(preEpoch))
+assert(getUtcOffset(OffsetDateTime.parse("2022-01-01T00:00:00-05:30")) == -19800)
+
+try {
+        withUtcOffset(preEpoch, 24 * 3600)
+        throw RuntimeException("Should have thrown a InvalidUtcOffset exception!")
+} catch (e: ChronologicalException.InvalidUtcOffset) {
+        // It's okay!
+}
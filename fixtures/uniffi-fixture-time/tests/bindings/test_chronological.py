@@ -45,3 +45,27 @@ assert abs(datetime.now(timezone.utc) - now()) <= timedelta(seconds=1)
 assert(optional(now(), timedelta(seconds=0)))
 assert(not optional(None, timedelta(seconds=0)))
 assert(not optional(now(), None))
+
+# Test that offset date-times keep their UTC offset.
+pre_epoch = datetime.fromisoformat('1955-11-05T00:06:00.283001+00:00')
+shifted = with_utc_offset(pre_epoch, 3600)
+assert shifted == pre_epoch
+assert shifted.utcoffset() == timedelta(hours=1)
+assert shifted.hour == 1
+assert to_timestamp(datetime.fromisoformat('1955-11-05T01:06:00.283001+01:00')) == pre_epoch
+assert get_utc_offset(datetime(2022, 1, 1, tzinfo=timezone(timedelta(hours=-5, minutes=-30)))) == -19800
+
+try:
+    with_utc_offset(now(), 24 * 3600)
+    assert(not("Should have thrown a InvalidUtcOffset exception!"))
+except ChronologicalError.InvalidUtcOffset:
+    # It's okay!
+    pass
+
+# Naive datetimes can't be offset date-times.
+try:
+    get_utc_offset(datetime(2022, 1, 1))
+    assert(not("Should have thrown a ValueError exception!"))
+except ValueError:
+    # It's okay!
+    pass
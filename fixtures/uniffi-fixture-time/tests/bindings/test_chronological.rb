@@ -73,6 +73,23 @@ class TestChronological < Test::Unit::TestCase
     assert (Time.now.utc - Chronological.now).abs <= 1.0
   end
 
+  def test_offset_datetimes_keep_their_utc_offset
+    pre_epoch = Time.parse '1955-11-05T00:06:00.283001 UTC'
+    shifted   = Chronological.with_utc_offset(pre_epoch, 3600)
+
+    assert_equal pre_epoch, shifted
+    assert_equal 3600, shifted.utc_offset
+    assert_equal 1, shifted.hour
+    assert_equal pre_epoch, Chronological.to_timestamp(Time.parse('1955-11-05T01:06:00.283001 +01:00'))
+    assert_equal(-19_800, Chronological.get_utc_offset(Time.new(2022, 1, 1, 0, 0, 0, '-05:30')))
+  end
+
+  def test_invalid_utc_offsets_are_rejected
+    assert_raises Chronological::ChronologicalError::InvalidUtcOffset do
+      Chronological.with_utc_offset Time.now, 24 * 3600
+    end
+  end
+
   private
 
   def duration(*args)
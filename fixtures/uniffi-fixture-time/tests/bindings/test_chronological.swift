@@ -26,4 +26,18 @@ let rustNow = now()
 let swiftAfter = Date.init()
 
 assert(swiftBefore <= rustNow)
-assert(swiftAfter >= rustNow)
\ No newline at end of file
+assert(swiftAfter >= rustNow)
+// Test that offset date-times keep their UTC offset
+let preEpoch = iso8601.date(from: "1955-11-05T00:06:00.283Z")!
+let shifted = try! withUtcOffset(a: preEpoch, seconds: 3600)
+assert(abs(shifted.date.timeIntervalSince(preEpoch)) < 0.000001, "offset date")
+assert(shifted.timeZone.secondsFromGMT(for: shifted.date) == 3600, "offset time zone")
+assert(abs(toTimestamp(a: shifted).timeIntervalSince(preEpoch)) < 0.000001, "offset to timestamp")
+assert(getUtcOffset(a: OffsetDateTime(date: preEpoch, timeZone: TimeZone(secondsFromGMT: -19800)!)) == -19800, "utc offset")
+
+do {
+    let _ = try withUtcOffset(a: preEpoch, seconds: 24 * 3600)
+    fatalError("Should have thrown a InvalidUtcOffset exception!")
+} catch ChronologicalError.InvalidUtcOffset {
+    // It's okay!
+}
@@ -0,0 +1,26 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.utf16_strings.echo as echoUtf16
+import uniffi.utf8_strings.echo as echoUtf8
+
+// Compare passing strings back and forth as UTF-16 and as UTF-8, through the same `echo()`
+// function of two components. The average time of a call is printed in microseconds.
+fun timeCalls(iterations: Int, call: () -> Unit): Double {
+    // Let the JIT compile the calls first.
+    repeat(iterations) { call() }
+    val start = System.nanoTime()
+    repeat(iterations) { call() }
+    return (System.nanoTime() - start) / 1e3 / iterations
+}
+
+for ((script, char) in listOf("ASCII" to "a", "Latin" to "é", "CJK" to "漢")) {
+    for (length in listOf(10, 1_000, 100_000)) {
+        val value = char.repeat(length)
+        val iterations = maxOf(10, 100_000 / length)
+        val utf16 = timeCalls(iterations) { echoUtf16(value) }
+        val utf8 = timeCalls(iterations) { echoUtf8(value) }
+        println("%-5s %7d chars: UTF-16 %10.2f µs, UTF-8 %10.2f µs".format(script, length, utf16, utf8))
+    }
+}
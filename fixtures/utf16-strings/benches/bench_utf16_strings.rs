@@ -0,0 +1,32 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi::deps::anyhow::Result;
+
+type RunScript = fn(&str, &str, &str) -> Result<()>;
+
+// Runs the benchmark scripts with `cargo bench -p uniffi-fixture-utf16-strings`, skipping the
+// languages listed in `UNIFFI_TESTS_DISABLE_EXTENSIONS` like the tests do.
+fn main() -> Result<()> {
+    let disabled = std::env::var("UNIFFI_TESTS_DISABLE_EXTENSIONS").unwrap_or_default();
+    let scripts: [(&str, RunScript); 2] = [
+        ("kts", uniffi_bindgen::bindings::kotlin::run_test),
+        ("swift", uniffi_bindgen::bindings::swift::run_test),
+    ];
+    for (extension, run_script) in scripts {
+        if disabled.split(',').any(|ext| ext == extension) {
+            continue;
+        }
+        let script_file = format!(
+            "{}/benches/bench_utf16_strings.{extension}",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        run_script(
+            env!("CARGO_TARGET_TMPDIR"),
+            env!("CARGO_PKG_NAME"),
+            &script_file,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import Foundation
+import utf16_strings
+import utf8_strings
+
+// Compare passing strings back and forth as UTF-16 and as UTF-8, through the same `echo()`
+// function of two components. The average time of a call is printed in microseconds.
+func timeCalls(_ iterations: Int, _ call: () -> Void) -> Double {
+    let start = DispatchTime.now().uptimeNanoseconds
+    for _ in 0..<iterations {
+        call()
+    }
+    return Double(DispatchTime.now().uptimeNanoseconds - start) / 1e3 / Double(iterations)
+}
+
+for (script, char) in [("ASCII", "a"), ("Latin", "é"), ("CJK", "漢")] {
+    for length in [10, 1_000, 100_000] {
+        let value = String(repeating: char, count: length)
+        let iterations = max(10, 100_000 / length)
+        let utf16 = timeCalls(iterations) { _ = utf16_strings.echo(value: value) }
+        let utf8 = timeCalls(iterations) { _ = utf8_strings.echo(value: value) }
+        print(String(format: "%@ %7d chars: UTF-16 %10.2f µs, UTF-8 %10.2f µs", script.padding(toLength: 5, withPad: " ", startingAt: 0), length, utf16, utf8))
+    }
+}
@@ -0,0 +1,26 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+#[derive(Debug, Clone)]
+pub struct Text {
+    value: String,
+}
+
+fn echo(value: String) -> String {
+    value
+}
+
+fn count_chars(value: String) -> u32 {
+    value.chars().count() as u32
+}
+
+fn split_words(value: String) -> Vec<String> {
+    value.split_whitespace().map(str::to_string).collect()
+}
+
+fn echo_text(text: Text) -> Text {
+    text
+}
+
+include!(concat!(env!("OUT_DIR"), "/utf16_strings.uniffi.rs"));
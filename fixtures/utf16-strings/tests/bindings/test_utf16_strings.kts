@@ -0,0 +1,21 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.utf16_strings.*
+
+// The crab is outside of the BMP, so it's a surrogate pair in UTF-16.
+for (value in listOf("", "hello", "héllo wörld", "漢字", "🦀 crab")) {
+    assert(echo(value) == value)
+    assert(echoText(Text(value)) == Text(value))
+}
+assert(countChars("🦀 crab") == 6u)
+assert(splitWords("le 🦀 crabe") == listOf("le", "🦀", "crabe"))
+
+// Kotlin strings can hold unpaired surrogates, which aren't valid Rust strings.
+try {
+    echo("\uD800")
+    throw RuntimeException("Should have thrown an InternalException!")
+} catch (e: InternalException) {
+    // It's okay!
+}
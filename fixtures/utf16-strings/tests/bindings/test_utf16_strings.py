@@ -0,0 +1,12 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+from utf16_strings import *
+
+# The crab is outside of the BMP, so it's a surrogate pair in UTF-16.
+for value in ["", "hello", "héllo wörld", "漢字", "🦀 crab"]:
+    assert echo(value) == value
+    assert echo_text(Text(value)) == Text(value)
+assert count_chars("🦀 crab") == 6
+assert split_words("le 🦀 crabe") == ["le", "🦀", "crabe"]
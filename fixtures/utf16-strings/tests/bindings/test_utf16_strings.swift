@@ -0,0 +1,13 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import utf16_strings
+
+// The crab is outside of the BMP, so it's a surrogate pair in UTF-16.
+for value in ["", "hello", "héllo wörld", "漢字", "🦀 crab"] {
+    assert(echo(value: value) == value)
+    assert(echoText(text: Text(value: value)) == Text(value: value))
+}
+assert(countChars(value: "🦀 crab") == 6)
+assert(splitWords(value: "le 🦀 crabe") == ["le", "🦀", "crabe"])
@@ -0,0 +1,5 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_utf16_strings.kts",
+    "tests/bindings/test_utf16_strings.swift",
+    "tests/bindings/test_utf16_strings.py",
+);
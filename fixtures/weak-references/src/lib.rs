@@ -0,0 +1,61 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::sync::{Arc, Mutex, Weak};
+
+pub struct Node {
+    name: String,
+    parent: Option<Weak<Node>>,
+    children: Mutex<Vec<Arc<Node>>>,
+}
+
+impl Node {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            parent: None,
+            children: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn add_child(self: Arc<Self>, name: String) -> Arc<Node> {
+        let child = Arc::new(Node {
+            name,
+            parent: Some(Arc::downgrade(&self)),
+            children: Mutex::new(Vec::new()),
+        });
+        self.children.lock().unwrap().push(Arc::clone(&child));
+        child
+    }
+
+    fn children(&self) -> Vec<Arc<Node>> {
+        self.children.lock().unwrap().clone()
+    }
+
+    fn parent(&self) -> Option<Weak<Node>> {
+        self.parent.clone()
+    }
+
+    fn weak_count(self: Arc<Self>) -> u64 {
+        Arc::weak_count(&self) as u64
+    }
+}
+
+fn downgrade(node: Arc<Node>) -> Weak<Node> {
+    Arc::downgrade(&node)
+}
+
+fn upgrade_name(node: Weak<Node>) -> Option<String> {
+    node.upgrade().map(|node| node.name())
+}
+
+fn count_alive(nodes: Vec<Weak<Node>>) -> u32 {
+    nodes.iter().filter(|node| node.upgrade().is_some()).count() as u32
+}
+
+include!(concat!(env!("OUT_DIR"), "/weak_references.uniffi.rs"));
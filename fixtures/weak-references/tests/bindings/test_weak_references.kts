@@ -0,0 +1,31 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import uniffi.weak_references.*
+
+val root = Node("root")
+val child = root.addChild("child")
+// The nodes in the list are strong references, which must not outlive the test.
+val children = root.children()
+assert(children.map { it.name() } == listOf("child"))
+children.forEach { it.destroy() }
+assert(root.parent() == null)
+
+child.parent()!!.use { parent ->
+    // The parent can be reached from its children while it's alive.
+    parent.upgrade()!!.use { assert(it.name() == "root") }
+    assert(upgradeName(parent) == "root")
+
+    // The children don't keep it alive.
+    root.destroy()
+    assert(parent.upgrade() == null)
+    assert(upgradeName(parent) == null)
+}
+
+// Weak references can be made from any node.
+downgrade(child).use { weak ->
+    weak.upgrade()!!.use { assert(it.name() == "child") }
+    child.destroy()
+    assert(weak.upgrade() == null)
+}
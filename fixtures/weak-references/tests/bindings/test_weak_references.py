@@ -0,0 +1,38 @@
+# This Source Code Form is subject to the terms of the Mozilla Public
+# License, v. 2.0. If a copy of the MPL was not distributed with this
+# file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+from weak_references import *
+
+root = Node("root")
+child = root.add_child("child")
+assert [node.name() for node in root.children()] == ["child"]
+assert root.parent() is None
+
+# The parent can be reached from its children while it's alive.
+parent = child.parent()
+assert parent.upgrade().name() == "root"
+assert upgrade_name(parent) == "root"
+
+# The children don't keep it alive.
+del root
+assert parent.upgrade() is None
+assert upgrade_name(parent) is None
+assert child.parent().upgrade() is None
+
+# Weak references can be made from any node.
+weak = downgrade(child)
+assert weak.upgrade().name() == "child"
+assert child.weak_count() == 1
+assert count_alive([weak, weak]) == 2
+assert child.weak_count() == 1
+
+# The weak references cloned for Rust are freed when writing the buffer fails.
+try:
+    count_alive([weak, "not a weak reference"])
+    raise AssertionError("should have failed")
+except TypeError:
+    pass
+assert child.weak_count() == 1
+del child
+assert weak.upgrade() is None
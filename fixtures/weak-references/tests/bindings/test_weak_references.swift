@@ -0,0 +1,26 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+import weak_references
+
+var root: Node? = Node(name: "root")
+var child: Node? = root!.addChild(name: "child")
+assert(root!.children().map { $0.name() } == ["child"])
+assert(root!.parent() == nil)
+
+// The parent can be reached from its children while it's alive.
+let parent = child!.parent()!
+assert(parent.upgrade()!.name() == "root")
+assert(upgradeName(node: parent) == "root")
+
+// The children don't keep it alive.
+root = nil
+assert(parent.upgrade() == nil)
+assert(upgradeName(node: parent) == nil)
+
+// Weak references can be made from any node.
+let weak = downgrade(node: child!)
+assert(weak.upgrade()!.name() == "child")
+child = nil
+assert(weak.upgrade() == nil)
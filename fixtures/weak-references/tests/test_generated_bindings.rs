@@ -0,0 +1,5 @@
+uniffi_macros::build_foreign_language_testcases!(
+    "tests/bindings/test_weak_references.kts",
+    "tests/bindings/test_weak_references.swift",
+    "tests/bindings/test_weak_references.py",
+);
@@ -0,0 +1,35 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+plugins {
+    `kotlin-dsl`
+    `java-gradle-plugin`
+}
+
+group = "org.mozilla.uniffi"
+version = "0.21.0"
+
+repositories {
+    google()
+    mavenCentral()
+}
+
+dependencies {
+    compileOnly("com.android.tools.build:gradle-api:7.4.0")
+    testImplementation("com.android.tools.build:gradle-api:7.4.0")
+    testImplementation(kotlin("test"))
+}
+
+tasks.test {
+    useJUnitPlatform()
+}
+
+gradlePlugin {
+    plugins {
+        create("uniffi") {
+            id = "org.mozilla.uniffi"
+            implementationClass = "org.mozilla.uniffi.gradle.UniffiPlugin"
+        }
+    }
+}
@@ -0,0 +1 @@
+rootProject.name = "uniffi-gradle-plugin"
@@ -0,0 +1,80 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+package org.mozilla.uniffi.gradle
+
+import javax.inject.Inject
+import org.gradle.api.DefaultTask
+import org.gradle.api.file.DirectoryProperty
+import org.gradle.api.file.RegularFileProperty
+import org.gradle.api.provider.Property
+import org.gradle.api.tasks.CacheableTask
+import org.gradle.api.tasks.Input
+import org.gradle.api.tasks.InputFile
+import org.gradle.api.tasks.Optional
+import org.gradle.api.tasks.OutputDirectory
+import org.gradle.api.tasks.PathSensitive
+import org.gradle.api.tasks.PathSensitivity
+import org.gradle.api.tasks.TaskAction
+import org.gradle.process.ExecOperations
+
+/**
+ * Runs `uniffi-bindgen generate --language kotlin` for a component.
+ *
+ * The UDL, config and library files are tracked as inputs, so the task is up-to-date until one of
+ * them changes.
+ */
+@CacheableTask
+abstract class GenerateUniffiBindingsTask @Inject constructor(
+    private val execOperations: ExecOperations
+) : DefaultTask() {
+    @get:InputFile
+    @get:PathSensitive(PathSensitivity.RELATIVE)
+    abstract val udlFile: RegularFileProperty
+
+    @get:InputFile
+    @get:Optional
+    @get:PathSensitive(PathSensitivity.RELATIVE)
+    abstract val configFile: RegularFileProperty
+
+    @get:InputFile
+    @get:Optional
+    @get:PathSensitive(PathSensitivity.NONE)
+    abstract val libraryFile: RegularFileProperty
+
+    @get:Input
+    abstract val bindgenExecutable: Property<String>
+
+    @get:Input
+    abstract val formatCode: Property<Boolean>
+
+    @get:OutputDirectory
+    abstract val outputDirectory: DirectoryProperty
+
+    @TaskAction
+    fun generate() {
+        val outDir = outputDirectory.get().asFile
+        // Remove the bindings of a previous run, in case the package name changed.
+        outDir.deleteRecursively()
+        outDir.mkdirs()
+
+        execOperations.exec {
+            executable = bindgenExecutable.get()
+            args = bindgenArguments()
+        }
+    }
+
+    /** The arguments `uniffi-bindgen` is run with. */
+    internal fun bindgenArguments(): List<String> {
+        val outDir = outputDirectory.get().asFile
+        val arguments = mutableListOf("generate", "--language", "kotlin", "--out-dir", outDir.path)
+        if (!formatCode.get()) {
+            arguments += "--no-format"
+        }
+        configFile.orNull?.let { arguments += listOf("--config", it.asFile.path) }
+        libraryFile.orNull?.let { arguments += listOf("--lib-file", it.asFile.path) }
+        arguments += udlFile.get().asFile.path
+        return arguments
+    }
+}
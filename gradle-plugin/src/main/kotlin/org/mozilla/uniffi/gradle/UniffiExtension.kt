@@ -0,0 +1,31 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+package org.mozilla.uniffi.gradle
+
+import org.gradle.api.file.RegularFileProperty
+import org.gradle.api.provider.Property
+
+/**
+ * The `uniffi { }` block of a project applying the plugin.
+ */
+abstract class UniffiExtension {
+    /** The UDL file of the component. */
+    abstract val udlFile: RegularFileProperty
+
+    /** The `uniffi.toml` file, when it isn't next to the UDL file. */
+    abstract val configFile: RegularFileProperty
+
+    /**
+     * The built Rust library, for components which use proc-macros. Changes to it cause the
+     * bindings to be generated again.
+     */
+    abstract val libraryFile: RegularFileProperty
+
+    /** The `uniffi-bindgen` executable. Defaults to the one on the `PATH`. */
+    abstract val bindgenExecutable: Property<String>
+
+    /** Whether to format the generated code with `ktlint`. Defaults to `false`. */
+    abstract val formatCode: Property<Boolean>
+}
@@ -0,0 +1,62 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+package org.mozilla.uniffi.gradle
+
+import com.android.build.api.variant.AndroidComponentsExtension
+import org.gradle.api.GradleException
+import org.gradle.api.Plugin
+import org.gradle.api.Project
+
+/**
+ * Generates the Kotlin bindings of a UniFFI component as part of an Android build.
+ *
+ * A `generate{Variant}UniffiBindings` task is registered for each variant, and its output is
+ * added to the variant's Java sources, so the bindings are generated before compilation and show
+ * up as generated sources in Android Studio.
+ */
+class UniffiPlugin : Plugin<Project> {
+    override fun apply(project: Project) {
+        val extension = project.extensions.create("uniffi", UniffiExtension::class.java)
+        extension.bindgenExecutable.convention("uniffi-bindgen")
+        extension.formatCode.convention(false)
+
+        project.plugins.withId("com.android.base") {
+            val androidComponents = project.extensions.getByType(AndroidComponentsExtension::class.java)
+            androidComponents.onVariants { variant ->
+                val name = variant.name.replaceFirstChar { it.uppercase() }
+                val task = project.tasks.register(
+                    "generate${name}UniffiBindings",
+                    GenerateUniffiBindingsTask::class.java
+                ) {
+                    group = "uniffi"
+                    description = "Generates the UniFFI Kotlin bindings for the ${variant.name} variant."
+                    udlFile.set(extension.udlFile)
+                    configFile.set(extension.configFile)
+                    libraryFile.set(extension.libraryFile)
+                    bindgenExecutable.set(extension.bindgenExecutable)
+                    formatCode.set(extension.formatCode)
+                    outputDirectory.set(
+                        project.layout.buildDirectory.dir("generated/source/uniffi/${variant.name}/java")
+                    )
+                }
+                variant.sources.java?.addGeneratedSourceDirectory(
+                    task,
+                    GenerateUniffiBindingsTask::outputDirectory
+                )
+            }
+        }
+
+        project.afterEvaluate {
+            if (!project.plugins.hasPlugin("com.android.base")) {
+                throw GradleException(
+                    "The org.mozilla.uniffi plugin requires the Android application or library plugin"
+                )
+            }
+            if (!extension.udlFile.isPresent) {
+                throw GradleException("uniffi.udlFile must be set")
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+package org.mozilla.uniffi.gradle
+
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+import org.gradle.api.GradleException
+import org.gradle.api.Project
+import org.gradle.api.internal.project.ProjectInternal
+import org.gradle.testfixtures.ProjectBuilder
+
+class UniffiPluginTest {
+    private fun project(): Project = ProjectBuilder.builder().build()
+
+    private fun Project.bindingsTask(configure: GenerateUniffiBindingsTask.() -> Unit) =
+        tasks.register("generateUniffiBindings", GenerateUniffiBindingsTask::class.java) {
+            udlFile.set(file("src/main/rust/arithmetic.udl"))
+            bindgenExecutable.set("uniffi-bindgen")
+            formatCode.set(false)
+            outputDirectory.set(layout.buildDirectory.dir("uniffi"))
+            configure()
+        }.get()
+
+    @Test
+    fun `the bindings are generated without formatting by default`() {
+        val project = project()
+        val task = project.bindingsTask {}
+        assertEquals(
+            listOf(
+                "generate", "--language", "kotlin",
+                "--out-dir", project.buildDir.resolve("uniffi").path,
+                "--no-format",
+                project.file("src/main/rust/arithmetic.udl").path,
+            ),
+            task.bindgenArguments()
+        )
+    }
+
+    @Test
+    fun `the config and library files are passed when set`() {
+        val project = project()
+        val task = project.bindingsTask {
+            formatCode.set(true)
+            configFile.set(project.file("uniffi.toml"))
+            libraryFile.set(project.file("target/debug/libarithmetic.so"))
+        }
+        assertEquals(
+            listOf(
+                "generate", "--language", "kotlin",
+                "--out-dir", project.buildDir.resolve("uniffi").path,
+                "--config", project.file("uniffi.toml").path,
+                "--lib-file", project.file("target/debug/libarithmetic.so").path,
+                project.file("src/main/rust/arithmetic.udl").path,
+            ),
+            task.bindgenArguments()
+        )
+    }
+
+    @Test
+    fun `the extension has conventions`() {
+        val project = project()
+        project.plugins.apply(UniffiPlugin::class.java)
+        val extension = project.extensions.getByType(UniffiExtension::class.java)
+        assertEquals("uniffi-bindgen", extension.bindgenExecutable.get())
+        assertEquals(false, extension.formatCode.get())
+    }
+
+    @Test
+    fun `the plugin requires the Android plugin`() {
+        val project = project()
+        project.plugins.apply(UniffiPlugin::class.java)
+        project.extensions.getByType(UniffiExtension::class.java)
+            .udlFile.set(project.file("src/main/rust/arithmetic.udl"))
+        val error = assertFailsWith<Exception> { (project as ProjectInternal).evaluate() }
+        val cause = generateSequence(error as Throwable) { it.cause }.last()
+        assertTrue(cause is GradleException)
+        assertTrue(cause.message!!.contains("requires the Android application or library plugin"))
+    }
+}
@@ -0,0 +1,112 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Custom allocation of the bytes of `RustBuffer`s.
+//!
+//! Embedders which must route the allocations through their own heaps, for example to track
+//! memory budgets, can register the functions allocating and freeing the bytes of the
+//! `RustBuffer`s handed to the foreign-language code, with [`set_rustbuffer_allocator`] from Rust
+//! or `uniffi_rustbuffer_set_allocator` from the foreign-language code:
+//!
+//! ```
+//! unsafe extern "C" fn engine_alloc(size: usize) -> *mut u8 {
+//!     // Allocate from the heap of the engine...
+//! #   std::alloc::alloc(std::alloc::Layout::array::<u8>(size).unwrap())
+//! }
+//!
+//! unsafe extern "C" fn engine_free(ptr: *mut u8, size: usize) {
+//!     // Free to the heap of the engine...
+//! #   std::alloc::dealloc(ptr, std::alloc::Layout::array::<u8>(size).unwrap())
+//! }
+//!
+//! uniffi::set_rustbuffer_allocator(uniffi::RustBufferAllocator {
+//!     alloc: engine_alloc,
+//!     free: engine_free,
+//! })
+//! .unwrap();
+//! ```
+//!
+//! The allocator must be registered before the first `RustBuffer` is allocated, and can't be
+//! changed afterwards, so that every buffer is freed by the allocator which allocated it.
+//!
+//! The buffers allocated for the foreign-language code, to lower values into, are allocated by
+//! the registered allocator, and the values lifted from them are read in place. The values lowered
+//! by the Rust code are still serialized into a `Vec<u8>`, allocated by the global allocator, which
+//! can be replaced with `#[global_allocator]`, and copied once into a buffer allocated by the
+//! registered allocator.
+
+use super::{call_with_output, RustCallStatus};
+use once_cell::sync::OnceCell;
+
+/// The functions allocating and freeing the bytes of the `RustBuffer`s, see
+/// [`set_rustbuffer_allocator`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RustBufferAllocator {
+    /// Allocate `size` bytes, where `size` is never `0`, or return null if it fails, which aborts
+    /// the process.
+    pub alloc: unsafe extern "C" fn(size: usize) -> *mut u8,
+    /// Free the bytes at `ptr`, allocated by `alloc` with the same `size`.
+    pub free: unsafe extern "C" fn(ptr: *mut u8, size: usize),
+}
+
+/// The error of [`set_rustbuffer_allocator`] once a buffer was allocated, or an allocator was
+/// already registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocatorAlreadySet;
+
+impl std::fmt::Display for AllocatorAlreadySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the RustBuffer allocator must be set once, before any RustBuffer is allocated"
+        )
+    }
+}
+
+impl std::error::Error for AllocatorAlreadySet {}
+
+// Initialized to `None` by the first buffer allocated without an allocator registered.
+static ALLOCATOR: OnceCell<Option<RustBufferAllocator>> = OnceCell::new();
+
+/// Register the functions allocating and freeing the bytes of the `RustBuffer`s.
+///
+/// Fails if a `RustBuffer` was already allocated, or an allocator already registered.
+pub fn set_rustbuffer_allocator(allocator: RustBufferAllocator) -> Result<(), AllocatorAlreadySet> {
+    ALLOCATOR
+        .set(Some(allocator))
+        .map_err(|_| AllocatorAlreadySet)
+}
+
+/// The registered allocator, or `None` to use the global allocator through `Vec<u8>`.
+pub(crate) fn custom_allocator() -> Option<RustBufferAllocator> {
+    *ALLOCATOR.get_or_init(|| None)
+}
+
+/// Allocate `size` bytes with the allocator, aborting if it fails.
+pub(crate) fn alloc_bytes(allocator: RustBufferAllocator, size: usize) -> *mut u8 {
+    let data = unsafe { (allocator.alloc)(size) };
+    if data.is_null() {
+        std::alloc::handle_alloc_error(std::alloc::Layout::array::<u8>(size).unwrap());
+    }
+    data
+}
+
+/// Register the functions allocating and freeing the bytes of the `RustBuffer`s, for the
+/// foreign-language code, see [`set_rustbuffer_allocator`].
+///
+/// Returns `1` if the allocator was registered, and `0` if a `RustBuffer` was already allocated
+/// or an allocator already registered.
+///
+/// # Safety
+/// The functions must allocate and free memory as documented by [`RustBufferAllocator`], and be
+/// callable from any thread for as long as the library is loaded.
+pub unsafe fn uniffi_rustbuffer_set_allocator<const COMPACT: bool>(
+    allocator: RustBufferAllocator,
+    call_status: &mut RustCallStatus,
+) -> i8 {
+    call_with_output::<COMPACT, _, _>(call_status, || {
+        i8::from(set_rustbuffer_allocator(allocator).is_ok())
+    })
+}
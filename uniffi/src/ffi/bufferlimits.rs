@@ -0,0 +1,387 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Limits on the memory of the `RustBuffer`s.
+//!
+//! A bug serializing gigabytes of data into a `RustBuffer` would make the foreign-language code
+//! allocate as much to read it, which is likely to get the host application killed for running
+//! out of memory. Embedders can instead cap the size of each buffer, and the total size of the
+//! buffers alive at once, with [`set_rustbuffer_limits`], typically from a `#[uniffi::init]`
+//! hook:
+//!
+//! ```
+//! # fn main() {}
+//! #[uniffi::init]
+//! fn init() {
+//!     uniffi::set_rustbuffer_limits(uniffi::RustBufferLimits {
+//!         max_buffer_size: 64 * 1024 * 1024,
+//!         max_total_size: 256 * 1024 * 1024,
+//!     })
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! A call whose return value is lowered into a buffer beyond the limits, see
+//! `RustBuffer::try_from_vec`, fails with the `CALL_PAYLOAD_TOO_LARGE` status, which the
+//! foreign-language code raises as a [`PayloadTooLarge`] exception, rather than handing it the
+//! buffer. The requests of the foreign-language code to allocate buffers are checked before
+//! anything is allocated. The other buffers, like the ones reporting the errors of the calls,
+//! are counted but not limited.
+//!
+//! The live buffers are counted whether limits are set or not, see [`rustbuffer_stats`], which
+//! the foreign-language code reads through the `rustbuffer_stats` FFI function of each component.
+
+use super::{call_with_output, RustBuffer, RustCallStatus};
+use crate::FfiConverter;
+use once_cell::sync::OnceCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The limits on the sizes of the `RustBuffer`s, see [`set_rustbuffer_limits`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RustBufferLimits {
+    /// The maximum capacity of a single buffer, in bytes, `0` for no limit.
+    pub max_buffer_size: usize,
+    /// The maximum total capacity of the buffers alive at once, in bytes, `0` for no limit.
+    pub max_total_size: usize,
+}
+
+/// The error of [`set_rustbuffer_limits`] once limits were already set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitsAlreadySet;
+
+impl std::fmt::Display for LimitsAlreadySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the RustBuffer limits can only be set once")
+    }
+}
+
+impl std::error::Error for LimitsAlreadySet {}
+
+/// The error of a `RustBuffer` going over the limits, raised by the foreign-language code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    /// The size of the buffer, or the total size of the live buffers with it, in bytes.
+    pub size: u64,
+    /// The limit it goes over, in bytes.
+    pub limit: u64,
+}
+
+impl std::fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "payload too large: {} bytes of RustBuffers over the limit of {} bytes",
+            self.size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+/// The counters of the `RustBuffer`s of the library, see [`rustbuffer_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RustBufferStats {
+    /// The number of buffers allocated and not freed yet.
+    pub live_buffers: u64,
+    /// The total capacity of the live buffers, in bytes.
+    pub live_bytes: u64,
+    /// The highest total capacity of the live buffers so far, in bytes.
+    pub peak_bytes: u64,
+    /// The number of buffers which weren't allocated because of the limits.
+    pub rejected: u64,
+}
+
+// No limits until they're set.
+static LIMITS: OnceCell<RustBufferLimits> = OnceCell::new();
+
+static LIVE_BUFFERS: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+static REJECTED: AtomicU64 = AtomicU64::new(0);
+
+/// Set the limits on the sizes of the `RustBuffer`s.
+///
+/// They can only be set once, so that they stay under the control of the embedder setting them
+/// at initialization. The buffers allocated before count toward the total size.
+pub fn set_rustbuffer_limits(limits: RustBufferLimits) -> Result<(), LimitsAlreadySet> {
+    LIMITS.set(limits).map_err(|_| LimitsAlreadySet)
+}
+
+/// The counters of the `RustBuffer`s of the library.
+pub fn rustbuffer_stats() -> RustBufferStats {
+    RustBufferStats {
+        live_buffers: LIVE_BUFFERS.load(Ordering::Relaxed),
+        live_bytes: LIVE_BYTES.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+        rejected: REJECTED.load(Ordering::Relaxed),
+    }
+}
+
+fn limits() -> RustBufferLimits {
+    LIMITS.get().copied().unwrap_or_default()
+}
+
+// The error of a buffer of `size` bytes going over the limits, when `live_bytes` are already
+// allocated.
+fn check(size: u64, live_bytes: u64) -> Result<(), PayloadTooLarge> {
+    let limits = limits();
+    let max_buffer_size = limits.max_buffer_size as u64;
+    if max_buffer_size > 0 && size > max_buffer_size {
+        return Err(PayloadTooLarge {
+            size,
+            limit: max_buffer_size,
+        });
+    }
+    let max_total_size = limits.max_total_size as u64;
+    if max_total_size > 0 && live_bytes + size > max_total_size {
+        return Err(PayloadTooLarge {
+            size: live_bytes + size,
+            limit: max_total_size,
+        });
+    }
+    Ok(())
+}
+
+// Count a buffer which wasn't allocated because of the limits.
+fn rejected(err: PayloadTooLarge) -> PayloadTooLarge {
+    REJECTED.fetch_add(1, Ordering::Relaxed);
+    err
+}
+
+/// Check that a buffer of `size` bytes would fit in the limits, before allocating it.
+pub(crate) fn check_size(size: usize) -> Result<(), PayloadTooLarge> {
+    check(size as u64, LIVE_BYTES.load(Ordering::Relaxed)).map_err(rejected)
+}
+
+/// Count a buffer of `capacity` bytes as live, unless it goes over the limits.
+pub(crate) fn track(capacity: usize) -> Result<(), PayloadTooLarge> {
+    track_growth(0, capacity)
+}
+
+/// Count a live buffer of `capacity` bytes, or a new one if it's `0`, as growing to
+/// `new_capacity` bytes, unless it goes over the limits.
+///
+/// The growth is counted before the buffer is reallocated, so that it's left untouched when it
+/// goes over the limits.
+pub(crate) fn track_growth(capacity: usize, new_capacity: usize) -> Result<(), PayloadTooLarge> {
+    let growth = new_capacity.saturating_sub(capacity) as u64;
+    let live_bytes = LIVE_BYTES.fetch_add(growth, Ordering::Relaxed);
+    // The buffer replaces its current capacity rather than adding to it.
+    let other_bytes = live_bytes.saturating_sub(capacity as u64);
+    if let Err(err) = check(new_capacity as u64, other_bytes) {
+        saturating_sub(&LIVE_BYTES, growth);
+        return Err(rejected(err));
+    }
+    if capacity == 0 {
+        LIVE_BUFFERS.fetch_add(1, Ordering::Relaxed);
+    }
+    PEAK_BYTES.fetch_max(live_bytes + growth, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Correct the capacity counted for a live buffer, without checking the limits, when it ends up
+/// with another one than expected.
+pub(crate) fn retrack(capacity: usize, new_capacity: usize) {
+    if new_capacity > capacity {
+        let live_bytes = LIVE_BYTES.fetch_add((new_capacity - capacity) as u64, Ordering::Relaxed);
+        PEAK_BYTES.fetch_max(
+            live_bytes + (new_capacity - capacity) as u64,
+            Ordering::Relaxed,
+        );
+    } else {
+        saturating_sub(&LIVE_BYTES, (capacity - new_capacity) as u64);
+    }
+}
+
+/// Count a buffer of `capacity` bytes as live, even if it goes over the limits, for the buffers
+/// which aren't limited.
+pub(crate) fn track_unchecked(capacity: usize) {
+    let capacity = capacity as u64;
+    let live_bytes = LIVE_BYTES.fetch_add(capacity, Ordering::Relaxed);
+    LIVE_BUFFERS.fetch_add(1, Ordering::Relaxed);
+    PEAK_BYTES.fetch_max(live_bytes + capacity, Ordering::Relaxed);
+}
+
+/// Stop counting a freed buffer of `capacity` bytes.
+///
+/// The counters saturate at zero, since a buffer can be allocated by another copy of this crate,
+/// like the one of a test calling into a library.
+pub(crate) fn untrack(capacity: usize) {
+    saturating_sub(&LIVE_BYTES, capacity as u64);
+    saturating_sub(&LIVE_BUFFERS, 1);
+}
+
+fn saturating_sub(counter: &AtomicU64, n: u64) {
+    // The closure always returns `Some`, so this can't fail.
+    let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |value| {
+        Some(value.saturating_sub(n))
+    });
+}
+
+/// Serialize the error of a call, see `RustCallStatus`.
+pub(crate) fn write_payload_too_large<const COMPACT: bool>(
+    err: PayloadTooLarge,
+    buf: &mut Vec<u8>,
+) {
+    if COMPACT {
+        <String as FfiConverter>::write_compact(err.to_string(), buf);
+        <u64 as FfiConverter>::write_compact(err.size, buf);
+        <u64 as FfiConverter>::write_compact(err.limit, buf);
+    } else {
+        <String as FfiConverter>::write(err.to_string(), buf);
+        <u64 as FfiConverter>::write(err.size, buf);
+        <u64 as FfiConverter>::write(err.limit, buf);
+    }
+}
+
+/// The counters of the `RustBuffer`s, for the foreign-language code, see [`rustbuffer_stats`].
+///
+/// They're serialized as the `u64`s of the fields of [`RustBufferStats`], in order, with the
+/// encoding of the component, see [`FfiConverter`].
+pub fn uniffi_rustbuffer_stats<const COMPACT: bool>(
+    call_status: &mut RustCallStatus,
+) -> RustBuffer {
+    call_with_output::<COMPACT, _, _>(call_status, || {
+        let stats = rustbuffer_stats();
+        let write_u64 = if COMPACT {
+            <u64 as FfiConverter>::write_compact
+        } else {
+            <u64 as FfiConverter>::write
+        };
+        let mut buf = Vec::new();
+        for counter in [
+            stats.live_buffers,
+            stats.live_bytes,
+            stats.peak_bytes,
+            stats.rejected,
+        ] {
+            write_u64(counter, &mut buf);
+        }
+        RustBuffer::from_vec(buf)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ffi::rustbuffer::{
+        uniffi_rustbuffer_alloc, uniffi_rustbuffer_free, uniffi_rustbuffer_reserve,
+    };
+    use crate::ffi::rustcalls::{call_with_lowered_result, CALL_PAYLOAD_TOO_LARGE, CALL_SUCCESS};
+
+    // The limits are global, and set once, so the tests all set the same ones.
+    fn set_test_limits() {
+        let _ = set_rustbuffer_limits(RustBufferLimits {
+            max_buffer_size: 1 << 20,
+            max_total_size: 1 << 30,
+        });
+    }
+
+    #[test]
+    fn test_limits() {
+        set_test_limits();
+        assert_eq!(
+            set_rustbuffer_limits(RustBufferLimits::default()),
+            Err(LimitsAlreadySet)
+        );
+
+        assert_eq!(
+            check_size((1 << 20) + 1),
+            Err(PayloadTooLarge {
+                size: (1 << 20) + 1,
+                limit: 1 << 20,
+            })
+        );
+        let err = check(1 << 20, (1 << 30) - 10).unwrap_err();
+        assert_eq!(err.limit, 1 << 30);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "payload too large: {} bytes of RustBuffers over the limit of {} bytes",
+                (1u64 << 30) + (1 << 20) - 10,
+                1u64 << 30
+            )
+        );
+
+        // The call fails without allocating the buffer.
+        let rejected = rustbuffer_stats().rejected;
+        let mut status = RustCallStatus::default();
+        let buf = uniffi_rustbuffer_alloc::<false>((1 << 20) + 1, &mut status);
+        assert_eq!(buf.len(), 0);
+        assert_eq!(status.code, CALL_PAYLOAD_TOO_LARGE);
+        let bytes = unsafe { status.error_buf.assume_init() }.destroy_into_vec();
+        let mut bytes = bytes.as_slice();
+        assert!(<String as FfiConverter>::try_read(&mut bytes)
+            .unwrap()
+            .starts_with("payload too large"));
+        assert_eq!(
+            <u64 as FfiConverter>::try_read(&mut bytes).unwrap(),
+            (1 << 20) + 1
+        );
+        assert_eq!(
+            <u64 as FfiConverter>::try_read(&mut bytes).unwrap(),
+            1 << 20
+        );
+        assert!(bytes.is_empty());
+        assert!(rustbuffer_stats().rejected > rejected);
+
+        // The buffers returned by the calls wrapped by `call_with_lowered_result` are limited.
+        let mut status = RustCallStatus::default();
+        let buf = call_with_lowered_result::<false, _, _>(&mut status, || {
+            Ok(<String as FfiConverter>::try_lower(
+                "a".repeat((1 << 20) + 1),
+            )?)
+        });
+        assert_eq!(buf.len(), 0);
+        assert_eq!(status.code, CALL_PAYLOAD_TOO_LARGE);
+        unsafe { status.error_buf.assume_init() }.destroy();
+        // Not the other ones, like the ones of the errors.
+        let buf = RustBuffer::from_vec(vec![0; (1 << 20) + 1]);
+        assert_eq!(buf.len(), (1 << 20) + 1);
+        buf.destroy();
+    }
+
+    #[test]
+    fn test_reserve() {
+        set_test_limits();
+
+        // Exactly as many bytes are reserved as requested.
+        let mut status = RustCallStatus::default();
+        let buf = RustBuffer::from_vec(vec![1, 2, 3]);
+        let buf = unsafe { uniffi_rustbuffer_reserve::<false>(buf, 1000, &mut status) };
+        assert_eq!(status.code, CALL_SUCCESS);
+
+        // The buffer is left untouched when it would go over the limits, so that the caller,
+        // which still owns it, can free it.
+        let rejected = rustbuffer_stats().rejected;
+        let original = unsafe { std::ptr::read(&buf) };
+        let empty = unsafe { uniffi_rustbuffer_reserve::<false>(buf, 1 << 20, &mut status) };
+        assert_eq!(status.code, CALL_PAYLOAD_TOO_LARGE);
+        assert_eq!(empty.len(), 0);
+        unsafe { status.error_buf.assume_init() }.destroy();
+        assert!(rustbuffer_stats().rejected > rejected);
+        let bytes = original.destroy_into_vec();
+        assert_eq!(bytes, [1, 2, 3]);
+        assert_eq!(bytes.capacity(), 1003);
+
+        // It's reallocated up to the limit of a buffer, which its current capacity doesn't add to.
+        let mut status = RustCallStatus::default();
+        let buf = RustBuffer::from_vec(vec![0; 1000]);
+        let buf = unsafe { uniffi_rustbuffer_reserve::<false>(buf, (1 << 20) - 1000, &mut status) };
+        assert_eq!(status.code, CALL_SUCCESS);
+        unsafe { uniffi_rustbuffer_free::<false>(buf, &mut status) };
+        assert_eq!(status.code, CALL_SUCCESS);
+    }
+
+    #[test]
+    fn test_stats() {
+        // Other tests allocate buffers concurrently, so this only checks what they can't undo.
+        let buf = RustBuffer::from_vec(Vec::with_capacity(1000));
+        let stats = rustbuffer_stats();
+        assert!(stats.live_buffers >= 1);
+        assert!(stats.live_bytes >= 1000);
+        assert!(stats.peak_bytes >= stats.live_bytes);
+        buf.destroy();
+    }
+}
@@ -0,0 +1,206 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Pooling of the byte buffers behind `RustBuffer`s.
+//!
+//! Every call lowering a compound value allocates a `Vec<u8>` for its `RustBuffer`, which is
+//! freed once the other side is done with it. For chatty APIs this churns the allocator, so the
+//! buffers can instead be kept in a pool and reused, by enabling it with [`configure_buffer_pool`]:
+//!
+//! ```
+//! uniffi::configure_buffer_pool(uniffi::BufferPoolConfig {
+//!     max_buffers: 32,
+//!     high_water_capacity: 64 * 1024,
+//! });
+//! ```
+//!
+//! Each thread has its own pool, so that taking and returning buffers doesn't need any
+//! synchronization. The buffers are taken from the pool of the thread lowering a value or
+//! allocating a `RustBuffer` for the foreign-language code, and returned to the pool of the
+//! thread lifting it or freeing it with the `rustbuffer_free` FFI function, so they move
+//! between the threads as the values do.
+//!
+//! The pool is disabled by default, and when it is, buffers are allocated and freed as usual.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The configuration of the buffer pools, see [`configure_buffer_pool`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferPoolConfig {
+    /// The maximum number of buffers kept by the pool of each thread, `0` to disable pooling.
+    pub max_buffers: usize,
+    /// The capacity which the buffers are trimmed to when returned to a pool, so that a few
+    /// large values don't keep a lot of memory allocated. `0` for no trimming.
+    pub high_water_capacity: usize,
+}
+
+/// The counters of the buffer pool of the current thread, see [`buffer_pool_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// The number of buffers taken from the pool.
+    pub reused: u64,
+    /// The number of buffers allocated because the pool was empty or disabled.
+    pub allocated: u64,
+    /// The number of buffers returned to the pool.
+    pub returned: u64,
+    /// The number of buffers trimmed to the high-water capacity when returned.
+    pub trimmed: u64,
+    /// The number of buffers freed because the pool was full or disabled.
+    pub freed: u64,
+}
+
+static MAX_BUFFERS: AtomicUsize = AtomicUsize::new(0);
+static HIGH_WATER_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Default)]
+struct BufferPool {
+    buffers: Vec<Vec<u8>>,
+    stats: BufferPoolStats,
+}
+
+impl BufferPool {
+    fn take(&mut self, config: BufferPoolConfig, capacity: usize) -> Vec<u8> {
+        let excess = self.buffers.len().saturating_sub(config.max_buffers);
+        if excess > 0 {
+            self.buffers.drain(..excess);
+            self.stats.freed += excess as u64;
+        }
+        match self.buffers.pop() {
+            Some(mut buf) => {
+                self.stats.reused += 1;
+                buf.reserve(capacity);
+                buf
+            }
+            None => {
+                self.stats.allocated += 1;
+                Vec::with_capacity(capacity)
+            }
+        }
+    }
+
+    fn put(&mut self, config: BufferPoolConfig, mut buf: Vec<u8>) {
+        if buf.capacity() == 0 || self.buffers.len() >= config.max_buffers {
+            self.stats.freed += 1;
+            return;
+        }
+        buf.clear();
+        if config.high_water_capacity > 0 && buf.capacity() > config.high_water_capacity {
+            buf.shrink_to(config.high_water_capacity);
+            self.stats.trimmed += 1;
+        }
+        self.stats.returned += 1;
+        self.buffers.push(buf);
+    }
+
+    fn trim(&mut self) {
+        self.stats.freed += self.buffers.len() as u64;
+        self.buffers.clear();
+    }
+}
+
+thread_local! {
+    static POOL: RefCell<BufferPool> = RefCell::default();
+}
+
+fn current_config() -> BufferPoolConfig {
+    BufferPoolConfig {
+        max_buffers: MAX_BUFFERS.load(Ordering::Relaxed),
+        high_water_capacity: HIGH_WATER_CAPACITY.load(Ordering::Relaxed),
+    }
+}
+
+/// Enable, disable or reconfigure the buffer pools of all the threads.
+///
+/// The buffers already pooled beyond the new limits are freed as the pools are next used.
+pub fn configure_buffer_pool(config: BufferPoolConfig) {
+    MAX_BUFFERS.store(config.max_buffers, Ordering::Relaxed);
+    HIGH_WATER_CAPACITY.store(config.high_water_capacity, Ordering::Relaxed);
+}
+
+/// The counters of the buffer pool of the current thread.
+pub fn buffer_pool_stats() -> BufferPoolStats {
+    POOL.with(|pool| pool.borrow().stats)
+}
+
+/// Free all the buffers of the pool of the current thread, for example before it goes idle.
+pub fn trim_buffer_pool() {
+    POOL.with(|pool| pool.borrow_mut().trim())
+}
+
+/// Take an empty buffer from the pool of the current thread, with at least `capacity` bytes.
+pub(crate) fn take_buffer(capacity: usize) -> Vec<u8> {
+    let config = current_config();
+    POOL.try_with(|pool| pool.borrow_mut().take(config, capacity))
+        // The pool is gone while the thread is exiting.
+        .unwrap_or_else(|_| Vec::with_capacity(capacity))
+}
+
+/// Return a buffer to the pool of the current thread, or free it if the pool is full.
+pub(crate) fn return_buffer(buf: Vec<u8>) {
+    let config = current_config();
+    let _ = POOL.try_with(|pool| pool.borrow_mut().put(config, buf));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // The global configuration is shared with the other tests, so the pool is tested with a
+    // configuration of its own.
+    #[test]
+    fn test_buffer_pool() {
+        let mut pool = BufferPool::default();
+
+        // Disabled, the buffers are allocated and freed.
+        let disabled = BufferPoolConfig::default();
+        let buf = pool.take(disabled, 8);
+        pool.put(disabled, buf);
+        assert_eq!(
+            pool.stats,
+            BufferPoolStats {
+                allocated: 1,
+                freed: 1,
+                ..Default::default()
+            }
+        );
+
+        let config = BufferPoolConfig {
+            max_buffers: 1,
+            high_water_capacity: 64,
+        };
+        let mut buf = pool.take(config, 8);
+        buf.extend_from_slice(&[1, 2, 3]);
+        let ptr = buf.as_ptr();
+        pool.put(config, buf);
+        // The same allocation is reused, emptied.
+        let buf = pool.take(config, 4);
+        assert_eq!(buf.as_ptr(), ptr);
+        assert!(buf.is_empty());
+        pool.put(config, buf);
+        // The pool is full.
+        pool.put(config, vec![0; 8]);
+        pool.trim();
+
+        // Large buffers are trimmed to the high-water capacity.
+        pool.put(config, Vec::with_capacity(1024));
+        assert!(pool.take(config, 0).capacity() < 1024);
+        assert_eq!(
+            pool.stats,
+            BufferPoolStats {
+                reused: 2,
+                allocated: 2,
+                returned: 3,
+                trimmed: 1,
+                freed: 3,
+            }
+        );
+
+        // Lowering the limits frees the excess buffers on the next take.
+        pool.put(config, vec![0; 8]);
+        pool.take(disabled, 0);
+        assert_eq!(pool.stats.freed, 4);
+        assert!(pool.buffers.is_empty());
+    }
+}
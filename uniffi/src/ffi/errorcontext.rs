@@ -0,0 +1,147 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Key/value context of the failed calls
+//!
+//! While a call into Rust runs, its code can record key/value pairs describing what it was doing,
+//! with [`add_error_context`] or the [`ErrorContext`] extension trait of `Result`.  When the call
+//! fails, with an error or a panic, they're serialized into the `error_buf` of its
+//! `RustCallStatus` after the error, and the foreign-language code exposes them on the exception
+//! as a map.  They're discarded when the call succeeds.
+//!
+//! ```
+//! use uniffi::ErrorContext;
+//!
+//! fn read_config(path: &str) -> std::io::Result<String> {
+//!     std::fs::read_to_string(path).error_context("path", path)
+//! }
+//! ```
+//!
+//! The errors declared with `[Error, Source]` in the UDL also send the descriptions of their
+//! chain of [`source`](std::error::Error::source)s, after the key/value context.  They're
+//! recorded with [`record_error_sources`] when the error of the call is lowered with
+//! [`lower_error`](crate::lower_error), but not when an error is lowered as the value of a field
+//! or of a sequence.
+
+use crate::{write_len, write_len_compact, FfiConverter, RustBuffer};
+use std::cell::RefCell;
+
+// What the Rust code attached to the error of a call.
+#[derive(Default)]
+pub(crate) struct CallContext {
+    pairs: Vec<(String, String)>,
+    // The descriptions of the sources of the error, the outermost first.
+    sources: Vec<String>,
+}
+
+thread_local! {
+    // The context recorded by the current call, see `make_call()`.
+    static CONTEXT: RefCell<CallContext> = RefCell::new(CallContext::default());
+}
+
+/// Attach a key/value pair to the error of the current call, if it fails.
+///
+/// When a key is added more than once, the foreign-language code keeps the last value.
+pub fn add_error_context(key: impl Into<String>, value: impl ToString) {
+    CONTEXT.with(|context| {
+        context
+            .borrow_mut()
+            .pairs
+            .push((key.into(), value.to_string()))
+    });
+}
+
+/// Record the chain of sources of the error which the current call is returning, to send their
+/// descriptions along with it.
+///
+/// The [`FfiError`](crate::FfiError) implementations of the errors declared with `[Error, Source]`
+/// call it, see [`lower_error`](crate::lower_error).
+#[doc(hidden)]
+pub fn record_error_sources(error: &dyn std::error::Error) {
+    let sources = std::iter::successors(error.source(), |source| source.source())
+        .map(ToString::to_string)
+        .collect();
+    CONTEXT.with(|context| context.borrow_mut().sources = sources);
+}
+
+/// Extension trait to attach context to the errors of the current call, see
+/// [`add_error_context`].
+pub trait ErrorContext: Sized {
+    /// Attach `key` and `value` to the error of the current call if `self` is an error.
+    fn error_context(self, key: &str, value: impl ToString) -> Self;
+
+    /// Like [`ErrorContext::error_context`], but only computes the value if `self` is an error.
+    fn with_error_context<V: ToString>(self, key: &str, value: impl FnOnce() -> V) -> Self;
+}
+
+impl<T, E> ErrorContext for Result<T, E> {
+    fn error_context(self, key: &str, value: impl ToString) -> Self {
+        if self.is_err() {
+            add_error_context(key, value);
+        }
+        self
+    }
+
+    fn with_error_context<V: ToString>(self, key: &str, value: impl FnOnce() -> V) -> Self {
+        if self.is_err() {
+            add_error_context(key, value());
+        }
+        self
+    }
+}
+
+// Start recording the context of a call, returning the one of the outer call, if any.  Calls
+// nest when the Rust code calls back into foreign code, which calls into Rust again.
+pub(crate) fn enter_call() -> CallContext {
+    CONTEXT.with(|context| std::mem::take(&mut *context.borrow_mut()))
+}
+
+// Stop recording the context of a call, returning it, and restore the one of the outer call.
+pub(crate) fn exit_call(outer: CallContext) -> CallContext {
+    CONTEXT.with(|context| std::mem::replace(&mut *context.borrow_mut(), outer))
+}
+
+// Serialize the context after the error, see `RustCallStatus`.  Nothing is written without
+// context, so that the buffer is unchanged, and the pairs are written, even if there are none,
+// before the sources of the error.
+pub(crate) fn write_error_context<const COMPACT: bool>(context: CallContext, buf: &mut Vec<u8>) {
+    if context.pairs.is_empty() && context.sources.is_empty() {
+        return;
+    }
+    let write_len = if COMPACT {
+        write_len_compact
+    } else {
+        write_len
+    };
+    let write_string = if COMPACT {
+        <String as FfiConverter>::write_compact
+    } else {
+        <String as FfiConverter>::write
+    };
+    write_len(buf, context.pairs.len());
+    for (key, value) in context.pairs {
+        write_string(key, buf);
+        write_string(value, buf);
+    }
+    if context.sources.is_empty() {
+        return;
+    }
+    write_len(buf, context.sources.len());
+    for source in context.sources {
+        write_string(source, buf);
+    }
+}
+
+// Append the context to a serialized error.
+pub(crate) fn append_error_context<const COMPACT: bool>(
+    buf: RustBuffer,
+    context: CallContext,
+) -> RustBuffer {
+    if context.pairs.is_empty() && context.sources.is_empty() {
+        return buf;
+    }
+    let mut buf = buf.destroy_into_vec();
+    write_error_context::<COMPACT>(context, &mut buf);
+    RustBuffer::from_vec(buf)
+}
@@ -45,6 +45,13 @@ impl FfiDefault for *const std::ffi::c_void {
     }
 }
 
+// The result of a call wrapped by `call_with_lowered_result()`, e.g. when lifting an argument fails.
+impl<R: FfiDefault> FfiDefault for Result<R, crate::CallError> {
+    fn ffi_default() -> Self {
+        Ok(R::ffi_default())
+    }
+}
+
 impl FfiDefault for crate::RustBuffer {
     fn ffi_default() -> Self {
         unsafe { Self::from_raw_parts(std::ptr::null_mut(), 0, 0) }
@@ -112,19 +112,33 @@
 //! `ForeignCallback`. The caller of `ForeignCallback`, the `KeychainProxy` unpacks the returned buffer into the correct
 //! type and then returns to client code.
 //!
+//! ## Reentrancy
+//!
+//! The foreign-language implementation of a callback can call back into Rust, which can call
+//! callbacks again, all on the same thread, including the callback object already being called,
+//! e.g. for a recursive visitor. Each proxy method records its call in a stack of the calls in
+//! progress on its thread, see [`foreign_callbacks_in_progress`], and the calls are nested at most
+//! [`MAX_NESTED_FOREIGN_CALLBACKS`] deep: past that, Rust and the foreign-language code are most
+//! likely calling each other endlessly, and the call fails with the chain of calls rather than
+//! ending with a stack overflow, or waiting for a lock which the same thread holds.
+//!
 
 use super::RustBuffer;
+use std::cell::RefCell;
 use std::fmt;
+use std::marker::PhantomData;
 use std::os::raw::c_int;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 
 /// ForeignCallback is the Rust representation of a foreign language function.
-/// It is the basis for all callbacks interfaces. It is registered exactly once per callback interface,
-/// at library start up time.
+/// It is the basis for all callbacks interfaces. It is registered once per callback interface by
+/// each instance of the bindings, at library start up time. There's usually a single instance, but
+/// e.g. every Python sub-interpreter importing the bindings registers its own callback.
 /// Calling this method is only done by generated objects which mirror callback interfaces objects in the foreign language.
 ///
 /// * The `handle` is the key into a handle map on the other side of the FFI used to look up the foreign language object
-///   that implements the callback interface/trait.
+///   that implements the callback interface/trait. Its two most significant bytes are the registration returned by
+///   [`ForeignCallbackInternals::set_callback`] when the callback was registered.
 /// * The `method` selector specifies the method that will be called on the object, by looking it up in a list of methods from
 ///   the IDL. The index is 1 indexed. Note that the list of methods is generated by at uniffi from the IDL and used in all
 ///   bindings: so we can rely on the method list being stable within the same run of uniffi.
@@ -160,47 +174,226 @@ pub const IDX_CALLBACK_FREE: u32 = 0;
 static_assertions::assert_eq_size!(usize, ForeignCallback);
 static_assertions::assert_eq_size!(usize, Option<ForeignCallback>);
 
-/// Struct to hold a foreign callback.
+/// The number of callbacks which can be registered at the same time for a callback interface.
+pub const MAX_FOREIGN_CALLBACKS: usize = 256;
+
+/// The handles passed to Rust carry the registration of their callback in their two most
+/// significant bytes: the slot of the callback, then the generation of the slot.
+const REGISTRATION_SHIFT: u32 = 48;
+
+/// Struct to hold the foreign callbacks of a callback interface.
+///
+/// Each instance of the bindings registers its own callback and gets a registration back, which it
+/// stores in the two most significant bytes of the handles it passes to Rust. That way, a handle
+/// is always given back to the callback, and so to the handle map, which created it.
+///
+/// The registration is made of the slot of the callback and of the generation of that slot, which
+/// is bumped when the callback is unregistered, so that the handles of an unregistered callback
+/// are never given to the next callback registered in its slot. A slot is retired rather than
+/// reused once its generation would wrap.
 pub struct ForeignCallbackInternals {
-    callback_ptr: AtomicUsize,
+    callback_ptrs: [AtomicUsize; MAX_FOREIGN_CALLBACKS],
+    generations: [AtomicU8; MAX_FOREIGN_CALLBACKS],
 }
 
 const EMPTY_PTR: usize = 0;
+// The pointer of a slot whose generations are exhausted. It's never a function pointer, which is
+// aligned.
+const RETIRED_PTR: usize = 1;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_CALLBACK_PTR: AtomicUsize = AtomicUsize::new(EMPTY_PTR);
+#[allow(clippy::declare_interior_mutable_const)]
+const FIRST_GENERATION: AtomicU8 = AtomicU8::new(0);
 
 impl ForeignCallbackInternals {
     pub const fn new() -> Self {
         ForeignCallbackInternals {
-            callback_ptr: AtomicUsize::new(EMPTY_PTR),
+            callback_ptrs: [EMPTY_CALLBACK_PTR; MAX_FOREIGN_CALLBACKS],
+            generations: [FIRST_GENERATION; MAX_FOREIGN_CALLBACKS],
+        }
+    }
+
+    /// Register a callback, returning the registration to store in the handles which are given to
+    /// it, see [`Self::unset_callback`].
+    ///
+    /// Fails if [`MAX_FOREIGN_CALLBACKS`] callbacks are already registered.
+    pub fn set_callback(&self, callback: ForeignCallback) -> Result<u16, CallbackSlotsFullError> {
+        for (slot, ptr) in self.callback_ptrs.iter().enumerate() {
+            if ptr
+                .compare_exchange(
+                    EMPTY_PTR,
+                    callback as usize,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                )
+                .is_ok()
+            {
+                let generation = self.generations[slot].load(Ordering::SeqCst);
+                return Ok(((slot as u16) << 8) | u16::from(generation));
+            }
         }
+        Err(CallbackSlotsFullError)
     }
 
-    pub fn set_callback(&self, callback: ForeignCallback) {
-        let as_usize = callback as usize;
-        let old_ptr = self.callback_ptr.compare_exchange(
-            EMPTY_PTR,
-            as_usize,
+    /// Unregister the callback of a `registration` returned by [`Self::set_callback`], freeing
+    /// its slot for the next callback.
+    ///
+    /// The objects behind the handles of the callback can't be called anymore, and aren't freed
+    /// when Rust drops them, so the bindings only unregister it once they are gone anyway, like
+    /// when a Python sub-interpreter is destroyed.
+    pub fn unset_callback(&self, registration: u16) {
+        let slot = usize::from(registration >> 8);
+        let generation = registration as u8;
+        // Bump the generation before freeing the slot, so that the next callback registered in
+        // it gets the new one.
+        let current = self.generations[slot].compare_exchange(
+            generation,
+            generation.wrapping_add(1),
             Ordering::SeqCst,
             Ordering::SeqCst,
         );
-        match old_ptr {
-            // We get the previous value back. If this is anything except EMPTY_PTR,
-            // then this has been set before we get here.
-            Ok(EMPTY_PTR) => (),
-            _ =>
-            // This is an internal bug, the other side of the FFI should ensure
-            // it sets this only once.
-            {
-                panic!("Bug: call set_callback multiple times. This is likely a uniffi bug")
-            }
+        if current.is_err() {
+            // Unregistered already.
+            return;
+        }
+        let ptr = if generation == u8::MAX {
+            RETIRED_PTR
+        } else {
+            EMPTY_PTR
         };
+        self.callback_ptrs[slot].store(ptr, Ordering::SeqCst);
     }
 
-    pub fn get_callback(&self) -> Option<ForeignCallback> {
-        let ptr_value = self.callback_ptr.load(Ordering::SeqCst);
+    /// Get the callback which the object behind `handle` was registered with, if it's still
+    /// registered.
+    pub fn get_callback(&self, handle: u64) -> Option<ForeignCallback> {
+        let registration = (handle >> REGISTRATION_SHIFT) as u16;
+        let slot = usize::from(registration >> 8);
+        let ptr_value = self.callback_ptrs[slot].load(Ordering::SeqCst);
+        if ptr_value == RETIRED_PTR
+            || self.generations[slot].load(Ordering::SeqCst) != registration as u8
+        {
+            return None;
+        }
         unsafe { std::mem::transmute::<usize, Option<ForeignCallback>>(ptr_value) }
     }
 }
 
+/// The error of registering a callback when [`MAX_FOREIGN_CALLBACKS`] callbacks are already
+/// registered for the callback interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallbackSlotsFullError;
+
+impl fmt::Display for CallbackSlotsFullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Can't register more than {MAX_FOREIGN_CALLBACKS} callbacks at the same time for a callback interface"
+        )
+    }
+}
+
+impl std::error::Error for CallbackSlotsFullError {}
+
+/// The maximum number of foreign callback calls which can be nested into each other on a thread.
+pub const MAX_NESTED_FOREIGN_CALLBACKS: usize = 64;
+
+/// A call of a foreign callback, in progress on the current thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForeignCallbackCall {
+    /// The name of the callback interface.
+    pub interface: &'static str,
+    /// The name of the method.
+    pub method: &'static str,
+    /// The handle of the foreign object.
+    pub handle: u64,
+}
+
+impl fmt::Display for ForeignCallbackCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.interface, self.method)
+    }
+}
+
+thread_local! {
+    static CALLS_IN_PROGRESS: RefCell<Vec<ForeignCallbackCall>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The foreign callback calls in progress on the current thread, the outermost first.
+///
+/// Rust code called back by the foreign-language code can check it to avoid waiting for a lock
+/// which the code calling the callback holds, on the same thread.
+pub fn foreign_callbacks_in_progress() -> Vec<ForeignCallbackCall> {
+    CALLS_IN_PROGRESS.with(|calls| calls.borrow().clone())
+}
+
+/// The error of a foreign callback call nested into too many other ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackNestingLimitError {
+    /// The calls in progress on the thread, the outermost first, and the call which failed last.
+    pub calls: Vec<ForeignCallbackCall>,
+}
+
+impl fmt::Display for CallbackNestingLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "foreign callbacks exceeded the nesting limit of {MAX_NESTED_FOREIGN_CALLBACKS} calls on the same thread: "
+        )?;
+        for (index, call) in self.calls.iter().enumerate() {
+            if index > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{call}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CallbackNestingLimitError {}
+
+/// Records a foreign callback call as in progress on the current thread, until it's dropped.
+///
+/// The proxies of the callback interfaces hold one while calling the foreign-language code.
+pub struct ForeignCallbackGuard {
+    // The call is recorded for the current thread, so it must be dropped on it.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ForeignCallbackGuard {
+    pub fn enter(
+        interface: &'static str,
+        method: &'static str,
+        handle: u64,
+    ) -> Result<Self, CallbackNestingLimitError> {
+        let call = ForeignCallbackCall {
+            interface,
+            method,
+            handle,
+        };
+        CALLS_IN_PROGRESS.with(|calls| {
+            let mut calls = calls.borrow_mut();
+            if calls.len() >= MAX_NESTED_FOREIGN_CALLBACKS {
+                let mut calls = calls.clone();
+                calls.push(call);
+                return Err(CallbackNestingLimitError { calls });
+            }
+            calls.push(call);
+            Ok(Self {
+                _not_send: PhantomData,
+            })
+        })
+    }
+}
+
+impl Drop for ForeignCallbackGuard {
+    fn drop(&mut self) {
+        // The calls are gone anyway if the thread is exiting.
+        let _ = CALLS_IN_PROGRESS.try_with(|calls| calls.borrow_mut().pop());
+    }
+}
+
 /// Used when internal/unexpected error happened when calling a foreign callback, for example when
 /// a unknown exception is raised
 ///
@@ -227,3 +420,129 @@ impl fmt::Display for UnexpectedUniFFICallbackError {
 }
 
 impl std::error::Error for UnexpectedUniFFICallbackError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    unsafe extern "C" fn callback_one(_: u64, _: u32, _: RustBuffer, _: *mut RustBuffer) -> c_int {
+        1
+    }
+
+    unsafe extern "C" fn callback_two(_: u64, _: u32, _: RustBuffer, _: *mut RustBuffer) -> c_int {
+        2
+    }
+
+    fn handle(registration: u16, handle: u64) -> u64 {
+        (u64::from(registration) << REGISTRATION_SHIFT) | handle
+    }
+
+    #[test]
+    fn test_callback_slots() {
+        let internals = ForeignCallbackInternals::new();
+        assert!(internals.get_callback(0).is_none());
+        assert_eq!(internals.set_callback(callback_one), Ok(0));
+        assert_eq!(internals.set_callback(callback_two), Ok(1 << 8));
+        let call = |handle: u64| unsafe {
+            let mut buf = RustBuffer::new();
+            internals.get_callback(handle).unwrap()(handle, 0, RustBuffer::new(), &mut buf)
+        };
+        assert_eq!(call(42), 1);
+        assert_eq!(call(handle(1 << 8, 42)), 2);
+        assert!(internals.get_callback(handle(2 << 8, 0)).is_none());
+    }
+
+    #[test]
+    fn test_callback_slots_are_reused() {
+        let internals = ForeignCallbackInternals::new();
+        let registrations = (0..MAX_FOREIGN_CALLBACKS)
+            .map(|_| internals.set_callback(callback_one).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            internals.set_callback(callback_two),
+            Err(CallbackSlotsFullError)
+        );
+
+        // The slot of an unregistered callback is given to the next one, with a new generation,
+        // and the handles of the unregistered callback aren't given to the new one.
+        internals.unset_callback(registrations[3]);
+        assert!(internals
+            .get_callback(handle(registrations[3], 42))
+            .is_none());
+        let registration = internals.set_callback(callback_two).unwrap();
+        assert_eq!(registration, (3 << 8) | 1);
+        assert!(internals
+            .get_callback(handle(registrations[3], 42))
+            .is_none());
+        assert!(internals.get_callback(handle(registration, 42)).is_some());
+        // Unregistering twice doesn't unregister the next callback.
+        internals.unset_callback(registrations[3]);
+        assert!(internals.get_callback(handle(registration, 42)).is_some());
+
+        // A slot is retired once its generations are exhausted.
+        let mut registration = registration;
+        for _ in 1..u8::MAX {
+            internals.unset_callback(registration);
+            registration = internals.set_callback(callback_two).unwrap();
+        }
+        assert_eq!(registration, (3 << 8) | 0xff);
+        internals.unset_callback(registration);
+        assert!(internals.get_callback(handle(registration, 42)).is_none());
+        assert!(internals.get_callback(handle(3 << 8, 42)).is_none());
+        assert_eq!(
+            internals.set_callback(callback_two),
+            Err(CallbackSlotsFullError)
+        );
+    }
+
+    #[test]
+    fn test_nested_callbacks() {
+        fn nest(depth: usize) -> Result<usize, CallbackNestingLimitError> {
+            let _guard = ForeignCallbackGuard::enter("Counter", "count", depth as u64)?;
+            if depth == 0 {
+                return Ok(foreign_callbacks_in_progress().len());
+            }
+            nest(depth - 1)
+        }
+
+        assert!(foreign_callbacks_in_progress().is_empty());
+        assert_eq!(nest(2).unwrap(), 3);
+        assert_eq!(
+            nest(MAX_NESTED_FOREIGN_CALLBACKS - 1).unwrap(),
+            MAX_NESTED_FOREIGN_CALLBACKS
+        );
+        let err = nest(MAX_NESTED_FOREIGN_CALLBACKS).unwrap_err();
+        assert_eq!(err.calls.len(), MAX_NESTED_FOREIGN_CALLBACKS + 1);
+        assert_eq!(err.calls[0].handle, MAX_NESTED_FOREIGN_CALLBACKS as u64);
+        assert!(err.to_string().ends_with("Counter.count -> Counter.count"));
+        // The calls are popped as they return, even the failed ones.
+        assert!(foreign_callbacks_in_progress().is_empty());
+
+        // Each thread has its own calls.
+        let _guard = ForeignCallbackGuard::enter("Counter", "count", 0).unwrap();
+        std::thread::spawn(|| assert!(foreign_callbacks_in_progress().is_empty()))
+            .join()
+            .unwrap();
+        assert_eq!(foreign_callbacks_in_progress().len(), 1);
+    }
+
+    #[test]
+    fn test_recursive_callbacks() {
+        // The same object can be called again while it's being called, like a visitor called for
+        // the children of a node, up to the nesting limit.
+        fn visit(depth: usize) -> Result<usize, CallbackNestingLimitError> {
+            let _guard = ForeignCallbackGuard::enter("Visitor", "visit", 1)?;
+            if depth == 0 {
+                return Ok(foreign_callbacks_in_progress().len());
+            }
+            visit(depth - 1)
+        }
+
+        assert_eq!(
+            visit(MAX_NESTED_FOREIGN_CALLBACKS - 1).unwrap(),
+            MAX_NESTED_FOREIGN_CALLBACKS
+        );
+        assert!(visit(MAX_NESTED_FOREIGN_CALLBACKS).is_err());
+        assert!(foreign_callbacks_in_progress().is_empty());
+    }
+}
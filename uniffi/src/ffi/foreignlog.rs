@@ -0,0 +1,91 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Forwarding of the records of the `log` crate to the logging system of the foreign language.
+//!
+//! The bindings install a [`ForeignLogCallback`] with [`uniffi_install_log_forwarder`], which is
+//! re-exported by the scaffolding of each component. It's installed as the logger of the `log`
+//! crate, so it receives the records logged by the component as well as by its dependencies.
+
+use std::sync::RwLock;
+
+use log::{LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+
+use super::{call_with_output, RustCallStatus};
+
+/// The foreign function which receives the records of the `log` crate.
+///
+/// The level goes from 1 for `Level::Error` to 5 for `Level::Trace`. The target and the message
+/// are UTF-8 strings, given as a pointer and a length, which are only valid during the call.
+pub type ForeignLogCallback = unsafe extern "C" fn(
+    level: i32,
+    target: *const u8,
+    target_len: i32,
+    message: *const u8,
+    message_len: i32,
+);
+
+static FOREIGN_LOG_CALLBACK: RwLock<Option<ForeignLogCallback>> = RwLock::new(None);
+
+struct ForeignLogger;
+
+impl Log for ForeignLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let callback = match *FOREIGN_LOG_CALLBACK.read().unwrap() {
+            Some(callback) => callback,
+            None => return,
+        };
+        let target = record.target();
+        let message = record.args().to_string();
+        // Safety: the callback was given to us by the bindings, which promise that it can be
+        // called from any thread for as long as the library is loaded.
+        unsafe {
+            callback(
+                record.level() as i32,
+                target.as_ptr(),
+                target.len() as i32,
+                message.as_ptr(),
+                message.len() as i32,
+            )
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install `callback` as the logger of the `log` crate, receiving the records up to `max_level`,
+/// which goes from 0 for `LevelFilter::Off` to 5 for `LevelFilter::Trace`.
+///
+/// Installing again replaces the callback and the maximum level. Returns 0 if another logger was
+/// installed already, in which case the callback isn't, and 1 otherwise.
+pub fn uniffi_install_log_forwarder<const COMPACT: bool>(
+    callback: ForeignLogCallback,
+    max_level: i32,
+    call_status: &mut RustCallStatus,
+) -> i8 {
+    static INSTALLED: OnceCell<bool> = OnceCell::new();
+    call_with_output::<COMPACT, _, _>(call_status, || {
+        if !*INSTALLED.get_or_init(|| log::set_logger(&ForeignLogger).is_ok()) {
+            return 0;
+        }
+        *FOREIGN_LOG_CALLBACK.write().unwrap() = Some(callback);
+        log::set_max_level(match max_level {
+            i32::MIN..=0 => LevelFilter::Off,
+            1 => LevelFilter::Error,
+            2 => LevelFilter::Warn,
+            3 => LevelFilter::Info,
+            4 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        });
+        1
+    })
+}
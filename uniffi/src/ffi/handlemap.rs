@@ -0,0 +1,321 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The handles through which the foreign-language code refers to the objects.
+//!
+//! Rather than the raw pointers of the `Arc`s, which would be undefined behaviour to use once
+//! stale or corrupted, the foreign-language code gets `u64` handles to entries of a map holding
+//! the `Arc`s. Each handle is made of the index of its entry and of the generation of the entry,
+//! which is bumped whenever the entry is freed, so that every handle is checked before using the
+//! object: a handle which was never handed out, whose object was already freed, or which refers to
+//! an object of another type, is rejected with a [`HandleError`] rather than being dereferenced.
+//!
+//! Each handle owns a reference to its object, which is dropped when the handle is freed.
+//!
+//! The entries are spread over several shards, each behind its own `RwLock`, so that lifting
+//! objects, which only reads the map, never waits for other lifts, and that inserting and freeing
+//! handles only waits for the handles of the same shard. Each thread inserts its handles in its
+//! own shard. An entry whose generation would wrap is retired rather than reused, so that a stale
+//! handle never refers to a new object.
+
+use super::leakdetection::{self, AllocationKind};
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The error of a handle which doesn't refer to a live object of the expected type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleError {
+    /// The handle was never handed out by the map.
+    Invalid(u64),
+    /// The object of the handle was already freed.
+    Stale(u64),
+    /// The handle refers to an object of another type.
+    WrongType(u64),
+}
+
+impl std::fmt::Display for HandleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(handle) => write!(f, "invalid object handle {handle:#x}"),
+            Self::Stale(handle) => {
+                write!(f, "stale object handle {handle:#x}, the object was freed")
+            }
+            Self::WrongType(handle) => {
+                write!(
+                    f,
+                    "object handle {handle:#x} refers to an object of another type"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HandleError {}
+
+type Object = Arc<dyn Any + Send + Sync>;
+
+struct Entry {
+    // Odd while the entry holds an object, even while it's free, so that `0` is never a valid
+    // generation and the zeroed handle is never valid.
+    generation: u32,
+    object: Option<Object>,
+}
+
+struct Entries {
+    entries: Vec<Entry>,
+    // The indices of the free entries of the shard, which doesn't include the retired ones.
+    free: Vec<u32>,
+    // The number of entries holding an object.
+    len: usize,
+}
+
+// The low bits of the index of a handle are its shard, the other ones its index in the shard.
+const SHARD_BITS: u32 = 4;
+const SHARDS: usize = 1 << SHARD_BITS;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_SHARD: RwLock<Entries> = RwLock::new(Entries {
+    entries: Vec::new(),
+    free: Vec::new(),
+    len: 0,
+});
+
+static NEXT_THREAD_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // The shard in which the thread inserts its handles, so that the threads are spread over all
+    // of them.
+    static THREAD_SHARD: usize = NEXT_THREAD_SHARD.fetch_add(1, Ordering::Relaxed) % SHARDS;
+}
+
+/// A generational map of the objects referred to by handles.
+pub struct HandleMap {
+    shards: [RwLock<Entries>; SHARDS],
+    // Whether the handles are recorded by the leak detection, see `leakdetection.rs`.
+    track_leaks: bool,
+}
+
+/// The map of the handles of all the objects.
+pub static OBJECT_HANDLES: HandleMap = HandleMap::with_leak_tracking(true);
+
+impl HandleMap {
+    pub const fn new() -> Self {
+        Self::with_leak_tracking(false)
+    }
+
+    const fn with_leak_tracking(track_leaks: bool) -> Self {
+        Self {
+            shards: [EMPTY_SHARD; SHARDS],
+            track_leaks,
+        }
+    }
+
+    // The shards are never left inconsistent, so they're still usable if a thread panicked with
+    // them.
+    fn read(&self, shard: usize) -> RwLockReadGuard<'_, Entries> {
+        self.shards[shard]
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write(&self, shard: usize) -> RwLockWriteGuard<'_, Entries> {
+        self.shards[shard]
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Store an object, and return the handle referring to it.
+    pub fn insert<T: Any + Send + Sync>(&self, object: Arc<T>) -> u64 {
+        // The thread-local is gone while the thread is being destroyed.
+        let shard = THREAD_SHARD.try_with(|shard| *shard).unwrap_or(0);
+        let handle = {
+            let mut map = self.write(shard);
+            let index = match map.free.pop() {
+                Some(index) => index,
+                None => {
+                    let index = u32::try_from(map.entries.len())
+                        .ok()
+                        .filter(|index| index.leading_zeros() >= SHARD_BITS)
+                        .expect("too many object handles");
+                    map.entries.push(Entry {
+                        generation: 0,
+                        object: None,
+                    });
+                    index
+                }
+            };
+            map.len += 1;
+            let entry = &mut map.entries[index as usize];
+            // Free entries have an even generation, which is never `u32::MAX`.
+            entry.generation += 1;
+            entry.object = Some(object as Object);
+            (u64::from(entry.generation) << 32) | u64::from(index << SHARD_BITS) | shard as u64
+        };
+        if self.track_leaks {
+            leakdetection::track(AllocationKind::ObjectHandle, handle, || {
+                std::any::type_name::<T>().to_string()
+            });
+        }
+        handle
+    }
+
+    fn shard(handle: u64) -> usize {
+        handle as usize % SHARDS
+    }
+
+    fn index(handle: u64) -> usize {
+        (handle as u32 >> SHARD_BITS) as usize
+    }
+
+    fn check(entry: Option<&Entry>, handle: u64) -> Result<&Object, HandleError> {
+        let generation = (handle >> 32) as u32;
+        let entry = entry
+            .filter(|entry| generation % 2 == 1 && generation <= entry.generation)
+            .ok_or(HandleError::Invalid(handle))?;
+        match &entry.object {
+            Some(object) if entry.generation == generation => Ok(object),
+            _ => Err(HandleError::Stale(handle)),
+        }
+    }
+
+    /// Get a new reference to the object of a handle.
+    pub fn get<T: Any + Send + Sync>(&self, handle: u64) -> Result<Arc<T>, HandleError> {
+        let map = self.read(Self::shard(handle));
+        let object = Self::check(map.entries.get(Self::index(handle)), handle)?.clone();
+        object
+            .downcast()
+            .map_err(|_| HandleError::WrongType(handle))
+    }
+
+    /// Free a handle, and return the reference to the object it owned.
+    pub fn remove<T: Any + Send + Sync>(&self, handle: u64) -> Result<Arc<T>, HandleError> {
+        let object = {
+            let mut map = self.write(Self::shard(handle));
+            let index = Self::index(handle);
+            if !Self::check(map.entries.get(index), handle)?.is::<T>() {
+                return Err(HandleError::WrongType(handle));
+            }
+            let entry = &mut map.entries[index];
+            let object = entry.object.take().unwrap();
+            // An entry whose generation would wrap is retired: its generation stays the last one,
+            // which makes all its handles stale, and it isn't freed.
+            if entry.generation != u32::MAX {
+                entry.generation += 1;
+                map.free.push(index as u32);
+            }
+            map.len -= 1;
+            object.downcast().unwrap_or_else(|_| unreachable!())
+        };
+        if self.track_leaks {
+            leakdetection::untrack(AllocationKind::ObjectHandle, handle);
+        }
+        Ok(object)
+    }
+
+    /// Make a new handle referring to the same object as a handle.
+    pub fn clone_handle<T: Any + Send + Sync>(&self, handle: u64) -> Result<u64, HandleError> {
+        let object = self.get::<T>(handle)?;
+        Ok(self.insert(object))
+    }
+
+    /// The number of handles currently referring to objects.
+    pub fn len(&self) -> usize {
+        (0..SHARDS).map(|shard| self.read(shard).len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for HandleMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_handle_map() {
+        let map = HandleMap::new();
+        let handle = map.insert(Arc::new(42u32));
+        assert_ne!(handle, 0);
+        assert_eq!(*map.get::<u32>(handle).unwrap(), 42);
+        assert_eq!(map.get::<u64>(handle), Err(HandleError::WrongType(handle)));
+        assert_eq!(map.get::<u32>(0), Err(HandleError::Invalid(0)));
+        assert_eq!(
+            map.get::<u32>(handle + 1),
+            Err(HandleError::Invalid(handle + 1))
+        );
+
+        let clone = map.clone_handle::<u32>(handle).unwrap();
+        assert_ne!(clone, handle);
+        assert_eq!(map.len(), 2);
+        assert_eq!(*map.remove::<u32>(handle).unwrap(), 42);
+        assert_eq!(map.remove::<u32>(handle), Err(HandleError::Stale(handle)));
+        assert_eq!(*map.get::<u32>(clone).unwrap(), 42);
+
+        // The freed entry is reused, but the stale handle still doesn't refer to it.
+        let other = map.insert(Arc::new(7u32));
+        assert_eq!(other as u32, handle as u32);
+        assert_eq!(map.get::<u32>(handle), Err(HandleError::Stale(handle)));
+        assert_eq!(*map.get::<u32>(other).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_handles_are_spread_over_shards() {
+        let map = Arc::new(HandleMap::new());
+        let threads = (0..SHARDS)
+            .map(|i| {
+                let map = Arc::clone(&map);
+                std::thread::spawn(move || {
+                    (0..2)
+                        .map(|j| map.insert(Arc::new(i * 2 + j)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let handles = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(map.len(), SHARDS * 2);
+        for (i, handle) in handles.iter().enumerate() {
+            assert_eq!(*map.get::<usize>(*handle).unwrap(), i);
+        }
+        // The handles of a thread are in its shard, and the threads aren't all in the same one.
+        for pair in handles.chunks(2) {
+            assert_eq!(HandleMap::shard(pair[0]), HandleMap::shard(pair[1]));
+        }
+        assert!(handles
+            .iter()
+            .any(|handle| HandleMap::shard(*handle) != HandleMap::shard(handles[0])));
+    }
+
+    #[test]
+    fn test_entries_are_retired_before_their_generation_wraps() {
+        let map = HandleMap::new();
+        let handle = map.insert(Arc::new(42u32));
+        map.remove::<u32>(handle).unwrap();
+        // Skip to the last generation of the entry.
+        map.write(HandleMap::shard(handle)).entries[0].generation = u32::MAX - 1;
+        let last = map.insert(Arc::new(7u32));
+        assert_eq!(last as u32, handle as u32);
+        assert_eq!(last >> 32, u64::from(u32::MAX));
+
+        assert_eq!(*map.remove::<u32>(last).unwrap(), 7);
+        assert_eq!(map.get::<u32>(last), Err(HandleError::Stale(last)));
+        assert_eq!(map.get::<u32>(handle), Err(HandleError::Stale(handle)));
+        assert!(map.is_empty());
+        // The retired entry isn't reused.
+        for _ in 0..SHARDS {
+            let other = map.insert(Arc::new(1u32));
+            assert_ne!(other as u32, handle as u32);
+        }
+    }
+}
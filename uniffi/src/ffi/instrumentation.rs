@@ -0,0 +1,162 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Instrumentation of the calls from the foreign-language code into Rust.
+//!
+//! A [`CallHook`] registered with [`set_call_hook`] is invoked after every call of a function,
+//! method or constructor of a component, with the name of its FFI function, the duration of the
+//! call and its outcome, for example to feed call-level metrics into telemetry:
+//!
+//! ```
+//! use uniffi::{CallInfo, CallOutcome};
+//!
+//! uniffi::set_call_hook(Some(Box::new(|call: &CallInfo<'_>| {
+//!     if call.outcome != CallOutcome::Success {
+//!         uniffi::deps::log::warn!("{} failed after {:?}", call.name, call.duration);
+//!     }
+//! })));
+//! ```
+//!
+//! The hook is called on the thread of the call, so it must be quick.  Without a hook, the calls
+//! aren't even timed.
+
+use super::rustcalls::{CALL_ERROR, CALL_PANIC};
+use super::RustCallStatus;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// How a call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallOutcome {
+    /// The call returned a value.
+    Success,
+    /// The call returned an `Err`, raised as an exception by the foreign-language code.
+    Error,
+    /// The call panicked, or failed with an internal error.
+    Panic,
+}
+
+/// A call from the foreign-language code, see [`CallHook`].
+#[derive(Debug, Clone, Copy)]
+pub struct CallInfo<'a> {
+    /// The name of the FFI function which was called.
+    pub name: &'a str,
+    /// The time spent in Rust, including the lifting of the arguments and the lowering of the
+    /// return value.
+    pub duration: Duration,
+    pub outcome: CallOutcome,
+}
+
+/// A hook invoked after every call from the foreign-language code, see [`set_call_hook`].
+///
+/// It's implemented for the closures taking a [`CallInfo`].
+pub trait CallHook: Send + Sync {
+    fn on_call(&self, call: &CallInfo<'_>);
+}
+
+impl<F> CallHook for F
+where
+    F: Fn(&CallInfo<'_>) + Send + Sync,
+{
+    fn on_call(&self, call: &CallInfo<'_>) {
+        self(call)
+    }
+}
+
+// Checked first, so that the calls don't take the lock without a hook.
+static HOOK_SET: AtomicBool = AtomicBool::new(false);
+static HOOK: RwLock<Option<Arc<dyn CallHook>>> = RwLock::new(None);
+
+/// Set the hook invoked after every call from the foreign-language code, replacing the previous
+/// one, or remove it with `None`.
+pub fn set_call_hook(hook: Option<Box<dyn CallHook>>) {
+    let mut current = HOOK.write().unwrap_or_else(|err| err.into_inner());
+    HOOK_SET.store(hook.is_some(), Ordering::Release);
+    *current = hook.map(Arc::from);
+}
+
+fn current_hook() -> Option<Arc<dyn CallHook>> {
+    if !HOOK_SET.load(Ordering::Acquire) {
+        return None;
+    }
+    HOOK.read().unwrap_or_else(|err| err.into_inner()).clone()
+}
+
+/// Make a call from the foreign-language code with `call`, and report it to the hook.
+///
+/// This is used by the scaffolding code, around [`call_with_output`](super::call_with_output) or
+/// [`call_with_result`](super::call_with_result), which set the code of `out_status`.
+pub fn instrument_call<R>(
+    name: &str,
+    out_status: &mut RustCallStatus,
+    call: impl FnOnce(&mut RustCallStatus) -> R,
+) -> R {
+    let hook = match current_hook() {
+        Some(hook) => hook,
+        None => return call(out_status),
+    };
+    let start = Instant::now();
+    let result = call(out_status);
+    let info = CallInfo {
+        name,
+        duration: start.elapsed(),
+        outcome: match out_status.code {
+            CALL_ERROR => CallOutcome::Error,
+            CALL_PANIC => CallOutcome::Panic,
+            _ => CallOutcome::Success,
+        },
+    };
+    // A panic must not unwind into the foreign-language code, and the call itself succeeded.
+    if panic::catch_unwind(panic::AssertUnwindSafe(|| hook.on_call(&info))).is_err() {
+        log::error!("The call hook panicked on {name}");
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{call_with_output, call_with_result, RustBuffer};
+    use std::mem::MaybeUninit;
+    use std::sync::Mutex;
+
+    // The hook is global, so everything is tested in a single test.
+    #[test]
+    fn test_call_hook() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&calls);
+        set_call_hook(Some(Box::new(move |call: &CallInfo<'_>| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((call.name.to_string(), call.outcome))
+        })));
+
+        let mut status = RustCallStatus {
+            code: 0,
+            error_buf: MaybeUninit::new(RustBuffer::new()),
+        };
+        let value = instrument_call("ok", &mut status, |status| {
+            call_with_output::<false, _, _>(status, || 42u8)
+        });
+        assert_eq!(value, 42);
+        instrument_call("err", &mut status, |status| {
+            call_with_result::<false, _, _>(status, || Err::<u8, _>(RustBuffer::new()))
+        });
+        set_call_hook(None);
+        instrument_call("unhooked", &mut status, |status| {
+            call_with_output::<false, _, _>(status, || 0u8)
+        });
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                ("ok".to_string(), CallOutcome::Success),
+                ("err".to_string(), CallOutcome::Error),
+            ]
+        );
+    }
+}
@@ -0,0 +1,420 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The runtime of the JNI glue which the scaffolding generates for the Kotlin bindings using the
+//! `jni` FFI backend.
+//!
+//! The `external` functions of the bindings only pass numbers: the `RustBuffer`s as their fields,
+//! and the objects as their handles. Whatever else crosses the FFI goes through the scratch
+//! memory of the calling thread, see [`JniScratch`], which the bindings read and write as a
+//! direct `ByteBuffer`:
+//!
+//! * The glue writes the call status at offset 0, once the FFI function has returned.
+//! * It writes the returned `RustBuffer`, or the fields of the returned struct, at
+//!   [`SCRATCH_RETURN_OFFSET`].
+//! * The bindings write the fields of the struct arguments after the return value, which the glue
+//!   reads before calling the FFI function.
+//!
+//! The fields of the structs are written in slots of 8 bytes, in the order of the fields.
+//! Everything is written in the native byte order.
+//!
+//! Since the scratch memory is only read right after being written, the foreign code called back
+//! by the FFI function can call Rust again on the same thread.
+//!
+//! Rust calls the callback interfaces and the log forwarder through the static methods of the
+//! class declaring the `external` functions, see [`JniStaticMethod`]. Their arguments are numbers
+//! too, and the callbacks write the `RustBuffer` they return to the scratch memory, at
+//! [`SCRATCH_RETURN_OFFSET`].
+//!
+//! There's no crate for JNI among our dependencies, so the few functions of the JNI interface
+//! which are needed are called through their index in its function table.
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::os::raw::c_int;
+use std::ptr;
+
+use once_cell::sync::OnceCell;
+
+use super::{RustBuffer, RustCallStatus};
+
+/// The `JNIEnv` pointer, to a pointer to the function table of the JNI interface.
+pub type JniEnv = *mut *const *const c_void;
+
+/// A local or global reference to a JVM object.
+pub type JniObject = *mut c_void;
+
+/// The `JavaVM` pointer, to a pointer to the function table of the invocation interface.
+type JavaVm = *mut *const *const c_void;
+
+/// The offset of the return value in the scratch memory, after the call status.
+pub const SCRATCH_RETURN_OFFSET: usize = 32;
+
+/// The size of the slots of the fields of the structs in the scratch memory.
+pub const SCRATCH_SLOT_SIZE: usize = 8;
+
+// The indices of the functions in the table of the JNI interface, see
+// https://docs.oracle.com/en/java/javase/17/docs/specs/jni/functions.html#interface-function-table
+const EXCEPTION_CLEAR: usize = 17;
+const NEW_GLOBAL_REF: usize = 21;
+const DELETE_LOCAL_REF: usize = 23;
+const GET_OBJECT_CLASS: usize = 31;
+const GET_STATIC_METHOD_ID: usize = 113;
+const CALL_STATIC_INT_METHOD_A: usize = 131;
+const CALL_STATIC_VOID_METHOD_A: usize = 143;
+const GET_JAVA_VM: usize = 219;
+const EXCEPTION_CHECK: usize = 228;
+const NEW_DIRECT_BYTE_BUFFER: usize = 229;
+
+// The indices of the functions in the table of the invocation interface.
+const GET_ENV: usize = 6;
+const ATTACH_CURRENT_THREAD_AS_DAEMON: usize = 7;
+
+const JNI_OK: c_int = 0;
+const JNI_VERSION_1_6: c_int = 0x0001_0006;
+
+/// A `jvalue`, the argument of a JVM method, of which we only pass `int`s and `long`s.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union JValue {
+    i: i32,
+    j: i64,
+}
+
+impl JValue {
+    pub fn int(i: i32) -> Self {
+        Self { i }
+    }
+
+    pub fn long(j: i64) -> Self {
+        Self { j }
+    }
+}
+
+// Get the function at `index` in the function table of `env`, which is either a `JNIEnv` or a
+// `JavaVM`, as a function pointer of type `F`.
+unsafe fn function<F: Copy>(env: *mut *const *const c_void, index: usize) -> F {
+    std::mem::transmute_copy(&*(*env).add(index))
+}
+
+/// The scratch memory of each thread, declared by the glue of each component with
+/// `thread_local!`, since the components built into the same library share this crate.
+pub struct JniScratch(RefCell<Vec<u64>>);
+
+impl JniScratch {
+    pub const fn new() -> Self {
+        // In words, so that it's aligned for any field.
+        Self(RefCell::new(Vec::new()))
+    }
+
+    /// Allocate `size` bytes of scratch memory, and return it as a direct `ByteBuffer`. The
+    /// bindings call it once on each thread, and keep the buffer in a `ThreadLocal`.
+    ///
+    /// # Safety
+    ///
+    /// `env` must be the `JNIEnv` of the calling thread.
+    pub unsafe fn buffer(&self, env: JniEnv, size: i32) -> JniObject {
+        let size = size.max(0) as usize;
+        let mut words = self.0.borrow_mut();
+        // A buffer returned before keeps pointing to the memory, so it's never reallocated.
+        if words.is_empty() {
+            *words = vec![0; ((size + 7) / 8).max(1)];
+        }
+        assert!(
+            words.len() * 8 >= size,
+            "the scratch memory of the thread was allocated smaller"
+        );
+        new_direct_byte_buffer(env, words.as_mut_ptr() as *mut u8, size)
+    }
+
+    /// The scratch memory, which the bindings allocate on the thread before any call.
+    pub fn as_ptr(&self) -> *mut u8 {
+        let mut words = self.0.borrow_mut();
+        assert!(
+            !words.is_empty(),
+            "the scratch memory of the thread isn't allocated"
+        );
+        words.as_mut_ptr() as *mut u8
+    }
+}
+
+impl Default for JniScratch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Write the call status to the `scratch` memory.
+///
+/// # Safety
+///
+/// `scratch` must be the scratch memory of the calling thread.
+pub unsafe fn write_call_status(scratch: *mut u8, status: RustCallStatus) {
+    ptr::write(scratch as *mut RustCallStatus, status)
+}
+
+/// Write a returned `RustBuffer` to the `scratch` memory.
+///
+/// # Safety
+///
+/// `scratch` must be the scratch memory of the calling thread.
+pub unsafe fn write_return_buffer(scratch: *mut u8, buf: RustBuffer) {
+    ptr::write(scratch.add(SCRATCH_RETURN_OFFSET) as *mut RustBuffer, buf)
+}
+
+/// Read the `index`th field of the struct at `offset` in the `scratch` memory.
+///
+/// # Safety
+///
+/// `scratch` must be the scratch memory of the calling thread, holding a `T` in the slot.
+pub unsafe fn read_slot<T: Copy>(scratch: *mut u8, offset: usize, index: usize) -> T {
+    ptr::read(scratch.add(offset + index * SCRATCH_SLOT_SIZE) as *const T)
+}
+
+/// Write the `index`th field of the struct at `offset` in the `scratch` memory.
+///
+/// # Safety
+///
+/// `scratch` must be the scratch memory of the calling thread, large enough for the slot.
+pub unsafe fn write_slot<T: Copy>(scratch: *mut u8, offset: usize, index: usize, value: T) {
+    ptr::write(
+        scratch.add(offset + index * SCRATCH_SLOT_SIZE) as *mut T,
+        value,
+    )
+}
+
+/// A direct `ByteBuffer` over `len` bytes at `data`, which the bindings read the `RustBuffer`s,
+/// the shared slices and the logged strings through.
+///
+/// # Safety
+///
+/// `env` must be the `JNIEnv` of the calling thread, and `data` must be valid for `len` bytes
+/// for as long as the buffer is used.
+pub unsafe fn new_direct_byte_buffer(env: JniEnv, data: *mut u8, len: usize) -> JniObject {
+    let new_direct_byte_buffer: unsafe extern "system" fn(JniEnv, *mut c_void, i64) -> JniObject =
+        function(env, NEW_DIRECT_BYTE_BUFFER);
+    new_direct_byte_buffer(env, data as *mut c_void, len as i64)
+}
+
+/// A static method of the class declaring the `external` functions of the bindings, which Rust
+/// calls from any thread.
+///
+/// It's resolved by the glue of the FFI function installing the callback, from the object
+/// calling it. Rust can't tell when the class is unloaded, so it's kept loaded.
+pub struct JniStaticMethod {
+    // The name and the signature of the method, ending with a NUL byte.
+    name: &'static str,
+    signature: &'static str,
+    resolved: OnceCell<ResolvedMethod>,
+}
+
+struct ResolvedMethod {
+    vm: JavaVm,
+    // A global reference.
+    class: JniObject,
+    method: *mut c_void,
+}
+
+// The `JavaVM`, the global references and the method IDs are valid on any thread.
+unsafe impl Send for ResolvedMethod {}
+unsafe impl Sync for ResolvedMethod {}
+
+impl JniStaticMethod {
+    pub const fn new(name: &'static str, signature: &'static str) -> Self {
+        Self {
+            name,
+            signature,
+            resolved: OnceCell::new(),
+        }
+    }
+
+    /// Resolve the method in the class of `this`, the object whose `external` function is being
+    /// called. Returns whether it was found.
+    ///
+    /// # Safety
+    ///
+    /// `env` must be the `JNIEnv` of the calling thread, and `this` a reference valid in it.
+    pub unsafe fn resolve(&self, env: JniEnv, this: JniObject) -> bool {
+        self.resolved
+            .get_or_try_init(|| {
+                let get_object_class: unsafe extern "system" fn(JniEnv, JniObject) -> JniObject =
+                    function(env, GET_OBJECT_CLASS);
+                let get_static_method_id: unsafe extern "system" fn(
+                    JniEnv,
+                    JniObject,
+                    *const u8,
+                    *const u8,
+                )
+                    -> *mut c_void = function(env, GET_STATIC_METHOD_ID);
+                let new_global_ref: unsafe extern "system" fn(JniEnv, JniObject) -> JniObject =
+                    function(env, NEW_GLOBAL_REF);
+                let delete_local_ref: unsafe extern "system" fn(JniEnv, JniObject) =
+                    function(env, DELETE_LOCAL_REF);
+                let get_java_vm: unsafe extern "system" fn(JniEnv, *mut JavaVm) -> c_int =
+                    function(env, GET_JAVA_VM);
+
+                let class = get_object_class(env, this);
+                let method =
+                    get_static_method_id(env, class, self.name.as_ptr(), self.signature.as_ptr());
+                if method.is_null() {
+                    // `GetStaticMethodID` threw `NoSuchMethodError`.
+                    clear_exception(env);
+                    delete_local_ref(env, class);
+                    return Err(());
+                }
+                let mut vm = ptr::null_mut();
+                if get_java_vm(env, &mut vm) != JNI_OK {
+                    delete_local_ref(env, class);
+                    return Err(());
+                }
+                let global_class = new_global_ref(env, class);
+                delete_local_ref(env, class);
+                Ok(ResolvedMethod {
+                    vm,
+                    class: global_class,
+                    method,
+                })
+            })
+            .is_ok()
+    }
+
+    /// Call the method, which returns an `int`, with `args`, from any thread. Returns `None` if
+    /// it isn't resolved, if the thread can't be attached to the JVM, or if the method threw.
+    ///
+    /// # Safety
+    ///
+    /// `args` must match the signature of the method.
+    pub unsafe fn call_int(&self, args: &[JValue]) -> Option<i32> {
+        let resolved = self.resolved.get()?;
+        let env = attach_current_thread(resolved.vm)?;
+        let call_static_int_method_a: unsafe extern "system" fn(
+            JniEnv,
+            JniObject,
+            *mut c_void,
+            *const JValue,
+        ) -> i32 = function(env, CALL_STATIC_INT_METHOD_A);
+        let result = call_static_int_method_a(env, resolved.class, resolved.method, args.as_ptr());
+        (!clear_exception(env)).then_some(result)
+    }
+
+    /// Call the method, which returns `void`, with `args`, from any thread. Returns whether it
+    /// was called without throwing.
+    ///
+    /// # Safety
+    ///
+    /// `args` must match the signature of the method.
+    pub unsafe fn call_void(&self, args: &[JValue]) -> bool {
+        let resolved = match self.resolved.get() {
+            Some(resolved) => resolved,
+            None => return false,
+        };
+        let env = match attach_current_thread(resolved.vm) {
+            Some(env) => env,
+            None => return false,
+        };
+        let call_static_void_method_a: unsafe extern "system" fn(
+            JniEnv,
+            JniObject,
+            *mut c_void,
+            *const JValue,
+        ) = function(env, CALL_STATIC_VOID_METHOD_A);
+        call_static_void_method_a(env, resolved.class, resolved.method, args.as_ptr());
+        !clear_exception(env)
+    }
+}
+
+// The `JNIEnv` of the calling thread, which is attached to the JVM as a daemon thread if it isn't
+// yet, like the threads created by Rust. It then stays attached until it exits.
+unsafe fn attach_current_thread(vm: JavaVm) -> Option<JniEnv> {
+    let get_env: unsafe extern "system" fn(JavaVm, *mut JniEnv, c_int) -> c_int =
+        function(vm, GET_ENV);
+    let mut env = ptr::null_mut();
+    if get_env(vm, &mut env, JNI_VERSION_1_6) == JNI_OK {
+        return Some(env);
+    }
+    let attach_current_thread_as_daemon: unsafe extern "system" fn(
+        JavaVm,
+        *mut JniEnv,
+        *mut c_void,
+    ) -> c_int = function(vm, ATTACH_CURRENT_THREAD_AS_DAEMON);
+    (attach_current_thread_as_daemon(vm, &mut env, ptr::null_mut()) == JNI_OK).then_some(env)
+}
+
+// Clear the pending exception of the thread, if any, which must not unwind into Rust. Returns
+// whether there was one.
+unsafe fn clear_exception(env: JniEnv) -> bool {
+    let exception_check: unsafe extern "system" fn(JniEnv) -> u8 = function(env, EXCEPTION_CHECK);
+    if exception_check(env) == 0 {
+        return false;
+    }
+    let exception_clear: unsafe extern "system" fn(JniEnv) = function(env, EXCEPTION_CLEAR);
+    exception_clear(env);
+    true
+}
+
+/// Call the callback of a callback interface through its static method, with the signature
+/// `(JIIIJ)I`: the handle, the method, and the fields of `args`. The method writes the
+/// `RustBuffer` it returns to the `scratch` memory of the thread, which is copied to `buf_ptr`.
+///
+/// It's the body of the [`ForeignCallback`](super::ForeignCallback) which the glue installs for
+/// the callback interface.
+///
+/// # Safety
+///
+/// `buf_ptr` must be valid for writes.
+pub unsafe fn call_foreign_callback(
+    method: &JniStaticMethod,
+    scratch: &JniScratch,
+    handle: u64,
+    method_index: u32,
+    args: RustBuffer,
+    buf_ptr: *mut RustBuffer,
+) -> c_int {
+    let (data, len, capacity) = args.into_raw_parts();
+    let result = method.call_int(&[
+        JValue::long(handle as i64),
+        JValue::int(method_index as i32),
+        JValue::int(capacity),
+        JValue::int(len),
+        JValue::long(data as usize as i64),
+    ]);
+    match result {
+        Some(code) => {
+            // The method ran on this thread, so its scratch memory is allocated.
+            ptr::write(
+                buf_ptr,
+                ptr::read(scratch.as_ptr().add(SCRATCH_RETURN_OFFSET) as *const RustBuffer),
+            );
+            code
+        }
+        // An unexpected error, without a message.
+        None => -1,
+    }
+}
+
+/// Forward a log record through the static method of the log forwarder, with the signature
+/// `(IJIJI)V`: the level, then the address and the length of the target and of the message.
+///
+/// It's the body of the [`ForeignLogCallback`](super::ForeignLogCallback) which the glue installs.
+///
+/// # Safety
+///
+/// `target` and `message` must be valid for their lengths.
+pub unsafe fn call_log_callback(
+    method: &JniStaticMethod,
+    level: i32,
+    target: *const u8,
+    target_len: i32,
+    message: *const u8,
+    message_len: i32,
+) {
+    // There's nowhere to report a failure.
+    method.call_void(&[
+        JValue::int(level),
+        JValue::long(target as usize as i64),
+        JValue::int(target_len),
+        JValue::long(message as usize as i64),
+        JValue::int(message_len),
+    ]);
+}
@@ -0,0 +1,197 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Tracking of the allocations handed out to the foreign-language code, to debug leaks.
+//!
+//! With the `leak-detection` feature, every object handle of [`OBJECT_HANDLES`](super::OBJECT_HANDLES)
+//! and every `RustBuffer` is recorded along with the backtrace of its creation until it's freed,
+//! and [`live_allocations`] lists the ones which are still alive. Foreign-language code can get
+//! the same list as text, through the `dump_live_allocations` FFI function of each component.
+//!
+//! Capturing a backtrace for every allocation is slow, so the feature is meant for debug builds
+//! only. Without it, nothing is tracked and the list of live allocations is always empty.
+
+use super::{call_with_output, RustBuffer, RustCallStatus};
+use crate::FfiConverter;
+
+/// The kind of a tracked allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AllocationKind {
+    /// A handle to an object, see [`HandleMap`](super::HandleMap).
+    ObjectHandle,
+    /// The bytes of a `RustBuffer`.
+    RustBuffer,
+}
+
+/// An allocation which wasn't freed yet.
+#[derive(Debug, Clone)]
+pub struct LiveAllocation {
+    pub kind: AllocationKind,
+    /// The handle of the object, or the address of the bytes of the buffer.
+    pub id: u64,
+    /// The type of the object, or the capacity of the buffer.
+    pub description: String,
+    /// The backtrace of the creation of the allocation.
+    pub backtrace: String,
+}
+
+impl std::fmt::Display for LiveAllocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            AllocationKind::ObjectHandle => "object handle",
+            AllocationKind::RustBuffer => "RustBuffer",
+        };
+        writeln!(
+            f,
+            "{kind} {:#x} ({}), created at:",
+            self.id, self.description
+        )?;
+        write!(f, "{}", self.backtrace)
+    }
+}
+
+#[cfg(feature = "leak-detection")]
+mod tracking {
+    use super::{AllocationKind, LiveAllocation};
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    struct Allocation {
+        description: String,
+        // Resolving the symbols is by far the slowest part, so it's only done when dumping.
+        backtrace: backtrace::Backtrace,
+    }
+
+    static LIVE: Lazy<Mutex<HashMap<(AllocationKind, u64), Allocation>>> =
+        Lazy::new(Default::default);
+
+    fn with_live<R>(f: impl FnOnce(&mut HashMap<(AllocationKind, u64), Allocation>) -> R) -> R {
+        f(&mut LIVE.lock().unwrap_or_else(|err| err.into_inner()))
+    }
+
+    pub(crate) fn track(kind: AllocationKind, id: u64, description: impl FnOnce() -> String) {
+        let allocation = Allocation {
+            description: description(),
+            backtrace: backtrace::Backtrace::new_unresolved(),
+        };
+        with_live(|live| live.insert((kind, id), allocation));
+    }
+
+    pub(crate) fn untrack(kind: AllocationKind, id: u64) {
+        with_live(|live| live.remove(&(kind, id)));
+    }
+
+    pub fn live_allocations() -> Vec<LiveAllocation> {
+        let mut allocations = with_live(|live| {
+            live.iter()
+                .map(|(&(kind, id), allocation)| {
+                    (
+                        kind,
+                        id,
+                        allocation.description.clone(),
+                        allocation.backtrace.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+        allocations.sort_by_key(|&(kind, id, ..)| (kind, id));
+        allocations
+            .into_iter()
+            .map(|(kind, id, description, mut backtrace)| {
+                backtrace.resolve();
+                LiveAllocation {
+                    kind,
+                    id,
+                    description,
+                    backtrace: format!("{backtrace:?}"),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(feature = "leak-detection"))]
+mod tracking {
+    use super::{AllocationKind, LiveAllocation};
+
+    #[inline(always)]
+    pub(crate) fn track(_kind: AllocationKind, _id: u64, _description: impl FnOnce() -> String) {}
+
+    #[inline(always)]
+    pub(crate) fn untrack(_kind: AllocationKind, _id: u64) {}
+
+    pub fn live_allocations() -> Vec<LiveAllocation> {
+        Vec::new()
+    }
+}
+
+pub(crate) use tracking::{track, untrack};
+
+/// List the allocations which weren't freed yet, or nothing without the `leak-detection` feature.
+pub fn live_allocations() -> Vec<LiveAllocation> {
+    tracking::live_allocations()
+}
+
+/// Describe the allocations which weren't freed yet, with the backtraces of their creation.
+pub fn dump_live_allocations() -> String {
+    if cfg!(not(feature = "leak-detection")) {
+        return "leak detection is disabled, enable the `leak-detection` feature of uniffi"
+            .to_string();
+    }
+    let allocations = live_allocations();
+    let mut dump = format!("{} live allocation(s)\n", allocations.len());
+    for allocation in allocations {
+        dump.push_str(&format!("\n{allocation}\n"));
+    }
+    dump
+}
+
+/// Return the description of the live allocations to the foreign-language code, as a string in
+/// a `RustBuffer` which the caller must free.
+///
+/// The buffer of the description itself is allocated after listing the allocations, so it's not
+/// part of them.
+pub fn uniffi_dump_live_allocations<const COMPACT: bool>(
+    call_status: &mut RustCallStatus,
+) -> RustBuffer {
+    call_with_output::<COMPACT, _, _>(call_status, || {
+        <String as FfiConverter>::lower(dump_live_allocations())
+    })
+}
+
+#[cfg(all(test, feature = "leak-detection"))]
+mod test {
+    use super::*;
+    use crate::OBJECT_HANDLES;
+    use std::sync::Arc;
+
+    fn is_live(kind: AllocationKind, id: u64) -> bool {
+        live_allocations()
+            .iter()
+            .any(|allocation| allocation.kind == kind && allocation.id == id)
+    }
+
+    #[test]
+    fn test_leak_detection() {
+        let handle = OBJECT_HANDLES.insert(Arc::new(42u32));
+        let allocation = live_allocations()
+            .into_iter()
+            .find(|allocation| {
+                allocation.kind == AllocationKind::ObjectHandle && allocation.id == handle
+            })
+            .unwrap();
+        assert_eq!(allocation.description, "u32");
+        assert!(allocation.backtrace.contains("test_leak_detection"));
+        OBJECT_HANDLES.remove::<u32>(handle).unwrap();
+        assert!(!is_live(AllocationKind::ObjectHandle, handle));
+
+        let bytes = vec![0u8; 8];
+        let id = bytes.as_ptr() as u64;
+        let buf = RustBuffer::from_vec(bytes);
+        assert!(is_live(AllocationKind::RustBuffer, id));
+        buf.destroy();
+        assert!(!is_live(AllocationKind::RustBuffer, id));
+    }
+}
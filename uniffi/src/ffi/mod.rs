@@ -2,14 +2,47 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+pub mod bufferalloc;
+pub mod bufferlimits;
+pub mod bufferpool;
+pub mod errorcontext;
 pub mod ffidefault;
 pub mod foreignbytes;
 pub mod foreigncallbacks;
+pub mod foreignlog;
+pub mod handlemap;
+pub mod instrumentation;
+#[doc(hidden)]
+pub mod jni;
+pub mod leakdetection;
 pub mod rustbuffer;
 pub mod rustcalls;
+pub mod sharedslice;
+#[cfg(not(feature = "prefixed-symbols-only"))]
+pub mod unprefixed;
 
+pub use bufferalloc::{
+    set_rustbuffer_allocator, uniffi_rustbuffer_set_allocator, AllocatorAlreadySet,
+    RustBufferAllocator,
+};
+pub use bufferlimits::{
+    rustbuffer_stats, set_rustbuffer_limits, uniffi_rustbuffer_stats, LimitsAlreadySet,
+    PayloadTooLarge, RustBufferLimits, RustBufferStats,
+};
+pub use bufferpool::{
+    buffer_pool_stats, configure_buffer_pool, trim_buffer_pool, BufferPoolConfig, BufferPoolStats,
+};
+pub use errorcontext::{add_error_context, record_error_sources, ErrorContext};
 use ffidefault::FfiDefault;
 pub use foreignbytes::*;
 pub use foreigncallbacks::*;
+pub use foreignlog::*;
+pub use handlemap::{HandleError, HandleMap, OBJECT_HANDLES};
+pub use instrumentation::{instrument_call, set_call_hook, CallHook, CallInfo, CallOutcome};
+pub use leakdetection::{
+    dump_live_allocations, live_allocations, uniffi_dump_live_allocations, AllocationKind,
+    LiveAllocation,
+};
 pub use rustbuffer::*;
 pub use rustcalls::*;
+pub use sharedslice::{shared_slice_data, shared_slice_free, shared_slice_len, SharedSlice};
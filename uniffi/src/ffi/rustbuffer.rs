@@ -2,7 +2,11 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use crate::ffi::{call_with_output, ForeignBytes, RustCallStatus};
+use crate::ffi::bufferalloc::{alloc_bytes, custom_allocator};
+use crate::ffi::bufferlimits::{self, PayloadTooLarge};
+use crate::ffi::bufferpool::{return_buffer, take_buffer};
+use crate::ffi::leakdetection::{self, AllocationKind};
+use crate::ffi::{call_with_lowered_result, call_with_output, ForeignBytes, RustCallStatus};
 
 /// Support for passing an allocated-by-Rust buffer of bytes over the FFI.
 ///
@@ -46,6 +50,14 @@ use crate::ffi::{call_with_output, ForeignBytes, RustCallStatus};
 /// received it by calling some Rust function, and should be respecting the
 /// invariants listed above).
 ///
+/// When a [custom allocator](crate::set_rustbuffer_allocator) is registered, the buffers
+/// allocated for the foreign-language code are allocated by it, and the values lifted from them
+/// are read in place. The bytes of the `Vec<u8>`s which values are lowered into are copied to
+/// memory allocated by it, and `destroy_into_vec` copies them back to a `Vec<u8>`.
+///
+/// The buffers are counted, and the ones created with [`RustBuffer::try_from_vec`] can't go over
+/// the [limits](crate::set_rustbuffer_limits).
+///
 /// This struct is based on `ByteBuffer` from the `ffi-support` crate, but modified
 /// to retain unallocated capacity rather than truncating to the occupied length.
 #[repr(C)]
@@ -86,6 +98,15 @@ impl RustBuffer {
         }
     }
 
+    /// Consumes a `RustBuffer` into its constituent fields, in the order of
+    /// [`RustBuffer::from_raw_parts`].
+    ///
+    /// Like it, this is intended mainly as an internal convenience function, for passing the
+    /// buffer over an FFI which can't pass structs.
+    pub fn into_raw_parts(self) -> (*mut u8, i32, i32) {
+        (self.data, self.len, self.capacity)
+    }
+
     /// Get the current length of the buffer, as a `usize`.
     ///
     /// This is mostly a helper function to convert the `i32` length field
@@ -108,6 +129,7 @@ impl RustBuffer {
 
     /// Creates a `RustBuffer` zero-filed to the requested size.
     ///
+    /// Its buffer is taken from the [buffer pool](crate::configure_buffer_pool) when it's enabled.
     /// The resulting vector will not be automatically dropped; you must
     /// arrange to call `destroy` or `destroy_into_vec` when finished with it.
     ///
@@ -120,7 +142,9 @@ impl RustBuffer {
             size < i32::MAX as usize,
             "RustBuffer requested size too large"
         );
-        Self::from_vec(vec![0u8; size])
+        let mut v = take_buffer(size);
+        v.resize(size, 0);
+        Self::from_vec(v)
     }
 
     /// Consumes a `Vec<u8>` and returns its raw parts as a `RustBuffer`.
@@ -128,15 +152,105 @@ impl RustBuffer {
     /// The resulting vector will not be automatically dropped; you must
     /// arrange to call `destroy` or `destroy_into_vec` when finished with it.
     ///
+    /// Its capacity is counted by the [`rustbuffer_stats`](crate::rustbuffer_stats), but not
+    /// checked against the [limits](crate::set_rustbuffer_limits), see [`Self::try_from_vec`].
+    ///
     /// # Panics
     ///
     /// Panics if the vector's length or capacity are too large to fit in an `i32`,
     /// and hence would risk incompatibility with some foreign-language code.
     pub fn from_vec(v: Vec<u8>) -> Self {
-        let capacity = i32::try_from(v.capacity()).expect("buffer capacity cannot fit into a i32.");
-        let len = i32::try_from(v.len()).expect("buffer length cannot fit into a i32.");
-        let mut v = std::mem::ManuallyDrop::new(v);
-        unsafe { Self::from_raw_parts(v.as_mut_ptr(), len, capacity) }
+        if v.capacity() > 0 {
+            bufferlimits::track_unchecked(v.capacity());
+        }
+        Self::from_tracked_vec(v)
+    }
+
+    /// Like [`Self::from_vec`], but fails if the capacity of the `Vec<u8>` goes over the
+    /// [limits](crate::set_rustbuffer_limits), in which case it's freed.
+    ///
+    /// The calls wrapped by [`call_with_lowered_result`] report the error with the
+    /// `CALL_PAYLOAD_TOO_LARGE` status, see `uniffi/src/ffi/bufferlimits.rs`.
+    pub fn try_from_vec(v: Vec<u8>) -> Result<Self, PayloadTooLarge> {
+        if v.capacity() > 0 {
+            if let Err(err) = bufferlimits::track(v.capacity()) {
+                return_buffer(v);
+                return Err(err);
+            }
+        }
+        Ok(Self::from_tracked_vec(v))
+    }
+
+    // Convert a `Vec<u8>` already counted by `bufferlimits`.
+    fn from_tracked_vec(v: Vec<u8>) -> Self {
+        let (len, capacity) = (v.len(), v.capacity());
+        let data = match custom_allocator() {
+            Some(allocator) if capacity > 0 => {
+                let data = alloc_bytes(allocator, capacity);
+                unsafe { std::ptr::copy_nonoverlapping(v.as_ptr(), data, len) };
+                return_buffer(v);
+                data
+            }
+            _ => std::mem::ManuallyDrop::new(v).as_mut_ptr(),
+        };
+        Self::from_tracked_parts(data, len, capacity)
+    }
+
+    // Wrap bytes already counted by `bufferlimits`, allocated by the custom allocator, or by a
+    // `Vec<u8>` when there's none.
+    fn from_tracked_parts(data: *mut u8, len: usize, capacity: usize) -> Self {
+        let capacity = i32::try_from(capacity).expect("buffer capacity cannot fit into a i32.");
+        let len = i32::try_from(len).expect("buffer length cannot fit into a i32.");
+        if capacity > 0 {
+            leakdetection::track(AllocationKind::RustBuffer, data as u64, || {
+                format!("{capacity} bytes")
+            });
+        }
+        unsafe { Self::from_raw_parts(data, len, capacity) }
+    }
+
+    // Allocate a buffer of `len` bytes for the foreign-language code, starting with a copy of
+    // `init` and zero-filled after it, directly with the custom allocator when one is registered.
+    // Fails if it goes over the limits.
+    fn try_alloc(len: usize, init: &[u8]) -> Result<Self, PayloadTooLarge> {
+        let allocator = match custom_allocator() {
+            Some(allocator) if len > 0 => allocator,
+            _ => {
+                let mut v = take_buffer(len);
+                v.extend_from_slice(init);
+                v.resize(len, 0);
+                return Self::try_from_vec(v);
+            }
+        };
+        bufferlimits::track(len)?;
+        let data = alloc_bytes(allocator, len);
+        unsafe {
+            std::ptr::copy_nonoverlapping(init.as_ptr(), data, init.len());
+            std::ptr::write_bytes(data.add(init.len()), 0, len - init.len());
+        }
+        Ok(Self::from_tracked_parts(data, len, len))
+    }
+
+    // Check the invariants of the length and capacity, which may come from foreign-language
+    // code, and return them, or `None` for a null buffer.
+    fn checked_len_and_capacity(&self) -> Option<(usize, usize)> {
+        // Rust will never give us a null `data` pointer for a `Vec`, but
+        // foreign-language code can use it to cheaply pass an empty buffer.
+        if self.data.is_null() {
+            assert!(self.capacity == 0, "null RustBuffer had non-zero capacity");
+            assert!(self.len == 0, "null RustBuffer had non-zero length");
+            return None;
+        }
+        let capacity: usize = self
+            .capacity
+            .try_into()
+            .expect("buffer capacity negative or overflowed");
+        let len: usize = self
+            .len
+            .try_into()
+            .expect("buffer length negative or overflowed");
+        assert!(len <= capacity, "RustBuffer length exceeds capacity");
+        Some((len, capacity))
     }
 
     /// Converts this `RustBuffer` back into an owned `Vec<u8>`.
@@ -150,34 +264,119 @@ impl RustBuffer {
     /// Panics if called on an invalid struct obtained from foreign-language code,
     /// which does not respect the invairiants on `len` and `capacity`.
     pub fn destroy_into_vec(self) -> Vec<u8> {
-        // Rust will never give us a null `data` pointer for a `Vec`, but
-        // foreign-language code can use it to cheaply pass an empty buffer.
-        if self.data.is_null() {
-            assert!(self.capacity == 0, "null RustBuffer had non-zero capacity");
-            assert!(self.len == 0, "null RustBuffer had non-zero length");
-            vec![]
-        } else {
-            let capacity: usize = self
-                .capacity
-                .try_into()
-                .expect("buffer capacity negative or overflowed");
-            let len: usize = self
-                .len
-                .try_into()
-                .expect("buffer length negative or overflowed");
-            assert!(len <= capacity, "RustBuffer length exceeds capacity");
-            unsafe { Vec::from_raw_parts(self.data, len, capacity) }
+        let (v, capacity) = self.destroy_into_tracked_vec();
+        if capacity > 0 {
+            bufferlimits::untrack(capacity);
+        }
+        v
+    }
+
+    // Like `destroy_into_vec()`, but the capacity of the buffer, which is returned too, stays
+    // counted by `bufferlimits`. The `Vec<u8>` can have a larger one when it's taken from the
+    // buffer pool.
+    fn destroy_into_tracked_vec(self) -> (Vec<u8>, usize) {
+        let (len, capacity) = match self.checked_len_and_capacity() {
+            Some(parts) => parts,
+            None => return (vec![], 0),
+        };
+        if capacity == 0 {
+            return (unsafe { Vec::from_raw_parts(self.data, len, capacity) }, 0);
+        }
+        leakdetection::untrack(AllocationKind::RustBuffer, self.data as u64);
+        let v = match custom_allocator() {
+            Some(allocator) => {
+                let mut v = take_buffer(capacity);
+                v.extend_from_slice(unsafe { std::slice::from_raw_parts(self.data, len) });
+                unsafe { (allocator.free)(self.data, capacity) };
+                v
+            }
+            None => unsafe { Vec::from_raw_parts(self.data, len, capacity) },
+        };
+        (v, capacity)
+    }
+
+    // Reserve the capacity for at least `additional` more bytes, exactly unless it's there
+    // already. The new capacity is counted by `bufferlimits` before the buffer is reallocated, so
+    // it's left untouched, and still owned by the caller, when it goes over the limits.
+    fn try_reserve_exact(self, additional: usize) -> Result<Self, PayloadTooLarge> {
+        let (len, capacity) = self.checked_len_and_capacity().unwrap_or((0, 0));
+        let new_capacity = len.saturating_add(additional);
+        assert!(
+            new_capacity < i32::MAX as usize,
+            "RustBuffer requested size too large"
+        );
+        if new_capacity <= capacity {
+            return Ok(self);
+        }
+        bufferlimits::track_growth(capacity, new_capacity)?;
+        if let Some(allocator) = custom_allocator() {
+            // Move the bytes to a larger allocation, rather than through a `Vec<u8>`.
+            let data = alloc_bytes(allocator, new_capacity);
+            if capacity > 0 {
+                leakdetection::untrack(AllocationKind::RustBuffer, self.data as u64);
+                unsafe {
+                    std::ptr::copy_nonoverlapping(self.data, data, len);
+                    (allocator.free)(self.data, capacity);
+                }
+            }
+            return Ok(Self::from_tracked_parts(data, len, new_capacity));
+        }
+        let (mut v, _) = self.destroy_into_tracked_vec();
+        v.reserve_exact(new_capacity - v.len());
+        if v.capacity() != new_capacity {
+            bufferlimits::retrack(new_capacity, v.capacity());
         }
+        Ok(Self::from_tracked_vec(v))
+    }
+
+    /// Call `f` with the bytes of this `RustBuffer`, then reclaim its memory like
+    /// [`Self::destroy`].
+    ///
+    /// Unlike [`Self::destroy_into_vec`], the bytes aren't copied out of the memory allocated by
+    /// the [custom allocator](crate::set_rustbuffer_allocator).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called on an invalid struct obtained from foreign-language code,
+    /// which does not respect the invairiants on `len` and `capacity`.
+    pub(crate) fn read_and_destroy<R>(self, f: impl FnOnce(&[u8]) -> R) -> R {
+        if custom_allocator().is_none() {
+            let v = self.destroy_into_vec();
+            let result = f(&v);
+            return_buffer(v);
+            return result;
+        }
+        let result = match self.checked_len_and_capacity() {
+            Some((len, _)) => f(unsafe { std::slice::from_raw_parts(self.data, len) }),
+            None => f(&[]),
+        };
+        self.destroy();
+        result
     }
 
     /// Reclaim memory stored in this `RustBuffer`.
     ///
+    /// Its buffer is returned to the [buffer pool](crate::configure_buffer_pool) when it's
+    /// enabled, and freed otherwise, by the [custom allocator](crate::set_rustbuffer_allocator)
+    /// if one is registered.
+    ///
     /// # Panics
     ///
     /// Panics if called on an invalid struct obtained from foreign-language code,
     /// which does not respect the invairiants on `len` and `capacity`.
     pub fn destroy(self) {
-        drop(self.destroy_into_vec());
+        match custom_allocator() {
+            Some(allocator) => {
+                if let Some((_, capacity)) = self.checked_len_and_capacity() {
+                    if capacity > 0 {
+                        leakdetection::untrack(AllocationKind::RustBuffer, self.data as u64);
+                        bufferlimits::untrack(capacity);
+                        unsafe { (allocator.free)(self.data, capacity) };
+                    }
+                }
+            }
+            None => return_buffer(self.destroy_into_vec()),
+        }
     }
 }
 
@@ -187,28 +386,31 @@ impl Default for RustBuffer {
     }
 }
 
-// extern "C" functions for the RustBuffer functionality.
+// Functions for the RustBuffer functionality.
 //
 // These are used in two ways:
 //   1. Code that statically links to UniFFI can use these directly to handle RustBuffer
-//      allocation/destruction. The plan is to use this for the Firefox desktop JS bindings.
+//      allocation/destruction, through their unprefixed `extern "C"` symbols, see
+//      `ffi/unprefixed.rs`. The plan is to use this for the Firefox desktop JS bindings.
 //
 //   2. The scaffolding code re-exports these functions, prefixed with the component name and UDL
 //      hash  This creates a separate set of functions for each UniFFIed component, which is needed
 //      in the case where we create multiple dylib artifacts since each dylib will have its own
-//      allocator.
+//      allocator. The errors of the calls are serialized with the encoding of the component.
 
 /// This helper allocates a new byte buffer owned by the Rust code, and returns it
 /// to the foreign-language code as a `RustBuffer` struct. Callers must eventually
 /// free the resulting buffer, either by explicitly calling [`uniffi_rustbuffer_free`] defined
 /// below, or by passing ownership of the buffer back into Rust code.
-#[no_mangle]
-pub extern "C" fn uniffi_rustbuffer_alloc(
+pub fn uniffi_rustbuffer_alloc<const COMPACT: bool>(
     size: i32,
     call_status: &mut RustCallStatus,
 ) -> RustBuffer {
-    call_with_output(call_status, || {
-        RustBuffer::new_with_size(size.max(0) as usize)
+    call_with_lowered_result::<COMPACT, _, _>(call_status, || {
+        let size = size.max(0) as usize;
+        // The buffer is checked before it's allocated.
+        bufferlimits::check_size(size)?;
+        Ok(RustBuffer::try_alloc(size, &[])?)
     })
 }
 
@@ -220,26 +422,31 @@ pub extern "C" fn uniffi_rustbuffer_alloc(
 /// # Safety
 /// This function will dereference a provided pointer in order to copy bytes from it, so
 /// make sure the `ForeignBytes` struct contains a valid pointer and length.
-#[no_mangle]
-pub unsafe extern "C" fn uniffi_rustbuffer_from_bytes(
+pub unsafe fn uniffi_rustbuffer_from_bytes<const COMPACT: bool>(
     bytes: ForeignBytes,
     call_status: &mut RustCallStatus,
 ) -> RustBuffer {
-    call_with_output(call_status, || {
+    call_with_lowered_result::<COMPACT, _, _>(call_status, || {
         let bytes = bytes.as_slice();
-        RustBuffer::from_vec(bytes.to_vec())
+        // The buffer is checked before it's allocated.
+        bufferlimits::check_size(bytes.len())?;
+        Ok(RustBuffer::try_alloc(bytes.len(), bytes)?)
     })
 }
 
 /// Free a byte buffer that had previously been passed to the foreign language code.
 ///
+/// This returns it to the [buffer pool](crate::configure_buffer_pool) when it's enabled.
+///
 /// # Safety
 /// The argument *must* be a uniquely-owned `RustBuffer` previously obtained from a call
 /// into the Rust code that returned a buffer, or you'll risk freeing unowned memory or
 /// corrupting the allocator state.
-#[no_mangle]
-pub unsafe extern "C" fn uniffi_rustbuffer_free(buf: RustBuffer, call_status: &mut RustCallStatus) {
-    call_with_output(call_status, || RustBuffer::destroy(buf))
+pub unsafe fn uniffi_rustbuffer_free<const COMPACT: bool>(
+    buf: RustBuffer,
+    call_status: &mut RustCallStatus,
+) {
+    call_with_output::<COMPACT, _, _>(call_status, || RustBuffer::destroy(buf))
 }
 
 /// Reserve additional capacity in a byte buffer that had previously been passed to the
@@ -249,27 +456,27 @@ pub unsafe extern "C" fn uniffi_rustbuffer_free(buf: RustBuffer, call_status: &m
 /// obtained from a call into the Rust code that returned a buffer. Its underlying data pointer
 /// will be reallocated if necessary and returned in a new `RustBuffer` struct.
 ///
-/// The second argument must be the minimum number of *additional* bytes to reserve
-/// capacity for in the buffer; it is likely to reserve additional capacity in practice
-/// due to amortized growth strategy of Rust vectors.
+/// The second argument must be the number of *additional* bytes to reserve capacity for in the
+/// buffer, beyond its length. Exactly as many are reserved, unless the buffer has enough
+/// capacity already.
+///
+/// If the buffer would go over the [limits](crate::set_rustbuffer_limits), the call fails and the
+/// first argument is left untouched, still owned by the caller.
 ///
 /// # Safety
 /// The first argument *must* be a uniquely-owned `RustBuffer` previously obtained from a call
 /// into the Rust code that returned a buffer, or you'll risk freeing unowned memory or
 /// corrupting the allocator state.
-#[no_mangle]
-pub unsafe extern "C" fn uniffi_rustbuffer_reserve(
+pub unsafe fn uniffi_rustbuffer_reserve<const COMPACT: bool>(
     buf: RustBuffer,
     additional: i32,
     call_status: &mut RustCallStatus,
 ) -> RustBuffer {
-    call_with_output(call_status, || {
+    call_with_lowered_result::<COMPACT, _, _>(call_status, || {
         let additional: usize = additional
             .try_into()
             .expect("additional buffer length negative or overflowed");
-        let mut v = buf.destroy_into_vec();
-        v.reserve(additional);
-        RustBuffer::from_vec(v)
+        Ok(buf.try_reserve_exact(additional)?)
     })
 }
 
@@ -9,10 +9,24 @@
 //! It handles:
 //!    - Catching panics
 //!    - Adapting `Result<>` types into either a return value or an error
+//!    - Reporting internal errors, like arguments which can't be lifted
+//!    - Reporting the return values lowered into a `RustBuffer` over the
+//!      [limits](crate::set_rustbuffer_limits), see [`call_with_lowered_result`]
+//!
+//! ## `panic = "abort"`
+//!
+//! When the crate is built with `panic = "abort"`, panics can't be caught and abort the process of
+//! the foreign-language code.  Building it this way must be acknowledged with the `panic-abort`
+//! feature of uniffi, otherwise it fails to compile.  In this mode, the internal errors are
+//! reported with [`internal_error`] without panicking, so that they're still raised as exceptions
+//! by the foreign-language code; only the panics of the Rust code itself abort the process.
 
-use super::FfiDefault;
+use super::bufferlimits::{self, PayloadTooLarge};
+use super::{errorcontext, FfiDefault};
+use crate::panichook::PanicDetails;
 use crate::{FfiConverter, RustBuffer, RustBufferFfiConverter};
 use anyhow::Result;
+use std::cell::RefCell;
 use std::mem::MaybeUninit;
 use std::panic;
 
@@ -45,11 +59,30 @@ use std::panic;
 ///  - `CALL_SUCCESS` (0) for successful calls
 ///  - `CALL_ERROR` (1) for calls that returned an `Err` value
 ///  - `CALL_PANIC` (2) for calls that panicked
+///  - `CALL_PAYLOAD_TOO_LARGE` (3) for calls that allocated a `RustBuffer` over the
+///    [limits](crate::set_rustbuffer_limits)
 ///
 /// #### The `error_buf` field.
 ///
 /// - For `CALL_ERROR` this is a `RustBuffer` with the serialized error.  The consumer code is
 ///   responsible for freeing this `RustBuffer`.
+/// - For `CALL_PANIC` this is a `RustBuffer` with three serialized strings: the panic message,
+///   the `file:line:column` location of the panic, and its backtrace.  The location is empty
+///   unless the `log_panics` feature is enabled, and the backtrace is empty unless the
+///   `panic-backtraces` feature is enabled.  The
+///   buffer itself is empty if serializing them failed.  The consumer code is responsible for
+///   freeing this `RustBuffer`.
+/// - For `CALL_PAYLOAD_TOO_LARGE` this is a `RustBuffer` with the serialized message of the
+///   [`PayloadTooLarge`] error, then its size and limit as `u64`s.  The consumer code is
+///   responsible for freeing this `RustBuffer`.
+/// - In all cases, the serialized error may be followed by the key/value context which the Rust
+///   code attached to it, see [`ErrorContext`](crate::ErrorContext): the number of pairs then the
+///   serialized key and value strings of each pair.  Nothing follows the error without context.
+/// - For the errors declared with `[Error, Source]`, which the call lowered with
+///   [`lower_error`], the context, even without any pair, is then
+///   followed by the descriptions of the chain of sources of the error, if it has any, see
+///   [`record_error_sources`](crate::record_error_sources): their number then the serialized
+///   string of each source, the outermost first.
 #[repr(C)]
 pub struct RustCallStatus {
     pub code: i8,
@@ -76,32 +109,158 @@ impl Default for RustCallStatus {
 }
 
 #[allow(dead_code)]
-const CALL_SUCCESS: i8 = 0; // CALL_SUCCESS is set by the calling code
-const CALL_ERROR: i8 = 1;
-const CALL_PANIC: i8 = 2;
+pub(crate) const CALL_SUCCESS: i8 = 0; // CALL_SUCCESS is set by the calling code
+pub(crate) const CALL_ERROR: i8 = 1;
+pub(crate) const CALL_PANIC: i8 = 2;
+pub(crate) const CALL_PAYLOAD_TOO_LARGE: i8 = 3;
+
+thread_local! {
+    // The internal error of the current call with `panic = "abort"`, see `internal_error()`.
+    static INTERNAL_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Fail the current call with an internal error, like an argument which can't be lifted.
+///
+/// The error is raised by the foreign-language code like a panic.  With `panic = "unwind"`, this
+/// simply panics.  With `panic = "abort"`, where panicking would abort the process, the error is
+/// instead recorded for the call to report it, and the `FfiDefault` value is returned, which the
+/// callback of the call must return right away.
+pub fn internal_error<R: FfiDefault>(message: String) -> R {
+    if cfg!(panic = "abort") {
+        INTERNAL_ERROR.with(|error| *error.borrow_mut() = Some(message));
+        R::ffi_default()
+    } else {
+        panic!("{message}")
+    }
+}
+
+// Serialize the message of a panic followed by its details and context, see `RustCallStatus`.
+fn lower_panic<const COMPACT: bool>(
+    message: String,
+    details: PanicDetails,
+    context: errorcontext::CallContext,
+) -> RustBuffer {
+    let write_string = if COMPACT {
+        <String as FfiConverter>::write_compact
+    } else {
+        <String as FfiConverter>::write
+    };
+    let mut buf = Vec::new();
+    write_string(message, &mut buf);
+    write_string(details.location, &mut buf);
+    write_string(details.backtrace, &mut buf);
+    errorcontext::write_error_context::<COMPACT>(context, &mut buf);
+    RustBuffer::from_vec(buf)
+}
+
+// Serialize a buffer going over the limits followed by the context, see `RustCallStatus`.
+fn lower_payload_too_large<const COMPACT: bool>(
+    err: PayloadTooLarge,
+    context: errorcontext::CallContext,
+) -> RustBuffer {
+    let mut buf = Vec::new();
+    bufferlimits::write_payload_too_large::<COMPACT>(err, &mut buf);
+    errorcontext::write_error_context::<COMPACT>(context, &mut buf);
+    RustBuffer::from_vec(buf)
+}
 
 // A trait for errors that can be thrown to the FFI code
 //
 // This gets implemented in uniffi_bindgen/src/scaffolding/templates/ErrorTemplate.rs
-pub trait FfiError: RustBufferFfiConverter {}
+pub trait FfiError: RustBufferFfiConverter {
+    /// Record the chain of sources of the error returned by the current call, for the errors
+    /// declared with `[Source]`, see [`record_error_sources`](crate::record_error_sources).
+    fn record_sources(_obj: &Self::RustType) {}
+}
 
-// Generalized rust call handling function
-fn make_call<F, R>(out_status: &mut RustCallStatus, callback: F) -> R
+/// Lower the error returned by the current call, recording the chain of its sources.
+///
+/// The sources are only recorded for the error of the call itself, not for the errors lowered as
+/// the values of fields or sequences, which are written without them.
+pub fn lower_error<E: FfiError>(obj: E::RustType) -> RustBuffer {
+    E::record_sources(&obj);
+    <E as FfiConverter>::lower(obj)
+}
+
+/// Lower the error returned by the current call with the compact encoding, see [`lower_error`].
+pub fn lower_error_compact<E: FfiError>(obj: E::RustType) -> RustBuffer {
+    E::record_sources(&obj);
+    <E as FfiConverter>::lower_compact(obj)
+}
+
+/// The failure of a call wrapped by [`call_with_lowered_result`], other than a panic.
+pub enum CallError {
+    /// The Rust function returned an error, lowered into a `RustBuffer`.
+    Error(RustBuffer),
+    /// The return value was lowered into a `RustBuffer` over the
+    /// [limits](crate::set_rustbuffer_limits).
+    PayloadTooLarge(PayloadTooLarge),
+}
+
+impl From<RustBuffer> for CallError {
+    fn from(buf: RustBuffer) -> Self {
+        Self::Error(buf)
+    }
+}
+
+impl From<PayloadTooLarge> for CallError {
+    fn from(err: PayloadTooLarge) -> Self {
+        Self::PayloadTooLarge(err)
+    }
+}
+
+// Generalized rust call handling function, which serializes the errors with the encoding selected
+// by `COMPACT`, see `FfiConverter`.
+fn make_call<const COMPACT: bool, F, R>(out_status: &mut RustCallStatus, callback: F) -> R
 where
-    F: panic::UnwindSafe + FnOnce() -> Result<R, RustBuffer>,
+    F: panic::UnwindSafe + FnOnce() -> Result<R, CallError>,
     R: FfiDefault,
 {
+    let outer_context = errorcontext::enter_call();
+    // With `panic = "abort"`, there's no panic to catch.
+    #[cfg(panic = "abort")]
+    let result: std::thread::Result<_> = {
+        crate::panichook::ensure_setup();
+        let result = callback();
+        if let Some(message) = INTERNAL_ERROR.with(|error| error.borrow_mut().take()) {
+            // The callback returned right away with the default value, which owns nothing.
+            let context = errorcontext::exit_call(outer_context);
+            out_status.code = CALL_PANIC;
+            let buf = lower_panic::<COMPACT>(message, PanicDetails::default(), context);
+            unsafe {
+                // Unsafe because we're setting the `MaybeUninit` value, see above for safety
+                // invariants.
+                out_status.error_buf.as_mut_ptr().write(buf);
+            }
+            return R::ffi_default();
+        }
+        Ok(result)
+    };
+    #[cfg(not(panic = "abort"))]
     let result = panic::catch_unwind(|| {
         crate::panichook::ensure_setup();
         callback()
     });
+    let context = errorcontext::exit_call(outer_context);
     match result {
         // Happy path.  Note: no need to update out_status in this case because the calling code
         // initializes it to CALL_SUCCESS
         Ok(Ok(v)) => v,
         // Callback returned an Err.
-        Ok(Err(buf)) => {
+        Ok(Err(CallError::Error(buf))) => {
             out_status.code = CALL_ERROR;
+            let buf = errorcontext::append_error_context::<COMPACT>(buf, context);
+            unsafe {
+                // Unsafe because we're setting the `MaybeUninit` value, see above for safety
+                // invariants.
+                out_status.error_buf.as_mut_ptr().write(buf);
+            }
+            R::ffi_default()
+        }
+        // The return value went over the limits, see `RustBuffer::try_from_vec()`.
+        Ok(Err(CallError::PayloadTooLarge(err))) => {
+            out_status.code = CALL_PAYLOAD_TOO_LARGE;
+            let buf = lower_payload_too_large::<COMPACT>(err, context);
             unsafe {
                 // Unsafe because we're setting the `MaybeUninit` value, see above for safety
                 // invariants.
@@ -112,8 +271,10 @@ where
         // Callback panicked
         Err(cause) => {
             out_status.code = CALL_PANIC;
-            // Try to coerce the cause into a RustBuffer containing a String.  Since this code can
-            // panic, we need to use a second catch_unwind().
+            let details = crate::panichook::take_panic_details().unwrap_or_default();
+            // Try to coerce the cause into a RustBuffer containing a String, followed by the
+            // details of the panic.  Since this code can panic, we need to use a second
+            // catch_unwind().
             let message_result = panic::catch_unwind(panic::AssertUnwindSafe(move || {
                 // The documentation suggests that it will *usually* be a str or String.
                 let message = if let Some(s) = cause.downcast_ref::<&'static str>() {
@@ -124,7 +285,7 @@ where
                     "Unknown panic!".to_string()
                 };
                 log::error!("Caught a panic calling rust code: {:?}", message);
-                String::lower(message)
+                lower_panic::<COMPACT>(message, details, context)
             }));
             if let Ok(buf) = message_result {
                 unsafe {
@@ -150,12 +311,18 @@ where
 /// - If the function panics:
 ///     - `out_status.code` will be set to `CALL_PANIC`
 ///     - the return value is undefined
-pub fn call_with_output<F, R>(out_status: &mut RustCallStatus, callback: F) -> R
+///
+/// The errors are serialized with the encoding selected by `COMPACT`, the one of the component,
+/// see [`FfiConverter`].
+pub fn call_with_output<const COMPACT: bool, F, R>(
+    out_status: &mut RustCallStatus,
+    callback: F,
+) -> R
 where
     F: panic::UnwindSafe + FnOnce() -> R,
     R: FfiDefault,
 {
-    make_call(out_status, || Ok(callback()))
+    make_call::<COMPACT, _, _>(out_status, || Ok(callback()))
 }
 
 /// Wrap a rust function call that returns a `Result<_, RustBuffer>`
@@ -174,18 +341,43 @@ where
 /// - If the function panics:
 ///     - `out_status.code` will be set to `CALL_PANIC`
 ///     - the return value is undefined
-pub fn call_with_result<F, R>(out_status: &mut RustCallStatus, callback: F) -> R
+pub fn call_with_result<const COMPACT: bool, F, R>(
+    out_status: &mut RustCallStatus,
+    callback: F,
+) -> R
 where
     F: panic::UnwindSafe + FnOnce() -> Result<R, RustBuffer>,
     R: FfiDefault,
 {
-    make_call(out_status, callback)
+    make_call::<COMPACT, _, _>(out_status, || callback().map_err(CallError::Error))
+}
+
+/// Wrap a rust function call whose return value is lowered with
+/// [`FfiConverter::try_lower`](crate::FfiConverter::try_lower)
+///
+/// Like [`call_with_result`], and the `Err` of `callback` can also be the error of a `RustBuffer`
+/// over the [limits](crate::set_rustbuffer_limits), typically from `try_lower()`:
+///
+/// - If the function returns an `Err` with a [`PayloadTooLarge`] error:
+///     - `out_status.code` will be set to `CALL_PAYLOAD_TOO_LARGE`
+///     - `out_status.error_buf` will be set to a newly allocated `RustBuffer` containing the error,
+///       see [`RustCallStatus`].  The calling code is responsible for freeing the `RustBuffer`
+///     - the return value is undefined
+pub fn call_with_lowered_result<const COMPACT: bool, F, R>(
+    out_status: &mut RustCallStatus,
+    callback: F,
+) -> R
+where
+    F: panic::UnwindSafe + FnOnce() -> Result<R, CallError>,
+    R: FfiDefault,
+{
+    make_call::<COMPACT, _, _>(out_status, callback)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{FfiConverter, RustBufferFfiConverter};
+    use crate::{add_error_context, ErrorContext, FfiConverter, RustBufferFfiConverter};
 
     fn function(a: u8) -> i8 {
         match a {
@@ -194,6 +386,21 @@ mod test {
         }
     }
 
+    // Read the message and the location of a panic from its error buffer.
+    fn lift_panic(buf: RustBuffer) -> (String, String) {
+        let bytes = buf.destroy_into_vec();
+        let mut buf = bytes.as_slice();
+        let message = <String as FfiConverter>::try_read(&mut buf).unwrap();
+        let location = <String as FfiConverter>::try_read(&mut buf).unwrap();
+        let backtrace = <String as FfiConverter>::try_read(&mut buf).unwrap();
+        assert!(buf.is_empty());
+        assert_eq!(
+            backtrace.is_empty(),
+            cfg!(not(feature = "panic-backtraces"))
+        );
+        (message, location)
+    }
+
     fn create_call_status() -> RustCallStatus {
         RustCallStatus {
             code: 0,
@@ -204,18 +411,30 @@ mod test {
     #[test]
     fn test_call_with_output() {
         let mut status = create_call_status();
-        let return_value = call_with_output(&mut status, || function(0));
+        let return_value = call_with_output::<false, _, _>(&mut status, || function(0));
         assert_eq!(status.code, CALL_SUCCESS);
         assert_eq!(return_value, 100);
 
-        call_with_output(&mut status, || function(1));
+        call_with_output::<false, _, _>(&mut status, || function(1));
         assert_eq!(status.code, CALL_PANIC);
-        unsafe {
-            assert_eq!(
-                String::try_lift(status.error_buf.assume_init()).unwrap(),
-                "Unexpected value: 1"
-            );
-        }
+        let (message, location) = lift_panic(unsafe { status.error_buf.assume_init() });
+        assert_eq!(message, "Unexpected value: 1");
+        assert_eq!(
+            location.contains("rustcalls.rs:"),
+            cfg!(feature = "log_panics"),
+            "{location}"
+        );
+    }
+
+    #[test]
+    fn test_internal_error() {
+        let mut status = create_call_status();
+        call_with_output::<false, _, _>(&mut status, || {
+            internal_error::<u8>("Failed to convert arg 'a'".to_string())
+        });
+        assert_eq!(status.code, CALL_PANIC);
+        let (message, _) = lift_panic(unsafe { status.error_buf.assume_init() });
+        assert_eq!(message, "Failed to convert arg 'a'");
     }
 
     #[derive(Debug, PartialEq)]
@@ -230,7 +449,15 @@ mod test {
         }
 
         fn try_read(buf: &mut &[u8]) -> Result<Self> {
-            String::try_read(buf).map(TestError)
+            <String as FfiConverter>::try_read(buf).map(TestError)
+        }
+
+        fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+            <String as FfiConverter>::write_compact(obj.0, buf);
+        }
+
+        fn try_read_compact(buf: &mut &[u8]) -> Result<Self> {
+            <String as FfiConverter>::try_read_compact(buf).map(TestError)
         }
     }
 
@@ -247,32 +474,223 @@ mod test {
     #[test]
     fn test_call_with_result() {
         let mut status = create_call_status();
-        let return_value = call_with_result(&mut status, || {
+        let return_value = call_with_result::<false, _, _>(&mut status, || {
             function_with_result(0).map_err(TestError::lower)
         });
         assert_eq!(status.code, CALL_SUCCESS);
         assert_eq!(return_value, 100);
 
-        call_with_result(&mut status, || {
+        call_with_result::<false, _, _>(&mut status, || {
             function_with_result(1).map_err(TestError::lower)
         });
         assert_eq!(status.code, CALL_ERROR);
         unsafe {
             assert_eq!(
-                TestError::try_lift(status.error_buf.assume_init()).unwrap(),
+                <TestError as FfiConverter>::try_lift(status.error_buf.assume_init()).unwrap(),
                 TestError("Error".to_owned())
             );
         }
 
         let mut status = create_call_status();
-        call_with_result(&mut status, || {
+        call_with_result::<false, _, _>(&mut status, || {
             function_with_result(2).map_err(TestError::lower)
         });
         assert_eq!(status.code, CALL_PANIC);
+        let (message, _) = lift_panic(unsafe { status.error_buf.assume_init() });
+        assert_eq!(message, "Unexpected value: 2");
+    }
+
+    // Read the key/value context which follows an error.
+    fn read_context(buf: &mut &[u8]) -> Vec<(String, String)> {
+        let len = crate::read_len(buf).unwrap();
+        (0..len)
+            .map(|_| {
+                (
+                    <String as FfiConverter>::try_read(buf).unwrap(),
+                    <String as FfiConverter>::try_read(buf).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_error_context() {
+        let mut status = create_call_status();
+        call_with_result::<false, _, _>(&mut status, || {
+            add_error_context("step", "parsing");
+            function_with_result(1)
+                .error_context("a", 1)
+                .with_error_context("b", || "unused")
+                .map_err(TestError::lower)
+        });
+        assert_eq!(status.code, CALL_ERROR);
+        let bytes = unsafe { status.error_buf.assume_init() }.destroy_into_vec();
+        let mut buf = bytes.as_slice();
+        assert_eq!(
+            <TestError as FfiConverter>::try_read(&mut buf).unwrap().0,
+            "Error"
+        );
+        assert_eq!(
+            read_context(&mut buf),
+            vec![
+                ("step".to_owned(), "parsing".to_owned()),
+                ("a".to_owned(), "1".to_owned()),
+                ("b".to_owned(), "unused".to_owned()),
+            ]
+        );
+        assert!(buf.is_empty());
+
+        // The context of a successful call is discarded.
+        let mut status = create_call_status();
+        call_with_result::<false, _, _>(&mut status, || {
+            function_with_result(0)
+                .error_context("a", 0)
+                .map_err(TestError::lower)
+        });
+        add_error_context("outside", "a call");
+        call_with_result::<false, _, _>(&mut status, || {
+            function_with_result(1).map_err(TestError::lower)
+        });
+        assert_eq!(status.code, CALL_ERROR);
         unsafe {
             assert_eq!(
-                String::try_lift(status.error_buf.assume_init()).unwrap(),
-                "Unexpected value: 2"
+                <TestError as FfiConverter>::try_lift(status.error_buf.assume_init()).unwrap(),
+                TestError("Error".to_owned())
+            );
+        }
+
+        let mut status = create_call_status();
+        call_with_output::<false, _, _>(&mut status, || {
+            add_error_context("a", 2);
+            function(2)
+        });
+        assert_eq!(status.code, CALL_PANIC);
+        let bytes = unsafe { status.error_buf.assume_init() }.destroy_into_vec();
+        let mut buf = bytes.as_slice();
+        assert_eq!(
+            <String as FfiConverter>::try_read(&mut buf).unwrap(),
+            "Unexpected value: 2"
+        );
+        <String as FfiConverter>::try_read(&mut buf).unwrap();
+        <String as FfiConverter>::try_read(&mut buf).unwrap();
+        assert_eq!(
+            read_context(&mut buf),
+            vec![("a".to_owned(), "2".to_owned())]
+        );
+        assert!(buf.is_empty());
+    }
+
+    // An error with an optional source, like those wrapping the errors of other crates.
+    #[derive(Debug)]
+    struct SourcedError(String, Option<Box<SourcedError>>);
+
+    impl SourcedError {
+        fn new(message: &str, source: Option<SourcedError>) -> Self {
+            Self(message.to_owned(), source.map(Box::new))
+        }
+    }
+
+    impl std::fmt::Display for SourcedError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    impl std::error::Error for SourcedError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.1.as_ref().map(|source| source as _)
+        }
+    }
+
+    // Written as its message, like a flat error.
+    impl RustBufferFfiConverter for SourcedError {
+        type RustType = Self;
+
+        fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+            <String as FfiConverter>::write(obj.0, buf);
+        }
+
+        fn try_read(buf: &mut &[u8]) -> Result<Self> {
+            Ok(Self(<String as FfiConverter>::try_read(buf)?, None))
+        }
+
+        fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+            <String as FfiConverter>::write_compact(obj.0, buf);
+        }
+
+        fn try_read_compact(buf: &mut &[u8]) -> Result<Self> {
+            Ok(Self(<String as FfiConverter>::try_read_compact(buf)?, None))
+        }
+    }
+
+    impl FfiError for SourcedError {
+        fn record_sources(obj: &Self) {
+            crate::record_error_sources(obj);
+        }
+    }
+
+    #[test]
+    fn test_error_sources() {
+        let mut status = create_call_status();
+        call_with_result::<false, _, _>(&mut status, || {
+            let error = SourcedError::new(
+                "top",
+                Some(SourcedError::new(
+                    "middle",
+                    Some(SourcedError::new("bottom", None)),
+                )),
+            );
+            Err::<i8, _>(lower_error::<SourcedError>(error))
+        });
+        assert_eq!(status.code, CALL_ERROR);
+        let bytes = unsafe { status.error_buf.assume_init() }.destroy_into_vec();
+        let mut buf = bytes.as_slice();
+        assert_eq!(
+            <SourcedError as FfiConverter>::try_read(&mut buf)
+                .unwrap()
+                .0,
+            "top"
+        );
+        // The context is written, without any pair, before the sources.
+        assert_eq!(read_context(&mut buf), vec![]);
+        let len = crate::read_len(&mut buf).unwrap();
+        let sources: Vec<String> = (0..len)
+            .map(|_| <String as FfiConverter>::try_read(&mut buf).unwrap())
+            .collect();
+        assert_eq!(sources, vec!["middle", "bottom"]);
+        assert!(buf.is_empty());
+
+        // An error without sources is followed by nothing.
+        let mut status = create_call_status();
+        call_with_result::<false, _, _>(&mut status, || {
+            Err::<i8, _>(lower_error::<SourcedError>(SourcedError::new(
+                "alone", None,
+            )))
+        });
+        unsafe {
+            assert_eq!(
+                <SourcedError as FfiConverter>::try_lift(status.error_buf.assume_init())
+                    .unwrap()
+                    .0,
+                "alone"
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_errors_send_no_sources() {
+        // The sources of the errors written as values aren't sent with the error of the call.
+        let mut status = create_call_status();
+        call_with_result::<false, _, _>(&mut status, || {
+            let nested = SourcedError::new("nested", Some(SourcedError::new("source", None)));
+            <Vec<SourcedError> as FfiConverter>::lower(vec![nested]);
+            function_with_result(1).map_err(lower_error::<TestError>)
+        });
+        assert_eq!(status.code, CALL_ERROR);
+        unsafe {
+            assert_eq!(
+                <TestError as FfiConverter>::try_lift(status.error_buf.assume_init()).unwrap(),
+                TestError("Error".to_owned())
             );
         }
     }
@@ -0,0 +1,182 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Rust-owned memory read in place by the foreign-language code
+//!
+//! Values are usually serialized into a `RustBuffer`, which the foreign-language code copies into
+//! its own types.  For very large payloads, like datasets of hundreds of megabytes, Rust functions
+//! can instead return a [`SharedSlice`], the `shared_slice` type of the UDL.  The foreign-language
+//! code gets a handle to it, reads its bytes in place through a read-only view (a `ByteBuffer` in
+//! Kotlin, a `memoryview` in Python, a `Data` in Swift), and releases it explicitly, which drops
+//! it on the Rust side.
+//!
+//! Like iterators, shared slices can only be returned by functions and methods.  The scaffolding
+//! provides the `ffi_shared_slice_data`, `ffi_shared_slice_len` and `ffi_shared_slice_free` FFI
+//! functions of the component, which call the functions of this module.
+
+use super::{HandleError, OBJECT_HANDLES};
+use crate::{FfiConverter, Result};
+use anyhow::bail;
+use bytes::BufMut;
+use std::os::raw::c_void;
+use std::sync::Arc;
+
+/// A region of read-only memory owned by Rust, which the foreign-language code reads without
+/// copying it.
+///
+/// It's made from any owner of bytes, like a `Vec<u8>`, a `Box<[u8]>`, an `Arc<[u8]>` or a
+/// memory-mapped file, which is kept alive until the foreign-language code releases the slice.
+/// Cloning it shares the owner.
+#[derive(Clone)]
+pub struct SharedSlice {
+    owner: Arc<dyn AsRef<[u8]> + Send + Sync>,
+}
+
+impl SharedSlice {
+    pub fn new(owner: impl AsRef<[u8]> + Send + Sync + 'static) -> Self {
+        Self {
+            owner: Arc::new(owner),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        (*self.owner).as_ref()
+    }
+}
+
+impl std::ops::Deref for SharedSlice {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for SharedSlice {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl From<Vec<u8>> for SharedSlice {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl std::fmt::Debug for SharedSlice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSlice")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Support for returning shared slices via the FFI.
+///
+/// Shared slices are passed like objects, as handles to them in [`OBJECT_HANDLES`], which are
+/// checked whenever they're used.  They can only be returned by functions and methods, so they're
+/// never lifted or read.
+unsafe impl FfiConverter for SharedSlice {
+    type RustType = Self;
+    type FfiType = u64;
+
+    /// Transfer the ownership of the slice to the foreign-language code, as a new handle, which
+    /// it's responsible for freeing by calling the `ffi_shared_slice_free` FFI function.
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        OBJECT_HANDLES.insert(Arc::new(obj))
+    }
+
+    fn try_lift(_v: Self::FfiType) -> Result<Self::RustType> {
+        bail!("shared slices can only be returned by Rust")
+    }
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_u64(<Self as FfiConverter>::lower(obj));
+    }
+
+    fn try_read(_buf: &mut &[u8]) -> Result<Self::RustType> {
+        bail!("shared slices can only be returned by Rust")
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Self as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as FfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_read(buf)
+    }
+}
+
+/// The address of the bytes of a lowered shared slice, which stays valid until it's freed.
+///
+/// Fails if the handle doesn't refer to a live shared slice.
+pub fn shared_slice_data(handle: u64) -> Result<*const c_void, HandleError> {
+    // The map keeps its own reference to the slice, so the bytes outlive this one.
+    Ok(OBJECT_HANDLES.get::<SharedSlice>(handle)?.as_ptr() as *const c_void)
+}
+
+/// The number of bytes of a lowered shared slice.
+///
+/// Fails if the handle doesn't refer to a live shared slice.
+pub fn shared_slice_len(handle: u64) -> Result<u64, HandleError> {
+    Ok(OBJECT_HANDLES.get::<SharedSlice>(handle)?.len() as u64)
+}
+
+/// Drop a lowered shared slice, after which the foreign-language code can't read its bytes.
+///
+/// Fails if the handle doesn't refer to a live shared slice, like when it was already freed.
+pub fn shared_slice_free(handle: u64) -> Result<(), HandleError> {
+    OBJECT_HANDLES.remove::<SharedSlice>(handle).map(drop)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bytes::Buf;
+
+    #[test]
+    fn test_bytes_are_read_in_place() {
+        let owner: Arc<[u8]> = Arc::from(vec![1u8, 2, 3]);
+        let slice = SharedSlice::new(owner.clone());
+        assert_eq!(Arc::strong_count(&owner), 2);
+
+        let handle = <SharedSlice as FfiConverter>::lower(slice);
+        assert_eq!(
+            shared_slice_data(handle).unwrap(),
+            owner.as_ptr() as *const c_void
+        );
+        assert_eq!(shared_slice_len(handle).unwrap(), 3);
+        shared_slice_free(handle).unwrap();
+        assert_eq!(Arc::strong_count(&owner), 1);
+
+        // The handle can't be used anymore, nor freed twice.
+        assert_eq!(shared_slice_len(handle), Err(HandleError::Stale(handle)));
+        assert_eq!(shared_slice_free(handle), Err(HandleError::Stale(handle)));
+    }
+
+    #[test]
+    fn test_shared_slices_are_only_returned() {
+        let slice = SharedSlice::from(vec![1u8, 2, 3]);
+        assert_eq!(&*slice.clone(), &[1, 2, 3]);
+        assert!(<SharedSlice as FfiConverter>::try_lift(0).is_err());
+        let mut buf = Vec::new();
+        <SharedSlice as FfiConverter>::write(slice, &mut buf);
+        let handle = (&buf[..]).get_u64();
+        // The handles of the objects aren't the ones of shared slices.
+        let other = OBJECT_HANDLES.insert(Arc::new(1u32));
+        assert_eq!(shared_slice_len(other), Err(HandleError::WrongType(other)));
+        OBJECT_HANDLES.remove::<u32>(other).unwrap();
+        shared_slice_free(handle).unwrap();
+        assert!(<SharedSlice as FfiConverter>::try_read(&mut &buf[..]).is_err());
+    }
+}
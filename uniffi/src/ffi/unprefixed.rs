@@ -0,0 +1,79 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The unprefixed `extern "C"` symbols of the FFI functions of the runtime, for the code that
+//! statically links to UniFFI rather than going through the functions which the scaffolding of
+//! each component re-exports with its prefix.
+//!
+//! They serialize the errors of the calls with the standard encoding, since they don't belong to
+//! any component. They're not exported with the `prefixed-symbols-only` feature.
+
+use super::{ForeignBytes, RustBuffer, RustBufferAllocator, RustCallStatus};
+
+/// See [`super::uniffi_rustbuffer_alloc`].
+#[no_mangle]
+pub extern "C" fn uniffi_rustbuffer_alloc(
+    size: i32,
+    call_status: &mut RustCallStatus,
+) -> RustBuffer {
+    super::uniffi_rustbuffer_alloc::<false>(size, call_status)
+}
+
+/// See [`super::uniffi_rustbuffer_from_bytes`].
+///
+/// # Safety
+/// Like [`super::uniffi_rustbuffer_from_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_rustbuffer_from_bytes(
+    bytes: ForeignBytes,
+    call_status: &mut RustCallStatus,
+) -> RustBuffer {
+    super::uniffi_rustbuffer_from_bytes::<false>(bytes, call_status)
+}
+
+/// See [`super::uniffi_rustbuffer_free`].
+///
+/// # Safety
+/// Like [`super::uniffi_rustbuffer_free`].
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_rustbuffer_free(buf: RustBuffer, call_status: &mut RustCallStatus) {
+    super::uniffi_rustbuffer_free::<false>(buf, call_status)
+}
+
+/// See [`super::uniffi_rustbuffer_reserve`].
+///
+/// # Safety
+/// Like [`super::uniffi_rustbuffer_reserve`].
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_rustbuffer_reserve(
+    buf: RustBuffer,
+    additional: i32,
+    call_status: &mut RustCallStatus,
+) -> RustBuffer {
+    super::uniffi_rustbuffer_reserve::<false>(buf, additional, call_status)
+}
+
+/// See [`super::uniffi_rustbuffer_stats`].
+#[no_mangle]
+pub extern "C" fn uniffi_rustbuffer_stats(call_status: &mut RustCallStatus) -> RustBuffer {
+    super::uniffi_rustbuffer_stats::<false>(call_status)
+}
+
+/// See [`super::uniffi_rustbuffer_set_allocator`].
+///
+/// # Safety
+/// Like [`super::uniffi_rustbuffer_set_allocator`].
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_rustbuffer_set_allocator(
+    allocator: RustBufferAllocator,
+    call_status: &mut RustCallStatus,
+) -> i8 {
+    super::uniffi_rustbuffer_set_allocator::<false>(allocator, call_status)
+}
+
+/// See [`super::uniffi_dump_live_allocations`].
+#[no_mangle]
+pub extern "C" fn uniffi_dump_live_allocations(call_status: &mut RustCallStatus) -> RustBuffer {
+    super::uniffi_dump_live_allocations::<false>(call_status)
+}
@@ -34,7 +34,8 @@ use anyhow::bail;
 use bytes::buf::{Buf, BufMut};
 use paste::paste;
 use std::{
-    collections::HashMap,
+    any::TypeId,
+    collections::{BTreeSet, HashMap, HashSet},
     convert::TryFrom,
     time::{Duration, SystemTime},
 };
@@ -45,16 +46,36 @@ pub use anyhow::Result;
 pub mod ffi;
 pub use ffi::*;
 
+// Panics can't be caught with `panic = "abort"`, so they abort the process of the foreign-language
+// code, which must be acknowledged, see `ffi/rustcalls.rs`.
+#[cfg(all(panic = "abort", not(feature = "panic-abort")))]
+compile_error!(
+    "uniffi can't catch panics with `panic = \"abort\"`, so they abort the process of the \
+     foreign-language code. Enable the `panic-abort` feature of uniffi to build it this way."
+);
+
 // Re-export the libs that we use in the generated code,
 // so the consumer doesn't have to depend on them directly.
 pub mod deps {
     pub use anyhow;
     pub use bytes;
+    #[cfg(feature = "chrono")]
+    pub use chrono;
     pub use log;
+    #[cfg(feature = "num-bigint")]
+    pub use num_bigint;
+    #[cfg(feature = "rust_decimal")]
+    pub use rust_decimal;
+    #[cfg(feature = "serde_json")]
+    pub use serde_json;
     pub use static_assertions;
+    #[cfg(feature = "url")]
+    pub use url;
+    #[cfg(feature = "uuid")]
+    pub use uuid;
 }
 
-pub use uniffi_macros::{export, Enum, Object, Record};
+pub use uniffi_macros::{export, init, shutdown, Enum, Object, Record};
 
 mod panichook;
 
@@ -146,7 +167,26 @@ macro_rules! assert_compatible_version {
 ///
 /// In general, you should not need to implement this trait by hand, and should instead rely on
 /// implementations generated from your component UDL via the `uniffi-bindgen scaffolding` command.
-
+///
+/// ## Encoding
+///
+/// Each component serializes the values in its `RustBuffer`s with one of two encodings, which it
+/// chooses with the `compact_encoding` setting of its `uniffi.toml`, and which its bindings use
+/// too. The methods of this trait use the standard encoding, and the ones with a `_compact` suffix
+/// the compact encoding, in which the integers wider than a byte are written as varints (LEB128),
+/// zigzag-encoded if they're signed, and so are the lengths of the strings, sequences and maps,
+/// and the variants of the enums. The strings of a `Vec` share their length when they all have the
+/// same one, see [`write_strings_compact`]. Every other value is written as usual.
+///
+/// The `_compact` methods are required, rather than defaulting to the standard ones, because a
+/// converter which forgot them would silently write values with the standard encoding into a
+/// buffer of the compact one, which the bindings would then misread. The converters of the types
+/// whose serialized form is the same in both encodings, including the ones written by hand,
+/// implement them by calling the standard methods. The converters of the generic
+/// types pass the encoding on to the ones of their items. The converters of the records, enums,
+/// errors and custom types of a component write their fields with the encoding of the component in
+/// both methods, so that the values of the types of another component, used as external types, are
+/// serialized with the encoding of that component.
 pub unsafe trait FfiConverter: Sized {
     /// The type used in Rust code.
     ///
@@ -176,6 +216,16 @@ pub unsafe trait FfiConverter: Sized {
     /// in turn to the foreign language code, e.g. by boxing the value and passing a pointer.
     fn lower(obj: Self::RustType) -> Self::FfiType;
 
+    /// Lower a rust value returned by a call, failing if it's lowered into a `RustBuffer` over
+    /// the [limits](crate::set_rustbuffer_limits).
+    ///
+    /// The scaffolding lowers the return values with this method, in the calls wrapped by
+    /// [`call_with_lowered_result`], which report the error to the foreign language code. The
+    /// types lowered into a `RustBuffer` override it, the other ones lower with [`Self::lower`].
+    fn try_lower(obj: Self::RustType) -> std::result::Result<Self::FfiType, PayloadTooLarge> {
+        Ok(Self::lower(obj))
+    }
+
     /// Lift a rust value of the target type, from an FFI value of type Self::FfiType.
     ///
     /// This trait method is used for receiving data from the foreign language code in rust,
@@ -209,6 +259,25 @@ pub unsafe trait FfiConverter: Sized {
     /// because we want to be able to advance the start of the slice after reading an item
     /// from it (but will not mutate the actual contents of the slice).
     fn try_read(buf: &mut &[u8]) -> Result<Self::RustType>;
+
+    /// Lower a rust value with the compact encoding, see [`Self::lower`].
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType;
+
+    /// Lower a rust value returned by a call with the compact encoding, see [`Self::try_lower`].
+    fn try_lower_compact(
+        obj: Self::RustType,
+    ) -> std::result::Result<Self::FfiType, PayloadTooLarge> {
+        Ok(Self::lower_compact(obj))
+    }
+
+    /// Lift a rust value with the compact encoding, see [`Self::try_lift`].
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType>;
+
+    /// Write a rust value into a buffer with the compact encoding, see [`Self::write`].
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>);
+
+    /// Read a rust value from a buffer with the compact encoding, see [`Self::try_read`].
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType>;
 }
 
 /// A helper function to ensure we don't read past the end of a buffer.
@@ -226,6 +295,135 @@ pub fn check_remaining(buf: &[u8], num_bytes: usize) -> Result<()> {
     Ok(())
 }
 
+/// Write a varint (LEB128), as used by the compact encoding.
+pub fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+/// Read a varint (LEB128), as used by the compact encoding.
+pub fn read_varint(buf: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        check_remaining(buf, 1)?;
+        let byte = buf.get_u8();
+        // Only the lowest bit of the 10th byte fits in a `u64`.
+        if shift == 63 && byte > 1 {
+            bail!("varint overflows a u64");
+        }
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("varint longer than 10 bytes")
+}
+
+/// Write the length of a string, sequence or map, as an `i32`.
+pub fn write_len(buf: &mut Vec<u8>, len: usize) {
+    // TODO: would be nice not to panic here :-/
+    let len = i32::try_from(len).unwrap(); // We limit lengths to i32::MAX
+    buf.put_i32(len);
+}
+
+/// Write the length of a string, sequence or map with the compact encoding, as a varint.
+pub fn write_len_compact(buf: &mut Vec<u8>, len: usize) {
+    let len = i32::try_from(len).unwrap(); // We limit lengths to i32::MAX
+    write_varint(buf, len as u64);
+}
+
+/// Read the length of a string, sequence or map, see [`write_len`].
+pub fn read_len(buf: &mut &[u8]) -> Result<usize> {
+    check_remaining(buf, 4)?;
+    Ok(usize::try_from(buf.get_i32())?)
+}
+
+/// Read the length of a string, sequence or map, see [`write_len_compact`].
+pub fn read_len_compact(buf: &mut &[u8]) -> Result<usize> {
+    Ok(usize::try_from(i32::try_from(read_varint(buf)?)?)?)
+}
+
+/// Write the variant of an enum, by its position starting from 1, as an `i32`.
+pub fn write_variant(buf: &mut Vec<u8>, index: i32) {
+    buf.put_i32(index);
+}
+
+/// Write the variant of an enum with the compact encoding, as a varint.
+pub fn write_variant_compact(buf: &mut Vec<u8>, index: i32) {
+    write_varint(buf, index as u64);
+}
+
+/// Read the variant of an enum, see [`write_variant`].
+pub fn read_variant(buf: &mut &[u8]) -> Result<i32> {
+    check_remaining(buf, 4)?;
+    Ok(buf.get_i32())
+}
+
+/// Read the variant of an enum, see [`write_variant_compact`].
+pub fn read_variant_compact(buf: &mut &[u8]) -> Result<i32> {
+    Ok(i32::try_from(read_varint(buf)?)?)
+}
+
+// The compact encoding of the numeric primitives, see `FfiConverter`.
+trait CompactNum: Sized {
+    fn put_compact(self, buf: &mut Vec<u8>);
+    fn try_get_compact(buf: &mut &[u8]) -> Result<Self>;
+}
+
+macro_rules! impl_compact_num {
+    (unsigned: $($U:ty),*; signed: $($S:ty),*; fixed: $($F:ty),*) => {
+        $(
+            impl CompactNum for $U {
+                fn put_compact(self, buf: &mut Vec<u8>) {
+                    write_varint(buf, u64::from(self));
+                }
+
+                fn try_get_compact(buf: &mut &[u8]) -> Result<Self> {
+                    Ok(<$U>::try_from(read_varint(buf)?)?)
+                }
+            }
+        )*
+        $(
+            impl CompactNum for $S {
+                // Zigzag-encoded, so that the small negative numbers are short too.
+                fn put_compact(self, buf: &mut Vec<u8>) {
+                    let value = i64::from(self);
+                    write_varint(buf, ((value << 1) ^ (value >> 63)) as u64);
+                }
+
+                fn try_get_compact(buf: &mut &[u8]) -> Result<Self> {
+                    let value = read_varint(buf)?;
+                    let value = ((value >> 1) as i64) ^ -((value & 1) as i64);
+                    Ok(<$S>::try_from(value)?)
+                }
+            }
+        )*
+        $(
+            paste! {
+                impl CompactNum for $F {
+                    fn put_compact(self, buf: &mut Vec<u8>) {
+                        buf.[<put_ $F>](self);
+                    }
+
+                    fn try_get_compact(buf: &mut &[u8]) -> Result<Self> {
+                        check_remaining(buf, std::mem::size_of::<$F>())?;
+                        Ok(buf.[<get_ $F>]())
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_compact_num! {
+    unsigned: u16, u32, u64;
+    signed: i16, i32, i64;
+    fixed: i8, u8, f32, f64
+}
+
 /// Blanket implementation of `FfiConverter` for numeric primitives.
 ///
 /// Numeric primitives have a straightforward mapping into C-compatible numeric types,
@@ -255,6 +453,22 @@ macro_rules! impl_via_ffi_for_num_primitive {
                             check_remaining(buf, std::mem::size_of::<$T>())?;
                             Ok(buf.[<get_ $T>]())
                         }
+
+                        fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+                            obj
+                        }
+
+                        fn try_lift_compact(v: Self::FfiType) -> Result<Self> {
+                            Ok(v)
+                        }
+
+                        fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+                            obj.put_compact(buf);
+                        }
+
+                        fn try_read_compact(buf: &mut &[u8]) -> Result<Self> {
+                            <$T as CompactNum>::try_get_compact(buf)
+                        }
                     }
                 }
             )*
@@ -293,6 +507,69 @@ unsafe impl FfiConverter for bool {
         check_remaining(buf, 1)?;
         <bool as FfiConverter>::try_lift(buf.get_i8())
     }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <bool as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <bool as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <bool as FfiConverter>::write(obj, buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <bool as FfiConverter>::try_read(buf)
+    }
+}
+
+/// Support for passing Unicode scalar values via the FFI.
+///
+/// A `char` is passed as its `u32` value. Lifting checks that the value is a Unicode
+/// scalar value, which the foreign values may not be, e.g. UTF-16 surrogates.
+///
+/// Chars are serialized as 4 bytes in both encodings, they're not integers on the foreign side.
+unsafe impl FfiConverter for char {
+    type RustType = Self;
+    type FfiType = u32;
+
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        u32::from(obj)
+    }
+
+    fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
+        match char::from_u32(v) {
+            Some(c) => Ok(c),
+            None => bail!("{v:#x} is not a Unicode scalar value"),
+        }
+    }
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_u32(<char as FfiConverter>::lower(obj));
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        check_remaining(buf, 4)?;
+        <char as FfiConverter>::try_lift(buf.get_u32())
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <char as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <char as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <char as FfiConverter>::write(obj, buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <char as FfiConverter>::try_read(buf)
+    }
 }
 
 /// Support for passing Strings via the FFI.
@@ -303,9 +580,8 @@ unsafe impl FfiConverter for bool {
 /// *must* be a valid `RustBuffer` and it *must* contain valid utf-8 data (in other
 /// words, it *must* be a `Vec<u8>` suitable for use as an actual rust `String`).
 ///
-/// When serialized in a buffer, strings are represented as a i32 byte length
-/// followed by utf8-encoded bytes. (It's a signed integer because unsigned types are
-/// currently experimental in Kotlin).
+/// When serialized in a buffer, strings are represented as their byte length, see
+/// [`write_len`], followed by utf8-encoded bytes.
 unsafe impl FfiConverter for String {
     type RustType = Self;
     type FfiType = RustBuffer;
@@ -317,6 +593,10 @@ unsafe impl FfiConverter for String {
         RustBuffer::from_vec(obj.into_bytes())
     }
 
+    fn try_lower(obj: Self::RustType) -> std::result::Result<Self::FfiType, PayloadTooLarge> {
+        RustBuffer::try_from_vec(obj.into_bytes())
+    }
+
     // The argument here *must* be a uniquely-owned `RustBuffer` previously obtained
     // from `lower` above, and hence must be the bytes of a valid rust string.
     fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
@@ -331,23 +611,126 @@ unsafe impl FfiConverter for String {
 
     fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
         // N.B. `len()` gives us the length in bytes, not in chars or graphemes.
-        // TODO: it would be nice not to panic here.
-        let len = i32::try_from(obj.len()).unwrap();
-        buf.put_i32(len); // We limit strings to u32::MAX bytes
+        write_len(buf, obj.len());
         buf.put(obj.as_bytes());
     }
 
     fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
-        check_remaining(buf, 4)?;
-        let len = usize::try_from(buf.get_i32())?;
-        check_remaining(buf, len)?;
-        // N.B: In the general case `Buf::chunk()` may return partial data.
-        // But in the specific case of `<&[u8] as Buf>` it returns the full slice,
-        // so there is no risk of having less than `len` bytes available here.
-        let bytes = &buf.chunk()[..len];
-        let res = String::from_utf8(bytes.to_vec())?;
-        buf.advance(len);
-        Ok(res)
+        let len = read_len(buf)?;
+        read_utf8(buf, len)
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <String as FfiConverter>::lower(obj)
+    }
+
+    fn try_lower_compact(
+        obj: Self::RustType,
+    ) -> std::result::Result<Self::FfiType, PayloadTooLarge> {
+        <String as FfiConverter>::try_lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <String as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_len_compact(buf, obj.len());
+        buf.put(obj.as_bytes());
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        let len = read_len_compact(buf)?;
+        read_utf8(buf, len)
+    }
+}
+
+// Read the `len` utf8-encoded bytes of a string, whose length was read already.
+fn read_utf8(buf: &mut &[u8], len: usize) -> Result<String> {
+    check_remaining(buf, len)?;
+    // N.B: In the general case `Buf::chunk()` may return partial data.
+    // But in the specific case of `<&[u8] as Buf>` it returns the full slice,
+    // so there is no risk of having less than `len` bytes available here.
+    let bytes = &buf.chunk()[..len];
+    let res = String::from_utf8(bytes.to_vec())?;
+    buf.advance(len);
+    Ok(res)
+}
+
+/// Support for passing strings as UTF-16, for the components with the `[Utf16Strings]` attribute.
+///
+/// Kotlin and Swift strings are UTF-16 already, so it's cheaper to convert them once on the Rust
+/// side than to transcode them to UTF-8 and back on every call. The strings which are lowered or
+/// lifted on their own are passed as a `RustBuffer` of little-endian UTF-16 code units, without a
+/// length prefix. The strings which are serialized inside other values are still written as
+/// UTF-8, like with the implementation for `String`.
+pub struct Utf16String;
+
+impl Utf16String {
+    fn encode(obj: String) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(obj.len() * 2);
+        for unit in obj.encode_utf16() {
+            bytes.put_u16_le(unit);
+        }
+        bytes
+    }
+}
+
+unsafe impl FfiConverter for Utf16String {
+    type RustType = String;
+    type FfiType = RustBuffer;
+
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        RustBuffer::from_vec(Self::encode(obj))
+    }
+
+    fn try_lower(obj: Self::RustType) -> std::result::Result<Self::FfiType, PayloadTooLarge> {
+        RustBuffer::try_from_vec(Self::encode(obj))
+    }
+
+    // Unlike UTF-8 strings, the buffer is checked, since the foreign strings may hold unpaired
+    // surrogates.
+    fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
+        v.read_and_destroy(|bytes| {
+            if bytes.len() % 2 != 0 {
+                bail!("odd number of bytes in a UTF-16 string");
+            }
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect();
+            Ok(String::from_utf16(&units)?)
+        })
+    }
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write(obj, buf);
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <String as FfiConverter>::try_read(buf)
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Utf16String as FfiConverter>::lower(obj)
+    }
+
+    fn try_lower_compact(
+        obj: Self::RustType,
+    ) -> std::result::Result<Self::FfiType, PayloadTooLarge> {
+        <Utf16String as FfiConverter>::try_lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <Utf16String as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write_compact(obj, buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <String as FfiConverter>::try_read_compact(buf)
     }
 }
 
@@ -356,10 +739,37 @@ unsafe impl FfiConverter for String {
 /// For complex types where it's too fiddly or too unsafe to convert them into a special-purpose
 /// C-compatible value, you can use this trait to implement `lower()` in terms of `write()` and
 /// `lift` in terms of `read()`.
+///
+/// Like with [`FfiConverter`], the methods with a `_compact` suffix use the compact encoding.
 pub trait RustBufferFfiConverter: Sized {
     type RustType;
     fn write(obj: Self::RustType, buf: &mut Vec<u8>);
     fn try_read(buf: &mut &[u8]) -> Result<Self::RustType>;
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>);
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType>;
+
+    /// Lower a value into the bytes of the `RustBuffer` passed over the FFI, by serializing it
+    /// with `write`, the method of the encoding, unless overridden.
+    fn lower_into_bytes(obj: Self::RustType, write: fn(Self::RustType, &mut Vec<u8>)) -> Vec<u8> {
+        let mut buf = bufferpool::take_buffer(0);
+        write(obj, &mut buf);
+        buf
+    }
+
+    /// Lift a value from the `RustBuffer` passed over the FFI, by deserializing it with
+    /// `try_read`, the method of the encoding, unless overridden.
+    fn try_lift_from_rust_buffer(
+        v: RustBuffer,
+        try_read: fn(&mut &[u8]) -> Result<Self::RustType>,
+    ) -> Result<Self::RustType> {
+        v.read_and_destroy(|mut buf| {
+            let value = try_read(&mut buf)?;
+            if buf.remaining() != 0 {
+                bail!("junk data left in buffer after lifting")
+            }
+            Ok(value)
+        })
+    }
 }
 
 unsafe impl<T: RustBufferFfiConverter> FfiConverter for T {
@@ -367,19 +777,15 @@ unsafe impl<T: RustBufferFfiConverter> FfiConverter for T {
     type FfiType = RustBuffer;
 
     fn lower(obj: Self::RustType) -> RustBuffer {
-        let mut buf = Vec::new();
-        <T as RustBufferFfiConverter>::write(obj, &mut buf);
-        RustBuffer::from_vec(buf)
+        RustBuffer::from_vec(T::lower_into_bytes(obj, T::write))
+    }
+
+    fn try_lower(obj: Self::RustType) -> std::result::Result<RustBuffer, PayloadTooLarge> {
+        RustBuffer::try_from_vec(T::lower_into_bytes(obj, T::write))
     }
 
     fn try_lift(v: RustBuffer) -> Result<Self::RustType> {
-        let vec = v.destroy_into_vec();
-        let mut buf = vec.as_slice();
-        let value = T::try_read(&mut buf)?;
-        if buf.remaining() != 0 {
-            bail!("junk data left in buffer after lifting")
-        }
-        Ok(value)
+        T::try_lift_from_rust_buffer(v, T::try_read)
     }
 
     fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
@@ -389,6 +795,26 @@ unsafe impl<T: RustBufferFfiConverter> FfiConverter for T {
     fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
         T::try_read(buf)
     }
+
+    fn lower_compact(obj: Self::RustType) -> RustBuffer {
+        RustBuffer::from_vec(T::lower_into_bytes(obj, T::write_compact))
+    }
+
+    fn try_lower_compact(obj: Self::RustType) -> std::result::Result<RustBuffer, PayloadTooLarge> {
+        RustBuffer::try_from_vec(T::lower_into_bytes(obj, T::write_compact))
+    }
+
+    fn try_lift_compact(v: RustBuffer) -> Result<Self::RustType> {
+        T::try_lift_from_rust_buffer(v, T::try_read_compact)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        T::write_compact(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        T::try_read_compact(buf)
+    }
 }
 
 /// Support for passing timestamp values via the FFI.
@@ -405,6 +831,8 @@ unsafe impl<T: RustBufferFfiConverter> FfiConverter for T {
 /// the sign of the seconds portion represents the direction of the offset
 /// overall. The sign of the seconds portion can then be used to determine
 /// if the total offset should be added to or subtracted from the unix epoch.
+///
+/// The fields keep their fixed size in the compact encoding.
 impl RustBufferFfiConverter for SystemTime {
     type RustType = Self;
 
@@ -437,6 +865,14 @@ impl RustBufferFfiConverter for SystemTime {
             Ok(SystemTime::UNIX_EPOCH - epoch_offset)
         }
     }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as RustBufferFfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as RustBufferFfiConverter>::try_read(buf)
+    }
 }
 
 /// Support for passing duration values via the FFI.
@@ -446,7 +882,8 @@ impl RustBufferFfiConverter for SystemTime {
 /// Durations are represented on the buffer by a u64 that indicates the
 /// magnitude in seconds, and a u32 that indicates the nanosecond portion
 /// of the magnitude. The nanosecond portion is expected to be between 0
-/// and 999,999,999.
+/// and 999,999,999. Like for timestamps, they keep their fixed size in the
+/// compact encoding.
 impl RustBufferFfiConverter for Duration {
     type RustType = Self;
 
@@ -459,181 +896,1054 @@ impl RustBufferFfiConverter for Duration {
         check_remaining(buf, 12)?;
         Ok(Duration::new(buf.get_u64(), buf.get_u32()))
     }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as RustBufferFfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as RustBufferFfiConverter>::try_read(buf)
+    }
 }
 
-/// Support for passing optional values via the FFI.
+/// Support for passing date-times with a fixed offset from UTC via the FFI, when the
+/// `chrono` feature is enabled.
 ///
-/// Optional values are currently always passed by serializing to a buffer.
-/// We write either a zero byte for `None`, or a one byte followed by the containing
-/// item for `Some`.
+/// Offset date-times are currently always passed by serializing to a buffer.
 ///
-/// In future we could do the same optimization as rust uses internally, where the
-/// `None` option is represented as a null pointer and the `Some` as a valid pointer,
-/// but that seems more fiddly and less safe in the short term, so it can wait.
-impl<T: FfiConverter> RustBufferFfiConverter for Option<T> {
-    type RustType = Option<T::RustType>;
+/// They are represented on the buffer by an i64 for the whole seconds since
+/// epoch, rounded towards negative infinity, a u32 for the nanoseconds after
+/// those seconds, between 0 and 999,999,999, and an i32 for the offset from
+/// UTC in seconds. Unlike timestamps, the seconds are not a sign and magnitude
+/// pair, which is how most date-time libraries split an instant anyway.
+#[cfg(feature = "chrono")]
+impl RustBufferFfiConverter for chrono::DateTime<chrono::FixedOffset> {
+    type RustType = Self;
 
     fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
-        match obj {
-            None => buf.put_i8(0),
-            Some(v) => {
-                buf.put_i8(1);
-                <T as FfiConverter>::write(v, buf);
-            }
-        }
+        buf.put_i64(obj.timestamp());
+        buf.put_u32(obj.timestamp_subsec_nanos());
+        buf.put_i32(obj.offset().local_minus_utc());
     }
 
     fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
-        check_remaining(buf, 1)?;
-        Ok(match buf.get_i8() {
-            0 => None,
-            1 => Some(<T as FfiConverter>::try_read(buf)?),
-            _ => bail!("unexpected tag byte for Option"),
-        })
+        check_remaining(buf, 16)?;
+        let seconds = buf.get_i64();
+        let nanos = buf.get_u32();
+        let offset = buf.get_i32();
+        let offset = match chrono::FixedOffset::east_opt(offset) {
+            Some(offset) => offset,
+            None => bail!("{offset} seconds is not a valid UTC offset"),
+        };
+        match chrono::DateTime::from_timestamp(seconds, nanos) {
+            Some(utc) => Ok(utc.with_timezone(&offset)),
+            None => bail!("date-time out of range: {seconds}s and {nanos}ns since epoch"),
+        }
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as RustBufferFfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as RustBufferFfiConverter>::try_read(buf)
     }
 }
 
-/// Support for passing vectors of values via the FFI.
+/// Support for passing arbitrary-precision integers via the FFI, when the `num-bigint`
+/// feature is enabled.
 ///
-/// Vectors are currently always passed by serializing to a buffer.
-/// We write a `i32` item count followed by each item in turn.
-/// (It's a signed type due to limits of the JVM).
+/// Big integers are currently always passed by serializing to a buffer.
 ///
-/// Ideally we would pass `Vec<u8>` directly as a `RustBuffer` rather
-/// than serializing, and perhaps even pass other vector types using a
-/// similar struct. But that's for future work.
-impl<T: FfiConverter> RustBufferFfiConverter for Vec<T> {
-    type RustType = Vec<T::RustType>;
+/// They are written as strings of decimal digits with an optional leading "-", which
+/// every foreign language can parse and print whatever their size, even those without
+/// an arbitrary-precision integer type of their own.
+#[cfg(feature = "num-bigint")]
+impl RustBufferFfiConverter for num_bigint::BigInt {
+    type RustType = Self;
 
     fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
-        // TODO: would be nice not to panic here :-/
-        let len = i32::try_from(obj.len()).unwrap();
-        buf.put_i32(len); // We limit arrays to i32::MAX items
-        for item in obj {
-            <T as FfiConverter>::write(item, buf);
-        }
+        <String as FfiConverter>::write(obj.to_string(), buf);
     }
 
     fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
-        check_remaining(buf, 4)?;
-        let len = usize::try_from(buf.get_i32())?;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(<T as FfiConverter>::try_read(buf)?)
-        }
-        Ok(vec)
+        parse_bigint(<String as FfiConverter>::try_read(buf)?)
     }
-}
 
-/// Support for associative arrays via the FFI.
-/// Note that because of webidl limitations,
-/// the key must always be of the String type.
-///
-/// HashMaps are currently always passed by serializing to a buffer.
-/// We write a `i32` entries count followed by each entry (string
-/// key followed by the value) in turn.
-/// (It's a signed type due to limits of the JVM).
-impl<K: FfiConverter, V: FfiConverter> RustBufferFfiConverter for HashMap<K, V>
-where
-    K::RustType: std::hash::Hash + Eq,
-{
-    type RustType = HashMap<K::RustType, V::RustType>;
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write_compact(obj.to_string(), buf);
+    }
 
-    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
-        // TODO: would be nice not to panic here :-/
-        let len = i32::try_from(obj.len()).unwrap();
-        buf.put_i32(len); // We limit HashMaps to i32::MAX entries
-        for (key, value) in obj {
-            <K as FfiConverter>::write(key, buf);
-            <V as FfiConverter>::write(value, buf);
-        }
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_bigint(<String as FfiConverter>::try_read_compact(buf)?)
     }
+}
 
-    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
-        check_remaining(buf, 4)?;
-        let len = usize::try_from(buf.get_i32())?;
-        let mut map = HashMap::with_capacity(len);
-        for _ in 0..len {
-            let key = <K as FfiConverter>::try_read(buf)?;
-            let value = <V as FfiConverter>::try_read(buf)?;
-            map.insert(key, value);
-        }
-        Ok(map)
+#[cfg(feature = "num-bigint")]
+fn parse_bigint(value: String) -> Result<num_bigint::BigInt> {
+    match value.parse() {
+        Ok(int) => Ok(int),
+        Err(err) => bail!("{value:?} is not a valid integer: {err}"),
     }
 }
 
-/// Support for passing reference-counted shared objects via the FFI.
+/// Support for passing decimal numbers via the FFI, when the `rust_decimal` feature
+/// is enabled.
 ///
-/// To avoid dealing with complex lifetime semantics over the FFI, any data passed
-/// by reference must be encapsulated in an `Arc`, and must be safe to share
-/// across threads.
-unsafe impl<T: Sync + Send> FfiConverter for std::sync::Arc<T> {
+/// Decimals are currently always passed by serializing to a buffer.
+///
+/// They are written as strings, in plain notation such as "-12.50", which every
+/// foreign decimal type can parse and print without losing precision. Lifting
+/// fails rather than rounding when the value has more digits than a `Decimal`
+/// can hold, such as more than 28 digits after the decimal point.
+#[cfg(feature = "rust_decimal")]
+impl RustBufferFfiConverter for rust_decimal::Decimal {
     type RustType = Self;
-    // Don't use a pointer to <T> as that requires a `pub <T>`
-    type FfiType = *const std::os::raw::c_void;
 
-    /// When lowering, we have an owned `Arc<T>` and we transfer that ownership
-    /// to the foreign-language code, "leaking" it out of Rust's ownership system
-    /// as a raw pointer. This works safely because we have unique ownership of `self`.
-    /// The foreign-language code is responsible for freeing this by calling the
-    /// `ffi_object_free` FFI function provided by the corresponding UniFFI type.
-    ///
-    /// Safety: when freeing the resulting pointer, the foreign-language code must
-    /// call the destructor function specific to the type `T`. Calling the destructor
-    /// function for other types may lead to undefined behaviour.
-    fn lower(obj: Self::RustType) -> Self::FfiType {
-        std::sync::Arc::into_raw(obj) as Self::FfiType
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write(obj.to_string(), buf);
     }
 
-    /// When lifting, we receive a "borrow" of the `Arc<T>` that is owned by
-    /// the foreign-language code, and make a clone of it for our own use.
-    ///
-    /// Safety: the provided value must be a pointer previously obtained by calling
-    /// the `lower()` or `write()` method of this impl.
-    fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
-        let v = v as *const T;
-        // We musn't drop the `Arc<T>` that is owned by the foreign-language code.
-        let foreign_arc = std::mem::ManuallyDrop::new(unsafe { Self::from_raw(v) });
-        // Take a clone for our own use.
-        Ok(std::sync::Arc::clone(&*foreign_arc))
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_decimal(<String as FfiConverter>::try_read(buf)?)
     }
 
-    /// When writing as a field of a complex structure, make a clone and transfer ownership
-    /// of it to the foreign-language code by writing its pointer into the buffer.
-    /// The foreign-language code is responsible for freeing this by calling the
-    /// `ffi_object_free` FFI function provided by the corresponding UniFFI type.
-    ///
-    /// Safety: when freeing the resulting pointer, the foreign-language code must
-    /// call the destructor function specific to the type `T`. Calling the destructor
-    /// function for other types may lead to undefined behaviour.
-    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
-        static_assertions::const_assert!(std::mem::size_of::<*const std::ffi::c_void>() <= 8);
-        buf.put_u64(Self::lower(obj) as u64);
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write_compact(obj.to_string(), buf);
     }
 
-    /// When reading as a field of a complex structure, we receive a "borrow" of the `Arc<T>`
-    /// that is owned by the foreign-language code, and make a clone for our own use.
-    ///
-    /// Safety: the buffer must contain a pointer previously obtained by calling
-    /// the `lower()` or `write()` method of this impl.
-    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
-        static_assertions::const_assert!(std::mem::size_of::<*const std::ffi::c_void>() <= 8);
-        check_remaining(buf, 8)?;
-        Self::try_lift(buf.get_u64() as Self::FfiType)
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_decimal(<String as FfiConverter>::try_read_compact(buf)?)
     }
 }
 
-pub fn lower_anyhow_error_or_panic<ErrConverter>(
-    err: anyhow::Error,
-    arg_name: &str,
-) -> ErrConverter::FfiType
-where
-    ErrConverter: FfiConverter,
-    ErrConverter::RustType: 'static + Sync + Send + std::fmt::Debug + std::fmt::Display,
-{
+#[cfg(feature = "rust_decimal")]
+fn parse_decimal(value: String) -> Result<rust_decimal::Decimal> {
+    let decimal: rust_decimal::Decimal = match value.parse() {
+        Ok(decimal) => decimal,
+        Err(err) => bail!("{value:?} is not a valid decimal: {err}"),
+    };
+    // Parsing rounds the digits which don't fit, which lowers the scale.
+    let fraction_digits = value.split_once('.').map_or(0, |(_, fraction)| {
+        fraction.bytes().filter(u8::is_ascii_digit).count()
+    });
+    if (decimal.scale() as usize) < fraction_digits {
+        bail!("{value:?} has more digits than a decimal can hold");
+    }
+    Ok(decimal)
+}
+
+/// Support for passing JSON values via the FFI, when the `serde_json` feature is
+/// enabled.
+///
+/// JSON values are currently always passed by serializing to a buffer, as their
+/// JSON text, which every foreign language can parse into its own JSON types.
+#[cfg(feature = "serde_json")]
+impl RustBufferFfiConverter for serde_json::Value {
+    type RustType = Self;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write(json_text(obj), buf);
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_json(<String as FfiConverter>::try_read(buf)?)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write_compact(json_text(obj), buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_json(<String as FfiConverter>::try_read_compact(buf)?)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn json_text(value: serde_json::Value) -> String {
+    // A `Value` always has string keys, so it can always be serialized.
+    serde_json::to_string(&value).expect("JSON values are always serializable")
+}
+
+#[cfg(feature = "serde_json")]
+fn parse_json(text: String) -> Result<serde_json::Value> {
+    match serde_json::from_str(&text) {
+        Ok(value) => Ok(value),
+        Err(err) => bail!("{text:?} is not valid JSON: {err}"),
+    }
+}
+
+/// Support for passing URLs via the FFI, when the `url` feature is enabled.
+///
+/// URLs are currently always passed by serializing to a buffer, as strings.
+/// The foreign languages may hold strings that aren't URLs, so lifting parses
+/// them again and fails with the reason the string isn't a valid URL.
+#[cfg(feature = "url")]
+impl RustBufferFfiConverter for url::Url {
+    type RustType = Self;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write(obj.into(), buf);
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_url(<String as FfiConverter>::try_read(buf)?)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <String as FfiConverter>::write_compact(obj.into(), buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        parse_url(<String as FfiConverter>::try_read_compact(buf)?)
+    }
+}
+
+#[cfg(feature = "url")]
+fn parse_url(value: String) -> Result<url::Url> {
+    match url::Url::parse(&value) {
+        Ok(url) => Ok(url),
+        Err(err) => bail!("{value:?} is not a valid URL: {err}"),
+    }
+}
+
+/// Support for passing UUIDs via the FFI, when the `uuid` feature is enabled.
+///
+/// UUIDs are currently always passed by serializing to a buffer, as their 16 bytes
+/// in big-endian order, which is how every foreign UUID type exposes them.
+#[cfg(feature = "uuid")]
+impl RustBufferFfiConverter for uuid::Uuid {
+    type RustType = Self;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_slice(obj.as_bytes());
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        check_remaining(buf, 16)?;
+        let mut bytes = uuid::Bytes::default();
+        buf.copy_to_slice(&mut bytes);
+        Ok(uuid::Uuid::from_bytes(bytes))
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as RustBufferFfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as RustBufferFfiConverter>::try_read(buf)
+    }
+}
+
+// The builtin containers serialize their items with the methods of the encoding they're written
+// with, which the helpers below take as arguments, so that each container is written once for
+// both encodings.
+
+fn write_option<T>(obj: Option<T>, buf: &mut Vec<u8>, write: fn(T, &mut Vec<u8>)) {
+    match obj {
+        None => buf.put_i8(0),
+        Some(v) => {
+            buf.put_i8(1);
+            write(v, buf);
+        }
+    }
+}
+
+fn read_option<T>(buf: &mut &[u8], try_read: fn(&mut &[u8]) -> Result<T>) -> Result<Option<T>> {
+    check_remaining(buf, 1)?;
+    Ok(match buf.get_i8() {
+        0 => None,
+        1 => Some(try_read(buf)?),
+        _ => bail!("unexpected tag byte for Option"),
+    })
+}
+
+fn write_items<I>(
+    items: I,
+    buf: &mut Vec<u8>,
+    write_len: fn(&mut Vec<u8>, usize),
+    mut write_item: impl FnMut(I::Item, &mut Vec<u8>),
+) where
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    write_len(buf, items.len());
+    for item in items {
+        write_item(item, buf);
+    }
+}
+
+// The length is read from the buffer, so the collection isn't allocated for it upfront: each item
+// takes at least a byte, and only the items which are actually there take space.
+fn read_items<C, T>(
+    buf: &mut &[u8],
+    read_len: fn(&mut &[u8]) -> Result<usize>,
+    mut try_read_item: impl FnMut(&mut &[u8]) -> Result<T>,
+) -> Result<C>
+where
+    C: FromIterator<T>,
+{
+    let len = read_len(buf)?;
+    (0..len).map(|_| try_read_item(buf)).collect()
+}
+
+/// Support for passing optional values via the FFI.
+///
+/// Optional values are currently always passed by serializing to a buffer.
+/// We write either a zero byte for `None`, or a one byte followed by the containing
+/// item for `Some`.
+///
+/// In future we could do the same optimization as rust uses internally, where the
+/// `None` option is represented as a null pointer and the `Some` as a valid pointer,
+/// but that seems more fiddly and less safe in the short term, so it can wait.
+impl<T: FfiConverter> RustBufferFfiConverter for Option<T> {
+    type RustType = Option<T::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_option(obj, buf, T::write)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_option(buf, T::try_read)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_option(obj, buf, T::write_compact)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_option(buf, T::try_read_compact)
+    }
+}
+
+/// Support for passing boxed values via the FFI, for the fields of recursive records and enums.
+///
+/// The box is only needed for the Rust type to have a size, so it doesn't appear on the wire:
+/// a boxed value is serialized exactly like the value itself.
+impl<T: FfiConverter> RustBufferFfiConverter for Box<T> {
+    type RustType = Box<T::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        T::write(*obj, buf);
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        Ok(Box::new(T::try_read(buf)?))
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        T::write_compact(*obj, buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        Ok(Box::new(T::try_read_compact(buf)?))
+    }
+}
+
+/// Support for passing results as values via the FFI, such as the partial failures of a batch.
+///
+/// Results are serialized to a buffer like optional values: we write a zero byte followed by
+/// the value for `Ok`, or a one byte followed by the error for `Err`.
+impl<T: FfiConverter, E: FfiConverter> RustBufferFfiConverter for std::result::Result<T, E> {
+    type RustType = std::result::Result<T::RustType, E::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_result(obj, buf, T::write, E::write)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_result(buf, T::try_read, E::try_read)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_result(obj, buf, T::write_compact, E::write_compact)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_result(buf, T::try_read_compact, E::try_read_compact)
+    }
+}
+
+fn write_result<T, E>(
+    obj: std::result::Result<T, E>,
+    buf: &mut Vec<u8>,
+    write_ok: fn(T, &mut Vec<u8>),
+    write_err: fn(E, &mut Vec<u8>),
+) {
+    match obj {
+        Ok(v) => {
+            buf.put_i8(0);
+            write_ok(v, buf);
+        }
+        Err(e) => {
+            buf.put_i8(1);
+            write_err(e, buf);
+        }
+    }
+}
+
+fn read_result<T, E>(
+    buf: &mut &[u8],
+    try_read_ok: fn(&mut &[u8]) -> Result<T>,
+    try_read_err: fn(&mut &[u8]) -> Result<E>,
+) -> Result<std::result::Result<T, E>> {
+    check_remaining(buf, 1)?;
+    Ok(match buf.get_i8() {
+        0 => Ok(try_read_ok(buf)?),
+        1 => Err(try_read_err(buf)?),
+        _ => bail!("unexpected tag byte for Result"),
+    })
+}
+
+/// Support for passing vectors of values via the FFI.
+///
+/// Vectors are passed by serializing to a buffer.
+/// We write the item count, see [`write_len`], followed by each item in turn.
+///
+/// As an exception, a `Vec<u8>` which is passed on its own, rather than inside another value, is
+/// passed as its raw bytes, without a count. Its allocation becomes the `RustBuffer`, or is taken
+/// over from it, without copying the bytes.
+///
+/// With the compact encoding, the strings of a vector share their length prefix when they all have
+/// the same length, see [`write_strings_compact`].
+impl<T: FfiConverter + 'static> RustBufferFfiConverter for Vec<T>
+where
+    T::RustType: 'static,
+{
+    type RustType = Vec<T::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len, T::write)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len, T::try_read)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        if is_string::<T, T::RustType>() {
+            // SAFETY: `T::RustType` is `String`, see `is_string()`.
+            write_strings_compact(unsafe { cast_vec(obj) }, buf);
+        } else {
+            write_items(obj, buf, write_len_compact, T::write_compact)
+        }
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        if is_string::<T, T::RustType>() {
+            // SAFETY: `T::RustType` is `String`, see `is_string()`.
+            Ok(unsafe { cast_vec(read_strings_compact(buf)?) })
+        } else {
+            read_items(buf, read_len_compact, T::try_read_compact)
+        }
+    }
+
+    fn lower_into_bytes(obj: Self::RustType, write: fn(Self::RustType, &mut Vec<u8>)) -> Vec<u8> {
+        if !is_byte::<T, T::RustType>() {
+            let mut buf = bufferpool::take_buffer(0);
+            write(obj, &mut buf);
+            return buf;
+        }
+        // SAFETY: `T::RustType` is `u8`, see `is_byte()`.
+        unsafe { cast_vec(obj) }
+    }
+
+    fn try_lift_from_rust_buffer(
+        v: RustBuffer,
+        try_read: fn(&mut &[u8]) -> Result<Self::RustType>,
+    ) -> Result<Self::RustType> {
+        if !is_byte::<T, T::RustType>() {
+            return v.read_and_destroy(|mut buf| {
+                let value = try_read(&mut buf)?;
+                if buf.remaining() != 0 {
+                    bail!("junk data left in buffer after lifting")
+                }
+                Ok(value)
+            });
+        }
+        // SAFETY: `T::RustType` is `u8`, see `is_byte()`.
+        Ok(unsafe { cast_vec(v.destroy_into_vec()) })
+    }
+}
+
+// Whether the items of a `Vec` are passed by the `FfiConverter` of `u8`, so that the `Vec` can be
+// passed as its raw bytes. Checking the `RustType` too is what makes reinterpreting a
+// `Vec<T::RustType>` as a `Vec<u8>` sound.
+fn is_byte<T: 'static, R: 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<u8>() && TypeId::of::<R>() == TypeId::of::<u8>()
+}
+
+// Whether the items of a `Vec` are passed by the `FfiConverter` of `String` or `Utf16String`, which
+// serialize them the same way, so that they can share their length prefix.
+fn is_string<T: 'static, R: 'static>() -> bool {
+    (TypeId::of::<T>() == TypeId::of::<String>()
+        || TypeId::of::<T>() == TypeId::of::<Utf16String>())
+        && TypeId::of::<R>() == TypeId::of::<String>()
+}
+
+// Reinterpret a vector as a vector of another type, without copying it.
+//
+// SAFETY: `A` and `B` must be the same type.
+unsafe fn cast_vec<A, B>(v: Vec<A>) -> Vec<B> {
+    let mut v = std::mem::ManuallyDrop::new(v);
+    Vec::from_raw_parts(v.as_mut_ptr().cast::<B>(), v.len(), v.capacity())
+}
+
+/// Write a vector of strings with the compact encoding.
+///
+/// After the item count, a non-empty vector has a varint which is the byte length of every string
+/// plus one, when they all have the same one, e.g. for identifiers or hashes. The strings are then
+/// written as their bytes only. Otherwise the varint is zero, and each string is written with its
+/// own length, like with [`FfiConverter::write_compact`].
+pub fn write_strings_compact(strings: Vec<String>, buf: &mut Vec<u8>) {
+    write_len_compact(buf, strings.len());
+    let shared_len = match strings.split_first() {
+        None => return,
+        Some((first, rest)) => Some(first.len()).filter(|&len| rest.iter().all(|s| s.len() == len)),
+    };
+    match shared_len {
+        Some(len) => write_varint(buf, len as u64 + 1),
+        None => write_varint(buf, 0),
+    }
+    for s in strings {
+        if shared_len.is_none() {
+            write_len_compact(buf, s.len());
+        }
+        buf.put(s.as_bytes());
+    }
+}
+
+/// Read a vector of strings, see [`write_strings_compact`].
+pub fn read_strings_compact(buf: &mut &[u8]) -> Result<Vec<String>> {
+    let count = read_len_compact(buf)?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let shared_len = match read_varint(buf)? {
+        0 => None,
+        len => Some(usize::try_from(i32::try_from(len - 1)?)?),
+    };
+    (0..count)
+        .map(|_| {
+            let len = match shared_len {
+                Some(len) => len,
+                None => read_len_compact(buf)?,
+            };
+            read_utf8(buf, len)
+        })
+        .collect()
+}
+
+/// Support for passing sets of values via the FFI.
+///
+/// Sets are passed like vectors, by serializing to a buffer the item count
+/// followed by each item in turn. The foreign sets can't hold duplicates, but if the
+/// buffer does anyway, reading it keeps a single copy of each item.
+impl<T: FfiConverter> RustBufferFfiConverter for HashSet<T>
+where
+    T::RustType: std::hash::Hash + Eq,
+{
+    type RustType = HashSet<T::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len, T::write)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len, T::try_read)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len_compact, T::write_compact)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len_compact, T::try_read_compact)
+    }
+}
+
+/// Support for passing ordered sets of values via the FFI.
+///
+/// They're serialized like a `HashSet`, with the items in order.
+impl<T: FfiConverter> RustBufferFfiConverter for BTreeSet<T>
+where
+    T::RustType: Ord,
+{
+    type RustType = BTreeSet<T::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len, T::write)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len, T::try_read)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len_compact, T::write_compact)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len_compact, T::try_read_compact)
+    }
+}
+
+/// Support for associative arrays via the FFI.
+/// Note that because of webidl limitations,
+/// the key must always be of the String type.
+///
+/// HashMaps are currently always passed by serializing to a buffer.
+/// We write the entries count, see [`write_len`], followed by each entry (string
+/// key followed by the value) in turn.
+impl<K: FfiConverter, V: FfiConverter> RustBufferFfiConverter for HashMap<K, V>
+where
+    K::RustType: std::hash::Hash + Eq,
+{
+    type RustType = HashMap<K::RustType, V::RustType>;
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len, |(key, value), buf| {
+            K::write(key, buf);
+            V::write(value, buf);
+        })
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len, |buf| {
+            Ok((K::try_read(buf)?, V::try_read(buf)?))
+        })
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        write_items(obj, buf, write_len_compact, |(key, value), buf| {
+            K::write_compact(key, buf);
+            V::write_compact(value, buf);
+        })
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        read_items(buf, read_len_compact, |buf| {
+            Ok((K::try_read_compact(buf)?, V::try_read_compact(buf)?))
+        })
+    }
+}
+
+/// Support for passing tuples via the FFI.
+///
+/// Tuples are currently always passed by serializing to a buffer.
+/// We write each element in turn, there's no need for a count since
+/// the number of elements is part of the type.
+impl<A: FfiConverter, B: FfiConverter> RustBufferFfiConverter for (A, B) {
+    type RustType = (A::RustType, B::RustType);
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        A::write(obj.0, buf);
+        B::write(obj.1, buf);
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        Ok((A::try_read(buf)?, B::try_read(buf)?))
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        A::write_compact(obj.0, buf);
+        B::write_compact(obj.1, buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        Ok((A::try_read_compact(buf)?, B::try_read_compact(buf)?))
+    }
+}
+
+impl<A: FfiConverter, B: FfiConverter, C: FfiConverter> RustBufferFfiConverter for (A, B, C) {
+    type RustType = (A::RustType, B::RustType, C::RustType);
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        A::write(obj.0, buf);
+        B::write(obj.1, buf);
+        C::write(obj.2, buf);
+    }
+
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        Ok((A::try_read(buf)?, B::try_read(buf)?, C::try_read(buf)?))
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        A::write_compact(obj.0, buf);
+        B::write_compact(obj.1, buf);
+        C::write_compact(obj.2, buf);
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        Ok((
+            A::try_read_compact(buf)?,
+            B::try_read_compact(buf)?,
+            C::try_read_compact(buf)?,
+        ))
+    }
+}
+
+/// Support for passing reference-counted shared objects via the FFI.
+///
+/// To avoid dealing with complex lifetime semantics over the FFI, any data passed
+/// by reference must be encapsulated in an `Arc`, and must be safe to share
+/// across threads.
+///
+/// The foreign-language code doesn't get the pointers of the `Arc`s, but handles to them in
+/// [`OBJECT_HANDLES`], which are checked whenever they're lifted.
+unsafe impl<T: Sync + Send + 'static> FfiConverter for std::sync::Arc<T> {
+    type RustType = Self;
+    type FfiType = u64;
+
+    /// When lowering, we have an owned `Arc<T>` and we transfer that ownership
+    /// to the foreign-language code, as a new handle.
+    /// The foreign-language code is responsible for freeing this by calling the
+    /// `ffi_object_free` FFI function provided by the corresponding UniFFI type.
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        OBJECT_HANDLES.insert(obj)
+    }
+
+    /// When lifting, we receive a "borrow" of the handle that is owned by
+    /// the foreign-language code, and make a clone of its `Arc<T>` for our own use.
+    ///
+    /// Fails if the handle doesn't refer to a live `T`.
+    fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
+        Ok(OBJECT_HANDLES.get(v)?)
+    }
+
+    /// When writing as a field of a complex structure, make a clone and transfer ownership
+    /// of it to the foreign-language code by writing its handle into the buffer.
+    /// The foreign-language code is responsible for freeing this by calling the
+    /// `ffi_object_free` FFI function provided by the corresponding UniFFI type.
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_u64(<Self as FfiConverter>::lower(obj));
+    }
+
+    /// When reading as a field of a complex structure, we take the ownership of the handle
+    /// written by the foreign-language code, which cloned it for us with the `ffi_object_clone`
+    /// FFI function, see the "Managing Object References" chapter of the manual.
+    ///
+    /// Fails if the handle doesn't refer to a live `T`.
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        check_remaining(buf, 8)?;
+        Ok(OBJECT_HANDLES.remove(buf.get_u64())?)
+    }
+
+    // Handles are written as 8 bytes in both encodings.
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Self as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as FfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_read(buf)
+    }
+}
+
+/// Support for passing weak references to shared objects via the FFI.
+///
+/// Weak references are passed like `Arc<T>`s, as handles to them in [`OBJECT_HANDLES`], which
+/// are checked whenever they're lifted, and whose ownership follows the same rules. As a `Weak<T>`
+/// can't be the object of a handle as is, the handles refer to an `Arc<Weak<T>>` instead.
+///
+/// The foreign-language code can't use the object through them: it has to call the
+/// `ffi_weak_upgrade` FFI function provided by the corresponding UniFFI type, which gives it
+/// the object if it's still alive.
+unsafe impl<T: Sync + Send + 'static> FfiConverter for std::sync::Weak<T> {
+    type RustType = Self;
+    type FfiType = u64;
+
+    /// Transfer the ownership of the weak reference to the foreign-language code, as a new
+    /// handle, which it's responsible for freeing by calling the `ffi_weak_free` FFI function
+    /// provided by the corresponding UniFFI type.
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        OBJECT_HANDLES.insert(std::sync::Arc::new(obj))
+    }
+
+    /// When lifting, we receive a "borrow" of the handle owned by the foreign-language code, and
+    /// make a clone of its weak reference for our own use.
+    ///
+    /// Fails if the handle doesn't refer to a live weak reference to a `T`.
+    fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
+        Ok(std::sync::Weak::clone(&*OBJECT_HANDLES.get::<Self>(v)?))
+    }
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_u64(<Self as FfiConverter>::lower(obj));
+    }
+
+    /// Take the ownership of the handle written by the foreign-language code, which made one of
+    /// its own for us by calling the `ffi_weak_clone` FFI function.
+    ///
+    /// Fails if the handle doesn't refer to a live weak reference to a `T`.
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        check_remaining(buf, 8)?;
+        Ok(std::sync::Weak::clone(
+            &*OBJECT_HANDLES.remove::<Self>(buf.get_u64())?,
+        ))
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Self as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as FfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_read(buf)
+    }
+}
+
+/// Support for passing the `Arc<dyn Trait>`s of `[Trait]` interfaces via the FFI.
+///
+/// `Arc<dyn Trait>` can't be stored in [`OBJECT_HANDLES`] as is, so the handles refer to an
+/// `Arc<Arc<dyn Trait>>` instead. The handles are freed by the `ffi_object_free` FFI function of
+/// the interface.
+pub struct FfiConverterArcDyn<T: ?Sized>(std::marker::PhantomData<T>);
+
+unsafe impl<T: ?Sized + Sync + Send + 'static> FfiConverter for FfiConverterArcDyn<T> {
+    type RustType = std::sync::Arc<T>;
+    type FfiType = u64;
+
+    /// Transfer the ownership of the `Arc` to the foreign-language code, as a new handle.
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        OBJECT_HANDLES.insert(std::sync::Arc::new(obj))
+    }
+
+    /// Make a clone of the `Arc` of the handle owned by the foreign-language code.
+    ///
+    /// Fails if the handle doesn't refer to a live `Arc<T>`.
+    fn try_lift(v: Self::FfiType) -> Result<Self::RustType> {
+        Ok(std::sync::Arc::clone(
+            &*OBJECT_HANDLES.get::<std::sync::Arc<T>>(v)?,
+        ))
+    }
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_u64(<Self as FfiConverter>::lower(obj));
+    }
+
+    /// Take the ownership of the handle written by the foreign-language code, which made a handle
+    /// of its own for us with the `ffi_object_clone` FFI function of the interface.
+    ///
+    /// Fails if the handle doesn't refer to a live `Arc<T>`.
+    fn try_read(buf: &mut &[u8]) -> Result<Self::RustType> {
+        check_remaining(buf, 8)?;
+        let foreign_arc = OBJECT_HANDLES.remove::<std::sync::Arc<T>>(buf.get_u64())?;
+        Ok(std::sync::Arc::clone(&foreign_arc))
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Self as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as FfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_read(buf)
+    }
+}
+
+/// Support for the compile-time checks of `#[derive(uniffi::Object)]`.
+///
+/// Objects must be `Send + Sync`, and when one isn't, the derive reports the fields which aren't
+/// rather than leaving the compiler to fail deep in the generated code. The fields are checked
+/// through `(&&ObjectFields::<T>(PhantomData)).field_must_be_send_and_sync::<F>()`, which
+/// resolves to the method of [`ObjectIsSendSync`](object_checks::ObjectIsSendSync), without any
+/// bound on the field, when the object is `Send + Sync`, possibly through an `unsafe impl`, and
+/// to the one of [`ObjectIsNotSendSync`](object_checks::ObjectIsNotSendSync) otherwise.
+///
+/// The FFI functions freeing and cloning the handles of the object go through them too, so that
+/// they don't fail to compile as well when it isn't `Send + Sync`.
+#[doc(hidden)]
+pub mod object_checks {
+    use super::{HandleError, OBJECT_HANDLES};
+    use std::any::Any;
+
+    pub struct ObjectFields<T: ?Sized>(pub std::marker::PhantomData<T>);
+
+    pub trait ObjectIsSendSync {
+        fn field_must_be_send_and_sync<F: ?Sized>(&self) {}
+        fn free_handle(&self, handle: u64) -> Result<(), HandleError>;
+        fn clone_handle(&self, handle: u64) -> Result<u64, HandleError>;
+    }
+
+    impl<T: Any + Send + Sync> ObjectIsSendSync for &ObjectFields<T> {
+        fn free_handle(&self, handle: u64) -> Result<(), HandleError> {
+            OBJECT_HANDLES.remove::<T>(handle).map(drop)
+        }
+
+        fn clone_handle(&self, handle: u64) -> Result<u64, HandleError> {
+            OBJECT_HANDLES.clone_handle::<T>(handle)
+        }
+    }
+
+    // The handles of an object which isn't `Send + Sync` can't be created, and the fields it's
+    // reported for fail to compile anyway.
+    pub trait ObjectIsNotSendSync {
+        fn field_must_be_send_and_sync<F: ?Sized + Send + Sync>(&self) {}
+
+        fn free_handle(&self, handle: u64) -> Result<(), HandleError> {
+            Err(HandleError::Invalid(handle))
+        }
+
+        fn clone_handle(&self, handle: u64) -> Result<u64, HandleError> {
+            Err(HandleError::Invalid(handle))
+        }
+    }
+
+    impl<T: ?Sized> ObjectIsNotSendSync for ObjectFields<T> {}
+}
+
+/// The cursor handed out to the foreign-language code for an iterator returned by Rust.
+///
+/// The foreign-language code gets its items one at a time, from the `ffi_iterator_next` FFI
+/// function provided by the corresponding UniFFI type, and frees it with `ffi_iterator_free`.
+/// The iterator is behind a mutex since the foreign-language code may advance it from any thread,
+/// and it's freed once it's no longer being advanced.
+pub struct FfiIterator<T> {
+    iterator: std::sync::Mutex<Box<dyn Iterator<Item = T> + Send>>,
+    // The thread producing an item, to tell a reentrant call from a concurrent one.
+    advancing: std::sync::Mutex<Option<std::thread::ThreadId>>,
+}
+
+/// The error of an iterator advanced again on the thread which is producing its next item, for
+/// example by a foreign callback called by the iterator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReentrantIteratorError;
+
+impl std::fmt::Display for ReentrantIteratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the iterator was advanced again while producing an item, on the same thread"
+        )
+    }
+}
+
+impl std::error::Error for ReentrantIteratorError {}
+
+impl<T> FfiIterator<T> {
+    pub fn new(iterator: Box<dyn Iterator<Item = T> + Send>) -> Self {
+        Self {
+            iterator: std::sync::Mutex::new(iterator),
+            advancing: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Get the next item, or fail rather than waiting forever for the same thread.
+    pub fn next(&self) -> std::result::Result<Option<T>, ReentrantIteratorError> {
+        struct Advancing<'a>(&'a std::sync::Mutex<Option<std::thread::ThreadId>>);
+
+        impl Drop for Advancing<'_> {
+            fn drop(&mut self) {
+                *self.0.lock().unwrap_or_else(|err| err.into_inner()) = None;
+            }
+        }
+
+        // Only the current thread records itself, so this can't race with other threads.
+        let current = std::thread::current().id();
+        if *self.advancing.lock().unwrap() == Some(current) {
+            return Err(ReentrantIteratorError);
+        }
+        let mut iterator = self.iterator.lock().unwrap();
+        *self.advancing.lock().unwrap() = Some(current);
+        let _advancing = Advancing(&self.advancing);
+        Ok(iterator.next())
+    }
+}
+
+/// Support for returning iterators via the FFI, so their items are produced lazily.
+///
+/// Iterators are wrapped in an `FfiIterator`, to which the foreign-language code gets a handle in
+/// [`OBJECT_HANDLES`], which is checked whenever it's used like the ones of the objects. They can
+/// only be returned by functions and methods, so they're never lifted or read.
+pub struct FfiConverterIterator<T>(std::marker::PhantomData<T>);
+
+unsafe impl<T: FfiConverter> FfiConverter for FfiConverterIterator<T>
+where
+    T::RustType: 'static,
+{
+    type RustType = Box<dyn Iterator<Item = T::RustType> + Send>;
+    type FfiType = u64;
+
+    /// Transfer the ownership of the iterator to the foreign-language code, as a new handle, which
+    /// it's responsible for freeing by calling the `ffi_iterator_free` FFI function.
+    ///
+    /// The iterator is fused, since the foreign-language code may keep advancing it after its end.
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        let iterator: Self::RustType = Box::new(obj.fuse());
+        OBJECT_HANDLES.insert(std::sync::Arc::new(FfiIterator::new(iterator)))
+    }
+
+    fn try_lift(_v: Self::FfiType) -> Result<Self::RustType> {
+        bail!("iterators can only be returned by Rust")
+    }
+
+    fn write(obj: Self::RustType, buf: &mut Vec<u8>) {
+        buf.put_u64(<Self as FfiConverter>::lower(obj));
+    }
+
+    fn try_read(_buf: &mut &[u8]) -> Result<Self::RustType> {
+        bail!("iterators can only be returned by Rust")
+    }
+
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Self as FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut Vec<u8>) {
+        <Self as FfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> Result<Self::RustType> {
+        <Self as FfiConverter>::try_read(buf)
+    }
+}
+
+/// Lower the error of an argument which couldn't be lifted, for the functions throwing the errors
+/// of the custom types of their arguments.
+///
+/// When the error is the one `ErrConverter` lowers, the call throws it.  Otherwise, the call fails
+/// with an [`internal_error`]: with `panic = "unwind"` this panics, and with `panic = "abort"` the
+/// error is recorded for the call to report it and an empty `RustBuffer` is returned.  Either way,
+/// the foreign-language code raises it like a panic.
+pub fn lower_anyhow_error_or_internal_error<ErrConverter>(
+    err: anyhow::Error,
+    arg_name: &str,
+) -> RustBuffer
+where
+    ErrConverter: FfiError,
+    ErrConverter::RustType: 'static + Sync + Send + std::fmt::Debug + std::fmt::Display,
+{
     match err.downcast::<ErrConverter::RustType>() {
-        Ok(actual_error) => ErrConverter::lower(actual_error),
-        Err(ohno) => panic!("Failed to convert arg '{arg_name}': {ohno}"),
+        Ok(actual_error) => lower_error::<ErrConverter>(actual_error),
+        Err(ohno) => internal_error(format!("Failed to convert arg '{arg_name}': {ohno}")),
     }
 }
 
@@ -647,20 +1957,433 @@ mod test {
         t.compile_fail("tests/ui/*.rs");
     }
 
+    #[test]
+    fn utf16_string_roundtrip() {
+        for value in ["", "hello", "h\u{e9}llo \u{1f980}"] {
+            let buf = <Utf16String as FfiConverter>::lower(value.to_string());
+            assert_eq!(buf.len(), value.encode_utf16().count() * 2);
+            assert_eq!(<Utf16String as FfiConverter>::try_lift(buf).unwrap(), value);
+        }
+        // An unpaired surrogate.
+        let buf = RustBuffer::from_vec(vec![0x00, 0xd8]);
+        assert!(<Utf16String as FfiConverter>::try_lift(buf).is_err());
+        let buf = RustBuffer::from_vec(vec![0x61]);
+        assert!(<Utf16String as FfiConverter>::try_lift(buf).is_err());
+    }
+
+    #[test]
+    fn varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            assert_eq!(read_varint(&mut buf.as_slice()).unwrap(), value);
+        }
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 300);
+        assert_eq!(buf, [0xac, 0x02]);
+        assert!(read_varint(&mut &[0x80u8][..]).is_err());
+        assert!(read_varint(&mut &[0xffu8; 11][..]).is_err());
+        let mut overflow = [0xffu8; 10];
+        overflow[9] = 0x02;
+        assert_eq!(
+            read_varint(&mut &overflow[..]).unwrap_err().to_string(),
+            "varint overflows a u64"
+        );
+    }
+
+    #[test]
+    fn compact_num_roundtrip() {
+        fn roundtrip<T: CompactNum + Copy + PartialEq + std::fmt::Debug>(value: T) -> usize {
+            let mut buf = Vec::new();
+            value.put_compact(&mut buf);
+            let mut slice = buf.as_slice();
+            assert_eq!(T::try_get_compact(&mut slice).unwrap(), value);
+            assert!(slice.is_empty());
+            buf.len()
+        }
+        assert_eq!(roundtrip(1u32), 1);
+        assert_eq!(roundtrip(-1i32), 1);
+        assert_eq!(roundtrip(-64i64), 1);
+        assert_eq!(roundtrip(64i16), 2);
+        assert_eq!(roundtrip(i64::MIN), 10);
+        assert_eq!(roundtrip(u16::MAX), 3);
+        assert_eq!(roundtrip(1.5f64), 8);
+        // Out of range for the type.
+        let mut buf = Vec::new();
+        write_varint(&mut buf, u64::from(u16::MAX) + 1);
+        assert!(<u16 as CompactNum>::try_get_compact(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn value_encoding_is_selected_by_the_method() {
+        let standard = <Vec<u32> as FfiConverter>::lower(vec![1]).destroy_into_vec();
+        assert_eq!(standard, [0, 0, 0, 1, 0, 0, 0, 1]);
+        let compact = <Vec<u32> as FfiConverter>::lower_compact(vec![1]).destroy_into_vec();
+        assert_eq!(compact, [1, 1]);
+        let buf = RustBuffer::from_vec(vec![2, 1, 0x80, 1]);
+        let lifted = <Vec<u32> as FfiConverter>::try_lift_compact(buf).unwrap();
+        assert_eq!(lifted, [1, 128]);
+        let lifted = <Vec<u32> as FfiConverter>::try_lift(RustBuffer::from_vec(compact));
+        assert!(lifted.is_err());
+        let mut buf = Vec::new();
+        write_variant_compact(&mut buf, 3);
+        assert_eq!(read_variant_compact(&mut buf.as_slice()).unwrap(), 3);
+        assert_eq!(buf, [3]);
+    }
+
     #[test]
     fn timestamp_roundtrip_post_epoch() {
         let expected = SystemTime::UNIX_EPOCH + Duration::new(100, 100);
-        let result = SystemTime::try_lift(SystemTime::lower(expected)).expect("Failed to lift!");
+        let result =
+            <SystemTime as FfiConverter>::try_lift(<SystemTime as FfiConverter>::lower(expected))
+                .expect("Failed to lift!");
         assert_eq!(expected, result)
     }
 
     #[test]
     fn timestamp_roundtrip_pre_epoch() {
         let expected = SystemTime::UNIX_EPOCH - Duration::new(100, 100);
-        let result = SystemTime::try_lift(SystemTime::lower(expected)).expect("Failed to lift!");
+        let result =
+            <SystemTime as FfiConverter>::try_lift(<SystemTime as FfiConverter>::lower(expected))
+                .expect("Failed to lift!");
         assert_eq!(
             expected, result,
             "Expected results after lowering and lifting to be equal"
         )
     }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn offset_datetime_roundtrip_keeps_the_offset() {
+        use chrono::{DateTime, FixedOffset, TimeZone};
+
+        let paris = FixedOffset::east_opt(3600).unwrap();
+        let expected = paris.timestamp_opt(-100, 100).unwrap();
+        let result = <DateTime<FixedOffset> as FfiConverter>::try_lift(
+            <DateTime<FixedOffset> as FfiConverter>::lower(expected),
+        )
+        .expect("Failed to lift!");
+        assert_eq!(expected, result);
+        assert_eq!(result.offset(), &paris);
+
+        let mut buf = Vec::new();
+        buf.put_i64(0);
+        buf.put_u32(0);
+        buf.put_i32(86_400);
+        let err =
+            <DateTime<FixedOffset> as FfiConverter>::try_read(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.to_string(), "86400 seconds is not a valid UTC offset");
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn bigint_roundtrip_is_lossless() {
+        use num_bigint::BigInt;
+
+        let expected: BigInt = "-340282366920938463463374607431768211457".parse().unwrap();
+        let result =
+            <BigInt as FfiConverter>::try_lift(<BigInt as FfiConverter>::lower(expected.clone()))
+                .expect("Failed to lift!");
+        assert_eq!(expected, result);
+
+        let mut buf = Vec::new();
+        <String as FfiConverter>::write("1e3".into(), &mut buf);
+        let err = <BigInt as FfiConverter>::try_read(&mut buf.as_slice()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("\"1e3\" is not a valid integer"));
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimal_roundtrip_is_lossless() {
+        use rust_decimal::Decimal;
+
+        let expected: Decimal = "-1234567890123456789.0123456789".parse().unwrap();
+        let result =
+            <Decimal as FfiConverter>::try_lift(<Decimal as FfiConverter>::lower(expected))
+                .expect("Failed to lift!");
+        assert_eq!(expected, result);
+        assert_eq!(result.to_string(), "-1234567890123456789.0123456789");
+
+        let mut buf = Vec::new();
+        <String as FfiConverter>::write("NaN".into(), &mut buf);
+        let err = <Decimal as FfiConverter>::try_read(&mut buf.as_slice()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("\"NaN\" is not a valid decimal"));
+
+        for value in [
+            "0.12345678901234567890123456789",
+            "1234567890123456789.0123456789012",
+        ] {
+            let mut buf = Vec::new();
+            <String as FfiConverter>::write(value.into(), &mut buf);
+            let err = <Decimal as FfiConverter>::try_read(&mut buf.as_slice()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("{value:?} has more digits than a decimal can hold")
+            );
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_roundtrip_keeps_the_value() {
+        use serde_json::{json, Value};
+
+        let expected = json!({"name": "coverall", "holes": [1, 2.5, null], "ok": true});
+        let result =
+            <Value as FfiConverter>::try_lift(<Value as FfiConverter>::lower(expected.clone()))
+                .expect("Failed to lift!");
+        assert_eq!(result, expected);
+
+        let mut buf = Vec::new();
+        <String as FfiConverter>::write("NaN".into(), &mut buf);
+        let err = <Value as FfiConverter>::try_read(&mut buf.as_slice()).unwrap_err();
+        assert!(err.to_string().starts_with("\"NaN\" is not valid JSON"));
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn url_lifting_checks_the_url() {
+        use url::Url;
+
+        let expected = Url::parse("https://example.com/a b?q=1").unwrap();
+        let result =
+            <Url as FfiConverter>::try_lift(<Url as FfiConverter>::lower(expected.clone()))
+                .expect("Failed to lift!");
+        assert_eq!(result.as_str(), "https://example.com/a%20b?q=1");
+        assert_eq!(result, expected);
+
+        let mut buf = Vec::new();
+        <String as FfiConverter>::write("example.com".into(), &mut buf);
+        let err = <Url as FfiConverter>::try_read(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "\"example.com\" is not a valid URL: relative URL without a base"
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_roundtrip_keeps_the_byte_order() {
+        use uuid::Uuid;
+
+        let expected = Uuid::parse_str("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8").unwrap();
+        let mut buf = Vec::new();
+        <Uuid as FfiConverter>::write(expected, &mut buf);
+        assert_eq!(buf.as_slice(), expected.as_bytes());
+        let result = <Uuid as FfiConverter>::try_lift(<Uuid as FfiConverter>::lower(expected))
+            .expect("Failed to lift!");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn char_lifting_checks_scalar_values() {
+        assert_eq!(
+            <char as FfiConverter>::try_lift(<char as FfiConverter>::lower('é')).unwrap(),
+            'é'
+        );
+        assert_eq!(<char as FfiConverter>::try_lift(0x1F980).unwrap(), '🦀');
+        let err = <char as FfiConverter>::try_lift(0xD800).unwrap_err();
+        assert_eq!(err.to_string(), "0xd800 is not a Unicode scalar value");
+        assert!(<char as FfiConverter>::try_lift(0x110000).is_err());
+    }
+
+    #[test]
+    fn set_lifting_drops_duplicates() {
+        let items = vec!["b".to_string(), "a".to_string(), "b".to_string()];
+        let set = <HashSet<String> as FfiConverter>::try_lift(
+            <Vec<String> as FfiConverter>::lower(items.clone()),
+        )
+        .unwrap();
+        assert_eq!(set, HashSet::from(["a".to_string(), "b".to_string()]));
+        let set = <BTreeSet<String> as FfiConverter>::try_lift(
+            <Vec<String> as FfiConverter>::lower(items),
+        )
+        .unwrap();
+        assert_eq!(Vec::from_iter(set), vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn byte_vec_lowering_passes_raw_bytes() {
+        type Bytes = Vec<u8>;
+        for lower in [
+            <Bytes as FfiConverter>::lower,
+            <Bytes as FfiConverter>::lower_compact,
+        ] {
+            assert_eq!(lower(vec![1, 2, 3]).destroy_into_vec(), vec![1, 2, 3]);
+        }
+        for try_lift in [
+            <Bytes as FfiConverter>::try_lift,
+            <Bytes as FfiConverter>::try_lift_compact,
+        ] {
+            assert_eq!(
+                try_lift(RustBuffer::from_vec(vec![1, 2, 3])).unwrap(),
+                vec![1, 2, 3]
+            );
+        }
+        // Other vectors, and byte vectors inside other values, are still serialized.
+        let bytes = <Vec<u16> as FfiConverter>::lower(vec![1]).destroy_into_vec();
+        assert_eq!(bytes, [0, 0, 0, 1, 0, 1]);
+        let bytes = <Vec<u16> as FfiConverter>::lower_compact(vec![1]).destroy_into_vec();
+        assert_eq!(bytes, [1, 1]);
+        type OptionalBytes = Option<Vec<u8>>;
+        let bytes = <OptionalBytes as FfiConverter>::lower(Some(vec![7])).destroy_into_vec();
+        assert_eq!(bytes, [1, 0, 0, 0, 1, 7]);
+        let lifted = <OptionalBytes as FfiConverter>::try_lift(RustBuffer::from_vec(bytes));
+        assert_eq!(lifted.unwrap(), Some(vec![7]));
+        let bytes =
+            <OptionalBytes as FfiConverter>::lower_compact(Some(vec![7])).destroy_into_vec();
+        assert_eq!(bytes, [1, 1, 7]);
+        let lifted = <OptionalBytes as FfiConverter>::try_lift_compact(RustBuffer::from_vec(bytes));
+        assert_eq!(lifted.unwrap(), Some(vec![7]));
+
+        // Only the converter of `u8` passes raw bytes, not the others of `u8` values.
+        struct Percent;
+        unsafe impl FfiConverter for Percent {
+            type RustType = u8;
+            type FfiType = u8;
+            fn lower(obj: u8) -> u8 {
+                obj
+            }
+            fn try_lift(v: u8) -> Result<u8> {
+                Ok(v)
+            }
+            fn write(obj: u8, buf: &mut Vec<u8>) {
+                buf.put_u8(obj);
+            }
+            fn try_read(buf: &mut &[u8]) -> Result<u8> {
+                check_remaining(buf, 1)?;
+                Ok(buf.get_u8())
+            }
+            fn lower_compact(obj: u8) -> u8 {
+                obj
+            }
+            fn try_lift_compact(v: u8) -> Result<u8> {
+                Ok(v)
+            }
+            fn write_compact(obj: u8, buf: &mut Vec<u8>) {
+                Self::write(obj, buf)
+            }
+            fn try_read_compact(buf: &mut &[u8]) -> Result<u8> {
+                Self::try_read(buf)
+            }
+        }
+        let bytes = <Vec<Percent> as FfiConverter>::lower(vec![50]).destroy_into_vec();
+        assert_eq!(bytes, [0, 0, 0, 1, 50]);
+    }
+
+    #[test]
+    fn compact_string_vecs_share_their_length() {
+        fn roundtrip(items: &[&str]) -> Vec<u8> {
+            let items: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+            let bytes = <Vec<String> as FfiConverter>::lower_compact(items.clone());
+            let bytes = bytes.destroy_into_vec();
+            let lifted = <Vec<String> as FfiConverter>::try_lift_compact(RustBuffer::from_vec(
+                bytes.clone(),
+            ));
+            assert_eq!(lifted.unwrap(), items);
+            let lifted = <Vec<Utf16String> as FfiConverter>::try_lift_compact(
+                RustBuffer::from_vec(bytes.clone()),
+            );
+            assert_eq!(lifted.unwrap(), items);
+            bytes
+        }
+        assert_eq!(roundtrip(&[]), [0]);
+        assert_eq!(roundtrip(&["ab", "cd", "ef"]), b"\x03\x03abcdef");
+        assert_eq!(roundtrip(&["", ""]), [2, 1]);
+        assert_eq!(roundtrip(&["ab", "c"]), b"\x02\x00\x02ab\x01c");
+        // Inside other values too, but not in the standard encoding.
+        let bytes = <Option<Vec<String>> as FfiConverter>::lower_compact(Some(vec!["a".into()]));
+        assert_eq!(bytes.destroy_into_vec(), [1, 1, 2, b'a']);
+        let bytes = <Vec<String> as FfiConverter>::lower(vec!["a".into()]);
+        assert_eq!(bytes.destroy_into_vec(), [0, 0, 0, 1, 0, 0, 0, 1, b'a']);
+        // The shared length is bounded like the others.
+        let mut buf = vec![1];
+        write_varint(&mut buf, u64::MAX);
+        let err = <Vec<String> as FfiConverter>::try_read_compact(&mut buf.as_slice());
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn object_handles_are_checked() {
+        type A = std::sync::Arc<String>;
+        let handle = <A as FfiConverter>::lower(A::new("a".to_string()));
+        assert_eq!(*<A as FfiConverter>::try_lift(handle).unwrap(), "a");
+        let err = <std::sync::Arc<u32> as FfiConverter>::try_lift(handle).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&HandleError::WrongType(handle)));
+        OBJECT_HANDLES.remove::<String>(handle).unwrap();
+        let err = <A as FfiConverter>::try_lift(handle).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&HandleError::Stale(handle)));
+    }
+
+    #[test]
+    fn result_lowering_and_lifting() {
+        type R = std::result::Result<u32, String>;
+        let results: Vec<R> = vec![Ok(5), Err("boom".to_string())];
+        let lifted =
+            <Vec<R> as FfiConverter>::try_lift(<Vec<R> as FfiConverter>::lower(results.clone()))
+                .unwrap();
+        assert_eq!(lifted, results);
+        let err = <R as FfiConverter>::try_lift(RustBuffer::from_vec(vec![2])).unwrap_err();
+        assert_eq!(err.to_string(), "unexpected tag byte for Result");
+    }
+
+    #[test]
+    fn iterator_lowering_yields_items_lazily() {
+        type I = FfiConverterIterator<u32>;
+        let handle = <I as FfiConverter>::lower(Box::new((1..).map(|i| i * 2)));
+        let iterator = OBJECT_HANDLES.get::<FfiIterator<u32>>(handle).unwrap();
+        assert_eq!(iterator.next().unwrap(), Some(2));
+        assert_eq!(iterator.next().unwrap(), Some(4));
+        OBJECT_HANDLES.remove::<FfiIterator<u32>>(handle).unwrap();
+        let handle = <I as FfiConverter>::lower(Box::new([1, 2].into_iter()));
+        let iterator = OBJECT_HANDLES.get::<FfiIterator<u32>>(handle).unwrap();
+        assert_eq!(iterator.next().unwrap(), Some(1));
+        assert_eq!(iterator.next().unwrap(), Some(2));
+        assert_eq!(iterator.next().unwrap(), None);
+        assert_eq!(iterator.next().unwrap(), None);
+        let err = <I as FfiConverter>::try_lift(handle).err().unwrap();
+        assert_eq!(err.to_string(), "iterators can only be returned by Rust");
+        OBJECT_HANDLES.remove::<FfiIterator<u32>>(handle).unwrap();
+    }
+
+    #[test]
+    fn iterator_handles_are_checked() {
+        type I = FfiConverterIterator<u32>;
+        let handle = <I as FfiConverter>::lower(Box::new(1..));
+        // The handle of an iterator over other items is rejected.
+        assert_eq!(
+            OBJECT_HANDLES.get::<FfiIterator<u8>>(handle).err(),
+            Some(HandleError::WrongType(handle))
+        );
+        // An iterator still being advanced outlives its handle.
+        let iterator = OBJECT_HANDLES.get::<FfiIterator<u32>>(handle).unwrap();
+        OBJECT_HANDLES.remove::<FfiIterator<u32>>(handle).unwrap();
+        assert_eq!(iterator.next().unwrap(), Some(1));
+        // Freeing it twice fails rather than dropping it again.
+        assert_eq!(
+            OBJECT_HANDLES.remove::<FfiIterator<u32>>(handle).err(),
+            Some(HandleError::Stale(handle))
+        );
+    }
+
+    #[test]
+    fn iterator_reentrancy_fails() {
+        type Slot = std::sync::Arc<std::sync::Mutex<Option<std::sync::Arc<FfiIterator<bool>>>>>;
+        let slot = Slot::default();
+        let inner = slot.clone();
+        // Each item tells whether advancing the iterator again, from its own `next`, failed.
+        let iterator =
+            std::sync::Arc::new(FfiIterator::new(Box::new(std::iter::from_fn(move || {
+                let iterator = inner.lock().unwrap().clone().unwrap();
+                Some(iterator.next() == Err(ReentrantIteratorError))
+            }))));
+        *slot.lock().unwrap() = Some(iterator.clone());
+        assert_eq!(iterator.next(), Ok(Some(true)));
+        assert_eq!(iterator.next(), Ok(Some(true)));
+        slot.lock().unwrap().take();
+    }
 }
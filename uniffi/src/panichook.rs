@@ -1,4 +1,41 @@
-/// Initialize our panic handling hook to optionally log panics
+//! The panic hook logging panics and recording where they happened, so that the foreign-language
+//! exceptions raised for them can tell, see `rustcalls.rs`. It's only installed with the
+//! `log_panics` feature.
+
+#[cfg(feature = "log_panics")]
+use std::cell::RefCell;
+
+/// Where a panic happened.
+#[derive(Debug, Default)]
+pub(crate) struct PanicDetails {
+    /// The `file:line:column` of the `panic!`, or empty if unknown.
+    pub location: String,
+    /// The backtrace of the panic with the `panic-backtraces` feature, or empty.
+    pub backtrace: String,
+}
+
+#[cfg(feature = "log_panics")]
+thread_local! {
+    // The details of the last panic of the thread, until the call catching it takes them.
+    static LAST_PANIC: RefCell<Option<PanicDetails>> = const { RefCell::new(None) };
+}
+
+/// Take the details of the last panic of the current thread, recorded by the panic hook.
+#[cfg(feature = "log_panics")]
+pub(crate) fn take_panic_details() -> Option<PanicDetails> {
+    LAST_PANIC
+        .try_with(|last_panic| last_panic.borrow_mut().take())
+        .ok()
+        .flatten()
+}
+
+/// Take the details of the last panic of the current thread, which are unknown without the hook.
+#[cfg(not(feature = "log_panics"))]
+pub(crate) fn take_panic_details() -> Option<PanicDetails> {
+    None
+}
+
+/// Initialize our panic handling hook to log panics and record their details
 #[cfg(feature = "log_panics")]
 pub fn ensure_setup() {
     use std::sync::Once;
@@ -12,19 +49,29 @@ pub fn ensure_setup() {
         // "Location" (file/line). We do both in case we've been stripped,
         // ).
         std::panic::set_hook(Box::new(move |panic_info| {
-            let (file, line) = if let Some(loc) = panic_info.location() {
-                (loc.file(), loc.line())
+            let (file, line, column) = if let Some(loc) = panic_info.location() {
+                (loc.file(), loc.line(), loc.column())
             } else {
                 // Apparently this won't happen but rust has reserved the
                 // ability to start returning None from location in some cases
                 // in the future.
-                ("<unknown>", 0)
+                ("<unknown>", 0, 0)
             };
             log::error!("### Rust `panic!` hit at file '{file}', line {line}");
             #[cfg(all(feature = "log_backtraces", not(target_os = "android")))]
             {
                 log::error!("  Complete stack trace:\n{:?}", backtrace::Backtrace::new());
             }
+            #[cfg(feature = "panic-backtraces")]
+            let backtrace = format!("{:?}", backtrace::Backtrace::new());
+            #[cfg(not(feature = "panic-backtraces"))]
+            let backtrace = String::new();
+            let details = PanicDetails {
+                location: format!("{file}:{line}:{column}"),
+                backtrace,
+            };
+            // The thread-local is gone while the thread is exiting.
+            let _ = LAST_PANIC.try_with(|last_panic| *last_panic.borrow_mut() = Some(details));
         }));
     });
 }
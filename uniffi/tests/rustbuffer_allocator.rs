@@ -0,0 +1,84 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// The allocator is set once for the whole process, so this is a test binary of its own.
+
+use std::alloc::{alloc, dealloc, Layout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use uniffi::{
+    buffer_pool_stats, set_rustbuffer_allocator, uniffi_rustbuffer_alloc,
+    uniffi_rustbuffer_from_bytes, uniffi_rustbuffer_reserve, AllocatorAlreadySet, FfiConverter,
+    ForeignBytes, RustBuffer, RustBufferAllocator, RustCallStatus,
+};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "C" fn counting_alloc(size: usize) -> *mut u8 {
+    ALLOCATED.fetch_add(size, Ordering::SeqCst);
+    alloc(Layout::array::<u8>(size).unwrap())
+}
+
+unsafe extern "C" fn counting_free(ptr: *mut u8, size: usize) {
+    ALLOCATED.fetch_sub(size, Ordering::SeqCst);
+    dealloc(ptr, Layout::array::<u8>(size).unwrap())
+}
+
+#[test]
+fn test_rustbuffer_allocator() {
+    let allocator = RustBufferAllocator {
+        alloc: counting_alloc,
+        free: counting_free,
+    };
+    set_rustbuffer_allocator(allocator).unwrap();
+    assert_eq!(
+        set_rustbuffer_allocator(allocator),
+        Err(AllocatorAlreadySet)
+    );
+
+    let buf = RustBuffer::from_vec(vec![1, 2, 3]);
+    assert!(ALLOCATED.load(Ordering::SeqCst) >= 3);
+    assert_eq!(buf.destroy_into_vec(), vec![1, 2, 3]);
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+
+    let buf = RustBuffer::new_with_size(16);
+    assert!(ALLOCATED.load(Ordering::SeqCst) >= 16);
+    buf.destroy();
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+
+    // Empty buffers don't allocate.
+    RustBuffer::new().destroy();
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+
+    // The buffers of the foreign-language code are allocated by the allocator, and lifted from
+    // in place, without going through a `Vec<u8>`.
+    let pool_allocated = buffer_pool_stats().allocated;
+    let mut status = RustCallStatus::default();
+    let bytes = [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2];
+    let buf = unsafe {
+        uniffi_rustbuffer_from_bytes::<false>(
+            ForeignBytes::from_raw_parts(bytes.as_ptr(), bytes.len() as i32),
+            &mut status,
+        )
+    };
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 12);
+    let buf = unsafe { uniffi_rustbuffer_reserve::<false>(buf, 4, &mut status) };
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 16);
+    assert_eq!(buf.len(), 12);
+    assert_eq!(
+        <Vec<u32> as FfiConverter>::try_lift(buf).unwrap(),
+        vec![1, 2]
+    );
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+
+    let buf = uniffi_rustbuffer_alloc::<false>(8, &mut status);
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 8);
+    assert_eq!(
+        <Option<u64> as FfiConverter>::try_lift(buf)
+            .unwrap_err()
+            .to_string(),
+        "junk data left in buffer after lifting"
+    );
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+    assert_eq!(buffer_pool_stats().allocated, pool_allocated);
+}
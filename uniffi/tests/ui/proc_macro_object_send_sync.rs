@@ -0,0 +1,25 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn main() {}
+
+// Each field which isn't `Send + Sync` is reported.
+#[derive(uniffi::Object)]
+pub struct Counter {
+    name: String,
+    count: Cell<u32>,
+    owner: Rc<String>,
+}
+
+// The fields aren't checked when the object is `Send + Sync` anyway.
+#[derive(uniffi::Object)]
+pub struct Shared {
+    owner: Rc<String>,
+}
+
+unsafe impl Send for Shared {}
+unsafe impl Sync for Shared {}
+
+mod uniffi_types {
+    pub use super::{Counter, Shared};
+}
@@ -178,6 +178,12 @@ impl CodeTypeDispatch for Argument {
     }
 }
 
+impl CodeTypeDispatch for Constant {
+    fn code_type_impl(&self, oracle: &dyn CodeOracle) -> Box<dyn CodeType> {
+        oracle.find(self.type_())
+    }
+}
+
 // Needed to handle &&Type and &&&Type values, which we sometimes end up with in the template code
 impl<T, C> CodeTypeDispatch for T
 where
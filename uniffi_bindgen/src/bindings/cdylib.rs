@@ -0,0 +1,127 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Build the cdylib of a component, for the tooling which packages it with its bindings.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+
+// The extensions of the dynamic libraries built for the platforms we support.
+const CDYLIB_EXTENSIONS: &[&str] = &[".so", ".dylib", ".dll"];
+
+/// Build the cdylib of the crate at `crate_root` and return the path of the resulting library.
+///
+/// The library is built for `target` if given, or for the host otherwise.
+///
+/// We ask cargo for JSON messages rather than guessing the layout of the target directory, so that
+/// workspaces and `CARGO_TARGET_DIR` are handled for us.
+pub(crate) fn build_cdylib(
+    crate_root: &Utf8Path,
+    target: Option<&str>,
+    release: bool,
+) -> Result<Utf8PathBuf> {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("build")
+        .arg("--lib")
+        .arg("--message-format=json-render-diagnostics")
+        .arg("--manifest-path")
+        .arg(crate_root.join("Cargo.toml"));
+    if let Some(target) = target {
+        cmd.arg("--target").arg(target);
+    }
+    if release {
+        cmd.arg("--release");
+    }
+    let target = target.unwrap_or("the host");
+    let cdylib = run_cargo_build(cmd, &format!("the library for {target}"))?
+        .pop()
+        .map(|(_, cdylib)| cdylib);
+    cdylib.with_context(|| {
+        format!("No cdylib was built for {target}, is `crate-type = [\"cdylib\"]` set?")
+    })
+}
+
+/// Build the cdylibs of the `packages` of the workspace of `manifest_path`, or of the current
+/// directory, for the host, and return the path of each library by package id.
+pub(crate) fn build_cdylibs(
+    manifest_path: Option<&Utf8Path>,
+    packages: &[&str],
+    release: bool,
+) -> Result<HashMap<String, Utf8PathBuf>> {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("build")
+        .arg("--lib")
+        .arg("--message-format=json-render-diagnostics");
+    if let Some(manifest_path) = manifest_path {
+        cmd.arg("--manifest-path").arg(manifest_path);
+    }
+    for package in packages {
+        cmd.arg("--package").arg(package);
+    }
+    if release {
+        cmd.arg("--release");
+    }
+    Ok(run_cargo_build(cmd, "the libraries")?.into_iter().collect())
+}
+
+// Run a `cargo build` command asking for JSON messages, and return the package id and the path
+// of each cdylib it built. `what` describes the build in its error.
+fn run_cargo_build(mut cmd: Command, what: &str) -> Result<Vec<(String, Utf8PathBuf)>> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to run cargo")?;
+
+    let mut cdylibs = Vec::new();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    for line in BufReader::new(stdout).lines() {
+        let message: serde_json::Value = serde_json::from_str(&line?)?;
+        // Proc-macro dependencies are dynamic libraries too, so check the crate type first.
+        let is_cdylib = message["target"]["crate_types"]
+            .as_array()
+            .map_or(false, |types| types.iter().any(|t| t == "cdylib"));
+        if message["reason"] != "compiler-artifact" || !is_cdylib {
+            continue;
+        }
+        let package_id = message["package_id"].as_str().unwrap_or_default();
+        if let Some(filenames) = message["filenames"].as_array() {
+            for filename in filenames.iter().filter_map(|f| f.as_str()) {
+                if CDYLIB_EXTENSIONS.iter().any(|ext| filename.ends_with(ext)) {
+                    cdylibs.push((package_id.to_owned(), Utf8PathBuf::from(filename)));
+                }
+            }
+        }
+    }
+
+    if !child.wait()?.success() {
+        bail!("Failed to build {what}");
+    }
+    Ok(cdylibs)
+}
+
+/// The `[package]` table of the manifest of the crate at `crate_root`, for the metadata of the
+/// packages holding its cdylib.
+pub(crate) fn crate_package(crate_root: &Utf8Path) -> Result<toml::Value> {
+    let mut manifest: toml::Value =
+        toml::from_str(&fs::read_to_string(crate_root.join("Cargo.toml"))?)?;
+    manifest
+        .as_table_mut()
+        .and_then(|manifest| manifest.remove("package"))
+        .context("The crate has no `package` table")
+}
+
+/// The version of the crate at `crate_root`, which the packages holding its cdylib are versioned
+/// like.
+pub(crate) fn crate_version(crate_root: &Utf8Path) -> Result<String> {
+    crate_package(crate_root)?
+        .get("version")
+        .and_then(|version| version.as_str())
+        .map(str::to_owned)
+        .context("The crate has no `package.version`")
+}
@@ -0,0 +1,781 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Compatibility of the changes between two versions of an interface.
+//!
+//! `uniffi-bindgen diff` compares the [JSON descriptions](crate::interface::json) of two versions
+//! of the interfaces of components, and reports each change with the target languages in which
+//! code using the old bindings may not work with the new ones.  The changes breaking for none are
+//! additive.
+//!
+//! Most changes break every language, or none, but a few depend on the language:
+//!
+//!  * Adding a variant to an enum or an error breaks the exhaustive `when` and `switch` of Kotlin
+//!    and Swift, but not Python and Ruby code.
+//!  * Changing the size of a number, like `u32` to `u64`, breaks Kotlin and Swift, which have a
+//!    type per size, but not Python and Ruby, which have a single integer and float type, as long
+//!    as the numbers passed to Rust can only grow.
+//!
+//! Arguments and fields added with a default value are additive, since they can be left out, and
+//! so is making an argument optional.  The callback interfaces are implemented by the
+//! foreign-language code instead of being called by it, so adding a method to them, or changing
+//! one, is breaking.  Docstrings are ignored.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::TargetLanguage;
+use crate::interface::json::{
+    ArgumentJson, CallbackInterfaceJson, ComponentJson, ConstantJson, ConstructorJson, EnumJson,
+    FieldJson, FunctionJson, InterfacesJson, MethodJson, ObjectJson, RecordJson, TypeJson,
+    VariantJson,
+};
+
+const ALL: &[TargetLanguage] = &TargetLanguage::ALL;
+const NONE: &[TargetLanguage] = &[];
+// The languages with exhaustive matches on enums, which break when variants are added.
+const EXHAUSTIVE_MATCHES: &[TargetLanguage] = &[TargetLanguage::Kotlin, TargetLanguage::Swift];
+// The languages with a type per size of numbers.
+const SIZED_NUMBERS: &[TargetLanguage] = &[TargetLanguage::Kotlin, TargetLanguage::Swift];
+
+/// A change between two versions of an interface.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// The path of the item which changed, e.g. `arithmetic.Point.x`.
+    pub item: String,
+    /// What changed, e.g. `field added without a default value`.
+    pub description: String,
+    /// The languages for which the change is breaking, none for additive changes.
+    pub breaking_for: Vec<TargetLanguage>,
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking_for.is_empty()
+    }
+}
+
+/// The changes between two versions of the interfaces of a set of components.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffReport {
+    pub changes: Vec<Change>,
+}
+
+impl DiffReport {
+    /// Compare two versions of the interfaces, whose components are matched by namespace.
+    pub fn new(old: &InterfacesJson, new: &InterfacesJson) -> Self {
+        let mut report = Self::default();
+        report.diff_items(
+            "",
+            "component",
+            &old.components,
+            &new.components,
+            |component| &component.namespace,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_component,
+        );
+        report
+    }
+
+    /// The languages for which some changes are breaking, in order.
+    pub fn breaking_languages(&self) -> Vec<TargetLanguage> {
+        let mut languages: Vec<_> = self
+            .changes
+            .iter()
+            .flat_map(|change| change.breaking_for.iter().copied())
+            .collect();
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+
+    fn push(&mut self, item: String, description: String, breaking_for: &[TargetLanguage]) {
+        self.changes.push(Change {
+            item,
+            description,
+            breaking_for: breaking_for.to_vec(),
+        });
+    }
+
+    // Report the items removed and added, and compare the ones in both versions, matched by name.
+    #[allow(clippy::too_many_arguments)]
+    fn diff_items<T>(
+        &mut self,
+        path: &str,
+        kind: &str,
+        old: &[T],
+        new: &[T],
+        name: impl Fn(&T) -> &str,
+        removed: impl Fn(&T) -> &'static [TargetLanguage],
+        added: impl Fn(&T) -> (&'static [TargetLanguage], &'static str),
+        compare: impl Fn(&mut Self, &str, &T, &T),
+    ) {
+        for old_item in old {
+            let item = child(path, name(old_item));
+            match new.iter().find(|new_item| name(new_item) == name(old_item)) {
+                Some(new_item) => compare(self, &item, old_item, new_item),
+                None => self.push(item, format!("{kind} removed"), removed(old_item)),
+            }
+        }
+        for new_item in new {
+            if !old.iter().any(|old_item| name(old_item) == name(new_item)) {
+                let (breaking_for, details) = added(new_item);
+                self.push(
+                    child(path, name(new_item)),
+                    format!("{kind} added{details}"),
+                    breaking_for,
+                );
+            }
+        }
+    }
+
+    // Report when the items in both versions aren't in the same order, e.g. positional arguments.
+    fn check_order(&mut self, path: &str, kind: &str, old: Vec<&str>, new: Vec<&str>) {
+        let common = |names: &[&str], others: &[&str]| -> Vec<String> {
+            names
+                .iter()
+                .filter(|name| others.contains(name))
+                .map(ToString::to_string)
+                .collect()
+        };
+        if common(&old, &new) != common(&new, &old) {
+            self.push(path.to_string(), format!("{kind} reordered"), ALL);
+        }
+    }
+
+    fn compare_component(&mut self, path: &str, old: &ComponentJson, new: &ComponentJson) {
+        self.diff_items(
+            path,
+            "function",
+            &old.functions,
+            &new.functions,
+            |func| &func.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_function,
+        );
+        self.diff_items(
+            path,
+            "object",
+            &old.objects,
+            &new.objects,
+            |obj| &obj.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_object,
+        );
+        self.diff_items(
+            path,
+            "record",
+            &old.records,
+            &new.records,
+            |rec| &rec.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_record,
+        );
+        self.diff_items(
+            path,
+            "enum",
+            &old.enums,
+            &new.enums,
+            |enum_| &enum_.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_enum,
+        );
+        self.diff_items(
+            path,
+            "error",
+            &old.errors,
+            &new.errors,
+            |error| &error.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_enum,
+        );
+        self.diff_items(
+            path,
+            "callback interface",
+            &old.callback_interfaces,
+            &new.callback_interfaces,
+            |cbi| &cbi.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_callback_interface,
+        );
+        self.diff_items(
+            path,
+            "constant",
+            &old.constants,
+            &new.constants,
+            |constant| &constant.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            Self::compare_constant,
+        );
+        self.diff_items(
+            path,
+            "custom type",
+            &old.custom_types,
+            &new.custom_types,
+            |custom| &custom.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            |report, path, old, new| {
+                if old.builtin != new.builtin {
+                    report.push(
+                        path.to_string(),
+                        format!(
+                            "builtin type changed from {} to {}",
+                            old.builtin, new.builtin
+                        ),
+                        ALL,
+                    );
+                }
+            },
+        );
+        self.diff_items(
+            path,
+            "external type",
+            &old.external_types,
+            &new.external_types,
+            |external| &external.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            |report, path, old, new| {
+                if old.crate_name != new.crate_name || old.kind != new.kind {
+                    report.push(path.to_string(), "external type changed".to_string(), ALL);
+                }
+            },
+        );
+    }
+
+    fn compare_function(&mut self, path: &str, old: &FunctionJson, new: &FunctionJson) {
+        self.compare_signature(path, old.into(), new.into(), false);
+    }
+
+    fn compare_object(&mut self, path: &str, old: &ObjectJson, new: &ObjectJson) {
+        if old.imp != new.imp {
+            self.push(
+                path.to_string(),
+                "implementation changed between a struct and a trait".to_string(),
+                ALL,
+            );
+        }
+        self.diff_items(
+            path,
+            "constructor",
+            &old.constructors,
+            &new.constructors,
+            |cons| &cons.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            |report, path, old, new| report.compare_signature(path, old.into(), new.into(), false),
+        );
+        self.diff_items(
+            path,
+            "method",
+            &old.methods,
+            &new.methods,
+            |meth| &meth.name,
+            |_| ALL,
+            |_| (NONE, ""),
+            |report, path, old, new| report.compare_method(path, old, new, false),
+        );
+    }
+
+    fn compare_method(&mut self, path: &str, old: &MethodJson, new: &MethodJson, callback: bool) {
+        if old.is_static != new.is_static {
+            let description = if new.is_static {
+                "method made static"
+            } else {
+                "method made non-static"
+            };
+            self.push(path.to_string(), description.to_string(), ALL);
+        }
+        self.compare_signature(path, old.into(), new.into(), callback);
+    }
+
+    fn compare_callback_interface(
+        &mut self,
+        path: &str,
+        old: &CallbackInterfaceJson,
+        new: &CallbackInterfaceJson,
+    ) {
+        if old.is_function != new.is_function {
+            self.push(
+                path.to_string(),
+                "changed between a callback interface and a callback function".to_string(),
+                ALL,
+            );
+        }
+        // The foreign-language code implements the methods, so it can ignore the ones removed, but
+        // must implement the ones added.
+        self.diff_items(
+            path,
+            "method",
+            &old.methods,
+            &new.methods,
+            |meth| &meth.name,
+            |_| NONE,
+            |_| (ALL, ""),
+            |report, path, old, new| report.compare_method(path, old, new, true),
+        );
+    }
+
+    // Compare the signatures of functions, constructors and methods.  With `callback`, they're
+    // implemented by the foreign-language code, so any change of the types is breaking.
+    fn compare_signature(
+        &mut self,
+        path: &str,
+        old: Signature<'_>,
+        new: Signature<'_>,
+        callback: bool,
+    ) {
+        self.diff_items(
+            path,
+            "argument",
+            old.arguments,
+            new.arguments,
+            |arg| &arg.name,
+            |_| ALL,
+            |arg| match (&arg.default, callback) {
+                (Some(_), false) => (NONE, " with a default value"),
+                (Some(_), true) => (ALL, " with a default value"),
+                (None, _) => (ALL, " without a default value"),
+            },
+            |report, path, old, new| report.compare_argument(path, old, new, callback),
+        );
+        self.check_order(
+            path,
+            "arguments",
+            old.arguments.iter().map(|arg| arg.name.as_str()).collect(),
+            new.arguments.iter().map(|arg| arg.name.as_str()).collect(),
+        );
+
+        match (old.return_type, new.return_type) {
+            (None, None) => {}
+            (Some(old), Some(new)) => {
+                if old != new {
+                    let breaking_for = if callback {
+                        ALL
+                    } else {
+                        type_change_impact(old, new, Direction::FromRust)
+                    };
+                    self.push(
+                        path.to_string(),
+                        format!("return type changed from {old} to {new}"),
+                        breaking_for,
+                    );
+                }
+            }
+            (None, Some(new)) => self.push(
+                path.to_string(),
+                format!("return type {new} added"),
+                if callback { ALL } else { NONE },
+            ),
+            (Some(old), None) => {
+                self.push(path.to_string(), format!("return type {old} removed"), ALL)
+            }
+        }
+
+        match (old.throws, new.throws) {
+            (None, None) => {}
+            (Some(old), Some(new)) => {
+                if old != new {
+                    self.push(
+                        path.to_string(),
+                        format!("thrown error changed from {old} to {new}"),
+                        ALL,
+                    );
+                }
+            }
+            (None, Some(new)) => self.push(path.to_string(), format!("now throws {new}"), ALL),
+            (Some(old), None) => self.push(
+                path.to_string(),
+                format!("no longer throws {old}"),
+                if callback { ALL } else { NONE },
+            ),
+        }
+    }
+
+    fn compare_argument(
+        &mut self,
+        path: &str,
+        old: &ArgumentJson,
+        new: &ArgumentJson,
+        callback: bool,
+    ) {
+        if old.type_ != new.type_ {
+            // The callbacks receive the arguments, like the return values of the functions.
+            let direction = if callback {
+                Direction::FromRust
+            } else {
+                Direction::ToRust
+            };
+            let breaking_for = type_change_impact(&old.type_, &new.type_, direction);
+            self.push(
+                path.to_string(),
+                format!("argument type changed from {} to {}", old.type_, new.type_),
+                breaking_for,
+            );
+        }
+        self.compare_default(path, &old.default, &new.default);
+    }
+
+    fn compare_record(&mut self, path: &str, old: &RecordJson, new: &RecordJson) {
+        self.compare_fields(path, &old.fields, &new.fields);
+    }
+
+    fn compare_fields(&mut self, path: &str, old: &[FieldJson], new: &[FieldJson]) {
+        self.diff_items(
+            path,
+            "field",
+            old,
+            new,
+            |field| &field.name,
+            |_| ALL,
+            |field| match field.default {
+                Some(_) => (NONE, " with a default value"),
+                None => (ALL, " without a default value"),
+            },
+            |report, path, old, new| {
+                if old.type_ != new.type_ {
+                    // The fields are both read and written by the foreign-language code.
+                    report.push(
+                        path.to_string(),
+                        format!("field type changed from {} to {}", old.type_, new.type_),
+                        type_change_impact(&old.type_, &new.type_, Direction::Both),
+                    );
+                }
+                report.compare_default(path, &old.default, &new.default);
+            },
+        );
+        self.check_order(
+            path,
+            "fields",
+            old.iter().map(|field| field.name.as_str()).collect(),
+            new.iter().map(|field| field.name.as_str()).collect(),
+        );
+    }
+
+    fn compare_default<T: PartialEq>(&mut self, path: &str, old: &Option<T>, new: &Option<T>) {
+        match (old, new) {
+            (Some(_), None) => {
+                self.push(path.to_string(), "default value removed".to_string(), ALL)
+            }
+            (None, Some(_)) => self.push(path.to_string(), "default value added".to_string(), NONE),
+            (Some(old), Some(new)) if old != new => {
+                self.push(path.to_string(), "default value changed".to_string(), NONE)
+            }
+            _ => {}
+        }
+    }
+
+    fn compare_enum(&mut self, path: &str, old: &EnumJson, new: &EnumJson) {
+        if old.flat != new.flat {
+            self.push(
+                path.to_string(),
+                "changed between a flat enum and an enum with fields".to_string(),
+                ALL,
+            );
+        }
+        self.diff_items(
+            path,
+            "variant",
+            &old.variants,
+            &new.variants,
+            |variant| &variant.name,
+            |_| ALL,
+            |_| (EXHAUSTIVE_MATCHES, ""),
+            Self::compare_variant,
+        );
+    }
+
+    fn compare_variant(&mut self, path: &str, old: &VariantJson, new: &VariantJson) {
+        if old.discriminant != new.discriminant {
+            self.push(
+                path.to_string(),
+                format!(
+                    "discriminant changed from {} to {}",
+                    old.discriminant, new.discriminant
+                ),
+                ALL,
+            );
+        }
+        self.compare_fields(path, &old.fields, &new.fields);
+    }
+
+    fn compare_constant(&mut self, path: &str, old: &ConstantJson, new: &ConstantJson) {
+        if old.type_ != new.type_ {
+            self.push(
+                path.to_string(),
+                format!("constant type changed from {} to {}", old.type_, new.type_),
+                ALL,
+            );
+        } else if old.value != new.value {
+            self.push(path.to_string(), "constant value changed".to_string(), NONE);
+        }
+    }
+}
+
+// The parts of functions, constructors and methods which can change.
+struct Signature<'a> {
+    arguments: &'a [ArgumentJson],
+    return_type: Option<&'a TypeJson>,
+    throws: Option<&'a TypeJson>,
+}
+
+impl<'a> From<&'a FunctionJson> for Signature<'a> {
+    fn from(func: &'a FunctionJson) -> Self {
+        Self {
+            arguments: &func.arguments,
+            return_type: func.return_type.as_ref(),
+            throws: func.throws.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<&'a ConstructorJson> for Signature<'a> {
+    fn from(cons: &'a ConstructorJson) -> Self {
+        Self {
+            arguments: &cons.arguments,
+            return_type: None,
+            throws: cons.throws.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<&'a MethodJson> for Signature<'a> {
+    fn from(meth: &'a MethodJson) -> Self {
+        Self {
+            arguments: &meth.arguments,
+            return_type: meth.return_type.as_ref(),
+            throws: meth.throws.as_ref(),
+        }
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            write!(f, "{}: {} ", change.item, change.description)?;
+            if change.is_breaking() {
+                let languages: Vec<_> = change
+                    .breaking_for
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                writeln!(f, "(breaking for {})", languages.join(", "))?;
+            } else {
+                writeln!(f, "(additive)")?;
+            }
+        }
+        let breaking = self.changes.iter().filter(|c| c.is_breaking()).count();
+        write!(
+            f,
+            "{breaking} breaking, {} additive changes",
+            self.changes.len() - breaking
+        )
+    }
+}
+
+fn child(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+// Which way the values of a type are passed between Rust and the foreign-language code.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    ToRust,
+    FromRust,
+    Both,
+}
+
+// The languages a change of type breaks.  The types only passed to Rust can become optional, and
+// the numbers passed to Rust can only grow for the code passing them to keep working.
+fn type_change_impact(
+    old: &TypeJson,
+    new: &TypeJson,
+    direction: Direction,
+) -> &'static [TargetLanguage] {
+    match new {
+        TypeJson::Optional { inner } if direction == Direction::ToRust && **inner == *old => NONE,
+        _ => match number_fits(old, new) {
+            Some(fits) if fits || direction == Direction::FromRust => SIZED_NUMBERS,
+            _ => ALL,
+        },
+    }
+}
+
+// Whether all the values of the number type `old` fit in `new`, or `None` unless they're both
+// integers or both floats.
+fn number_fits(old: &TypeJson, new: &TypeJson) -> Option<bool> {
+    let float_bits = |t: &TypeJson| match t {
+        TypeJson::F32 => Some(32),
+        TypeJson::F64 => Some(64),
+        _ => None,
+    };
+    if let (Some(old), Some(new)) = (float_bits(old), float_bits(new)) {
+        return Some(old <= new);
+    }
+    let integer_range = |t: &TypeJson| -> Option<(i128, i128)> {
+        Some(match t {
+            TypeJson::U8 => (0, u8::MAX.into()),
+            TypeJson::I8 => (i8::MIN.into(), i8::MAX.into()),
+            TypeJson::U16 => (0, u16::MAX.into()),
+            TypeJson::I16 => (i16::MIN.into(), i16::MAX.into()),
+            TypeJson::U32 => (0, u32::MAX.into()),
+            TypeJson::I32 => (i32::MIN.into(), i32::MAX.into()),
+            TypeJson::U64 => (0, u64::MAX.into()),
+            TypeJson::I64 => (i64::MIN.into(), i64::MAX.into()),
+            _ => return None,
+        })
+    };
+    let (old, new) = (integer_range(old)?, integer_range(new)?);
+    Some(new.0 <= old.0 && old.1 <= new.1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::ComponentInterface;
+
+    fn diff(old: &str, new: &str) -> Vec<(String, String, Vec<TargetLanguage>)> {
+        let old = ComponentInterface::from_webidl(old).unwrap();
+        let new = ComponentInterface::from_webidl(new).unwrap();
+        DiffReport::new(&InterfacesJson::new([&old]), &InterfacesJson::new([&new]))
+            .changes
+            .into_iter()
+            .map(|change| (change.item, change.description, change.breaking_for))
+            .collect()
+    }
+
+    #[test]
+    fn test_functions() {
+        let old = r#"
+            namespace math {
+                u32 add(u32 a, u32 b);
+                void reset();
+            };
+        "#;
+        let new = r#"
+            namespace math {
+                u64 add(u16 a, u32? b, optional u32 c = 0);
+                u32 sub(u32 a, u32 b);
+            };
+        "#;
+        assert_eq!(
+            diff(old, new),
+            vec![
+                (
+                    "math.add.a".to_string(),
+                    "argument type changed from u32 to u16".to_string(),
+                    ALL.to_vec(),
+                ),
+                (
+                    "math.add.b".to_string(),
+                    "argument type changed from u32 to u32?".to_string(),
+                    vec![],
+                ),
+                (
+                    "math.add.c".to_string(),
+                    "argument added with a default value".to_string(),
+                    vec![],
+                ),
+                (
+                    "math.add".to_string(),
+                    "return type changed from u32 to u64".to_string(),
+                    SIZED_NUMBERS.to_vec(),
+                ),
+                (
+                    "math.reset".to_string(),
+                    "function removed".to_string(),
+                    ALL.to_vec(),
+                ),
+                ("math.sub".to_string(), "function added".to_string(), vec![],),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_types() {
+        let old = r#"
+            namespace shapes {};
+            enum Color { "Red", "Green" };
+            dictionary Point { f32 x; f32 y; };
+            callback interface Listener { void changed(Point point); };
+        "#;
+        let new = r#"
+            namespace shapes {};
+            enum Color { "Green", "Red", "Blue" };
+            dictionary Point { f32 y; f32 x; string? label = null; };
+            callback interface Listener {
+                void changed(Point point);
+                void removed();
+            };
+        "#;
+        let changes = diff(old, new);
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "shapes.Point.label".to_string(),
+                    "field added with a default value".to_string(),
+                    vec![],
+                ),
+                (
+                    "shapes.Point".to_string(),
+                    "fields reordered".to_string(),
+                    ALL.to_vec(),
+                ),
+                (
+                    "shapes.Color.Red".to_string(),
+                    "discriminant changed from 0 to 1".to_string(),
+                    ALL.to_vec(),
+                ),
+                (
+                    "shapes.Color.Green".to_string(),
+                    "discriminant changed from 1 to 0".to_string(),
+                    ALL.to_vec(),
+                ),
+                (
+                    "shapes.Color.Blue".to_string(),
+                    "variant added".to_string(),
+                    EXHAUSTIVE_MATCHES.to_vec(),
+                ),
+                (
+                    "shapes.Listener.removed".to_string(),
+                    "method added".to_string(),
+                    ALL.to_vec(),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let old = "namespace math { u32 add(u32 a, u32 b); };";
+        let new = "namespace math { u64 add(u32 a, u32 b); u32 sub(u32 a, u32 b); };";
+        let old = ComponentInterface::from_webidl(old).unwrap();
+        let new = ComponentInterface::from_webidl(new).unwrap();
+        let report = DiffReport::new(&InterfacesJson::new([&old]), &InterfacesJson::new([&new]));
+        assert_eq!(report.breaking_languages(), SIZED_NUMBERS);
+        assert_eq!(
+            report.to_string(),
+            "math.add: return type changed from u32 to u64 (breaking for kotlin, swift)\n\
+             math.sub: function added (additive)\n\
+             1 breaking, 1 additive changes"
+        );
+        let same = DiffReport::new(&InterfacesJson::new([&old]), &InterfacesJson::new([&old]));
+        assert!(same.changes.is_empty());
+    }
+}
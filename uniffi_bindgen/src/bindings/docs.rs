@@ -0,0 +1,78 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Helpers for rendering the Rust documentation of items, captured by the proc-macros, as the
+//! documentation of the bindings.
+
+use std::collections::HashMap;
+
+/// Split the conventional `# Arguments` section out of the Rust documentation of a function, so the
+/// bindings can render it in the conventions of their language. It's a list of items like
+/// ``* `name` - description``.
+///
+/// Returns the rest of the documentation, and the description of each argument by its Rust name.
+pub(crate) fn split_arguments_section(docstring: &str) -> (String, HashMap<String, String>) {
+    let mut body = Vec::new();
+    let mut descriptions: Vec<(String, String)> = Vec::new();
+    let mut in_section = false;
+    // Lines starting with `#` in code blocks are hidden lines of examples rather than headings.
+    let mut in_code_block = false;
+    for line in docstring.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if let Some(heading) = line.strip_prefix('#').filter(|_| !in_code_block) {
+            let heading = heading.trim_start_matches('#').trim();
+            in_section = heading == "Arguments" || heading == "Parameters";
+            if in_section {
+                continue;
+            }
+        }
+        if !in_section {
+            body.push(line);
+            continue;
+        }
+        let item = line
+            .strip_prefix("* ")
+            .or_else(|| line.strip_prefix("- "))
+            .and_then(|item| item.strip_prefix('`'))
+            .and_then(|item| item.split_once('`'));
+        match item {
+            Some((name, description)) => {
+                let description = description
+                    .trim_start_matches(|c: char| c == ' ' || c == '-' || c == ':')
+                    .to_owned();
+                descriptions.push((name.to_owned(), description));
+            }
+            // The description of the previous item continues on this line.
+            None => match descriptions.last_mut() {
+                Some((_, description)) if !line.trim().is_empty() => {
+                    description.push(' ');
+                    description.push_str(line.trim());
+                }
+                _ => (),
+            },
+        }
+    }
+    let body = body.join("\n").trim().to_owned();
+    (body, descriptions.into_iter().collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_arguments_section() {
+        let (body, descriptions) = split_arguments_section(
+            "Summary.\n\n# Arguments\n\n* `a` - The first\n  argument.\n* `b`: The second.\n\n# Errors\n\nNever.\n\n```\n# Arguments\n```",
+        );
+        assert_eq!(
+            body,
+            "Summary.\n\n# Errors\n\nNever.\n\n```\n# Arguments\n```"
+        );
+        assert_eq!(descriptions["a"], "The first argument.");
+        assert_eq!(descriptions["b"], "The second.");
+    }
+}
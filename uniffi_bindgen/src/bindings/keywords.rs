@@ -0,0 +1,133 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Renaming of the identifiers which collide with a reserved word of the foreign language.
+//!
+//! Each backend has its own list of reserved words, and a `keywords` table in its config to
+//! choose how the colliding identifiers are renamed:
+//!
+//! ```toml
+//! [bindings.python.keywords]
+//! strategy = "suffix"
+//! rename = { in = "inside" }
+//! ```
+//!
+//! The naming filters of the templates are plain functions, so the config of the bindings
+//! being rendered is made available to them with [`with_config`].
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::MergeWith;
+
+/// How to rename an identifier which collides with a reserved word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeywordStrategy {
+    /// Put the affix before the identifier, like `_in`.
+    Prefix,
+    /// Put the affix after the identifier, like `in_`.
+    Suffix,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeywordConfig {
+    strategy: Option<KeywordStrategy>,
+    affix: Option<String>,
+    /// Explicit new names of reserved words, which take precedence over the strategy.
+    #[serde(default)]
+    rename: HashMap<String, String>,
+}
+
+impl KeywordConfig {
+    /// The affix added by the strategy, `_` unless set otherwise.
+    pub fn affix(&self) -> &str {
+        self.affix.as_deref().unwrap_or("_")
+    }
+
+    /// Rename `name` if it is one of `keywords`.
+    ///
+    /// `default` is the strategy of the language when none is configured, `None` for the
+    /// languages which can quote reserved words instead, like Kotlin and Swift.
+    pub fn fixup(
+        &self,
+        name: String,
+        keywords: &[&str],
+        default: Option<KeywordStrategy>,
+    ) -> String {
+        if !keywords.contains(&name.as_str()) {
+            return name;
+        }
+        if let Some(renamed) = self.rename.get(&name) {
+            return renamed.clone();
+        }
+        match self.strategy.or(default) {
+            Some(KeywordStrategy::Prefix) => format!("{}{name}", self.affix()),
+            Some(KeywordStrategy::Suffix) => format!("{name}{}", self.affix()),
+            None => name,
+        }
+    }
+}
+
+impl MergeWith for KeywordConfig {
+    fn merge_with(&self, other: &Self) -> Self {
+        KeywordConfig {
+            strategy: self.strategy.merge_with(&other.strategy),
+            affix: self.affix.merge_with(&other.affix),
+            rename: self.rename.merge_with(&other.rename),
+        }
+    }
+}
+
+thread_local! {
+    static ACTIVE_CONFIG: RefCell<KeywordConfig> = RefCell::default();
+}
+
+/// Run `f`, typically the rendering of some bindings, with `config` as the keyword config.
+pub fn with_config<T>(config: &KeywordConfig, f: impl FnOnce() -> T) -> T {
+    let previous = ACTIVE_CONFIG.with(|active| active.replace(config.clone()));
+    let result = f();
+    ACTIVE_CONFIG.with(|active| active.replace(previous));
+    result
+}
+
+/// Rename `name` if it is one of `keywords`, following the active keyword config.
+pub fn fixup(name: String, keywords: &[&str], default: Option<KeywordStrategy>) -> String {
+    ACTIVE_CONFIG.with(|active| active.borrow().fixup(name, keywords, default))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const KEYWORDS: &[&str] = &["in", "class"];
+
+    #[test]
+    fn test_fixup() {
+        let config = KeywordConfig::default();
+        let prefix = Some(KeywordStrategy::Prefix);
+        assert_eq!(config.fixup("inner".into(), KEYWORDS, prefix), "inner");
+        assert_eq!(config.fixup("in".into(), KEYWORDS, prefix), "_in");
+        assert_eq!(config.fixup("in".into(), KEYWORDS, None), "in");
+
+        let config: KeywordConfig = toml::from_str(
+            "strategy = \"suffix\"\naffix = \"Value\"\nrename = { in = \"inside\" }",
+        )
+        .unwrap();
+        assert_eq!(config.fixup("in".into(), KEYWORDS, prefix), "inside");
+        assert_eq!(config.fixup("class".into(), KEYWORDS, prefix), "classValue");
+        assert_eq!(config.fixup("class".into(), KEYWORDS, None), "classValue");
+    }
+
+    #[test]
+    fn test_with_config() {
+        let config: KeywordConfig = toml::from_str("strategy = \"suffix\"").unwrap();
+        let renamed = with_config(&config, || fixup("in".into(), KEYWORDS, None));
+        assert_eq!(renamed, "in_");
+        // The previous config is restored afterwards.
+        assert_eq!(fixup("in".into(), KEYWORDS, None), "in");
+    }
+}
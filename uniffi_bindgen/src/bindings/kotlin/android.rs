@@ -0,0 +1,217 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Package a component as an Android library module.
+//!
+//! The output is a Gradle source module using the standard Android library layout:
+//!
+//! ```text
+//! {out_dir}/
+//!     build.gradle
+//!     consumer-rules.pro
+//!     src/main/AndroidManifest.xml
+//!     src/main/java/{package path}/{namespace}.kt
+//!     src/main/jniLibs/{abi}/lib{cdylib_name}.so
+//! ```
+//!
+//! Running `gradle assembleRelease` in that directory (or including it in an existing Gradle
+//! build) produces the `.aar`.
+
+use anyhow::{bail, Result};
+use camino::Utf8Path;
+use fs_err as fs;
+
+use super::gen_kotlin::FfiBackend;
+use super::{write_bindings, Config};
+use crate::bindings::cdylib::build_cdylib;
+use crate::interface::ComponentInterface;
+
+/// The ABIs built when none are explicitly requested.
+pub const DEFAULT_ABIS: &[&str] = &["arm64-v8a", "armeabi-v7a", "x86", "x86_64"];
+
+/// Map an Android ABI name to the Rust target triple which builds for it.
+pub fn rust_target_for_abi(abi: &str) -> Result<&'static str> {
+    Ok(match abi {
+        "arm64-v8a" => "aarch64-linux-android",
+        "armeabi-v7a" => "armv7-linux-androideabi",
+        "x86" => "i686-linux-android",
+        "x86_64" => "x86_64-linux-android",
+        _ => bail!("Unknown Android ABI: \"{abi}\""),
+    })
+}
+
+pub fn write_android_library(
+    config: &Config,
+    ci: &ComponentInterface,
+    crate_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    abis: &[&str],
+    release: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let main_dir = out_dir.join("src").join("main");
+
+    for abi in abis {
+        let target = rust_target_for_abi(abi)?;
+        let cdylib = build_cdylib(crate_root, Some(target), release)?;
+        let jni_libs_dir = main_dir.join("jniLibs").join(abi);
+        fs::create_dir_all(&jni_libs_dir)?;
+        fs::copy(
+            &cdylib,
+            jni_libs_dir.join(format!("lib{}.so", config.cdylib_name())),
+        )?;
+    }
+
+    write_android_module(config, ci, out_dir, try_format_code)
+}
+
+// Write everything but the libraries: the bindings, the manifest, `build.gradle` and the
+// Proguard/R8 rules.
+fn write_android_module(
+    config: &Config,
+    ci: &ComponentInterface,
+    out_dir: &Utf8Path,
+    try_format_code: bool,
+) -> Result<()> {
+    let main_dir = out_dir.join("src").join("main");
+    write_bindings(config, ci, &main_dir.join("java"), try_format_code)?;
+    // The package is set by the `namespace` of `build.gradle`, which AGP 8 requires rather than
+    // the `package` attribute of the manifest.
+    fs::write(
+        main_dir.join("AndroidManifest.xml"),
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<manifest />\n",
+    )?;
+    fs::write(out_dir.join("build.gradle"), build_gradle(config))?;
+    fs::write(
+        out_dir.join("consumer-rules.pro"),
+        consumer_proguard_rules(config, ci),
+    )?;
+    Ok(())
+}
+
+/// The Proguard/R8 rules which keep the parts of the bindings that JNA looks up by reflection,
+/// or that the JNI glue looks up by name with the `jni` FFI backend.
+///
+/// Android libraries ship these as "consumer" rules, which are applied when minifying the
+/// application depending on the library.
+pub fn consumer_proguard_rules(config: &Config, ci: &ComponentInterface) -> String {
+    match config.ffi_backend() {
+        FfiBackend::Jna => format!(
+            r#"# Proguard rules for the `{namespace}` component, generated by uniffi-bindgen.
+#
+# JNA finds the FFI functions, structures and callbacks of the bindings by reflection, so they
+# must not be renamed or removed by minification.
+-dontwarn java.awt.**
+-keep class com.sun.jna.** {{ *; }}
+-keep class * implements com.sun.jna.** {{ *; }}
+-keep class {package_name}._UniFFILib {{ *; }}
+-keep class {package_name}._UniFFILib$* {{ *; }}
+-keep class {package_name}.** extends com.sun.jna.Structure {{ *; }}
+-keep class {package_name}.** implements com.sun.jna.Callback {{ *; }}
+"#,
+            namespace = ci.namespace(),
+            package_name = config.package_name()
+        ),
+        FfiBackend::Jni => format!(
+            r#"# Proguard rules for the `{namespace}` component, generated by uniffi-bindgen.
+#
+# The JNI glue of the library is exported under the names of the `external` functions of the
+# bindings, and calls back into their static methods by name, so they must not be renamed or
+# removed by minification.
+-keep class {package_name}._UniFFILib {{ *; }}
+"#,
+            namespace = ci.namespace(),
+            package_name = config.package_name()
+        ),
+    }
+}
+
+fn build_gradle(config: &Config) -> String {
+    // The `jni` FFI backend has no dependency.
+    let dependencies = match config.ffi_backend() {
+        FfiBackend::Jna => format!(
+            "\ndependencies {{\n    implementation \"net.java.dev.jna:jna:{}@aar\"\n}}\n",
+            config.jna_version()
+        ),
+        FfiBackend::Jni => String::new(),
+    };
+    format!(
+        r#"plugins {{
+    id 'com.android.library'
+    id 'org.jetbrains.kotlin.android'
+}}
+
+android {{
+    namespace '{package_name}'
+    compileSdk 33
+
+    defaultConfig {{
+        minSdk 21
+        consumerProguardFiles 'consumer-rules.pro'
+    }}
+}}
+{dependencies}"#,
+        package_name = config.package_name()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rust_target_for_abi() {
+        for abi in DEFAULT_ABIS {
+            assert!(rust_target_for_abi(abi).unwrap().contains("android"));
+        }
+        assert_eq!(
+            rust_target_for_abi("arm64-v8a").unwrap(),
+            "aarch64-linux-android"
+        );
+        assert!(rust_target_for_abi("mips").is_err());
+    }
+
+    #[test]
+    fn test_consumer_proguard_rules() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let config = Config::from(&ci);
+        let rules = consumer_proguard_rules(&config, &ci);
+        assert!(rules.contains("-keep class uniffi.test._UniFFILib { *; }"));
+        assert!(rules.contains("-keep class uniffi.test.** extends com.sun.jna.Structure { *; }"));
+
+        let config: Config = toml::from_str("ffi_backend = \"jni\"").unwrap();
+        let rules = consumer_proguard_rules(&config, &ci);
+        assert!(rules.contains("-keep class uniffi._UniFFILib { *; }"));
+        assert!(!rules.contains("com.sun.jna"));
+        assert!(!build_gradle(&config).contains("net.java.dev.jna"));
+    }
+
+    #[test]
+    fn test_write_android_module() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let out_dir = crate::TempDir::new().unwrap();
+        let read = |path: &str| fs::read_to_string(out_dir.0.join(path)).unwrap();
+
+        let config: Config = toml::from_str("package_name = \"org.example.test\"").unwrap();
+        write_android_module(&config, &ci, &out_dir.0, false).unwrap();
+        assert_eq!(
+            read("src/main/AndroidManifest.xml"),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<manifest />\n"
+        );
+        assert!(
+            read("src/main/java/org/example/test/test.kt").contains("package org.example.test;")
+        );
+        assert!(
+            read("consumer-rules.pro").contains("-keep class org.example.test._UniFFILib { *; }")
+        );
+        let build_gradle = read("build.gradle");
+        assert!(build_gradle.contains("    namespace 'org.example.test'\n"));
+        assert!(build_gradle.contains("        consumerProguardFiles 'consumer-rules.pro'\n"));
+        assert!(build_gradle.contains("    implementation \"net.java.dev.jna:jna:5.12.0@aar\"\n"));
+
+        let config: Config = toml::from_str("jna_version = \"5.14.0\"").unwrap();
+        write_android_module(&config, &ci, &out_dir.0, false).unwrap();
+        assert!(read("build.gradle").contains("implementation \"net.java.dev.jna:jna:5.14.0@aar\""));
+    }
+}
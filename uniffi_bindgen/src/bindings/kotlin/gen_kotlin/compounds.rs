@@ -51,6 +51,34 @@ macro_rules! impl_code_type_for_compound {
 
 impl_code_type_for_compound!(OptionalCodeType, "{}?", "Optional{}");
 impl_code_type_for_compound!(SequenceCodeType, "List<{}>", "Sequence{}");
+impl_code_type_for_compound!(IteratorCodeType, "RustIterator<{}>", "Iterator{}");
+
+pub struct SetCodeType {
+    inner: TypeIdentifier,
+}
+
+impl SetCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for SetCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Set<{}>", oracle.find(&self.inner).type_label(oracle))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Set{}", oracle.find(&self.inner).canonical_name(oracle))
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptySequence => "setOf()".into(),
+            _ => unreachable!(),
+        }
+    }
+}
 
 pub struct MapCodeType {
     key: TypeIdentifier,
@@ -92,3 +120,73 @@ impl CodeType for MapCodeType {
         render_literal(oracle, literal, &self.value)
     }
 }
+
+pub struct TupleCodeType {
+    elements: Vec<TypeIdentifier>,
+}
+
+impl TupleCodeType {
+    pub fn new(elements: Vec<TypeIdentifier>) -> Self {
+        Self { elements }
+    }
+}
+
+impl CodeType for TupleCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        let class = match self.elements.len() {
+            2 => "Pair",
+            3 => "Triple",
+            len => unreachable!("Tuples of {len} elements aren't supported"),
+        };
+        let elements = self
+            .elements
+            .iter()
+            .map(|t| oracle.find(t).type_label(oracle))
+            .collect::<Vec<_>>();
+        format!("{class}<{}>", elements.join(", "))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        let elements = self
+            .elements
+            .iter()
+            .map(|t| oracle.find(t).canonical_name(oracle))
+            .collect::<String>();
+        format!("Tuple{}{elements}", self.elements.len())
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unreachable!("Tuples have no literals")
+    }
+}
+
+pub struct ResultCodeType {
+    ok: TypeIdentifier,
+    err: TypeIdentifier,
+}
+
+impl ResultCodeType {
+    pub fn new(ok: TypeIdentifier, err: TypeIdentifier) -> Self {
+        Self { ok, err }
+    }
+}
+
+impl CodeType for ResultCodeType {
+    // Kotlin's `Result` holds any `Throwable` on failure, the error type only shows in the
+    // canonical name.
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Result<{}>", oracle.find(&self.ok).type_label(oracle))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "Result{}{}",
+            oracle.find(&self.ok).canonical_name(oracle),
+            oracle.find(&self.err).canonical_name(oracle),
+        )
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unreachable!("Results have no literals")
+    }
+}
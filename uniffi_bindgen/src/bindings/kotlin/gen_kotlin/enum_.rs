@@ -24,14 +24,17 @@ impl CodeType for EnumCodeType {
     }
 
     fn literal(&self, oracle: &dyn CodeOracle, literal: &Literal) -> String {
-        if let Literal::Enum(v, _) = literal {
-            format!(
+        match literal {
+            Literal::Enum(v, _) => format!(
                 "{}.{}",
                 self.type_label(oracle),
                 oracle.enum_variant_name(v)
-            )
-        } else {
-            unreachable!();
+            ),
+            // The variants without fields of a `sealed class` are objects.
+            Literal::EmptyVariant(v, _) => {
+                format!("{}.{}", self.type_label(oracle), oracle.class_name(v))
+            }
+            _ => unreachable!(),
         }
     }
 }
@@ -3,14 +3,16 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use crate::backend::{CodeOracle, CodeType, Literal};
+use crate::interface::ExternalKind;
 
 pub struct ExternalCodeType {
     name: String,
+    kind: ExternalKind,
 }
 
 impl ExternalCodeType {
-    pub fn new(name: String) -> Self {
-        Self { name }
+    pub fn new(name: String, kind: ExternalKind) -> Self {
+        Self { name, kind }
     }
 }
 
@@ -23,6 +25,17 @@ impl CodeType for ExternalCodeType {
         format!("Type{}", self.name)
     }
 
+    // Errors cross the FFI of this component in its own `RustBuffer`s, so they go through
+    // a converter of its own, which reads and writes them with the external one.
+    fn ffi_converter_name(&self, _oracle: &dyn CodeOracle) -> String {
+        match self.kind {
+            ExternalKind::Error => format!("FfiConverterExternal{}", self.name),
+            ExternalKind::DataClass | ExternalKind::CallbackInterface => {
+                format!("FfiConverterType{}", self.name)
+            }
+        }
+    }
+
     fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
         unreachable!("Can't have a literal of an external type");
     }
@@ -19,8 +19,13 @@ macro_rules! impl_code_type_for_miscellany {
                    $canonical_name.into()
                }
 
-                fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
-                    unreachable!()
+                fn literal(&self, _oracle: &dyn CodeOracle, literal: &Literal) -> String {
+                    match literal {
+                        Literal::Duration(secs, nanos) => {
+                            format!("java.time.Duration.ofSeconds({secs}L, {nanos}L)")
+                        }
+                        _ => unreachable!(),
+                    }
                 }
             }
         }
@@ -30,3 +35,25 @@ macro_rules! impl_code_type_for_miscellany {
 impl_code_type_for_miscellany!(TimestampCodeType, "java.time.Instant", "Timestamp");
 
 impl_code_type_for_miscellany!(DurationCodeType, "java.time.Duration", "Duration");
+
+impl_code_type_for_miscellany!(
+    OffsetDateTimeCodeType,
+    "java.time.OffsetDateTime",
+    "OffsetDateTime"
+);
+
+impl_code_type_for_miscellany!(BigIntCodeType, "java.math.BigInteger", "BigInt");
+
+impl_code_type_for_miscellany!(DecimalCodeType, "java.math.BigDecimal", "Decimal");
+
+impl_code_type_for_miscellany!(UrlCodeType, "java.net.URI", "Url");
+
+impl_code_type_for_miscellany!(UuidCodeType, "java.util.UUID", "Uuid");
+
+impl_code_type_for_miscellany!(
+    JsonCodeType,
+    "kotlinx.serialization.json.JsonElement",
+    "Json"
+);
+
+impl_code_type_for_miscellany!(SharedSliceCodeType, "SharedSlice", "SharedSlice");
@@ -12,6 +12,7 @@ use heck::{ToLowerCamelCase, ToShoutySnakeCase, ToUpperCamelCase};
 use serde::{Deserialize, Serialize};
 
 use crate::backend::{CodeOracle, CodeType, TemplateExpression, TypeIdentifier};
+use crate::bindings::keywords::{self, KeywordConfig};
 use crate::interface::*;
 use crate::MergeWith;
 
@@ -26,15 +27,61 @@ mod object;
 mod primitives;
 mod record;
 
+// The hard keywords of Kotlin, which can only be used as identifiers when quoted with backticks.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
 // config options to customize the generated Kotlin.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Config {
     package_name: Option<String>,
     cdylib_name: Option<String>,
+    ffi_backend: Option<FfiBackend>,
+    jna_version: Option<String>,
     #[serde(default)]
     custom_types: HashMap<String, CustomTypeConfig>,
     #[serde(default)]
     external_packages: HashMap<String, String>,
+    serializable: Option<bool>,
+    sealed_interfaces: Option<bool>,
+    value_classes: Option<bool>,
+    coroutine_dispatcher: Option<String>,
+    parcelable: Option<bool>,
+    split_files: Option<bool>,
+    direct_byte_buffers: Option<bool>,
+    blocking_thread_annotation: Option<String>,
+    callback_thread_annotation: Option<String>,
+    log_forwarder: Option<bool>,
+    #[serde(default)]
+    keywords: KeywordConfig,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -45,6 +92,17 @@ pub struct CustomTypeConfig {
     from_custom: TemplateExpression,
 }
 
+/// How the bindings call the FFI functions of the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FfiBackend {
+    /// Through a JNA `Library`, which finds the functions and converts their arguments by
+    /// reflection.
+    Jna,
+    /// Through `external` functions, implemented by the JNI glue generated in the scaffolding.
+    Jni,
+}
+
 impl Config {
     pub fn package_name(&self) -> String {
         if let Some(package_name) = &self.package_name {
@@ -61,6 +119,92 @@ impl Config {
             "uniffi".into()
         }
     }
+
+    /// A copy of this config, loading the library `cdylib_name`.
+    pub(crate) fn with_cdylib_name(&self, cdylib_name: &str) -> Self {
+        Self {
+            cdylib_name: Some(cdylib_name.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn ffi_backend(&self) -> FfiBackend {
+        self.ffi_backend.unwrap_or(FfiBackend::Jna)
+    }
+
+    /// The version of JNA which the Android library modules depend on.
+    pub fn jna_version(&self) -> String {
+        self.jna_version
+            .clone()
+            .unwrap_or_else(|| "5.12.0".to_string())
+    }
+
+    /// The JVM class declaring the `external` functions of the `jni` FFI backend, whose names
+    /// the JNI glue of the scaffolding is exported under.
+    pub fn jni_class_name(&self) -> Option<String> {
+        match self.ffi_backend() {
+            FfiBackend::Jna => None,
+            FfiBackend::Jni => Some(format!("{}._UniFFILib", self.package_name())),
+        }
+    }
+
+    pub fn serializable(&self) -> bool {
+        self.serializable.unwrap_or(false)
+    }
+
+    pub fn sealed_interfaces(&self) -> bool {
+        self.sealed_interfaces.unwrap_or(false)
+    }
+
+    pub fn value_classes(&self) -> bool {
+        self.value_classes.unwrap_or(false)
+    }
+
+    pub fn coroutine_dispatcher(&self) -> Option<&str> {
+        self.coroutine_dispatcher.as_deref()
+    }
+
+    pub fn parcelable(&self) -> bool {
+        self.parcelable.unwrap_or(false)
+    }
+
+    pub fn split_files(&self) -> bool {
+        self.split_files.unwrap_or(false)
+    }
+
+    pub fn direct_byte_buffers(&self) -> bool {
+        self.direct_byte_buffers.unwrap_or(false)
+    }
+
+    /// The annotation to put on functions, methods and constructors which block on a call into
+    /// Rust, such as `@androidx.annotation.WorkerThread`.
+    pub fn blocking_thread_annotation(&self) -> Option<String> {
+        self.blocking_thread_annotation
+            .as_deref()
+            .map(thread_annotation)
+    }
+
+    /// The annotation to put on callback interface methods, such as
+    /// `@androidx.annotation.MainThread`.
+    pub fn callback_thread_annotation(&self) -> Option<String> {
+        self.callback_thread_annotation
+            .as_deref()
+            .map(thread_annotation)
+    }
+
+    pub fn log_forwarder(&self) -> bool {
+        self.log_forwarder.unwrap_or(false)
+    }
+}
+
+// Thread annotations are given by their name in `androidx.annotation`, or by their fully
+// qualified name.
+fn thread_annotation(name: &str) -> String {
+    if name.contains('.') {
+        format!("@{name}")
+    } else {
+        format!("@androidx.annotation.{name}")
+    }
 }
 
 impl From<&ComponentInterface> for Config {
@@ -68,8 +212,7 @@ impl From<&ComponentInterface> for Config {
         Config {
             package_name: Some(format!("uniffi.{}", ci.namespace())),
             cdylib_name: Some(format!("uniffi_{}", ci.namespace())),
-            custom_types: HashMap::new(),
-            external_packages: HashMap::new(),
+            ..Default::default()
         }
     }
 }
@@ -79,17 +222,101 @@ impl MergeWith for Config {
         Config {
             package_name: self.package_name.merge_with(&other.package_name),
             cdylib_name: self.cdylib_name.merge_with(&other.cdylib_name),
+            ffi_backend: self.ffi_backend.merge_with(&other.ffi_backend),
+            jna_version: self.jna_version.merge_with(&other.jna_version),
             custom_types: self.custom_types.merge_with(&other.custom_types),
             external_packages: self.external_packages.merge_with(&other.external_packages),
+            serializable: self.serializable.merge_with(&other.serializable),
+            sealed_interfaces: self.sealed_interfaces.merge_with(&other.sealed_interfaces),
+            value_classes: self.value_classes.merge_with(&other.value_classes),
+            coroutine_dispatcher: self
+                .coroutine_dispatcher
+                .merge_with(&other.coroutine_dispatcher),
+            parcelable: self.parcelable.merge_with(&other.parcelable),
+            split_files: self.split_files.merge_with(&other.split_files),
+            direct_byte_buffers: self
+                .direct_byte_buffers
+                .merge_with(&other.direct_byte_buffers),
+            blocking_thread_annotation: self
+                .blocking_thread_annotation
+                .merge_with(&other.blocking_thread_annotation),
+            callback_thread_annotation: self
+                .callback_thread_annotation
+                .merge_with(&other.callback_thread_annotation),
+            log_forwarder: self.log_forwarder.merge_with(&other.log_forwarder),
+            keywords: self.keywords.merge_with(&other.keywords),
         }
     }
 }
 
 // Generate kotlin bindings for the given ComponentInterface, as a string.
 pub fn generate_bindings(config: &Config, ci: &ComponentInterface) -> Result<String> {
-    KotlinWrapper::new(config.clone(), ci)
-        .render()
-        .context("failed to render kotlin bindings")
+    keywords::with_config(&config.keywords, || {
+        KotlinWrapper::new(config.clone(), ci, KotlinFile::All).render()
+    })
+    .context("failed to render kotlin bindings")
+}
+
+// Generate kotlin bindings for the given ComponentInterface, as a list of file names and their
+// contents.  Unless the `split_files` option is set, there is a single file.
+pub fn generate_bindings_files(
+    config: &Config,
+    ci: &ComponentInterface,
+) -> Result<Vec<(String, String)>> {
+    let files = if config.split_files() {
+        vec![
+            KotlinFile::Internals,
+            KotlinFile::Types,
+            KotlinFile::Functions,
+        ]
+    } else {
+        vec![KotlinFile::All]
+    };
+    files
+        .into_iter()
+        .map(|file| {
+            let contents = keywords::with_config(&config.keywords, || {
+                KotlinWrapper::new(config.clone(), ci, file).render()
+            })
+            .with_context(|| format!("failed to render kotlin bindings for {file:?}"))?;
+            Ok((file.file_name(ci.namespace()), contents))
+        })
+        .collect()
+}
+
+/// The parts of the generated bindings which go in a given file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KotlinFile {
+    /// Everything, in a single file.
+    All,
+    /// The FFI declarations and the runtime helpers.
+    Internals,
+    /// The records, enums, errors, objects and other types.
+    Types,
+    /// The top-level functions.
+    Functions,
+}
+
+impl KotlinFile {
+    pub fn file_name(&self, namespace: &str) -> String {
+        match self {
+            KotlinFile::All | KotlinFile::Internals => format!("{namespace}.kt"),
+            KotlinFile::Types => format!("{namespace}_types.kt"),
+            KotlinFile::Functions => format!("{namespace}_functions.kt"),
+        }
+    }
+
+    pub fn includes_internals(&self) -> bool {
+        matches!(self, KotlinFile::All | KotlinFile::Internals)
+    }
+
+    pub fn includes_types(&self) -> bool {
+        matches!(self, KotlinFile::All | KotlinFile::Types)
+    }
+
+    pub fn includes_functions(&self) -> bool {
+        matches!(self, KotlinFile::All | KotlinFile::Functions)
+    }
 }
 
 /// Renders Kotlin helper code for all types
@@ -154,23 +381,35 @@ impl<'a> TypeRenderer<'a> {
 pub struct KotlinWrapper<'a> {
     config: Config,
     ci: &'a ComponentInterface,
+    file: KotlinFile,
     type_helper_code: String,
     type_imports: BTreeSet<String>,
 }
 
 impl<'a> KotlinWrapper<'a> {
-    pub fn new(config: Config, ci: &'a ComponentInterface) -> Self {
+    pub fn new(config: Config, ci: &'a ComponentInterface, file: KotlinFile) -> Self {
         let type_renderer = TypeRenderer::new(&config, ci);
         let type_helper_code = type_renderer.render().unwrap();
         let type_imports = type_renderer.imports.into_inner();
         Self {
             config,
             ci,
+            file,
             type_helper_code,
             type_imports,
         }
     }
 
+    // The visibility of the runtime helpers, which need to be visible from the other files
+    // when the bindings are split.
+    pub fn helpers_visibility(&self) -> &'static str {
+        if self.file == KotlinFile::All {
+            "private"
+        } else {
+            "internal"
+        }
+    }
+
     pub fn initialization_fns(&self) -> Vec<String> {
         self.ci
             .iter_types()
@@ -181,8 +420,41 @@ impl<'a> KotlinWrapper<'a> {
     pub fn imports(&self) -> Vec<String> {
         self.type_imports.iter().cloned().collect()
     }
+
+    /// The size of the scratch memory of the `jni` FFI backend, see `uniffi/src/ffi/jni.rs`: the
+    /// call status, then the largest return value and struct arguments of an FFI function. The
+    /// callbacks return a `RustBuffer` there too.
+    pub fn jni_scratch_size(&self) -> usize {
+        let size_of = |type_: &FFIType| match type_ {
+            FFIType::RustBuffer => JNI_RUST_BUFFER_SIZE,
+            FFIType::Struct(name) => self
+                .ci
+                .get_record_definition(name)
+                .map_or(0, |rec| rec.fields().len() * JNI_SLOT_SIZE),
+            _ => 0,
+        };
+        let largest = self
+            .ci
+            .iter_ffi_function_definitions()
+            .map(|func| {
+                func.return_type().map_or(0, size_of)
+                    + func
+                        .arguments()
+                        .iter()
+                        .map(|arg| size_of(&arg.type_()))
+                        .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0);
+        JNI_RETURN_OFFSET + largest.max(JNI_RUST_BUFFER_SIZE)
+    }
 }
 
+// The layout of the scratch memory of the `jni` FFI backend, see `uniffi/src/ffi/jni.rs`.
+const JNI_RETURN_OFFSET: usize = 32;
+const JNI_RUST_BUFFER_SIZE: usize = 16;
+const JNI_SLOT_SIZE: usize = 8;
+
 #[derive(Clone)]
 pub struct KotlinCodeOracle;
 
@@ -207,13 +479,21 @@ impl KotlinCodeOracle {
             Type::Float32 => Box::new(primitives::Float32CodeType),
             Type::Float64 => Box::new(primitives::Float64CodeType),
             Type::Boolean => Box::new(primitives::BooleanCodeType),
+            Type::Char => Box::new(primitives::CharCodeType),
             Type::String => Box::new(primitives::StringCodeType),
 
             Type::Timestamp => Box::new(miscellany::TimestampCodeType),
+            Type::OffsetDateTime => Box::new(miscellany::OffsetDateTimeCodeType),
+            Type::BigInt => Box::new(miscellany::BigIntCodeType),
+            Type::Decimal => Box::new(miscellany::DecimalCodeType),
+            Type::Url => Box::new(miscellany::UrlCodeType),
+            Type::Uuid => Box::new(miscellany::UuidCodeType),
+            Type::Json => Box::new(miscellany::JsonCodeType),
+            Type::SharedSlice => Box::new(miscellany::SharedSliceCodeType),
             Type::Duration => Box::new(miscellany::DurationCodeType),
 
             Type::Enum(id) => Box::new(enum_::EnumCodeType::new(id)),
-            Type::Object(id) => Box::new(object::ObjectCodeType::new(id)),
+            Type::Object { name, .. } => Box::new(object::ObjectCodeType::new(name)),
             Type::Record(id) => Box::new(record::RecordCodeType::new(id)),
             Type::Error(id) => Box::new(error::ErrorCodeType::new(id)),
             Type::CallbackInterface(id) => {
@@ -221,8 +501,15 @@ impl KotlinCodeOracle {
             }
             Type::Optional(inner) => Box::new(compounds::OptionalCodeType::new(*inner)),
             Type::Sequence(inner) => Box::new(compounds::SequenceCodeType::new(*inner)),
+            Type::Set(inner) => Box::new(compounds::SetCodeType::new(*inner)),
             Type::Map(key, value) => Box::new(compounds::MapCodeType::new(*key, *value)),
-            Type::External { name, .. } => Box::new(external::ExternalCodeType::new(name)),
+            Type::Tuple(elements) => Box::new(compounds::TupleCodeType::new(elements)),
+            Type::Result(ok, err) => Box::new(compounds::ResultCodeType::new(*ok, *err)),
+            Type::Weak(inner) => Box::new(object::WeakCodeType::new(*inner)),
+            Type::Iterator(inner) => Box::new(compounds::IteratorCodeType::new(*inner)),
+            Type::External { name, kind, .. } => {
+                Box::new(external::ExternalCodeType::new(name, kind))
+            }
             Type::Custom { name, .. } => Box::new(custom::CustomCodeType::new(name)),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling create_code_type")
@@ -231,6 +518,11 @@ impl KotlinCodeOracle {
     }
 }
 
+// Names are quoted with backticks, so reserved words are only renamed when configured to.
+fn fixup_keyword(name: String) -> String {
+    keywords::fixup(name, KEYWORDS, None)
+}
+
 impl CodeOracle for KotlinCodeOracle {
     fn find(&self, type_: &TypeIdentifier) -> Box<dyn CodeType> {
         self.create_code_type(type_.clone())
@@ -243,12 +535,12 @@ impl CodeOracle for KotlinCodeOracle {
 
     /// Get the idiomatic Kotlin rendering of a function name.
     fn fn_name(&self, nm: &str) -> String {
-        format!("`{}`", nm.to_string().to_lower_camel_case())
+        format!("`{}`", fixup_keyword(nm.to_string().to_lower_camel_case()))
     }
 
     /// Get the idiomatic Kotlin rendering of a variable name.
     fn var_name(&self, nm: &str) -> String {
-        format!("`{}`", nm.to_string().to_lower_camel_case())
+        format!("`{}`", fixup_keyword(nm.to_string().to_lower_camel_case()))
     }
 
     /// Get the idiomatic Kotlin rendering of an individual enum variant.
@@ -285,6 +577,7 @@ impl CodeOracle for KotlinCodeOracle {
             FFIType::RustBuffer => "RustBuffer.ByValue".to_string(),
             FFIType::ForeignBytes => "ForeignBytes.ByValue".to_string(),
             FFIType::ForeignCallback => "ForeignCallback".to_string(),
+            FFIType::Struct(name) => format!("FfiStruct{}.ByValue", self.class_name(name)),
         }
     }
 }
@@ -308,6 +601,15 @@ pub mod filters {
         Ok(codetype.ffi_converter_name(oracle()))
     }
 
+    /// The `CallStatusErrorHandler` lifting the errors thrown by a function: the companion object
+    /// of the exception for the errors of this component, or a generated one for external errors.
+    pub fn error_handler(type_: &Type) -> Result<String, askama::Error> {
+        Ok(match type_ {
+            Type::External { .. } => format!("{}ExternalErrorHandler", type_name(type_)?),
+            _ => type_name(type_)?,
+        })
+    }
+
     pub fn lower_fn(codetype: &impl CodeType) -> Result<String, askama::Error> {
         Ok(format!("{}.lower", codetype.ffi_converter_name(oracle())))
     }
@@ -343,6 +645,154 @@ pub mod filters {
         Ok(oracle().ffi_type_label(type_))
     }
 
+    /// Get the Kotlin syntax for a low-level `FFIType` with the `jni` FFI backend, which passes
+    /// the pointers as their address.
+    pub fn jni_ffi_type_name(type_: &FFIType) -> Result<String, askama::Error> {
+        Ok(match type_ {
+            FFIType::RustArcPtr(_) => "Long".into(),
+            type_ => oracle().ffi_type_label(type_),
+        })
+    }
+
+    /// The suffix of the `ByteBuffer` methods reading and writing a field of an FFI struct with the
+    /// `jni` FFI backend, like `Int` for `getInt` and `putInt`.
+    pub fn jni_byte_buffer_suffix(type_: &FFIType) -> Result<String, askama::Error> {
+        Ok(match type_ {
+            FFIType::Int8 | FFIType::UInt8 => "",
+            FFIType::Int16 | FFIType::UInt16 => "Short",
+            FFIType::Int32 | FFIType::UInt32 => "Int",
+            FFIType::Int64 | FFIType::UInt64 => "Long",
+            FFIType::Float32 => "Float",
+            FFIType::Float64 => "Double",
+            _ => unreachable!("the fields of FFI structs are numbers"),
+        }
+        .into())
+    }
+
+    /// Whether an FFI function installs the callback of a callback interface, which the `jni`
+    /// FFI backend calls through a static method.
+    pub fn has_foreign_callback(func: &FFIFunction) -> Result<bool, askama::Error> {
+        Ok(func
+            .arguments()
+            .iter()
+            .any(|arg| arg.type_() == FFIType::ForeignCallback))
+    }
+
+    /// The parameters of the `external` function which the `jni` FFI backend declares for an FFI
+    /// function, which are all numbers.
+    pub fn jni_arg_list_decl(func: &FFIFunction) -> Result<String, askama::Error> {
+        Ok(func
+            .arguments()
+            .into_iter()
+            .flat_map(|arg| jni_params(arg.name(), &arg.type_()))
+            .map(|(name, type_, _)| format!("{name}: {type_}"))
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+
+    /// The return type of the `external` function which the `jni` FFI backend declares for an FFI
+    /// function.
+    pub fn jni_return_type(func: &FFIFunction) -> Result<String, askama::Error> {
+        Ok(match func.return_type() {
+            None | Some(FFIType::RustBuffer | FFIType::Struct(_)) => "Unit".into(),
+            Some(type_) => jni_ffi_type_name(type_)?,
+        })
+    }
+
+    /// The body of the function of `_UniFFILib` which calls the `external` function of an FFI
+    /// function with the `jni` FFI backend.
+    ///
+    /// The struct arguments are written to the scratch memory of the thread after the return
+    /// value, in their order, and the call status and the return value which JNI can't return
+    /// are read from it after the call, see `uniffi/src/ffi/jni.rs`.
+    pub fn jni_call(func: &FFIFunction) -> Result<String, askama::Error> {
+        let mut statements = vec!["val _uniffi_scratch = uniffiJniScratch.get()".to_string()];
+        let mut offset = "UNIFFI_JNI_RETURN_OFFSET".to_string();
+        match func.return_type() {
+            Some(FFIType::RustBuffer) => offset.push_str(" + UNIFFI_JNI_RUST_BUFFER_SIZE"),
+            Some(FFIType::Struct(name)) => {
+                offset.push_str(&format!(" + {}.SIZE", ffi_struct_class_name(name)))
+            }
+            _ => (),
+        }
+        let mut args = Vec::new();
+        for arg in func.arguments() {
+            let var = oracle().var_name(arg.name());
+            match arg.type_() {
+                FFIType::Struct(name) => {
+                    statements.push(format!("{var}.jniWrite(_uniffi_scratch, {offset})"));
+                    offset.push_str(&format!(" + {}.SIZE", ffi_struct_class_name(&name)));
+                }
+                FFIType::ForeignCallback => {
+                    statements.push(format!("{} = {var}", jni_callback_field(func)?))
+                }
+                type_ => args.extend(
+                    jni_params(arg.name(), &type_)
+                        .into_iter()
+                        .map(|(_, _, lower)| lower),
+                ),
+            }
+        }
+        let call = format!("jni_{}({})", func.name(), args.join(", "));
+        let read_status = "uniffiJniReadStatus(_uniffi_scratch, _uniffi_out_err)";
+        match func.return_type() {
+            None => statements.extend([call, read_status.into()]),
+            Some(FFIType::RustBuffer) => statements.extend([
+                call,
+                read_status.into(),
+                "return uniffiJniReadRustBuffer(_uniffi_scratch, UNIFFI_JNI_RETURN_OFFSET)".into(),
+            ]),
+            Some(FFIType::Struct(name)) => statements.extend([
+                call,
+                read_status.into(),
+                format!(
+                    "return {}.jniRead(_uniffi_scratch, UNIFFI_JNI_RETURN_OFFSET)",
+                    ffi_struct_class_name(name)
+                ),
+            ]),
+            Some(_) => statements.extend([
+                format!("val _uniffi_result = {call}"),
+                read_status.into(),
+                "return _uniffi_result".into(),
+            ]),
+        }
+        Ok(statements.join("\n        "))
+    }
+
+    /// The field of `_UniFFILib` holding the callback installed by an FFI function, with the
+    /// `jni` FFI backend.
+    pub fn jni_callback_field(func: &FFIFunction) -> Result<String, askama::Error> {
+        Ok(format!("{}_callback", func.name()))
+    }
+
+    // The parameters of the `external` function passing an argument of an FFI function, with their
+    // Kotlin type and the expression lowering the argument to them: the `RustBuffer`s and
+    // `ForeignBytes` are passed as their fields. The structs and the callbacks aren't passed.
+    fn jni_params(name: &str, type_: &FFIType) -> Vec<(String, String, String)> {
+        let var = oracle().var_name(name);
+        let field = |suffix: &str| oracle().var_name(&format!("{name}_{suffix}"));
+        match type_ {
+            FFIType::RustBuffer => vec![
+                (field("capacity"), "Int".into(), format!("{var}.capacity")),
+                (field("len"), "Int".into(), format!("{var}.len")),
+                (field("data"), "Long".into(), format!("{var}.data")),
+            ],
+            FFIType::ForeignBytes => vec![
+                (field("len"), "Int".into(), format!("{var}.len")),
+                (field("data"), "Long".into(), format!("{var}.data")),
+            ],
+            FFIType::RustArcPtr(_) => vec![(var.clone(), "Long".into(), var)],
+            FFIType::ForeignCallback | FFIType::Struct(_) => vec![],
+            _ => vec![(var.clone(), oracle().ffi_type_label(type_), var)],
+        }
+    }
+
+    // The class of the struct of a record, which the `jni` FFI backend writes to and reads from
+    // the scratch memory.
+    fn ffi_struct_class_name(name: &str) -> String {
+        format!("FfiStruct{}", oracle().class_name(name))
+    }
+
     /// Get the idiomatic Kotlin rendering of a class name (for enums, records, errors, etc).
     pub fn class_name(nm: &str) -> Result<String, askama::Error> {
         Ok(oracle().class_name(nm))
@@ -353,6 +803,75 @@ pub mod filters {
         Ok(oracle().fn_name(nm))
     }
 
+    /// Get the idiomatic Kotlin rendering of the name of a function's `suspend` variant.
+    pub fn async_fn_name(nm: &str) -> Result<String, askama::Error> {
+        Ok(oracle().fn_name(&format!("{nm}_async")))
+    }
+
+    /// Get the idiomatic Kotlin rendering of the name of a function's `RustByteBuffer` variant.
+    pub fn direct_fn_name(nm: &str) -> Result<String, askama::Error> {
+        Ok(oracle().fn_name(&format!("{nm}_direct")))
+    }
+
+    /// Whether a type is a `sequence<u8>`, which the `direct_byte_buffers` option passes as a
+    /// `RustByteBuffer`.
+    pub fn is_bytes(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(is_bytes_type(type_))
+    }
+
+    /// Whether a type is a `sequence<string>`, whose strings share their length prefix with the
+    /// compact encoding.
+    pub fn is_strings(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::String))
+    }
+
+    /// Whether any of the arguments is a `sequence<u8>`.
+    pub fn has_bytes_argument<'a>(
+        arguments: impl Borrow<Vec<&'a Argument>>,
+    ) -> Result<bool, askama::Error> {
+        Ok(arguments
+            .borrow()
+            .iter()
+            .any(|arg| is_bytes_type(arg.type_())))
+    }
+
+    /// Whether a return type is a `sequence<u8>`.
+    pub fn is_bytes_return<'a>(
+        return_type: impl Borrow<Option<&'a Type>>,
+    ) -> Result<bool, askama::Error> {
+        Ok(return_type.borrow().map_or(false, is_bytes_type))
+    }
+
+    fn is_bytes_type(type_: &Type) -> bool {
+        matches!(type_, Type::Sequence(inner) if **inner == Type::UInt8)
+    }
+
+    /// Render the Rust documentation of a declaration as a KDoc comment.
+    ///
+    /// The comment starts with a newline, so it goes right before the newline which starts the
+    /// declaration, and `indent` is the indentation of the declaration. Declarations without
+    /// documentation render nothing.
+    pub fn docstring<'a>(
+        docstring: impl Borrow<Option<&'a str>>,
+        indent: &str,
+    ) -> Result<String, askama::Error> {
+        let docstring = match docstring.borrow() {
+            Some(docstring) => docstring,
+            None => return Ok(String::new()),
+        };
+        let mut kdoc = format!("\n{indent}/**");
+        // A `*/` in the documentation would end the comment early.
+        for line in docstring.replace("*/", "*&#47;").lines() {
+            if line.is_empty() {
+                kdoc.push_str(&format!("\n{indent} *"));
+            } else {
+                kdoc.push_str(&format!("\n{indent} * {line}"));
+            }
+        }
+        kdoc.push_str(&format!("\n{indent} */"));
+        Ok(kdoc)
+    }
+
     /// Get the idiomatic Kotlin rendering of a variable name.
     pub fn var_name(nm: &str) -> Result<String, askama::Error> {
         Ok(oracle().var_name(nm))
@@ -363,6 +882,11 @@ pub mod filters {
         Ok(oracle().enum_variant_name(nm))
     }
 
+    /// Get the idiomatic Kotlin rendering of a constant name.
+    pub fn const_name(nm: &str) -> Result<String, askama::Error> {
+        Ok(nm.to_string().to_shouty_snake_case())
+    }
+
     /// Get the idiomatic Kotlin rendering of an exception name, replacing
     /// `Error` with `Exception`.
     pub fn exception_name(nm: &str) -> Result<String, askama::Error> {
@@ -378,3 +902,6 @@ pub mod filters {
         Ok(nm.trim_matches('`').to_string())
     }
 }
+
+#[cfg(test)]
+mod tests;
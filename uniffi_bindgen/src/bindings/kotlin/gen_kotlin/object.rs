@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use crate::backend::{CodeOracle, CodeType, Literal};
+use crate::backend::{CodeOracle, CodeType, Literal, TypeIdentifier};
 
 pub struct ObjectCodeType {
     id: String,
@@ -27,3 +27,27 @@ impl CodeType for ObjectCodeType {
         unreachable!();
     }
 }
+
+pub struct WeakCodeType {
+    inner: TypeIdentifier,
+}
+
+impl WeakCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for WeakCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Weak{}", oracle.find(&self.inner).type_label(oracle))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Weak{}", oracle.find(&self.inner).canonical_name(oracle))
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unreachable!();
+    }
+}
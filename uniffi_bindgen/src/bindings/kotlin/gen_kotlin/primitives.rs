@@ -11,6 +11,9 @@ fn render_literal(_oracle: &dyn CodeOracle, literal: &Literal) -> String {
         match type_ {
             // Bytes, Shorts and Ints can all be inferred from the type.
             Type::Int8 | Type::Int16 | Type::Int32 => num_str,
+            // Kotlin reads `-9223372036854775808L` as the negation of a literal which doesn't fit
+            // in a `Long`.
+            Type::Int64 if num_str == i64::MIN.to_string() => "Long.MIN_VALUE".into(),
             Type::Int64 => format!("{num_str}L"),
 
             Type::UInt8 | Type::UInt16 | Type::UInt32 => format!("{num_str}u"),
@@ -24,7 +27,7 @@ fn render_literal(_oracle: &dyn CodeOracle, literal: &Literal) -> String {
 
     match literal {
         Literal::Boolean(v) => format!("{v}"),
-        Literal::String(s) => format!("\"{s}\""),
+        Literal::String(s) => format!("\"{}\"", escape_string(s)),
         Literal::Int(i, radix, type_) => typed_number(
             type_,
             match radix {
@@ -47,6 +50,21 @@ fn render_literal(_oracle: &dyn CodeOracle, literal: &Literal) -> String {
     }
 }
 
+/// Escape a string for a Kotlin string literal, where `$` starts a template expression.
+fn escape_string(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\\' => "\\\\".into(),
+            '"' => "\\\"".into(),
+            '$' => "\\$".into(),
+            '\n' => "\\n".into(),
+            '\r' => "\\r".into(),
+            '\t' => "\\t".into(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
 macro_rules! impl_code_type_for_primitive {
     ($T:ty, $class_name:literal) => {
         paste! {
@@ -66,6 +84,7 @@ macro_rules! impl_code_type_for_primitive {
 }
 
 impl_code_type_for_primitive!(BooleanCodeType, "Boolean");
+impl_code_type_for_primitive!(CharCodeType, "Char");
 impl_code_type_for_primitive!(StringCodeType, "String");
 impl_code_type_for_primitive!(Int8CodeType, "Byte");
 impl_code_type_for_primitive!(Int16CodeType, "Short");
@@ -77,3 +96,31 @@ impl_code_type_for_primitive!(UInt32CodeType, "UInt");
 impl_code_type_for_primitive!(UInt64CodeType, "ULong");
 impl_code_type_for_primitive!(Float32CodeType, "Float");
 impl_code_type_for_primitive!(Float64CodeType, "Double");
+
+#[cfg(test)]
+mod test {
+    use super::super::KotlinCodeOracle;
+    use super::*;
+
+    #[test]
+    fn test_render_string_literal() {
+        let render = |s: &str| render_literal(&KotlinCodeOracle, &Literal::String(s.into()));
+        assert_eq!(render("Hello"), r#""Hello""#);
+        assert_eq!(render("${name} costs $5"), r#""\${name} costs \$5""#);
+        assert_eq!(render(r"C:\temp"), r#""C:\\temp""#);
+        assert_eq!(render("say \"hi\"\n"), r#""say \"hi\"\n""#);
+    }
+
+    #[test]
+    fn test_render_int64_literal() {
+        let render = |i| {
+            render_literal(
+                &KotlinCodeOracle,
+                &Literal::Int(i, Radix::Decimal, Type::Int64),
+            )
+        };
+        assert_eq!(render(-16), "-16L");
+        assert_eq!(render(i64::MAX), "9223372036854775807L");
+        assert_eq!(render(i64::MIN), "Long.MIN_VALUE");
+    }
+}
@@ -0,0 +1,258 @@
+use super::{generate_bindings, generate_bindings_files, Config};
+use crate::interface::ComponentInterface;
+
+const UDL: &str = r#"
+    namespace test {
+        Point origin();
+        Guid new_guid(Url base);
+        [Throws=DrawError]
+        void log_with(Logger logger);
+    };
+    [Error]
+    enum DrawError { "Empty" };
+    callback interface Logger {
+        void log(string message);
+    };
+    [Custom]
+    typedef string Guid;
+    [Custom]
+    typedef string Url;
+    dictionary Point {
+        i32 x;
+        i32 y;
+    };
+    enum Direction { "North", "South" };
+    [Enum]
+    interface Shape {
+        Dot(Point at);
+        Empty();
+    };
+    interface Canvas {
+        constructor();
+        void draw(Shape shape);
+    };
+    dictionary Layer {
+        Canvas canvas;
+    };
+"#;
+
+fn render(config: &str) -> String {
+    let ci = ComponentInterface::from_webidl(UDL).unwrap();
+    let config: Config = toml::from_str(config).unwrap();
+    generate_bindings(&config, &ci).unwrap()
+}
+
+fn render_files(config: &str) -> Vec<(String, String)> {
+    let ci = ComponentInterface::from_webidl(UDL).unwrap();
+    let config: Config = toml::from_str(config).unwrap();
+    generate_bindings_files(&config, &ci).unwrap()
+}
+
+#[test]
+fn serializable() {
+    assert!(!render("").contains("kotlinx.serialization"));
+
+    let kotlin = render("serializable = true");
+    assert!(kotlin.contains(concat!(
+        "@kotlinx.serialization.Serializable\n",
+        "@kotlinx.serialization.SerialName(\"Point\")\n",
+        "data class Point",
+    )));
+    assert!(kotlin.contains(r#"@kotlinx.serialization.SerialName("x") var `x`: Int"#));
+    assert!(kotlin.contains(r#"@kotlinx.serialization.SerialName("North") NORTH(0L)"#));
+    assert!(kotlin.contains(concat!(
+        "    @kotlinx.serialization.Serializable\n",
+        "    @kotlinx.serialization.SerialName(\"Dot\")\n",
+        "    data class Dot(",
+    )));
+    // Records holding objects can't be serialized.
+    assert!(!kotlin.contains("SerialName(\"Layer\")"));
+}
+
+#[test]
+fn sealed_interfaces() {
+    let kotlin = render("");
+    assert!(kotlin.contains("sealed class Shape {"));
+    assert!(kotlin.contains(") : Shape()"));
+    assert!(kotlin.contains("    object Empty : Shape()"));
+
+    let kotlin = render("sealed_interfaces = true");
+    assert!(kotlin.contains("sealed interface Shape {"));
+    assert!(kotlin.contains(") : Shape\n"));
+    assert!(kotlin.contains("    data object Empty : Shape\n"));
+    assert!(!kotlin.contains("sealed class Shape"));
+    // Flat enums are still enum classes.
+    assert!(kotlin.contains("enum class Direction"));
+}
+
+#[test]
+fn value_classes() {
+    let kotlin = render("");
+    assert!(kotlin.contains("public typealias Guid = String"));
+    assert!(!kotlin.contains("value class"));
+
+    let kotlin = render(concat!(
+        "value_classes = true\n",
+        "[custom_types.Url]\n",
+        "type_name = \"java.net.URL\"\n",
+        "into_custom = \"java.net.URL({})\"\n",
+        "from_custom = \"{}.toString()\"\n",
+    ));
+    assert!(kotlin.contains("@JvmInline\npublic value class Guid(val value: String)"));
+    assert!(kotlin.contains("return Guid(FfiConverterString.lift(value))"));
+    assert!(kotlin.contains("return FfiConverterString.lower(value.value)"));
+    assert!(!kotlin.contains("typealias Guid"));
+    // The custom types with a configuration keep it.
+    assert!(kotlin.contains("public typealias Url = java.net.URL"));
+    assert!(!kotlin.contains("value class Url"));
+}
+
+#[test]
+fn coroutine_dispatcher() {
+    let kotlin = render("");
+    assert!(!kotlin.contains("UniFFICoroutineDispatcher"));
+    assert!(!kotlin.contains("suspend fun"));
+
+    let kotlin = render("coroutine_dispatcher = \"kotlinx.coroutines.Dispatchers.IO\"");
+    assert!(kotlin.contains(concat!(
+        "object UniFFICoroutineDispatcher {\n",
+        "    @Volatile\n",
+        "    var dispatcher: kotlinx.coroutines.CoroutineDispatcher = ",
+        "kotlinx.coroutines.Dispatchers.IO\n",
+        "}",
+    )));
+    // Both the functions and the methods get a `suspend` variant.
+    assert!(kotlin.contains(concat!(
+        "suspend fun `originAsync`(): Point =\n",
+        "        kotlinx.coroutines.withContext(UniFFICoroutineDispatcher.dispatcher) {\n",
+        "            `origin`()\n",
+        "        }",
+    )));
+    assert!(kotlin.contains(concat!(
+        "suspend fun `drawAsync`(`shape`: Shape) =\n",
+        "        kotlinx.coroutines.withContext(UniFFICoroutineDispatcher.dispatcher) {\n",
+        "            `draw`(`shape`)\n",
+        "        }",
+    )));
+}
+
+#[test]
+fn parcelable() {
+    assert!(!render("").contains("Parcel"));
+
+    let kotlin = render("parcelable = true");
+    assert!(kotlin.contains("@kotlinx.parcelize.Parcelize\ndata class Point ("));
+    assert!(kotlin.contains("    var `y`: Int\n) : android.os.Parcelable {"));
+    assert!(kotlin.contains("sealed class Shape: android.os.Parcelable  {"));
+    assert!(kotlin.contains("    @kotlinx.parcelize.Parcelize\n    data class Dot("));
+    assert!(kotlin.contains("    @kotlinx.parcelize.Parcelize\n    object Empty : Shape()"));
+    // Records holding objects can't be parcelled.
+    assert!(kotlin.contains("data class Layer (\n    var `canvas`: Canvas\n) : Disposable {"));
+    assert!(!kotlin.contains("@kotlinx.parcelize.Parcelize\ndata class Layer"));
+}
+
+// The names of the top-level declarations of `kotlin` which are private to its file.
+fn private_declarations(kotlin: &str) -> Vec<&str> {
+    kotlin
+        .lines()
+        .filter_map(|line| line.strip_prefix("private "))
+        .filter_map(|decl| {
+            let keyword = ["fun ", "class ", "object ", "interface ", "val ", "var "]
+                .into_iter()
+                .find_map(|keyword| decl.find(keyword).map(|i| i + keyword.len()))?;
+            // Skip the type parameters of generic functions.
+            let decl = match decl[keyword..].strip_prefix('<') {
+                Some(generic) => generic.split_once("> ")?.1,
+                None => &decl[keyword..],
+            };
+            decl.split(|c: char| !c.is_alphanumeric() && c != '_')
+                .next()
+        })
+        .collect()
+}
+
+fn uses_name(kotlin: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    kotlin.match_indices(name).any(|(i, _)| {
+        !kotlin[..i].ends_with(is_ident) && !kotlin[i + name.len()..].starts_with(is_ident)
+    })
+}
+
+#[test]
+fn split_files() {
+    let files = render_files("");
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].0, "test.kt");
+
+    let files = render_files("split_files = true");
+    let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["test.kt", "test_types.kt", "test_functions.kt"]);
+    let (internals, types, functions) = (&files[0].1, &files[1].1, &files[2].1);
+    assert!(internals.contains("internal interface _UniFFILib : Library"));
+    assert!(internals.contains("internal open class RustCallStatus"));
+    assert!(types.contains("data class Point ("));
+    assert!(types.contains("class Canvas("));
+    assert!(types.contains("public object FfiConverterTypeLogger"));
+    assert!(functions.contains("fun `origin`(): Point"));
+    for (name, kotlin) in &files {
+        assert!(kotlin.contains("package uniffi;"), "no package in {name}");
+    }
+
+    assert!(private_declarations(internals).contains(&"findLibraryName"));
+    assert!(private_declarations(types).contains(&"JavaLangRefCleaner"));
+
+    // The declarations private to a file can't be used by the others, with either FFI backend.
+    for config in [
+        "split_files = true",
+        "split_files = true\nffi_backend = \"jni\"",
+    ] {
+        let files = render_files(config);
+        for (name, kotlin) in &files {
+            for (other_name, other) in files.iter().filter(|(other_name, _)| other_name != name) {
+                for decl in private_declarations(kotlin) {
+                    assert!(
+                        !uses_name(other, decl),
+                        "{decl} is private to {name} but used in {other_name}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn thread_annotations() {
+    let kotlin = render(concat!(
+        "blocking_thread_annotation = \"WorkerThread\"\n",
+        "callback_thread_annotation = \"com.example.CallbackThread\"\n",
+        "coroutine_dispatcher = \"kotlinx.coroutines.Dispatchers.IO\"\n",
+    ));
+    assert!(kotlin.contains("@androidx.annotation.WorkerThread\nfun `origin`(): Point {"));
+    assert!(kotlin.contains(
+        "@Throws(DrawException::class)\n@androidx.annotation.WorkerThread\nfun `logWith`("
+    ));
+    assert!(kotlin.contains("    @androidx.annotation.WorkerThread constructor() :"));
+    assert!(kotlin.contains("    @androidx.annotation.WorkerThread\n    override fun `draw`("));
+    assert!(kotlin.contains("    @com.example.CallbackThread\n    fun `log`(`message`: String)"));
+    // The `suspend` variants move the call off the calling thread.
+    assert!(!kotlin.contains("WorkerThread\n    suspend fun"));
+    assert!(kotlin.contains("\n    suspend fun `drawAsync`("));
+
+    let kotlin = render("");
+    assert!(!kotlin.contains("@androidx.annotation"));
+}
+
+#[test]
+fn jni_backend() {
+    // Only numbers cross JNI, so the bindings don't depend on JNA at all.
+    let kotlin = render("ffi_backend = \"jni\"\nlog_forwarder = true");
+    assert!(!kotlin.contains("com.sun.jna"));
+    assert!(!kotlin.contains(": Pointer"));
+    assert!(!kotlin.contains("Structure"));
+    assert!(kotlin.contains("internal object _UniFFILib {"));
+    assert!(kotlin.contains("    companion object {\n        internal const val SIZE = 16\n"));
+    assert!(kotlin.contains("    fun uniffiJniLog(level: Int, target: Long, targetLen: Int,"));
+
+    let kotlin = render("");
+    assert!(kotlin.contains("import com.sun.jna.Library"));
+}
@@ -7,8 +7,10 @@ use camino::{Utf8Path, Utf8PathBuf};
 use fs_err::{self as fs, File};
 use std::{io::Write, process::Command};
 
+mod android;
 pub mod gen_kotlin;
-pub use gen_kotlin::{generate_bindings, Config};
+pub use android::{consumer_proguard_rules, write_android_library, DEFAULT_ABIS};
+pub use gen_kotlin::{generate_bindings, generate_bindings_files, Config};
 mod test;
 
 use super::super::interface::ComponentInterface;
@@ -20,20 +22,26 @@ pub fn write_bindings(
     out_dir: &Utf8Path,
     try_format_code: bool,
 ) -> Result<()> {
-    let mut kt_file = full_bindings_path(config, out_dir);
-    fs::create_dir_all(&kt_file)?;
-    kt_file.push(format!("{}.kt", ci.namespace()));
-    let mut f = File::create(&kt_file)?;
-    write!(f, "{}", generate_bindings(config, ci)?)?;
-    if try_format_code {
-        if let Err(e) = Command::new("ktlint").arg("-F").arg(&kt_file).output() {
-            println!(
-                "Warning: Unable to auto-format {} using ktlint: {:?}",
-                kt_file.file_name().unwrap(),
-                e
-            )
+    let bindings_path = full_bindings_path(config, out_dir);
+    fs::create_dir_all(&bindings_path)?;
+    for (file_name, contents) in generate_bindings_files(config, ci)? {
+        let kt_file = bindings_path.join(file_name);
+        let mut f = File::create(&kt_file)?;
+        write!(f, "{}", contents)?;
+        if try_format_code {
+            if let Err(e) = Command::new("ktlint").arg("-F").arg(&kt_file).output() {
+                println!(
+                    "Warning: Unable to auto-format {} using ktlint: {:?}",
+                    kt_file.file_name().unwrap(),
+                    e
+                )
+            }
         }
     }
+    fs::write(
+        bindings_path.join(format!("{}-consumer-rules.pro", ci.namespace())),
+        consumer_proguard_rules(config, ci),
+    )?;
     Ok(())
 }
 
@@ -0,0 +1,17 @@
+public object FfiConverterBigInt: FfiConverterRustBuffer<java.math.BigInteger> {
+    override fun read(buf: ByteBuffer): java.math.BigInteger {
+        val len = buf.getLength()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return java.math.BigInteger(byteArr.toString(Charsets.UTF_8))
+    }
+
+    // Big integers are passed as their decimal digits, which are all ASCII.
+    override fun allocationSize(value: java.math.BigInteger) = LENGTH_SIZE + value.toString().length
+
+    override fun write(value: java.math.BigInteger, buf: ByteBuffer) {
+        val byteArr = value.toString().toByteArray(Charsets.UTF_8)
+        buf.putLength(byteArr.size)
+        buf.put(byteArr)
+    }
+}
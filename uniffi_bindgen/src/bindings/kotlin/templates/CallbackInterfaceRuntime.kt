@@ -3,18 +3,25 @@ internal class ConcurrentHandleMap<T>(
     private val leftMap: MutableMap<Handle, T> = mutableMapOf(),
     private val rightMap: MutableMap<T, Handle> = mutableMapOf()
 ) {
+    // Reentrant, since the implementation of a callback can call back into Rust, which can free
+    // callbacks, all on the same thread.
     private val lock = java.util.concurrent.locks.ReentrantLock()
     private val currentHandle = AtomicLong(0L)
     private val stride = 1L
+    // The same object can be passed to Rust several times, for example by the implementation of
+    // one of its own methods, and Rust frees each of those handles once. Count them, so that the
+    // object is only removed when Rust doesn't hold any of them anymore.
+    private val refCounts: MutableMap<Handle, Int> = mutableMapOf()
 
     fun insert(obj: T): Handle =
         lock.withLock {
-            rightMap[obj] ?:
+            (rightMap[obj] ?:
                 currentHandle.getAndAdd(stride)
                     .also { handle ->
                         leftMap[handle] = obj
                         rightMap[obj] = handle
-                    }
+                    })
+                .also { handle -> refCounts[handle] = (refCounts[handle] ?: 0) + 1 }
             }
 
     fun get(handle: Handle) = lock.withLock {
@@ -27,6 +34,12 @@ internal class ConcurrentHandleMap<T>(
 
     fun remove(handle: Handle): T? =
         lock.withLock {
+            val count = refCounts[handle] ?: return@withLock null
+            if (count > 1) {
+                refCounts[handle] = count - 1
+                return@withLock leftMap[handle]
+            }
+            refCounts.remove(handle)
             leftMap.remove(handle)?.let { obj ->
                 rightMap.remove(obj)
                 obj
@@ -34,7 +47,13 @@ internal class ConcurrentHandleMap<T>(
         }
 }
 
+{%- match kotlin_config.ffi_backend() %}
+{%- when FfiBackend::Jna %}
 interface ForeignCallback : com.sun.jna.Callback {
+{%- when FfiBackend::Jni %}
+// Called through a static method of `_UniFFILib` with the `jni` FFI backend.
+interface ForeignCallback {
+{%- endmatch %}
     public fun invoke(handle: Handle, method: Int, args: RustBuffer.ByValue, outBuf: RustBufferByReference): Int
 }
 
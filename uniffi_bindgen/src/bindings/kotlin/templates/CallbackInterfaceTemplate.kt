@@ -8,9 +8,19 @@
 {{- self.add_import("kotlin.concurrent.withLock") }}
 
 // Declaration and FfiConverters for {{ type_name }} Callback Interface
+{%- if cbi.is_function() %}
+{%- for meth in cbi.methods() %}
+
+public typealias {{ type_name }} = (
+    {%- for arg in meth.arguments() %}{{ arg|type_name }}{% if !loop.last %}, {% endif %}{% endfor -%}
+    ) -> {% match meth.return_type() %}{% when Some with (return_type) %}{{ return_type|type_name }}{% else %}Unit{% endmatch %}
+{%- endfor %}
+{%- else %}
 
 public interface {{ type_name }} {
     {% for meth in cbi.methods() -%}
+    {% match kotlin_config.callback_thread_annotation() %}{% when Some with (annotation) %}{{ annotation }}
+    {% else %}{% endmatch -%}
     fun {{ meth.name()|fn_name }}({% call kt::arg_list_decl(meth) %})
     {%- match meth.return_type() -%}
     {%- when Some with (return_type) %}: {{ return_type|type_name -}}
@@ -18,6 +28,7 @@ public interface {{ type_name }} {
     {%- endmatch %}
     {% endfor %}
 }
+{%- endif %}
 
 // The ForeignCallback that is passed to Rust.
 internal class {{ foreign_callback }} : ForeignCallback {
@@ -90,14 +101,14 @@ internal class {{ foreign_callback }} : ForeignCallback {
             {%- if meth.arguments().len() != 0 -%}
             {#- Calling the concrete callback object #}
             val buf = args.asByteBuffer() ?: throw InternalException("No ByteBuffer in RustBuffer; this is a Uniffi bug")
-            kotlinCallbackInterface.{{ meth.name()|fn_name }}(
+            kotlinCallbackInterface{% if !cbi.is_function() %}.{{ meth.name()|fn_name }}{% endif %}(
                     {% for arg in meth.arguments() -%}
                     {{ arg|read_fn }}(buf)
                     {%- if !loop.last %}, {% endif %}
                     {% endfor -%}
                 )
             {% else %}
-            kotlinCallbackInterface.{{ meth.name()|fn_name }}()
+            kotlinCallbackInterface{% if !cbi.is_function() %}.{{ meth.name()|fn_name }}{% endif %}()
             {% endif -%}
 
         {#- Packing up the return value into a RustBuffer #}
@@ -127,4 +138,11 @@ public object {{ ffi_converter_name }}: FfiConverterCallbackInterface<{{ type_na
             lib.{{ cbi.ffi_init_callback().name() }}(this.foreignCallback, status)
         }
     }
+
+    override fun lower(value: {{ type_name }}): Handle {
+        // Loading the library registers the callbacks with Rust, which the bindings of other
+        // components using this callback interface as an external type don't do themselves.
+        _UniFFILib.INSTANCE
+        return super.lower(value)
+    }
 }
@@ -0,0 +1,27 @@
+public object FfiConverterChar: FfiConverter<Char, Int> {
+    override fun lift(value: Int): Char {
+        // A `Char` is a UTF-16 code unit, which can't hold the scalars outside of the Basic
+        // Multilingual Plane.
+        if (value > 0xFFFF) {
+            throw InternalException("U+${value.toString(16).uppercase()} doesn't fit in a Char")
+        }
+        return value.toChar()
+    }
+
+    override fun read(buf: ByteBuffer): Char {
+        return lift(buf.getInt())
+    }
+
+    override fun lower(value: Char): Int {
+        require(!value.isSurrogate()) {
+            "U+${value.code.toString(16).uppercase()} is a surrogate, not a Unicode scalar value"
+        }
+        return value.code
+    }
+
+    override fun allocationSize(value: Char) = 4
+
+    override fun write(value: Char, buf: ByteBuffer) {
+        buf.putInt(lower(value))
+    }
+}
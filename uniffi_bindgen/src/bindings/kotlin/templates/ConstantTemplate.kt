@@ -0,0 +1,7 @@
+{#- Durations are objects, which can't be compile-time constants. #}
+{%- match constant.type_() %}
+{%- when Type::Duration %}
+val {{ constant.name()|const_name }}: {{ constant|type_name }} = {{ constant.value()|render_literal(constant) }}
+{%- else %}
+const val {{ constant.name()|const_name }}: {{ constant|type_name }} = {{ constant.value()|render_literal(constant) }}
+{%- endmatch %}
@@ -0,0 +1,11 @@
+/**
+ * The dispatcher on which the `suspend` variants of functions and methods make their blocking
+ * call into Rust.
+ *
+ * It defaults to the `coroutine_dispatcher` from `uniffi.toml`, and can be replaced at runtime,
+ * e.g. with a dedicated bounded pool to avoid starving `Dispatchers.IO`.
+ */
+object UniFFICoroutineDispatcher {
+    @Volatile
+    var dispatcher: kotlinx.coroutines.CoroutineDispatcher = {{ config.coroutine_dispatcher().unwrap() }}
+}
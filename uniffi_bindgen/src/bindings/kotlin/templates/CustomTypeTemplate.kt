@@ -1,5 +1,37 @@
 {%- match kotlin_config.custom_types.get(name.as_str())  %}
 {%- when None %}
+{%- if kotlin_config.value_classes() %}
+{#- Define the type as a value class wrapping the builtin #}
+{%- let ffi_type_name=builtin.ffi_type().borrow()|ffi_type_name %}
+/**
+ * Type-safe wrapper around the builtin type, which is used for the type name from the UDL file.
+ * Being a value class, it is usually represented as the builtin type at runtime.
+ */
+@JvmInline
+public value class {{ name }}(val value: {{ builtin|type_name }})
+
+public object {{ ffi_converter_name }}: FfiConverter<{{ name }}, {{ ffi_type_name }}> {
+    override fun lift(value: {{ ffi_type_name }}): {{ name }} {
+        return {{ name }}({{ builtin|lift_fn }}(value))
+    }
+
+    override fun lower(value: {{ name }}): {{ ffi_type_name }} {
+        return {{ builtin|lower_fn }}(value.value)
+    }
+
+    override fun read(buf: ByteBuffer): {{ name }} {
+        return {{ name }}({{ builtin|read_fn }}(buf))
+    }
+
+    override fun allocationSize(value: {{ name }}): Int {
+        return {{ builtin|allocation_size_fn }}(value.value)
+    }
+
+    override fun write(value: {{ name }}, buf: ByteBuffer) {
+        {{ builtin|write_fn }}(value.value, buf)
+    }
+}
+{%- else %}
 {#- Define the type using typealiases to the builtin #}
 /**
  * Typealias from the type name used in the UDL file to the builtin type.  This
@@ -8,6 +40,7 @@
  */
 public typealias {{ name }} = {{ builtin|type_name }}
 public typealias {{ ffi_converter_name }} = {{ builtin|ffi_converter_name }}
+{%- endif %}
 
 {%- when Some with (config) %}
 
@@ -0,0 +1,27 @@
+{#
+// Methods of records and enums, which are standalone functions in the FFI that receive the
+// record or enum first. Included in the body of their class, for the current `type_`.
+#}
+{%- let receiver = "{}.lower(this)"|format(ffi_converter_name) %}
+{%- for meth in ci.methods_of(type_) %}
+
+    {{ meth.docstring()|docstring("    ") }}
+    {%- match meth.throws_type() -%}
+    {%- when Some with (throwable) %}
+    @Throws({{ throwable|type_name }}::class)
+    {%- else -%}
+    {%- endmatch %}
+    {%- match kotlin_config.blocking_thread_annotation() -%}
+    {%- when Some with (annotation) %}
+    {{ annotation }}
+    {%- else -%}
+    {%- endmatch %}
+    {%- match meth.return_type() -%}
+    {%- when Some with (return_type) %}
+    fun {{ meth.name()|fn_name }}({% call kt::arg_list_decl(meth) %}): {{ return_type|type_name }} =
+        {{ return_type|lift_fn }}({% call kt::to_ffi_call_with_prefix(receiver, meth) %})
+    {%- when None %}
+    fun {{ meth.name()|fn_name }}({% call kt::arg_list_decl(meth) %}) =
+        {% call kt::to_ffi_call_with_prefix(receiver, meth) %}
+    {%- endmatch %}
+{%- endfor %}
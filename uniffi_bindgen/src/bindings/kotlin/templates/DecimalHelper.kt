@@ -0,0 +1,18 @@
+public object FfiConverterDecimal: FfiConverterRustBuffer<java.math.BigDecimal> {
+    override fun read(buf: ByteBuffer): java.math.BigDecimal {
+        val len = buf.getLength()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return java.math.BigDecimal(byteArr.toString(Charsets.UTF_8))
+    }
+
+    // Decimals are passed in plain notation, as `toString()` may use an exponent
+    // which Rust can't parse.
+    override fun allocationSize(value: java.math.BigDecimal) = LENGTH_SIZE + value.toPlainString().length
+
+    override fun write(value: java.math.BigDecimal, buf: ByteBuffer) {
+        val byteArr = value.toPlainString().toByteArray(Charsets.UTF_8)
+        buf.putLength(byteArr.size)
+        buf.put(byteArr)
+    }
+}
@@ -5,41 +5,87 @@
 // and `sealed class` for the general case.
 #}
 {%- let e = ci.get_enum_definition(name).unwrap() %}
+{%- let serializable = kotlin_config.serializable() && !contains_object_references %}
+{%- let parcelable = kotlin_config.parcelable() && !contains_object_references %}
 
 {%- if e.is_flat() %}
-
-enum class {{ type_name }} {
+{{ e.docstring()|docstring("") }}
+{% if serializable -%}
+@kotlinx.serialization.Serializable
+@kotlinx.serialization.SerialName("{{ e.name() }}")
+{% endif -%}
+enum class {{ type_name }}(val value: Long) {
     {% for variant in e.variants() -%}
-    {{ variant.name()|enum_variant }}{% if loop.last %};{% else %},{% endif %}
+    {% if variant.docstring().is_some() %}{{ variant.docstring()|docstring("    ") }}
+    {% endif -%}
+    {% if serializable %}@kotlinx.serialization.SerialName("{{ variant.name() }}") {% endif -%}
+    {{ variant.name()|enum_variant }}({{ variant.discriminant() }}L){% if loop.last %};{% else %},{% endif %}
     {%- endfor %}
+
+    companion object {
+        // `value` is the discriminant of the variant in the Rust enum, which doesn't change when
+        // variants are reordered, unlike `ordinal`.
+        fun fromValue(value: Long): {{ type_name }} =
+            values().firstOrNull { it.value == value }
+                ?: throw IllegalArgumentException("invalid {{ type_name }} value: $value")
+    }
+    {%- include "DataMethodsTemplate.kt" %}
 }
 
 public object {{ e|ffi_converter_name }}: FfiConverterRustBuffer<{{ type_name }}> {
-    override fun read(buf: ByteBuffer) = try {
-        {{ type_name }}.values()[buf.getInt() - 1]
-    } catch (e: IndexOutOfBoundsException) {
-        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    // Variants are passed by their position in the Rust enum, starting from 1.
+    override fun read(buf: ByteBuffer) = when (buf.getVariant()) {
+        {%- for variant in e.variants() %}
+        {{ loop.index }} -> {{ type_name }}.{{ variant.name()|enum_variant }}
+        {%- endfor %}
+        else -> throw RuntimeException("invalid enum value, something is very wrong!!")
     }
 
-    override fun allocationSize(value: {{ type_name }}) = 4
+    override fun allocationSize(value: {{ type_name }}) = LENGTH_SIZE
 
     override fun write(value: {{ type_name }}, buf: ByteBuffer) {
-        buf.putInt(value.ordinal + 1)
+        buf.putVariant(when (value) {
+            {%- for variant in e.variants() %}
+            {{ type_name }}.{{ variant.name()|enum_variant }} -> {{ loop.index }}
+            {%- endfor %}
+        })
     }
 }
 
 {% else %}
-
-sealed class {{ type_name }}{% if contains_object_references %}: Disposable {% endif %} {
+{{ e.docstring()|docstring("") }}
+{% if serializable -%}
+@kotlinx.serialization.Serializable
+@kotlinx.serialization.SerialName("{{ e.name() }}")
+{% endif -%}
+{%- if kotlin_config.sealed_interfaces() %}
+sealed interface {{ type_name }}{% if contains_object_references %}: Disposable {% else if parcelable %}: android.os.Parcelable {% endif %} {
+{%- else %}
+sealed class {{ type_name }}{% if contains_object_references %}: Disposable {% else if parcelable %}: android.os.Parcelable {% endif %} {
+{%- endif %}
     {% for variant in e.variants() -%}
+    {% if variant.docstring().is_some() %}{{ variant.docstring()|docstring("    ") }}
+    {% endif -%}
+    {% if serializable -%}
+    @kotlinx.serialization.Serializable
+    @kotlinx.serialization.SerialName("{{ variant.name() }}")
+    {% endif -%}
+    {% if parcelable -%}
+    @kotlinx.parcelize.Parcelize
+    {% endif -%}
     {% if !variant.has_fields() -%}
+    {% if kotlin_config.sealed_interfaces() -%}
+    data object {{ variant.name()|class_name }} : {{ type_name }}
+    {%- else -%}
     object {{ variant.name()|class_name }} : {{ type_name }}()
+    {%- endif %}
     {% else -%}
     data class {{ variant.name()|class_name }}(
         {% for field in variant.fields() -%}
+        {% if serializable %}@kotlinx.serialization.SerialName("{{ field.name() }}") {% endif -%}
         val {{ field.name()|var_name }}: {{ field|type_name}}{% if loop.last %}{% else %}, {% endif %}
         {% endfor -%}
-    ) : {{ type_name }}()
+    ) : {{ type_name }}{% if !kotlin_config.sealed_interfaces() %}(){% endif %}
     {%- endif %}
     {% endfor %}
 
@@ -59,11 +105,12 @@ sealed class {{ type_name }}{% if contains_object_references %}: Disposable {% e
         }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
     }
     {% endif %}
+    {%- include "DataMethodsTemplate.kt" %}
 }
 
 public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }}>{
     override fun read(buf: ByteBuffer): {{ type_name }} {
-        return when(buf.getInt()) {
+        return when(buf.getVariant()) {
             {%- for variant in e.variants() %}
             {{ loop.index }} -> {{ type_name }}.{{ variant.name()|class_name }}{% if variant.has_fields() %}(
                 {% for field in variant.fields() -%}
@@ -80,7 +127,7 @@ public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }
         is {{ type_name }}.{{ variant.name()|class_name }} -> {
             // Add the size for the Int that specifies the variant plus the size needed for all fields
             (
-                4
+                LENGTH_SIZE
                 {%- for field in variant.fields() %}
                 + {{ field|allocation_size_fn }}(value.{{ field.name()|var_name }})
                 {%- endfor %}
@@ -93,7 +140,7 @@ public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }
         when(value) {
             {%- for variant in e.variants() %}
             is {{ type_name }}.{{ variant.name()|class_name }} -> {
-                buf.putInt({{ loop.index }})
+                buf.putVariant({{ loop.index }})
                 {%- for field in variant.fields() %}
                 {{ field|write_fn }}(value.{{ field.name()|var_name }}, buf)
                 {%- endfor %}
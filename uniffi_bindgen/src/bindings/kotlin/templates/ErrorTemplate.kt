@@ -8,8 +8,19 @@ sealed class {{ type_name }}(message: String): Exception(message){% if contains_
         class {{ variant.name()|exception_name }}(message: String) : {{ type_name }}(message)
         {% endfor %}
 
+    override val message: String?
+        get() = messageWithErrorSources(super.message)
+
+    // The key/value context which the Rust code attached to the error.
+    var errorContext: Map<String, String> = emptyMap()
+
     companion object ErrorHandler : CallStatusErrorHandler<{{ type_name }}> {
-        override fun lift(error_buf: RustBuffer.ByValue): {{ type_name }} = {{ e|lift_fn }}(error_buf)
+        override fun lift(error_buf: RustBuffer.ByValue): {{ type_name }} = liftFromErrorBuffer(error_buf) { buf ->
+            {{ e|read_fn }}(buf).apply {
+                errorContext = readErrorContext(buf)
+                readErrorSources(buf)?.let { initCause(it) }
+            }
+        }
     }
 }
 {%- else %}
@@ -23,12 +34,20 @@ sealed class {{ type_name }}: Exception(){% if contains_object_references %}, Di
         {% endfor -%}
     ) : {{ type_name }}() {
         override val message
-            get() = "{%- for field in variant.fields() %}{{ field.name()|var_name|unquote }}=${ {{field.name()|var_name }} }{% if !loop.last %}, {% endif %}{% endfor %}"
+            get() = messageWithErrorSources("{%- for field in variant.fields() %}{{ field.name()|var_name|unquote }}=${ {{field.name()|var_name }} }{% if !loop.last %}, {% endif %}{% endfor %}")
     }
     {% endfor %}
 
+    // The key/value context which the Rust code attached to the error.
+    var errorContext: Map<String, String> = emptyMap()
+
     companion object ErrorHandler : CallStatusErrorHandler<{{ type_name }}> {
-        override fun lift(error_buf: RustBuffer.ByValue): {{ type_name }} = {{ e|lift_fn }}(error_buf)
+        override fun lift(error_buf: RustBuffer.ByValue): {{ type_name }} = liftFromErrorBuffer(error_buf) { buf ->
+            {{ e|read_fn }}(buf).apply {
+                errorContext = readErrorContext(buf)
+                readErrorSources(buf)?.let { initCause(it) }
+            }
+        }
     }
 
     {% if contains_object_references %}
@@ -53,7 +72,7 @@ sealed class {{ type_name }}: Exception(){% if contains_object_references %}, Di
 public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }}> {
     override fun read(buf: ByteBuffer): {{ type_name }} {
         {% if e.is_flat() %}
-            return when(buf.getInt()) {
+            return when(buf.getVariant()) {
             {%- for variant in e.variants() %}
             {{ loop.index }} -> {{ type_name }}.{{ variant.name()|exception_name }}({{ TypeIdentifier::String.borrow()|read_fn }}(buf))
             {%- endfor %}
@@ -61,7 +80,7 @@ public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }
         }
         {% else %}
 
-        return when(buf.getInt()) {
+        return when(buf.getVariant()) {
             {%- for variant in e.variants() %}
             {{ loop.index }} -> {{ type_name }}.{{ variant.name()|exception_name }}({% if variant.has_fields() %}
                 {% for field in variant.fields() -%}
@@ -76,13 +95,13 @@ public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }
 
     override fun allocationSize(value: {{ type_name }}): Int {
         {%- if e.is_flat() %}
-        return 4
+        return LENGTH_SIZE
         {%- else %}
         return when(value) {
             {%- for variant in e.variants() %}
             is {{ type_name }}.{{ variant.name()|exception_name }} -> (
                 // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4
+                LENGTH_SIZE
                 {%- for field in variant.fields() %}
                 + {{ field|allocation_size_fn }}(value.{{ field.name()|var_name }})
                 {%- endfor %}
@@ -96,7 +115,7 @@ public object {{ e|ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }
         when(value) {
             {%- for variant in e.variants() %}
             is {{ type_name }}.{{ variant.name()|exception_name }} -> {
-                buf.putInt({{ loop.index }})
+                buf.putVariant({{ loop.index }})
                 {%- for field in variant.fields() %}
                 {{ field|write_fn }}(value.{{ field.name()|var_name }}, buf)
                 {%- endfor %}
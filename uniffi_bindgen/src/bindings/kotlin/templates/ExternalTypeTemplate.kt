@@ -4,3 +4,27 @@
 
 {{- self.add_import(fully_qualified_type_name) }}
 {{ self.add_import(fully_qualified_ffi_converter_name) }}
+
+{%- match kind %}
+{%- when ExternalKind::Error %}
+
+// The `{{ name }}` errors of `{{ crate_name }}` are lifted from, and lowered into, the `RustBuffer`s of
+// this component, with the same bytes as the `FfiConverterType{{ name }}` of their own package.
+internal object {{ ffi_converter_name }} : FfiConverterRustBuffer<{{ type_name }}> {
+    override fun read(buf: ByteBuffer): {{ type_name }} = FfiConverterType{{ name }}.read(buf)
+
+    override fun allocationSize(value: {{ type_name }}) = FfiConverterType{{ name }}.allocationSize(value)
+
+    override fun write(value: {{ type_name }}, buf: ByteBuffer) = FfiConverterType{{ name }}.write(value, buf)
+}
+
+internal object {{ type_name }}ExternalErrorHandler : CallStatusErrorHandler<{{ type_name }}> {
+    override fun lift(error_buf: RustBuffer.ByValue): {{ type_name }} = liftFromErrorBuffer(error_buf) { buf ->
+        {{ ffi_converter_name }}.read(buf).apply {
+            errorContext = readErrorContext(buf)
+            readErrorSources(buf)?.let { initCause(it) }
+        }
+    }
+}
+{%- else %}
+{%- endmatch %}
@@ -33,14 +33,15 @@ public interface FfiConverter<KotlinType, FfiType> {
     // normal FFI type.
     fun lowerIntoRustBuffer(value: KotlinType): RustBuffer.ByValue {
         val rbuf = RustBuffer.alloc(allocationSize(value))
+        val clonedHandles = UniFFIClonedHandles.mark()
         try {
-            val bbuf = rbuf.data!!.getByteBuffer(0, rbuf.capacity.toLong()).also {
-                it.order(ByteOrder.BIG_ENDIAN)
-            }
+            val bbuf = rbuf.asWritableByteBuffer()
             write(value, bbuf)
-            rbuf.writeField("len", bbuf.position())
+            rbuf.len = bbuf.position()
+            UniFFIClonedHandles.keep(clonedHandles)
             return rbuf
         } catch (e: Throwable) {
+            UniFFIClonedHandles.release(clonedHandles)
             RustBuffer.free(rbuf)
             throw e
         }
@@ -64,8 +65,97 @@ public interface FfiConverter<KotlinType, FfiType> {
     }
 }
 
+// The functions releasing the handles of the objects cloned for Rust while writing values into a
+// `RustBuffer`, which are called if the writing fails, as Rust then never reads the buffer.
+internal object UniFFIClonedHandles {
+    private val releases = ThreadLocal.withInitial { ArrayList<() -> Unit>() }
+
+    fun add(release: () -> Unit) {
+        releases.get().add(release)
+    }
+
+    fun mark(): Int = releases.get().size
+
+    // The buffer was written: Rust owns the handles cloned since `mark`.
+    fun keep(mark: Int) {
+        releases.get().run { subList(mark, size).clear() }
+    }
+
+    fun release(mark: Int) {
+        releases.get().run {
+            val pending = subList(mark, size)
+            pending.forEach { it() }
+            pending.clear()
+        }
+    }
+}
+
 // FfiConverter that uses `RustBuffer` as the FfiType
 public interface FfiConverterRustBuffer<KotlinType>: FfiConverter<KotlinType, RustBuffer.ByValue> {
     override fun lift(value: RustBuffer.ByValue) = liftFromRustBuffer(value)
     override fun lower(value: KotlinType) = lowerIntoRustBuffer(value)
 }
+
+{% if ci.compact_encoding() -%}
+// The component has `compact_encoding = true` in its `uniffi.toml`: the integers wider than a
+// byte are written as varints (LEB128), zigzag-encoded if they're signed, and so are the lengths
+// of the strings, sequences and maps, and the variants of the enums.
+
+// The maximum size of a length or a variant.
+internal const val LENGTH_SIZE = 5
+
+internal fun ByteBuffer.getVarLong(): Long {
+    var result = 0L
+    var shift = 0
+    while (shift < 64) {
+        val byte = get().toInt()
+        result = result or ((byte and 0x7f).toLong() shl shift)
+        if (byte and 0x80 == 0) {
+            return result
+        }
+        shift += 7
+    }
+    throw RuntimeException("varint longer than 10 bytes, something is very wrong!!")
+}
+
+internal fun ByteBuffer.putVarLong(value: Long) {
+    var v = value
+    while (v and 0x7fL.inv() != 0L) {
+        put(((v and 0x7f) or 0x80).toByte())
+        v = v ushr 7
+    }
+    put(v.toByte())
+}
+
+internal fun ByteBuffer.getZigZagLong(): Long {
+    val v = getVarLong()
+    return (v ushr 1) xor -(v and 1)
+}
+
+internal fun ByteBuffer.putZigZagLong(value: Long) = putVarLong((value shl 1) xor (value shr 63))
+
+internal fun ByteBuffer.getLength(): Int = getVarLong().toInt()
+
+internal fun ByteBuffer.putLength(value: Int) = putVarLong(value.toLong())
+
+internal fun ByteBuffer.getVariant(): Int = getVarLong().toInt()
+
+internal fun ByteBuffer.putVariant(value: Int) = putVarLong(value.toLong())
+{%- else -%}
+// The lengths of the strings, sequences and maps, and the variants of the enums, are `Int`s.
+
+// The size of a length or a variant.
+internal const val LENGTH_SIZE = 4
+
+internal fun ByteBuffer.getLength(): Int = getInt()
+
+internal fun ByteBuffer.putLength(value: Int) {
+    putInt(value)
+}
+
+internal fun ByteBuffer.getVariant(): Int = getInt()
+
+internal fun ByteBuffer.putVariant(value: Int) {
+    putInt(value)
+}
+{%- endif %}
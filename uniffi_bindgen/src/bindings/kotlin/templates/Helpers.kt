@@ -1,8 +1,14 @@
 // A handful of classes and functions to support the generated data structures.
 // This would be a good candidate for isolating in its own ffi-support lib.
 // Error runtime.
+{%- match config.ffi_backend() %}
+{%- when FfiBackend::Jna %}
 @Structure.FieldOrder("code", "error_buf")
 internal open class RustCallStatus : Structure() {
+{%- when FfiBackend::Jni %}
+// Read back from the scratch memory of the `jni` FFI backend when the call fails.
+internal open class RustCallStatus {
+{%- endmatch %}
     @JvmField var code: Int = 0
     @JvmField var error_buf: RustBuffer.ByValue = RustBuffer.ByValue()
 
@@ -17,9 +23,25 @@ internal open class RustCallStatus : Structure() {
     fun isPanic(): Boolean {
         return code == 2
     }
+
+    fun isPayloadTooLarge(): Boolean {
+        return code == 3
+    }
+}
+
+open class InternalException(message: String) : Exception(message) {
+    // The key/value context which the Rust code attached to the panic.
+    var errorContext: Map<String, String> = emptyMap()
+    // Where the Rust code panicked, as `file:line:column`, or empty if unknown.
+    var location: String = ""
+    // The backtrace of the panic, or empty if unknown.
+    var backtrace: String = ""
 }
 
-class InternalException(message: String) : Exception(message)
+// A RustBuffer going over the limits set by the Rust code, rather than being allocated. `size` is
+// the size of the buffer, or the total size of the live buffers with it, which goes over `limit`,
+// in bytes.
+class PayloadTooLargeException(message: String, val size: Long, val limit: Long) : InternalException(message)
 
 // Each top-level error class has a companion object that can lift the error from the call status's rust buffer
 interface CallStatusErrorHandler<E> {
@@ -31,7 +53,7 @@ interface CallStatusErrorHandler<E> {
 // synchronize itself
 
 // Call a rust function that returns a Result<>.  Pass in the Error class companion that corresponds to the Err
-private inline fun <U, E: Exception> rustCallWithError(errorHandler: CallStatusErrorHandler<E>, callback: (RustCallStatus) -> U): U {
+{{ self.helpers_visibility() }} inline fun <U, E: Exception> rustCallWithError(errorHandler: CallStatusErrorHandler<E>, callback: (RustCallStatus) -> U): U {
     var status = RustCallStatus();
     val return_value = callback(status)
     if (status.isSuccess()) {
@@ -43,15 +65,99 @@ private inline fun <U, E: Exception> rustCallWithError(errorHandler: CallStatusE
         // with the message.  but if that code panics, then it just sends back
         // an empty buffer.
         if (status.error_buf.len > 0) {
-            throw InternalException({{ TypeIdentifier::String.borrow()|lift_fn }}(status.error_buf))
+            throw liftPanic(status.error_buf)
         } else {
             throw InternalException("Rust panic")
         }
+    } else if (status.isPayloadTooLarge()) {
+        throw liftPayloadTooLarge(status.error_buf)
     } else {
         throw InternalException("Unknown rust call status: $status.code")
     }
 }
 
+// The error buffer of a panic holds its message, followed by its location and its backtrace, which
+// are empty when unknown, and its context.
+{{ self.helpers_visibility() }} fun liftPanic(error_buf: RustBuffer.ByValue): InternalException {
+    try {
+        val buf = error_buf.asByteBuffer()!!
+        val message = {{ TypeIdentifier::String.borrow()|read_fn }}(buf)
+        return InternalException(message).apply {
+            location = {{ TypeIdentifier::String.borrow()|read_fn }}(buf)
+            backtrace = {{ TypeIdentifier::String.borrow()|read_fn }}(buf)
+            errorContext = readErrorContext(buf)
+        }
+    } finally {
+        RustBuffer.free(error_buf)
+    }
+}
+
+// The error buffer of a RustBuffer going over the limits holds its message, size and limit, and its
+// context.
+{{ self.helpers_visibility() }} fun liftPayloadTooLarge(error_buf: RustBuffer.ByValue): PayloadTooLargeException {
+    try {
+        val buf = error_buf.asByteBuffer()!!
+        val message = {{ TypeIdentifier::String.borrow()|read_fn }}(buf)
+        return PayloadTooLargeException(message, buf.getLong(), buf.getLong()).apply {
+            errorContext = readErrorContext(buf)
+        }
+    } finally {
+        RustBuffer.free(error_buf)
+    }
+}
+
+// The key/value context which the Rust code attached to an error follows it, unless empty.
+internal fun readErrorContext(buf: ByteBuffer): Map<String, String> {
+    val errorContext: MutableMap<String, String> = mutableMapOf()
+    if (buf.hasRemaining()) {
+        repeat(buf.getLength()) {
+            val key = {{ TypeIdentifier::String.borrow()|read_fn }}(buf)
+            errorContext[key] = {{ TypeIdentifier::String.borrow()|read_fn }}(buf)
+        }
+    }
+    return errorContext
+}
+
+// A source of a Rust error declared with `[Source]`: the exception raised for the error has the
+// first source as its `cause`, whose own `cause` is the next source, and so on.
+class ErrorSourceException(message: String, cause: ErrorSourceException?) : Exception(message, cause)
+
+// The descriptions of the sources of an error follow its context, if it has any, the outermost
+// first.
+internal fun readErrorSources(buf: ByteBuffer): ErrorSourceException? {
+    if (!buf.hasRemaining()) {
+        return null
+    }
+    val sources = List(buf.getLength()) { {{ TypeIdentifier::String.borrow()|read_fn }}(buf) }
+    return sources.foldRight<String, ErrorSourceException?>(null) { message, cause -> ErrorSourceException(message, cause) }
+}
+
+// The message of an error, followed by the ones of its sources, so that they show up in the crash
+// reports which only keep the message.
+internal fun Throwable.messageWithErrorSources(message: String?): String? {
+    var messageWithSources = message
+    var source = cause
+    while (source is ErrorSourceException) {
+        messageWithSources = "$messageWithSources: ${source.message}"
+        source = source.cause
+    }
+    return messageWithSources
+}
+
+// Lift an error from the error buffer of a call with `read`, which also reads its context.
+internal fun <E: Exception> liftFromErrorBuffer(error_buf: RustBuffer.ByValue, read: (ByteBuffer) -> E): E {
+    val buf = error_buf.asByteBuffer()!!
+    try {
+        val error = read(buf)
+        if (buf.hasRemaining()) {
+            throw RuntimeException("junk remaining in buffer after lifting, something is very wrong!!")
+        }
+        return error
+    } finally {
+        RustBuffer.free(error_buf)
+    }
+}
+
 // CallStatusErrorHandler implementation for times when we don't expect a CALL_ERROR
 object NullCallStatusErrorHandler: CallStatusErrorHandler<InternalException> {
     override fun lift(error_buf: RustBuffer.ByValue): InternalException {
@@ -61,6 +167,6 @@ object NullCallStatusErrorHandler: CallStatusErrorHandler<InternalException> {
 }
 
 // Call a rust function that returns a plain value
-private inline fun <U> rustCall(callback: (RustCallStatus) -> U): U {
+{{ self.helpers_visibility() }} inline fun <U> rustCall(callback: (RustCallStatus) -> U): U {
     return rustCallWithError(NullCallStatusErrorHandler, callback);
 }
@@ -4,16 +4,24 @@ public object FfiConverterShort: FfiConverter<Short, Short> {
     }
 
     override fun read(buf: ByteBuffer): Short {
+        {%- if ci.compact_encoding() %}
+        return buf.getZigZagLong().toShort()
+        {%- else %}
         return buf.getShort()
+        {%- endif %}
     }
 
     override fun lower(value: Short): Short {
         return value
     }
 
-    override fun allocationSize(value: Short) = 2
+    override fun allocationSize(value: Short) = {% if ci.compact_encoding() %}3{% else %}2{% endif %}
 
     override fun write(value: Short, buf: ByteBuffer) {
+        {%- if ci.compact_encoding() %}
+        buf.putZigZagLong(value.toLong())
+        {%- else %}
         buf.putShort(value)
+        {%- endif %}
     }
 }
@@ -4,16 +4,24 @@ public object FfiConverterInt: FfiConverter<Int, Int> {
     }
 
     override fun read(buf: ByteBuffer): Int {
+        {%- if ci.compact_encoding() %}
+        return buf.getZigZagLong().toInt()
+        {%- else %}
         return buf.getInt()
+        {%- endif %}
     }
 
     override fun lower(value: Int): Int {
         return value
     }
 
-    override fun allocationSize(value: Int) = 4
+    override fun allocationSize(value: Int) = {% if ci.compact_encoding() %}5{% else %}4{% endif %}
 
     override fun write(value: Int, buf: ByteBuffer) {
+        {%- if ci.compact_encoding() %}
+        buf.putZigZagLong(value.toLong())
+        {%- else %}
         buf.putInt(value)
+        {%- endif %}
     }
 }
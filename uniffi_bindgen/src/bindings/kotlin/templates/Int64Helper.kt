@@ -4,16 +4,24 @@ public object FfiConverterLong: FfiConverter<Long, Long> {
     }
 
     override fun read(buf: ByteBuffer): Long {
+        {%- if ci.compact_encoding() %}
+        return buf.getZigZagLong()
+        {%- else %}
         return buf.getLong()
+        {%- endif %}
     }
 
     override fun lower(value: Long): Long {
         return value
     }
 
-    override fun allocationSize(value: Long) = 8
+    override fun allocationSize(value: Long) = {% if ci.compact_encoding() %}10{% else %}8{% endif %}
 
     override fun write(value: Long, buf: ByteBuffer) {
+        {%- if ci.compact_encoding() %}
+        buf.putZigZagLong(value)
+        {%- else %}
         buf.putLong(value)
+        {%- endif %}
     }
 }
@@ -0,0 +1,33 @@
+// The base class of the iterators returned by Rust, which produces their items lazily.
+//
+// The items are fetched one at a time over the FFI, `hasNext()` reading the next one ahead.
+// Like objects, iterators hold a handle to a Rust struct, so they must be destroyed once
+// they're no longer needed, for example with the `use` helper method.
+abstract class RustIterator<T : Any>(
+    handle: Long
+) : FFIObject<Long>(handle), Iterator<T> {
+    private var lookahead: T? = null
+    private var exhausted = false
+
+    // Get the next item from Rust, or `null` at the end of the iterator.
+    protected abstract fun fetchNext(): T?
+
+    @Synchronized
+    override fun hasNext(): Boolean {
+        if (lookahead == null && !exhausted) {
+            lookahead = fetchNext()
+            exhausted = lookahead == null
+        }
+        return lookahead != null
+    }
+
+    @Synchronized
+    override fun next(): T {
+        if (!hasNext()) {
+            throw NoSuchElementException()
+        }
+        val item = lookahead!!
+        lookahead = null
+        return item
+    }
+}
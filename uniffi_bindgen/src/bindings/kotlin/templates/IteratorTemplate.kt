@@ -0,0 +1,49 @@
+{%- let item_type_name = item_type|type_name %}
+{%- let next_type = ci.ffi_iterator_next_type(type_) %}
+{%- if self.include_once_check("ObjectRuntime.kt") %}{% include "ObjectRuntime.kt" %}{% endif %}
+{%- if self.include_once_check("IteratorRuntime.kt") %}{% include "IteratorRuntime.kt" %}{% endif %}
+
+// The iterator over `{{ item_type_name }}` items returned by Rust.
+internal class {{ canonical_type_name }}(
+    handle: Long
+) : RustIterator<{{ item_type_name }}>(handle) {
+
+    override protected val cleanable: UniFFICleaner.Cleanable =
+        UniFFICleaner.INSTANCE.register(this, UniFFICleanAction(handle))
+
+    // Frees the handle of the underlying Rust iterator, like the clean action of the objects.
+    private class UniFFICleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ ci.ffi_iterator_free(type_).name() }}(handle, status)
+            }
+        }
+    }
+
+    override protected fun fetchNext(): {{ item_type_name }}? =
+        callWithPointer {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ ci.ffi_iterator_next(type_).name() }}(it, status)
+            }
+        }.let {
+            {{ next_type.borrow()|lift_fn }}(it)
+        }
+}
+
+public object {{ ffi_converter_name }}: FfiConverter<{{ type_name }}, Long> {
+    override fun lower(value: {{ type_name }}): Long = value.callWithPointer { it }
+
+    override fun lift(value: Long): {{ type_name }} {
+        return {{ canonical_type_name }}(value)
+    }
+
+    override fun read(buf: ByteBuffer): {{ type_name }} {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: {{ type_name }}) = 8
+
+    override fun write(value: {{ type_name }}, buf: ByteBuffer) {
+        throw RuntimeException("Iterators can only be returned by Rust")
+    }
+}
@@ -0,0 +1,114 @@
+// The library is loaded by the JVM, like any other with `external` functions. The override of its
+// name may be the absolute path of the library file.
+private fun uniffiJniLoadLibrary(name: String) {
+    if (java.io.File(name).isAbsolute) {
+        System.load(name)
+    } else {
+        System.loadLibrary(name)
+    }
+}
+
+// The extern-C FFI definitions, called through JNI rather than JNA: each function passes its
+// arguments as numbers to its `external` function, implemented by the JNI glue generated in the
+// scaffolding, which calls the FFI function itself.
+//
+// The rest goes through the scratch memory of the thread, which is owned by Rust: the glue writes
+// the call status and the returned `RustBuffer`s and structs there, and reads the struct arguments
+// from there. Rust calls the callbacks through the static methods of this object. See
+// `uniffi/src/ffi/jni.rs` for the details.
+// This is an implementation detail which will be called internally by the public API.
+
+internal object _UniFFILib {
+    internal val INSTANCE: _UniFFILib by lazy {
+        uniffiJniLoadLibrary(findLibraryName(componentName = "{{ ci.namespace() }}"))
+        uniffiCheckContractVersion(this)
+        {%- for fn in self.initialization_fns() %}
+        {{ fn }}(this)
+        {%- endfor %}
+        {%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+        uniffiRunLifecycleHooks(this)
+        {%- endif %}
+        this
+    }
+
+    // The layout of the scratch memory, after the call status.
+    private const val UNIFFI_JNI_RETURN_OFFSET = 32
+    private const val UNIFFI_JNI_RUST_BUFFER_SIZE = 16
+
+    // Allocated by Rust for each thread calling it.
+    private val uniffiJniScratch = object : ThreadLocal<ByteBuffer>() {
+        override fun initialValue(): ByteBuffer =
+            jni_uniffi_scratch({{ self.jni_scratch_size() }}).order(ByteOrder.nativeOrder())
+    }
+    private external fun jni_uniffi_scratch(size: Int): ByteBuffer
+
+    // The call status is only read back when the call failed.
+    private fun uniffiJniReadStatus(scratch: ByteBuffer, status: RustCallStatus) {
+        val code = scratch.get(0)
+        if (code != 0.toByte()) {
+            status.code = code.toInt()
+            status.error_buf = uniffiJniReadRustBuffer(scratch, 8)
+        }
+    }
+
+    private fun uniffiJniReadRustBuffer(scratch: ByteBuffer, offset: Int) =
+        RustBuffer.ByValue(scratch.getInt(offset), scratch.getInt(offset + 4), scratch.getLong(offset + 8))
+
+    // A direct buffer over `len` bytes of memory owned by Rust, at the address `data`.
+    internal fun uniffiByteBuffer(data: Long, len: Long): ByteBuffer = jni_uniffi_bytes(data, len)
+    private external fun jni_uniffi_bytes(data: Long, len: Long): ByteBuffer
+
+    fun {{ ci.ffi_contract_version_name() }}(): Int = jni_{{ ci.ffi_contract_version_name() }}()
+    private external fun jni_{{ ci.ffi_contract_version_name() }}(): Int
+
+    {% for func in ci.iter_ffi_function_definitions() -%}
+    {%- if func|has_foreign_callback -%}
+    @Volatile
+    private var {{ func|jni_callback_field }}: ForeignCallback? = null
+
+    // Called by Rust with the fields of the `RustBuffer` of the arguments, and returns the one of
+    // the result through the scratch memory.
+    @JvmStatic
+    fun jni_callback_{{ func.name() }}(handle: Long, method: Int, argsCapacity: Int, argsLen: Int, argsData: Long): Int {
+        val outBuf = RustBufferByReference()
+        val code = {{ func|jni_callback_field }}!!.invoke(handle, method, RustBuffer.ByValue(argsCapacity, argsLen, argsData), outBuf)
+        uniffiJniScratch.get().apply {
+            putInt(UNIFFI_JNI_RETURN_OFFSET, outBuf.rustBuffer.capacity)
+            putInt(UNIFFI_JNI_RETURN_OFFSET + 4, outBuf.rustBuffer.len)
+            putLong(UNIFFI_JNI_RETURN_OFFSET + 8, outBuf.rustBuffer.data)
+        }
+        return code
+    }
+
+    {% endif -%}
+    fun {{ func.name() }}(
+        {%- for arg in func.arguments() %}
+        {{- arg.name()|var_name }}: {{ arg.type_().borrow()|jni_ffi_type_name -}},
+        {%- endfor %}
+        _uniffi_out_err: RustCallStatus
+    ){%- match func.return_type() -%}{%- when Some with (type_) %}: {{ type_.borrow()|jni_ffi_type_name }}{% when None %}{% endmatch %} {
+        {{ func|jni_call }}
+    }
+    private external fun jni_{{ func.name() }}({{ func|jni_arg_list_decl }}): {{ func|jni_return_type }}
+
+    {% endfor %}
+    {%- if config.log_forwarder() %}
+    @Volatile
+    private var uniffiLogCallback: UniFFILogCallback? = null
+
+    // Called by Rust with the address and the length of the UTF-8 target and message.
+    @JvmStatic
+    fun uniffiJniLog(level: Int, target: Long, targetLen: Int, message: Long, messageLen: Int) {
+        uniffiLogCallback?.invoke(level, target, targetLen, message, messageLen)
+    }
+
+    fun {{ ci.ffi_install_log_forwarder_name() }}(callback: UniFFILogCallback, maxLevel: Int, _uniffi_out_err: RustCallStatus): Byte {
+        val _uniffi_scratch = uniffiJniScratch.get()
+        uniffiLogCallback = callback
+        val _uniffi_result = jni_{{ ci.ffi_install_log_forwarder_name() }}(maxLevel)
+        uniffiJniReadStatus(_uniffi_scratch, _uniffi_out_err)
+        return _uniffi_result
+    }
+    private external fun jni_{{ ci.ffi_install_log_forwarder_name() }}(maxLevel: Int): Byte
+    {%- endif %}
+}
@@ -0,0 +1,18 @@
+// JSON values are passed as their JSON text, and need a dependency on `kotlinx-serialization-json`.
+public object FfiConverterJson: FfiConverterRustBuffer<kotlinx.serialization.json.JsonElement> {
+    override fun read(buf: ByteBuffer): kotlinx.serialization.json.JsonElement {
+        val len = buf.getLength()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return kotlinx.serialization.json.Json.parseToJsonElement(byteArr.toString(Charsets.UTF_8))
+    }
+
+    override fun allocationSize(value: kotlinx.serialization.json.JsonElement) = LENGTH_SIZE + value.toString().toByteArray(Charsets.UTF_8).size
+
+    override fun write(value: kotlinx.serialization.json.JsonElement, buf: ByteBuffer) {
+        // `toString()` returns the JSON text of the element.
+        val byteArr = value.toString().toByteArray(Charsets.UTF_8)
+        buf.putLength(byteArr.size)
+        buf.put(byteArr)
+    }
+}
@@ -0,0 +1,121 @@
+{%- let install_fn = ci.ffi_install_log_forwarder_name() %}
+// The callback through which Rust forwards the records of the `log` crate, see
+// `uniffi/src/ffi/foreignlog.rs`. The target and the message are UTF-8 bytes, valid for the
+// duration of the call only.
+{%- match config.ffi_backend() %}
+{%- when FfiBackend::Jna %}
+internal interface UniFFILogCallback : com.sun.jna.Callback {
+    fun invoke(level: Int, target: Pointer, targetLen: Int, message: Pointer, messageLen: Int)
+}
+{%- when FfiBackend::Jni %}
+// With the `jni` FFI backend, the target and the message are passed as their address.
+internal interface UniFFILogCallback {
+    fun invoke(level: Int, target: Long, targetLen: Int, message: Long, messageLen: Int)
+}
+
+private fun uniffiJniString(data: Long, len: Int): String {
+    if (len == 0) {
+        return ""
+    }
+    val bytes = ByteArray(len)
+    _UniFFILib.INSTANCE.uniffiByteBuffer(data, len.toLong()).get(bytes)
+    return String(bytes, Charsets.UTF_8)
+}
+{%- endmatch %}
+
+// The levels of the records of the Rust `log` crate, from the least to the most verbose.
+enum class RustLogLevel {
+    ERROR,
+    WARN,
+    INFO,
+    DEBUG,
+    TRACE,
+}
+
+typealias RustLogger = (level: RustLogLevel, target: String, message: String) -> Unit
+
+private object UniFFILogForwarder : UniFFILogCallback {
+    @Volatile
+    var logger: RustLogger = ::logToPlatform
+
+    {%- match config.ffi_backend() %}
+    {%- when FfiBackend::Jna %}
+    override fun invoke(level: Int, target: Pointer, targetLen: Int, message: Pointer, messageLen: Int) {
+        val rustLevel = RustLogLevel.values().getOrElse(level - 1) { RustLogLevel.TRACE }
+        // An exception must never unwind into Rust.
+        try {
+            logger(
+                rustLevel,
+                String(target.getByteArray(0, targetLen), Charsets.UTF_8),
+                String(message.getByteArray(0, messageLen), Charsets.UTF_8),
+            )
+    {%- when FfiBackend::Jni %}
+    override fun invoke(level: Int, target: Long, targetLen: Int, message: Long, messageLen: Int) {
+        val rustLevel = RustLogLevel.values().getOrElse(level - 1) { RustLogLevel.TRACE }
+        // An exception must never unwind into Rust.
+        try {
+            logger(rustLevel, uniffiJniString(target, targetLen), uniffiJniString(message, messageLen))
+    {%- endmatch %}
+        } catch (e: Throwable) {
+            // Nowhere left to report it.
+        }
+    }
+}
+
+// `android.util.Log.println`, when running on Android.
+private val androidLogPrintln: java.lang.reflect.Method? by lazy {
+    try {
+        Class.forName("android.util.Log").getMethod(
+            "println",
+            Int::class.javaPrimitiveType,
+            String::class.java,
+            String::class.java,
+        )
+    } catch (e: ReflectiveOperationException) {
+        null
+    }
+}
+
+// Log to `android.util.Log` on Android, and to `java.util.logging` elsewhere.
+private fun logToPlatform(level: RustLogLevel, target: String, message: String) {
+    val println = androidLogPrintln
+    if (println != null) {
+        val priority = when (level) {
+            RustLogLevel.ERROR -> 6 // Log.ERROR
+            RustLogLevel.WARN -> 5 // Log.WARN
+            RustLogLevel.INFO -> 4 // Log.INFO
+            RustLogLevel.DEBUG -> 3 // Log.DEBUG
+            RustLogLevel.TRACE -> 2 // Log.VERBOSE
+        }
+        // Older Android versions reject the tags longer than 23 characters.
+        println.invoke(null, priority, target.take(23), message)
+    } else {
+        val julLevel = when (level) {
+            RustLogLevel.ERROR -> java.util.logging.Level.SEVERE
+            RustLogLevel.WARN -> java.util.logging.Level.WARNING
+            RustLogLevel.INFO -> java.util.logging.Level.INFO
+            RustLogLevel.DEBUG -> java.util.logging.Level.FINE
+            RustLogLevel.TRACE -> java.util.logging.Level.FINEST
+        }
+        java.util.logging.Logger.getLogger(target).log(julLevel, message)
+    }
+}
+
+/**
+ * Forward the records of the Rust `log` crate to [logger], which defaults to `android.util.Log`
+ * on Android and to `java.util.logging` elsewhere, with the Rust target as the tag.
+ *
+ * The records more verbose than [maxLevel] are dropped in Rust, without calling into Kotlin. The
+ * logger may be called from any thread.
+ *
+ * @throws IllegalStateException if another logger is already installed for the Rust `log` crate.
+ */
+fun installLogForwarder(maxLevel: RustLogLevel = RustLogLevel.INFO, logger: RustLogger = ::logToPlatform) {
+    UniFFILogForwarder.logger = logger
+    val installed = rustCall() { status ->
+        _UniFFILib.INSTANCE.{{ install_fn }}(UniFFILogForwarder, maxLevel.ordinal + 1, status)
+    }
+    if (installed == 0.toByte()) {
+        throw IllegalStateException("Another logger is already installed for the Rust `log` crate")
+    }
+}
@@ -4,7 +4,7 @@ public object {{ ffi_converter_name }}: FfiConverterRustBuffer<Map<{{ key_type_n
     override fun read(buf: ByteBuffer): Map<{{ key_type_name }}, {{ value_type_name }}> {
         // TODO: Once Kotlin's `buildMap` API is stabilized we should use it here.
         val items : MutableMap<{{ key_type_name }}, {{ value_type_name }}> = mutableMapOf()
-        val len = buf.getInt()
+        val len = buf.getLength()
         repeat(len) {
             val k = {{ key_type|read_fn }}(buf)
             val v = {{ value_type|read_fn }}(buf)
@@ -14,7 +14,7 @@ public object {{ ffi_converter_name }}: FfiConverterRustBuffer<Map<{{ key_type_n
     }
 
     override fun allocationSize(value: Map<{{ key_type_name }}, {{ value_type_name }}>): Int {
-        val spaceForMapSize = 4
+        val spaceForMapSize = LENGTH_SIZE
         val spaceForChildren = value.map { (k, v) ->
             {{ key_type|allocation_size_fn }}(k) +
             {{ value_type|allocation_size_fn }}(v)
@@ -23,7 +23,7 @@ public object {{ ffi_converter_name }}: FfiConverterRustBuffer<Map<{{ key_type_n
     }
 
     override fun write(value: Map<{{ key_type_name }}, {{ value_type_name }}>, buf: ByteBuffer) {
-        buf.putInt(value.size)
+        buf.putLength(value.size)
         // The parens on `(k, v)` here ensure we're calling the right method,
         // which is important for compatibility with older android devices.
         // Ref https://blog.danlew.net/2017/03/16/kotlin-puzzler-whose-line-is-it-anyways/
@@ -7,6 +7,8 @@ private fun findLibraryName(componentName: String): String {
     return "{{ config.cdylib_name() }}"
 }
 
+{% match config.ffi_backend() %}
+{%- when FfiBackend::Jna -%}
 private inline fun <reified Lib : Library> loadIndirect(
     componentName: String
 ): Lib {
@@ -20,6 +22,7 @@ internal interface _UniFFILib : Library {
     companion object {
         internal val INSTANCE: _UniFFILib by lazy {
             loadIndirect<_UniFFILib>(componentName = "{{ ci.namespace() }}")
+            .also { lib: _UniFFILib -> uniffiCheckContractVersion(lib) }
             {% let initialization_fns = self.initialization_fns() %}
             {%- if !initialization_fns.is_empty() -%}
             .also { lib: _UniFFILib ->
@@ -28,13 +31,58 @@ internal interface _UniFFILib : Library {
                 {% endfor -%}
             }
             {% endif %}
+            {%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+            .also { lib: _UniFFILib -> uniffiRunLifecycleHooks(lib) }
+            {%- endif %}
         }
     }
 
+    fun {{ ci.ffi_contract_version_name() }}(): Int
+
     {% for func in ci.iter_ffi_function_definitions() -%}
     fun {{ func.name() }}(
         {%- call kt::arg_list_ffi_decl(func) %}
     ){%- match func.return_type() -%}{%- when Some with (type_) %}: {{ type_.borrow()|ffi_type_name }}{% when None %}: Unit{% endmatch %}
 
     {% endfor %}
+    {%- if config.log_forwarder() %}
+    fun {{ ci.ffi_install_log_forwarder_name() }}(callback: UniFFILogCallback, maxLevel: Int, _uniffi_out_err: RustCallStatus): Byte
+    {%- endif %}
+}
+{%- when FfiBackend::Jni -%}
+{% include "JniLibraryTemplate.kt" %}
+{%- endmatch %}
+
+{%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+// Runs the `#[uniffi::init]` hooks of the library once it is loaded, and registers its
+// `#[uniffi::shutdown]` hooks to run when the JVM exits. The init hooks are called without
+// `rustCall`, whose error handling would use the library being loaded.
+private fun uniffiRunLifecycleHooks(lib: _UniFFILib) {
+    {%- for func in ci.init_hooks() %}
+    RustCallStatus().also { status ->
+        lib.{{ func.name() }}(status)
+        if (!status.isSuccess()) {
+            throw InternalException("The init hook {{ func.name() }} of {{ ci.namespace() }} panicked")
+        }
+    }
+    {%- endfor %}
+    {%- for func in ci.shutdown_hooks() %}
+    Runtime.getRuntime().addShutdownHook(Thread { rustCall { lib.{{ func.name() }}(it) } })
+    {%- endfor %}
+}
+
+{% endif -%}
+// Checks that the library was built from the same interface as these bindings, and with the same
+// version of UniFFI, rather than failing on the first call with mismatched symbols.
+private fun uniffiCheckContractVersion(lib: _UniFFILib) {
+    val bindingsContractVersion = {{ ci.contract_version() }}
+    val scaffoldingContractVersion = lib.{{ ci.ffi_contract_version_name() }}()
+    if (bindingsContractVersion != scaffoldingContractVersion) {
+        throw RuntimeException(
+            "UniFFI contract version mismatch: the bindings of {{ ci.namespace() }} expect version " +
+            "0x${bindingsContractVersion.toString(16)}, but the library provides " +
+            "0x${scaffoldingContractVersion.toString(16)}. Regenerate the bindings with the " +
+            "version of uniffi-bindgen and the UDL file the library was built with."
+        )
+    }
 }
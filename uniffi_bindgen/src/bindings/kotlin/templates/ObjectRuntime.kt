@@ -30,14 +30,15 @@ inline fun <T : Disposable?, R> T.use(block: (T) -> R) =
 
 // The base class for all UniFFI Object types.
 //
-// This class provides core operations for working with the Rust `Arc<T>` pointer to
-// the live Rust struct on the other side of the FFI.
+// This class provides core operations for working with the reference to the live Rust struct
+// on the other side of the FFI: the `Long` handle of an object, or the pointer of a weak reference
+// or of an iterator.
 //
 // There's some subtlety here, because we have to be careful not to operate on a Rust
 // struct after it has been dropped, and because we must expose a public API for freeing
 // the Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
 //
-//   * Each `FFIObject` instance holds an opaque pointer to the underlying Rust struct.
+//   * Each `FFIObject` instance holds an opaque reference to the underlying Rust struct.
 //     Method calls need to read this pointer from the object's state and pass it in to
 //     the Rust FFI.
 //
@@ -99,26 +100,30 @@ inline fun <T : Disposable?, R> T.use(block: (T) -> R) =
 // called *and* all in-flight method calls have completed, avoiding violating any of the expectations
 // of the underlying Rust code.
 //
-// In the future we may be able to replace some of this with automatic finalization logic, such as using
-// the new "Cleaner" functionaility in Java 9. The above scheme has been designed to work even if `destroy` is
-// invoked by garbage-collection machinery rather than by calling code (which by the way, it's apparently also
-// possible for the JVM to finalize an object while there is an in-flight call to one of its methods [1],
-// so there would still be some complexity here).
+// Rust checks the handles of the objects whenever they're used, so that a stale handle is reported as
+// an error rather than being dereferenced, but these rules still make sure it never happens.
 //
-// Sigh...all of this for want of a robust finalization mechanism.
+// Calling code may also never call `destroy`, so each `FFIObject` registers a cleanup action with a
+// shared `UniFFICleaner`, which frees the underlying Rust struct once the object has become
+// unreachable. The action is also what `destroy` runs, and is guaranteed to run at most once.
+// The cleanup action must not hold a reference to the object itself, otherwise it would never
+// become unreachable, so it only captures the pointer.
+//
+// It's apparently possible for the JVM to consider an object unreachable while there is an
+// in-flight call to one of its methods [1]. `callWithPointer` reads the call counter once the
+// call has completed, which keeps the object reachable until then.
 //
 // [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
 //
-abstract class FFIObject(
-    protected val pointer: Pointer
+abstract class FFIObject<P>(
+    protected val pointer: P
 ): Disposable, AutoCloseable {
 
     private val wasDestroyed = AtomicBoolean(false)
     private val callCounter = AtomicLong(1)
 
-    open protected fun freeRustArcPtr() {
-        // To be overridden in subclasses.
-    }
+    // Frees the underlying Rust struct. Created by subclasses, which know how to free it.
+    protected abstract val cleanable: UniFFICleaner.Cleanable
 
     override fun destroy() {
         // Only allow a single call to this method.
@@ -126,7 +131,7 @@ abstract class FFIObject(
         if (this.wasDestroyed.compareAndSet(false, true)) {
             // This decrement always matches the initial count of 1 given at creation time.
             if (this.callCounter.decrementAndGet() == 0L) {
-                this.freeRustArcPtr()
+                this.cleanable.clean()
             }
         }
     }
@@ -136,7 +141,7 @@ abstract class FFIObject(
         this.destroy()
     }
 
-    internal inline fun <R> callWithPointer(block: (ptr: Pointer) -> R): R {
+    internal inline fun <R> callWithPointer(block: (ptr: P) -> R): R {
         // Check and increment the call counter, to keep the object alive.
         // This needs a compare-and-set retry loop in case of concurrent updates.
         do {
@@ -154,8 +159,85 @@ abstract class FFIObject(
         } finally {
             // This decrement aways matches the increment we performed above.
             if (this.callCounter.decrementAndGet() == 0L) {
-                this.freeRustArcPtr()
+                this.cleanable.clean()
             }
         }
     }
 }
+
+// Runs cleanup actions once the objects they were registered for have become unreachable.
+//
+// `java.lang.ref.Cleaner` is only available from Java 9 and Android API level 33, so we fall back
+// to an equivalent based on `PhantomReference` on older runtimes.
+interface UniFFICleaner {
+    interface Cleanable {
+        fun clean()
+    }
+
+    fun register(value: Any, cleanUpTask: Runnable): UniFFICleaner.Cleanable
+
+    companion object {
+        internal val INSTANCE: UniFFICleaner by lazy {
+            try {
+                JavaLangRefCleaner()
+            } catch (e: NoClassDefFoundError) {
+                PhantomReferenceCleaner()
+            }
+        }
+    }
+}
+
+private class JavaLangRefCleaner : UniFFICleaner {
+    val cleaner = java.lang.ref.Cleaner.create()
+
+    override fun register(value: Any, cleanUpTask: Runnable): UniFFICleaner.Cleanable =
+        JavaLangRefCleanable(cleaner.register(value, cleanUpTask))
+}
+
+private class JavaLangRefCleanable(
+    val cleanable: java.lang.ref.Cleaner.Cleanable
+) : UniFFICleaner.Cleanable {
+    override fun clean() = cleanable.clean()
+}
+
+private class PhantomReferenceCleaner : UniFFICleaner {
+    private val queue = java.lang.ref.ReferenceQueue<Any>()
+    // Keeps the references themselves alive until they have been enqueued.
+    private val cleanables = java.util.concurrent.ConcurrentHashMap.newKeySet<PhantomReferenceCleanable>()
+
+    init {
+        val thread = Thread({
+            while (true) {
+                (queue.remove() as PhantomReferenceCleanable).clean()
+            }
+        }, "uniffi-cleaner")
+        thread.isDaemon = true
+        thread.start()
+    }
+
+    override fun register(value: Any, cleanUpTask: Runnable): UniFFICleaner.Cleanable =
+        PhantomReferenceCleanable(value, queue, cleanUpTask).also { cleanables.add(it) }
+
+    private inner class PhantomReferenceCleanable(
+        value: Any,
+        queue: java.lang.ref.ReferenceQueue<Any>,
+        private val cleanUpTask: Runnable
+    ) : java.lang.ref.PhantomReference<Any>(value, queue), UniFFICleaner.Cleanable {
+        private val cleaned = AtomicBoolean(false)
+
+        override fun clean() {
+            if (cleaned.compareAndSet(false, true)) {
+                cleanables.remove(this)
+                try {
+                    cleanUpTask.run()
+                } catch (e: Throwable) {
+                    // Like `java.lang.ref.Cleaner`, ignore exceptions thrown by the cleanup action.
+                }
+            }
+        }
+    }
+}
+
+// The handle of a Rust object, which the object classes are constructed with. It's wrapped so that
+// their constructors can't clash with the constructors declared in the UDL.
+class UniFFIHandle(val value: Long)
@@ -5,41 +5,51 @@
 
 public interface {{ type_name }}Interface {
     {% for meth in obj.methods() -%}
+    {{ meth.docstring()|docstring("    ") }}
     {%- match meth.throws_type() -%}
     {%- when Some with (throwable) %}
     @Throws({{ throwable|type_name }}::class)
     {%- else -%}
     {%- endmatch %}
+    {%- match kotlin_config.blocking_thread_annotation() -%}
+    {%- when Some with (annotation) %}
+    {{ annotation }}
+    {%- else -%}
+    {%- endmatch %}
     fun {{ meth.name()|fn_name }}({% call kt::arg_list_decl(meth) %})
     {%- match meth.return_type() -%}
     {%- when Some with (return_type) %}: {{ return_type|type_name -}}
     {%- else -%}
     {%- endmatch %}
+    {% if kotlin_config.coroutine_dispatcher().is_some() -%}
+    {% call kt::suspend_fn(meth) %}
+    {% endif -%}
     {% endfor %}
 }
-
+{{ obj.docstring()|docstring("") }}
 class {{ type_name }}(
-    pointer: Pointer
-) : FFIObject(pointer), {{ type_name }}Interface {
+    handle: UniFFIHandle
+) : FFIObject<Long>(handle.value), {{ type_name }}Interface {
 
     {%- match obj.primary_constructor() %}
     {%- when Some with (cons) %}
+    {% if cons.has_default_arguments() %}@JvmOverloads {% endif -%}
+    {% match kotlin_config.blocking_thread_annotation() %}{% when Some with (annotation) %}{{ annotation }} {% else %}{% endmatch -%}
     constructor({% call kt::arg_list_decl(cons) -%}) :
-        this({% call kt::to_ffi_call(cons) %})
+        this(UniFFIHandle({% call kt::to_ffi_call(cons) %}))
     {%- when None %}
     {%- endmatch %}
 
-    /**
-     * Disconnect the object from the underlying Rust object.
-     *
-     * It can be called more than once, but once called, interacting with the object
-     * causes an `IllegalStateException`.
-     *
-     * Clients **must** call this method once done with the object, or cause a memory leak.
-     */
-    override protected fun freeRustArcPtr() {
-        rustCall() { status ->
-            _UniFFILib.INSTANCE.{{ obj.ffi_object_free().name() }}(this.pointer, status)
+    override protected val cleanable: UniFFICleaner.Cleanable =
+        UniFFICleaner.INSTANCE.register(this, UniFFICleanAction(handle.value))
+
+    // Frees the handle of the underlying Rust object, either when `destroy` is called or when this
+    // object is garbage collected. It must not hold a reference to this object.
+    private class UniFFICleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_object_free().name() }}(handle, status)
+            }
         }
     }
 
@@ -49,6 +59,11 @@ class {{ type_name }}(
     @Throws({{ throwable|type_name }}::class)
     {%- else -%}
     {%- endmatch %}
+    {%- match kotlin_config.blocking_thread_annotation() -%}
+    {%- when Some with (annotation) %}
+    {{ annotation }}
+    {% else -%}
+    {%- endmatch %}
     {%- match meth.return_type() -%}
 
     {%- when Some with (return_type) -%}
@@ -65,36 +80,66 @@ class {{ type_name }}(
             {%- call kt::to_ffi_call_with_prefix("it", meth) %}
         }
     {% endmatch %}
+    {%- if kotlin_config.direct_byte_buffers() && (meth.arguments()|has_bytes_argument || meth.return_type()|is_bytes_return) %}
+    {% call kt::direct_fn(meth, true, kotlin_config.blocking_thread_annotation()) %}
+    {% endif %}
     {% endfor %}
 
-    {% if !obj.alternate_constructors().is_empty() -%}
+    {% if !obj.alternate_constructors().is_empty() || !obj.static_methods().is_empty() -%}
     companion object {
         {% for cons in obj.alternate_constructors() -%}
+        {% if cons.has_default_arguments() %}@JvmOverloads {% endif -%}
+        {% match kotlin_config.blocking_thread_annotation() %}{% when Some with (annotation) %}{{ annotation }} {% else %}{% endmatch -%}
         fun {{ cons.name()|fn_name }}({% call kt::arg_list_decl(cons) %}): {{ type_name }} =
-            {{ type_name }}({% call kt::to_ffi_call(cons) %})
+            {{ type_name }}(UniFFIHandle({% call kt::to_ffi_call(cons) %}))
+        {% endfor %}
+
+        {%- for meth in obj.static_methods() %}
+        {{ meth.docstring()|docstring("        ") }}
+        {%- match meth.throws_type() -%}
+        {%- when Some with (throwable) %}
+        @Throws({{ throwable|type_name }}::class)
+        {%- else -%}
+        {%- endmatch %}
+        {% if meth.has_default_arguments() %}@JvmOverloads {% endif -%}
+        {% match kotlin_config.blocking_thread_annotation() %}{% when Some with (annotation) %}{{ annotation }} {% else %}{% endmatch -%}
+        {%- match meth.return_type() -%}
+        {%- when Some with (return_type) -%}
+        fun {{ meth.name()|fn_name }}({% call kt::arg_list_decl(meth) %}): {{ return_type|type_name }} =
+            {{ return_type|lift_fn }}({% call kt::to_ffi_call(meth) %})
+        {%- when None -%}
+        fun {{ meth.name()|fn_name }}({% call kt::arg_list_decl(meth) %}) =
+            {% call kt::to_ffi_call(meth) %}
+        {%- endmatch %}
         {% endfor %}
     }
     {% endif %}
 }
 
-public object {{ obj|ffi_converter_name }}: FfiConverter<{{ type_name }}, Pointer> {
-    override fun lower(value: {{ type_name }}): Pointer = value.callWithPointer { it }
+public object {{ obj|ffi_converter_name }}: FfiConverter<{{ type_name }}, Long> {
+    override fun lower(value: {{ type_name }}): Long = value.callWithPointer { it }
 
-    override fun lift(value: Pointer): {{ type_name }} {
-        return {{ type_name }}(value)
+    override fun lift(value: Long): {{ type_name }} {
+        return {{ type_name }}(UniFFIHandle(value))
     }
 
     override fun read(buf: ByteBuffer): {{ type_name }} {
-        // The Rust code always writes pointers as 8 bytes, and will
-        // fail to compile if they don't fit.
-        return lift(Pointer(buf.getLong()))
+        return lift(buf.getLong())
     }
 
     override fun allocationSize(value: {{ type_name }}) = 8
 
     override fun write(value: {{ type_name }}, buf: ByteBuffer) {
-        // The Rust code always expects pointers written as 8 bytes,
-        // and will fail to compile if they don't fit.
-        buf.putLong(Pointer.nativeValue(lower(value)))
+        val handle = value.callWithPointer {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_object_clone().name() }}(it, status)
+            }
+        }
+        UniFFIClonedHandles.add {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_object_free().name() }}(handle, status)
+            }
+        }
+        buf.putLong(handle)
     }
 }
@@ -0,0 +1,21 @@
+public object FfiConverterOffsetDateTime: FfiConverterRustBuffer<java.time.OffsetDateTime> {
+    override fun read(buf: ByteBuffer): java.time.OffsetDateTime {
+        val seconds = buf.getLong()
+        // Type mismatch (should be u32) but values are always between 0 and 999,999,999
+        val nanoseconds = buf.getInt().toLong()
+        val offset = java.time.ZoneOffset.ofTotalSeconds(buf.getInt())
+        return java.time.OffsetDateTime.ofInstant(java.time.Instant.ofEpochSecond(seconds, nanoseconds), offset)
+    }
+
+    // 8 bytes for seconds, 4 bytes for nanoseconds, 4 bytes for the offset
+    override fun allocationSize(value: java.time.OffsetDateTime) = 16
+
+    override fun write(value: java.time.OffsetDateTime, buf: ByteBuffer) {
+        // `toEpochSecond()` rounds towards negative infinity and `nano` is always
+        // positive, which is how the date-time is split on the buffer.
+        buf.putLong(value.toEpochSecond())
+        // Type mismatch (should be u32) but values are always between 0 and 999,999,999
+        buf.putInt(value.nano)
+        buf.putInt(value.offset.totalSeconds)
+    }
+}
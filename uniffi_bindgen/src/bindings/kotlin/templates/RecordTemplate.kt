@@ -1,7 +1,18 @@
 {%- let rec = ci.get_record_definition(name).unwrap() %}
-
-data class {{ type_name }} (
+{%- let serializable = kotlin_config.serializable() && !contains_object_references %}
+{%- let parcelable = kotlin_config.parcelable() && !contains_object_references %}
+{{ rec.docstring()|docstring("") }}
+{% if serializable -%}
+@kotlinx.serialization.Serializable
+@kotlinx.serialization.SerialName("{{ rec.name() }}")
+{% endif -%}
+{% if parcelable -%}
+@kotlinx.parcelize.Parcelize
+{% endif -%}
+data class {{ type_name }} {% if rec.has_default_fields() %}@JvmOverloads constructor{% endif %}(
     {%- for field in rec.fields() %}
+    {{- field.docstring()|docstring("    ") }}
+    {% if serializable %}@kotlinx.serialization.SerialName("{{ field.name() }}") {% endif -%}
     var {{ field.name()|var_name }}: {{ field|type_name -}}
     {%- match field.default_value() %}
         {%- when Some with(literal) %} = {{ literal|render_literal(field) }}
@@ -9,16 +20,81 @@ data class {{ type_name }} (
     {%- endmatch -%}
     {% if !loop.last %}, {% endif %}
     {%- endfor %}
-) {% if contains_object_references %}: Disposable {% endif %}{
+) {% if contains_object_references %}: Disposable {% else if parcelable %}: android.os.Parcelable {% endif %}{
     {% if contains_object_references %}
     @Suppress("UNNECESSARY_SAFE_CALL") // codegen is much simpler if we unconditionally emit safe calls here
     override fun destroy() {
         {% call kt::destroy_fields(rec) %}
     }
     {% endif %}
+    {%- include "DataMethodsTemplate.kt" %}
+}
+
+{% if rec.is_ffi_struct() -%}
+{%- let ffi_struct = format!("FfiStruct{}", type_name) %}
+// The record is made only of numbers and booleans, so it's passed as a C struct of the FFI values
+// of its fields, rather than serialized. It's still serialized inside other values.
+{%- match kotlin_config.ffi_backend() %}
+{%- when FfiBackend::Jna %}
+@Structure.FieldOrder({% for field in rec.fields() %}"{{ field.name()|var_name|unquote }}"{% if !loop.last %}, {% endif %}{% endfor %})
+open class {{ ffi_struct }} : Structure() {
+{%- when FfiBackend::Jni %}
+// With the `jni` FFI backend, the fields are written to and read from the scratch memory of the
+// thread, in slots of 8 bytes, see `uniffi/src/ffi/jni.rs`.
+open class {{ ffi_struct }} {
+{%- endmatch %}
+    {%- for field in rec.fields() %}
+    {%- let ffi_type = FFIType::from(field.type_()) %}
+    @JvmField var {{ field.name()|var_name }}: {{ ffi_type|ffi_type_name }} = {% match ffi_type %}{% when FFIType::Float32 %}0.0f{% when FFIType::Float64 %}0.0{% else %}0{% endmatch %}
+    {%- endfor %}
+    {%- match kotlin_config.ffi_backend() %}
+    {%- when FfiBackend::Jna %}
+
+    class ByValue : {{ ffi_struct }}(), Structure.ByValue
+    {%- when FfiBackend::Jni %}
+
+    internal fun jniWrite(buf: ByteBuffer, offset: Int) {
+        {%- for field in rec.fields() %}
+        {%- let ffi_type = FFIType::from(field.type_()) %}
+        buf.put{{ ffi_type|jni_byte_buffer_suffix }}(offset + {{ loop.index0 * 8 }}, {{ field.name()|var_name }})
+        {%- endfor %}
+    }
+
+    class ByValue : {{ ffi_struct }}()
+
+    companion object {
+        internal const val SIZE = {{ rec.fields().len() * 8 }}
+
+        internal fun jniRead(buf: ByteBuffer, offset: Int) = ByValue().also {
+            {%- for field in rec.fields() %}
+            {%- let ffi_type = FFIType::from(field.type_()) %}
+            it.{{ field.name()|var_name }} = buf.get{{ ffi_type|jni_byte_buffer_suffix }}(offset + {{ loop.index0 * 8 }})
+            {%- endfor %}
+        }
+    }
+    {%- endmatch %}
 }
 
+public object {{ rec|ffi_converter_name }}: FfiConverter<{{ type_name }}, {{ ffi_struct }}.ByValue> {
+    override fun lift(value: {{ ffi_struct }}.ByValue): {{ type_name }} {
+        return {{ type_name }}(
+        {%- for field in rec.fields() %}
+            {{ field|lift_fn }}(value.{{ field.name()|var_name }}),
+        {%- endfor %}
+        )
+    }
+
+    override fun lower(value: {{ type_name }}): {{ ffi_struct }}.ByValue {
+        return {{ ffi_struct }}.ByValue().also {
+        {%- for field in rec.fields() %}
+            it.{{ field.name()|var_name }} = {{ field|lower_fn }}(value.{{ field.name()|var_name }})
+        {%- endfor %}
+        }
+    }
+
+{% else -%}
 public object {{ rec|ffi_converter_name }}: FfiConverterRustBuffer<{{ type_name }}> {
+{% endif -%}
     override fun read(buf: ByteBuffer): {{ type_name }} {
         return {{ type_name }}(
         {%- for field in rec.fields() %}
@@ -0,0 +1,32 @@
+{%- let err_type_name = err_type|type_name %}
+
+public object {{ ffi_converter_name }}: FfiConverterRustBuffer<{{ type_name }}> {
+    override fun read(buf: ByteBuffer): {{ type_name }} {
+        return when(buf.get().toInt()) {
+            0 -> Result.success({{ ok_type|read_fn }}(buf))
+            1 -> Result.failure({{ err_type|read_fn }}(buf))
+            else -> throw InternalException("Unexpected tag byte for Result")
+        }
+    }
+
+    override fun allocationSize(value: {{ type_name }}): Int {
+        return 1 + value.fold(
+            { {{ ok_type|allocation_size_fn }}(it) },
+            { {{ err_type|allocation_size_fn }}(it as {{ err_type_name }}) }
+        )
+    }
+
+    // Writing a failure which doesn't hold a `{{ err_type_name }}` throws a `ClassCastException`.
+    override fun write(value: {{ type_name }}, buf: ByteBuffer) {
+        value.fold(
+            {
+                buf.put(0)
+                {{ ok_type|write_fn }}(it, buf)
+            },
+            {
+                buf.put(1)
+                {{ err_type|write_fn }}(it as {{ err_type_name }}, buf)
+            }
+        )
+    }
+}
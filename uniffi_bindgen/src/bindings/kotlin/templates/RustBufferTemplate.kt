@@ -1,6 +1,8 @@
 // This is a helper for safely working with byte buffers returned from the Rust code.
 // A rust-owned buffer is represented by its capacity, its current length, and a
 // pointer to the underlying data.
+{%- match config.ffi_backend() %}
+{%- when FfiBackend::Jna %}
 
 @Structure.FieldOrder("capacity", "len", "data")
 open class RustBuffer : Structure() {
@@ -30,6 +32,12 @@ open class RustBuffer : Structure() {
         this.data?.getByteBuffer(0, this.len.toLong())?.also {
             it.order(ByteOrder.BIG_ENDIAN)
         }
+
+    // A buffer over the whole capacity, to write a value into.
+    internal fun asWritableByteBuffer() =
+        this.data!!.getByteBuffer(0, this.capacity.toLong()).also {
+            it.order(ByteOrder.BIG_ENDIAN)
+        }
 }
 
 /**
@@ -64,3 +72,76 @@ open class ForeignBytes : Structure() {
 
     class ByValue : ForeignBytes(), Structure.ByValue
 }
+{%- when FfiBackend::Jni %}
+
+// With the `jni` FFI backend, the pointer is the address of the data, and the buffer is passed
+// as its fields.
+open class RustBuffer(
+    @JvmField var capacity: Int = 0,
+    @JvmField var len: Int = 0,
+    // The address of the data, or 0.
+    @JvmField var data: Long = 0L,
+) {
+    class ByValue(capacity: Int = 0, len: Int = 0, data: Long = 0L) : RustBuffer(capacity, len, data)
+
+    companion object {
+        internal fun alloc(size: Int = 0) = rustCall() { status ->
+            _UniFFILib.INSTANCE.{{ ci.ffi_rustbuffer_alloc().name() }}(size, status).also {
+                if(it.data == 0L) {
+                   throw RuntimeException("RustBuffer.alloc() returned null data pointer (size=${size})")
+               }
+            }
+        }
+
+        internal fun free(buf: RustBuffer.ByValue) = rustCall() { status ->
+            _UniFFILib.INSTANCE.{{ ci.ffi_rustbuffer_free().name() }}(buf, status)
+        }
+    }
+
+    fun asByteBuffer() =
+        if (this.data == 0L) {
+            null
+        } else {
+            _UniFFILib.INSTANCE.uniffiByteBuffer(this.data, this.len.toLong()).also {
+                it.order(ByteOrder.BIG_ENDIAN)
+            }
+        }
+
+    // A buffer over the whole capacity, to write a value into.
+    internal fun asWritableByteBuffer() =
+        _UniFFILib.INSTANCE.uniffiByteBuffer(this.data, this.capacity.toLong()).also {
+            it.order(ByteOrder.BIG_ENDIAN)
+        }
+}
+
+/**
+ * The equivalent of the `*mut RustBuffer` type.
+ * Required for callbacks taking in an out pointer.
+ *
+ * The `RustBuffer` is written to the scratch memory once the callback returns.
+ */
+class RustBufferByReference {
+    internal var rustBuffer = RustBuffer.ByValue()
+
+    /**
+     * Set the pointed-to `RustBuffer` to the given value.
+     */
+    fun setValue(value: RustBuffer.ByValue) {
+        rustBuffer = value
+    }
+}
+
+// This is a helper for safely passing byte references into the rust code.
+// It's not actually used at the moment, because there aren't many things that you
+// can take a direct pointer to in the JVM, and if we're going to copy something
+// then we might as well copy it into a `RustBuffer`. But it's here for API
+// completeness.
+
+open class ForeignBytes(
+    @JvmField var len: Int = 0,
+    // The address of the data, or 0.
+    @JvmField var data: Long = 0L,
+) {
+    class ByValue(len: Int = 0, data: Long = 0L) : ForeignBytes(len, data)
+}
+{%- endmatch %}
@@ -0,0 +1,49 @@
+/**
+ * A `sequence<u8>` held in memory owned by Rust, and exposed as a direct `ByteBuffer` without
+ * copying it.
+ *
+ * It must be destroyed once done with, or it leaks the underlying memory. Passing it as an
+ * argument to Rust transfers its ownership, after which it can't be used anymore.
+ */
+class RustByteBuffer internal constructor(
+    private var rustBuffer: RustBuffer.ByValue?
+) : AutoCloseable {
+    /**
+     * The bytes, as a direct `ByteBuffer` over the memory owned by Rust.
+     *
+     * It must not be used once this `RustByteBuffer` has been destroyed or passed to Rust.
+     */
+    val buffer: ByteBuffer
+        @Synchronized get() {
+            val rbuf = rustBuffer ?: throw IllegalStateException("RustByteBuffer has already been destroyed")
+            return rbuf.asByteBuffer()!!
+        }
+
+    @Synchronized
+    fun destroy() {
+        rustBuffer?.let { RustBuffer.free(it) }
+        rustBuffer = null
+    }
+
+    override fun close() {
+        this.destroy()
+    }
+
+    // Hand the underlying `RustBuffer` over to Rust, which will free it.
+    @Synchronized
+    internal fun intoRustBuffer(): RustBuffer.ByValue {
+        val rbuf = rustBuffer ?: throw IllegalStateException("RustByteBuffer has already been destroyed")
+        rustBuffer = null
+        return rbuf
+    }
+
+    companion object {
+        /**
+         * Allocate a buffer of `size` bytes in memory owned by Rust, to be filled through
+         * `buffer` before passing it to Rust.
+         */
+        fun allocate(size: Int) = RustByteBuffer(RustBuffer.alloc(size))
+
+        internal fun lift(rbuf: RustBuffer.ByValue) = RustByteBuffer(rbuf)
+    }
+}
@@ -1,23 +1,82 @@
 {%- let inner_type_name = inner_type|type_name %}
 
 public object {{ ffi_converter_name }}: FfiConverterRustBuffer<List<{{ inner_type_name }}>> {
+    {%- if ci.compact_encoding() && type_|is_strings %}
+    // With the compact encoding, the strings share their length prefix when they all have the same
+    // length: it's written once, plus one, after the count, and is zero otherwise.
+    override fun read(buf: ByteBuffer): List<String> {
+        val len = buf.getLength()
+        if (len == 0) {
+            return listOf()
+        }
+        val sharedLength = buf.getLength()
+        return List<String>(len) {
+            val byteArr = ByteArray(if (sharedLength == 0) buf.getLength() else sharedLength - 1)
+            buf.get(byteArr)
+            byteArr.toString(Charsets.UTF_8)
+        }
+    }
+
+    override fun allocationSize(value: List<String>): Int {
+        val sizeForLengths = LENGTH_SIZE * 2
+        val sizeForItems = value.map { FfiConverterString.allocationSize(it) }.sum()
+        return sizeForLengths + sizeForItems
+    }
+
+    override fun write(value: List<String>, buf: ByteBuffer) {
+        buf.putLength(value.size)
+        if (value.isEmpty()) {
+            return
+        }
+        val byteArrs = value.map { it.toByteArray(Charsets.UTF_8) }
+        val shared = byteArrs.all { it.size == byteArrs[0].size }
+        buf.putLength(if (shared) byteArrs[0].size + 1 else 0)
+        byteArrs.forEach {
+            if (!shared) {
+                buf.putLength(it.size)
+            }
+            buf.put(it)
+        }
+    }
+    {%- else %}
     override fun read(buf: ByteBuffer): List<{{ inner_type_name }}> {
-        val len = buf.getInt()
+        val len = buf.getLength()
         return List<{{ inner_type_name }}>(len) {
             {{ inner_type|read_fn }}(buf)
         }
     }
 
     override fun allocationSize(value: List<{{ inner_type_name }}>): Int {
-        val sizeForLength = 4
+        val sizeForLength = LENGTH_SIZE
         val sizeForItems = value.map { {{ inner_type|allocation_size_fn }}(it) }.sum()
         return sizeForLength + sizeForItems
     }
 
     override fun write(value: List<{{ inner_type_name }}>, buf: ByteBuffer) {
-        buf.putInt(value.size)
+        buf.putLength(value.size)
         value.forEach {
             {{ inner_type|write_fn }}(it, buf)
         }
     }
+    {%- endif %}
+    {%- if type_|is_bytes %}
+
+    // A `sequence<u8>` passed on its own, rather than inside another value, is passed as its raw
+    // bytes, without a length prefix.
+    override fun lift(value: RustBuffer.ByValue): List<UByte> {
+        try {
+            val bytes = ByteArray(value.len)
+            value.asByteBuffer()?.get(bytes)
+            return bytes.map { it.toUByte() }
+        } finally {
+            RustBuffer.free(value)
+        }
+    }
+
+    override fun lower(value: List<UByte>): RustBuffer.ByValue {
+        val rbuf = RustBuffer.alloc(value.size)
+        rbuf.asByteBuffer()!!.put(ByteArray(value.size) { value[it].toByte() })
+        return rbuf
+    }
+    {%- endif %}
 }
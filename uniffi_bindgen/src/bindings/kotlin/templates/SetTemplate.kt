@@ -0,0 +1,26 @@
+{%- let inner_type_name = inner_type|type_name %}
+
+public object {{ ffi_converter_name }}: FfiConverterRustBuffer<Set<{{ inner_type_name }}>> {
+    override fun read(buf: ByteBuffer): Set<{{ inner_type_name }}> {
+        // The items keep the order they have in Rust, which is sorted for a `BTreeSet`.
+        val items : MutableSet<{{ inner_type_name }}> = LinkedHashSet()
+        val len = buf.getLength()
+        repeat(len) {
+            items.add({{ inner_type|read_fn }}(buf))
+        }
+        return items
+    }
+
+    override fun allocationSize(value: Set<{{ inner_type_name }}>): Int {
+        val sizeForLength = LENGTH_SIZE
+        val sizeForItems = value.map { {{ inner_type|allocation_size_fn }}(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: Set<{{ inner_type_name }}>, buf: ByteBuffer) {
+        buf.putLength(value.size)
+        value.forEach {
+            {{ inner_type|write_fn }}(it, buf)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+{%- if self.include_once_check("ObjectRuntime.kt") %}{% include "ObjectRuntime.kt" %}{% endif %}
+
+// A region of read-only memory owned by Rust, which is read through `buffer` without copying it.
+//
+// Like objects, shared slices must be released once they're no longer needed, with `release()` or
+// the `use` helper method, after which `buffer` must not be read anymore. Otherwise, the memory is
+// freed once `buffer` and the buffers derived from it have become unreachable, rather than the
+// slice itself, so that they can't outlive it.
+public class SharedSlice internal constructor(
+    handle: Long
+) : FFIObject<Long>(handle) {
+
+    public val buffer: ByteBuffer
+
+    override protected val cleanable: UniFFICleaner.Cleanable
+
+    init {
+        val len = rustCall() { status ->
+            _UniFFILib.INSTANCE.{{ ci.ffi_shared_slice_len().name() }}(handle, status)
+        }
+        if (len > Int.MAX_VALUE) {
+            UniFFICleanAction(handle).run()
+            throw InternalException("Shared slices of more than 2 GiB can't be read as a ByteBuffer")
+        }
+        val data = rustCall() { status ->
+            _UniFFILib.INSTANCE.{{ ci.ffi_shared_slice_data().name() }}(handle, status)
+        }
+        // The read-only buffer, like any buffer derived from it, keeps `rawBuffer` reachable.
+        {%- match kotlin_config.ffi_backend() %}
+        {%- when FfiBackend::Jna %}
+        val rawBuffer = data.getByteBuffer(0, len)
+        {%- when FfiBackend::Jni %}
+        val rawBuffer = _UniFFILib.INSTANCE.uniffiByteBuffer(data, len)
+        {%- endmatch %}
+        buffer = rawBuffer.asReadOnlyBuffer()
+        cleanable = UniFFICleaner.INSTANCE.register(rawBuffer, UniFFICleanAction(handle))
+    }
+
+    // Frees the underlying Rust memory, after which `buffer` must not be read anymore.
+    public fun release() = destroy()
+
+    private class UniFFICleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ ci.ffi_shared_slice_free().name() }}(handle, status)
+            }
+        }
+    }
+}
+
+public object FfiConverterSharedSlice: FfiConverter<SharedSlice, Long> {
+    override fun lower(value: SharedSlice): Long = value.callWithPointer { it }
+
+    override fun lift(value: Long): SharedSlice {
+        return SharedSlice(value)
+    }
+
+    override fun read(buf: ByteBuffer): SharedSlice {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: SharedSlice) = 8
+
+    override fun write(value: SharedSlice, buf: ByteBuffer) {
+        throw RuntimeException("Shared slices can only be returned by Rust")
+    }
+}
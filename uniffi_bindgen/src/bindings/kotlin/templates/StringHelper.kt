@@ -2,44 +2,58 @@ public object FfiConverterString: FfiConverter<String, RustBuffer.ByValue> {
     // Note: we don't inherit from FfiConverterRustBuffer, because we use a
     // special encoding when lowering/lifting.  We can use `RustBuffer.len` to
     // store our length and avoid writing it out to the buffer.
+    {%- if ci.utf16_strings() %}
+    // The strings lowered and lifted on their own are passed as little-endian UTF-16, which
+    // Rust converts, rather than transcoded to UTF-8 here.
+    {%- endif %}
     override fun lift(value: RustBuffer.ByValue): String {
         try {
+            {%- if ci.utf16_strings() %}
+            return value.asByteBuffer()!!.order(ByteOrder.LITTLE_ENDIAN).asCharBuffer().toString()
+            {%- else %}
             val byteArr = ByteArray(value.len)
             value.asByteBuffer()!!.get(byteArr)
             return byteArr.toString(Charsets.UTF_8)
+            {%- endif %}
         } finally {
             RustBuffer.free(value)
         }
     }
 
     override fun read(buf: ByteBuffer): String {
-        val len = buf.getInt()
+        val len = buf.getLength()
         val byteArr = ByteArray(len)
         buf.get(byteArr)
         return byteArr.toString(Charsets.UTF_8)
     }
 
     override fun lower(value: String): RustBuffer.ByValue {
+        {%- if ci.utf16_strings() %}
+        val rbuf = RustBuffer.alloc(value.length * 2)
+        rbuf.asByteBuffer()!!.order(ByteOrder.LITTLE_ENDIAN).asCharBuffer().put(value)
+        return rbuf
+        {%- else %}
         val byteArr = value.toByteArray(Charsets.UTF_8)
         // Ideally we'd pass these bytes to `ffi_bytebuffer_from_bytes`, but doing so would require us
         // to copy them into a JNA `Memory`. So we might as well directly copy them into a `RustBuffer`.
         val rbuf = RustBuffer.alloc(byteArr.size)
         rbuf.asByteBuffer()!!.put(byteArr)
         return rbuf
+        {%- endif %}
     }
 
     // We aren't sure exactly how many bytes our string will be once it's UTF-8
     // encoded.  Allocate 3 bytes per unicode codepoint which will always be
     // enough.
     override fun allocationSize(value: String): Int {
-        val sizeForLength = 4
+        val sizeForLength = LENGTH_SIZE
         val sizeForString = value.length * 3
         return sizeForLength + sizeForString
     }
 
     override fun write(value: String, buf: ByteBuffer) {
         val byteArr = value.toByteArray(Charsets.UTF_8)
-        buf.putInt(byteArr.size)
+        buf.putLength(byteArr.size)
         buf.put(byteArr)
     }
 }
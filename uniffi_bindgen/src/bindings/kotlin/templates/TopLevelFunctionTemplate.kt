@@ -1,17 +1,33 @@
+{{ func.docstring()|docstring("") }}
 {%- match func.throws_type() -%}
 {%- when Some with (throwable) %}
 @Throws({{ throwable|type_name }}::class)
 {%- else -%}
 {%- endmatch %}
+{%- match config.blocking_thread_annotation() -%}
+{%- when Some with (annotation) %}
+{{ annotation }}
+{%- else -%}
+{%- endmatch %}
 {%- match func.return_type() -%}
 {%- when Some with (return_type) %}
-
+{%- if func.has_default_arguments() %}
+@JvmOverloads
+{%- endif %}
 fun {{ func.name()|fn_name }}({%- call kt::arg_list_decl(func) -%}): {{ return_type|type_name }} {
     return {{ return_type|lift_fn }}({% call kt::to_ffi_call(func) %})
 }
 
 {% when None %}
-
+{%- if func.has_default_arguments() %}
+@JvmOverloads
+{%- endif %}
 fun {{ func.name()|fn_name }}({% call kt::arg_list_decl(func) %}) =
     {% call kt::to_ffi_call(func) %}
 {% endmatch %}
+{%- if config.coroutine_dispatcher().is_some() %}
+{% call kt::suspend_fn(func) %}
+{% endif %}
+{%- if config.direct_byte_buffers() && (func.arguments()|has_bytes_argument || func.return_type()|is_bytes_return) %}
+{% call kt::direct_fn(func, false, config.blocking_thread_annotation()) %}
+{% endif %}
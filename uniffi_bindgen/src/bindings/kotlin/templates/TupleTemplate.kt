@@ -0,0 +1,25 @@
+public object {{ ffi_converter_name }}: FfiConverterRustBuffer<{{ type_name }}> {
+    override fun read(buf: ByteBuffer): {{ type_name }} {
+        return {% if element_types.len() == 2 %}Pair{% else %}Triple{% endif %}(
+            {%- for element_type in element_types %}
+            {{ element_type|read_fn }}(buf){% if !loop.last %},{% endif %}
+            {%- endfor %}
+        )
+    }
+
+    override fun allocationSize(value: {{ type_name }}): Int {
+        val ({% for element_type in element_types %}v{{ loop.index }}{% if !loop.last %}, {% endif %}{% endfor %}) = value
+        return (
+            {%- for element_type in element_types %}
+            {{ element_type|allocation_size_fn }}(v{{ loop.index }}){% if !loop.last %} +{% endif %}
+            {%- endfor %}
+        )
+    }
+
+    override fun write(value: {{ type_name }}, buf: ByteBuffer) {
+        val ({% for element_type in element_types %}v{{ loop.index }}{% if !loop.last %}, {% endif %}{% endfor %}) = value
+        {%- for element_type in element_types %}
+        {{ element_type|write_fn }}(v{{ loop.index }}, buf)
+        {%- endfor %}
+    }
+}
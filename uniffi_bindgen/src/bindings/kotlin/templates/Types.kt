@@ -20,6 +20,9 @@
 {%- when Type::Boolean %}
 {%- include "BooleanHelper.kt" %}
 
+{%- when Type::Char %}
+{%- include "CharHelper.kt" %}
+
 {%- when Type::Int8 %}
 {%- include "Int8Helper.kt" %}
 
@@ -59,7 +62,7 @@
 {%- when Type::Error(name) %}
 {% include "ErrorTemplate.kt" %}
 
-{%- when Type::Object(name) %}
+{%- when Type::Object { name, imp } %}
 {% include "ObjectTemplate.kt" %}
 
 {%- when Type::Record(name) %}
@@ -71,9 +74,28 @@
 {%- when Type::Sequence(inner_type) %}
 {% include "SequenceTemplate.kt" %}
 
+{%- when Type::Set(inner_type) %}
+{% include "SetTemplate.kt" %}
+
 {%- when Type::Map(key_type, value_type) %}
 {% include "MapTemplate.kt" %}
 
+{%- when Type::Tuple(element_types) %}
+{% include "TupleTemplate.kt" %}
+
+{%- when Type::Result(ok_type, err_type) %}
+{% include "ResultTemplate.kt" %}
+
+{%- when Type::Weak(inner_type) %}
+{%- match inner_type.as_ref() %}
+{%- when Type::Object { name, imp } %}
+{% include "WeakTemplate.kt" %}
+{%- else %}
+{%- endmatch %}
+
+{%- when Type::Iterator(item_type) %}
+{% include "IteratorTemplate.kt" %}
+
 {%- when Type::CallbackInterface(name) %}
 {% include "CallbackInterfaceTemplate.kt" %}
 
@@ -83,10 +105,31 @@
 {%- when Type::Duration %}
 {% include "DurationHelper.kt" %}
 
+{%- when Type::OffsetDateTime %}
+{% include "OffsetDateTimeHelper.kt" %}
+
+{%- when Type::BigInt %}
+{% include "BigIntHelper.kt" %}
+
+{%- when Type::Decimal %}
+{% include "DecimalHelper.kt" %}
+
+{%- when Type::Url %}
+{% include "UrlHelper.kt" %}
+
+{%- when Type::Uuid %}
+{% include "UuidHelper.kt" %}
+
+{%- when Type::Json %}
+{% include "JsonHelper.kt" %}
+
+{%- when Type::SharedSlice %}
+{% include "SharedSliceHelper.kt" %}
+
 {%- when Type::Custom { name, builtin } %}
 {% include "CustomTypeTemplate.kt" %}
 
-{%- when Type::External { crate_name, name } %}
+{%- when Type::External { crate_name, name, kind } %}
 {% include "ExternalTypeTemplate.kt" %}
 
 {%- else %}
@@ -4,16 +4,24 @@ public object FfiConverterUShort: FfiConverter<UShort, Short> {
     }
 
     override fun read(buf: ByteBuffer): UShort {
+        {%- if ci.compact_encoding() %}
+        return lift(buf.getVarLong().toShort())
+        {%- else %}
         return lift(buf.getShort())
+        {%- endif %}
     }
 
     override fun lower(value: UShort): Short {
         return value.toShort()
     }
 
-    override fun allocationSize(value: UShort) = 2
+    override fun allocationSize(value: UShort) = {% if ci.compact_encoding() %}3{% else %}2{% endif %}
 
     override fun write(value: UShort, buf: ByteBuffer) {
+        {%- if ci.compact_encoding() %}
+        buf.putVarLong(value.toLong())
+        {%- else %}
         buf.putShort(value.toShort())
+        {%- endif %}
     }
 }
@@ -4,16 +4,24 @@ public object FfiConverterUInt: FfiConverter<UInt, Int> {
     }
 
     override fun read(buf: ByteBuffer): UInt {
+        {%- if ci.compact_encoding() %}
+        return lift(buf.getVarLong().toInt())
+        {%- else %}
         return lift(buf.getInt())
+        {%- endif %}
     }
 
     override fun lower(value: UInt): Int {
         return value.toInt()
     }
 
-    override fun allocationSize(value: UInt) = 4
+    override fun allocationSize(value: UInt) = {% if ci.compact_encoding() %}5{% else %}4{% endif %}
 
     override fun write(value: UInt, buf: ByteBuffer) {
+        {%- if ci.compact_encoding() %}
+        buf.putVarLong(value.toLong())
+        {%- else %}
         buf.putInt(value.toInt())
+        {%- endif %}
     }
 }
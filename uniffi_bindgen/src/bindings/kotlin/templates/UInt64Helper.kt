@@ -4,16 +4,24 @@ public object FfiConverterULong: FfiConverter<ULong, Long> {
     }
 
     override fun read(buf: ByteBuffer): ULong {
+        {%- if ci.compact_encoding() %}
+        return lift(buf.getVarLong())
+        {%- else %}
         return lift(buf.getLong())
+        {%- endif %}
     }
 
     override fun lower(value: ULong): Long {
         return value.toLong()
     }
 
-    override fun allocationSize(value: ULong) = 8
+    override fun allocationSize(value: ULong) = {% if ci.compact_encoding() %}10{% else %}8{% endif %}
 
     override fun write(value: ULong, buf: ByteBuffer) {
+        {%- if ci.compact_encoding() %}
+        buf.putVarLong(value.toLong())
+        {%- else %}
         buf.putLong(value.toLong())
+        {%- endif %}
     }
 }
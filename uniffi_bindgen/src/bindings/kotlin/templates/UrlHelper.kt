@@ -0,0 +1,17 @@
+// URLs are `URI`s rather than `URL`s, whose `equals()` and `hashCode()` resolve their host.
+public object FfiConverterUrl: FfiConverterRustBuffer<java.net.URI> {
+    override fun read(buf: ByteBuffer): java.net.URI {
+        val len = buf.getLength()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return java.net.URI(byteArr.toString(Charsets.UTF_8))
+    }
+
+    override fun allocationSize(value: java.net.URI) = LENGTH_SIZE + value.toString().toByteArray(Charsets.UTF_8).size
+
+    override fun write(value: java.net.URI, buf: ByteBuffer) {
+        val byteArr = value.toString().toByteArray(Charsets.UTF_8)
+        buf.putLength(byteArr.size)
+        buf.put(byteArr)
+    }
+}
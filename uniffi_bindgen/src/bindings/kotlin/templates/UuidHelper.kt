@@ -0,0 +1,15 @@
+public object FfiConverterUuid: FfiConverterRustBuffer<java.util.UUID> {
+    override fun read(buf: ByteBuffer): java.util.UUID {
+        // The 16 bytes are in big-endian order, like the two halves of a `UUID`.
+        val mostSignificantBits = buf.getLong()
+        val leastSignificantBits = buf.getLong()
+        return java.util.UUID(mostSignificantBits, leastSignificantBits)
+    }
+
+    override fun allocationSize(value: java.util.UUID) = 16
+
+    override fun write(value: java.util.UUID, buf: ByteBuffer) {
+        buf.putLong(value.mostSignificantBits)
+        buf.putLong(value.leastSignificantBits)
+    }
+}
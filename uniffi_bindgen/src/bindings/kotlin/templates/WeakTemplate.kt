@@ -0,0 +1,63 @@
+{%- let obj = ci.get_object_definition(name).unwrap() %}
+{%- let upgrade_type = obj.weak_upgrade_type() %}
+{%- if self.include_once_check("ObjectRuntime.kt") %}{% include "ObjectRuntime.kt" %}{% endif %}
+
+/**
+ * A weak reference to a `{{ name|class_name }}`, which doesn't keep it alive.
+ */
+class {{ type_name }}(
+    handle: UniFFIHandle
+) : FFIObject<Long>(handle.value) {
+
+    override protected val cleanable: UniFFICleaner.Cleanable =
+        UniFFICleaner.INSTANCE.register(this, UniFFICleanAction(handle.value))
+
+    // Frees the handle of the underlying Rust weak reference, like the clean action of the objects.
+    private class UniFFICleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_weak_free().name() }}(handle, status)
+            }
+        }
+    }
+
+    /**
+     * Get the referenced object, or `null` if it has already been dropped.
+     */
+    fun upgrade(): {{ upgrade_type.borrow()|type_name }} =
+        callWithPointer {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_weak_upgrade().name() }}(it, status)
+            }
+        }.let {
+            {{ upgrade_type.borrow()|lift_fn }}(it)
+        }
+}
+
+public object {{ ffi_converter_name }}: FfiConverter<{{ type_name }}, Long> {
+    override fun lower(value: {{ type_name }}): Long = value.callWithPointer { it }
+
+    override fun lift(value: Long): {{ type_name }} {
+        return {{ type_name }}(UniFFIHandle(value))
+    }
+
+    override fun read(buf: ByteBuffer): {{ type_name }} {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: {{ type_name }}) = 8
+
+    override fun write(value: {{ type_name }}, buf: ByteBuffer) {
+        val handle = value.callWithPointer {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_weak_clone().name() }}(it, status)
+            }
+        }
+        UniFFIClonedHandles.add {
+            rustCall() { status ->
+                _UniFFILib.INSTANCE.{{ obj.ffi_weak_free().name() }}(handle, status)
+            }
+        }
+        buf.putLong(handle)
+    }
+}
@@ -7,7 +7,7 @@
 {%- macro to_ffi_call(func) -%}
     {%- match func.throws_type() %}
     {%- when Some with (e) %}
-    rustCallWithError({{ e|type_name}})
+    rustCallWithError({{ e|error_handler }})
     {%- else %}
     rustCall()
     {%- endmatch %} { _status ->
@@ -18,7 +18,7 @@
 {%- macro to_ffi_call_with_prefix(prefix, func) %}
     {%- match func.throws_type() %}
     {%- when Some with (e) %}
-    rustCallWithError({{ e|type_name}})
+    rustCallWithError({{ e|error_handler }})
     {%- else %}
     rustCall()
     {%- endmatch %} { _status ->
@@ -56,6 +56,92 @@
         {%- if !loop.last %}, {% endif -%}
     {%- endfor %}
 {%- endmacro %}
+
+{#-
+// Arglist as used when forwarding the arguments of a function to another one.
+-#}
+{% macro arg_list_forward(func) %}
+    {%- for arg in func.arguments() -%}
+        {{ arg.name()|var_name }}
+        {%- if !loop.last %}, {% endif -%}
+    {%- endfor %}
+{%- endmacro %}
+
+{#-
+// A `suspend` variant of a function or method, which makes the blocking call
+// into Rust on the configured coroutine dispatcher.
+-#}
+{% macro suspend_fn(func) %}
+    {%- match func.throws_type() -%}
+    {%- when Some with (throwable) %}
+    @Throws({{ throwable|type_name }}::class)
+    {%- else -%}
+    {%- endmatch %}
+    suspend fun {{ func.name()|async_fn_name }}({% call arg_list_decl(func) %})
+    {%- match func.return_type() -%}
+    {%- when Some with (return_type) %}: {{ return_type|type_name -}}
+    {%- else -%}
+    {%- endmatch %} =
+        kotlinx.coroutines.withContext(UniFFICoroutineDispatcher.dispatcher) {
+            {{ func.name()|fn_name }}({% call arg_list_forward(func) %})
+        }
+{%- endmacro %}
+
+{#-
+// A variant of a function or method which passes `sequence<u8>` values as `RustByteBuffer`s,
+// without copying them. Only generated with the `direct_byte_buffers` option.
+-#}
+{% macro direct_fn(func, is_method, thread_annotation) %}
+    {%- match func.throws_type() -%}
+    {%- when Some with (throwable) %}
+    @Throws({{ throwable|type_name }}::class)
+    {%- else -%}
+    {%- endmatch %}
+    {%- match thread_annotation -%}
+    {%- when Some with (annotation) %}
+    {{ annotation }}
+    {%- else -%}
+    {%- endmatch %}
+    fun {{ func.name()|direct_fn_name }}({% call _direct_arg_list_decl(func) %})
+    {%- match func.return_type() -%}
+    {%- when Some with (return_type) %}: {% if return_type|is_bytes %}RustByteBuffer{% else %}{{ return_type|type_name }}{% endif %} =
+        {% if return_type|is_bytes %}RustByteBuffer.lift{% else %}{{ return_type|lift_fn }}{% endif %}({% call _to_direct_ffi_call(func, is_method) %})
+    {%- else %} =
+        {% call _to_direct_ffi_call(func, is_method) %}
+    {%- endmatch %}
+{%- endmacro %}
+
+{%- macro _to_direct_ffi_call(func, is_method) -%}
+    {%- if is_method %}callWithPointer { ptr -> {% endif -%}
+    {%- match func.throws_type() %}
+    {%- when Some with (e) %}
+    rustCallWithError({{ e|error_handler }})
+    {%- else %}
+    rustCall()
+    {%- endmatch %} { _status ->
+    _UniFFILib.INSTANCE.{{ func.ffi_func().name() }}({% if is_method %}ptr, {% endif %}{% call _direct_arg_list_ffi_call(func) -%}{% if func.arguments().len() > 0 %},{% endif %} _status)
+}
+    {%- if is_method %} }{% endif %}
+{%- endmacro -%}
+
+{%- macro _direct_arg_list_decl(func) %}
+    {%- for arg in func.arguments() -%}
+        {{ arg.name()|var_name }}: {% if arg.type_()|is_bytes %}RustByteBuffer{% else %}{{ arg|type_name }}{% endif -%}
+        {%- if !loop.last %}, {% endif -%}
+    {%- endfor %}
+{%- endmacro %}
+
+{%- macro _direct_arg_list_ffi_call(func) %}
+    {%- for arg in func.arguments() %}
+        {%- if arg.type_()|is_bytes %}
+        {{- arg.name()|var_name }}.intoRustBuffer()
+        {%- else %}
+        {{- arg|lower_fn }}({{ arg.name()|var_name }})
+        {%- endif %}
+        {%- if !loop.last %}, {% endif %}
+    {%- endfor %}
+{%- endmacro -%}
+
 {#-
 // Arglist as used in the _UniFFILib function declations.
 // Note unfiltered name but ffi_type_name filters.
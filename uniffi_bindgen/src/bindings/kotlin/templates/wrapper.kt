@@ -17,11 +17,15 @@ package {{ config.package_name() }};
 // compile the Rust component. The easiest way to ensure this is to bundle the Kotlin
 // helpers directly inline like we're doing here.
 
+{%- match config.ffi_backend() %}
+{%- when FfiBackend::Jna %}
 import com.sun.jna.Library
 import com.sun.jna.Native
 import com.sun.jna.Pointer
 import com.sun.jna.Structure
 import com.sun.jna.ptr.ByReference
+{%- when FfiBackend::Jni %}
+{%- endmatch %}
 import java.nio.ByteBuffer
 import java.nio.ByteOrder
 
@@ -29,20 +33,80 @@ import java.nio.ByteOrder
 import {{ imported_class }}
 {%- endfor %}
 
+{%- if file.includes_internals() %}
+
 {% include "RustBufferTemplate.kt" %}
 {% include "FfiConverterTemplate.kt" %}
 {% include "Helpers.kt" %}
+{%- if config.coroutine_dispatcher().is_some() %}
+{% include "CoroutineDispatcherTemplate.kt" %}
+{%- endif %}
+{%- if config.direct_byte_buffers() %}
+{% include "RustByteBufferTemplate.kt" %}
+{%- endif %}
 
 // Contains loading, initialization code,
-// and the FFI Function declarations in a com.sun.jna.Library.
+// and the FFI Function declarations.
 {% include "NamespaceLibraryTemplate.kt" %}
+{%- if config.log_forwarder() %}
+{% include "LogForwarderTemplate.kt" %}
+{%- endif %}
+{%- endif %}
+
+{%- if file.includes_types() %}
 
 // Public interface members begin here.
 {{ type_helper_code }}
+{%- for constant in ci.constant_definitions() %}
+{% include "ConstantTemplate.kt" %}
+{%- endfor %}
+{%- endif %}
+
+{%- if file.includes_functions() %}
 
 {%- for func in ci.function_definitions() %}
 {%- include "TopLevelFunctionTemplate.kt" %}
 {%- endfor %}
 
+/**
+ * Describe the objects and buffers of this component which weren't freed yet, with the
+ * backtraces of their creation, when the `leak-detection` feature of uniffi is enabled.
+ */
+fun dumpLiveAllocations(): String =
+    {{ TypeIdentifier::String.borrow()|lift_fn }}(rustCall { _status ->
+        _UniFFILib.INSTANCE.{{ ci.ffi_dump_live_allocations().name() }}(_status)
+    })
+
+/**
+ * The counters of the RustBuffers of the Rust library, see `rustBufferStats()`.
+ *
+ * @property liveBuffers The number of buffers allocated and not freed yet.
+ * @property liveBytes The total capacity of the live buffers, in bytes.
+ * @property peakBytes The highest total capacity of the live buffers so far, in bytes.
+ * @property rejected The number of buffers which weren't allocated because of the limits.
+ */
+data class RustBufferStats(
+    val liveBuffers: Long,
+    val liveBytes: Long,
+    val peakBytes: Long,
+    val rejected: Long
+)
+
+/**
+ * Get the counters of the RustBuffers of the Rust library, which may set limits on them.
+ */
+fun rustBufferStats(): RustBufferStats {
+    val rbuf = rustCall { _status ->
+        _UniFFILib.INSTANCE.{{ ci.ffi_rustbuffer_stats().name() }}(_status)
+    }
+    try {
+        val buf = rbuf.asByteBuffer()!!
+        return RustBufferStats(buf.getLong(), buf.getLong(), buf.getLong(), buf.getLong())
+    } finally {
+        RustBuffer.free(rbuf)
+    }
+}
+{%- endif %}
+
 
 {% import "macros.kt" as kt %}
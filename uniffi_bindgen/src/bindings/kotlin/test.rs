@@ -27,6 +27,9 @@ pub fn run_test(tmp_dir: &str, fixture_name: &str, script_file: &str) -> Result<
         .arg(calc_classpath(vec![&out_dir, &jar_file]))
         // Enable runtime assertions, for easy testing etc.
         .arg("-J-ea")
+        // The bindings using the `jni` FFI backend load the library like the JVM does, rather
+        // than from the classpath like JNA.
+        .arg(format!("-J-Djava.library.path={out_dir}"))
         // Our test scripts should not produce any warnings.
         .arg("-Werror")
         .arg("-script")
@@ -57,6 +60,7 @@ fn generate_sources(
             vec!["kotlin"],
             Some(out_dir),
             Some(library_path),
+            None,
             false,
         )?;
     }
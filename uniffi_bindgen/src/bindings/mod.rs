@@ -10,10 +10,15 @@
 use anyhow::{bail, Result};
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use crate::interface::ComponentInterface;
 use crate::MergeWith;
 
+pub(crate) mod cdylib;
+pub mod diff;
+mod docs;
+mod keywords;
 pub mod kotlin;
 pub mod python;
 pub mod ruby;
@@ -25,7 +30,8 @@ pub mod swift;
 /// on the provided `TargetLanguage`. For convenience of calling code we also provide
 /// a few `TryFrom` implementations to help guess the correct target language from
 /// e.g. a file extension of command-line argument.
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TargetLanguage {
     Kotlin,
     Swift,
@@ -33,6 +39,27 @@ pub enum TargetLanguage {
     Ruby,
 }
 
+impl TargetLanguage {
+    /// All the target languages, in order.
+    pub const ALL: [TargetLanguage; 4] = [
+        TargetLanguage::Kotlin,
+        TargetLanguage::Swift,
+        TargetLanguage::Python,
+        TargetLanguage::Ruby,
+    ];
+}
+
+impl fmt::Display for TargetLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TargetLanguage::Kotlin => "kotlin",
+            TargetLanguage::Swift => "swift",
+            TargetLanguage::Python => "python",
+            TargetLanguage::Ruby => "ruby",
+        })
+    }
+}
+
 impl TryFrom<&str> for TargetLanguage {
     type Error = anyhow::Error;
     fn try_from(value: &str) -> Result<Self> {
@@ -86,6 +113,25 @@ impl From<&ComponentInterface> for Config {
     }
 }
 
+impl Config {
+    /// A copy of this config, where the bindings of every language load the library
+    /// `cdylib_name`, e.g. the one combining several components.
+    pub fn with_cdylib_name(&self, cdylib_name: &str) -> Self {
+        Config {
+            kotlin: self.kotlin.with_cdylib_name(cdylib_name),
+            swift: self.swift.with_cdylib_name(cdylib_name),
+            python: self.python.with_cdylib_name(cdylib_name),
+            ruby: self.ruby.with_cdylib_name(cdylib_name),
+        }
+    }
+
+    /// The JVM class whose `external` functions the scaffolding implements with JNI glue, when
+    /// the Kotlin bindings use the `jni` FFI backend.
+    pub fn kotlin_jni_class_name(&self) -> Option<String> {
+        self.kotlin.jni_class_name()
+    }
+}
+
 impl MergeWith for Config {
     fn merge_with(&self, other: &Self) -> Self {
         Config {
@@ -119,3 +165,66 @@ pub fn write_bindings(
     }
     Ok(())
 }
+
+/// Package a component, along with its Kotlin bindings, as an Android library module.
+pub fn write_android_library(
+    config: &Config,
+    ci: &ComponentInterface,
+    crate_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    abis: &[&str],
+    release: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    kotlin::write_android_library(
+        &config.kotlin,
+        ci,
+        crate_root,
+        out_dir,
+        abis,
+        release,
+        try_format_code,
+    )
+}
+
+/// Package a component, along with its Python bindings, as a pip-installable Python package.
+pub fn write_python_package(
+    config: &Config,
+    ci: &ComponentInterface,
+    crate_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    release: bool,
+    build_wheel: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    python::write_python_package(
+        &config.python,
+        ci,
+        crate_root,
+        out_dir,
+        release,
+        build_wheel,
+        try_format_code,
+    )
+}
+
+/// Package a component, along with its Ruby bindings, as a Ruby gem.
+pub fn write_ruby_gem(
+    config: &Config,
+    ci: &ComponentInterface,
+    crate_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    release: bool,
+    build_gem: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    ruby::write_ruby_gem(
+        &config.ruby,
+        ci,
+        crate_root,
+        out_dir,
+        release,
+        build_gem,
+        try_format_code,
+    )
+}
@@ -73,7 +73,44 @@ impl CodeType for SequenceCodeType {
 
     fn coerce(&self, oracle: &dyn CodeOracle, nm: &str) -> String {
         format!(
-            "list({} for x in {})",
+            "[{} for x in {}]",
+            oracle.find(&self.inner).coerce(oracle, "x"),
+            nm
+        )
+    }
+}
+
+pub struct SetCodeType {
+    inner: TypeIdentifier,
+}
+
+impl SetCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for SetCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "set".to_string()
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Set{}", oracle.find(&self.inner).canonical_name(oracle))
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptySequence => "set()".into(),
+            _ => unimplemented!(),
+        }
+    }
+
+    // Any iterable is accepted, keeping a single copy of duplicated items. A set comprehension
+    // rather than `set()`, which an argument named `set` would shadow.
+    fn coerce(&self, oracle: &dyn CodeOracle, nm: &str) -> String {
+        format!(
+            "{{{} for x in {}}}",
             oracle.find(&self.inner).coerce(oracle, "x"),
             nm
         )
@@ -113,10 +150,114 @@ impl CodeType for MapCodeType {
 
     fn coerce(&self, oracle: &dyn CodeOracle, nm: &str) -> String {
         format!(
-            "dict(({}, {}) for (k, v) in {}.items())",
+            "{{{}: {} for (k, v) in {}.items()}}",
             self.key.coerce(oracle, "k"),
             self.value.coerce(oracle, "v"),
             nm
         )
     }
 }
+
+pub struct TupleCodeType {
+    elements: Vec<TypeIdentifier>,
+}
+
+impl TupleCodeType {
+    pub fn new(elements: Vec<TypeIdentifier>) -> Self {
+        Self { elements }
+    }
+}
+
+impl CodeType for TupleCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "tuple".to_string()
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        let elements = self
+            .elements
+            .iter()
+            .map(|t| oracle.find(t).canonical_name(oracle))
+            .collect::<String>();
+        format!("Tuple{}{elements}", self.elements.len())
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unimplemented!()
+    }
+
+    fn coerce(&self, oracle: &dyn CodeOracle, nm: &str) -> String {
+        let elements = self
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(i, t)| t.coerce(oracle, &format!("{nm}[{i}]")))
+            .collect::<Vec<_>>();
+        format!("({})", elements.join(", "))
+    }
+}
+
+pub struct ResultCodeType {
+    ok: TypeIdentifier,
+    err: TypeIdentifier,
+}
+
+impl ResultCodeType {
+    pub fn new(ok: TypeIdentifier, err: TypeIdentifier) -> Self {
+        Self { ok, err }
+    }
+}
+
+impl CodeType for ResultCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "Result".to_string()
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "Result{}{}",
+            oracle.find(&self.ok).canonical_name(oracle),
+            oracle.find(&self.err).canonical_name(oracle),
+        )
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unimplemented!()
+    }
+
+    // The `Ok` and `Err` wrappers are checked when the result is written.
+    fn coerce(&self, _oracle: &dyn CodeOracle, nm: &str) -> String {
+        nm.to_string()
+    }
+}
+
+pub struct IteratorCodeType {
+    inner: TypeIdentifier,
+}
+
+impl IteratorCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for IteratorCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        self.canonical_name(oracle)
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "Iterator{}",
+            oracle.find(&self.inner).canonical_name(oracle),
+        )
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unreachable!();
+    }
+
+    fn coerce(&self, _oracle: &dyn CodeOracle, nm: &str) -> String {
+        nm.to_string()
+    }
+}
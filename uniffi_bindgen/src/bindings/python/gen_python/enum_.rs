@@ -24,14 +24,19 @@ impl CodeType for EnumCodeType {
     }
 
     fn literal(&self, oracle: &dyn CodeOracle, literal: &Literal) -> String {
-        if let Literal::Enum(v, _) = literal {
-            format!(
+        match literal {
+            Literal::Enum(v, _) => format!(
                 "{}.{}",
                 self.type_label(oracle),
                 oracle.enum_variant_name(v)
-            )
-        } else {
-            unreachable!();
+            ),
+            // The variants of enums with associated data are nested classes.
+            Literal::EmptyVariant(v, _) => format!(
+                "{}.{}()",
+                self.type_label(oracle),
+                oracle.enum_variant_name(v)
+            ),
+            _ => unreachable!(),
         }
     }
 
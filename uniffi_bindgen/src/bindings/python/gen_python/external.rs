@@ -3,14 +3,16 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use crate::backend::{CodeOracle, CodeType};
+use crate::interface::ExternalKind;
 
 pub struct ExternalCodeType {
     name: String,
+    kind: ExternalKind,
 }
 
 impl ExternalCodeType {
-    pub fn new(name: String) -> Self {
-        Self { name }
+    pub fn new(name: String, kind: ExternalKind) -> Self {
+        Self { name, kind }
     }
 }
 
@@ -20,10 +22,15 @@ impl CodeType for ExternalCodeType {
     }
 
     fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
-        format!("Type{}", self.name)
+        match self.kind {
+            ExternalKind::CallbackInterface => format!("CallbackInterface{}", self.name),
+            ExternalKind::DataClass | ExternalKind::Error => format!("Type{}", self.name),
+        }
     }
 
-    fn coerce(&self, _oracle: &dyn CodeOracle, _nm: &str) -> String {
-        panic!("should not be necessary to coerce External types");
+    // The values of external types, like the callback interfaces passed as arguments, are
+    // coerced by the module of their crate if needed.
+    fn coerce(&self, _oracle: &dyn CodeOracle, nm: &str) -> String {
+        nm.to_string()
     }
 }
@@ -19,8 +19,15 @@ macro_rules! impl_code_type_for_miscellany {
                     format!("{}", $canonical_name)
                 }
 
-                fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
-                    unreachable!()
+                fn literal(&self, _oracle: &dyn CodeOracle, literal: &Literal) -> String {
+                    match literal {
+                        // Python durations are only accurate to the microsecond.
+                        Literal::Duration(secs, nanos) => format!(
+                            "datetime.timedelta(seconds={secs}, microseconds={})",
+                            nanos / 1000
+                        ),
+                        _ => unreachable!(),
+                    }
                 }
 
                 fn coerce(&self, _oracle: &dyn CodeOracle, nm: &str) -> String {
@@ -34,3 +41,17 @@ macro_rules! impl_code_type_for_miscellany {
 impl_code_type_for_miscellany!(TimestampCodeType, "Timestamp");
 
 impl_code_type_for_miscellany!(DurationCodeType, "Duration");
+
+impl_code_type_for_miscellany!(OffsetDateTimeCodeType, "OffsetDateTime");
+
+impl_code_type_for_miscellany!(BigIntCodeType, "BigInt");
+
+impl_code_type_for_miscellany!(DecimalCodeType, "Decimal");
+
+impl_code_type_for_miscellany!(UrlCodeType, "Url");
+
+impl_code_type_for_miscellany!(UuidCodeType, "Uuid");
+
+impl_code_type_for_miscellany!(JsonCodeType, "Json");
+
+impl_code_type_for_miscellany!(SharedSliceCodeType, "SharedSlice");
@@ -5,13 +5,14 @@
 use anyhow::{Context, Result};
 use askama::Template;
 use heck::{ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
-use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::backend::{CodeOracle, CodeType, TemplateExpression, TypeIdentifier};
+use crate::bindings::docs::split_arguments_section;
+use crate::bindings::keywords::{self, KeywordConfig, KeywordStrategy};
 use crate::interface::*;
 use crate::MergeWith;
 
@@ -27,47 +28,44 @@ mod primitives;
 mod record;
 
 // Taken from Python's `keyword.py` module.
-static KEYWORDS: Lazy<HashSet<String>> = Lazy::new(|| {
-    let kwlist = vec![
-        "False",
-        "None",
-        "True",
-        "__peg_parser__",
-        "and",
-        "as",
-        "assert",
-        "async",
-        "await",
-        "break",
-        "class",
-        "continue",
-        "def",
-        "del",
-        "elif",
-        "else",
-        "except",
-        "finally",
-        "for",
-        "from",
-        "global",
-        "if",
-        "import",
-        "in",
-        "is",
-        "lambda",
-        "nonlocal",
-        "not",
-        "or",
-        "pass",
-        "raise",
-        "return",
-        "try",
-        "while",
-        "with",
-        "yield",
-    ];
-    HashSet::from_iter(kwlist.into_iter().map(|s| s.to_string()))
-});
+const KEYWORDS: &[&str] = &[
+    "False",
+    "None",
+    "True",
+    "__peg_parser__",
+    "and",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "break",
+    "class",
+    "continue",
+    "def",
+    "del",
+    "elif",
+    "else",
+    "except",
+    "finally",
+    "for",
+    "from",
+    "global",
+    "if",
+    "import",
+    "in",
+    "is",
+    "lambda",
+    "nonlocal",
+    "not",
+    "or",
+    "pass",
+    "raise",
+    "return",
+    "try",
+    "while",
+    "with",
+    "yield",
+];
 
 // Config options to customize the generated python.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -75,6 +73,26 @@ pub struct Config {
     cdylib_name: Option<String>,
     #[serde(default)]
     custom_types: HashMap<String, CustomTypeConfig>,
+    frozen_records: Option<bool>,
+    memoryview_bytes: Option<bool>,
+    enum_class: Option<EnumClass>,
+    package_name: Option<String>,
+    #[serde(default)]
+    external_packages: HashMap<String, String>,
+    log_forwarder: Option<bool>,
+    #[serde(default)]
+    keywords: KeywordConfig,
+}
+
+/// The class of the `enum` module which enums without associated data derive from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnumClass {
+    /// The values are the 1-based positions of the variants.
+    Enum,
+    /// The values are the Rust discriminants of the variants.
+    IntEnum,
+    /// The values are the names of the variants.
+    StrEnum,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -94,13 +112,42 @@ impl Config {
             "uniffi".into()
         }
     }
+
+    /// A copy of this config, loading the library `cdylib_name`.
+    pub(crate) fn with_cdylib_name(&self, cdylib_name: &str) -> Self {
+        Self {
+            cdylib_name: Some(cdylib_name.to_string()),
+            ..self.clone()
+        }
+    }
+
+    pub fn frozen_records(&self) -> bool {
+        self.frozen_records.unwrap_or(true)
+    }
+
+    pub fn memoryview_bytes(&self) -> bool {
+        self.memoryview_bytes.unwrap_or(false)
+    }
+
+    pub fn enum_class(&self) -> EnumClass {
+        self.enum_class.unwrap_or(EnumClass::Enum)
+    }
+
+    pub fn log_forwarder(&self) -> bool {
+        self.log_forwarder.unwrap_or(false)
+    }
+
+    /// The namespace package which the module is part of, if any.
+    pub fn package_name(&self) -> Option<&str> {
+        self.package_name.as_deref()
+    }
 }
 
 impl From<&ComponentInterface> for Config {
     fn from(ci: &ComponentInterface) -> Self {
         Config {
             cdylib_name: Some(format!("uniffi_{}", ci.namespace())),
-            custom_types: HashMap::new(),
+            ..Default::default()
         }
     }
 }
@@ -110,15 +157,23 @@ impl MergeWith for Config {
         Config {
             cdylib_name: self.cdylib_name.merge_with(&other.cdylib_name),
             custom_types: self.custom_types.merge_with(&other.custom_types),
+            frozen_records: self.frozen_records.merge_with(&other.frozen_records),
+            memoryview_bytes: self.memoryview_bytes.merge_with(&other.memoryview_bytes),
+            enum_class: self.enum_class.merge_with(&other.enum_class),
+            package_name: self.package_name.merge_with(&other.package_name),
+            external_packages: self.external_packages.merge_with(&other.external_packages),
+            log_forwarder: self.log_forwarder.merge_with(&other.log_forwarder),
+            keywords: self.keywords.merge_with(&other.keywords),
         }
     }
 }
 
 // Generate python bindings for the given ComponentInterface, as a string.
 pub fn generate_python_bindings(config: &Config, ci: &ComponentInterface) -> Result<String> {
-    PythonWrapper::new(config.clone(), ci)
-        .render()
-        .context("failed to render python bindings")
+    keywords::with_config(&config.keywords, || {
+        PythonWrapper::new(config.clone(), ci).render()
+    })
+    .context("failed to render python bindings")
 }
 
 /// Renders Python helper code for all types
@@ -146,6 +201,20 @@ impl<'a> TypeRenderer<'a> {
         }
     }
 
+    // Get the module to import an external type from.
+    //
+    // Unless configured otherwise, the module of another crate is in the same package as ours.
+    fn external_type_module_name(&self, crate_name: &str) -> String {
+        if let Some(name) = self.python_config.external_packages.get(crate_name) {
+            return name.clone();
+        }
+        let module_name = PythonCodeOracle.fn_name(crate_name);
+        match self.python_config.package_name() {
+            Some(package_name) => format!("{package_name}.{module_name}"),
+            None => module_name,
+        }
+    }
+
     // The following methods are used by the `Types.py` macros.
 
     // Helper for the including a template, but only once.
@@ -174,17 +243,17 @@ impl<'a> TypeRenderer<'a> {
 #[template(syntax = "py", escape = "none", path = "wrapper.py")]
 pub struct PythonWrapper<'a> {
     ci: &'a ComponentInterface,
-    config: Config,
+    python_config: Config,
     type_helper_code: String,
     type_imports: BTreeSet<String>,
 }
 impl<'a> PythonWrapper<'a> {
-    pub fn new(config: Config, ci: &'a ComponentInterface) -> Self {
-        let type_renderer = TypeRenderer::new(&config, ci);
+    pub fn new(python_config: Config, ci: &'a ComponentInterface) -> Self {
+        let type_renderer = TypeRenderer::new(&python_config, ci);
         let type_helper_code = type_renderer.render().unwrap();
         let type_imports = type_renderer.imports.into_inner();
         Self {
-            config,
+            python_config,
             ci,
             type_helper_code,
             type_imports,
@@ -196,14 +265,143 @@ impl<'a> PythonWrapper<'a> {
     }
 }
 
-fn fixup_keyword(name: String) -> String {
-    if KEYWORDS.contains(&name) {
-        format!("_{name}")
+// The Python type of the values of a given type, for use in type annotations.
+//
+// Arguments accept any sequence or mapping, whereas the values we lift are always lists and dicts,
+// or memoryviews for `sequence<u8>` with the `memoryview_bytes` option.
+fn type_hint(type_: &Type, for_argument: bool, memoryview_bytes: bool) -> String {
+    let oracle = &PythonCodeOracle;
+    match type_ {
+        Type::UInt8
+        | Type::Int8
+        | Type::UInt16
+        | Type::Int16
+        | Type::UInt32
+        | Type::Int32
+        | Type::UInt64
+        | Type::Int64
+        | Type::BigInt => "int".into(),
+        Type::Float32 | Type::Float64 => "float".into(),
+        Type::Boolean => "bool".into(),
+        Type::String | Type::Char | Type::Url => "str".into(),
+        Type::Timestamp | Type::OffsetDateTime => "datetime.datetime".into(),
+        Type::Duration => "datetime.timedelta".into(),
+        Type::Decimal => "decimal.Decimal".into(),
+        Type::Uuid => "uuid.UUID".into(),
+        Type::Json => "typing.Any".into(),
+        Type::SharedSlice => "SharedSlice".into(),
+        Type::Enum(name)
+        | Type::Record(name)
+        | Type::Object { name, .. }
+        | Type::Error(name)
+        | Type::CallbackInterface(name) => oracle.class_name(name),
+        Type::Optional(inner) => {
+            format!(
+                "typing.Optional[{}]",
+                type_hint(inner, for_argument, memoryview_bytes)
+            )
+        }
+        Type::Sequence(inner) if memoryview_bytes && !for_argument && **inner == Type::UInt8 => {
+            "memoryview".into()
+        }
+        Type::Sequence(inner) => format!(
+            "typing.{}[{}]",
+            if for_argument { "Sequence" } else { "List" },
+            type_hint(inner, for_argument, memoryview_bytes)
+        ),
+        Type::Set(inner) => format!(
+            "typing.{}[{}]",
+            if for_argument { "AbstractSet" } else { "Set" },
+            type_hint(inner, for_argument, memoryview_bytes)
+        ),
+        Type::Map(key, value) => format!(
+            "typing.{}[{}, {}]",
+            if for_argument { "Mapping" } else { "Dict" },
+            type_hint(key, for_argument, memoryview_bytes),
+            type_hint(value, for_argument, memoryview_bytes)
+        ),
+        Type::Tuple(elements) => format!(
+            "typing.Tuple[{}]",
+            elements
+                .iter()
+                .map(|t| type_hint(t, for_argument, memoryview_bytes))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Result(ok, err) => format!(
+            "typing.Union[Ok[{}], Err[{}]]",
+            type_hint(ok, for_argument, memoryview_bytes),
+            type_hint(err, for_argument, memoryview_bytes)
+        ),
+        Type::Weak(_) => oracle.find(type_).type_label(oracle),
+        Type::Iterator(inner) => format!(
+            "typing.Iterator[{}]",
+            type_hint(inner, for_argument, memoryview_bytes)
+        ),
+        // Custom types are declared as an alias of their Python type.
+        Type::External { name, .. } | Type::Custom { name, .. } => name.clone(),
+        Type::Unresolved { name } => {
+            unreachable!("Type `{name}` must be resolved before calling type_hint")
+        }
+    }
+}
+
+// Render a docstring, which goes right after the `:` of a `def` or `class` statement whose body is
+// indented with `indent`.
+//
+// The documentation written for rustdoc is Markdown, which reads fine as plain text. It is followed
+// by sections in the Google style understood by Sphinx and IDEs, such as `Args:` for the arguments
+// of a function, each holding `(name, description)` items.
+fn render_docstring(
+    body: &str,
+    sections: &[(&str, Vec<(String, String)>)],
+    indent: &str,
+) -> String {
+    let mut lines: Vec<String> = body.lines().map(str::to_owned).collect();
+    for (title, items) in sections.iter().filter(|(_, items)| !items.is_empty()) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("{title}:"));
+        for (name, description) in items {
+            let mut description = description.lines();
+            match description.next() {
+                Some(first) => lines.push(format!("    {name}: {first}")),
+                None => lines.push(format!("    {name}")),
+            }
+            lines.extend(description.map(|line| format!("        {line}")));
+        }
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    // Backslashes would start escape sequences, and quotes could end the docstring early.
+    let mut text = lines
+        .iter()
+        .map(|line| match line.as_str() {
+            "" => String::new(),
+            line => format!("{indent}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace('\\', "\\\\")
+        .replace("\"\"\"", "\\\"\"\"");
+    if text.ends_with('"') {
+        text.insert(text.len() - 1, '\\');
+    }
+    let text = text.trim_start();
+    if lines.len() == 1 {
+        format!("\n{indent}\"\"\"{text}\"\"\"")
     } else {
-        name
+        format!("\n{indent}\"\"\"{text}\n{indent}\"\"\"")
     }
 }
 
+// Reserved words are prefixed with an underscore unless configured otherwise.
+fn fixup_keyword(name: String) -> String {
+    keywords::fixup(name, KEYWORDS, Some(KeywordStrategy::Prefix))
+}
+
 #[derive(Clone, Default)]
 pub struct PythonCodeOracle;
 
@@ -228,13 +426,21 @@ impl PythonCodeOracle {
             Type::Float32 => Box::new(primitives::Float32CodeType),
             Type::Float64 => Box::new(primitives::Float64CodeType),
             Type::Boolean => Box::new(primitives::BooleanCodeType),
+            Type::Char => Box::new(primitives::CharCodeType),
             Type::String => Box::new(primitives::StringCodeType),
 
             Type::Timestamp => Box::new(miscellany::TimestampCodeType),
+            Type::OffsetDateTime => Box::new(miscellany::OffsetDateTimeCodeType),
+            Type::BigInt => Box::new(miscellany::BigIntCodeType),
+            Type::Decimal => Box::new(miscellany::DecimalCodeType),
+            Type::Url => Box::new(miscellany::UrlCodeType),
+            Type::Uuid => Box::new(miscellany::UuidCodeType),
+            Type::Json => Box::new(miscellany::JsonCodeType),
+            Type::SharedSlice => Box::new(miscellany::SharedSliceCodeType),
             Type::Duration => Box::new(miscellany::DurationCodeType),
 
             Type::Enum(id) => Box::new(enum_::EnumCodeType::new(id)),
-            Type::Object(id) => Box::new(object::ObjectCodeType::new(id)),
+            Type::Object { name, .. } => Box::new(object::ObjectCodeType::new(name)),
             Type::Record(id) => Box::new(record::RecordCodeType::new(id)),
             Type::Error(id) => Box::new(error::ErrorCodeType::new(id)),
             Type::CallbackInterface(id) => {
@@ -243,8 +449,15 @@ impl PythonCodeOracle {
 
             Type::Optional(inner) => Box::new(compounds::OptionalCodeType::new(*inner)),
             Type::Sequence(inner) => Box::new(compounds::SequenceCodeType::new(*inner)),
+            Type::Set(inner) => Box::new(compounds::SetCodeType::new(*inner)),
             Type::Map(key, value) => Box::new(compounds::MapCodeType::new(*key, *value)),
-            Type::External { name, .. } => Box::new(external::ExternalCodeType::new(name)),
+            Type::Tuple(elements) => Box::new(compounds::TupleCodeType::new(elements)),
+            Type::Result(ok, err) => Box::new(compounds::ResultCodeType::new(*ok, *err)),
+            Type::Weak(inner) => Box::new(object::WeakCodeType::new(*inner)),
+            Type::Iterator(inner) => Box::new(compounds::IteratorCodeType::new(*inner)),
+            Type::External { name, kind, .. } => {
+                Box::new(external::ExternalCodeType::new(name, kind))
+            }
             Type::Custom { name, .. } => Box::new(custom::CustomCodeType::new(name)),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling create_code_type")
@@ -304,6 +517,7 @@ impl CodeOracle for PythonCodeOracle {
             FFIType::RustBuffer => "RustBuffer".to_string(),
             FFIType::ForeignBytes => "ForeignBytes".to_string(),
             FFIType::ForeignCallback => "FOREIGN_CALLBACK_T".to_string(),
+            FFIType::Struct(name) => format!("FfiStruct{}", self.class_name(name)),
         }
     }
 }
@@ -320,6 +534,169 @@ pub mod filters {
         Ok(codetype.type_label(oracle))
     }
 
+    /// Get the Python type annotation for the values of a type.
+    pub fn type_hint(type_: &Type, config: &Config) -> Result<String, askama::Error> {
+        Ok(super::type_hint(type_, false, config.memoryview_bytes()))
+    }
+
+    /// Get the Python type annotation for an argument of a type, which accepts any sequence or
+    /// mapping rather than only lists and dicts.
+    pub fn arg_type_hint(type_: &Type) -> Result<String, askama::Error> {
+        Ok(super::type_hint(type_, true, false))
+    }
+
+    /// The index of the first field without a default value after one with a default value,
+    /// which the `__init__` generated by `dataclasses` doesn't allow. The `__init__` of such a
+    /// record is written by hand, and takes that field and the following ones by keyword.
+    pub fn first_keyword_only_field(rec: &Record) -> Result<Option<usize>, askama::Error> {
+        let fields = rec.fields();
+        Ok(fields
+            .iter()
+            .position(|field| field.default_value().is_some())
+            .and_then(|first_default| {
+                fields[first_default..]
+                    .iter()
+                    .position(|field| field.default_value().is_none())
+                    .map(|index| first_default + index)
+            }))
+    }
+
+    /// Whether the Python value of a literal is immutable and defined before the records, so that
+    /// it can be the default value of a dataclass field, rather than made by a `default_factory`.
+    pub fn is_plain_default(literal: &Literal) -> Result<bool, askama::Error> {
+        Ok(matches!(
+            literal,
+            Literal::Boolean(_)
+                | Literal::String(_)
+                | Literal::UInt(..)
+                | Literal::Int(..)
+                | Literal::Float(..)
+                | Literal::Duration(..)
+                | Literal::Null
+        ))
+    }
+
+    /// Whether a type is a `sequence<u8>`, which the `memoryview_bytes` option exposes as a
+    /// `memoryview`.
+    pub fn is_bytes(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::UInt8))
+    }
+
+    /// Whether a type is a `sequence<string>`, whose strings share their length prefix with the
+    /// compact encoding.
+    pub fn is_strings(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::String))
+    }
+
+    /// Whether an object has a `close()` method, which is called when leaving a `with` block
+    /// rather than destroying the object.
+    pub fn has_close_method(obj: &Object) -> Result<bool, askama::Error> {
+        Ok(obj
+            .methods()
+            .iter()
+            .any(|meth| meth.name() == "close" && meth.arguments().is_empty()))
+    }
+
+    /// Render the Rust documentation of a class as its docstring, indented with `indent`.
+    ///
+    /// The docstring starts with a newline, so it goes right after the `:` of the `class`
+    /// statement. Classes without documentation render nothing.
+    pub fn docstring<'a>(
+        docstring: impl Borrow<Option<&'a str>>,
+        indent: &str,
+    ) -> Result<String, askama::Error> {
+        Ok(render_docstring(
+            docstring.borrow().unwrap_or_default(),
+            &[],
+            indent,
+        ))
+    }
+
+    /// Like [`docstring`], with an `Attributes:` section for the documented fields of a record or
+    /// enum variant.
+    pub fn fields_docstring<'a>(
+        docstring: impl Borrow<Option<&'a str>>,
+        fields: &[Field],
+        indent: &str,
+    ) -> Result<String, askama::Error> {
+        let attributes = fields
+            .iter()
+            .filter_map(|field| Some((oracle().var_name(field.name()), field.docstring()?.into())))
+            .collect();
+        Ok(render_docstring(
+            docstring.borrow().unwrap_or_default(),
+            &[("Attributes", attributes)],
+            indent,
+        ))
+    }
+
+    /// Like [`docstring`], with an `Attributes:` section for the documented variants of an enum
+    /// without associated data.
+    pub fn enum_docstring(e: &Enum, indent: &str) -> Result<String, askama::Error> {
+        let attributes = e
+            .variants()
+            .iter()
+            .filter_map(|variant| {
+                Some((
+                    oracle().enum_variant_name(variant.name()),
+                    variant.docstring()?.into(),
+                ))
+            })
+            .collect();
+        Ok(render_docstring(
+            e.docstring().unwrap_or_default(),
+            &[("Attributes", attributes)],
+            indent,
+        ))
+    }
+
+    /// Like [`docstring`], for a function or method, with an `Args:` section listing its
+    /// arguments. Their descriptions are taken from the `# Arguments` section of the Rust
+    /// documentation, if there is one.
+    pub fn fn_docstring<'a>(
+        docstring: impl Borrow<Option<&'a str>>,
+        arguments: Vec<&Argument>,
+        indent: &str,
+    ) -> Result<String, askama::Error> {
+        let docstring = match docstring.borrow() {
+            Some(docstring) => docstring,
+            None => return Ok(String::new()),
+        };
+        let (body, mut descriptions) = split_arguments_section(docstring);
+        let args = arguments
+            .iter()
+            .map(|arg| {
+                let name = format!(
+                    "{} ({})",
+                    oracle().var_name(arg.name()),
+                    super::type_hint(arg.type_(), true, false)
+                );
+                (name, descriptions.remove(arg.name()).unwrap_or_default())
+            })
+            .collect();
+        Ok(render_docstring(&body, &[("Args", args)], indent))
+    }
+
+    /// The type annotation of the items of an object which is an iterator, if it is one.
+    ///
+    /// Objects with a `next()` method taking no arguments and returning an optional value are
+    /// iterators, which are exhausted once `next()` returns `None`.
+    pub fn iterator_item_type_hint(
+        obj: &Object,
+        config: &Config,
+    ) -> Result<Option<String>, askama::Error> {
+        Ok(obj
+            .methods()
+            .iter()
+            .filter(|meth| meth.name() == "next" && meth.arguments().is_empty())
+            .find_map(|meth| match meth.return_type() {
+                Some(Type::Optional(item)) => {
+                    Some(super::type_hint(item, false, config.memoryview_bytes()))
+                }
+                _ => None,
+            }))
+    }
+
     pub fn ffi_converter_name(codetype: &impl CodeType) -> Result<String, askama::Error> {
         let oracle = oracle();
         Ok(codetype.ffi_converter_name(oracle))
@@ -379,8 +756,45 @@ pub mod filters {
         Ok(oracle().enum_variant_name(nm))
     }
 
-    pub fn coerce_py(nm: &str, type_: &Type) -> Result<String, askama::Error> {
+    /// Get the idiomatic Python rendering of a constant name.
+    pub fn const_name(nm: &str) -> Result<String, askama::Error> {
+        Ok(nm.to_string().to_shouty_snake_case())
+    }
+
+    pub fn coerce_py(nm: &str, type_: &Type, config: &Config) -> Result<String, askama::Error> {
+        // With `memoryview_bytes`, byte sequences are written from their buffer rather than
+        // being copied into a list first.
+        if config.memoryview_bytes() && is_bytes(type_)? {
+            return Ok(nm.to_owned());
+        }
         let oracle = oracle();
         Ok(oracle.find(type_).coerce(oracle, nm))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_docstring() {
+        assert_eq!(render_docstring("", &[], "    "), "");
+        assert_eq!(
+            render_docstring("One line.", &[], "    "),
+            "\n    \"\"\"One line.\"\"\""
+        );
+        assert_eq!(
+            render_docstring(
+                "Summary.\n\nDetails.",
+                &[("Args", vec![("a (int)".into(), "First.\nSecond.".into())])],
+                "    "
+            ),
+            "\n    \"\"\"Summary.\n\n    Details.\n\n    Args:\n        a (int): First.\n            Second.\n    \"\"\""
+        );
+        // Nothing in the documentation may end the docstring or start an escape sequence.
+        assert_eq!(
+            render_docstring(r#"Say """\n" and "hi""#, &[], ""),
+            concat!("\n", r#""""Say \"""\\n" and "hi\"""""#)
+        );
+    }
+}
@@ -23,8 +23,11 @@ impl CodeType for RecordCodeType {
         format!("Type{}", self.id)
     }
 
-    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
-        unreachable!();
+    fn literal(&self, oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptyRecord(_) => format!("{}()", self.type_label(oracle)),
+            _ => unreachable!(),
+        }
     }
 
     fn coerce(&self, _oracle: &dyn CodeOracle, nm: &str) -> String {
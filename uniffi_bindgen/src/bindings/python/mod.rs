@@ -5,16 +5,29 @@
 use std::{io::Write, process::Command};
 
 use anyhow::Result;
-use camino::Utf8Path;
-use fs_err::File;
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err::{self as fs, File};
 
 pub mod gen_python;
+mod package;
 mod test;
 
 use super::super::interface::ComponentInterface;
 pub use gen_python::{generate_python_bindings, Config};
+pub use package::{library_file_name, write_python_package};
 pub use test::run_test;
 
+/// The directory of `out_dir` in which the module is written, and from which it loads the library.
+///
+/// With `package_name`, the module is part of a namespace package (PEP 420), so that the modules of
+/// several crates can be installed side by side in it.
+pub fn module_dir(config: &Config, out_dir: &Utf8Path) -> Utf8PathBuf {
+    match config.package_name() {
+        Some(package_name) => out_dir.join(package_name.replace('.', "/")),
+        None => out_dir.to_owned(),
+    }
+}
+
 // Generate python bindings for the given ComponentInterface, in the given output directory.
 pub fn write_bindings(
     config: &Config,
@@ -22,10 +35,15 @@ pub fn write_bindings(
     out_dir: &Utf8Path,
     try_format_code: bool,
 ) -> Result<()> {
-    let py_file = out_dir.join(format!("{}.py", ci.namespace()));
+    let module_dir = module_dir(config, out_dir);
+    fs::create_dir_all(&module_dir)?;
+    let py_file = module_dir.join(format!("{}.py", ci.namespace()));
     let mut f = File::create(&py_file)?;
     write!(f, "{}", generate_python_bindings(config, ci)?)?;
 
+    // Tell type checkers that the generated module carries type annotations (PEP 561).
+    File::create(module_dir.join("py.typed"))?;
+
     if try_format_code {
         if let Err(e) = Command::new("yapf").arg(&py_file).output() {
             println!(
@@ -0,0 +1,189 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Package a component as a pip-installable Python package.
+//!
+//! The output is a project using the standard setuptools layout:
+//!
+//! ```text
+//! {out_dir}/
+//!     pyproject.toml
+//!     setup.py
+//!     {namespace}/__init__.py
+//!     {namespace}/{namespace}.py
+//!     {namespace}/py.typed
+//!     {namespace}/{cdylib file}
+//! ```
+//!
+//! With `package_name`, the module is part of a namespace package instead, which has no
+//! `__init__.py` so that the packages of several crates can be installed side by side:
+//!
+//! ```text
+//! {out_dir}/
+//!     pyproject.toml
+//!     setup.py
+//!     {package_name}/{namespace}.py
+//!     {package_name}/py.typed
+//!     {package_name}/{cdylib file}
+//! ```
+//!
+//! Running `pip install {out_dir}` installs it, and `pip wheel {out_dir}` produces a wheel for the
+//! platform the library was built for.
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use camino::Utf8Path;
+use fs_err as fs;
+
+use super::{module_dir, write_bindings, Config};
+use crate::bindings::cdylib::{build_cdylib, crate_version};
+use crate::interface::ComponentInterface;
+
+pub fn write_python_package(
+    config: &Config,
+    ci: &ComponentInterface,
+    crate_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    release: bool,
+    build_wheel: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let package_dir = match config.package_name() {
+        Some(_) => module_dir(config, out_dir),
+        None => out_dir.join(ci.namespace()),
+    };
+    fs::create_dir_all(&package_dir)?;
+
+    let cdylib = build_cdylib(crate_root, None, release)?;
+    fs::copy(&cdylib, package_dir.join(library_file_name(config)))?;
+
+    if config.package_name().is_some() {
+        write_bindings(config, ci, out_dir, try_format_code)?;
+    } else {
+        write_bindings(config, ci, &package_dir, try_format_code)?;
+        fs::write(
+            package_dir.join("__init__.py"),
+            format!("from .{} import *  # noqa: F401,F403\n", ci.namespace()),
+        )?;
+    }
+    // The package is versioned like the crate.
+    fs::write(
+        out_dir.join("pyproject.toml"),
+        pyproject_toml(config, ci, &crate_version(crate_root)?),
+    )?;
+    fs::write(out_dir.join("setup.py"), SETUP_PY)?;
+
+    if build_wheel {
+        let status = Command::new("python3")
+            .arg("-m")
+            .arg("pip")
+            .arg("wheel")
+            .arg("--no-deps")
+            .arg("--wheel-dir")
+            .arg(out_dir.join("dist"))
+            .arg(out_dir)
+            .status()
+            .context("Failed to run pip")?;
+        if !status.success() {
+            bail!("Failed to build the wheel");
+        }
+    }
+    Ok(())
+}
+
+/// The file name under which the generated module loads the library on the host platform.
+pub fn library_file_name(config: &Config) -> String {
+    format!(
+        "{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        config.cdylib_name(),
+        std::env::consts::DLL_SUFFIX
+    )
+}
+
+fn pyproject_toml(config: &Config, ci: &ComponentInterface, version: &str) -> String {
+    let (name, package) = match config.package_name() {
+        Some(package_name) => (
+            format!("{}-{}", package_name.replace('.', "-"), ci.namespace()),
+            package_name.to_owned(),
+        ),
+        None => (ci.namespace().to_owned(), ci.namespace().to_owned()),
+    };
+    format!(
+        r#"[build-system]
+requires = ["setuptools>=61"]
+build-backend = "setuptools.build_meta"
+
+[project]
+name = "{name}"
+version = "{version}"
+requires-python = ">=3.7"
+
+[tool.setuptools]
+packages = ["{package}"]
+
+[tool.setuptools.package-data]
+"{package}" = ["*.so", "*.dylib", "*.dll", "py.typed"]
+"#,
+    )
+}
+
+const SETUP_PY: &str = r#"# The package holds a native library, so its wheels must be specific to a platform.
+from setuptools import setup
+from setuptools.dist import Distribution
+
+
+class BinaryDistribution(Distribution):
+    def has_ext_modules(self):
+        return True
+
+
+setup(distclass=BinaryDistribution)
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pyproject_toml() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let pyproject: toml::Value =
+            toml::from_str(&pyproject_toml(&Config::from(&ci), &ci, "1.2.3")).unwrap();
+        assert_eq!(pyproject["project"]["name"].as_str(), Some("test"));
+        assert_eq!(pyproject["project"]["version"].as_str(), Some("1.2.3"));
+        assert!(pyproject["tool"]["setuptools"]["package-data"]["test"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|pattern| pattern.as_str() == Some("py.typed")));
+    }
+
+    #[test]
+    fn test_pyproject_toml_namespace_package() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let config: Config = toml::from_str("package_name = \"mysdk.bindings\"").unwrap();
+        let pyproject: toml::Value =
+            toml::from_str(&pyproject_toml(&config, &ci, "1.2.3")).unwrap();
+        assert_eq!(
+            pyproject["project"]["name"].as_str(),
+            Some("mysdk-bindings-test")
+        );
+        assert_eq!(
+            pyproject["tool"]["setuptools"]["packages"][0].as_str(),
+            Some("mysdk.bindings")
+        );
+        assert!(pyproject["tool"]["setuptools"]["package-data"]
+            .get("mysdk.bindings")
+            .is_some());
+    }
+
+    #[test]
+    fn test_library_file_name() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let name = library_file_name(&Config::from(&ci));
+        assert!(name.contains("uniffi_test"));
+    }
+}
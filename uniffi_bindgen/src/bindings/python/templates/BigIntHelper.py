@@ -0,0 +1,14 @@
+# The BigInt type, passed as a string of decimal digits since Python integers have no size limit.
+class FfiConverterBigInt(FfiConverterRustBuffer):
+    @staticmethod
+    def read(buf):
+        size = buf.readLength()
+        if size < 0:
+            raise InternalError("Unexpected negative integer length")
+        return int(buf.read(size).decode("utf-8"))
+
+    @staticmethod
+    def write(value, buf):
+        utf8Bytes = str(int(value)).encode("utf-8")
+        buf.writeLength(len(utf8Bytes))
+        buf.write(utf8Bytes)
@@ -0,0 +1,69 @@
+{{- self.add_import("weakref") }}
+
+class {{ ffi_converter_name}}(FfiConverterRustBuffer):
+    # `sequence<u8>` values are lifted as memoryviews, and lowered from any object implementing the
+    # buffer protocol, without going through a list of ints.
+
+    @staticmethod
+    def _as_view(value):
+        try:
+            view = memoryview(value)
+        except TypeError:
+            # Not a buffer, but maybe a sequence of ints.
+            return memoryview(bytes(value))
+        if not view.c_contiguous:
+            return memoryview(view.tobytes())
+        return view.cast("B")
+
+    @staticmethod
+    def _source(value, view):
+        if isinstance(value, bytes):
+            return value
+        if view.readonly:
+            # ctypes can only take the address of `bytes` and of writable buffers.
+            return view.tobytes()
+        return (ctypes.c_char * len(view)).from_buffer(view)
+
+    @classmethod
+    def write(cls, value, buf):
+        view = cls._as_view(value)
+        count = len(view)
+        buf.writeLength(count)
+        if count == 0:
+            return
+        source = cls._source(value, view)
+        with buf._reserve(count):
+            ctypes.memmove(ctypes.addressof(buf.rbuf.data.contents) + buf.rbuf.len, source, count)
+
+    @classmethod
+    def lower(cls, value):
+        # A `sequence<u8>` passed on its own, rather than inside another value, is passed as its
+        # raw bytes, without a length prefix.
+        view = cls._as_view(value)
+        count = len(view)
+        rbuf = RustBuffer.alloc(count)
+        if count > 0:
+            ctypes.memmove(ctypes.addressof(rbuf.data.contents), cls._source(value, view), count)
+        return rbuf
+
+    @staticmethod
+    def read(buf):
+        count = buf.readLength()
+        if count < 0:
+            raise InternalError("Unexpected negative sequence length")
+        # The buffer is freed once read, so the bytes have to be copied.
+        return memoryview(buf.read(count))
+
+    @staticmethod
+    def lift(rbuf):
+        # Expose the raw bytes in place, and free the buffer once no view over it is referenced
+        # anymore.
+        count = rbuf.len
+        if count == 0:
+            rbuf.free()
+            return memoryview(b"")
+        array = (ctypes.c_uint8 * count).from_address(ctypes.addressof(rbuf.data.contents))
+        finalizer = weakref.finalize(array, rbuf.free)
+        # The library may already be unloaded when the interpreter exits.
+        finalizer.atexit = False
+        return memoryview(array).cast("B")
@@ -9,8 +9,14 @@ class ConcurrentHandleMap:
         # type Handle = int
         self._left_map = {}  # type: Dict[Handle, Any]
         self._right_map = {}  # type: Dict[Any, Handle]
-
-        self._lock = threading.Lock()
+        # The same object can be passed to Rust several times, possibly from several threads, and
+        # Rust frees each of those handles once. Count them, so that the object is only removed when
+        # Rust doesn't hold any of them anymore.
+        self._ref_counts = {}  # type: Dict[Handle, int]
+
+        # Reentrant, since the objects' `__hash__` and `__eq__`, or finalizers run by the garbage
+        # collector, can call back into Rust while the lock is held, which can free callbacks.
+        self._lock = threading.RLock()
         self._current_handle = 0
         self._stride = 1
 
@@ -18,12 +24,15 @@ class ConcurrentHandleMap:
     def insert(self, obj):
         with self._lock:
             if obj in self._right_map:
-                return self._right_map[obj]
+                handle = self._right_map[obj]
+                self._ref_counts[handle] += 1
+                return handle
             else:
                 handle = self._current_handle
                 self._current_handle += self._stride
                 self._left_map[handle] = obj
                 self._right_map[obj] = handle
+                self._ref_counts[handle] = 1
                 return handle
 
     def get(self, handle):
@@ -33,6 +42,10 @@ class ConcurrentHandleMap:
     def remove(self, handle):
         with self._lock:
             if handle in self._left_map:
+                self._ref_counts[handle] -= 1
+                if self._ref_counts[handle] > 0:
+                    return self._left_map[handle]
+                del self._ref_counts[handle]
                 obj = self._left_map.pop(handle)
                 del self._right_map[obj]
                 return obj
@@ -41,33 +54,37 @@ class ConcurrentHandleMap:
 # to free the callback once it's dropped by Rust.
 IDX_CALLBACK_FREE = 0
 
+# Every sub-interpreter importing this module registers its own callback with Rust, which gives it a
+# registration back. The registration is stored in the two most significant bytes of the handles
+# passed to Rust, so that Rust calls the callback of the interpreter owning the object. The callback
+# is unregistered when the interpreter exits, which frees its slot for other interpreters.
+_CALLBACK_REGISTRATION_SHIFT = 48
+_CALLBACK_HANDLE_MASK = (1 << _CALLBACK_REGISTRATION_SHIFT) - 1
+
 class FfiConverterCallbackInterface:
     _handle_map = ConcurrentHandleMap()
 
-    def __init__(self, cb):
+    def __init__(self, cb, registration):
         self._foreign_callback = cb
+        self._registration = registration
 
     def drop(self, handle):
-        self.__class__._handle_map.remove(handle)
+        self._handle_map.remove(handle & _CALLBACK_HANDLE_MASK)
 
-    @classmethod
-    def lift(cls, handle):
-        obj = cls._handle_map.get(handle)
+    def lift(self, handle):
+        obj = self._handle_map.get(handle & _CALLBACK_HANDLE_MASK)
         if not obj:
             raise InternalError("The object in the handle map has been dropped already")
 
         return obj
 
-    @classmethod
-    def read(cls, buf):
+    def read(self, buf):
         handle = buf.readU64()
-        cls.lift(handle)
+        return self.lift(handle)
 
-    @classmethod
-    def lower(cls, cb):
-        handle = cls._handle_map.insert(cb)
-        return handle
+    def lower(self, cb):
+        handle = self._handle_map.insert(cb)
+        return (self._registration << _CALLBACK_REGISTRATION_SHIFT) | handle
 
-    @classmethod
-    def write(cls, cb, buf):
-        buf.writeU64(cls.lower(cb))
+    def write(self, cb, buf):
+        buf.writeU64(self.lower(cb))
@@ -4,13 +4,19 @@
 {% if self.include_once_check("CallbackInterfaceRuntime.py") %}{% include "CallbackInterfaceRuntime.py" %}{% endif %}
 
 # Declaration and FfiConverters for {{ type_name }} Callback Interface
+{%- if cbi.is_function() %}
+
+# Any callable, such as a function or a lambda, taking the arguments of the callback function.
+{{ type_name }} = typing.Callable
+{%- else %}
 
 class {{ type_name }}:
     {% for meth in cbi.methods() -%}
-    def {{ meth.name()|fn_name }}({% call py::arg_list_decl(meth) %}):
+    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
         raise NotImplementedError
 
     {% endfor %}
+{%- endif %}
 
 def py_{{ foreign_callback }}(handle, method, args, buf_ptr):
     {% for meth in cbi.methods() -%}
@@ -20,14 +26,14 @@ def py_{{ foreign_callback }}(handle, method, args, buf_ptr):
         {%- if meth.arguments().len() != 0 -%}
         {#- Calling the concrete callback object #}
         with args.consumeWithStream() as buf:
-            rval = python_callback.{{ meth.name()|fn_name }}(
+            rval = python_callback{% if !cbi.is_function() %}.{{ meth.name()|fn_name }}{% endif %}(
                 {% for arg in meth.arguments() -%}
                 {{ arg|read_fn }}(buf)
                 {%- if !loop.last %}, {% endif %}
                 {% endfor -%}
             )
         {% else %}
-        rval = python_callback.{{ meth.name()|fn_name }}()
+        rval = python_callback{% if !cbi.is_function() %}.{{ meth.name()|fn_name }}{% endif %}()
         {% endif -%}
 
         {#- Packing up the return value into a RustBuffer #}
@@ -100,5 +106,14 @@ def py_{{ foreign_callback }}(handle, method, args, buf_ptr):
 {{ foreign_callback }} = FOREIGN_CALLBACK_T(py_{{ foreign_callback }})
 
 # The FfiConverter which transforms the Callbacks in to Handles to pass to Rust.
-rust_call(lambda err: _UniFFILib.{{ cbi.ffi_init_callback().name() }}({{ foreign_callback }}, err))
-{{ ffi_converter_name }} = FfiConverterCallbackInterface({{ foreign_callback }})
+{{ ffi_converter_name }} = FfiConverterCallbackInterface(
+    {{ foreign_callback }},
+    rust_call(lambda err: _UniFFILib.{{ cbi.ffi_init_callback().name() }}({{ foreign_callback }}, err)),
+)
+# Unregister the callback when the interpreter exits. This looks up the library when it runs, like
+# the shutdown hooks, since the module may have been reloaded in the meantime.
+atexit.register(
+    lambda registration={{ ffi_converter_name }}._registration: rust_call(
+        _UniFFILib.{{ cbi.ffi_unregister_callback().name() }}, registration
+    )
+)
@@ -0,0 +1,21 @@
+class FfiConverterChar:
+    @classmethod
+    def read(cls, buf):
+        return cls.lift(buf.readU32())
+
+    @classmethod
+    def write(cls, value, buf):
+        buf.writeU32(cls.lower(value))
+
+    @staticmethod
+    def lift(value):
+        return chr(value)
+
+    @staticmethod
+    def lower(value):
+        if not isinstance(value, str) or len(value) != 1:
+            raise TypeError("Expected a string of length 1, got {!r}".format(value))
+        code_point = ord(value)
+        if 0xD800 <= code_point <= 0xDFFF:
+            raise ValueError("{!r} is a surrogate, not a Unicode scalar value".format(value))
+        return code_point
@@ -1,6 +1,8 @@
 {%- match python_config.custom_types.get(name.as_str())  %}
 {% when None %}
 {#- No custom type config, just forward all methods to our builtin type #}
+{{ name }} = {{ builtin|type_hint(python_config) }}
+
 class FfiConverterType{{ name }}:
     @staticmethod
     def write(value, buf):
@@ -29,6 +31,9 @@ class FfiConverterType{{ name }}:
 {%- endmatch %}
 
 {#- Custom type config supplied, use it to convert the builtin type #}
+# The Python type of {{ name }} is controlled by the custom type configuration.
+{{ name }} = typing.Any
+
 class FfiConverterType{{ name }}:
     @staticmethod
     def write(value, buf):
@@ -0,0 +1,25 @@
+{#
+# Methods of records and enums, which are standalone functions in the FFI that receive the
+# record or enum first. Included in the body of their class, for the current `type_`.
+#}
+{%- let receiver = "{}.lower(self)"|format(ffi_converter_name) %}
+{%- for meth in ci.methods_of(type_) %}
+    {%- match meth.return_type() -%}
+
+    {%- when Some with (return_type) %}
+
+    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
+        {{- meth.docstring()|fn_docstring(meth.arguments(), "        ") }}
+        {%- call py::setup_args_extra_indent(meth) %}
+        return {{ return_type|lift_fn }}(
+            {% call py::to_ffi_call_with_prefix(receiver, meth) %}
+        )
+
+    {%- when None %}
+
+    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
+        {{- meth.docstring()|fn_docstring(meth.arguments(), "        ") }}
+        {%- call py::setup_args_extra_indent(meth) %}
+        {% call py::to_ffi_call_with_prefix(receiver, meth) %}
+    {%- endmatch %}
+{%- endfor %}
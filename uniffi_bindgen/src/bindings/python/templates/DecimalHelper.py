@@ -0,0 +1,16 @@
+{{- self.add_import("decimal") }}
+# The Decimal type, passed as a string in plain notation so that no precision is lost.
+class FfiConverterDecimal(FfiConverterRustBuffer):
+    @staticmethod
+    def read(buf):
+        size = buf.readLength()
+        if size < 0:
+            raise InternalError("Unexpected negative decimal length")
+        return decimal.Decimal(buf.read(size).decode("utf-8"))
+
+    @staticmethod
+    def write(value, buf):
+        # `str()` may use an exponent, such as "1E+3", which Rust can't parse.
+        utf8Bytes = format(decimal.Decimal(value), "f").encode("utf-8")
+        buf.writeLength(len(utf8Bytes))
+        buf.write(utf8Bytes)
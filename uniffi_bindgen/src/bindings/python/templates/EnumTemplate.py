@@ -6,21 +6,47 @@
 #}
 {%- let e = ci.get_enum_definition(name).unwrap() %}
 {% if e.is_flat() %}
+{%- match python_config.enum_class() %}
+{%- when EnumClass::IntEnum %}
+
+class {{ type_name }}(enum.IntEnum):
+    {{- e|enum_docstring("    ") }}
+    {% for variant in e.variants() -%}
+    {{ variant.name()|enum_variant_py }} = {{ variant.discriminant() }}
+    {% endfor %}
+    {%- include "DataMethodsTemplate.py" %}
+{%- when EnumClass::StrEnum %}
+
+# Like `enum.StrEnum`, which is only available from Python 3.11.
+class {{ type_name }}(str, enum.Enum):
+    {{- e|enum_docstring("    ") }}
+    {% for variant in e.variants() -%}
+    {{ variant.name()|enum_variant_py }} = "{{ variant.name() }}"
+    {% endfor %}
+    def __str__(self):
+        return self.value
+    {%- include "DataMethodsTemplate.py" %}
+{%- when EnumClass::Enum %}
 
 class {{ type_name }}(enum.Enum):
+    {{- e|enum_docstring("    ") }}
     {% for variant in e.variants() -%}
     {{ variant.name()|enum_variant_py }} = {{ loop.index }}
     {% endfor %}
+    {%- include "DataMethodsTemplate.py" %}
+{%- endmatch %}
 {% else %}
 
 class {{ type_name }}:
+    {{- e.docstring()|docstring("    ") }}
     def __init__(self):
         raise RuntimeError("{{ type_name }} cannot be instantiated directly")
 
     # Each enum variant is a nested class of the enum itself.
     {% for variant in e.variants() -%}
     class {{ variant.name()|enum_variant_py }}(object):
-        def __init__(self,{% for field in variant.fields() %}{{ field.name()|var_name }}{% if loop.last %}{% else %}, {% endif %}{% endfor %}):
+        {{- variant.docstring()|fields_docstring(variant.fields(), "        ") }}
+        def __init__(self{% for field in variant.fields() %}, {{ field.name()|var_name }}: {{ field.type_()|type_hint(python_config) }}{% endfor %}) -> None:
             {% if variant.has_fields() %}
             {%- for field in variant.fields() %}
             self.{{ field.name()|var_name }} = {{ field.name()|var_name }}
@@ -32,7 +58,12 @@ class {{ type_name }}:
         def __str__(self):
             return "{{ type_name }}.{{ variant.name()|enum_variant_py }}({% for field in variant.fields() %}{{ field.name()|var_name }}={}{% if loop.last %}{% else %}, {% endif %}{% endfor %})".format({% for field in variant.fields() %}self.{{ field.name()|var_name }}{% if loop.last %}{% else %}, {% endif %}{% endfor %})
 
+        def __repr__(self):
+            return "{{ type_name }}.{{ variant.name()|enum_variant_py }}({% for field in variant.fields() %}{{ field.name()|var_name }}={!r}{% if loop.last %}{% else %}, {% endif %}{% endfor %})".format({% for field in variant.fields() %}self.{{ field.name()|var_name }}{% if loop.last %}{% else %}, {% endif %}{% endfor %})
+
         def __eq__(self, other):
+            if not isinstance(other, {{ type_name }}):
+                return NotImplemented
             if not other.is_{{ variant.name()|var_name }}():
                 return False
             {%- for field in variant.fields() %}
@@ -40,20 +71,24 @@ class {{ type_name }}:
                 return False
             {%- endfor %}
             return True
+
+        def __hash__(self):
+            return hash(("{{ variant.name()|enum_variant_py }}", {% for field in variant.fields() %}self.{{ field.name()|var_name }}, {% endfor %}))
     {% endfor %}
 
     # For each variant, we have an `is_NAME` method for easily checking
     # whether an instance is that variant.
     {% for variant in e.variants() -%}
-    def is_{{ variant.name()|var_name }}(self):
+    def is_{{ variant.name()|var_name }}(self) -> bool:
         return isinstance(self, {{ type_name }}.{{ variant.name()|enum_variant_py }})
     {% endfor %}
+    {%- include "DataMethodsTemplate.py" %}
 
 # Now, a little trick - we make each nested variant class be a subclass of the main
 # enum class, so that method calls and instance checks etc will work intuitively.
 # We might be able to do this a little more neatly with a metaclass, but this'll do.
 {% for variant in e.variants() -%}
-{{ type_name }}.{{ variant.name()|enum_variant_py }} = type("{{ type_name }}.{{ variant.name()|enum_variant_py }}", ({{ type_name }}.{{variant.name()|enum_variant_py}}, {{ type_name }},), {})
+{{ type_name }}.{{ variant.name()|enum_variant_py }} = type("{{ type_name }}.{{ variant.name()|enum_variant_py }}", ({{ type_name }}.{{variant.name()|enum_variant_py}}, {{ type_name }},), {"__doc__": {{ type_name }}.{{ variant.name()|enum_variant_py }}.__doc__})
 {% endfor %}
 
 {% endif %}
@@ -61,7 +96,7 @@ class {{ type_name }}:
 class {{ ffi_converter_name }}(FfiConverterRustBuffer):
     @staticmethod
     def read(buf):
-        variant = buf.readI32()
+        variant = buf.readLength()
 
         {%- for variant in e.variants() %}
         if variant == {{ loop.index }}:
@@ -81,10 +116,10 @@ class {{ ffi_converter_name }}(FfiConverterRustBuffer):
         {%- for variant in e.variants() %}
         {%- if e.is_flat() %}
         if value == {{ type_name }}.{{ variant.name()|enum_variant_py }}:
-            buf.writeI32({{ loop.index }})
+            buf.writeLength({{ loop.index }})
         {%- else %}
         if value.is_{{ variant.name()|var_name }}():
-            buf.writeI32({{ loop.index }})
+            buf.writeLength({{ loop.index }})
             {%- for field in variant.fields() %}
             {{ field|write_fn }}(value.{{ field.name()|var_name }}, buf)
             {%- endfor %}
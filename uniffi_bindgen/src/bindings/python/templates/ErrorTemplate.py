@@ -18,7 +18,7 @@ class UniFFIExceptionTmpNamespace:
             return "{{ type_name }}.{{ variant_type_name }}({})".format(repr(super().__str__()))
     {%- else %}
     class {{ variant_type_name }}({{ type_name }}):
-        def __init__(self{% for field in variant.fields() %}, {{ field.name()|var_name }}{% endfor %}):
+        def __init__(self{% for field in variant.fields() %}, {{ field.name()|var_name }}: {{ field.type_()|type_hint(python_config) }}{% endfor %}) -> None:
             {%- if variant.has_fields() %}
             {%- for field in variant.fields() %}
             self.{{ field.name()|var_name }} = {{ field.name()|var_name }}
@@ -38,18 +38,27 @@ class UniFFIExceptionTmpNamespace:
             {%- else %}
             return "{{ type_name }}.{{ variant_type_name }}()"
             {%- endif %}
+
+        def __repr__(self):
+            return str(self)
+
+        def __reduce__(self):
+            return (type(self), ({% for field in variant.fields() %}self.{{ field.name()|var_name }}, {% endfor %}))
     {%- endif %}
 
     {{ type_name }}.{{ variant_type_name }} = {{ variant_type_name }}
+    {{ variant_type_name }}.__qualname__ = "{{ type_name }}.{{ variant_type_name }}"
     {%- endfor %}
 {{ type_name }} = UniFFIExceptionTmpNamespace.{{ type_name }}
+# Let pickle find the classes at the place they end up in the module.
+{{ type_name }}.__qualname__ = "{{ type_name }}"
 del UniFFIExceptionTmpNamespace
 
 
 class {{ ffi_converter_name }}(FfiConverterRustBuffer):
     @staticmethod
     def read(buf):
-        variant = buf.readI32()
+        variant = buf.readLength()
         {%- for variant in e.variants() %}
         if variant == {{ loop.index }}:
             return {{ type_name }}.{{ variant.name()|class_name }}(
@@ -68,7 +77,7 @@ class {{ ffi_converter_name }}(FfiConverterRustBuffer):
     def write(value, buf):
         {%- for variant in e.variants() %}
         if isinstance(value, {{ type_name }}.{{ variant.name()|class_name }}):
-            buf.writeI32({{ loop.index }})
+            buf.writeLength({{ loop.index }})
             {%- for field in variant.fields() %}
             {{ field|write_fn }}(value.{{ field.name()|var_name }}, buf)
             {%- endfor %}
@@ -1 +1,7 @@
-from {{ crate_name|fn_name }} import FfiConverterType{{ name }}
+{%- match kind %}
+{%- when ExternalKind::CallbackInterface %}
+# The callback interface {{ name }} registers its callbacks with the library when its module is
+# imported, so the handles lowered by {{ ffi_converter_name }} can be passed to this one.
+{%- else %}
+{%- endmatch %}
+from {{ self.external_type_module_name(crate_name) }} import {{ name }}, {{ ffi_converter_name }}
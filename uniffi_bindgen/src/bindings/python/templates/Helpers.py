@@ -2,7 +2,23 @@
 # This would be a good candidate for isolating in its own ffi-support lib.
 
 class InternalError(Exception):
-    pass
+    # The key/value context which the Rust code attached to the panic, see `_read_error_context()`.
+    error_context = {}
+    # Where the Rust code panicked, as `file:line:column`, or empty if unknown.
+    location = ""
+    # The backtrace of the panic, or empty if unknown.
+    backtrace = ""
+
+class PayloadTooLargeError(InternalError):
+    """A RustBuffer going over the limits set by the Rust code, rather than being allocated.
+
+    `size` is the size of the buffer, or the total size of the live buffers with it, which goes
+    over `limit`, in bytes.
+    """
+    def __init__(self, message, size, limit):
+        super().__init__(message)
+        self.size = size
+        self.limit = limit
 
 class RustCallStatus(ctypes.Structure):
     """
@@ -17,6 +33,7 @@ class RustCallStatus(ctypes.Structure):
     CALL_SUCCESS = 0
     CALL_ERROR = 1
     CALL_PANIC = 2
+    CALL_PAYLOAD_TOO_LARGE = 3
 
     def __str__(self):
         if self.code == RustCallStatus.CALL_SUCCESS:
@@ -25,6 +42,8 @@ class RustCallStatus(ctypes.Structure):
             return "RustCallStatus(CALL_ERROR)"
         elif self.code == RustCallStatus.CALL_PANIC:
             return "RustCallStatus(CALL_PANIC)"
+        elif self.code == RustCallStatus.CALL_PAYLOAD_TOO_LARGE:
+            return "RustCallStatus(CALL_PAYLOAD_TOO_LARGE)"
         else:
             return "RustCallStatus(<invalid code>)"
 
@@ -48,20 +67,51 @@ def rust_call_with_error(error_ffi_converter, fn, *args):
             call_status.err_buf.contents.free()
             raise InternalError("rust_call_with_error: CALL_ERROR, but error_ffi_converter is None")
         else:
-            raise error_ffi_converter.lift(call_status.error_buf)
+            with call_status.error_buf.consumeWithStream() as stream:
+                error = error_ffi_converter.read(stream)
+                error.error_context = _read_error_context(stream)
+            raise error
     elif call_status.code == RustCallStatus.CALL_PANIC:
         # When the rust code sees a panic, it tries to construct a RustBuffer
         # with the message.  But if that code panics, then it just sends back
         # an empty buffer.
         if call_status.error_buf.len > 0:
-            msg = FfiConverterString.lift(call_status.error_buf)
+            raise _lift_panic(call_status.error_buf)
         else:
-            msg = "Unknown rust panic"
-        raise InternalError(msg)
+            raise InternalError("Unknown rust panic")
+    elif call_status.code == RustCallStatus.CALL_PAYLOAD_TOO_LARGE:
+        # The error buffer holds its message, size and limit, followed by its context.
+        with call_status.error_buf.consumeWithStream() as stream:
+            error = PayloadTooLargeError(FfiConverterString.read(stream), stream.readU64(), stream.readU64())
+            error.error_context = _read_error_context(stream)
+        raise error
     else:
         raise InternalError("Invalid RustCallStatus code: {}".format(
             call_status.code))
 
+def _lift_panic(error_buf):
+    # The error buffer of a panic holds its message, followed by its location and its backtrace,
+    # which are empty when unknown, and its context.
+    with error_buf.consumeWithStream() as stream:
+        error = InternalError(FfiConverterString.read(stream))
+        error.location = FfiConverterString.read(stream)
+        error.backtrace = FfiConverterString.read(stream)
+        error.error_context = _read_error_context(stream)
+    return error
+
+def _read_error_context(stream):
+    # The key/value context which the Rust code attached to the error follows it, unless empty.
+    error_context = {}
+    if stream.remaining() > 0:
+        for _ in range(stream.readLength()):
+            key = FfiConverterString.read(stream)
+            error_context[key] = FfiConverterString.read(stream)
+    # The sources of the errors declared with `[Source]` come next, which aren't exposed in Python.
+    if stream.remaining() > 0:
+        for _ in range(stream.readLength()):
+            FfiConverterString.read(stream)
+    return error_context
+
 # A function pointer for a callback as defined by UniFFI.
 # Rust definition `fn(handle: u64, method: u32, args: RustBuffer, buf_ptr: *mut RustBuffer) -> int`
 FOREIGN_CALLBACK_T = ctypes.CFUNCTYPE(ctypes.c_int, ctypes.c_ulonglong, ctypes.c_ulong, RustBuffer, ctypes.POINTER(RustBuffer))
@@ -1,8 +1,16 @@
 class FfiConverterInt16(FfiConverterPrimitive):
     @staticmethod
     def read(buf):
+        {%- if ci.compact_encoding() %}
+        return buf.readZigZag()
+        {%- else %}
         return buf.readI16()
+        {%- endif %}
 
     @staticmethod
     def write(value, buf):
+        {%- if ci.compact_encoding() %}
+        buf.writeZigZag(value)
+        {%- else %}
         buf.writeI16(value)
+        {%- endif %}
@@ -1,8 +1,16 @@
 class FfiConverterInt32(FfiConverterPrimitive):
     @staticmethod
     def read(buf):
+        {%- if ci.compact_encoding() %}
+        return buf.readZigZag()
+        {%- else %}
         return buf.readI32()
+        {%- endif %}
 
     @staticmethod
     def write(value, buf):
+        {%- if ci.compact_encoding() %}
+        buf.writeZigZag(value)
+        {%- else %}
         buf.writeI32(value)
+        {%- endif %}
@@ -1,8 +1,16 @@
 class FfiConverterInt64(FfiConverterPrimitive):
     @staticmethod
     def read(buf):
+        {%- if ci.compact_encoding() %}
+        return buf.readZigZag()
+        {%- else %}
         return buf.readI64()
+        {%- endif %}
 
     @staticmethod
     def write(value, buf):
+        {%- if ci.compact_encoding() %}
+        buf.writeZigZag(value)
+        {%- else %}
         buf.writeI64(value)
+        {%- endif %}
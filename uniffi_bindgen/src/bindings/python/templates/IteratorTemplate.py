@@ -0,0 +1,55 @@
+{%- let next_type = ci.ffi_iterator_next_type(type_) %}
+
+class {{ type_name }}(object):
+    """An iterator over the items Rust produces lazily."""
+
+    def __del__(self):
+        self._free_()
+
+    def _free_(self):
+        # Popping the handle is atomic, so that only one thread frees it, like for objects.
+        handle = self.__dict__.pop("_handle", None)
+        if handle is not None:
+            rust_call(_UniFFILib.{{ ci.ffi_iterator_free(type_).name() }}, handle)
+
+    def __repr__(self):
+        handle = getattr(self, "_handle", None)
+        return "<{{ type_name }} handle={}>".format("None" if handle is None else hex(handle))
+
+    def __reduce__(self):
+        raise TypeError("{{ type_name }} wraps a Rust iterator and can't be pickled")
+
+    @classmethod
+    def _make_instance_(cls, handle):
+        inst = cls.__new__(cls)
+        inst._handle = handle
+        return inst
+
+    def __iter__(self) -> {{ type_name }}:
+        return self
+
+    def __next__(self) -> {{ item_type|type_hint(python_config) }}:
+        item = {{ next_type.borrow()|lift_fn }}(
+            rust_call(_UniFFILib.{{ ci.ffi_iterator_next(type_).name() }}, self._handle)
+        )
+        if item is None:
+            raise StopIteration
+        return item
+
+
+class {{ ffi_converter_name }}:
+    @classmethod
+    def read(cls, buf):
+        return cls.lift(buf.readU64())
+
+    @classmethod
+    def write(cls, value, buf):
+        raise TypeError("Iterators can only be returned by Rust")
+
+    @staticmethod
+    def lift(value):
+        return {{ type_name }}._make_instance_(value)
+
+    @staticmethod
+    def lower(value):
+        return value._handle
@@ -0,0 +1,16 @@
+{{- self.add_import("json") }}
+# The Json type, passed as JSON text, which is made of dicts, lists, strs, ints, floats, bools and None.
+class FfiConverterJson(FfiConverterRustBuffer):
+    @staticmethod
+    def read(buf):
+        size = buf.readLength()
+        if size < 0:
+            raise InternalError("Unexpected negative JSON length")
+        return json.loads(buf.read(size).decode("utf-8"))
+
+    @staticmethod
+    def write(value, buf):
+        # NaN and infinities aren't valid JSON, so Rust would fail to read them.
+        utf8Bytes = json.dumps(value, allow_nan=False).encode("utf-8")
+        buf.writeLength(len(utf8Bytes))
+        buf.write(utf8Bytes)
@@ -0,0 +1,53 @@
+{%- let install_fn = ci.ffi_install_log_forwarder_name() %}
+import logging
+
+# The `logging` levels of the records of the `log` crate, from `Level::Error` to `Level::Trace`.
+_UNIFFI_LOG_LEVELS = {
+    1: logging.ERROR,
+    2: logging.WARNING,
+    3: logging.INFO,
+    4: logging.DEBUG,
+    # `logging` has no level for tracing, so use one below `DEBUG`.
+    5: logging.DEBUG - 5,
+}
+
+_UNIFFI_LOGGER = logging.getLogger("{{ ci.namespace() }}")
+
+def _uniffi_forward_log(level, target, target_len, message, message_len):
+    py_level = _UNIFFI_LOG_LEVELS.get(level, logging.DEBUG)
+    if _UNIFFI_LOGGER.isEnabledFor(py_level):
+        _UNIFFI_LOGGER.log(
+            py_level,
+            "%s",
+            ctypes.string_at(message, message_len).decode("utf-8", "replace"),
+            extra={"rust_target": ctypes.string_at(target, target_len).decode("utf-8", "replace")},
+        )
+
+# Rust may call it from any thread for as long as the library is loaded, so it must never be
+# garbage collected.
+_UNIFFI_LOG_FORWARDER = ctypes.CFUNCTYPE(
+    None, ctypes.c_int32, ctypes.c_void_p, ctypes.c_int32, ctypes.c_void_p, ctypes.c_int32
+)(_uniffi_forward_log)
+
+_UniFFILib.{{ install_fn }}.argtypes = (
+    type(_UNIFFI_LOG_FORWARDER),
+    ctypes.c_int32,
+    ctypes.POINTER(RustCallStatus),
+)
+_UniFFILib.{{ install_fn }}.restype = ctypes.c_int8
+
+def install_log_forwarder(level: typing.Optional[int] = None) -> None:
+    """Forward the records of the Rust `log` crate to the `{{ ci.namespace() }}` logger.
+
+    The Rust target of each record is in its `rust_target` attribute.
+
+    Args:
+        level (typing.Optional[int]): The most verbose level to forward, which defaults to the
+            effective level of the logger. Records of more verbose levels are dropped in Rust,
+            without calling into Python.
+    """
+    if level is None:
+        level = _UNIFFI_LOGGER.getEffectiveLevel()
+    max_level = max((rust for rust, py in _UNIFFI_LOG_LEVELS.items() if py >= level), default=0)
+    if not rust_call(_UniFFILib.{{ install_fn }}, _UNIFFI_LOG_FORWARDER, max_level):
+        raise RuntimeError("Another logger is already installed for the Rust `log` crate")
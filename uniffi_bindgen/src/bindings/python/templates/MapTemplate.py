@@ -4,14 +4,14 @@
 class {{ ffi_converter_name }}(FfiConverterRustBuffer):
     @classmethod
     def write(cls, items, buf):
-        buf.writeI32(len(items))
+        buf.writeLength(len(items))
         for (key, value) in items.items():
             {{ key_ffi_converter }}.write(key, buf)
             {{ value_ffi_converter }}.write(value, buf)
 
     @classmethod
     def read(cls, buf):
-        count = buf.readI32()
+        count = buf.readLength()
         if count < 0:
             raise InternalError("Unexpected negative map size")
 
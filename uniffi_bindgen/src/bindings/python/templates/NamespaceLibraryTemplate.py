@@ -23,14 +23,48 @@ def loadIndirect():
         # Anything else must be an ELF platform - Linux, *BSD, Solaris/illumos
         libname = "lib{}.so"
 
-    lib = libname.format("{{ config.cdylib_name() }}")
+    lib = libname.format("{{ python_config.cdylib_name() }}")
     path = str(Path(__file__).parent / lib)
+    # Functions of a `ctypes.CDLL` release the GIL while they run, so that other Python threads
+    # keep running during long Rust calls. Callbacks from Rust reacquire it. Loading the library
+    # as a `ctypes.PyDLL` would keep the GIL held instead.
     return ctypes.cdll.LoadLibrary(path)
 
+{% for rec in ci.record_definitions() -%}
+{%- if rec.is_ffi_struct() -%}
+# The fields of the `{{ rec.name()|class_name }}` record, which is passed by value.
+class FfiStruct{{ rec.name()|class_name }}(ctypes.Structure):
+    _fields_ = [
+        {%- for field in rec.fields() %}
+        {%- let ffi_type = FFIType::from(field.type_()) %}
+        ("{{ field.name()|var_name }}", {{ ffi_type|ffi_type_name }}),
+        {%- endfor %}
+    ]
+
+{% endif -%}
+{%- endfor -%}
 # A ctypes library to expose the extern-C FFI definitions.
 # This is an implementation detail which will be called internally by the public API.
 
 _UniFFILib = loadIndirect()
+
+def uniffi_check_contract_version(lib):
+    # Check that the library was built from the same interface as these bindings, and with the same
+    # version of UniFFI, before declaring the other functions, which would be missing otherwise.
+    bindings_contract_version = {{ ci.contract_version() }}
+    lib.{{ ci.ffi_contract_version_name() }}.argtypes = ()
+    lib.{{ ci.ffi_contract_version_name() }}.restype = ctypes.c_uint32
+    scaffolding_contract_version = lib.{{ ci.ffi_contract_version_name() }}()
+    if bindings_contract_version != scaffolding_contract_version:
+        raise InternalError(
+            "UniFFI contract version mismatch: the bindings of {{ ci.namespace() }} expect version {}, "
+            "but the library provides {}. Regenerate the bindings with the version of uniffi-bindgen "
+            "and the UDL file the library was built with.".format(
+                hex(bindings_contract_version), hex(scaffolding_contract_version)
+            )
+        )
+
+uniffi_check_contract_version(_UniFFILib)
 {%- for func in ci.iter_ffi_function_definitions() %}
 _UniFFILib.{{ func.name() }}.argtypes = (
     {%- call py::arg_list_ffi_decl(func) -%}
@@ -1,52 +1,130 @@
 {%- let obj = ci.get_object_definition(name).unwrap() %}
 
 class {{ type_name }}(object):
+    {{- obj.docstring()|docstring("    ") }}
     {%- match obj.primary_constructor() %}
     {%- when Some with (cons) %}
-    def __init__(self, {% call py::arg_list_decl(cons) -%}):
+    def __init__(self, {% call py::arg_list_decl(cons) -%}) -> None:
         {%- call py::setup_args_extra_indent(cons) %}
-        self._pointer = {% call py::to_ffi_call(cons) %}
+        self._handle = {% call py::to_ffi_call(cons) %}
     {%- when None %}
     {%- endmatch %}
 
     def __del__(self):
-        # In case of partial initialization of instances.
-        pointer = getattr(self, "_pointer", None)
-        if pointer is not None:
-            rust_call(_UniFFILib.{{ obj.ffi_object_free().name() }}, pointer)
+        self._free_()
+
+    def _free_(self):
+        # In case of partial initialization of instances. Popping the handle is atomic, so that
+        # only one thread frees it, even without the GIL.
+        handle = self.__dict__.pop("_handle", None)
+        if handle is not None:
+            rust_call(_UniFFILib.{{ obj.ffi_object_free().name() }}, handle)
+{%- if !obj.has_method("destroy") %}
+
+    def destroy(self) -> None:
+        """
+        Free the Rust object right away, rather than when this object is garbage collected.
+
+        Calling the methods of this object afterwards raises a `ValueError`.
+        """
+        self._free_()
+{%- endif %}
+
+    def _uniffi_handle(self):
+        # Only `_free_` removes the handle, so the object was destroyed if it's missing.
+        handle = self.__dict__.get("_handle")
+        if handle is None:
+            raise ValueError("{{ type_name }} object has already been destroyed")
+        return handle
+
+    def __repr__(self):
+        handle = getattr(self, "_handle", None)
+        return "<{{ type_name }} handle={}>".format("None" if handle is None else hex(handle))
+
+    def __enter__(self) -> {{ type_name }}:
+        return self
+
+    def __exit__(self, exc_type, exc_value, traceback) -> None:
+        {%- if obj|has_close_method %}
+        self.close()
+        {%- else %}
+        # The object must not be used after leaving the `with` block.
+        self._free_()
+        {%- endif %}
+
+    {%- match obj|iterator_item_type_hint(python_config) %}
+    {%- when Some with (item_type_hint) %}
+
+    def __iter__(self) -> {{ type_name }}:
+        return self
+
+    def __next__(self) -> {{ item_type_hint }}:
+        item = self.next()
+        if item is None:
+            raise StopIteration
+        return item
+    {%- when None %}
+    {%- endmatch %}
+
+    def __reduce__(self):
+        # Pickling would copy the handle of the Rust object, which is only valid in this process
+        # and while this instance is alive.
+        raise TypeError("{{ type_name }} wraps a Rust object and can't be pickled")
 
     # Used by alternative constructors or any methods which return this type.
     @classmethod
-    def _make_instance_(cls, pointer):
+    def _make_instance_(cls, handle):
         # Lightly yucky way to bypass the usual __init__ logic
-        # and just create a new instance with the required pointer.
+        # and just create a new instance with the required handle.
         inst = cls.__new__(cls)
-        inst._pointer = pointer
+        inst._handle = handle
         return inst
 
     {% for cons in obj.alternate_constructors() -%}
     @classmethod
-    def {{ cons.name()|fn_name }}(cls, {% call py::arg_list_decl(cons) %}):
+    def {{ cons.name()|fn_name }}(cls, {% call py::arg_list_decl(cons) %}) -> {{ type_name }}:
         {%- call py::setup_args_extra_indent(cons) %}
         # Call the (fallible) function before creating any half-baked object instances.
-        pointer = {% call py::to_ffi_call(cons) %}
-        return cls._make_instance_(pointer)
+        handle = {% call py::to_ffi_call(cons) %}
+        return cls._make_instance_(handle)
     {% endfor %}
 
     {% for meth in obj.methods() -%}
     {%- match meth.return_type() -%}
 
     {%- when Some with (return_type) -%}
-    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}):
+    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
+        {{- meth.docstring()|fn_docstring(meth.arguments(), "        ") }}
         {%- call py::setup_args_extra_indent(meth) %}
         return {{ return_type|lift_fn }}(
-            {% call py::to_ffi_call_with_prefix("self._pointer", meth) %}
+            {% call py::to_ffi_call_with_prefix("self._uniffi_handle()", meth) %}
         )
 
     {%- when None -%}
-    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}):
+    def {{ meth.name()|fn_name }}(self, {% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
+        {{- meth.docstring()|fn_docstring(meth.arguments(), "        ") }}
         {%- call py::setup_args_extra_indent(meth) %}
-        {% call py::to_ffi_call_with_prefix("self._pointer", meth) %}
+        {% call py::to_ffi_call_with_prefix("self._uniffi_handle()", meth) %}
+    {% endmatch %}
+    {% endfor %}
+
+    {%- for meth in obj.static_methods() %}
+    @staticmethod
+    {%- match meth.return_type() %}
+
+    {%- when Some with (return_type) %}
+    def {{ meth.name()|fn_name }}({% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
+        {{- meth.docstring()|fn_docstring(meth.arguments(), "        ") }}
+        {%- call py::setup_args_extra_indent(meth) %}
+        return {{ return_type|lift_fn }}(
+            {% call py::to_ffi_call(meth) %}
+        )
+
+    {%- when None %}
+    def {{ meth.name()|fn_name }}({% call py::arg_list_decl(meth) %}){% call py::return_type_hint(meth) %}:
+        {{- meth.docstring()|fn_docstring(meth.arguments(), "        ") }}
+        {%- call py::setup_args_extra_indent(meth) %}
+        {% call py::to_ffi_call(meth) %}
     {% endmatch %}
     {% endfor %}
 
@@ -54,16 +132,16 @@ class {{ type_name }}(object):
 class {{ ffi_converter_name }}:
     @classmethod
     def read(cls, buf):
-        ptr = buf.readU64()
-        if ptr == 0:
-            raise InternalError("Raw pointer value was null")
-        return cls.lift(ptr)
+        return cls.lift(buf.readU64())
 
     @classmethod
     def write(cls, value, buf):
         if not isinstance(value, {{ type_name }}):
             raise TypeError("Expected {{ type_name }} instance, {} found".format(value.__class__.__name__))
-        buf.writeU64(cls.lower(value))
+        buf.writeClonedHandle(
+            rust_call(_UniFFILib.{{ obj.ffi_object_clone().name() }}, cls.lower(value)),
+            _UniFFILib.{{ obj.ffi_object_free().name() }},
+        )
 
     @staticmethod
     def lift(value):
@@ -71,4 +149,4 @@ class {{ ffi_converter_name }}:
 
     @staticmethod
     def lower(value):
-        return value._pointer
+        return value._uniffi_handle()
@@ -0,0 +1,22 @@
+# The OffsetDateTime type, which is an aware datetime with a fixed UTC offset.
+# As for timestamps, Python datetimes are only accurate to the microsecond.
+class FfiConverterOffsetDateTime(FfiConverterRustBuffer):
+    @staticmethod
+    def read(buf):
+        seconds = buf.readI64()
+        microseconds = buf.readU32() // 1000
+        offset = datetime.timezone(datetime.timedelta(seconds=buf.readI32()))
+        utc = datetime.datetime.fromtimestamp(0, tz=datetime.timezone.utc) + datetime.timedelta(seconds=seconds, microseconds=microseconds)
+        return utc.astimezone(offset)
+
+    @staticmethod
+    def write(value, buf):
+        offset = value.utcoffset()
+        if offset is None:
+            raise ValueError("OffsetDateTime values must be aware datetimes, with a tzinfo")
+        # timedeltas are normalized so that only the days can be negative, which
+        # rounds the seconds towards negative infinity, as expected on the buffer.
+        delta = value - datetime.datetime.fromtimestamp(0, datetime.timezone.utc)
+        buf.writeI64(delta.days * 24 * 3600 + delta.seconds)
+        buf.writeU32(delta.microseconds * 1000)
+        buf.writeI32(offset.days * 24 * 3600 + offset.seconds)
@@ -1,35 +1,70 @@
 {%- let rec = ci.get_record_definition(name).unwrap() %}
+{%- let frozen = python_config.frozen_records() %}
+{%- let keyword_only = rec|first_keyword_only_field %}
+{%- let custom_init = keyword_only.is_some() %}
+{{- self.add_import("dataclasses") }}
+@dataclasses.dataclass({% if custom_init %}init=False, {% endif %}{% if frozen %}frozen=True{% else %}eq=True{% endif %})
 class {{ type_name }}:
+    {{- rec.docstring()|fields_docstring(rec.fields(), "    ") }}
+    {%- for field in rec.fields() %}
+    {{ field.name()|var_name }}: {{ field.type_()|type_hint(python_config) }}
+    {%- match field.default_value() %}
+    {%- when Some with(literal) %}
+    {%- if literal|is_plain_default %} = {{ literal|literal_py(field) }}
+    {%- else %} = dataclasses.field(default_factory=lambda: {{ literal|literal_py(field) }})
+    {%- endif %}
+    {%- when None %}
+    {%- endmatch %}
+    {%- endfor %}
+    {%- if custom_init %}
 
+    # A field with a default value comes before a field without, so the `__init__` is ours.
     def __init__(self, {% for field in rec.fields() %}
-    {{- field.name()|var_name }}
+    {%- if keyword_only.as_ref() == Some(loop.index0) %}*, {% endif %}
+    {{- field.name()|var_name }}: {{ field.type_()|arg_type_hint }}
     {%- if field.default_value().is_some() %} = DEFAULT{% endif %}
     {%- if !loop.last %}, {% endif %}
-    {%- endfor %}):
+    {%- endfor %}) -> None:
         {%- for field in rec.fields() %}
         {%- let field_name = field.name()|var_name %}
         {%- match field.default_value() %}
         {%- when None %}
-        self.{{ field_name }} = {{ field_name }}
+        {% if frozen %}object.__setattr__(self, "{{ field_name }}", {{ field_name }}){% else %}self.{{ field_name }} = {{ field_name }}{% endif %}
         {%- when Some with(literal) %}
         if {{ field_name }} is DEFAULT:
-            self.{{ field_name }} = {{ literal|literal_py(field) }}
-        else:
-            self.{{ field_name }} = {{ field_name }}
+            {{ field_name }} = {{ literal|literal_py(field) }}
+        {% if frozen %}object.__setattr__(self, "{{ field_name }}", {{ field_name }}){% else %}self.{{ field_name }} = {{ field_name }}{% endif %}
         {%- endmatch %}
         {%- endfor %}
+    {%- endif %}
 
     def __str__(self):
         return "{{ type_name }}({% for field in rec.fields() %}{{ field.name()|var_name }}={}{% if loop.last %}{% else %}, {% endif %}{% endfor %})".format({% for field in rec.fields() %}self.{{ field.name()|var_name }}{% if loop.last %}{% else %}, {% endif %}{% endfor %})
+    {%- include "DataMethodsTemplate.py" %}
 
-    def __eq__(self, other):
-        {%- for field in rec.fields() %}
-        if self.{{ field.name()|var_name }} != other.{{ field.name()|var_name }}:
-            return False
-        {%- endfor %}
-        return True
+{% if rec.is_ffi_struct() -%}
+# The record is made only of numbers and booleans, so it's passed as a C struct of the FFI values
+# of its fields, rather than serialized. It's still serialized inside other values.
+class {{ ffi_converter_name }}:
+    @staticmethod
+    def lift(value):
+        return {{ type_name }}(
+            {%- for field in rec.fields() %}
+            {{ field.name()|var_name }}={{ field|lift_fn }}(value.{{ field.name()|var_name }}),
+            {%- endfor %}
+        )
+
+    @staticmethod
+    def lower(value):
+        return FfiStruct{{ type_name }}(
+            {%- for field in rec.fields() %}
+            {{ field|lower_fn }}(value.{{ field.name()|var_name }}),
+            {%- endfor %}
+        )
 
+{% else -%}
 class {{ ffi_converter_name }}(FfiConverterRustBuffer):
+{% endif %}
     @staticmethod
     def read(buf):
         return {{ type_name }}(
@@ -0,0 +1,14 @@
+_T = typing.TypeVar("_T")
+_E = typing.TypeVar("_E")
+
+
+@dataclasses.dataclass(frozen=True)
+class Ok(typing.Generic[_T]):
+    """The value of a successful result."""
+    value: _T
+
+
+@dataclasses.dataclass(frozen=True)
+class Err(typing.Generic[_E]):
+    """The error of a failed result."""
+    error: _E
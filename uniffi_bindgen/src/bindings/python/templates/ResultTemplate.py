@@ -0,0 +1,24 @@
+{{- self.add_import("dataclasses") }}
+{%- if self.include_once_check("ResultRuntime.py") %}{% include "ResultRuntime.py" %}{% endif %}
+
+class {{ ffi_converter_name }}(FfiConverterRustBuffer):
+    @classmethod
+    def write(cls, value, buf):
+        if isinstance(value, Ok):
+            buf.writeU8(0)
+            {{ ok_type|ffi_converter_name }}.write(value.value, buf)
+        elif isinstance(value, Err):
+            buf.writeU8(1)
+            {{ err_type|ffi_converter_name }}.write(value.error, buf)
+        else:
+            raise TypeError("Expected Ok or Err instance, {} found".format(value.__class__.__name__))
+
+    @classmethod
+    def read(cls, buf):
+        tag = buf.readU8()
+        if tag == 0:
+            return Ok({{ ok_type|ffi_converter_name }}.read(buf))
+        elif tag == 1:
+            return Err({{ err_type|ffi_converter_name }}.read(buf))
+        else:
+            raise InternalError("Unexpected tag byte for result type")
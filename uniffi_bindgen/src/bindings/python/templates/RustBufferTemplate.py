@@ -120,6 +120,33 @@ class RustBufferStream(object):
 
     def readDouble(self):
         return self._unpack_from(8, ">d")
+{%- if ci.compact_encoding() %}
+
+    # The component has `compact_encoding = true` in its `uniffi.toml`: the integers wider than a
+    # byte are written as varints (LEB128), zigzag-encoded if they're signed, and so are the lengths
+    # of the strings, sequences and maps, and the variants of the enums.
+
+    def readVarint(self):
+        value = 0
+        for shift in range(0, 64, 7):
+            byte = self.readU8()
+            value |= (byte & 0x7f) << shift
+            if byte & 0x80 == 0:
+                return value
+        raise InternalError("varint longer than 10 bytes")
+
+    def readZigZag(self):
+        v = self.readVarint()
+        return (v >> 1) ^ -(v & 1)
+
+    def readLength(self):
+        return self.readVarint()
+{%- else %}
+
+    # The lengths of the strings, sequences and maps, and the variants of the enums, are i32s.
+    def readLength(self):
+        return self.readI32()
+{%- endif %}
 
 
 class RustBufferBuilder(object):
@@ -130,14 +157,20 @@ class RustBufferBuilder(object):
     def __init__(self):
         self.rbuf = RustBuffer.alloc(16)
         self.rbuf.len = 0
+        # The handles of the objects cloned for Rust, with the functions freeing them, which are
+        # called if the buffer is discarded, as Rust then never reads it.
+        self.clonedHandles = []
 
     def finalize(self):
         rbuf = self.rbuf
         self.rbuf = None
+        self.clonedHandles = []
         return rbuf
 
     def discard(self):
         if self.rbuf is not None:
+            for (free, handle) in self.clonedHandles:
+                rust_call(free, handle)
             rbuf = self.finalize()
             rbuf.free()
 
@@ -159,6 +192,10 @@ class RustBufferBuilder(object):
             for i, byte in enumerate(value):
                 self.rbuf.data[self.rbuf.len + i] = byte
 
+    def writeClonedHandle(self, handle, free):
+        self.clonedHandles.append((free, handle))
+        self.writeU64(handle)
+
     def writeI8(self, v):
         self._pack_into(1, ">b", v)
 
@@ -188,3 +225,25 @@ class RustBufferBuilder(object):
 
     def writeDouble(self, v):
         self._pack_into(8, ">d", v)
+{%- if ci.compact_encoding() %}
+
+    def writeVarint(self, v):
+        if v < 0 or v >= 1 << 64:
+            raise ValueError("{} is out of range for a varint".format(v))
+        while v >= 0x80:
+            self.writeU8((v & 0x7f) | 0x80)
+            v >>= 7
+        self.writeU8(v)
+
+    def writeZigZag(self, v):
+        if v < -(1 << 63) or v >= 1 << 63:
+            raise ValueError("{} is out of range for a zigzag varint".format(v))
+        self.writeVarint((v << 1) ^ (v >> 63))
+
+    def writeLength(self, v):
+        self.writeVarint(v)
+{%- else %}
+
+    def writeLength(self, v):
+        self.writeI32(v)
+{%- endif %}
@@ -1,19 +1,68 @@
 {%- let inner_ffi_converter = inner_type|ffi_converter_name %}
 
 class {{ ffi_converter_name}}(FfiConverterRustBuffer):
+{%- if ci.compact_encoding() && type_|is_strings %}
+    # With the compact encoding, the strings share their length prefix when they all have the same
+    # length: it's written once, plus one, after the count, and is zero otherwise.
+    @classmethod
+    def write(cls, value, buf):
+        buf.writeLength(len(value))
+        if not value:
+            return
+        utf8Items = [item.encode("utf-8") for item in value]
+        shared = all(len(item) == len(utf8Items[0]) for item in utf8Items)
+        buf.writeLength(len(utf8Items[0]) + 1 if shared else 0)
+        for item in utf8Items:
+            if not shared:
+                buf.writeLength(len(item))
+            buf.write(item)
+
+    @classmethod
+    def read(cls, buf):
+        count = buf.readLength()
+        if count == 0:
+            return []
+        sharedLength = buf.readLength()
+
+        def readItem():
+            size = buf.readLength() if sharedLength == 0 else sharedLength - 1
+            return buf.read(size).decode("utf-8")
+
+        return [readItem() for i in range(count)]
+{%- else %}
     @classmethod
     def write(cls, value, buf):
         items = len(value)
-        buf.writeI32(items)
+        buf.writeLength(items)
         for item in value:
             {{ inner_ffi_converter }}.write(item, buf)
 
     @classmethod
     def read(cls, buf):
-        count = buf.readI32()
+        count = buf.readLength()
         if count < 0:
             raise InternalError("Unexpected negative sequence length")
 
         return [
             {{ inner_ffi_converter }}.read(buf) for i in range(count)
         ]
+{%- endif %}
+{%- if type_|is_bytes %}
+
+    # A `sequence<u8>` passed on its own, rather than inside another value, is passed as its raw
+    # bytes, without a length prefix.
+    @staticmethod
+    def lift(rbuf):
+        try:
+            return list(rbuf.data[0:rbuf.len])
+        finally:
+            rbuf.free()
+
+    @staticmethod
+    def lower(value):
+        data = bytes(value)
+        rbuf = RustBuffer.alloc(len(data))
+        if data:
+            ctypes.memmove(ctypes.addressof(rbuf.data.contents), data, len(data))
+        return rbuf
+{%- endif %}
@@ -0,0 +1,19 @@
+{%- let inner_ffi_converter = inner_type|ffi_converter_name %}
+
+class {{ ffi_converter_name}}(FfiConverterRustBuffer):
+    @classmethod
+    def write(cls, value, buf):
+        items = len(value)
+        buf.writeLength(items)
+        for item in value:
+            {{ inner_ffi_converter }}.write(item, buf)
+
+    @classmethod
+    def read(cls, buf):
+        count = buf.readLength()
+        if count < 0:
+            raise InternalError("Unexpected negative set length")
+
+        return {
+            {{ inner_ffi_converter }}.read(buf) for i in range(count)
+        }
@@ -0,0 +1,68 @@
+class SharedSlice(object):
+    """A region of read-only memory owned by Rust, exposed without copying it.
+
+    Its bytes are read through the `view` memoryview, which is only valid until the slice is
+    released, with `release()` or by leaving a `with` block.  It's released when it's garbage
+    collected otherwise, but datasets this large are better released as soon as possible.
+    """
+
+    def __del__(self):
+        self.release()
+
+    def release(self):
+        # Releasing the view raises `BufferError` while something still holds an export of it, like
+        # a `memoryview` or a numpy array made from it, before the pointer is given up, so that the
+        # release can be retried. Releasing it again is a no-op.
+        self.view.release()
+        # Popping the handle is atomic, so that only one thread frees it, like for objects.
+        handle = self.__dict__.pop("_handle", None)
+        if handle is not None:
+            rust_call(_UniFFILib.{{ ci.ffi_shared_slice_free().name() }}, handle)
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc_info):
+        self.release()
+
+    def __len__(self):
+        return len(self.view)
+
+    def __repr__(self):
+        handle = getattr(self, "_handle", None)
+        return "<SharedSlice len={} handle={}>".format(
+            len(self.view), "None" if handle is None else hex(handle)
+        )
+
+    def __reduce__(self):
+        raise TypeError("SharedSlice wraps Rust memory and can't be pickled")
+
+    @classmethod
+    def _make_instance_(cls, handle):
+        inst = cls.__new__(cls)
+        size = rust_call(_UniFFILib.{{ ci.ffi_shared_slice_len().name() }}, handle)
+        if size == 0:
+            inst.view = memoryview(b"")
+        else:
+            data = rust_call(_UniFFILib.{{ ci.ffi_shared_slice_data().name() }}, handle)
+            inst.view = memoryview((ctypes.c_uint8 * size).from_address(data)).cast("B").toreadonly()
+        inst._handle = handle
+        return inst
+
+
+class FfiConverterSharedSlice:
+    @classmethod
+    def read(cls, buf):
+        return cls.lift(buf.readU64())
+
+    @classmethod
+    def write(cls, value, buf):
+        raise TypeError("Shared slices can only be returned by Rust")
+
+    @staticmethod
+    def lift(value):
+        return SharedSlice._make_instance_(value)
+
+    @staticmethod
+    def lower(value):
+        return value._handle
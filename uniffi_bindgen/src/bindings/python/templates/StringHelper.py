@@ -1,7 +1,7 @@
 class FfiConverterString:
     @staticmethod
     def read(buf):
-        size = buf.readI32()
+        size = buf.readLength()
         if size < 0:
             raise InternalError("Unexpected negative string length")
         utf8Bytes = buf.read(size)
@@ -10,16 +10,16 @@ class FfiConverterString:
     @staticmethod
     def write(value, buf):
         utf8Bytes = value.encode("utf-8")
-        buf.writeI32(len(utf8Bytes))
+        buf.writeLength(len(utf8Bytes))
         buf.write(utf8Bytes)
 
     @staticmethod
     def lift(buf):
         with buf.consumeWithStream() as stream:
-            return stream.read(stream.remaining()).decode("utf-8")
+            return stream.read(stream.remaining()).decode("{% if ci.utf16_strings() %}utf-16-le{% else %}utf-8{% endif %}")
 
     @staticmethod
     def lower(value):
         with RustBuffer.allocWithBuilder() as builder:
-            builder.write(value.encode("utf-8"))
+            builder.write(value.encode("{% if ci.utf16_strings() %}utf-16-le{% else %}utf-8{% endif %}"))
             return builder.finalize()
@@ -1,13 +1,15 @@
 {%- match func.return_type() -%}
 {%- when Some with (return_type) %}
 
-def {{ func.name()|fn_name }}({%- call py::arg_list_decl(func) -%}):
+def {{ func.name()|fn_name }}({%- call py::arg_list_decl(func) -%}){% call py::return_type_hint(func) %}:
+    {{- func.docstring()|fn_docstring(func.arguments(), "    ") }}
     {%- call py::setup_args(func) %}
     return {{ return_type|lift_fn }}({% call py::to_ffi_call(func) %})
 
 {% when None %}
 
-def {{ func.name()|fn_name }}({%- call py::arg_list_decl(func) -%}):
+def {{ func.name()|fn_name }}({%- call py::arg_list_decl(func) -%}){% call py::return_type_hint(func) %}:
+    {{- func.docstring()|fn_docstring(func.arguments(), "    ") }}
     {%- call py::setup_args(func) %}
     {% call py::to_ffi_call(func) %}
 {% endmatch %}
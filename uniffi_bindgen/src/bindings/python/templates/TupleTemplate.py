@@ -0,0 +1,14 @@
+class {{ ffi_converter_name }}(FfiConverterRustBuffer):
+    @classmethod
+    def write(cls, value, buf):
+        {%- for element_type in element_types %}
+        {{ element_type|ffi_converter_name }}.write(value[{{ loop.index0 }}], buf)
+        {%- endfor %}
+
+    @classmethod
+    def read(cls, buf):
+        return (
+            {%- for element_type in element_types %}
+            {{ element_type|ffi_converter_name }}.read(buf),
+            {%- endfor %}
+        )
@@ -19,6 +19,9 @@
 {%- when Type::Boolean %}
 {%- include "BooleanHelper.py" %}
 
+{%- when Type::Char %}
+{%- include "CharHelper.py" %}
+
 {%- when Type::Int8 %}
 {%- include "Int8Helper.py" %}
 
@@ -61,7 +64,7 @@
 {%- when Type::Record(name) %}
 {%- include "RecordTemplate.py" %}
 
-{%- when Type::Object(name) %}
+{%- when Type::Object { name, imp } %}
 {%- include "ObjectTemplate.py" %}
 
 {%- when Type::Timestamp %}
@@ -70,22 +73,66 @@
 {%- when Type::Duration %}
 {%- include "DurationHelper.py" %}
 
+{%- when Type::OffsetDateTime %}
+{%- include "OffsetDateTimeHelper.py" %}
+
+{%- when Type::BigInt %}
+{%- include "BigIntHelper.py" %}
+
+{%- when Type::Decimal %}
+{%- include "DecimalHelper.py" %}
+
+{%- when Type::Url %}
+{%- include "UrlHelper.py" %}
+
+{%- when Type::Uuid %}
+{%- include "UuidHelper.py" %}
+
+{%- when Type::Json %}
+{%- include "JsonHelper.py" %}
+
+{%- when Type::SharedSlice %}
+{%- include "SharedSliceHelper.py" %}
+
 {%- when Type::Optional(inner_type) %}
 {%- include "OptionalTemplate.py" %}
 
 {%- when Type::Sequence(inner_type) %}
+{%- if python_config.memoryview_bytes() && type_|is_bytes %}
+{%- include "BytesTemplate.py" %}
+{%- else %}
 {%- include "SequenceTemplate.py" %}
+{%- endif %}
+
+{%- when Type::Set(inner_type) %}
+{%- include "SetTemplate.py" %}
 
 {%- when Type::Map(key_type, value_type) %}
 {%- include "MapTemplate.py" %}
 
+{%- when Type::Tuple(element_types) %}
+{%- include "TupleTemplate.py" %}
+
+{%- when Type::Result(ok_type, err_type) %}
+{%- include "ResultTemplate.py" %}
+
+{%- when Type::Weak(inner_type) %}
+{%- match inner_type.as_ref() %}
+{%- when Type::Object { name, imp } %}
+{%- include "WeakTemplate.py" %}
+{%- else %}
+{%- endmatch %}
+
+{%- when Type::Iterator(item_type) %}
+{%- include "IteratorTemplate.py" %}
+
 {%- when Type::CallbackInterface(id) %}
 {%- include "CallbackInterfaceTemplate.py" %}
 
 {%- when Type::Custom { name, builtin } %}
 {%- include "CustomType.py" %}
 
-{%- when Type::External { name, crate_name } %}
+{%- when Type::External { name, crate_name, kind } %}
 {%- include "ExternalTemplate.py" %}
 
 {%- else %}
@@ -1,8 +1,16 @@
 class FfiConverterUInt16(FfiConverterPrimitive):
     @staticmethod
     def read(buf):
+        {%- if ci.compact_encoding() %}
+        return buf.readVarint()
+        {%- else %}
         return buf.readU16()
+        {%- endif %}
 
     @staticmethod
     def write(value, buf):
+        {%- if ci.compact_encoding() %}
+        buf.writeVarint(value)
+        {%- else %}
         buf.writeU16(value)
+        {%- endif %}
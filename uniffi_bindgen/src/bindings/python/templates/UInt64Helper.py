@@ -1,8 +1,16 @@
 class FfiConverterUInt64(FfiConverterPrimitive):
     @staticmethod
     def read(buf):
+        {%- if ci.compact_encoding() %}
+        return buf.readVarint()
+        {%- else %}
         return buf.readU64()
+        {%- endif %}
 
     @staticmethod
     def write(value, buf):
+        {%- if ci.compact_encoding() %}
+        buf.writeVarint(value)
+        {%- else %}
         buf.writeU64(value)
+        {%- endif %}
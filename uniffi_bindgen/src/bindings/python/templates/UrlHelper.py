@@ -0,0 +1,17 @@
+# The Url type, which is a `str` in Python. Rust checks that it's a valid URL when lifting it.
+class FfiConverterUrl(FfiConverterRustBuffer):
+    @staticmethod
+    def read(buf):
+        size = buf.readLength()
+        if size < 0:
+            raise InternalError("Unexpected negative URL length")
+        return buf.read(size).decode("utf-8")
+
+    @staticmethod
+    def write(value, buf):
+        # Also accept the results of `urllib.parse.urlparse()` and `urlsplit()`.
+        if not isinstance(value, str):
+            value = value.geturl()
+        utf8Bytes = value.encode("utf-8")
+        buf.writeLength(len(utf8Bytes))
+        buf.write(utf8Bytes)
@@ -0,0 +1,10 @@
+{{- self.add_import("uuid") }}
+# The Uuid type, passed as its 16 bytes in big-endian order.
+class FfiConverterUuid(FfiConverterRustBuffer):
+    @staticmethod
+    def read(buf):
+        return uuid.UUID(bytes=buf.read(16))
+
+    @staticmethod
+    def write(value, buf):
+        buf.write(value.bytes)
@@ -0,0 +1,56 @@
+{%- let obj = ci.get_object_definition(name).unwrap() %}
+{%- let upgrade_type = obj.weak_upgrade_type() %}
+
+class {{ type_name }}(object):
+    """A weak reference to a `{{ name|class_name }}`, which doesn't keep it alive."""
+
+    def __del__(self):
+        self._free_()
+
+    def _free_(self):
+        # Popping the handle is atomic, so that only one thread frees it, like for objects.
+        handle = self.__dict__.pop("_handle", None)
+        if handle is not None:
+            rust_call(_UniFFILib.{{ obj.ffi_weak_free().name() }}, handle)
+
+    def __repr__(self):
+        handle = getattr(self, "_handle", None)
+        return "<{{ type_name }} handle={}>".format("None" if handle is None else hex(handle))
+
+    def __reduce__(self):
+        raise TypeError("{{ type_name }} wraps a Rust weak reference and can't be pickled")
+
+    @classmethod
+    def _make_instance_(cls, handle):
+        inst = cls.__new__(cls)
+        inst._handle = handle
+        return inst
+
+    def upgrade(self) -> {{ upgrade_type.borrow()|type_hint(python_config) }}:
+        """Get the referenced object, or `None` if it has already been dropped."""
+        return {{ upgrade_type.borrow()|lift_fn }}(
+            rust_call(_UniFFILib.{{ obj.ffi_weak_upgrade().name() }}, self._handle)
+        )
+
+
+class {{ ffi_converter_name }}:
+    @classmethod
+    def read(cls, buf):
+        return cls.lift(buf.readU64())
+
+    @classmethod
+    def write(cls, value, buf):
+        if not isinstance(value, {{ type_name }}):
+            raise TypeError("Expected {{ type_name }} instance, {} found".format(value.__class__.__name__))
+        buf.writeClonedHandle(
+            rust_call(_UniFFILib.{{ obj.ffi_weak_clone().name() }}, cls.lower(value)),
+            _UniFFILib.{{ obj.ffi_weak_free().name() }},
+        )
+
+    @staticmethod
+    def lift(value):
+        return {{ type_name }}._make_instance_(value)
+
+    @staticmethod
+    def lower(value):
+        return value._handle
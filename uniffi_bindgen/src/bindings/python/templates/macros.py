@@ -39,12 +39,12 @@ rust_call(
 
 {#-
 // Arglist as used in Python declarations of methods, functions and constructors.
-// Note the var_name and type_name filters.
+// Note the var_name and arg_type_hint filters.
 -#}
 
 {% macro arg_list_decl(func) %}
     {%- for arg in func.arguments() -%}
-        {{ arg.name()|var_name }}
+        {{ arg.name()|var_name }}: {{ arg.type_()|arg_type_hint }}
         {%- match arg.default_value() %}
         {%- when Some with(literal) %} = DEFAULT
         {%- else %}
@@ -53,6 +53,16 @@ rust_call(
     {%- endfor %}
 {%- endmacro %}
 
+{#-
+// The return type annotation of a method or function.
+-#}
+{%- macro return_type_hint(func) %}
+    {%- match func.return_type() %}
+    {%- when Some with (return_type) %} -> {{ return_type|type_hint(python_config) }}
+    {%- else %} -> None
+    {%- endmatch %}
+{%- endmacro %}
+
 {#-
 // Arglist as used in the _UniFFILib function declations.
 // Note unfiltered name but ffi_type_name filters.
@@ -72,12 +82,12 @@ rust_call(
     {%- for arg in func.arguments() %}
     {%- match arg.default_value() %}
     {%- when None %}
-    {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow()) -}}
+    {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow(), python_config) -}}
     {%- when Some with(literal) %}
     if {{ arg.name()|var_name }} is DEFAULT:
         {{ arg.name()|var_name }} = {{ literal|literal_py(arg.type_().borrow()) }}
     else:
-        {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow()) -}}
+        {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow(), python_config) -}}
     {%- endmatch %}
     {% endfor -%}
 {%- endmacro -%}
@@ -90,12 +100,12 @@ rust_call(
         {%- for arg in func.arguments() %}
         {%- match arg.default_value() %}
         {%- when None %}
-        {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow()) -}}
+        {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow(), python_config) -}}
         {%- when Some with(literal) %}
         if {{ arg.name()|var_name }} is DEFAULT:
             {{ arg.name()|var_name }} = {{ literal|literal_py(arg.type_().borrow()) }}
         else:
-            {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow()) -}}
+            {{ arg.name()|var_name }} = {{ arg.name()|var_name|coerce_py(arg.type_().borrow(), python_config) -}}
         {%- endmatch %}
         {% endfor -%}
 {%- endmacro -%}
@@ -17,6 +17,12 @@
 # compile the rust component. The easiest way to ensure this is to bundle the Python
 # helpers directly inline like we're doing here.
 
+"""Python bindings for the `{{ ci.namespace() }}` Rust component."""
+
+# Type annotations refer to classes which may be defined further down the file.
+from __future__ import annotations
+
+import atexit
 import os
 import sys
 import ctypes
@@ -24,6 +30,7 @@ import enum
 import struct
 import contextlib
 import datetime
+import typing
 {%- for module_name in self.imports() %}
 import {{ module_name }}
 {%- endfor %}
@@ -41,13 +48,66 @@ DEFAULT = object()
 
 # Public interface members begin here.
 {{ type_helper_code }}
+{%- for constant in ci.constant_definitions() %}
+
+{{ constant.name()|const_name }} = {{ constant.value()|literal_py(constant) }}
+{%- endfor %}
 
 {%- for func in ci.function_definitions() %}
 {%- include "TopLevelFunctionTemplate.py" %}
 {%- endfor %}
 
+{%- if python_config.log_forwarder() %}
+{% include "LogForwarderTemplate.py" %}
+{%- endif %}
+
+def dump_live_allocations() -> str:
+    """Describe the objects and buffers of this component which weren't freed yet.
+
+    Each of them comes with the backtrace of its creation, when the Rust library was built with
+    the `leak-detection` feature of uniffi.
+    """
+    buf = rust_call(_UniFFILib.{{ ci.ffi_dump_live_allocations().name() }})
+    try:
+        return ctypes.string_at(buf.data, buf.len).decode("{% if ci.utf16_strings() %}utf-16-le{% else %}utf-8{% endif %}")
+    finally:
+        buf.free()
+
+class RustBufferStats(typing.NamedTuple):
+    """The counters of the RustBuffers of the Rust library, see `rustbuffer_stats()`."""
+    # The number of buffers allocated and not freed yet.
+    live_buffers: int
+    # The total capacity of the live buffers, in bytes.
+    live_bytes: int
+    # The highest total capacity of the live buffers so far, in bytes.
+    peak_bytes: int
+    # The number of buffers which weren't allocated because of the limits.
+    rejected: int
+
+def rustbuffer_stats() -> RustBufferStats:
+    """Get the counters of the RustBuffers of the Rust library, which may set limits on them."""
+    with rust_call(_UniFFILib.{{ ci.ffi_rustbuffer_stats().name() }}).consumeWithStream() as stream:
+        return RustBufferStats(*(stream.readU64() for _ in RustBufferStats._fields))
+{%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+
+# Run the `#[uniffi::init]` hooks of the library now that the module is loaded, and register its
+# `#[uniffi::shutdown]` hooks to run when the process exits. These look up the library when they
+# run, which is the one of the last load of the module if it was reloaded.
+{%- for func in ci.init_hooks() %}
+rust_call(_UniFFILib.{{ func.name() }})
+{%- endfor %}
+{%- for func in ci.shutdown_hooks() %}
+atexit.register(lambda: rust_call(_UniFFILib.{{ func.name() }}))
+{%- endfor %}
+{%- endif %}
+
 __all__ = [
     "InternalError",
+    "PayloadTooLargeError",
+    "RustBufferStats",
+    {%- if ci.uses_shared_slices() %}
+    "SharedSlice",
+    {%- endif %}
     {%- for e in ci.enum_definitions() %}
     "{{ e|type_name }}",
     {%- endfor %}
@@ -57,6 +117,9 @@ __all__ = [
     {%- for func in ci.function_definitions() %}
     "{{ func.name()|fn_name }}",
     {%- endfor %}
+    {%- for constant in ci.constant_definitions() %}
+    "{{ constant.name()|const_name }}",
+    {%- endfor %}
     {%- for obj in ci.object_definitions() %}
     "{{ obj|type_name }}",
     {%- endfor %}
@@ -66,6 +129,15 @@ __all__ = [
     {%- for c in ci.callback_interface_definitions() %}
     "{{ c.name()|class_name }}",
     {%- endfor %}
+    {%- if ci.contains_result_types() %}
+    "Ok",
+    "Err",
+    {%- endif %}
+    {%- if python_config.log_forwarder() %}
+    "install_log_forwarder",
+    {%- endif %}
+    "dump_live_allocations",
+    "rustbuffer_stats",
 ]
 
 {% import "macros.py" as py %}
@@ -55,8 +55,18 @@ fn generate_sources(
             vec!["python"],
             Some(out_dir),
             Some(library_path),
+            None,
             false,
         )?;
+
+        // Modules in a namespace package load the library from the package directory.
+        let ci = crate::parse_udl(&source.udl_path)?;
+        let crate_root = crate::guess_crate_root(&source.udl_path)?;
+        let config = crate::get_config(&ci, Some(crate_root), source.config_path.as_deref())?;
+        let module_dir = super::module_dir(&config.bindings.python, out_dir);
+        if module_dir != out_dir {
+            test_helper.copy_cdylibs_to_out_dir(&module_dir)?;
+        }
     }
     Ok(())
 }
@@ -0,0 +1,230 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Package a component as a Ruby gem.
+//!
+//! The output is a gem using the standard layout:
+//!
+//! ```text
+//! {out_dir}/
+//!     {namespace}.gemspec
+//!     lib/{namespace}.rb
+//!     lib/{namespace}/{namespace}.rb
+//!     lib/{namespace}/native.rb
+//!     lib/{namespace}/native/{arch}-{os}/{cdylib file}
+//! ```
+//!
+//! `lib/{namespace}.rb` is the entry point of the gem, which requires the generated bindings.
+//! Those load the library through `native.rb`, from the directory of the platform Ruby runs on, so
+//! that the libraries built for several platforms can be shipped in the same gem.
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use camino::Utf8Path;
+use fs_err as fs;
+use heck::ToUpperCamelCase;
+
+use super::{write_bindings, Config};
+use crate::bindings::cdylib::{build_cdylib, crate_package};
+use crate::interface::ComponentInterface;
+
+pub fn write_ruby_gem(
+    config: &Config,
+    ci: &ComponentInterface,
+    crate_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    release: bool,
+    build_gem: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let namespace = ci.namespace();
+    let module_name = namespace.to_upper_camel_case();
+    let lib_dir = out_dir.join("lib");
+    let gem_dir = lib_dir.join(namespace);
+    let native_dir = gem_dir.join("native").join(host_platform());
+    fs::create_dir_all(&native_dir)?;
+
+    let cdylib = build_cdylib(crate_root, None, release)?;
+    fs::copy(&cdylib, native_dir.join(library_file_name(config)))?;
+
+    // `cdylib_path` is a Ruby expression, evaluated when the bindings are loaded.
+    let config = config.with_cdylib_path(format!("{module_name}.native_library_path"));
+    write_bindings(&config, ci, &gem_dir, try_format_code)?;
+    fs::write(gem_dir.join("native.rb"), native_rb(&config, &module_name))?;
+    fs::write(
+        lib_dir.join(format!("{namespace}.rb")),
+        format!(
+            "# frozen_string_literal: true\n\n\
+             require_relative '{namespace}/native'\n\
+             require_relative '{namespace}/{namespace}'\n"
+        ),
+    )?;
+    // The gem is versioned like the crate.
+    fs::write(
+        out_dir.join(format!("{namespace}.gemspec")),
+        gemspec(&config, ci, &crate_package(crate_root)?)?,
+    )?;
+
+    if build_gem {
+        let status = Command::new("gem")
+            .current_dir(out_dir)
+            .arg("build")
+            .arg(format!("{namespace}.gemspec"))
+            .status()
+            .context("Failed to run gem")?;
+        if !status.success() {
+            bail!("Failed to build the gem");
+        }
+    }
+    Ok(())
+}
+
+/// The name of the directory holding the library built for the host, which matches
+/// `"#{FFI::Platform::ARCH}-#{FFI::Platform::OS}"` in Ruby.
+fn host_platform() -> String {
+    let arch = match std::env::consts::ARCH {
+        "x86" => "i386",
+        arch => arch,
+    };
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    format!("{arch}-{os}")
+}
+
+/// The file name of the library on the host, which matches `FFI.map_library_name` in Ruby.
+fn library_file_name(config: &Config) -> String {
+    format!(
+        "{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        config.cdylib_name(),
+        std::env::consts::DLL_SUFFIX
+    )
+}
+
+fn native_rb(config: &Config, module_name: &str) -> String {
+    let cdylib_name = config.cdylib_name();
+    format!(
+        r##"# frozen_string_literal: true
+
+require 'ffi'
+
+module {module_name}
+  # The path of the library built for the platform Ruby runs on.
+  def self.native_library_path
+    platform = "#{{FFI::Platform::ARCH}}-#{{FFI::Platform::OS}}"
+    path = File.join(__dir__, 'native', platform, FFI.map_library_name('{cdylib_name}'))
+    raise LoadError, "The {module_name} gem has no library for #{{platform}}" unless File.exist?(path)
+
+    path
+  end
+end
+"##
+    )
+}
+
+fn gemspec(config: &Config, ci: &ComponentInterface, package: &toml::Value) -> Result<String> {
+    let version = package
+        .get("version")
+        .and_then(|version| version.as_str())
+        .context("The crate has no `package.version`")?;
+    let summary = package
+        .get("description")
+        .and_then(|description| description.as_str())
+        .map(str::to_owned)
+        .unwrap_or_else(|| format!("Ruby bindings for the {} Rust component", ci.namespace()));
+    let authors = package
+        .get("authors")
+        .and_then(|authors| authors.as_array())
+        .filter(|authors| !authors.is_empty())
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|author| author.as_str())
+                .map(ruby_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_else(|| ruby_string(&format!("The {} authors", ci.namespace())));
+    let license = match package.get("license").and_then(|license| license.as_str()) {
+        Some(license) => format!("\n  s.license = {}", ruby_string(license)),
+        None => String::new(),
+    };
+    // Calling the functions of a library from Ractors needs FFI 1.16.
+    let ffi_version = if config.ractor_safe() { "1.16" } else { "1.15" };
+    Ok(format!(
+        r#"# frozen_string_literal: true
+
+Gem::Specification.new do |s|
+  s.name = {name}
+  s.version = {version}
+  s.summary = {summary}
+  s.authors = [{authors}]{license}
+  s.files = Dir['lib/**/*']
+  s.require_paths = ['lib']
+  s.required_ruby_version = '>= 2.6'
+  s.add_dependency 'ffi', '~> {ffi_version}'
+end
+"#,
+        name = ruby_string(ci.namespace()),
+        version = ruby_string(&gem_version(version)),
+        summary = ruby_string(&summary),
+    ))
+}
+
+// Gem versions mark pre-releases with letters rather than a `-`, and have no build metadata.
+fn gem_version(version: &str) -> String {
+    let version = version.split('+').next().unwrap_or(version);
+    version.replacen('-', ".pre.", 1)
+}
+
+fn ruby_string(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gem_version() {
+        assert_eq!(gem_version("1.2.3"), "1.2.3");
+        assert_eq!(gem_version("1.2.3-alpha.1"), "1.2.3.pre.alpha.1");
+        assert_eq!(gem_version("1.2.3+build.5"), "1.2.3");
+    }
+
+    #[test]
+    fn test_gemspec() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let package: toml::Value = toml::from_str(
+            r#"
+            version = "0.1.0-beta"
+            authors = ["Jane O'Doe"]
+            license = "MPL-2.0"
+            "#,
+        )
+        .unwrap();
+        let gemspec = gemspec(&Config::from(&ci), &ci, &package).unwrap();
+        assert!(gemspec.contains("s.name = 'test'"));
+        assert!(gemspec.contains("s.version = '0.1.0.pre.beta'"));
+        assert!(gemspec.contains("s.summary = 'Ruby bindings for the test Rust component'"));
+        assert!(gemspec.contains(r"s.authors = ['Jane O\'Doe']"));
+        assert!(gemspec.contains("s.license = 'MPL-2.0'"));
+        assert!(gemspec.contains("s.add_dependency 'ffi', '~> 1.15'"));
+
+        let config: Config = toml::from_str("ractor_safe = true").unwrap();
+        let ractor_gemspec = super::gemspec(&config, &ci, &package).unwrap();
+        assert!(ractor_gemspec.contains("s.add_dependency 'ffi', '~> 1.16'"));
+    }
+
+    #[test]
+    fn test_native_rb() {
+        let ci = ComponentInterface::from_webidl("namespace test {};").unwrap();
+        let native_rb = native_rb(&Config::from(&ci), "Test");
+        assert!(native_rb.contains("module Test"));
+        assert!(native_rb.contains("FFI.map_library_name('uniffi_test')"));
+    }
+}
@@ -7,21 +7,20 @@ use askama::Template;
 use heck::{ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap};
 
+use crate::bindings::docs::split_arguments_section;
+use crate::bindings::keywords::{self, KeywordConfig, KeywordStrategy};
 use crate::interface::*;
 use crate::MergeWith;
 
 const RESERVED_WORDS: &[&str] = &[
     "alias", "and", "BEGIN", "begin", "break", "case", "class", "def", "defined?", "do", "else",
-    "elsif", "END", "end", "ensure", "false", "for", "if", "module", "next", "nil", "not", "or",
-    "redo", "rescue", "retry", "return", "self", "super", "then", "true", "undef", "unles",
+    "elsif", "END", "end", "ensure", "false", "for", "if", "in", "module", "next", "nil", "not",
+    "or", "redo", "rescue", "retry", "return", "self", "super", "then", "true", "undef", "unless",
     "until", "when", "while", "yield", "__FILE__", "__LINE__",
 ];
 
-fn is_reserved_word(word: &str) -> bool {
-    RESERVED_WORDS.contains(&word)
-}
-
 // Some config options for it the caller wants to customize the generated ruby.
 // Note that this can only be used to control details of the ruby *that do not affect the underlying component*,
 // since the details of the underlying component are entirely determined by the `ComponentInterface`.
@@ -29,6 +28,13 @@ fn is_reserved_word(word: &str) -> bool {
 pub struct Config {
     cdylib_name: Option<String>,
     cdylib_path: Option<String>,
+    async_variants: Option<bool>,
+    keyword_arguments: Option<bool>,
+    ractor_safe: Option<bool>,
+    #[serde(default)]
+    external_packages: HashMap<String, String>,
+    #[serde(default)]
+    keywords: KeywordConfig,
 }
 
 impl Config {
@@ -38,6 +44,14 @@ impl Config {
             .unwrap_or_else(|| "uniffi".to_string())
     }
 
+    /// A copy of this config, loading the library `cdylib_name`.
+    pub(crate) fn with_cdylib_name(&self, cdylib_name: &str) -> Self {
+        Self {
+            cdylib_name: Some(cdylib_name.to_string()),
+            ..self.clone()
+        }
+    }
+
     pub fn custom_cdylib_path(&self) -> bool {
         self.cdylib_path.is_some()
     }
@@ -45,6 +59,52 @@ impl Config {
     pub fn cdylib_path(&self) -> String {
         self.cdylib_path.clone().unwrap_or_default()
     }
+
+    /// A copy of this config, loading the library from the Ruby expression `cdylib_path`.
+    pub(super) fn with_cdylib_path(&self, cdylib_path: String) -> Self {
+        Self {
+            cdylib_path: Some(cdylib_path),
+            ..self.clone()
+        }
+    }
+
+    /// How the variables colliding with a reserved word are renamed.
+    pub(crate) fn keywords(&self) -> &KeywordConfig {
+        &self.keywords
+    }
+
+    pub fn async_variants(&self) -> bool {
+        self.async_variants.unwrap_or(false)
+    }
+
+    pub fn keyword_arguments(&self) -> bool {
+        self.keyword_arguments.unwrap_or(false)
+    }
+
+    pub fn ractor_safe(&self) -> bool {
+        self.ractor_safe.unwrap_or(false)
+    }
+
+    /// The path to `require` for the bindings of the crate `crate_name`, which define the types
+    /// this component uses as external types. It's the crate name, with `-` replaced by `_`,
+    /// unless configured otherwise in `external_packages`.
+    pub fn external_require_path(&self, crate_name: &str) -> String {
+        match self.external_packages.get(crate_name) {
+            Some(path) => path.clone(),
+            None => crate_name.replace('-', "_"),
+        }
+    }
+
+    /// The module defined by the bindings of the crate `crate_name`, which is named after the file
+    /// they're in, like the module of these bindings.
+    pub fn external_module_name(&self, crate_name: &str) -> String {
+        let path = self.external_require_path(crate_name);
+        let file_name = path.rsplit('/').next().unwrap_or(&path);
+        file_name
+            .strip_suffix(".rb")
+            .unwrap_or(file_name)
+            .to_upper_camel_case()
+    }
 }
 
 impl From<&ComponentInterface> for Config {
@@ -52,6 +112,11 @@ impl From<&ComponentInterface> for Config {
         Config {
             cdylib_name: Some(format!("uniffi_{}", ci.namespace())),
             cdylib_path: None,
+            async_variants: None,
+            keyword_arguments: None,
+            ractor_safe: None,
+            external_packages: HashMap::new(),
+            keywords: KeywordConfig::default(),
         }
     }
 }
@@ -61,10 +126,35 @@ impl MergeWith for Config {
         Config {
             cdylib_name: self.cdylib_name.merge_with(&other.cdylib_name),
             cdylib_path: self.cdylib_path.merge_with(&other.cdylib_path),
+            async_variants: self.async_variants.merge_with(&other.async_variants),
+            keyword_arguments: self.keyword_arguments.merge_with(&other.keyword_arguments),
+            ractor_safe: self.ractor_safe.merge_with(&other.ractor_safe),
+            external_packages: self.external_packages.merge_with(&other.external_packages),
+            keywords: self.keywords.merge_with(&other.keywords),
         }
     }
 }
 
+// Render YARD documentation, which goes right before a `def` or `class` statement indented with
+// `indent`, so every line but the first is indented, and the statement goes on a new line.
+//
+// The documentation written for rustdoc is Markdown, which YARD renders with `--markup markdown`.
+// It's followed by YARD tags, such as `@param` for the arguments of a method.
+fn render_doc_comment(body: &str, tags: &[String], indent: &str) -> String {
+    let mut lines: Vec<&str> = body.lines().collect();
+    if !lines.is_empty() && !tags.is_empty() {
+        lines.push("");
+    }
+    lines.extend(tags.iter().map(String::as_str));
+    lines
+        .iter()
+        .map(|line| match line.trim_end() {
+            "" => format!("#\n{indent}"),
+            line => format!("# {line}\n{indent}"),
+        })
+        .collect()
+}
+
 #[derive(Template)]
 #[template(syntax = "rb", escape = "none", path = "wrapper.rb")]
 pub struct RubyWrapper<'a> {
@@ -80,6 +170,243 @@ impl<'a> RubyWrapper<'a> {
 mod filters {
     use super::*;
 
+    /// The functions, methods and constructors, which are documented alike.
+    pub trait Callable {
+        fn docstring(&self) -> Option<&str>;
+        fn arguments(&self) -> Vec<&Argument>;
+        fn return_type(&self) -> Option<&Type>;
+        fn throws_type(&self) -> Option<Type>;
+    }
+
+    impl Callable for Function {
+        fn docstring(&self) -> Option<&str> {
+            Function::docstring(self)
+        }
+        fn arguments(&self) -> Vec<&Argument> {
+            Function::arguments(self)
+        }
+        fn return_type(&self) -> Option<&Type> {
+            Function::return_type(self)
+        }
+        fn throws_type(&self) -> Option<Type> {
+            Function::throws_type(self)
+        }
+    }
+
+    impl Callable for Method {
+        fn docstring(&self) -> Option<&str> {
+            Method::docstring(self)
+        }
+        fn arguments(&self) -> Vec<&Argument> {
+            Method::arguments(self)
+        }
+        fn return_type(&self) -> Option<&Type> {
+            Method::return_type(self)
+        }
+        fn throws_type(&self) -> Option<Type> {
+            Method::throws_type(self)
+        }
+    }
+
+    // Constructors have no documentation of their own, and their return type is the class.
+    impl Callable for Constructor {
+        fn docstring(&self) -> Option<&str> {
+            None
+        }
+        fn arguments(&self) -> Vec<&Argument> {
+            Constructor::arguments(self)
+        }
+        fn return_type(&self) -> Option<&Type> {
+            None
+        }
+        fn throws_type(&self) -> Option<Type> {
+            Constructor::throws_type(self)
+        }
+    }
+
+    impl<T: Callable> Callable for &T {
+        fn docstring(&self) -> Option<&str> {
+            (*self).docstring()
+        }
+        fn arguments(&self) -> Vec<&Argument> {
+            (*self).arguments()
+        }
+        fn return_type(&self) -> Option<&Type> {
+            (*self).return_type()
+        }
+        fn throws_type(&self) -> Option<Type> {
+            (*self).throws_type()
+        }
+    }
+
+    /// The crates defining the types used as external types, each of them once.
+    pub fn external_crate_names(ci: &ComponentInterface) -> Result<Vec<String>, askama::Error> {
+        Ok(ci
+            .iter_external_types()
+            .map(|(_, crate_name, _)| crate_name.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect())
+    }
+
+    /// The Ruby class of the errors raised by a function throwing `type_`, which is the class
+    /// defined by the module of another component for an external error.
+    pub fn error_class_rb(type_: &Type, config: &Config) -> Result<String, askama::Error> {
+        Ok(match type_ {
+            Type::External {
+                name, crate_name, ..
+            } => format!(
+                "{}::{}",
+                config.external_module_name(crate_name),
+                class_name_rb(name)?
+            ),
+            Type::Error(name) => class_name_rb(name)?,
+            _ => unreachable!("Type `{type_:?}` can't be thrown"),
+        })
+    }
+
+    /// Whether a type is a `sequence<u8>`, which is passed as its raw bytes when it isn't inside
+    /// another value.
+    pub fn is_bytes(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::UInt8))
+    }
+
+    /// Whether a type is a `sequence<string>`, whose strings share their length prefix with the
+    /// compact encoding.
+    pub fn is_strings(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::String))
+    }
+
+    /// The YARD name of a type, as used in the `[...]` of its tags.
+    pub fn yard_type(type_: &Type) -> Result<String, askama::Error> {
+        Ok(match type_ {
+            Type::Int8
+            | Type::UInt8
+            | Type::Int16
+            | Type::UInt16
+            | Type::Int32
+            | Type::UInt32
+            | Type::Int64
+            | Type::UInt64
+            | Type::BigInt => "Integer".into(),
+            Type::Float32 | Type::Float64 => "Float".into(),
+            Type::Boolean => "Boolean".into(),
+            Type::String | Type::Char | Type::Url | Type::Uuid => "String".into(),
+            Type::Timestamp | Type::Duration | Type::OffsetDateTime => "Time".into(),
+            Type::Decimal => "BigDecimal".into(),
+            Type::Json => "Object".into(),
+            Type::Object { name, .. }
+            | Type::Record(name)
+            | Type::Enum(name)
+            | Type::Error(name)
+            | Type::CallbackInterface(name)
+            | Type::External { name, .. }
+            | Type::Custom { name, .. } => class_name_rb(name)?,
+            Type::Optional(inner) => format!("{}, nil", yard_type(inner)?),
+            Type::Sequence(inner) => format!("Array<{}>", yard_type(inner)?),
+            Type::Set(inner) => format!("Set<{}>", yard_type(inner)?),
+            Type::Map(key, value) => {
+                format!("Hash{{{} => {}}}", yard_type(key)?, yard_type(value)?)
+            }
+            Type::Tuple(elements) => format!(
+                "Array({})",
+                elements
+                    .iter()
+                    .map(yard_type)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ")
+            ),
+            // Results, weak references and iterators aren't supported yet, so they have no Ruby class.
+            Type::Result(_, _) | Type::Weak(_) | Type::Iterator(_) | Type::SharedSlice => {
+                "Object".into()
+            }
+            Type::Unresolved { name } => {
+                unreachable!("Type `{name}` must be resolved before calling yard_type")
+            }
+        })
+    }
+
+    /// Render the Rust documentation of a class or constant as YARD documentation, to go right
+    /// before its statement, indented with `indent`. Items without documentation render nothing.
+    pub fn doc_comment<'a>(
+        docstring: impl Borrow<Option<&'a str>>,
+        indent: &str,
+    ) -> Result<String, askama::Error> {
+        Ok(render_doc_comment(
+            docstring.borrow().unwrap_or_default(),
+            &[],
+            indent,
+        ))
+    }
+
+    /// Like [`doc_comment`], for a function, method or constructor, with tags for the types of its
+    /// arguments, its return value and its errors. The descriptions of the arguments are taken
+    /// from the `# Arguments` section of the Rust documentation, if there is one.
+    pub fn yard_doc<T: Callable>(
+        func: &T,
+        ci: &ComponentInterface,
+        indent: &str,
+    ) -> Result<String, askama::Error> {
+        let (body, mut descriptions) =
+            split_arguments_section(func.docstring().unwrap_or_default());
+        let mut tags = Vec::new();
+        for arg in func.arguments() {
+            let description = descriptions.remove(arg.name()).unwrap_or_default();
+            tags.push(
+                format!(
+                    "@param {} [{}] {description}",
+                    var_name_rb(arg.name())?,
+                    yard_type(arg.type_())?
+                )
+                .trim_end()
+                .to_owned(),
+            );
+        }
+        if let Some(return_type) = func.return_type() {
+            tags.push(format!("@return [{}]", yard_type(return_type)?));
+        }
+        if let Some(Type::Error(name)) = func.throws_type() {
+            let error_name = class_name_rb(&name)?;
+            let variants = match ci.get_error_definition(&name) {
+                Some(e) => e
+                    .variants()
+                    .iter()
+                    .map(|variant| Ok(format!("{error_name}::{}", class_name_rb(variant.name())?)))
+                    .collect::<Result<Vec<_>, askama::Error>>()?,
+                None => vec![error_name],
+            };
+            tags.push(format!("@raise [{}]", variants.join(", ")));
+        }
+        Ok(render_doc_comment(&body, &tags, indent))
+    }
+
+    /// YARD documentation for the initializer of a record, with a tag for the type of each field.
+    pub fn fields_yard_doc(fields: &[Field], indent: &str) -> Result<String, askama::Error> {
+        let tags = fields
+            .iter()
+            .map(|field| {
+                Ok(format!(
+                    "@param {} [{}] {}",
+                    var_name_rb(field.name())?,
+                    yard_type(field.type_())?,
+                    field.docstring().unwrap_or_default().replace('\n', " ")
+                )
+                .trim_end()
+                .to_owned())
+            })
+            .collect::<Result<Vec<_>, askama::Error>>()?;
+        Ok(render_doc_comment("", &tags, indent))
+    }
+
+    /// YARD documentation for the reader of a field, with the documentation of the field.
+    pub fn field_yard_doc(field: &Field, indent: &str) -> Result<String, askama::Error> {
+        Ok(render_doc_comment(
+            field.docstring().unwrap_or_default(),
+            &[format!("@return [{}]", yard_type(field.type_())?)],
+            indent,
+        ))
+    }
+
     pub fn type_ffi(type_: &FFIType) -> Result<String, askama::Error> {
         Ok(match type_ {
             FFIType::Int8 => ":int8".to_string(),
@@ -94,6 +421,7 @@ mod filters {
             FFIType::Float64 => ":double".to_string(),
             FFIType::RustArcPtr(_) => ":pointer".to_string(),
             FFIType::RustBuffer => "RustBuffer.by_value".to_string(),
+            FFIType::Struct(name) => format!("FfiStruct{}.by_value", class_name_rb(name)?),
             FFIType::ForeignBytes => "ForeignBytes".to_string(),
             FFIType::ForeignCallback => unimplemented!("Callback interfaces are not implemented"),
         })
@@ -113,10 +441,21 @@ mod filters {
             Literal::Null => "nil".into(),
             Literal::EmptySequence => "[]".into(),
             Literal::EmptyMap => "{}".into(),
+            Literal::Duration(secs, nanos) => {
+                format!("Time.at({secs}, {nanos}, :nanosecond, in: '+00:00').utc")
+            }
+            Literal::EmptyRecord(type_) => match type_ {
+                Type::Record(name) => format!("{}.new", class_name_rb(name)?),
+                _ => panic!("Unexpected type in record literal: {type_:?}"),
+            },
             Literal::Enum(v, type_) => match type_ {
                 Type::Enum(name) => format!("{}::{}", class_name_rb(name)?, enum_name_rb(v)?),
                 _ => panic!("Unexpected type in enum literal: {type_:?}"),
             },
+            Literal::EmptyVariant(v, type_) => match type_ {
+                Type::Enum(name) => format!("{}::{}.new", class_name_rb(name)?, enum_name_rb(v)?),
+                _ => panic!("Unexpected type in enum literal: {type_:?}"),
+            },
             // https://docs.ruby-lang.org/en/2.0.0/syntax/literals_rdoc.html
             Literal::Int(i, radix, _) => match radix {
                 Radix::Octal => format!("0o{i:o}"),
@@ -141,16 +480,22 @@ mod filters {
     }
 
     pub fn var_name_rb(nm: &str) -> Result<String, askama::Error> {
-        let nm = nm.to_string();
-        let prefix = if is_reserved_word(&nm) { "_" } else { "" };
-
-        Ok(format!("{prefix}{}", nm.to_snake_case()))
+        // Reserved words are prefixed with an underscore unless configured otherwise.
+        Ok(keywords::fixup(
+            nm.to_string().to_snake_case(),
+            RESERVED_WORDS,
+            Some(KeywordStrategy::Prefix),
+        ))
     }
 
     pub fn enum_name_rb(nm: &str) -> Result<String, askama::Error> {
         Ok(nm.to_string().to_shouty_snake_case())
     }
 
+    pub fn const_name_rb(nm: &str) -> Result<String, askama::Error> {
+        Ok(nm.to_string().to_shouty_snake_case())
+    }
+
     pub fn coerce_rb(nm: &str, type_: &Type) -> Result<String, askama::Error> {
         Ok(match type_ {
             Type::Int8
@@ -161,11 +506,18 @@ mod filters {
             | Type::UInt32
             | Type::Int64
             | Type::UInt64 => format!("{nm}.to_i"), // TODO: check max/min value
+            Type::BigInt => format!("{nm}.to_i"),
             Type::Float32 | Type::Float64 => format!("{nm}.to_f"),
             Type::Boolean => format!("{nm} ? true : false"),
-            Type::Object(_) | Type::Enum(_) | Type::Error(_) | Type::Record(_) => nm.to_string(),
-            Type::String => format!("{nm}.to_s"),
-            Type::Timestamp | Type::Duration => nm.to_string(),
+            Type::Object { .. }
+            | Type::Enum(_)
+            | Type::Error(_)
+            | Type::Record(_)
+            | Type::External { .. } => nm.to_string(),
+            Type::String | Type::Url | Type::Uuid => format!("{nm}.to_s"),
+            Type::Timestamp | Type::Duration | Type::OffsetDateTime => nm.to_string(),
+            Type::Decimal => format!("BigDecimal({nm}.to_s)"),
+            Type::Json => nm.to_string(),
             Type::CallbackInterface(_) => panic!("No support for coercing callback interfaces yet"),
             Type::Optional(t) => format!("({nm} ? {} : nil)", coerce_rb(nm, t)?),
             Type::Sequence(t) => {
@@ -176,8 +528,8 @@ mod filters {
                     format!("{nm}.map {{ |v| {coerce_code} }}")
                 }
             }
-            Type::Map(_k, t) => {
-                let k_coerce_code = coerce_rb("k", &Type::String)?;
+            Type::Map(k, t) => {
+                let k_coerce_code = coerce_rb("k", k)?;
                 let v_coerce_code = coerce_rb("v", t)?;
 
                 if k_coerce_code == "k" && v_coerce_code == "v" {
@@ -189,7 +541,13 @@ mod filters {
                     )
                 }
             }
-            Type::External { .. } => panic!("No support for external types, yet"),
+            Type::Tuple(_) => panic!("No support for tuples, yet"),
+            Type::Char => panic!("No support for chars, yet"),
+            Type::Set(_) => panic!("No support for sets, yet"),
+            Type::Result(_, _) => panic!("No support for results, yet"),
+            Type::Weak(_) => panic!("No support for weak references, yet"),
+            Type::Iterator(_) => panic!("No support for iterators, yet"),
+            Type::SharedSlice => panic!("No support for shared slices, yet"),
             Type::Custom { .. } => panic!("No support for custom types, yet"),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling coerce_rb")
@@ -211,21 +569,35 @@ mod filters {
             | Type::Float64 => nm.to_string(),
             Type::Boolean => format!("({nm} ? 1 : 0)"),
             Type::String => format!("RustBuffer.allocFromString({nm})"),
-            Type::Object(name) => format!("({}._uniffi_lower {nm})", class_name_rb(name)?),
+            Type::Object { name, .. } => format!("({}._uniffi_lower {nm})", class_name_rb(name)?),
+            // Records are lowered into a `RustBuffer` or into a struct, see `RecordTemplate.rb`.
+            Type::Record(name) => format!("({}._uniffi_lower {nm})", class_name_rb(name)?),
             Type::CallbackInterface(_) => panic!("No support for lowering callback interfaces yet"),
             Type::Error(_) => panic!("No support for lowering errors, yet"),
             Type::Enum(_)
-            | Type::Record(_)
             | Type::Optional(_)
             | Type::Sequence(_)
             | Type::Timestamp
             | Type::Duration
-            | Type::Map(_, _) => format!(
+            | Type::OffsetDateTime
+            | Type::BigInt
+            | Type::Decimal
+            | Type::Url
+            | Type::Uuid
+            | Type::Json
+            | Type::Map(_, _)
+            | Type::External { .. } => format!(
                 "RustBuffer.alloc_from_{}({})",
                 class_name_rb(&type_.canonical_name())?,
                 nm
             ),
-            Type::External { .. } => panic!("No support for lowering external types, yet"),
+            Type::Tuple(_) => panic!("No support for lowering tuples, yet"),
+            Type::Char => panic!("No support for lowering chars, yet"),
+            Type::Set(_) => panic!("No support for lowering sets, yet"),
+            Type::Result(_, _) => panic!("No support for lowering results, yet"),
+            Type::Weak(_) => panic!("No support for lowering weak references, yet"),
+            Type::Iterator(_) => panic!("No support for lowering iterators, yet"),
+            Type::SharedSlice => panic!("No support for lowering shared slices, yet"),
             Type::Custom { .. } => panic!("No support for lowering custom types, yet"),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling lower_rb")
@@ -246,21 +618,34 @@ mod filters {
             Type::Float32 | Type::Float64 => format!("{nm}.to_f"),
             Type::Boolean => format!("1 == {nm}"),
             Type::String => format!("{nm}.consumeIntoString"),
-            Type::Object(name) => format!("{}._uniffi_allocate({nm})", class_name_rb(name)?),
+            Type::Object { name, .. } => format!("{}._uniffi_allocate({nm})", class_name_rb(name)?),
+            Type::Record(name) => format!("{}._uniffi_lift({nm})", class_name_rb(name)?),
             Type::CallbackInterface(_) => panic!("No support for lifting callback interfaces, yet"),
             Type::Error(_) => panic!("No support for lowering errors, yet"),
             Type::Enum(_)
-            | Type::Record(_)
             | Type::Optional(_)
             | Type::Sequence(_)
             | Type::Timestamp
             | Type::Duration
-            | Type::Map(_, _) => format!(
+            | Type::OffsetDateTime
+            | Type::BigInt
+            | Type::Decimal
+            | Type::Url
+            | Type::Uuid
+            | Type::Json
+            | Type::Map(_, _)
+            | Type::External { .. } => format!(
                 "{}.consumeInto{}",
                 nm,
                 class_name_rb(&type_.canonical_name())?
             ),
-            Type::External { .. } => panic!("No support for lifting external types, yet"),
+            Type::Tuple(_) => panic!("No support for lifting tuples, yet"),
+            Type::Char => panic!("No support for lifting chars, yet"),
+            Type::Set(_) => panic!("No support for lifting sets, yet"),
+            Type::Result(_, _) => panic!("No support for lifting results, yet"),
+            Type::Weak(_) => panic!("No support for lifting weak references, yet"),
+            Type::Iterator(_) => panic!("No support for lifting iterators, yet"),
+            Type::SharedSlice => panic!("No support for lifting shared slices, yet"),
             Type::Custom { .. } => panic!("No support for lifting custom types, yet"),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling lift_rb")
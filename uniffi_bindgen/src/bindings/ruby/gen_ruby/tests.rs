@@ -1,13 +1,13 @@
-use super::{is_reserved_word, Config};
+use super::{filters::var_name_rb, Config};
 
 #[test]
 fn when_reserved_word() {
-    assert!(is_reserved_word("end"));
+    assert_eq!(var_name_rb("end").unwrap(), "_end");
 }
 
 #[test]
 fn when_not_reserved_word() {
-    assert!(!is_reserved_word("ruby"));
+    assert_eq!(var_name_rb("ruby").unwrap(), "ruby");
 }
 
 #[test]
@@ -15,6 +15,7 @@ fn cdylib_name() {
     let config = Config {
         cdylib_name: None,
         cdylib_path: None,
+        ..Config::default()
     };
 
     assert_eq!("uniffi", config.cdylib_name());
@@ -22,6 +23,7 @@ fn cdylib_name() {
     let config = Config {
         cdylib_name: Some("todolist".to_string()),
         cdylib_path: None,
+        ..Config::default()
     };
 
     assert_eq!("todolist", config.cdylib_name());
@@ -32,6 +34,7 @@ fn cdylib_path() {
     let config = Config {
         cdylib_name: None,
         cdylib_path: None,
+        ..Config::default()
     };
 
     assert_eq!("", config.cdylib_path());
@@ -40,8 +43,252 @@ fn cdylib_path() {
     let config = Config {
         cdylib_name: None,
         cdylib_path: Some("/foo/bar".to_string()),
+        ..Config::default()
     };
 
     assert_eq!("/foo/bar", config.cdylib_path());
     assert!(config.custom_cdylib_path());
 }
+
+#[test]
+fn async_variants() {
+    assert!(!Config::default().async_variants());
+
+    let config: Config = toml::from_str("async_variants = true").unwrap();
+    assert!(config.async_variants());
+}
+
+#[test]
+fn keyword_arguments() {
+    assert!(!Config::default().keyword_arguments());
+
+    let config: Config = toml::from_str("keyword_arguments = true").unwrap();
+    assert!(config.keyword_arguments());
+}
+
+#[test]
+fn keyword_arguments_rendering() {
+    use crate::interface::ComponentInterface;
+    use askama::Template;
+
+    let ci = ComponentInterface::from_webidl(
+        r#"
+        namespace test {
+            string greet(string name, optional string greeting = "hello");
+        };
+        dictionary Point {
+            i32 x;
+            i32 y = 0;
+        };
+        "#,
+    )
+    .unwrap();
+    let render = |config: &str| {
+        let config: Config = toml::from_str(config).unwrap();
+        super::RubyWrapper::new(config, &ci).render().unwrap()
+    };
+
+    let positional = render("");
+    assert!(positional.contains(r#"def self.greet(name, greeting = "hello")"#));
+    assert!(positional.contains("def initialize(x, y = 0)"));
+
+    let keyword = render("keyword_arguments = true\nasync_variants = true");
+    assert!(keyword.contains(r#"def self.greet(name:, greeting: "hello")"#));
+    assert!(keyword.contains("greet(name: name, greeting: greeting)"));
+    assert!(keyword.contains("def initialize(x:, y: 0)"));
+    assert!(keyword.contains("x: readI32"));
+}
+
+#[test]
+fn keyword_renaming() {
+    use crate::interface::ComponentInterface;
+
+    let ci = ComponentInterface::from_webidl(
+        r#"
+        namespace test {
+            u32 first(sequence<u32> in);
+        };
+        "#,
+    )
+    .unwrap();
+    let render = |config: &str| {
+        let config: Config = toml::from_str(config).unwrap();
+        crate::bindings::ruby::generate_ruby_bindings(&config, &ci).unwrap()
+    };
+
+    assert!(render("").contains("def self.first(_in)"));
+    let suffixed = render("[keywords]\nstrategy = \"suffix\"");
+    assert!(suffixed.contains("def self.first(in_)"));
+    let renamed = render("[keywords]\nrename = { in = \"numbers\" }");
+    assert!(renamed.contains("def self.first(numbers)"));
+}
+
+#[test]
+fn render_doc_comment() {
+    assert_eq!(super::render_doc_comment("", &[], "  "), "");
+    assert_eq!(
+        super::render_doc_comment("One line.", &[], "  "),
+        "# One line.\n  "
+    );
+    assert_eq!(
+        super::render_doc_comment(
+            "Summary.\n\nDetails.",
+            &["@param a [Integer] First.".into()],
+            "  "
+        ),
+        "# Summary.\n  #\n  # Details.\n  #\n  # @param a [Integer] First.\n  "
+    );
+}
+
+#[test]
+fn yard_rendering() {
+    use crate::interface::ComponentInterface;
+    use askama::Template;
+
+    let ci = ComponentInterface::from_webidl(
+        r#"
+        namespace test {
+            [Throws=Oops]
+            sequence<string> split(string text, optional u8? limit = null);
+        };
+        [Error]
+        enum Oops { "Empty", "TooLong" };
+        dictionary Point {
+            i32 x;
+            record<DOMString, f64> weights;
+        };
+        "#,
+    )
+    .unwrap();
+    let rendered = super::RubyWrapper::new(Config::default(), &ci)
+        .render()
+        .unwrap();
+    assert!(rendered.contains(
+        "# @param text [String]\n\
+         # @param limit [Integer, nil]\n\
+         # @return [Array<String>]\n\
+         # @raise [Oops::Empty, Oops::TooLong]\n\
+         def self.split(text, limit = nil)"
+    ));
+    assert!(rendered.contains("  # @return [Integer]\n  attr_reader :x"));
+    assert!(rendered.contains("  # @return [Hash{String => Float}]\n  attr_reader :weights"));
+    assert!(rendered.contains(
+        "  # @param x [Integer]\n  # @param weights [Hash{String => Float}]\n  def initialize(x, weights)"
+    ));
+}
+
+#[test]
+fn error_classes() {
+    use crate::interface::ComponentInterface;
+    use askama::Template;
+
+    let ci = ComponentInterface::from_webidl(
+        r#"
+        namespace test {
+            [Throws=Flat]
+            void flat();
+            [Throws=Complex]
+            void complex();
+        };
+        [Error]
+        enum Flat { "Empty" };
+        [Error]
+        interface Complex {
+            Os(i16 code);
+        };
+        "#,
+    )
+    .unwrap();
+    let rendered = super::RubyWrapper::new(Config::default(), &ci)
+        .render()
+        .unwrap();
+    // Every variant can be rescued as the error it belongs to.
+    assert!(rendered.contains(
+        "class Flat < StandardError\n  # The key/value context which the Rust code attached to the error, as a Hash.\n  attr_accessor :error_context\n  Empty = Class.new self\nend"
+    ));
+    assert!(rendered.contains("class Complex < StandardError"));
+    assert!(rendered.contains("class Os < Complex"));
+    assert!(rendered.contains("  # @return [Integer]\n    attr_reader :code"));
+}
+
+#[test]
+fn ractor_safe() {
+    use crate::interface::ComponentInterface;
+    use askama::Template;
+
+    assert!(!Config::default().ractor_safe());
+
+    let ci = ComponentInterface::from_webidl(
+        r#"
+        namespace test {};
+        interface Counter {
+            constructor();
+        };
+        "#,
+    )
+    .unwrap();
+    let render = |config: &str| {
+        let config: Config = toml::from_str(config).unwrap();
+        super::RubyWrapper::new(config, &ci).render().unwrap()
+    };
+
+    let default = render("");
+    assert!(!default.contains("Ractor.make_shareable"));
+    assert!(!default.contains("\n  freeze\nend"));
+
+    let ractor_safe = render("ractor_safe = true");
+    assert!(ractor_safe.contains("Ractor.make_shareable(Proc.new do |_id|"));
+    // The finalizers only capture the integer handles of the objects, which are shareable.
+    assert!(!ractor_safe.contains("FFI::Pointer"));
+    assert!(ractor_safe.contains("\n  freeze\nend"));
+}
+
+#[test]
+fn external_packages() {
+    let config = Config::default();
+    assert_eq!(config.external_require_path("uniffi-one"), "uniffi_one");
+    assert_eq!(config.external_module_name("uniffi-one"), "UniffiOne");
+
+    let config: Config = toml::from_str(
+        r#"
+        [external_packages]
+        uniffi-one = "ext_types/uniffi_one.rb"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.external_require_path("uniffi-one"),
+        "ext_types/uniffi_one.rb"
+    );
+    assert_eq!(config.external_module_name("uniffi-one"), "UniffiOne");
+}
+
+#[test]
+fn external_types_rendering() {
+    use crate::interface::ComponentInterface;
+    use askama::Template;
+
+    let ci = ComponentInterface::from_webidl(
+        r#"
+        namespace test {
+            UniffiOneType echo(UniffiOneType value);
+        };
+        [External="uniffi-one"]
+        typedef extern UniffiOneType;
+        dictionary Wrapper {
+            sequence<UniffiOneType> values;
+        };
+        "#,
+    )
+    .unwrap();
+    let rendered = super::RubyWrapper::new(Config::default(), &ci)
+        .render()
+        .unwrap();
+    assert!(rendered.contains("require 'uniffi_one'\n"));
+    assert!(rendered.contains("UniffiOne._uniffi_read_external(\n      :TypeUniffiOneType"));
+    assert!(rendered.contains("UniffiOne._uniffi_write_external(:TypeUniffiOneType, v)"));
+    assert!(rendered.contains("RustBuffer.alloc_from_TypeUniffiOneType(value)"));
+    assert!(rendered.contains(".consumeIntoTypeUniffiOneType"));
+    // Every component can be used by others.
+    assert!(rendered.contains("def self._uniffi_read_external(type_name, rbuf, offset)"));
+}
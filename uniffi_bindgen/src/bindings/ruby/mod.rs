@@ -8,8 +8,10 @@ use anyhow::{Context, Result};
 use camino::Utf8Path;
 use fs_err::File;
 
+mod gem;
 pub mod gen_ruby;
 mod test;
+pub use gem::write_ruby_gem;
 pub use gen_ruby::{Config, RubyWrapper};
 pub use test::run_test;
 
@@ -44,7 +46,8 @@ pub fn write_bindings(
 
 pub fn generate_ruby_bindings(config: &Config, ci: &ComponentInterface) -> Result<String> {
     use askama::Template;
-    RubyWrapper::new(config.clone(), ci)
-        .render()
-        .context("failed to render ruby bindings")
+    super::keywords::with_config(config.keywords(), || {
+        RubyWrapper::new(config.clone(), ci).render()
+    })
+    .context("failed to render ruby bindings")
 }
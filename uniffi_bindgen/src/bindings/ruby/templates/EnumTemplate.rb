@@ -1,21 +1,21 @@
 {% if e.is_flat() %}
 
-class {{ e.name()|class_name_rb }}
+{{ e.docstring()|doc_comment("") }}class {{ e.name()|class_name_rb }}
   {% for variant in e.variants() -%}
-  {{ variant.name()|enum_name_rb }} = {{ loop.index }}
+  {{ variant.docstring()|doc_comment("  ") }}{{ variant.name()|enum_name_rb }} = {{ loop.index }}
   {% endfor %}
 end
 
 {% else %}
 
-class {{ e.name()|class_name_rb }}
+{{ e.docstring()|doc_comment("") }}class {{ e.name()|class_name_rb }}
   def initialize
     raise RuntimeError, '{{ e.name()|class_name_rb }} cannot be instantiated directly'
   end
 
   # Each enum variant is a nested class of the enum itself.
   {% for variant in e.variants() -%}
-  class {{ variant.name()|enum_name_rb }}
+  {{ variant.docstring()|doc_comment("  ") }}class {{ variant.name()|enum_name_rb }}
     {% if variant.has_fields() %}
     attr_reader {% for field in variant.fields() %}:{{ field.name()|var_name_rb }}{% if loop.last %}{% else %}, {% endif %}{%- endfor %}
     {% endif %}
@@ -19,56 +19,129 @@ end
 CALL_SUCCESS = 0
 CALL_ERROR = 1
 CALL_PANIC = 2
+CALL_PAYLOAD_TOO_LARGE = 3
 {%- for e in ci.error_definitions() %}
-{% if e.is_flat() %}
-class {{ e.name()|class_name_rb }}
-    {%- for variant in e.variants() %}
-    {{ variant.name()|class_name_rb }} = Class.new StandardError
-    {%- endfor %}
-{% else %}
-module {{ e.name()|class_name_rb }}
+{%- let e_enum = e.wrapped_enum() %}
+
+{{ e_enum.docstring()|doc_comment("") }}class {{ e.name()|class_name_rb }} < StandardError
+  # The key/value context which the Rust code attached to the error, as a Hash.
+  attr_accessor :error_context
+{%- if e.is_flat() %}
+  {%- for variant in e.variants() %}
+  {{ variant.docstring()|doc_comment("  ") }}{{ variant.name()|class_name_rb }} = Class.new self
+  {%- endfor %}
+{%- else %}
   {%- for variant in e.variants() %}
-  class {{ variant.name()|class_name_rb }} < StandardError
+
+  {{ variant.docstring()|doc_comment("  ") }}class {{ variant.name()|class_name_rb }} < {{ e.name()|class_name_rb }}
     def initialize({% for field in variant.fields() %}{{ field.name()|var_name_rb }}{% if !loop.last %}, {% endif %}{% endfor %})
-        {%- for field in variant.fields() %}
-        @{{ field.name()|var_name_rb }} = {{ field.name()|var_name_rb }}
-        {%- endfor %}
-        super()
-      end
-    {%- if variant.has_fields() %}
+      {%- for field in variant.fields() %}
+      @{{ field.name()|var_name_rb }} = {{ field.name()|var_name_rb }}
+      {%- endfor %}
+      super()
+    end
+    {%- for field in variant.fields() %}
 
-    attr_reader {% for field in variant.fields() %}:{{ field.name()|var_name_rb }}{% if !loop.last %}, {% endif %}{% endfor %}
-    {% endif %}
+    {{ field|field_yard_doc("    ") }}attr_reader :{{ field.name()|var_name_rb }}
+    {%- endfor %}
 
     def to_s
-     "#{self.class.name}({% for field in variant.fields() %}{{ field.name()|var_name_rb }}=#{@{{ field.name()|var_name_rb }}.inspect}{% if !loop.last %}, {% endif %}{% endfor %})"
+      "#{self.class.name}({% for field in variant.fields() %}{{ field.name()|var_name_rb }}=#{@{{ field.name()|var_name_rb }}.inspect}{% if !loop.last %}, {% endif %}{% endfor %})"
+    end
+
+    def ==(other)
+      other.instance_of?(self.class)
+      {%- for field in variant.fields() %} &&
+        @{{ field.name()|var_name_rb }} == other.{{ field.name()|var_name_rb }}
+      {%- endfor %}
     end
   end
   {%- endfor %}
-{% endif %}
+{%- endif %}
 end
 {%- endfor %}
 
-# Map error modules to the RustBuffer method name that reads them
+# Map error classes to the RustBuffer method name that reads them
 ERROR_MODULE_TO_READER_METHOD = {
 {%- for e in ci.error_definitions() %}
 {%- let typ=ci.get_type(e.name()).unwrap() %}
 {%- let canonical_type_name = typ.canonical_name().borrow()|class_name_rb %}
   {{ e.name()|class_name_rb }} => :read{{ canonical_type_name }},
 {%- endfor %}
-}
+{%- for (name, crate_name, kind) in ci.iter_external_types() %}
+{%- if kind == ExternalKind::Error %}
+  {{ config.external_module_name(crate_name) }}::{{ name|class_name_rb }} => :readType{{ name }},
+{%- endif %}
+{%- endfor %}
+}.freeze
 
 private_constant :ERROR_MODULE_TO_READER_METHOD, :CALL_SUCCESS, :CALL_ERROR, :CALL_PANIC,
-                 :RustCallStatus
+                 :CALL_PAYLOAD_TOO_LARGE, :RustCallStatus
 
 def self.consume_buffer_into_error(error_module, rust_buffer)
   rust_buffer.consumeWithStream do |stream|
     reader_method = ERROR_MODULE_TO_READER_METHOD[error_module]
-    return stream.send(reader_method)
+    error = stream.send(reader_method)
+    error.error_context = read_error_context(stream)
+    return error
+  end
+end
+
+def self.read_error_context(stream)
+  # The key/value context which the Rust code attached to an error follows it, unless empty.
+  error_context = {}
+  return error_context if stream.remaining.zero?
+
+  stream.read_length.times do
+    key = stream.readString
+    error_context[key] = stream.readString
   end
+  # The sources of the errors declared with `[Source]` come next, which aren't exposed in Ruby.
+  stream.read_length.times { stream.readString } unless stream.remaining.zero?
+  error_context
 end
 
 class InternalError < StandardError
+  # The key/value context which the Rust code attached to the panic, as a Hash.
+  attr_accessor :error_context
+  # Where the Rust code panicked, as `file:line:column`, and the backtrace of the panic, which are
+  # empty if unknown.
+  attr_accessor :location, :backtrace
+end
+
+# A RustBuffer going over the limits set by the Rust code, rather than being allocated. `size` is
+# the size of the buffer, or the total size of the live buffers with it, which goes over `limit`,
+# in bytes.
+class PayloadTooLargeError < InternalError
+  attr_reader :size, :limit
+
+  def initialize(message, size, limit)
+    super(message)
+    @size = size
+    @limit = limit
+  end
+end
+
+def self.lift_panic(rust_buffer)
+  # The error buffer of a panic holds its message, followed by its location and its backtrace,
+  # which are empty when unknown, and its context.
+  rust_buffer.consumeWithStream do |stream|
+    error = InternalError.new(stream.readString)
+    error.location = stream.readString
+    error.backtrace = stream.readString
+    error.error_context = read_error_context(stream)
+    return error
+  end
+end
+
+def self.lift_payload_too_large(rust_buffer)
+  # The error buffer of a RustBuffer going over the limits holds its message, size and limit, and
+  # its context.
+  rust_buffer.consumeWithStream do |stream|
+    error = PayloadTooLargeError.new(stream.readString, stream.readU64, stream.readU64)
+    error.error_context = read_error_context(stream)
+    return error
+  end
 end
 
 def self.rust_call(fn_name, *args)
@@ -76,6 +149,17 @@ def self.rust_call(fn_name, *args)
   rust_call_with_error(nil, fn_name, *args)
 end
 
+{% if config.async_variants() -%}
+def self.rust_call_async(&block)
+  # Make the call on a new thread and wait for its result. Joining the thread is a blocking
+  # operation which the Fiber scheduler of Ruby 3, if any, handles by running the other fibers in
+  # the meantime. Exceptions raised by the call are raised again here.
+  thread = Thread.new(&block)
+  thread.report_on_exception = false
+  thread.value
+end
+
+{% endif -%}
 def self.rust_call_with_error(error_module, fn_name, *args)
   # Call a rust function and handle errors
   #
@@ -105,13 +189,15 @@ def self.rust_call_with_error(error_module, fn_name, *args)
     # with the message.  But if that code panics, then it just sends back
     # an empty buffer.
     if status.error_buf.len > 0
-      raise InternalError, status.error_buf.consumeIntoString()
+      raise lift_panic(status.error_buf)
     else
       raise InternalError, "Rust panic"
     end
+  when CALL_PAYLOAD_TOO_LARGE
+    raise lift_payload_too_large(status.error_buf)
   else
     raise InternalError, "Unknown call status: #{status.code}"
   end
 end
 
-private_class_method :consume_buffer_into_error
+private_class_method :consume_buffer_into_error, :read_error_context
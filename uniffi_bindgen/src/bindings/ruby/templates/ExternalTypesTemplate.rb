@@ -0,0 +1,21 @@
+# The bindings of the components using the types of this one as external types read and write them
+# with these, so that their values only cross between the libraries of the components as bytes.
+
+# Read a value of the type `type_name` of this component, from the RustBuffer `rbuf` of another
+# component, at `offset`. Returns the value and the offset right after it.
+def self._uniffi_read_external(type_name, rbuf, offset)
+  stream = RustBufferStream.new rbuf, offset
+  value = stream.public_send("read#{type_name}")
+  [value, stream.offset]
+end
+
+# Write `value`, of the type `type_name` of this component, into the bytes it's serialized as.
+def self._uniffi_write_external(type_name, value)
+  rbuf = RustBuffer.allocWithBuilder do |builder|
+    builder.public_send("write_#{type_name}", value)
+    builder.finalize
+  end
+  rbuf.consumeWithStream do |stream|
+    return stream.read(stream.remaining)
+  end
+end
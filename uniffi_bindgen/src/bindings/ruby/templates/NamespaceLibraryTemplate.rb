@@ -1,3 +1,17 @@
+{% for rec in ci.record_definitions() -%}
+{%- if rec.is_ffi_struct() -%}
+# The fields of the `{{ rec.name()|class_name_rb }}` record, which is passed by value.
+class FfiStruct{{ rec.name()|class_name_rb }} < FFI::Struct
+  layout {% for field in rec.fields() -%}
+    {%- let ffi_type = FFIType::from(field.type_()) -%}
+    :{{ field.name()|var_name_rb }}, {{ ffi_type|type_ffi }}
+    {%- if !loop.last %},
+         {% endif %}
+  {%- endfor %}
+end
+
+{% endif -%}
+{%- endfor -%}
 # This is how we find and load the dynamic library provided by the component.
 # For now we just look it up by name.
 module UniFFILib
@@ -9,9 +23,29 @@ module UniFFILib
   ffi_lib '{{ config.cdylib_name() }}'
   {% endif %}
 
+  # Check that the library was built from the same interface as these bindings, and with the same
+  # version of UniFFI, before attaching the other functions, which would be missing otherwise.
+  attach_function :{{ ci.ffi_contract_version_name() }}, [], :uint32
+  bindings_contract_version = {{ ci.contract_version() }}
+  scaffolding_contract_version = {{ ci.ffi_contract_version_name() }}
+  if bindings_contract_version != scaffolding_contract_version
+    raise InternalError, "UniFFI contract version mismatch: the bindings of {{ ci.namespace() }} " \
+      "expect version 0x#{bindings_contract_version.to_s(16)}, but the library provides " \
+      "0x#{scaffolding_contract_version.to_s(16)}. Regenerate the bindings with the version of " \
+      "uniffi-bindgen and the UDL file the library was built with."
+  end
+
   {% for func in ci.iter_ffi_function_definitions() -%}
   attach_function :{{ func.name() }},
     {%- call rb::arg_list_ffi_decl(func) %},
     {% match func.return_type() %}{% when Some with (type_) %}{{ type_|type_ffi }}{% when None %}:void{% endmatch %}
+    {%- if config.async_variants() %},
+    {#- Release the GVL during the call, so that the other threads keep running. #}
+    blocking: true
+    {%- endif %}
   {% endfor %}
+  {%- if config.ractor_safe() %}
+  {#- The functions of a frozen library can be called from any Ractor. #}
+  freeze
+  {%- endif %}
 end
@@ -1,54 +1,91 @@
-class {{ obj.name()|class_name_rb }}
+{{ obj.docstring()|doc_comment("") }}class {{ obj.name()|class_name_rb }}
 
-  # A private helper for initializing instances of the class from a raw pointer,
+  # A private helper for initializing instances of the class from the handle of a Rust object,
   # bypassing any initialization logic and ensuring they are GC'd properly.
-  def self._uniffi_allocate(pointer)
-    pointer.autorelease = false
+  def self._uniffi_allocate(handle)
     inst = allocate
-    inst.instance_variable_set :@pointer, pointer
-    ObjectSpace.define_finalizer(inst, _uniffi_define_finalizer_by_pointer(pointer, inst.object_id))
+    inst.instance_variable_set :@handle, handle
+    ObjectSpace.define_finalizer(inst, _uniffi_define_finalizer_by_handle(handle, inst.object_id))
     return inst
   end
 
   # A private helper for registering an object finalizer.
   # N.B. it's important that this does not capture a reference
-  # to the actual instance, only its underlying pointer.
-  def self._uniffi_define_finalizer_by_pointer(pointer, object_id)
+  # to the actual instance, only its underlying handle.
+  def self._uniffi_define_finalizer_by_handle(handle, object_id)
+    {%- if config.ractor_safe() %}
+    # Finalizers defined in a Ractor must be shareable, which they are as they only capture the
+    # integer handle.
+    Ractor.make_shareable(Proc.new do |_id|
+      {{ ci.namespace()|class_name_rb }}.rust_call(
+        :{{ obj.ffi_object_free().name() }},
+        handle
+      )
+    end)
+    {%- else %}
     Proc.new do |_id|
       {{ ci.namespace()|class_name_rb }}.rust_call(
         :{{ obj.ffi_object_free().name() }},
-        pointer
+        handle
       )
     end
+    {%- endif %}
   end
 
-  # A private helper for lowering instances into a raw pointer.
-  # This does an explicit typecheck, because accidentally lowering a different type of
-  # object in a place where this type is expected, could lead to memory unsafety.
+  # A private helper for lowering instances into their handle.
+  # This does an explicit typecheck, so that lowering a different type of object in a place
+  # where this type is expected is reported here rather than by Rust.
   def self._uniffi_lower(inst)
     if not inst.is_a? self
       raise TypeError.new "Expected a {{ obj.name()|class_name_rb }} intance, got #{inst}"
     end
-    return inst.instance_variable_get :@pointer
+    return inst._uniffi_handle
+  end
+
+  # A private helper for getting the handle of the Rust object, which fails once it was freed.
+  def _uniffi_handle
+    handle = @handle
+    if handle.nil?
+      raise RuntimeError.new "{{ obj.name()|class_name_rb }} object has already been destroyed"
+    end
+    return handle
+  end
+  {%- if !obj.has_method("destroy") %}
+
+  # Frees the Rust object right away, rather than when this object is garbage collected.
+  # Calling the methods of this object afterwards raises a `RuntimeError`.
+  def destroy
+    # Other threads can't run between reading and clearing the handle under the GVL, so the
+    # object is only freed once.
+    handle = @handle
+    @handle = nil
+    return if handle.nil?
+    ObjectSpace.undefine_finalizer(self)
+    {{ ci.namespace()|class_name_rb }}.rust_call(
+      :{{ obj.ffi_object_free().name() }},
+      handle
+    )
   end
+  {%- endif %}
 
   {%- match obj.primary_constructor() %}
   {%- when Some with (cons) %}
-  def initialize({% call rb::arg_list_decl(cons) -%})
+  {{ cons|yard_doc(ci, "  ") }}def initialize({% call rb::arg_list_decl(cons) -%})
     {%- call rb::coerce_args_extra_indent(cons) %}
-    pointer = {% call rb::to_ffi_call(cons) %}
-    @pointer = pointer
-    ObjectSpace.define_finalizer(self, self.class._uniffi_define_finalizer_by_pointer(pointer, self.object_id))
+    handle = {% call rb::to_ffi_call(cons) %}
+    @handle = handle
+    ObjectSpace.define_finalizer(self, self.class._uniffi_define_finalizer_by_handle(handle, self.object_id))
   end
   {%- when None %}
   {%- endmatch %}
 
   {% for cons in obj.alternate_constructors() -%}
+  {{ cons|yard_doc(ci, "  ") }}# @return [{{ obj.name()|class_name_rb }}]
   def self.{{ cons.name()|fn_name_rb }}({% call rb::arg_list_decl(cons) %})
     {%- call rb::coerce_args_extra_indent(cons) %}
     # Call the (fallible) function before creating any half-baked object instances.
     # Lightly yucky way to bypass the usual "initialize" logic
-    # and just create a new instance with the required pointer.
+    # and just create a new instance with the required handle.
     return _uniffi_allocate({% call rb::to_ffi_call(cons) %})
   end
   {% endfor %}
@@ -57,17 +94,41 @@ class {{ obj.name()|class_name_rb }}
   {%- match meth.return_type() -%}
 
   {%- when Some with (return_type) -%}
-  def {{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
+  {{ meth|yard_doc(ci, "  ") }}def {{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
     {%- call rb::coerce_args_extra_indent(meth) %}
-    result = {% call rb::to_ffi_call_with_prefix("@pointer", meth) %}
+    result = {% call rb::to_ffi_call_with_prefix("_uniffi_handle", meth) %}
     return {{ "result"|lift_rb(return_type) }}
   end
 
   {%- when None -%}
-  def {{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
+  {{ meth|yard_doc(ci, "  ") }}def {{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
       {%- call rb::coerce_args_extra_indent(meth) %}
-      {% call rb::to_ffi_call_with_prefix("@pointer", meth) %}
+      {% call rb::to_ffi_call_with_prefix("_uniffi_handle", meth) %}
+  end
+  {% endmatch %}
+  {%- if config.async_variants() %}
+  {% call rb::async_fn(meth, "") %}
+  {% endif %}
+  {% endfor %}
+
+  {%- for meth in obj.static_methods() %}
+  {%- match meth.return_type() %}
+
+  {%- when Some with (return_type) %}
+  {{ meth|yard_doc(ci, "  ") }}def self.{{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
+    {%- call rb::coerce_args_extra_indent(meth) %}
+    result = {% call rb::to_ffi_call(meth) %}
+    return {{ "result"|lift_rb(return_type) }}
+  end
+
+  {%- when None %}
+  {{ meth|yard_doc(ci, "  ") }}def self.{{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
+    {%- call rb::coerce_args_extra_indent(meth) %}
+    {% call rb::to_ffi_call(meth) %}
   end
   {% endmatch %}
+  {%- if config.async_variants() %}
+  {% call rb::async_fn(meth, "self.") %}
+  {% endif %}
   {% endfor %}
 end
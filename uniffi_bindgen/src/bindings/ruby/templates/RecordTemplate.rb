@@ -1,8 +1,13 @@
+{%- if rec.docstring().is_none() %}
 # Record type {{ rec.name() }}
-class {{ rec.name()|class_name_rb }}
-  attr_reader {% for field in rec.fields() %}:{{ field.name()|var_name_rb }}{% if loop.last %}{% else %}, {% endif %}{%- endfor %}
+{%- endif %}
+{{ rec.docstring()|doc_comment("") }}class {{ rec.name()|class_name_rb }}
+  {%- for field in rec.fields() %}
+  {{ field|field_yard_doc("  ") }}attr_reader :{{ field.name()|var_name_rb }}
+  {%- endfor %}
 
-  def initialize({% for field in rec.fields() %}{{ field.name()|var_name_rb }}{% if loop.last %}{% else %}, {% endif %}{% endfor %})
+  {{ rec.fields()|fields_yard_doc("  ") }}def initialize({% for field in rec.fields() %}{% call rb::param_decl(field.name(), field.default_value()) %}
+    {%- if loop.last %}{% else %}, {% endif %}{% endfor %})
     {%- for field in rec.fields() %}
     @{{ field.name()|var_name_rb }} = {{ field.name()|var_name_rb }}
     {%- endfor %}
@@ -17,4 +22,57 @@ class {{ rec.name()|class_name_rb }}
 
     true
   end
+
+  {%- if rec.is_ffi_struct() %}
+
+  # Private helpers passing the instances of this record to Rust by value, as structs.
+  def self._uniffi_lower(rec)
+    struct = FfiStruct{{ rec.name()|class_name_rb }}.new
+    {%- for field in rec.fields() %}
+    {%- let field_name = field.name()|var_name_rb %}
+    struct[:{{ field_name }}] = {{ format!("rec.{}", field_name)|lower_rb(field.type_().borrow()) }}
+    {%- endfor %}
+    struct
+  end
+
+  def self._uniffi_lift(struct)
+    new(
+      {%- for field in rec.fields() %}
+      {%- let field_name = field.name()|var_name_rb %}
+      {% call rb::keyword_prefix(field.name()) %}{{ format!("struct[:{}]", field_name)|lift_rb(field.type_().borrow()) }}{% if !loop.last %},{% endif %}
+      {%- endfor %}
+    )
+  end
+
+  {%- else %}
+
+  # Private helpers passing the instances of this record to Rust, serialized in a `RustBuffer`.
+  def self._uniffi_lower(rec)
+    RustBuffer.alloc_from_{{ rec.type_().canonical_name().borrow()|class_name_rb }}(rec)
+  end
+
+  def self._uniffi_lift(rbuf)
+    rbuf.consumeInto{{ rec.type_().canonical_name().borrow()|class_name_rb }}
+  end
+  {%- endif %}
+  {%- let receiver = "self"|lower_rb(rec.type_().borrow()) %}
+  {%- for meth in ci.methods_of(rec.type_().borrow()) %}
+  {%- match meth.return_type() %}
+
+  {%- when Some with (return_type) %}
+
+  {{ meth|yard_doc(ci, "  ") }}def {{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
+    {%- call rb::coerce_args_extra_indent(meth) %}
+    result = {% call rb::to_ffi_call_with_prefix(receiver, meth) %}
+    return {{ "result"|lift_rb(return_type) }}
+  end
+
+  {%- when None %}
+
+  {{ meth|yard_doc(ci, "  ") }}def {{ meth.name()|fn_name_rb }}({% call rb::arg_list_decl(meth) %})
+    {%- call rb::coerce_args_extra_indent(meth) %}
+    {% call rb::to_ffi_call_with_prefix(receiver, meth) %}
+  end
+  {%- endmatch %}
+  {%- endfor %}
 end
@@ -4,12 +4,16 @@ class RustBufferBuilder
   def initialize
     @rust_buf = RustBuffer.alloc 16
     @rust_buf.len = 0
+    # The handles of the objects cloned for Rust, with the functions freeing them, which are
+    # called if the buffer is discarded, as Rust then never reads it.
+    @cloned_handles = []
   end
 
   def finalize
     rbuf = @rust_buf
 
     @rust_buf = nil
+    @cloned_handles = []
 
     rbuf
   end
@@ -17,10 +21,18 @@ class RustBufferBuilder
   def discard
     return if @rust_buf.nil?
 
+    @cloned_handles.each do |free, handle|
+      {{ ci.namespace()|class_name_rb }}.rust_call(free, handle)
+    end
     rbuf = finalize
     rbuf.free
   end
 
+  def write_cloned_handle(handle, free)
+    @cloned_handles << [free, handle]
+    pack_into(8, 'Q>', handle)
+  end
+
   def write(value)
     reserve(value.bytes.size) do
       @rust_buf.data.put_array_of_char @rust_buf.len, value.bytes
@@ -46,37 +58,61 @@ class RustBufferBuilder
   {% when Type::Int16 -%}
 
   def write_I16(v)
+    {%- if ci.compact_encoding() %}
+    write_zigzag(v)
+    {%- else %}
     pack_into(2, 's>', v)
+    {%- endif %}
   end
 
   {% when Type::UInt16 -%}
 
   def write_U16(v)
+    {%- if ci.compact_encoding() %}
+    write_varint(v)
+    {%- else %}
     pack_into(2, 'S>', v)
+    {%- endif %}
   end
 
   {% when Type::Int32 -%}
 
   def write_I32(v)
+    {%- if ci.compact_encoding() %}
+    write_zigzag(v)
+    {%- else %}
     pack_into(4, 'l>', v)
+    {%- endif %}
   end
 
   {% when Type::UInt32 -%}
 
   def write_U32(v)
+    {%- if ci.compact_encoding() %}
+    write_varint(v)
+    {%- else %}
     pack_into(4, 'L>', v)
+    {%- endif %}
   end
 
   {% when Type::Int64 -%}
 
   def write_I64(v)
+    {%- if ci.compact_encoding() %}
+    write_zigzag(v)
+    {%- else %}
     pack_into(8, 'q>', v)
+    {%- endif %}
   end
 
   {% when Type::UInt64 -%}
 
   def write_U64(v)
+    {%- if ci.compact_encoding() %}
+    write_varint(v)
+    {%- else %}
     pack_into(8, 'Q>', v)
+    {%- endif %}
   end
 
   {% when Type::Float32 -%}
@@ -101,7 +137,7 @@ class RustBufferBuilder
 
   def write_String(v)
     v = v.to_s
-    pack_into 4, 'l>', v.bytes.size
+    write_length v.bytes.size
     write v
   end
 
@@ -130,6 +166,64 @@ class RustBufferBuilder
     pack_into 4, 'L>', nanoseconds
   end
 
+  {% when Type::OffsetDateTime -%}
+  # The OffsetDateTime type.
+
+  def write_{{ canonical_type_name }}(v)
+    pack_into 8, 'q>', v.tv_sec
+    pack_into 4, 'L>', v.tv_nsec
+    pack_into 4, 'l>', v.utc_offset
+  end
+
+  {% when Type::BigInt -%}
+  # The BigInt type, passed as a string of decimal digits.
+
+  def write_{{ canonical_type_name }}(v)
+    v = v.to_s
+
+    write_length v.bytes.size
+    write v
+  end
+
+  {% when Type::Decimal -%}
+  # The Decimal type, passed as a string in plain notation.
+
+  def write_{{ canonical_type_name }}(v)
+    v = v.to_s('F')
+
+    write_length v.bytes.size
+    write v
+  end
+
+  {% when Type::Url -%}
+  # The Url type, which is a String in Ruby. Rust checks that it's a valid URL.
+
+  def write_{{ canonical_type_name }}(v)
+    v = v.to_s
+    write_length v.bytes.size
+    write v
+  end
+
+  {% when Type::Uuid -%}
+  # The Uuid type, passed as its 16 bytes and represented as a canonical string.
+
+  def write_{{ canonical_type_name }}(v)
+    hex = v.delete('-')
+
+    raise ArgumentError, "Invalid UUID: #{v}" unless hex.match?(/\A\h{32}\z/)
+
+    write [hex].pack('H*')
+  end
+
+  {% when Type::Json -%}
+  # The Json type, passed as JSON text.
+
+  def write_{{ canonical_type_name }}(v)
+    v = JSON.generate(v)
+    write_length v.bytes.size
+    write v
+  end
+
   {% when Type::Duration -%}
   # The Duration type.
 
@@ -143,12 +237,16 @@ class RustBufferBuilder
     pack_into 4, 'L>', nanoseconds
   end
 
-  {% when Type::Object with (object_name) -%}
+  {% when Type::Object { name: object_name, imp } -%}
+  {%- let obj = ci.get_object_definition(object_name).unwrap() -%}
   # The Object type {{ object_name }}.
 
   def write_{{ canonical_type_name }}(obj)
-    pointer = {{ object_name|class_name_rb}}._uniffi_lower obj
-    pack_into(8, 'Q>', pointer.address)
+    handle = {{ ci.namespace()|class_name_rb }}.rust_call(
+      :{{ obj.ffi_object_clone().name() }},
+      {{ object_name|class_name_rb}}._uniffi_lower(obj)
+    )
+    write_cloned_handle(handle, :{{ obj.ffi_object_free().name() }})
   end
 
   {% when Type::Enum with (enum_name) -%}
@@ -157,11 +255,11 @@ class RustBufferBuilder
 
   def write_{{ canonical_type_name }}(v)
     {%- if e.is_flat() %}
-    pack_into(4, 'l>', v)
+    write_length(v)
     {%- else -%}
     {%- for variant in e.variants() %}
     if v.{{ variant.name()|var_name_rb }}?
-      pack_into(4, 'l>', {{ loop.index }})
+      write_length({{ loop.index }})
       {%- for field in variant.fields() %}
       self.write_{{ field.type_().canonical_name().borrow()|class_name_rb }}(v.{{ field.name() }})
       {%- endfor %}
@@ -195,26 +293,59 @@ class RustBufferBuilder
   {% when Type::Sequence with (inner_type) -%}
   # The Sequence<T> type for {{ inner_type.canonical_name() }}.
 
+  {%- if ci.compact_encoding() && typ|is_strings %}
+  # With the compact encoding, the strings share their length prefix when they all have the same
+  # length: it's written once, plus one, after the count, and is zero otherwise.
+  def write_{{ canonical_type_name }}(items)
+    write_length(items.size)
+    return if items.empty?
+
+    items = items.map(&:to_s)
+    shared = items.all? { |item| item.bytesize == items.first.bytesize }
+    write_length(shared ? items.first.bytesize + 1 : 0)
+
+    items.each do |item|
+      write_length item.bytesize unless shared
+      write item
+    end
+  end
+  {%- else %}
   def write_{{ canonical_type_name }}(items)
-    pack_into(4, 'l>', items.size)
+    write_length(items.size)
 
     items.each do |item|
       self.write_{{ inner_type.canonical_name().borrow()|class_name_rb }}(item)
     end
   end
+  {%- endif %}
 
   {% when Type::Map with (k, inner_type) -%}
-  # The Map<T> type for {{ inner_type.canonical_name() }}.
+  # The Map<K, T> type for {{ k.canonical_name() }} keys and {{ inner_type.canonical_name() }} values.
 
   def write_{{ canonical_type_name }}(items)
-    pack_into(4, 'l>', items.size)
+    write_length(items.size)
 
     items.each do |k, v|
-      write_String(k)
+      self.write_{{ k.canonical_name().borrow()|class_name_rb }}(k)
       self.write_{{ inner_type.canonical_name().borrow()|class_name_rb }}(v)
     end
   end
 
+  {% when Type::External with { name, crate_name, kind } -%}
+  {%- match kind -%}
+  {%- when ExternalKind::CallbackInterface -%}
+  # Callback interfaces are not yet supported in the Ruby backend.
+  def write_{{ canonical_type_name }}(v)
+    raise InternalError('RustBufferStream.write() not implemented yet for {{ canonical_type_name }}')
+  end
+  {%- else %}
+  # The External type {{ name }}, written by the bindings of the {{ crate_name }} crate.
+
+  def write_{{ canonical_type_name }}(v)
+    write {{ config.external_module_name(crate_name) }}._uniffi_write_external(:{{ canonical_type_name }}, v)
+  end
+  {%- endmatch %}
+
   {%- else -%}
   # This type is not yet supported in the Ruby backend.
   def write_{{ canonical_type_name }}(v)
@@ -241,6 +372,33 @@ class RustBufferBuilder
       @rust_buf.data.put_array_of_char @rust_buf.len, [value].pack(format).bytes
     end
   end
+
+  # The length of a string, sequence or map, or the variant of an enum.
+  def write_length(v)
+    {%- if ci.compact_encoding() %}
+    write_varint(v)
+    {%- else %}
+    pack_into(4, 'l>', v)
+    {%- endif %}
+  end
+  {%- if ci.compact_encoding() %}
+
+  def write_varint(v)
+    raise ArgumentError, "#{v} is out of range for a varint" if v.negative? || v >= 1 << 64
+
+    while v >= 0x80
+      pack_into(1, 'C', (v & 0x7f) | 0x80)
+      v >>= 7
+    end
+    pack_into(1, 'C', v)
+  end
+
+  def write_zigzag(v)
+    raise ArgumentError, "#{v} is out of range for a zigzag varint" if v < -(1 << 63) || v >= 1 << 63
+
+    write_varint((v << 1) ^ (v >> 63))
+  end
+  {%- endif %}
 end
 
 private_constant :RustBufferBuilder
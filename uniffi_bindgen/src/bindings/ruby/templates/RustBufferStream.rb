@@ -2,9 +2,11 @@
 # Helper for structured reading of values from a RustBuffer.
 class RustBufferStream
 
-  def initialize(rbuf)
+  attr_reader :offset
+
+  def initialize(rbuf, offset = 0)
     @rbuf = rbuf
-    @offset = 0
+    @offset = offset
   end
 
   def remaining
@@ -40,37 +42,61 @@ class RustBufferStream
   {% when Type::Int16 -%}
 
   def readI16
+    {%- if ci.compact_encoding() %}
+    read_zigzag
+    {%- else %}
     unpack_from 2, 's>'
+    {%- endif %}
   end
 
   {% when Type::UInt16 -%}
 
   def readU16
+    {%- if ci.compact_encoding() %}
+    read_varint
+    {%- else %}
     unpack_from 2, 'S>'
+    {%- endif %}
   end
 
   {% when Type::Int32 -%}
 
   def readI32
+    {%- if ci.compact_encoding() %}
+    read_zigzag
+    {%- else %}
     unpack_from 4, 'l>'
+    {%- endif %}
   end
 
   {% when Type::UInt32 -%}
 
   def readU32
+    {%- if ci.compact_encoding() %}
+    read_varint
+    {%- else %}
     unpack_from 4, 'L>'
+    {%- endif %}
   end
 
   {% when Type::Int64 -%}
 
   def readI64
+    {%- if ci.compact_encoding() %}
+    read_zigzag
+    {%- else %}
     unpack_from 8, 'q>'
+    {%- endif %}
   end
 
   {% when Type::UInt64 -%}
 
   def readU64
+    {%- if ci.compact_encoding() %}
+    read_varint
+    {%- else %}
     unpack_from 8, 'Q>'
+    {%- endif %}
   end
 
   {% when Type::Float32 -%}
@@ -99,7 +125,7 @@ class RustBufferStream
   {% when Type::String -%}
 
   def readString
-    size = unpack_from 4, 'l>'
+    size = read_length
 
     raise InternalError, 'Unexpected negative string length' if size.negative?
 
@@ -130,6 +156,72 @@ class RustBufferStream
     Time.at(seconds, nanoseconds, :nanosecond, in: '+00:00').utc
   end
 
+  {% when Type::OffsetDateTime -%}
+  # The OffsetDateTime type.
+
+  def read{{ canonical_type_name }}
+    # Unlike timestamps, the seconds are rounded towards negative infinity and
+    # the nanoseconds are always positive, just like in `Time`.
+    seconds = unpack_from 8, 'q>'
+    nanoseconds = unpack_from 4, 'L>'
+    offset = unpack_from 4, 'l>'
+
+    Time.at(seconds, nanoseconds, :nanosecond, in: offset)
+  end
+
+  {% when Type::BigInt -%}
+  # The BigInt type, passed as a string of decimal digits.
+
+  def read{{ canonical_type_name }}
+    size = read_length
+
+    raise InternalError, 'Unexpected negative integer length' if size.negative?
+
+    Integer(read(size).force_encoding(Encoding::UTF_8), 10)
+  end
+
+  {% when Type::Decimal -%}
+  # The Decimal type, passed as a string in plain notation.
+  require 'bigdecimal'
+
+  def read{{ canonical_type_name }}
+    size = read_length
+
+    raise InternalError, 'Unexpected negative decimal length' if size.negative?
+
+    BigDecimal(read(size).force_encoding(Encoding::UTF_8))
+  end
+
+  {% when Type::Url -%}
+  # The Url type, which is a String in Ruby.
+
+  def read{{ canonical_type_name }}
+    size = read_length
+
+    raise InternalError, 'Unexpected negative URL length' if size.negative?
+
+    read(size).force_encoding(Encoding::UTF_8)
+  end
+
+  {% when Type::Uuid -%}
+  # The Uuid type, passed as its 16 bytes and represented as a canonical string.
+
+  def read{{ canonical_type_name }}
+    read(16).unpack('H8H4H4H4H12').join('-')
+  end
+
+  {% when Type::Json -%}
+  # The Json type, passed as JSON text.
+  require 'json'
+
+  def read{{ canonical_type_name }}
+    size = read_length
+
+    raise InternalError, 'Unexpected negative JSON length' if size.negative?
+
+    JSON.parse(read(size).force_encoding(Encoding::UTF_8))
+  end
+
   {% when Type::Duration -%}
   # The Duration type.
 
@@ -140,12 +232,12 @@ class RustBufferStream
     Time.at(seconds, nanoseconds, :nanosecond, in: '+00:00').utc
   end
 
-  {% when Type::Object with (object_name) -%}
+  {% when Type::Object { name: object_name, imp } -%}
   # The Object type {{ object_name }}.
 
   def read{{ canonical_type_name }}
-    pointer = FFI::Pointer.new unpack_from 8, 'Q>'
-    return {{ object_name|class_name_rb }}._uniffi_allocate(pointer)
+    handle = unpack_from 8, 'Q>'
+    return {{ object_name|class_name_rb }}._uniffi_allocate(handle)
   end
 
   {% when Type::Enum with (enum_name) -%}
@@ -153,7 +245,7 @@ class RustBufferStream
   # The Enum type {{ enum_name }}.
 
   def read{{ canonical_type_name }}
-    variant = unpack_from 4, 'l>'
+    variant = read_length
     {% if e.is_flat() -%}
     {%- for variant in e.variants() %}
     if variant == {{ loop.index }}
@@ -186,7 +278,7 @@ class RustBufferStream
   # The Error type {{ error_name }}
 
   def read{{ canonical_type_name }}
-    variant = unpack_from 4, 'l>'
+    variant = read_length
     {% if e.is_flat() -%}
     {%- for variant in e.variants() %}
     if variant == {{ loop.index }}
@@ -223,7 +315,7 @@ class RustBufferStream
   def read{{ canonical_type_name }}
     {{ rec.name()|class_name_rb }}.new(
       {%- for field in rec.fields() %}
-      read{{ field.type_().canonical_name().borrow()|class_name_rb }}{% if loop.last %}{% else %},{% endif %}
+      {% call rb::keyword_prefix(field.name()) %}read{{ field.type_().canonical_name().borrow()|class_name_rb }}{% if loop.last %}{% else %},{% endif %}
       {%- endfor %}
     )
   end
@@ -246,8 +338,23 @@ class RustBufferStream
   {% when Type::Sequence with (inner_type) -%}
   # The Sequence<T> type for {{ inner_type.canonical_name() }}.
 
+  {%- if ci.compact_encoding() && typ|is_strings %}
+  # With the compact encoding, the strings share their length prefix when they all have the same
+  # length: it's written once, plus one, after the count, and is zero otherwise.
+  def read{{ canonical_type_name }}
+    count = read_length
+    return [] if count.zero?
+
+    shared_length = read_length
+
+    Array.new(count) do
+      size = shared_length.zero? ? read_length : shared_length - 1
+      read(size).force_encoding(Encoding::UTF_8)
+    end
+  end
+  {%- else %}
   def read{{ canonical_type_name }}
-    count = unpack_from 4, 'l>'
+    count = read_length
 
     raise InternalError, 'Unexpected negative sequence length' if count.negative?
 
@@ -259,22 +366,41 @@ class RustBufferStream
 
     items
   end
+  {%- endif %}
 
   {% when Type::Map with (k, inner_type) -%}
-  # The Map<T> type for {{ inner_type.canonical_name() }}.
+  # The Map<K, T> type for {{ k.canonical_name() }} keys and {{ inner_type.canonical_name() }} values.
 
   def read{{ canonical_type_name }}
-    count = unpack_from 4, 'l>'
+    count = read_length
     raise InternalError, 'Unexpected negative map size' if count.negative?
 
     items = {}
     count.times do
-      key = readString
+      key = read{{ k.canonical_name().borrow()|class_name_rb }}
       items[key] = read{{ inner_type.canonical_name().borrow()|class_name_rb }}
     end
 
     items
   end
+  {% when Type::External with { name, crate_name, kind } -%}
+  {%- match kind -%}
+  {%- when ExternalKind::CallbackInterface -%}
+  # Callback interfaces are not yet supported in the Ruby backend.
+  def read{{ canonical_type_name }}
+    raise InternalError, 'RustBufferStream.read not implemented yet for {{ canonical_type_name }}'
+  end
+  {%- else %}
+  # The External type {{ name }}, read by the bindings of the {{ crate_name }} crate.
+
+  def read{{ canonical_type_name }}
+    value, @offset = {{ config.external_module_name(crate_name) }}._uniffi_read_external(
+      :{{ canonical_type_name }}, @rbuf, @offset
+    )
+    value
+  end
+  {%- endmatch %}
+
   {%- else -%}
   # This type is not yet supported in the Ruby backend.
   def read{{ canonical_type_name }}
@@ -296,6 +422,37 @@ class RustBufferStream
 
     value[0]
   end
+
+  # The length of a string, sequence or map, or the variant of an enum.
+  def read_length
+    {%- if ci.compact_encoding() %}
+    read_varint
+    {%- else %}
+    unpack_from 4, 'l>'
+    {%- endif %}
+  end
+  {%- if ci.compact_encoding() %}
+
+  # The component has `compact_encoding = true` in its `uniffi.toml`: the integers wider than a
+  # byte are written as varints (LEB128), zigzag-encoded if they're signed, and so are the lengths
+  # of the strings, sequences and maps, and the variants of the enums.
+
+  def read_varint
+    value = 0
+    (0...64).step(7) do |shift|
+      byte = unpack_from 1, 'C'
+      value |= (byte & 0x7f) << shift
+      return value if (byte & 0x80).zero?
+    end
+
+    raise InternalError, 'varint longer than 10 bytes'
+  end
+
+  def read_zigzag
+    v = read_varint
+    (v >> 1) ^ -(v & 1)
+  end
+  {%- endif %}
 end
 
 private_constant :RustBufferStream
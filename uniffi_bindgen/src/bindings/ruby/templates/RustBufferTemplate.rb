@@ -66,17 +66,28 @@ class RustBuffer < FFI::Struct
 
   {% when Type::String -%}
   # The primitive String type.
+  {%- if ci.utf16_strings() %}
+  # The strings lowered and lifted on their own are passed as little-endian UTF-16.
+  {%- endif %}
 
   def self.allocFromString(value)
     RustBuffer.allocWithBuilder do |builder|
+      {%- if ci.utf16_strings() %}
+      builder.write value.encode(Encoding::UTF_16LE)
+      {%- else %}
       builder.write value.encode('utf-8')
+      {%- endif %}
       return builder.finalize
     end
   end
 
   def consumeIntoString
     consumeWithStream do |stream|
+      {%- if ci.utf16_strings() %}
+      return stream.read(stream.remaining).force_encoding(Encoding::UTF_16LE).encode(Encoding::UTF_8)
+      {%- else %}
       return stream.read(stream.remaining).force_encoding(Encoding::UTF_8)
+      {%- endif %}
     end
   end
 
@@ -94,6 +105,90 @@ class RustBuffer < FFI::Struct
     end
   end
 
+  {% when Type::OffsetDateTime -%}
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
+  {% when Type::BigInt -%}
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
+  {% when Type::Decimal -%}
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
+  {% when Type::Url -%}
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
+  {% when Type::Uuid -%}
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
+  {% when Type::Json -%}
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
   {% when Type::Duration -%}
   def self.alloc_from_{{ canonical_type_name }}(v)
     RustBuffer.allocWithBuilder do |builder|
@@ -160,6 +255,23 @@ class RustBuffer < FFI::Struct
 
   {% when Type::Sequence with (inner_type) -%}
   # The Sequence<T> type for {{ inner_type.canonical_name() }}.
+  {%- if typ|is_bytes %}
+  # Passed on its own, rather than inside another value, it's passed as its raw bytes, without a
+  # length prefix.
+
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    bytes = v.pack('C*')
+    rbuf = RustBuffer.alloc(bytes.bytesize)
+    rbuf.data.put_bytes(0, bytes)
+    rbuf
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    data.read_bytes(len).unpack('C*')
+  ensure
+    free
+  end
+  {%- else %}
 
   def self.alloc_from_{{ canonical_type_name }}(v)
     RustBuffer.allocWithBuilder do |builder|
@@ -173,9 +285,31 @@ class RustBuffer < FFI::Struct
       return stream.read{{ canonical_type_name }}
     end
   end
+  {%- endif %}
 
   {% when Type::Map with (k, inner_type) -%}
-  # The Map<T> type for {{ inner_type.canonical_name() }}.
+  # The Map<K, T> type for {{ k.canonical_name() }} keys and {{ inner_type.canonical_name() }} values.
+
+  def self.alloc_from_{{ canonical_type_name }}(v)
+    RustBuffer.allocWithBuilder do |builder|
+      builder.write_{{ canonical_type_name }}(v)
+      return builder.finalize
+    end
+  end
+
+  def consumeInto{{ canonical_type_name }}
+    consumeWithStream do |stream|
+      return stream.read{{ canonical_type_name }}
+    end
+  end
+
+  {% when Type::External with { name, crate_name, kind } -%}
+  # The External type {{ name }}, from the {{ crate_name }} crate.
+  {%- match kind %}
+  {%- when ExternalKind::Error %}
+  # Also read from the error buffers of the functions throwing it.
+  {%- else %}
+  {%- endmatch %}
 
   def self.alloc_from_{{ canonical_type_name }}(v)
     RustBuffer.allocWithBuilder do |builder|
@@ -5,9 +5,9 @@
 #}
 
 {%- macro to_ffi_call(func) -%}
-    {%- match func.throws_name() -%}
+    {%- match func.throws_type() -%}
     {%- when Some with (e) -%}
-      {{ ci.namespace()|class_name_rb }}.rust_call_with_error({{ e|class_name_rb }},
+      {{ ci.namespace()|class_name_rb }}.rust_call_with_error({{ e|error_class_rb(config) }},
     {%- else -%}
       {{ ci.namespace()|class_name_rb }}.rust_call(
     {%- endmatch -%}
@@ -17,9 +17,9 @@
 {%- endmacro -%}
 
 {%- macro to_ffi_call_with_prefix(prefix, func) -%}
-    {%- match func.throws_name() -%}
+    {%- match func.throws_type() -%}
     {%- when Some with (e) -%}
-      {{ ci.namespace()|class_name_rb }}.rust_call_with_error({{ e|class_name_rb }},
+      {{ ci.namespace()|class_name_rb }}.rust_call_with_error({{ e|error_class_rb(config) }},
     {%- else -%}
       {{ ci.namespace()|class_name_rb }}.rust_call(
     {%- endmatch -%}
@@ -43,15 +43,60 @@
 
 {% macro arg_list_decl(func) %}
     {%- for arg in func.arguments() -%}
-        {{ arg.name()|var_name_rb }}
-        {%- match arg.default_value() %}
+        {%- call param_decl(arg.name(), arg.default_value()) -%}
+        {%- if !loop.last %}, {% endif -%}
+    {%- endfor %}
+{%- endmacro %}
+
+{#-
+// A parameter of a method, or of the initializer of a record: a keyword argument with the
+// `keyword_arguments` option, or a positional one otherwise.
+-#}
+{% macro param_decl(name, default_value) %}
+    {{- name|var_name_rb }}
+    {%- if config.keyword_arguments() -%}
+        :
+        {%- match default_value %}
+        {%- when Some with(literal) %} {{ literal|literal_rb }}
+        {%- else %}
+        {%- endmatch %}
+    {%- else -%}
+        {%- match default_value %}
         {%- when Some with(literal) %} = {{ literal|literal_rb }}
         {%- else %}
         {%- endmatch %}
+    {%- endif -%}
+{%- endmacro %}
+
+{#-
+// The prefix of an argument of a call to a method, or to the initializer of a record, declared with
+// `param_decl`: its keyword with the `keyword_arguments` option, and nothing otherwise.
+-#}
+{% macro keyword_prefix(name) %}
+    {%- if config.keyword_arguments() %}{{ name|var_name_rb }}: {% endif %}
+{%- endmacro %}
+
+{#-
+// Arglist as used when forwarding the arguments of a function to another one.
+-#}
+{% macro arg_list_forward(func) %}
+    {%- for arg in func.arguments() -%}
+        {%- call keyword_prefix(arg.name()) -%}
+        {{ arg.name()|var_name_rb }}
         {%- if !loop.last %}, {% endif -%}
     {%- endfor %}
 {%- endmacro %}
 
+{#-
+// A `_async` variant of a function or method, which makes the call into Rust on another thread.
+// Only generated with the `async_variants` option.
+-#}
+{% macro async_fn(func, prefix) %}
+  def {{ prefix }}{{ func.name()|fn_name_rb }}_async({% call arg_list_decl(func) %})
+    {{ ci.namespace()|class_name_rb }}.rust_call_async { {{ func.name()|fn_name_rb }}({% call arg_list_forward(func) %}) }
+  end
+{%- endmacro %}
+
 {#-
 // Arglist as used in the UniFFILib function declations.
 // Note unfiltered name but type_ffi filters.
@@ -14,11 +14,15 @@
 # helpers directly inline like we're doing here.
 
 require 'ffi'
+{%- for crate_name in ci|external_crate_names %}
+require '{{ config.external_require_path(crate_name) }}'
+{%- endfor %}
 
 module {{ ci.namespace()|class_name_rb }}
   {% include "RustBufferTemplate.rb" %}
   {% include "RustBufferStream.rb" %}
   {% include "RustBufferBuilder.rb" %}
+  {% include "ExternalTypesTemplate.rb" %}
 
   # Error definitions
   {% include "ErrorTemplate.rb" %}
@@ -26,6 +30,10 @@ module {{ ci.namespace()|class_name_rb }}
   {% include "NamespaceLibraryTemplate.rb" %}
 
   # Public interface members begin here.
+  {%- for constant in ci.constant_definitions() %}
+
+  {{ constant.name()|const_name_rb }} = {{ constant.value()|literal_rb }}
+  {%- endfor %}
 
   {% for e in ci.enum_definitions() %}
   {% include "EnumTemplate.rb" %}
@@ -42,6 +50,43 @@ module {{ ci.namespace()|class_name_rb }}
   {% for obj in ci.object_definitions() %}
   {% include "ObjectTemplate.rb" %}
   {% endfor %}
+
+  # Describe the objects and buffers of this component which weren't freed yet, with the
+  # backtraces of their creation, when the `leak-detection` feature of uniffi is enabled.
+  def self.dump_live_allocations
+    result = rust_call(:{{ ci.ffi_dump_live_allocations().name() }})
+    result.consumeWithStream do |stream|
+      {%- if ci.utf16_strings() %}
+      return stream.read(stream.remaining).force_encoding(Encoding::UTF_16LE).encode(Encoding::UTF_8)
+      {%- else %}
+      return stream.read(stream.remaining).force_encoding(Encoding::UTF_8)
+      {%- endif %}
+    end
+  end
+
+  # The counters of the RustBuffers of the Rust library: the number of buffers allocated and not
+  # freed yet, their total capacity in bytes, the highest total capacity so far, and the number of
+  # buffers which weren't allocated because of the limits.
+  RustBufferStats = Struct.new(:live_buffers, :live_bytes, :peak_bytes, :rejected)
+
+  # Get the counters of the RustBuffers of the Rust library, which may set limits on them.
+  def self.rustbuffer_stats
+    result = rust_call(:{{ ci.ffi_rustbuffer_stats().name() }})
+    result.consumeWithStream do |stream|
+      return RustBufferStats.new(stream.readU64, stream.readU64, stream.readU64, stream.readU64)
+    end
+  end
+  {%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+
+  # Run the `#[uniffi::init]` hooks of the library now that the module is loaded, and register its
+  # `#[uniffi::shutdown]` hooks to run when the process exits.
+  {%- for func in ci.init_hooks() %}
+  rust_call(:{{ func.name() }})
+  {%- endfor %}
+  {%- for func in ci.shutdown_hooks() %}
+  at_exit { rust_call(:{{ func.name() }}) }
+  {%- endfor %}
+  {%- endif %}
 end
 
 {% import "macros.rb" as rb %}
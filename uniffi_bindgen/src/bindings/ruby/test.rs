@@ -55,6 +55,7 @@ fn generate_sources(
             vec!["ruby"],
             Some(out_dir),
             Some(library_path),
+            None,
             false,
         )?;
     }
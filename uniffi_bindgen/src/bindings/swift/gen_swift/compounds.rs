@@ -61,6 +61,33 @@ impl CodeType for SequenceCodeType {
     }
 }
 
+pub struct SetCodeType {
+    inner: TypeIdentifier,
+}
+
+impl SetCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for SetCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Set<{}>", oracle.find(&self.inner).type_label(oracle))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Set{}", oracle.find(&self.inner).canonical_name(oracle))
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptySequence => "[]".into(),
+            _ => unreachable!(),
+        }
+    }
+}
+
 pub struct MapCodeType {
     key: TypeIdentifier,
     value: TypeIdentifier,
@@ -96,3 +123,96 @@ impl CodeType for MapCodeType {
         }
     }
 }
+
+pub struct TupleCodeType {
+    elements: Vec<TypeIdentifier>,
+}
+
+impl TupleCodeType {
+    pub fn new(elements: Vec<TypeIdentifier>) -> Self {
+        Self { elements }
+    }
+}
+
+impl CodeType for TupleCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        let elements = self
+            .elements
+            .iter()
+            .map(|t| oracle.find(t).type_label(oracle))
+            .collect::<Vec<_>>();
+        format!("({})", elements.join(", "))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        let elements = self
+            .elements
+            .iter()
+            .map(|t| oracle.find(t).canonical_name(oracle))
+            .collect::<String>();
+        format!("Tuple{}{elements}", self.elements.len())
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unreachable!()
+    }
+}
+
+pub struct ResultCodeType {
+    ok: TypeIdentifier,
+    err: TypeIdentifier,
+}
+
+impl ResultCodeType {
+    pub fn new(ok: TypeIdentifier, err: TypeIdentifier) -> Self {
+        Self { ok, err }
+    }
+}
+
+impl CodeType for ResultCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "Result<{}, {}>",
+            oracle.find(&self.ok).type_label(oracle),
+            oracle.find(&self.err).type_label(oracle)
+        )
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "Result{}{}",
+            oracle.find(&self.ok).canonical_name(oracle),
+            oracle.find(&self.err).canonical_name(oracle)
+        )
+    }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, _literal: &Literal) -> String {
+        unreachable!()
+    }
+}
+
+pub struct IteratorCodeType {
+    inner: TypeIdentifier,
+}
+
+impl IteratorCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for IteratorCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "RustIterator<{}>",
+            oracle.find(&self.inner).type_label(oracle)
+        )
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!(
+            "Iterator{}",
+            oracle.find(&self.inner).canonical_name(oracle)
+        )
+    }
+}
@@ -24,10 +24,11 @@ impl CodeType for EnumCodeType {
     }
 
     fn literal(&self, oracle: &dyn CodeOracle, literal: &Literal) -> String {
-        if let Literal::Enum(v, _) = literal {
-            format!(".{}", oracle.enum_variant_name(v))
-        } else {
-            unreachable!();
+        match literal {
+            Literal::Enum(v, _) | Literal::EmptyVariant(v, _) => {
+                format!(".{}", oracle.enum_variant_name(v))
+            }
+            _ => unreachable!(),
         }
     }
 }
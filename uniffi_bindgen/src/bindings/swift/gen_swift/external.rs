@@ -3,14 +3,16 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use crate::backend::{CodeOracle, CodeType};
+use crate::interface::ExternalKind;
 
 pub struct ExternalCodeType {
     name: String,
+    kind: ExternalKind,
 }
 
 impl ExternalCodeType {
-    pub fn new(name: String) -> Self {
-        ExternalCodeType { name }
+    pub fn new(name: String, kind: ExternalKind) -> Self {
+        ExternalCodeType { name, kind }
     }
 }
 
@@ -20,6 +22,9 @@ impl CodeType for ExternalCodeType {
     }
 
     fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
-        format!("Type{}", self.name)
+        match self.kind {
+            ExternalKind::CallbackInterface => format!("CallbackInterface{}", self.name),
+            ExternalKind::DataClass | ExternalKind::Error => format!("Type{}", self.name),
+        }
     }
 }
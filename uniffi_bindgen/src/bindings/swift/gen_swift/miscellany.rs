@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use crate::backend::{CodeOracle, CodeType};
+use crate::backend::{CodeOracle, CodeType, Literal};
 
 pub struct TimestampCodeType;
 
@@ -26,4 +26,95 @@ impl CodeType for DurationCodeType {
     fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
         "Duration".into()
     }
+
+    fn literal(&self, _oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        match literal {
+            Literal::Duration(secs, nanos) => format!("TimeInterval({secs}.{nanos:09})"),
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub struct OffsetDateTimeCodeType;
+
+impl CodeType for OffsetDateTimeCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "OffsetDateTime".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "OffsetDateTime".into()
+    }
+}
+
+pub struct BigIntCodeType;
+
+impl CodeType for BigIntCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "String".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "BigInt".into()
+    }
+}
+
+pub struct DecimalCodeType;
+
+impl CodeType for DecimalCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "Decimal".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "Decimal".into()
+    }
+}
+
+pub struct UrlCodeType;
+
+impl CodeType for UrlCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "URL".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "Url".into()
+    }
+}
+
+pub struct UuidCodeType;
+
+impl CodeType for UuidCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "UUID".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "Uuid".into()
+    }
+}
+
+pub struct JsonCodeType;
+
+impl CodeType for JsonCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "JsonValue".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "Json".into()
+    }
+}
+
+pub struct SharedSliceCodeType;
+
+impl CodeType for SharedSliceCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "SharedSlice".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "SharedSlice".into()
+    }
 }
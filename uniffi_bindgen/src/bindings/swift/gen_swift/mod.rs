@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 
 use super::Bindings;
 use crate::backend::{CodeOracle, CodeType, TemplateExpression, TypeIdentifier};
+use crate::bindings::keywords::{self, KeywordConfig};
 use crate::interface::*;
 use crate::MergeWith;
 
@@ -27,6 +28,65 @@ mod object;
 mod primitives;
 mod record;
 
+// The keywords of Swift which can't be used as identifiers unless quoted with backticks.
+const KEYWORDS: &[&str] = &[
+    "Any",
+    "Protocol",
+    "Self",
+    "Type",
+    "as",
+    "associatedtype",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "deinit",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "fallthrough",
+    "false",
+    "fileprivate",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "inout",
+    "internal",
+    "is",
+    "let",
+    "nil",
+    "open",
+    "operator",
+    "private",
+    "protocol",
+    "public",
+    "repeat",
+    "rethrows",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "subscript",
+    "super",
+    "switch",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "typealias",
+    "var",
+    "where",
+    "while",
+];
+
 /// Config options for the caller to customize the generated Swift.
 ///
 /// Note that this can only be used to control details of the Swift *that do not affect the underlying component*,
@@ -39,8 +99,11 @@ pub struct Config {
     ffi_module_filename: Option<String>,
     generate_module_map: Option<bool>,
     omit_argument_labels: Option<bool>,
+    log_forwarder: Option<bool>,
     #[serde(default)]
     custom_types: HashMap<String, CustomTypeConfig>,
+    #[serde(default)]
+    keywords: KeywordConfig,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -95,6 +158,14 @@ impl Config {
         }
     }
 
+    /// A copy of this config, loading the library `cdylib_name`.
+    pub(crate) fn with_cdylib_name(&self, cdylib_name: &str) -> Self {
+        Self {
+            cdylib_name: Some(cdylib_name.to_string()),
+            ..self.clone()
+        }
+    }
+
     /// Whether to generate a `.modulemap` file for the lower-level C module with FFI declarations.
     pub fn generate_module_map(&self) -> bool {
         self.generate_module_map.unwrap_or(true)
@@ -104,6 +175,12 @@ impl Config {
     pub fn omit_argument_labels(&self) -> bool {
         self.omit_argument_labels.unwrap_or(false)
     }
+
+    /// Whether to generate the `installLogForwarder()` function, which forwards the records of
+    /// the Rust `log` crate to `os_log`.
+    pub fn log_forwarder(&self) -> bool {
+        self.log_forwarder.unwrap_or(false)
+    }
 }
 
 impl From<&ComponentInterface> for Config {
@@ -131,7 +208,9 @@ impl MergeWith for Config {
             omit_argument_labels: self
                 .omit_argument_labels
                 .merge_with(&other.omit_argument_labels),
+            log_forwarder: self.log_forwarder.merge_with(&other.log_forwarder),
             custom_types: self.custom_types.merge_with(&other.custom_types),
+            keywords: self.keywords.merge_with(&other.keywords),
         }
     }
 }
@@ -139,6 +218,10 @@ impl MergeWith for Config {
 /// Generate UniFFI component bindings for Swift, as strings in memory.
 ///
 pub fn generate_bindings(config: &Config, ci: &ComponentInterface) -> Result<Bindings> {
+    keywords::with_config(&config.keywords, || render_bindings(config, ci))
+}
+
+fn render_bindings(config: &Config, ci: &ComponentInterface) -> Result<Bindings> {
     let header = BridgingHeader::new(config, ci)
         .render()
         .context("failed to render Swift bridging header")?;
@@ -218,16 +301,13 @@ impl<'a> TypeRenderer<'a> {
 #[derive(Template)]
 #[template(syntax = "c", escape = "none", path = "BridgingHeaderTemplate.h")]
 pub struct BridgingHeader<'config, 'ci> {
-    _config: &'config Config,
+    config: &'config Config,
     ci: &'ci ComponentInterface,
 }
 
 impl<'config, 'ci> BridgingHeader<'config, 'ci> {
     pub fn new(config: &'config Config, ci: &'ci ComponentInterface) -> Self {
-        Self {
-            _config: config,
-            ci,
-        }
+        Self { config, ci }
     }
 }
 
@@ -308,13 +388,21 @@ impl SwiftCodeOracle {
             Type::Float32 => Box::new(primitives::Float32CodeType),
             Type::Float64 => Box::new(primitives::Float64CodeType),
             Type::Boolean => Box::new(primitives::BooleanCodeType),
+            Type::Char => Box::new(primitives::CharCodeType),
             Type::String => Box::new(primitives::StringCodeType),
 
             Type::Timestamp => Box::new(miscellany::TimestampCodeType),
+            Type::OffsetDateTime => Box::new(miscellany::OffsetDateTimeCodeType),
+            Type::BigInt => Box::new(miscellany::BigIntCodeType),
+            Type::Decimal => Box::new(miscellany::DecimalCodeType),
+            Type::Url => Box::new(miscellany::UrlCodeType),
+            Type::Uuid => Box::new(miscellany::UuidCodeType),
+            Type::Json => Box::new(miscellany::JsonCodeType),
+            Type::SharedSlice => Box::new(miscellany::SharedSliceCodeType),
             Type::Duration => Box::new(miscellany::DurationCodeType),
 
             Type::Enum(id) => Box::new(enum_::EnumCodeType::new(id)),
-            Type::Object(id) => Box::new(object::ObjectCodeType::new(id)),
+            Type::Object { name, .. } => Box::new(object::ObjectCodeType::new(name)),
             Type::Record(id) => Box::new(record::RecordCodeType::new(id)),
             Type::Error(id) => Box::new(error::ErrorCodeType::new(id)),
             Type::CallbackInterface(id) => {
@@ -323,8 +411,15 @@ impl SwiftCodeOracle {
 
             Type::Optional(inner) => Box::new(compounds::OptionalCodeType::new(*inner)),
             Type::Sequence(inner) => Box::new(compounds::SequenceCodeType::new(*inner)),
+            Type::Set(inner) => Box::new(compounds::SetCodeType::new(*inner)),
             Type::Map(key, value) => Box::new(compounds::MapCodeType::new(*key, *value)),
-            Type::External { name, .. } => Box::new(external::ExternalCodeType::new(name)),
+            Type::Tuple(elements) => Box::new(compounds::TupleCodeType::new(elements)),
+            Type::Result(ok, err) => Box::new(compounds::ResultCodeType::new(*ok, *err)),
+            Type::Weak(inner) => Box::new(object::WeakCodeType::new(*inner)),
+            Type::Iterator(inner) => Box::new(compounds::IteratorCodeType::new(*inner)),
+            Type::External { name, kind, .. } => {
+                Box::new(external::ExternalCodeType::new(name, kind))
+            }
             Type::Custom { name, .. } => Box::new(custom::CustomCodeType::new(name)),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling create_code_type")
@@ -333,6 +428,11 @@ impl SwiftCodeOracle {
     }
 }
 
+// Names are quoted with backticks, so reserved words are only renamed when configured to.
+fn fixup_keyword(name: String) -> String {
+    keywords::fixup(name, KEYWORDS, None)
+}
+
 impl CodeOracle for SwiftCodeOracle {
     fn find(&self, type_: &TypeIdentifier) -> Box<dyn CodeType> {
         self.create_code_type(type_.clone())
@@ -345,17 +445,17 @@ impl CodeOracle for SwiftCodeOracle {
 
     /// Get the idiomatic Swift rendering of a function name.
     fn fn_name(&self, nm: &str) -> String {
-        format!("`{}`", nm.to_string().to_lower_camel_case())
+        format!("`{}`", fixup_keyword(nm.to_string().to_lower_camel_case()))
     }
 
     /// Get the idiomatic Swift rendering of a variable name.
     fn var_name(&self, nm: &str) -> String {
-        format!("`{}`", nm.to_string().to_lower_camel_case())
+        format!("`{}`", fixup_keyword(nm.to_string().to_lower_camel_case()))
     }
 
     /// Get the idiomatic Swift rendering of an individual enum variant.
     fn enum_variant_name(&self, nm: &str) -> String {
-        format!("`{}`", nm.to_string().to_lower_camel_case())
+        format!("`{}`", fixup_keyword(nm.to_string().to_lower_camel_case()))
     }
 
     /// Get the idiomatic Swift rendering of an exception name.
@@ -379,6 +479,7 @@ impl CodeOracle for SwiftCodeOracle {
             FFIType::RustBuffer => "RustBuffer".into(),
             FFIType::ForeignBytes => "ForeignBytes".into(),
             FFIType::ForeignCallback => "ForeignCallback  _Nonnull".to_string(),
+            FFIType::Struct(name) => format!("FfiStruct{}", self.class_name(name)),
         }
     }
 }
@@ -412,6 +513,16 @@ pub mod filters {
         Ok(format!("{}.write", codetype.ffi_converter_name(oracle())))
     }
 
+    /// Whether the `lower` function of a type throws, which is the case when it writes the value
+    /// into a `RustBuffer`, except for strings, which are encoded directly.
+    pub fn lower_throws(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(match type_ {
+            Type::String => false,
+            Type::Custom { builtin, .. } => lower_throws(builtin)?,
+            _ => FFIType::from(type_) == FFIType::RustBuffer,
+        })
+    }
+
     pub fn lift_fn(codetype: &impl CodeType) -> Result<String, askama::Error> {
         Ok(format!("{}.lift", codetype.ffi_converter_name(oracle())))
     }
@@ -420,6 +531,18 @@ pub mod filters {
         Ok(format!("{}.read", codetype.ffi_converter_name(oracle())))
     }
 
+    /// Whether a type is a `sequence<u8>`, which is passed as its raw bytes when it isn't inside
+    /// another value.
+    pub fn is_bytes(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::UInt8))
+    }
+
+    /// Whether a type is a `sequence<string>`, whose strings share their length prefix with the
+    /// compact encoding.
+    pub fn is_strings(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(matches!(type_, Type::Sequence(inner) if **inner == Type::String))
+    }
+
     pub fn literal_swift(
         literal: &Literal,
         codetype: &impl CodeType,
@@ -451,6 +574,7 @@ pub mod filters {
             FFIType::RustBuffer => "RustBuffer".into(),
             FFIType::ForeignBytes => "ForeignBytes".into(),
             FFIType::ForeignCallback => "ForeignCallback  _Nonnull".to_string(),
+            FFIType::Struct(name) => format!("FfiStruct{}", oracle().class_name(name)),
         })
     }
 
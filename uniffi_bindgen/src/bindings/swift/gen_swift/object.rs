@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use crate::backend::{CodeOracle, CodeType};
+use crate::backend::{CodeOracle, CodeType, TypeIdentifier};
 
 pub struct ObjectCodeType {
     id: String,
@@ -23,3 +23,23 @@ impl CodeType for ObjectCodeType {
         format!("Type{}", self.id)
     }
 }
+
+pub struct WeakCodeType {
+    inner: TypeIdentifier,
+}
+
+impl WeakCodeType {
+    pub fn new(inner: TypeIdentifier) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for WeakCodeType {
+    fn type_label(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Weak{}", oracle.find(&self.inner).type_label(oracle))
+    }
+
+    fn canonical_name(&self, oracle: &dyn CodeOracle) -> String {
+        format!("Weak{}", oracle.find(&self.inner).canonical_name(oracle))
+    }
+}
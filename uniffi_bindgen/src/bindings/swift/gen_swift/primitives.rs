@@ -74,6 +74,22 @@ macro_rules! impl_code_type_for_primitive {
 }
 
 impl_code_type_for_primitive!(BooleanCodeType, "Bool");
+
+pub struct CharCodeType;
+
+impl CodeType for CharCodeType {
+    fn type_label(&self, _oracle: &dyn CodeOracle) -> String {
+        "Unicode.Scalar".into()
+    }
+
+    fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
+        "Char".into()
+    }
+
+    fn literal(&self, oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        render_literal(oracle, literal)
+    }
+}
 impl_code_type_for_primitive!(StringCodeType, "String");
 impl_code_type_for_primitive!(Int8CodeType, "Int8");
 impl_code_type_for_primitive!(Int16CodeType, "Int16");
@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use crate::backend::{CodeOracle, CodeType};
+use crate::backend::{CodeOracle, CodeType, Literal};
 
 pub struct RecordCodeType {
     id: String,
@@ -22,4 +22,11 @@ impl CodeType for RecordCodeType {
     fn canonical_name(&self, _oracle: &dyn CodeOracle) -> String {
         format!("Type{}", self.id)
     }
+
+    fn literal(&self, oracle: &dyn CodeOracle, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptyRecord(_) => format!("{}()", self.type_label(oracle)),
+            _ => unreachable!(),
+        }
+    }
 }
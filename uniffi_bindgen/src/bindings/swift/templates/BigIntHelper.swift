@@ -0,0 +1,14 @@
+// Swift has no arbitrary-precision integer type, so big integers are strings of decimal digits.
+fileprivate struct FfiConverterBigInt: FfiConverterRustBuffer {
+    typealias SwiftType = String
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> String {
+        let len: Int32 = try readLength(&buf)
+        return String(bytes: try readBytes(&buf, count: Int(len)), encoding: String.Encoding.utf8)!
+    }
+
+    public static func write(_ value: String, into buf: inout [UInt8]) throws {
+        writeLength(&buf, Int32(value.utf8.count))
+        writeBytes(&buf, value.utf8)
+    }
+}
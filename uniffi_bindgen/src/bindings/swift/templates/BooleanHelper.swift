@@ -14,7 +14,7 @@ fileprivate struct FfiConverterBool : FfiConverter {
         return try lift(readInt(&buf))
     }
 
-    public static func write(_ value: Bool, into buf: inout [UInt8]) {
+    public static func write(_ value: Bool, into buf: inout [UInt8]) throws {
         writeInt(&buf, lower(value))
     }
 }
@@ -11,15 +11,15 @@
 // We ensure they are declared exactly once, with a header guard, UNIFFI_SHARED_H.
 #ifdef UNIFFI_SHARED_H
     // We also try to prevent mixing versions of shared uniffi header structs.
-    // If you add anything to the #else block, you must increment the version suffix in UNIFFI_SHARED_HEADER_V4
-    #ifndef UNIFFI_SHARED_HEADER_V4
+    // If you add anything to the #else block, you must increment the version suffix in UNIFFI_SHARED_HEADER_V5
+    #ifndef UNIFFI_SHARED_HEADER_V5
         #error Combining helper code from multiple versions of uniffi is not supported
-    #endif // ndef UNIFFI_SHARED_HEADER_V4
+    #endif // ndef UNIFFI_SHARED_HEADER_V5
 #else
 #define UNIFFI_SHARED_H
-#define UNIFFI_SHARED_HEADER_V4
+#define UNIFFI_SHARED_HEADER_V5
 // ⚠️ Attention: If you change this #else block (ending in `#endif // def UNIFFI_SHARED_H`) you *must* ⚠️
-// ⚠️ increment the version suffix in all instances of UNIFFI_SHARED_HEADER_V4 in this file.           ⚠️
+// ⚠️ increment the version suffix in all instances of UNIFFI_SHARED_HEADER_V5 in this file.           ⚠️
 
 typedef struct RustBuffer
 {
@@ -42,14 +42,46 @@ typedef struct RustCallStatus {
     RustBuffer errorBuf;
 } RustCallStatus;
 
+// An atomic flag, which the objects set once their Rust object is freed. Swift has no atomics of
+// its own before Swift 6.
+static inline bool uniffi_atomic_flag_test_and_set(bool *_Nonnull flag) {
+    return __atomic_exchange_n(flag, true, __ATOMIC_ACQ_REL);
+}
+
+static inline bool uniffi_atomic_flag_test(const bool *_Nonnull flag) {
+    return __atomic_load_n(flag, __ATOMIC_ACQUIRE);
+}
+
 // ⚠️ Attention: If you change this #else block (ending in `#endif // def UNIFFI_SHARED_H`) you *must* ⚠️
-// ⚠️ increment the version suffix in all instances of UNIFFI_SHARED_HEADER_V4 in this file.           ⚠️
+// ⚠️ increment the version suffix in all instances of UNIFFI_SHARED_HEADER_V5 in this file.           ⚠️
 #endif // def UNIFFI_SHARED_H
+{%- for rec in ci.record_definitions() %}
+{%- if rec.is_ffi_struct() %}
+
+// The fields of the `{{ rec.name()|class_name }}` record, which is passed by value.
+typedef struct FfiStruct{{ rec.name()|class_name }}
+{
+    {%- for field in rec.fields() %}
+    {%- let ffi_type = FFIType::from(field.type_()) %}
+    {{ ffi_type|ffi_type_name }} field{{ loop.index0 }};
+    {%- endfor %}
+} FfiStruct{{ rec.name()|class_name }};
+{%- endif %}
+{%- endfor %}
+
+// The version of the contract with the bindings, see `ComponentInterface::contract_version`.
+uint32_t {{ ci.ffi_contract_version_name() }}(void);
 
 {% for func in ci.iter_ffi_function_definitions() -%}
     {%- match func.return_type() -%}{%- when Some with (type_) %}{{ type_|ffi_type_name }}{% when None %}void{% endmatch %} {{ func.name() }}(
       {% call swift::arg_list_ffi_decl(func) %}
     );
 {% endfor -%}
+{%- if config.log_forwarder() %}
+// See `uniffi/src/ffi/foreignlog.rs` for documentation on this function.
+int8_t {{ ci.ffi_install_log_forwarder_name() }}(
+      void (*_Nonnull callback)(int32_t, const uint8_t *_Nonnull, int32_t, const uint8_t *_Nonnull, int32_t), int32_t max_level, RustCallStatus *_Nonnull out_status
+    );
+{%- endif %}
 
 {% import "macros.swift" as swift %}
@@ -41,6 +41,8 @@ fileprivate class UniFFICallbackHandleMap<T> {
         remove(handle: handle)
     }
 
+    // The removed object is released by the caller, after unlocking, since its `deinit` can call
+    // back into Rust, which can free callbacks on the same thread.
     @discardableResult
     func remove(handle: UniFFICallbackHandle) -> T? {
         lock.withLock {
@@ -55,6 +57,16 @@ fileprivate class UniFFICallbackHandleMap<T> {
     }
 }
 
+// The object holding the closure of a callback function in the handle map, which only keeps
+// objects. Each closure passed to Rust gets its own box, and so its own handle.
+fileprivate final class UniFFICallbackFunction<T> {
+    let function: T
+
+    init(_ function: T) {
+        self.function = function
+    }
+}
+
 // Magic number for the Rust proxy to call using the same mechanism as every other method,
 // to free the callback once it's dropped by Rust.
 private let IDX_CALLBACK_FREE: Int32 = 0
@@ -3,6 +3,14 @@
 {%- if self.include_once_check("CallbackInterfaceRuntime.swift") %}{%- include "CallbackInterfaceRuntime.swift" %}{%- endif %}
 
 // Declaration and FfiConverters for {{ type_name }} Callback Interface
+{%- if cbi.is_function() %}
+{%- for meth in cbi.methods() %}
+
+public typealias {{ type_name }} = (
+    {%- for arg in meth.arguments() %}{{ arg|type_name }}{% if !loop.last %}, {% endif %}{% endfor -%}
+    ){% if meth.throws() %} throws{% endif %} -> {% match meth.return_type() %}{% when Some with (return_type) %}{{ return_type|type_name }}{% else %}Void{% endmatch %}
+{%- endfor %}
+{%- else %}
 
 public protocol {{ type_name }} : AnyObject {
     {% for meth in cbi.methods() -%}
@@ -13,6 +21,7 @@ public protocol {{ type_name }} : AnyObject {
     {%- endmatch %}
     {% endfor %}
 }
+{%- endif %}
 
 // The ForeignCallback that is passed to Rust.
 fileprivate let {{ foreign_callback }} : ForeignCallback =
@@ -29,23 +38,23 @@ fileprivate let {{ foreign_callback }} : ForeignCallback =
             var reader = createReader(data: Data(rustBuffer: args))
             {% if meth.return_type().is_some() %}let result = {% endif -%}
             {% if meth.throws() %}try {% endif -%}
-            swiftCallbackInterface.{{ meth.name()|fn_name }}(
+            swiftCallbackInterface{% if !cbi.is_function() %}.{{ meth.name()|fn_name }}{% endif %}(
                     {% for arg in meth.arguments() -%}
-                    {% if !config.omit_argument_labels() %}{{ arg.name()|var_name }}: {% endif %} try {{ arg|read_fn }}(from: &reader)
+                    {% if !config.omit_argument_labels() && !cbi.is_function() %}{{ arg.name()|var_name }}: {% endif %} try {{ arg|read_fn }}(from: &reader)
                     {%- if !loop.last %}, {% endif %}
                     {% endfor -%}
                 )
             {% else %}
             {% if meth.return_type().is_some() %}let result = {% endif -%}
             {% if meth.throws() %}try {% endif -%}
-            swiftCallbackInterface.{{ meth.name()|fn_name }}()
+            swiftCallbackInterface{% if !cbi.is_function() %}.{{ meth.name()|fn_name }}{% endif %}()
             {% endif -%}
 
         {#- Packing up the return value into a RustBuffer #}
                 {%- match meth.return_type() -%}
                 {%- when Some with (return_type) -%}
                 var writer = [UInt8]()
-                {{ return_type|write_fn }}(result, into: &writer)
+                try UniffiClonedHandles.write { try {{ return_type|write_fn }}(result, into: &writer) }
                 return RustBuffer(bytes: writer)
                 {%- else -%}
                 return RustBuffer()
@@ -81,8 +90,13 @@ fileprivate let {{ foreign_callback }} : ForeignCallback =
                 {%- match meth.throws_type() %}
                 {%- when Some(error_type) %}
                 } catch let error as {{ error_type|type_name }} {
-                    out_buf.pointee = {{ error_type|lower_fn }}(error)
-                    return -2
+                    do {
+                        out_buf.pointee = try {{ error_type|lower_fn }}(error)
+                        return -2
+                    } catch let loweringError {
+                        out_buf.pointee = {{ Type::String.borrow()|lower_fn }}(String(describing: loweringError))
+                        return -1
+                    }
                 {%- else %}
                 {%- endmatch %}
                 } catch let error {
@@ -101,11 +115,14 @@ fileprivate let {{ foreign_callback }} : ForeignCallback =
     }
 
 // FFIConverter protocol for callback interfaces
-fileprivate struct {{ ffi_converter_name }} {
+// Public so the bindings of the components using this callback interface as an external type can
+// lower it.
+public struct {{ ffi_converter_name }} {
     // Initialize our callback method with the scaffolding code
     private static var callbackInitialized = false
     private static func initCallback() {
-        try! rustCall { (err: UnsafeMutablePointer<RustCallStatus>) in
+        // Swift only registers the callback once, so its handles always have the first registration.
+        _ = try! rustCall { (err: UnsafeMutablePointer<RustCallStatus>) in
                 {{ cbi.ffi_init_callback().name() }}({{ foreign_callback }}, err)
         }
     }
@@ -119,8 +136,14 @@ fileprivate struct {{ ffi_converter_name }} {
     static func drop(handle: UniFFICallbackHandle) {
         handleMap.remove(handle: handle)
     }
+    {%- if cbi.is_function() %}
+
+    // Closures aren't objects, so they are boxed in one to be kept in the handle map.
+    private static var handleMap = UniFFICallbackHandleMap<UniFFICallbackFunction<{{ type_name }}>>()
+    {%- else %}
 
     private static var handleMap = UniFFICallbackHandleMap<{{ type_name }}>()
+    {%- endif %}
 }
 
 extension {{ ffi_converter_name }} : FfiConverter {
@@ -133,7 +156,7 @@ extension {{ ffi_converter_name }} : FfiConverter {
         guard let callback = handleMap.get(handle: handle) else {
             throw UniffiInternalError.unexpectedStaleHandle
         }
-        return callback
+        return callback{% if cbi.is_function() %}.function{% endif %}
     }
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> SwiftType {
@@ -144,10 +167,10 @@ extension {{ ffi_converter_name }} : FfiConverter {
 
     public static func lower(_ v: SwiftType) -> UniFFICallbackHandle {
         ensureCallbackinitialized();
-        return handleMap.insert(obj: v)
+        return handleMap.insert(obj: {% if cbi.is_function() %}UniFFICallbackFunction(v){% else %}v{% endif %})
     }
 
-    public static func write(_ v: SwiftType, into buf: inout [UInt8]) {
+    public static func write(_ v: SwiftType, into buf: inout [UInt8]) throws {
         ensureCallbackinitialized();
         writeInt(&buf, lower(v))
     }
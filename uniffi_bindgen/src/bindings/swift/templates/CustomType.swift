@@ -12,16 +12,16 @@ public struct FfiConverterType{{ name }}: FfiConverter {
         return try {{ builtin|read_fn }}(from: &buf)
     }
 
-    public static func write(_ value: {{ name }}, into buf: inout [UInt8]) {
-        return {{ builtin|write_fn }}(value, into: &buf)
+    public static func write(_ value: {{ name }}, into buf: inout [UInt8]) throws {
+        return try {{ builtin|write_fn }}(value, into: &buf)
     }
 
     public static func lift(_ value: {{ ffi_type_name }}) throws -> {{ name }} {
         return try {{ builtin|lift_fn }}(value)
     }
 
-    public static func lower(_ value: {{ name }}) -> {{ ffi_type_name }} {
-        return {{ builtin|lower_fn }}(value)
+    public static func lower(_ value: {{ name }}) {% if builtin|lower_throws %}throws {% endif %}-> {{ ffi_type_name }} {
+        return {% if builtin|lower_throws %}try {% endif %}{{ builtin|lower_fn }}(value)
     }
 }
 
@@ -54,9 +54,9 @@ public struct FfiConverterType{{ name }}: FfiConverter {
         return {{ config.into_custom.render("builtinValue") }}
     }
 
-    public static func write(_ value: {{ name }}, into buf: inout [UInt8]) {
+    public static func write(_ value: {{ name }}, into buf: inout [UInt8]) throws {
         let builtinValue = {{ config.from_custom.render("value") }}
-        return {{ builtin|write_fn }}(builtinValue, into: &buf)
+        return try {{ builtin|write_fn }}(builtinValue, into: &buf)
     }
 
     public static func lift(_ value: {{ ffi_type_name }}) throws -> {{ name }} {
@@ -64,9 +64,9 @@ public struct FfiConverterType{{ name }}: FfiConverter {
         return {{ config.into_custom.render("builtinValue") }}
     }
 
-    public static func lower(_ value: {{ name }}) -> {{ ffi_type_name }} {
+    public static func lower(_ value: {{ name }}) {% if builtin|lower_throws %}throws {% endif %}-> {{ ffi_type_name }} {
         let builtinValue = {{ config.from_custom.render("value") }}
-        return {{ builtin|lower_fn }}(builtinValue)
+        return {% if builtin|lower_throws %}try {% endif %}{{ builtin|lower_fn }}(builtinValue)
     }
 }
 {%- endmatch %}
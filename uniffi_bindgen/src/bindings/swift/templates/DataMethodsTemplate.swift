@@ -0,0 +1,27 @@
+{#
+// Methods of records and enums, which are standalone functions in the FFI that receive the
+// record or enum first. Added to their type in an extension, for the current `type_`.
+#}
+{%- let methods = ci.methods_of(type_) %}
+{%- if !methods.is_empty() %}
+{%- let receiver = "try {}.lower(self)"|format(ffi_converter_name) %}
+
+extension {{ type_name }} {
+    {% for meth in methods -%}
+    {%- match meth.return_type() -%}
+
+    {%- when Some with (return_type) -%}
+    public func {{ meth.name()|fn_name }}({% call swift::arg_list_decl(meth) %}) {% call swift::throws(meth) %} -> {{ return_type|type_name }} {
+        return {% call swift::try(meth) %} {{ return_type|lift_fn }}(
+            {% call swift::to_ffi_call_with_prefix(receiver, meth) %}
+        )
+    }
+
+    {%- when None -%}
+    public func {{ meth.name()|fn_name }}({% call swift::arg_list_decl(meth) %}) {% call swift::throws(meth) %} {
+        {% call swift::to_ffi_call_with_prefix(receiver, meth) %}
+    }
+    {%- endmatch %}
+    {% endfor %}
+}
+{%- endif %}
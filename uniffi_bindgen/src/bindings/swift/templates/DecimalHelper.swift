@@ -0,0 +1,21 @@
+fileprivate struct FfiConverterDecimal: FfiConverterRustBuffer {
+    typealias SwiftType = Decimal
+
+    // Decimals are passed as strings, which don't depend on the current locale.
+    private static let locale = Locale(identifier: "en_US_POSIX")
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> Decimal {
+        let len: Int32 = try readLength(&buf)
+        let string = String(bytes: try readBytes(&buf, count: Int(len)), encoding: String.Encoding.utf8)!
+        guard let value = Decimal(string: string, locale: locale) else {
+            throw UniffiInternalError.invalidDecimal
+        }
+        return value
+    }
+
+    public static func write(_ value: Decimal, into buf: inout [UInt8]) throws {
+        let string = NSDecimalNumber(decimal: value).description(withLocale: locale)
+        writeLength(&buf, Int32(string.utf8.count))
+        writeBytes(&buf, string.utf8)
+    }
+}
@@ -7,7 +7,7 @@ fileprivate struct FfiConverterDuration: FfiConverterRustBuffer {
         return Double(seconds) + (Double(nanoseconds) / 1.0e9)
     }
 
-    public static func write(_ value: TimeInterval, into buf: inout [UInt8]) {
+    public static func write(_ value: TimeInterval, into buf: inout [UInt8]) throws {
         if value.rounded(.down) > Double(Int64.max) {
             fatalError("Duration overflow, exceeds max bounds supported by Uniffi")
         }
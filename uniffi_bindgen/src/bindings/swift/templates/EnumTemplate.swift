@@ -1,17 +1,16 @@
-// Note that we don't yet support `indirect` for enums.
-// See https://github.com/mozilla/uniffi-rs/issues/396 for further discussion.
 {%- let e = ci.get_enum_definition(name).unwrap() %}
-public enum {{ type_name }} {
+public {% if e.is_recursive() %}indirect {% endif %}enum {{ type_name }} {
     {% for variant in e.variants() %}
     case {{ variant.name()|enum_variant_swift }}{% if variant.fields().len() > 0 %}({% call swift::field_list_decl(variant) %}){% endif -%}
     {% endfor %}
 }
+{%- include "DataMethodsTemplate.swift" %}
 
 public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
     typealias SwiftType = {{ type_name }}
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
-        let variant: Int32 = try readInt(&buf)
+        let variant: Int32 = try readLength(&buf)
         switch variant {
         {% for variant in e.variants() %}
         case {{ loop.index }}: return .{{ variant.name()|enum_variant_swift }}{% if variant.has_fields() %}(
@@ -25,24 +24,24 @@ public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
         }
     }
 
-    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) {
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
         switch value {
         {% for variant in e.variants() %}
         {% if variant.has_fields() %}
         case let .{{ variant.name()|enum_variant_swift }}({% for field in variant.fields() %}{{ field.name()|var_name }}{%- if loop.last -%}{%- else -%},{%- endif -%}{% endfor %}):
-            writeInt(&buf, Int32({{ loop.index }}))
+            writeLength(&buf, Int32({{ loop.index }}))
             {% for field in variant.fields() -%}
-            {{ field|write_fn }}({{ field.name()|var_name }}, into: &buf)
+            try {{ field|write_fn }}({{ field.name()|var_name }}, into: &buf)
             {% endfor -%}
         {% else %}
         case .{{ variant.name()|enum_variant_swift }}:
-            writeInt(&buf, Int32({{ loop.index }}))
+            writeLength(&buf, Int32({{ loop.index }}))
         {% endif %}
         {%- endfor %}
         }
     }
 }
 
-{% if !contains_object_references %}
+{% if !contains_object_references && !contains_tuples %}
 extension {{ type_name }}: Equatable, Hashable {}
 {% endif %}
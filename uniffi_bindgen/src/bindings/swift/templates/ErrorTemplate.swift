@@ -19,7 +19,7 @@ public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
     typealias SwiftType = {{ type_name }}
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
-        let variant: Int32 = try readInt(&buf)
+        let variant: Int32 = try readLength(&buf)
         switch variant {
 
         {% if e.is_flat() %}
@@ -46,15 +46,15 @@ public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
         }
     }
 
-    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) {
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
         switch value {
 
         {% if e.is_flat() %}
 
         {% for variant in e.variants() %}
-        case let .{{ variant.name()|class_name }}(message):
-            writeInt(&buf, Int32({{ loop.index }}))
-            {{ Type::String.borrow()|write_fn }}(message, into: &buf)
+        // The message isn't written, the Rust side reads flat errors from their variant alone.
+        case .{{ variant.name()|class_name }}(_):
+            writeLength(&buf, Int32({{ loop.index }}))
         {%- endfor %}
 
         {% else %}
@@ -62,13 +62,13 @@ public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
         {% for variant in e.variants() %}
         {% if variant.has_fields() %}
         case let .{{ variant.name()|class_name }}({% for field in variant.fields() %}{{ field.name()|var_name }}{%- if loop.last -%}{%- else -%},{%- endif -%}{% endfor %}):
-            writeInt(&buf, Int32({{ loop.index }}))
+            writeLength(&buf, Int32({{ loop.index }}))
             {% for field in variant.fields() -%}
-            {{ field|write_fn }}({{ field.name()|var_name }}, into: &buf)
+            try {{ field|write_fn }}({{ field.name()|var_name }}, into: &buf)
             {% endfor -%}
         {% else %}
         case .{{ variant.name()|class_name }}:
-            writeInt(&buf, Int32({{ loop.index }}))
+            writeLength(&buf, Int32({{ loop.index }}))
         {% endif %}
         {%- endfor %}
 
@@ -77,7 +77,15 @@ public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
     }
 }
 
-{% if !contains_object_references %}
+extension {{ type_name }} {
+    // The key/value context which the Rust code attached to the last error thrown by a call on the
+    // current thread, see `storeErrorContext`.
+    public var errorContext: [String: String] {
+        return Thread.current.threadDictionary[uniffiErrorContextKey] as? [String: String] ?? [:]
+    }
+}
+
+{% if !contains_object_references && !contains_tuples %}
 extension {{ type_name }}: Equatable, Hashable {}
 {% endif %}
 extension {{ type_name }}: Error { }
@@ -6,7 +6,7 @@ fileprivate struct FfiConverterFloat: FfiConverterPrimitive {
         return try lift(readFloat(&buf))
     }
 
-    public static func write(_ value: Float, into buf: inout [UInt8]) {
+    public static func write(_ value: Float, into buf: inout [UInt8]) throws {
         writeFloat(&buf, lower(value))
     }
 }
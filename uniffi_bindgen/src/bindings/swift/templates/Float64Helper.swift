@@ -6,7 +6,7 @@ fileprivate struct FfiConverterDouble: FfiConverterPrimitive {
         return try lift(readDouble(&buf))
     }
 
-    public static func write(_ value: Double, into buf: inout [UInt8]) {
+    public static func write(_ value: Double, into buf: inout [UInt8]) throws {
         writeDouble(&buf, lower(value))
     }
 }
@@ -4,11 +4,18 @@ fileprivate enum UniffiInternalError: LocalizedError {
     case bufferOverflow
     case incompleteData
     case unexpectedOptionalTag
+    case unexpectedResultTag
     case unexpectedEnumCase
     case unexpectedNullPointer
     case unexpectedRustCallStatusCode
     case unexpectedRustCallError
     case unexpectedStaleHandle
+    case invalidUnicodeScalar
+    case invalidUtcOffset
+    case invalidDecimal
+    case invalidUrl
+    case invalidVarint
+    case objectDestroyed(_ typeName: String)
     case rustPanic(_ message: String)
 
     public var errorDescription: String? {
@@ -16,19 +23,40 @@ fileprivate enum UniffiInternalError: LocalizedError {
         case .bufferOverflow: return "Reading the requested value would read past the end of the buffer"
         case .incompleteData: return "The buffer still has data after lifting its containing value"
         case .unexpectedOptionalTag: return "Unexpected optional tag; should be 0 or 1"
+        case .unexpectedResultTag: return "Unexpected result tag; should be 0 or 1"
         case .unexpectedEnumCase: return "Raw enum value doesn't match any cases"
         case .unexpectedNullPointer: return "Raw pointer value was null"
         case .unexpectedRustCallStatusCode: return "Unexpected RustCallStatus code"
         case .unexpectedRustCallError: return "CALL_ERROR but no errorClass specified"
         case .unexpectedStaleHandle: return "The object in the handle map has been dropped already"
+        case .invalidUnicodeScalar: return "Raw char value isn't a Unicode scalar value"
+        case .invalidUtcOffset: return "Raw UTC offset isn't supported by TimeZone"
+        case .invalidDecimal: return "Raw decimal string can't be parsed as a Decimal"
+        case .invalidUrl: return "Raw URL string can't be parsed as a URL"
+        case .invalidVarint: return "Raw varint is too long, or out of range for its type"
+        case let .objectDestroyed(typeName): return "\(typeName) object has already been destroyed"
         case let .rustPanic(message): return message
         }
     }
 }
 
+// A RustBuffer going over the limits set by the Rust code, rather than being allocated. `size` is
+// the size of the buffer, or the total size of the live buffers with it, which goes over `limit`,
+// in bytes.
+public struct PayloadTooLargeError: LocalizedError {
+    public let message: String
+    public let size: UInt64
+    public let limit: UInt64
+
+    public var errorDescription: String? {
+        return message
+    }
+}
+
 fileprivate let CALL_SUCCESS: Int8 = 0
 fileprivate let CALL_ERROR: Int8 = 1
 fileprivate let CALL_PANIC: Int8 = 2
+fileprivate let CALL_PAYLOAD_TOO_LARGE: Int8 = 3
 
 fileprivate extension RustCallStatus {
     init() {
@@ -43,7 +71,7 @@ fileprivate extension RustCallStatus {
     }
 }
 
-private func rustCall<T>(_ callback: (UnsafeMutablePointer<RustCallStatus>) -> T) throws -> T {
+private func rustCall<T>(_ callback: (UnsafeMutablePointer<RustCallStatus>) throws -> T) throws -> T {
     try makeRustCall(callback, errorHandler: {
         $0.deallocate()
         return UniffiInternalError.unexpectedRustCallError
@@ -51,15 +79,119 @@ private func rustCall<T>(_ callback: (UnsafeMutablePointer<RustCallStatus>) -> T
 }
 
 private func rustCallWithError<T, F: FfiConverter>
-    (_ errorFfiConverter: F.Type, _ callback: (UnsafeMutablePointer<RustCallStatus>) -> T) throws -> T
+    (_ errorFfiConverter: F.Type, _ callback: (UnsafeMutablePointer<RustCallStatus>) throws -> T) throws -> T
     where F.SwiftType: Error, F.FfiType == RustBuffer
     {
-    try makeRustCall(callback, errorHandler: { return try errorFfiConverter.lift($0) })
+    try makeRustCall(callback, errorHandler: { errorBuf in
+        var reader = createReader(data: Data(rustBuffer: errorBuf))
+        let error = try errorFfiConverter.read(from: &reader)
+        try storeErrorContext(from: &reader)
+        if hasRemaining(reader) {
+            throw UniffiInternalError.incompleteData
+        }
+        errorBuf.deallocate()
+        return error
+    })
 }
 
-private func makeRustCall<T>(_ callback: (UnsafeMutablePointer<RustCallStatus>) -> T, errorHandler: (RustBuffer) throws -> Error) throws -> T {
+// Swift errors are enums, which can't hold the key/value context which the Rust code attached to
+// them: the context of the last error thrown by a call is kept for its thread instead, under a key
+// shared by all the components, see the `errorContext` property of the error types.
+fileprivate let uniffiErrorContextKey = "uniffi.errorContext"
+
+// Likewise, where the Rust code panicked and the backtrace of the panic are kept for the thread
+// which the last panic was raised on, as strings which are empty if unknown.
+fileprivate let uniffiPanicLocationKey = "uniffi.panicLocation"
+fileprivate let uniffiPanicBacktraceKey = "uniffi.panicBacktrace"
+
+// Reads the context which follows an error, unless empty, and keeps it for the current thread.
+private func storeErrorContext(from reader: inout (data: Data, offset: Data.Index)) throws {
+    var errorContext: [String: String] = [:]
+    if hasRemaining(reader) {
+        let len: Int32 = try readLength(&reader)
+        for _ in 0..<len {
+            let key = try {{ Type::String.borrow()|read_fn }}(from: &reader)
+            errorContext[key] = try {{ Type::String.borrow()|read_fn }}(from: &reader)
+        }
+    }
+    // The sources of the errors declared with `[Source]` come next, which aren't exposed in Swift.
+    if hasRemaining(reader) {
+        let len: Int32 = try readLength(&reader)
+        for _ in 0..<len {
+            _ = try {{ Type::String.borrow()|read_fn }}(from: &reader)
+        }
+    }
+    Thread.current.threadDictionary[uniffiErrorContextKey] = errorContext
+}
+
+// The error buffer of a panic holds its message, followed by its location and its backtrace, which
+// are empty when unknown, and its context.
+private func liftPanicMessage(_ errorBuf: RustBuffer) throws -> String {
+    defer { errorBuf.deallocate() }
+    var reader = createReader(data: Data(rustBuffer: errorBuf))
+    let message = try {{ Type::String.borrow()|read_fn }}(from: &reader)
+    let location = try {{ Type::String.borrow()|read_fn }}(from: &reader)
+    let backtrace = try {{ Type::String.borrow()|read_fn }}(from: &reader)
+    try storeErrorContext(from: &reader)
+    Thread.current.threadDictionary[uniffiPanicLocationKey] = location
+    Thread.current.threadDictionary[uniffiPanicBacktraceKey] = backtrace
+    return message
+}
+
+// The error buffer of a RustBuffer going over the limits holds its message, size and limit, and its
+// context.
+private func liftPayloadTooLarge(_ errorBuf: RustBuffer) throws -> PayloadTooLargeError {
+    defer { errorBuf.deallocate() }
+    var reader = createReader(data: Data(rustBuffer: errorBuf))
+    let error = PayloadTooLargeError(
+        message: try {{ Type::String.borrow()|read_fn }}(from: &reader),
+        size: try readInt(&reader),
+        limit: try readInt(&reader)
+    )
+    try storeErrorContext(from: &reader)
+    return error
+}
+
+// Checks that the library was built from the same interface as these bindings, and with the same
+// version of UniFFI, once before the first call into it. Swift globals are initialized lazily, and
+// only once even with several threads.
+private let uniffiContractVersionCheck: Void = {
+    let bindingsContractVersion: UInt32 = {{ ci.contract_version() }}
+    let scaffoldingContractVersion = {{ ci.ffi_contract_version_name() }}()
+    if bindingsContractVersion != scaffoldingContractVersion {
+        fatalError("UniFFI contract version mismatch: the bindings of {{ ci.namespace() }} expect version 0x\(String(bindingsContractVersion, radix: 16)), but the library provides 0x\(String(scaffoldingContractVersion, radix: 16)). Regenerate the bindings with the version of uniffi-bindgen and the UDL file the library was built with.")
+    }
+}()
+
+{%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+// Runs the `#[uniffi::init]` hooks of the library once, before the first call into it, and
+// registers its `#[uniffi::shutdown]` hooks to run when the process exits. They are called without
+// `rustCall`, which runs this first, and whose error handling would call into the library again.
+private let uniffiLifecycleHooks: Void = {
+    {%- for func in ci.init_hooks() %}
+    uniffiCallHook { {{ func.name() }}($0) }
+    {%- endfor %}
+    {%- for func in ci.shutdown_hooks() %}
+    atexit { uniffiCallHook { {{ func.name() }}($0) } }
+    {%- endfor %}
+}()
+
+private func uniffiCallHook(_ hook: (UnsafeMutablePointer<RustCallStatus>) -> Void) {
+    var callStatus = RustCallStatus.init()
+    hook(&callStatus)
+    if callStatus.code != CALL_SUCCESS {
+        fatalError("A hook of the {{ ci.namespace() }} library panicked")
+    }
+}
+
+{% endif -%}
+private func makeRustCall<T>(_ callback: (UnsafeMutablePointer<RustCallStatus>) throws -> T, errorHandler: (RustBuffer) throws -> Error) throws -> T {
+    _ = uniffiContractVersionCheck
+    {%- if !ci.init_hooks().is_empty() || !ci.shutdown_hooks().is_empty() %}
+    _ = uniffiLifecycleHooks
+    {%- endif %}
     var callStatus = RustCallStatus.init()
-    let returnedVal = callback(&callStatus)
+    let returnedVal = try callback(&callStatus)
     switch callStatus.code {
         case CALL_SUCCESS:
             return returnedVal
@@ -72,12 +204,15 @@ private func makeRustCall<T>(_ callback: (UnsafeMutablePointer<RustCallStatus>)
             // with the message.  But if that code panics, then it just sends back
             // an empty buffer.
             if callStatus.errorBuf.len > 0 {
-                throw UniffiInternalError.rustPanic(try {{ Type::String.borrow()|lift_fn }}(callStatus.errorBuf))
+                throw UniffiInternalError.rustPanic(try liftPanicMessage(callStatus.errorBuf))
             } else {
                 callStatus.errorBuf.deallocate()
                 throw UniffiInternalError.rustPanic("Rust panic")
             }
 
+        case CALL_PAYLOAD_TOO_LARGE:
+            throw try liftPayloadTooLarge(callStatus.errorBuf)
+
         default:
             throw UniffiInternalError.unexpectedRustCallStatusCode
     }
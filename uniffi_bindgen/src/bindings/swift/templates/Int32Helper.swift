@@ -3,10 +3,18 @@ fileprivate struct FfiConverterInt32: FfiConverterPrimitive {
     typealias SwiftType = Int32
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> Int32 {
+        {%- if ci.compact_encoding() %}
+        return try lift(readCompactInt(&buf))
+        {%- else %}
         return try lift(readInt(&buf))
+        {%- endif %}
     }
 
-    public static func write(_ value: Int32, into buf: inout [UInt8]) {
+    public static func write(_ value: Int32, into buf: inout [UInt8]) throws {
+        {%- if ci.compact_encoding() %}
+        writeCompactInt(&buf, lower(value))
+        {%- else %}
         writeInt(&buf, lower(value))
+        {%- endif %}
     }
 }
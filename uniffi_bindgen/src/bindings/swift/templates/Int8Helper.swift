@@ -6,7 +6,7 @@ fileprivate struct FfiConverterInt8: FfiConverterPrimitive {
         return try lift(readInt(&buf))
     }
 
-    public static func write(_ value: Int8, into buf: inout [UInt8]) {
+    public static func write(_ value: Int8, into buf: inout [UInt8]) throws {
         writeInt(&buf, lower(value))
     }
 }
@@ -0,0 +1,16 @@
+
+// The base class of the iterators returned by Rust, which produces their items lazily,
+// one at a time over the FFI.
+public class RustIterator<Element>: Sequence, IteratorProtocol {
+    // The handle of the underlying Rust iterator, which Rust checks whenever it's used.
+    fileprivate let handle: UInt64
+
+    fileprivate init(unsafeFromHandle handle: UInt64) {
+        self.handle = handle
+    }
+
+    // Get the next item from Rust, or `nil` at the end of the iterator.
+    public func next() -> Element? {
+        fatalError("RustIterator.next() is implemented by the iterators of each item type")
+    }
+}
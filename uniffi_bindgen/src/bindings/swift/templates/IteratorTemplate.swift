@@ -0,0 +1,37 @@
+{%- let item_type_name = item_type|type_name %}
+{%- let next_type = ci.ffi_iterator_next_type(type_) %}
+{%- if self.include_once_check("IteratorRuntime.swift") %}{%- include "IteratorRuntime.swift" %}{%- endif %}
+
+// The iterator over `{{ item_type_name }}` items returned by Rust.
+fileprivate final class {{ canonical_type_name }}: RustIterator<{{ item_type_name }}> {
+    deinit {
+        try! rustCall { {{ ci.ffi_iterator_free(type_).name() }}(handle, $0) }
+    }
+
+    override func next() -> {{ item_type_name }}? {
+        return try! {{ next_type.borrow()|lift_fn }}(
+            try! rustCall { {{ ci.ffi_iterator_next(type_).name() }}(self.handle, $0) }
+        )
+    }
+}
+
+public struct {{ ffi_converter_name }}: FfiConverter {
+    typealias FfiType = UInt64
+    typealias SwiftType = {{ type_name }}
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
+        return try lift(try readInt(&buf))
+    }
+
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
+        fatalError("Iterators can only be returned by Rust")
+    }
+
+    public static func lift(_ handle: UInt64) throws -> {{ type_name }} {
+        return {{ canonical_type_name }}(unsafeFromHandle: handle)
+    }
+
+    public static func lower(_ value: {{ type_name }}) -> UInt64 {
+        return value.handle
+    }
+}
@@ -0,0 +1,62 @@
+/**
+ * A JSON value, as passed to and from a `serde_json::Value` in Rust.
+ */
+public enum JsonValue: Equatable, Hashable, Codable {
+    case null
+    case bool(Bool)
+    case integer(Int64)
+    case double(Double)
+    case string(String)
+    case array([JsonValue])
+    case object([String: JsonValue])
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        // Booleans are tried before numbers, which some decoders also read them as.
+        if container.decodeNil() {
+            self = .null
+        } else if let value = try? container.decode(Bool.self) {
+            self = .bool(value)
+        } else if let value = try? container.decode(Int64.self) {
+            self = .integer(value)
+        } else if let value = try? container.decode(Double.self) {
+            self = .double(value)
+        } else if let value = try? container.decode(String.self) {
+            self = .string(value)
+        } else if let value = try? container.decode([JsonValue].self) {
+            self = .array(value)
+        } else {
+            self = .object(try container.decode([String: JsonValue].self))
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .null: try container.encodeNil()
+        case let .bool(value): try container.encode(value)
+        case let .integer(value): try container.encode(value)
+        case let .double(value): try container.encode(value)
+        case let .string(value): try container.encode(value)
+        case let .array(value): try container.encode(value)
+        case let .object(value): try container.encode(value)
+        }
+    }
+}
+
+fileprivate struct FfiConverterJson: FfiConverterRustBuffer {
+    typealias SwiftType = JsonValue
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> JsonValue {
+        let len: Int32 = try readLength(&buf)
+        let bytes = try readBytes(&buf, count: Int(len))
+        return try JSONDecoder().decode(JsonValue.self, from: Data(bytes))
+    }
+
+    public static func write(_ value: JsonValue, into buf: inout [UInt8]) throws {
+        // Encoding only fails for NaN and infinite doubles, which aren't valid JSON.
+        let data = try! JSONEncoder().encode(value)
+        writeLength(&buf, Int32(data.count))
+        writeBytes(&buf, data)
+    }
+}
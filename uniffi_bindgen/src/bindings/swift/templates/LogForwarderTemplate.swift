@@ -0,0 +1,48 @@
+{%- let install_fn = ci.ffi_install_log_forwarder_name() %}
+
+// The levels of the records of the Rust `log` crate, from the least to the most verbose.
+public enum RustLogLevel: Int32 {
+    case error = 1
+    case warn
+    case info
+    case debug
+    case trace
+}
+
+public typealias RustLogger = (_ level: RustLogLevel, _ target: String, _ message: String) -> Void
+
+// Rust may call the forwarder from any thread, but the logger is only set before installing it.
+fileprivate var uniffiLogger: RustLogger = logToOSLog
+
+// Log to `os_log`, with the Rust target as the category.
+fileprivate func logToOSLog(level: RustLogLevel, target: String, message: String) {
+    let type: OSLogType
+    switch level {
+    case .error: type = .error
+    case .warn: type = .default
+    case .info: type = .info
+    case .debug, .trace: type = .debug
+    }
+    os_log("%{public}@", log: OSLog(subsystem: "{{ config.module_name() }}", category: target), type: type, message)
+}
+
+/// Forward the records of the Rust `log` crate to `logger`, which defaults to `os_log` with the
+/// Rust target as the category.
+///
+/// The records more verbose than `maxLevel` are dropped in Rust, without calling into Swift. The
+/// logger may be called from any thread.
+///
+/// Returns `false` if another logger is already installed for the Rust `log` crate.
+@discardableResult
+public func installLogForwarder(maxLevel: RustLogLevel = .info, logger: RustLogger? = nil) -> Bool {
+    uniffiLogger = logger ?? logToOSLog
+    let installed = try! rustCall {
+        {{ install_fn }}({ level, target, targetLen, message, messageLen in
+            // The target and the message are UTF-8 bytes, valid for the duration of the call only.
+            let target = String(decoding: UnsafeBufferPointer(start: target, count: Int(targetLen)), as: UTF8.self)
+            let message = String(decoding: UnsafeBufferPointer(start: message, count: Int(messageLen)), as: UTF8.self)
+            uniffiLogger(RustLogLevel(rawValue: level) ?? .trace, target, message)
+        }, maxLevel.rawValue, $0)
+    }
+    return installed != 0
+}
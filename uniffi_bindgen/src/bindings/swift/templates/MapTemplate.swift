@@ -1,15 +1,15 @@
 fileprivate struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
-    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) {
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
         let len = Int32(value.count)
-        writeInt(&buf, len)
+        writeLength(&buf, len)
         for (key, value) in value {
-            {{ key_type|write_fn }}(key, into: &buf)
-            {{ value_type|write_fn }}(value, into: &buf)
+            try {{ key_type|write_fn }}(key, into: &buf)
+            try {{ value_type|write_fn }}(value, into: &buf)
         }
     }
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
-        let len: Int32 = try readInt(&buf)
+        let len: Int32 = try readLength(&buf)
         var dict = {{ type_name }}()
         dict.reserveCapacity(Int(len))
         for _ in 0..<len {
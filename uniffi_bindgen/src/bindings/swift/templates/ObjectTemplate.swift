@@ -10,30 +10,64 @@ public protocol {{ obj.name() }}Protocol {
 }
 
 public class {{ type_name }}: {{ obj.name() }}Protocol {
-    fileprivate let pointer: UnsafeMutableRawPointer
+    // The handle of the underlying Rust object, which Rust checks whenever it's used.
+    private let handle: UInt64
+    // Whether the Rust object was freed, by `destroy()` or when this object is deinitialized. It's
+    // an atomic flag, allocated on its own to have a stable address.
+    private let destroyed: UnsafeMutablePointer<Bool> = {
+        let flag = UnsafeMutablePointer<Bool>.allocate(capacity: 1)
+        flag.initialize(to: false)
+        return flag
+    }()
 
     // TODO: We'd like this to be `private` but for Swifty reasons,
     // we can't implement `FfiConverter` without making this `required` and we can't
     // make it `required` without making it `public`.
-    required init(unsafeFromRawPointer pointer: UnsafeMutableRawPointer) {
-        self.pointer = pointer
+    required init(unsafeFromHandle handle: UInt64) {
+        self.handle = handle
     }
 
     {%- match obj.primary_constructor() %}
     {%- when Some with (cons) %}
     public convenience init({% call swift::arg_list_decl(cons) -%}) {% call swift::throws(cons) %} {
-        self.init(unsafeFromRawPointer: {% call swift::to_ffi_call(cons) %})
+        self.init(unsafeFromHandle: {% call swift::to_ffi_call(cons) %})
     }
     {%- when None %}
     {%- endmatch %}
 
     deinit {
-        try! rustCall { {{ obj.ffi_object_free().name() }}(pointer, $0) }
+        uniffiFree()
+        destroyed.deallocate()
+    }
+
+    {%- if !obj.has_method("destroy") %}
+
+    // Frees the Rust object right away, rather than once this object is deinitialized. Calling the
+    // methods of this object afterwards throws an error from the methods which throw, while the
+    // other ones, and passing this object to Rust, trap.
+    public func destroy() {
+        uniffiFree()
+    }
+    {%- endif %}
+
+    // Frees the Rust object, only the first time it's called.
+    private func uniffiFree() {
+        if !uniffi_atomic_flag_test_and_set(destroyed) {
+            try! rustCall { {{ obj.ffi_object_free().name() }}(handle, $0) }
+        }
+    }
+
+    // The handle of the Rust object, which throws once it's freed.
+    fileprivate func uniffiCheckedHandle() throws -> UInt64 {
+        if uniffi_atomic_flag_test(destroyed) {
+            throw UniffiInternalError.objectDestroyed("{{ type_name }}")
+        }
+        return handle
     }
 
     {% for cons in obj.alternate_constructors() %}
     public static func {{ cons.name()|fn_name }}({% call swift::arg_list_decl(cons) %}) {% call swift::throws(cons) %} -> {{ type_name }} {
-        return {{ type_name }}(unsafeFromRawPointer: {% call swift::to_ffi_call(cons) %})
+        return {{ type_name }}(unsafeFromHandle: {% call swift::to_ffi_call(cons) %})
     }
     {% endfor %}
 
@@ -43,14 +77,33 @@ public class {{ type_name }}: {{ obj.name() }}Protocol {
 
     {%- when Some with (return_type) -%}
     public func {{ meth.name()|fn_name }}({% call swift::arg_list_decl(meth) %}) {% call swift::throws(meth) %} -> {{ return_type|type_name }} {
+        let uniffiHandle = {% call swift::try(meth) %} self.uniffiCheckedHandle()
         return {% call swift::try(meth) %} {{ return_type|lift_fn }}(
-            {% call swift::to_ffi_call_with_prefix("self.pointer", meth) %}
+            {% call swift::to_ffi_call_with_prefix("uniffiHandle", meth) %}
         )
     }
 
     {%- when None -%}
     public func {{ meth.name()|fn_name }}({% call swift::arg_list_decl(meth) %}) {% call swift::throws(meth) %} {
-        {% call swift::to_ffi_call_with_prefix("self.pointer", meth) %}
+        let uniffiHandle = {% call swift::try(meth) %} self.uniffiCheckedHandle()
+        {% call swift::to_ffi_call_with_prefix("uniffiHandle", meth) %}
+    }
+    {%- endmatch %}
+    {% endfor %}
+
+    {%- for meth in obj.static_methods() %}
+    {%- match meth.return_type() -%}
+
+    {%- when Some with (return_type) %}
+    public static func {{ meth.name()|fn_name }}({% call swift::arg_list_decl(meth) %}) {% call swift::throws(meth) %} -> {{ return_type|type_name }} {
+        return {% call swift::try(meth) %} {{ return_type|lift_fn }}(
+            {% call swift::to_ffi_call(meth) %}
+        )
+    }
+
+    {%- when None %}
+    public static func {{ meth.name()|fn_name }}({% call swift::arg_list_decl(meth) %}) {% call swift::throws(meth) %} {
+        {% call swift::to_ffi_call(meth) %}
     }
     {%- endmatch %}
     {% endfor %}
@@ -58,31 +111,26 @@ public class {{ type_name }}: {{ obj.name() }}Protocol {
 
 
 public struct {{ ffi_converter_name }}: FfiConverter {
-    typealias FfiType = UnsafeMutableRawPointer
+    typealias FfiType = UInt64
     typealias SwiftType = {{ type_name }}
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
-        let v: UInt64 = try readInt(&buf)
-        // The Rust code won't compile if a pointer won't fit in a UInt64.
-        // We have to go via `UInt` because that's the thing that's the size of a pointer.
-        let ptr = UnsafeMutableRawPointer(bitPattern: UInt(truncatingIfNeeded: v))
-        if (ptr == nil) {
-            throw UniffiInternalError.unexpectedNullPointer
-        }
-        return try lift(ptr!)
+        return try lift(try readInt(&buf))
     }
 
-    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) {
-        // This fiddling is because `Int` is the thing that's the same size as a pointer.
-        // The Rust code won't compile if a pointer won't fit in a `UInt64`.
-        writeInt(&buf, UInt64(bitPattern: Int64(Int(bitPattern: lower(value)))))
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
+        let handle = try rustCall { {{ obj.ffi_object_clone().name() }}(lower(value), $0) }
+        UniffiClonedHandles.add {
+            _ = try? rustCall { {{ obj.ffi_object_free().name() }}(handle, $0) }
+        }
+        writeInt(&buf, handle)
     }
 
-    public static func lift(_ pointer: UnsafeMutableRawPointer) throws -> {{ type_name }} {
-        return {{ type_name}}(unsafeFromRawPointer: pointer)
+    public static func lift(_ handle: UInt64) throws -> {{ type_name }} {
+        return {{ type_name}}(unsafeFromHandle: handle)
     }
 
-    public static func lower(_ value: {{ type_name }}) -> UnsafeMutableRawPointer {
-        return value.pointer
+    public static func lower(_ value: {{ type_name }}) -> UInt64 {
+        return try! value.uniffiCheckedHandle()
     }
 }
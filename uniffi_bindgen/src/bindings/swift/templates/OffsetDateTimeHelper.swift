@@ -0,0 +1,41 @@
+/**
+ * A date with the time zone it was observed in, which only carries a fixed
+ * offset from GMT when lifted from Rust.
+ */
+public struct OffsetDateTime: Equatable, Hashable {
+    public var date: Date
+    public var timeZone: TimeZone
+
+    public init(date: Date, timeZone: TimeZone) {
+        self.date = date
+        self.timeZone = timeZone
+    }
+}
+
+fileprivate struct FfiConverterOffsetDateTime: FfiConverterRustBuffer {
+    typealias SwiftType = OffsetDateTime
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> OffsetDateTime {
+        let seconds: Int64 = try readInt(&buf)
+        let nanoseconds: UInt32 = try readInt(&buf)
+        let offset: Int32 = try readInt(&buf)
+        guard let timeZone = TimeZone(secondsFromGMT: Int(offset)) else {
+            throw UniffiInternalError.invalidUtcOffset
+        }
+        let delta = Double(seconds) + (Double(nanoseconds) / 1.0e9)
+        return OffsetDateTime(date: Date(timeIntervalSince1970: delta), timeZone: timeZone)
+    }
+
+    public static func write(_ value: OffsetDateTime, into buf: inout [UInt8]) throws {
+        let delta = value.date.timeIntervalSince1970
+        // The seconds are rounded towards negative infinity, so that the
+        // nanoseconds are always positive.
+        let seconds = delta.rounded(.down)
+        if seconds > Double(Int64.max) || seconds < Double(Int64.min) {
+            fatalError("OffsetDateTime overflow, exceeds the bounds supported by Uniffi")
+        }
+        writeInt(&buf, Int64(seconds))
+        writeInt(&buf, UInt32((delta - seconds) * 1.0e9))
+        writeInt(&buf, Int32(value.timeZone.secondsFromGMT(for: value.date)))
+    }
+}
@@ -1,13 +1,13 @@
 fileprivate struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
     typealias SwiftType = {{ type_name }}
 
-    public static func write(_ value: SwiftType, into buf: inout [UInt8]) {
+    public static func write(_ value: SwiftType, into buf: inout [UInt8]) throws {
         guard let value = value else {
             writeInt(&buf, Int8(0))
             return
         }
         writeInt(&buf, Int8(1))
-        {{ inner_type|write_fn }}(value, into: &buf)
+        try {{ inner_type|write_fn }}(value, into: &buf)
     }
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> SwiftType {
@@ -1,5 +1,10 @@
 {%- let rec = ci.get_record_definition(name).unwrap() %}
+{#- A struct can't contain itself, even through an optional, so recursive records are classes. #}
+{%- if rec.is_recursive() %}
+public final class {{ type_name }} {
+{%- else %}
 public struct {{ type_name }} {
+{%- endif %}
     {%- for field in rec.fields() %}
     public var {{ field.name()|var_name }}: {{ field|type_name }}
     {%- endfor %}
@@ -12,8 +17,9 @@ public struct {{ type_name }} {
         {%- endfor %}
     }
 }
+{%- include "DataMethodsTemplate.swift" %}
 
-{% if !contains_object_references %}
+{% if !contains_object_references && !contains_tuples %}
 extension {{ type_name }}: Equatable, Hashable {
     public static func ==(lhs: {{ type_name }}, rhs: {{ type_name }}) -> Bool {
         {%- for field in rec.fields() %}
@@ -32,7 +38,34 @@ extension {{ type_name }}: Equatable, Hashable {
 }
 {% endif %}
 
+{% if rec.is_ffi_struct() -%}
+// The record is made only of numbers and booleans, so it's passed as a C struct of the FFI values
+// of its fields, rather than serialized. It's still serialized inside other values.
+public struct {{ ffi_converter_name }}: FfiConverter {
+    typealias SwiftType = {{ type_name }}
+    typealias FfiType = FfiStruct{{ type_name }}
+
+    public static func lift(_ value: FfiStruct{{ type_name }}) throws -> {{ type_name }} {
+        return try {{ type_name }}(
+            {%- for field in rec.fields() %}
+            {{ field.name()|var_name }}: {{ field|lift_fn }}(value.field{{ loop.index0 }})
+            {%- if !loop.last %}, {% endif %}
+            {%- endfor %}
+        )
+    }
+
+    public static func lower(_ value: {{ type_name }}) throws -> FfiStruct{{ type_name }} {
+        return FfiStruct{{ type_name }}(
+            {%- for field in rec.fields() %}
+            field{{ loop.index0 }}: {{ field|lower_fn }}(value.{{ field.name()|var_name }})
+            {%- if !loop.last %}, {% endif %}
+            {%- endfor %}
+        )
+    }
+
+{% else -%}
 public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
+{% endif -%}
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
         return try {{ type_name }}(
             {%- for field in rec.fields() %}
@@ -42,9 +75,9 @@ public struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
         )
     }
 
-    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) {
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
         {%- for field in rec.fields() %}
-        {{ field|write_fn }}(value.{{ field.name()|var_name }}, into: &buf)
+        try {{ field|write_fn }}(value.{{ field.name()|var_name }}, into: &buf)
         {%- endfor %}
     }
 }
@@ -0,0 +1,22 @@
+fileprivate struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
+    typealias SwiftType = {{ type_name }}
+
+    public static func write(_ value: SwiftType, into buf: inout [UInt8]) throws {
+        switch value {
+        case let .success(value):
+            writeInt(&buf, Int8(0))
+            try {{ ok_type|write_fn }}(value, into: &buf)
+        case let .failure(error):
+            writeInt(&buf, Int8(1))
+            try {{ err_type|write_fn }}(error, into: &buf)
+        }
+    }
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> SwiftType {
+        switch try readInt(&buf) as Int8 {
+        case 0: return .success(try {{ ok_type|read_fn }}(from: &buf))
+        case 1: return .failure(try {{ err_type|read_fn }}(from: &buf))
+        default: throw UniffiInternalError.unexpectedResultTag
+        }
+    }
+}
@@ -135,6 +135,84 @@ fileprivate func writeDouble(_ writer: inout [UInt8], _ value: Double) {
     writeInt(&writer, value.bitPattern)
 }
 
+{%- if ci.compact_encoding() %}
+
+// The component has `compact_encoding = true` in its `uniffi.toml`: the
+// integers wider than a byte are written as varints (LEB128), zigzag-encoded if
+// they're signed, and so are the lengths of the strings, sequences and maps,
+// and the variants of the enums.
+
+fileprivate func readVarint(_ reader: inout (data: Data, offset: Data.Index)) throws -> UInt64 {
+    var value: UInt64 = 0
+    for shift in stride(from: UInt64(0), to: 64, by: 7) {
+        let byte: UInt8 = try readInt(&reader)
+        value |= UInt64(byte & 0x7f) << shift
+        if byte & 0x80 == 0 {
+            return value
+        }
+    }
+    throw UniffiInternalError.invalidVarint
+}
+
+fileprivate func writeVarint(_ writer: inout [UInt8], _ value: UInt64) {
+    var value = value
+    while value >= 0x80 {
+        writer.append(UInt8(truncatingIfNeeded: value) | 0x80)
+        value >>= 7
+    }
+    writer.append(UInt8(value))
+}
+
+// Reads an integer written with the compact encoding.
+fileprivate func readCompactInt<T: FixedWidthInteger>(_ reader: inout (data: Data, offset: Data.Index)) throws -> T {
+    let value = try readVarint(&reader)
+    let decoded: T?
+    if T.isSigned {
+        decoded = T(exactly: Int64(bitPattern: value >> 1) ^ -Int64(bitPattern: value & 1))
+    } else {
+        decoded = T(exactly: value)
+    }
+    guard let decoded = decoded else {
+        throw UniffiInternalError.invalidVarint
+    }
+    return decoded
+}
+
+// Writes an integer with the compact encoding.
+fileprivate func writeCompactInt<T: FixedWidthInteger>(_ writer: inout [UInt8], _ value: T) {
+    if T.isSigned {
+        let value = Int64(value)
+        writeVarint(&writer, UInt64(bitPattern: (value << 1) ^ (value >> 63)))
+    } else {
+        writeVarint(&writer, UInt64(value))
+    }
+}
+
+// Reads the length of a string, sequence or map, or the variant of an enum.
+fileprivate func readLength(_ reader: inout (data: Data, offset: Data.Index)) throws -> Int32 {
+    guard let value = Int32(exactly: try readVarint(&reader)) else {
+        throw UniffiInternalError.invalidVarint
+    }
+    return value
+}
+
+// Writes the length of a string, sequence or map, or the variant of an enum.
+fileprivate func writeLength(_ writer: inout [UInt8], _ value: Int32) {
+    writeVarint(&writer, UInt64(value))
+}
+{%- else %}
+
+// Reads the length of a string, sequence or map, or the variant of an enum.
+fileprivate func readLength(_ reader: inout (data: Data, offset: Data.Index)) throws -> Int32 {
+    return try readInt(&reader)
+}
+
+// Writes the length of a string, sequence or map, or the variant of an enum.
+fileprivate func writeLength(_ writer: inout [UInt8], _ value: Int32) {
+    writeInt(&writer, value)
+}
+{%- endif %}
+
 // Protocol for types that transfer other types across the FFI. This is
 // analogous go the Rust trait of the same name.
 fileprivate protocol FfiConverter {
@@ -142,9 +220,9 @@ fileprivate protocol FfiConverter {
     associatedtype SwiftType
 
     static func lift(_ value: FfiType) throws -> SwiftType
-    static func lower(_ value: SwiftType) -> FfiType
+    static func lower(_ value: SwiftType) throws -> FfiType
     static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> SwiftType
-    static func write(_ value: SwiftType, into buf: inout [UInt8])
+    static func write(_ value: SwiftType, into buf: inout [UInt8]) throws
 }
 
 // Types conforming to `Primitive` pass themselves directly over the FFI.
@@ -175,9 +253,43 @@ extension FfiConverterRustBuffer {
         return value
     }
 
-    public static func lower(_ value: SwiftType) -> RustBuffer {
+    public static func lower(_ value: SwiftType) throws -> RustBuffer {
           var writer = createWriter()
-          write(value, into: &writer)
+          try UniffiClonedHandles.write { try write(value, into: &writer) }
           return RustBuffer(bytes: writer)
     }
 }
+
+// The functions releasing the handles of the objects cloned for Rust while writing a value into a
+// `RustBuffer`, which are called if the writing fails, as Rust then never reads the buffer. They
+// are kept for the current thread, like the error context.
+fileprivate final class UniffiClonedHandles {
+    private static let key = "uniffi.{{ ci.namespace() }}.clonedHandles"
+
+    private var releases: [() -> Void] = []
+
+    private static var current: UniffiClonedHandles {
+        if let clonedHandles = Thread.current.threadDictionary[key] as? UniffiClonedHandles {
+            return clonedHandles
+        }
+        let clonedHandles = UniffiClonedHandles()
+        Thread.current.threadDictionary[key] = clonedHandles
+        return clonedHandles
+    }
+
+    static func add(_ release: @escaping () -> Void) {
+        current.releases.append(release)
+    }
+
+    static func write(_ body: () throws -> Void) throws {
+        let clonedHandles = current
+        let mark = clonedHandles.releases.count
+        defer { clonedHandles.releases.removeSubrange(mark...) }
+        do {
+            try body()
+        } catch {
+            clonedHandles.releases[mark...].forEach { $0() }
+            throw error
+        }
+    }
+}
@@ -1,16 +1,50 @@
 fileprivate struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
     typealias SwiftType = {{ type_name }}
+    {%- if ci.compact_encoding() && type_|is_strings %}
 
-    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) {
+    // With the compact encoding, the strings share their length prefix when they all have the same
+    // length: it's written once, plus one, after the count, and is zero otherwise.
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
+        writeLength(&buf, Int32(value.count))
+        guard let first = value.first else {
+            return
+        }
+        let shared = value.allSatisfy { $0.utf8.count == first.utf8.count }
+        writeLength(&buf, shared ? Int32(first.utf8.count) + 1 : 0)
+        for item in value {
+            if !shared {
+                writeLength(&buf, Int32(item.utf8.count))
+            }
+            writeBytes(&buf, item.utf8)
+        }
+    }
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
+        let len: Int32 = try readLength(&buf)
+        var seq = {{ type_name }}()
+        if len == 0 {
+            return seq
+        }
+        let sharedLength: Int32 = try readLength(&buf)
+        seq.reserveCapacity(Int(len))
+        for _ in 0 ..< len {
+            let itemLength = try sharedLength == 0 ? readLength(&buf) : sharedLength - 1
+            seq.append(String(bytes: try readBytes(&buf, count: Int(itemLength)), encoding: String.Encoding.utf8)!)
+        }
+        return seq
+    }
+    {%- else %}
+
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
         let len = Int32(value.count)
-        writeInt(&buf, len)
+        writeLength(&buf, len)
         for item in value {
-            {{ inner_type|write_fn }}(item, into: &buf)
+            try {{ inner_type|write_fn }}(item, into: &buf)
         }
     }
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
-        let len: Int32 = try readInt(&buf)
+        let len: Int32 = try readLength(&buf)
         var seq = {{ type_name }}()
         seq.reserveCapacity(Int(len))
         for _ in 0 ..< len {
@@ -18,4 +52,18 @@ fileprivate struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
         }
         return seq
     }
+    {%- endif %}
+    {%- if type_|is_bytes %}
+
+    // A `sequence<u8>` passed on its own, rather than inside another value, is passed as its raw
+    // bytes, without a length prefix.
+    public static func lift(_ buf: RustBuffer) throws -> {{ type_name }} {
+        defer { buf.deallocate() }
+        return [UInt8](Data(rustBuffer: buf))
+    }
+
+    public static func lower(_ value: {{ type_name }}) throws -> RustBuffer {
+        return RustBuffer(bytes: value)
+    }
+    {%- endif %}
 }
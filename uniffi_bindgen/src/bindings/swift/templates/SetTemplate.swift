@@ -0,0 +1,21 @@
+fileprivate struct {{ ffi_converter_name }}: FfiConverterRustBuffer {
+    typealias SwiftType = {{ type_name }}
+
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
+        let len = Int32(value.count)
+        writeLength(&buf, len)
+        for item in value {
+            try {{ inner_type|write_fn }}(item, into: &buf)
+        }
+    }
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
+        let len: Int32 = try readLength(&buf)
+        var set = {{ type_name }}()
+        set.reserveCapacity(Int(len))
+        for _ in 0 ..< len {
+            set.insert(try {{ inner_type|read_fn }}(from: &buf))
+        }
+        return set
+    }
+}
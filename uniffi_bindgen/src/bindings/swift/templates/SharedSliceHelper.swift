@@ -0,0 +1,49 @@
+// A region of read-only memory owned by Rust, which is read through `data` without copying it.
+//
+// `data` must not be used after the slice is released, with `release()` or when the slice is
+// deinitialized, so the slice must outlive it.
+public final class SharedSlice {
+    // The handle of the underlying Rust slice, which Rust checks whenever it's used.
+    fileprivate var handle: UInt64?
+    public let data: Data
+
+    fileprivate init(unsafeFromHandle handle: UInt64) {
+        let count = try! rustCall { {{ ci.ffi_shared_slice_len().name() }}(handle, $0) }
+        let bytes = try! rustCall { {{ ci.ffi_shared_slice_data().name() }}(handle, $0) }
+        self.handle = handle
+        self.data = Data(bytesNoCopy: bytes, count: Int(count), deallocator: .none)
+    }
+
+    deinit {
+        release()
+    }
+
+    // Frees the underlying Rust memory, after which `data` must not be used anymore.
+    public func release() {
+        if let handle = self.handle {
+            self.handle = nil
+            try! rustCall { {{ ci.ffi_shared_slice_free().name() }}(handle, $0) }
+        }
+    }
+}
+
+public struct FfiConverterSharedSlice: FfiConverter {
+    typealias FfiType = UInt64
+    typealias SwiftType = SharedSlice
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> SharedSlice {
+        return try lift(try readInt(&buf))
+    }
+
+    public static func write(_ value: SharedSlice, into buf: inout [UInt8]) throws {
+        fatalError("Shared slices can only be returned by Rust")
+    }
+
+    public static func lift(_ handle: UInt64) throws -> SharedSlice {
+        return SharedSlice(unsafeFromHandle: handle)
+    }
+
+    public static func lower(_ value: SharedSlice) -> UInt64 {
+        return value.handle!
+    }
+}
@@ -10,10 +10,26 @@ fileprivate struct FfiConverterString: FfiConverter {
             return String()
         }
         let bytes = UnsafeBufferPointer<UInt8>(start: value.data!, count: Int(value.len))
+        {%- if ci.utf16_strings() %}
+        // The strings lowered and lifted on their own are passed as little-endian UTF-16, which
+        // Rust converts, rather than transcoded to UTF-8 here.
+        let units = (0..<bytes.count / 2).map { UInt16(bytes[2 * $0]) | UInt16(bytes[2 * $0 + 1]) << 8 }
+        return String(decoding: units, as: UTF16.self)
+        {%- else %}
         return String(bytes: bytes, encoding: String.Encoding.utf8)!
+        {%- endif %}
     }
 
     public static func lower(_ value: String) -> RustBuffer {
+        {%- if ci.utf16_strings() %}
+        var bytes: [UInt8] = []
+        bytes.reserveCapacity(value.utf16.count * 2)
+        for unit in value.utf16 {
+            bytes.append(UInt8(truncatingIfNeeded: unit))
+            bytes.append(UInt8(truncatingIfNeeded: unit >> 8))
+        }
+        return bytes.withUnsafeBufferPointer { RustBuffer.from($0) }
+        {%- else %}
         return value.utf8CString.withUnsafeBufferPointer { ptr in
             // The swift string gives us int8_t, we want uint8_t.
             ptr.withMemoryRebound(to: UInt8.self) { ptr in
@@ -22,16 +38,17 @@ fileprivate struct FfiConverterString: FfiConverter {
                 return RustBuffer.from(buf)
             }
         }
+        {%- endif %}
     }
 
     public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> String {
-        let len: Int32 = try readInt(&buf)
+        let len: Int32 = try readLength(&buf)
         return String(bytes: try readBytes(&buf, count: Int(len)), encoding: String.Encoding.utf8)!
     }
 
-    public static func write(_ value: String, into buf: inout [UInt8]) {
+    public static func write(_ value: String, into buf: inout [UInt8]) throws {
         let len = Int32(value.utf8.count)
-        writeInt(&buf, len)
+        writeLength(&buf, len)
         writeBytes(&buf, value.utf8)
     }
 }
@@ -13,7 +13,7 @@ fileprivate struct FfiConverterTimestamp: FfiConverterRustBuffer {
         }
     }
 
-    public static func write(_ value: Date, into buf: inout [UInt8]) {
+    public static func write(_ value: Date, into buf: inout [UInt8]) throws {
         var delta = value.timeIntervalSince1970
         var sign: Int64 = 1
         if delta < 0 {
@@ -4,6 +4,8 @@
 {%- let ffi_converter_name = type_|ffi_converter_name %}
 {%- let canonical_type_name = type_|canonical_name %}
 {%- let contains_object_references = ci.item_contains_object_references(type_) %}
+{#- Swift tuples are neither `Equatable` nor `Hashable`. #}
+{%- let contains_tuples = ci.item_contains_tuples(type_) %}
 
 {#
  # Map `Type` instances to an include statement for that type.
@@ -19,6 +21,9 @@
 {%- when Type::Boolean %}
 {%- include "BooleanHelper.swift" %}
 
+{%- when Type::Char %}
+{%- include "CharHelper.swift" %}
+
 {%- when Type::String %}
 {%- include "StringHelper.swift" %}
 
@@ -58,6 +63,27 @@
 {%- when Type::Duration %}
 {%- include "DurationHelper.swift" %}
 
+{%- when Type::OffsetDateTime %}
+{%- include "OffsetDateTimeHelper.swift" %}
+
+{%- when Type::BigInt %}
+{%- include "BigIntHelper.swift" %}
+
+{%- when Type::Decimal %}
+{%- include "DecimalHelper.swift" %}
+
+{%- when Type::Url %}
+{%- include "UrlHelper.swift" %}
+
+{%- when Type::Uuid %}
+{%- include "UuidHelper.swift" %}
+
+{%- when Type::Json %}
+{%- include "JsonHelper.swift" %}
+
+{%- when Type::SharedSlice %}
+{%- include "SharedSliceHelper.swift" %}
+
 {%- when Type::CallbackInterface(name) %}
 {%- include "CallbackInterfaceTemplate.swift" %}
 
@@ -70,7 +96,7 @@
 {%- when Type::Error(name) %}
 {%- include "ErrorTemplate.swift" %}
 
-{%- when Type::Object(name) %}
+{%- when Type::Object { name, imp } %}
 {%- include "ObjectTemplate.swift" %}
 
 {%- when Type::Record(name) %}
@@ -82,9 +108,28 @@
 {%- when Type::Sequence(inner_type) %}
 {%- include "SequenceTemplate.swift" %}
 
+{%- when Type::Set(inner_type) %}
+{%- include "SetTemplate.swift" %}
+
 {%- when Type::Map(key_type, value_type) %}
 {%- include "MapTemplate.swift" %}
 
+{%- when Type::Tuple(element_types) %}
+{%- include "TupleTemplate.swift" %}
+
+{%- when Type::Result(ok_type, err_type) %}
+{%- include "ResultTemplate.swift" %}
+
+{%- when Type::Weak(inner_type) %}
+{%- match inner_type.as_ref() %}
+{%- when Type::Object { name, imp } %}
+{%- include "WeakTemplate.swift" %}
+{%- else %}
+{%- endmatch %}
+
+{%- when Type::Iterator(item_type) %}
+{%- include "IteratorTemplate.swift" %}
+
 {%- else %}
 {%- endmatch %}
 {%- endfor %}
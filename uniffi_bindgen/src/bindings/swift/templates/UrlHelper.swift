@@ -0,0 +1,18 @@
+fileprivate struct FfiConverterUrl: FfiConverterRustBuffer {
+    typealias SwiftType = URL
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> URL {
+        let len: Int32 = try readLength(&buf)
+        let string = String(bytes: try readBytes(&buf, count: Int(len)), encoding: String.Encoding.utf8)!
+        guard let value = URL(string: string) else {
+            throw UniffiInternalError.invalidUrl
+        }
+        return value
+    }
+
+    public static func write(_ value: URL, into buf: inout [UInt8]) throws {
+        let string = value.absoluteString
+        writeLength(&buf, Int32(string.utf8.count))
+        writeBytes(&buf, string.utf8)
+    }
+}
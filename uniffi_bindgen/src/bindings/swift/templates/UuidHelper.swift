@@ -0,0 +1,17 @@
+fileprivate struct FfiConverterUuid: FfiConverterRustBuffer {
+    typealias SwiftType = UUID
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> UUID {
+        let b = try readBytes(&buf, count: 16)
+        return UUID(uuid: (
+            b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+            b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+        ))
+    }
+
+    public static func write(_ value: UUID, into buf: inout [UInt8]) throws {
+        withUnsafeBytes(of: value.uuid) { bytes in
+            writeBytes(&buf, bytes)
+        }
+    }
+}
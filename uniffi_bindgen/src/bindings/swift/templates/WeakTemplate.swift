@@ -0,0 +1,47 @@
+{%- let obj = ci.get_object_definition(name).unwrap() %}
+{%- let upgrade_type = obj.weak_upgrade_type() %}
+// A weak reference to a `{{ name|class_name }}`, which doesn't keep it alive.
+public class {{ type_name }} {
+    // The handle of the underlying Rust weak reference, which Rust checks whenever it's used.
+    fileprivate let handle: UInt64
+
+    required init(unsafeFromHandle handle: UInt64) {
+        self.handle = handle
+    }
+
+    deinit {
+        try! rustCall { {{ obj.ffi_weak_free().name() }}(handle, $0) }
+    }
+
+    // Get the referenced object, or `nil` if it has already been dropped.
+    public func upgrade() -> {{ upgrade_type.borrow()|type_name }} {
+        return try! {{ upgrade_type.borrow()|lift_fn }}(
+            try! rustCall { {{ obj.ffi_weak_upgrade().name() }}(self.handle, $0) }
+        )
+    }
+}
+
+public struct {{ ffi_converter_name }}: FfiConverter {
+    typealias FfiType = UInt64
+    typealias SwiftType = {{ type_name }}
+
+    public static func read(from buf: inout (data: Data, offset: Data.Index)) throws -> {{ type_name }} {
+        return try lift(try readInt(&buf))
+    }
+
+    public static func write(_ value: {{ type_name }}, into buf: inout [UInt8]) throws {
+        let handle = try rustCall { {{ obj.ffi_weak_clone().name() }}(lower(value), $0) }
+        UniffiClonedHandles.add {
+            _ = try? rustCall { {{ obj.ffi_weak_free().name() }}(handle, $0) }
+        }
+        writeInt(&buf, handle)
+    }
+
+    public static func lift(_ handle: UInt64) throws -> {{ type_name }} {
+        return {{ type_name }}(unsafeFromHandle: handle)
+    }
+
+    public static func lower(_ value: {{ type_name }}) -> UInt64 {
+        return value.handle
+    }
+}
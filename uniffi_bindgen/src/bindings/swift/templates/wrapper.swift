@@ -2,6 +2,9 @@
 // Trust me, you don't want to mess with it!
 {%- import "macros.swift" as swift %}
 import Foundation
+{%- if config.log_forwarder() %}
+import os
+{%- endif %}
 {%- for imported_class in self.imports() %}
 import {{ imported_class }}
 {%- endfor %}
@@ -18,10 +21,56 @@ import {{ config.ffi_module_name() }}
 
 // Public interface members begin here.
 {{ type_helper_code }}
+{%- for constant in ci.constant_definitions() %}
+
+public let {{ constant.name()|var_name }}: {{ constant|type_name }} = {{ constant.value()|literal_swift(constant) }}
+{%- endfor %}
 
 {%- for func in ci.function_definitions() %}
 {%- include "TopLevelFunctionTemplate.swift" %}
 {%- endfor %}
+{%- if config.log_forwarder() %}
+{% include "LogForwarderTemplate.swift" %}
+{%- endif %}
+
+/**
+ * Describe the objects and buffers of this component which weren't freed yet, with the
+ * backtraces of their creation, when the `leak-detection` feature of uniffi is enabled.
+ */
+public func dumpLiveAllocations() -> String {
+    return try! {{ Type::String.borrow()|lift_fn }}(
+        try! rustCall { {{ ci.ffi_dump_live_allocations().name() }}($0) }
+    )
+}
+
+/**
+ * The counters of the RustBuffers of the Rust library, see `rustBufferStats()`.
+ */
+public struct RustBufferStats: Equatable {
+    // The number of buffers allocated and not freed yet.
+    public let liveBuffers: UInt64
+    // The total capacity of the live buffers, in bytes.
+    public let liveBytes: UInt64
+    // The highest total capacity of the live buffers so far, in bytes.
+    public let peakBytes: UInt64
+    // The number of buffers which weren't allocated because of the limits.
+    public let rejected: UInt64
+}
+
+/**
+ * Get the counters of the RustBuffers of the Rust library, which may set limits on them.
+ */
+public func rustBufferStats() -> RustBufferStats {
+    let rbuf = try! rustCall { {{ ci.ffi_rustbuffer_stats().name() }}($0) }
+    defer { rbuf.deallocate() }
+    var reader = createReader(data: Data(rustBuffer: rbuf))
+    return RustBufferStats(
+        liveBuffers: try! readInt(&reader),
+        liveBytes: try! readInt(&reader),
+        peakBytes: try! readInt(&reader),
+        rejected: try! readInt(&reader)
+    )
+}
 
 /**
  * Top level initializers and tear down methods.
@@ -146,6 +146,7 @@ impl GeneratedSources {
                 vec!["swift"],
                 Some(out_dir),
                 Some(library_path),
+                None,
                 false,
             )?;
         }
@@ -190,6 +191,7 @@ impl GeneratedSources {
             vec!["swift"],
             Some(out_dir),
             Some(library_path),
+            None,
             false,
         )
     }
@@ -16,6 +16,9 @@
 
 use anyhow::{bail, Result};
 
+use super::object::ObjectImpl;
+use super::types::ExternalKind;
+
 /// Represents an attribute parsed from UDL, like `[ByRef]` or `[Throws]`.
 ///
 /// This is a convenience enum for parsing UDL attributes and erroring out if we encounter
@@ -26,14 +29,37 @@ pub(super) enum Attribute {
     ByRef,
     Enum,
     Error,
+    // `[Source]` - The error sends the chain of its sources along with it, see
+    // `uniffi::record_error_sources`. Only supported with `[Error]`.
+    Source,
     Name(String),
     SelfType(SelfType),
+    // `[Static]` - The method is an associated function, without a `self` receiver.
+    Static,
     Threadsafe, // N.B. the `[Threadsafe]` attribute is deprecated and will be removed
     Throws(String),
+    // `[Trait]` - The interface is implemented by a Rust trait rather than a struct.
+    Trait,
+    // `[Instance=Name(Type T)]` - An instance of a generic interface, with its type parameters.
+    // Only the name is kept here, the types are resolved when converting the interface.
+    Instance(String),
+    // `[Method=Name]` - The namespace function is a method of the named dictionary or enum,
+    // which it receives as an implicit first argument.
+    Method(String),
+    // `[Rename="name"]` - The name of the field, variant or argument in the foreign language
+    // bindings, instead of its Rust name.
+    Rename(String),
     // `[External="crate_name"]` - We can `use crate_name::...` for the type.
-    External(String),
+    // `[ExternalError="crate_name"]` and `[ExternalCallbackInterface="crate_name"]` are the
+    // same, for the errors and callback interfaces of that crate.
+    External {
+        crate_name: String,
+        kind: ExternalKind,
+    },
     // Custom type on the scaffolding side
     Custom,
+    // `[Utf16Strings]` - The strings are passed as UTF-16, see `uniffi::Utf16String`.
+    Utf16Strings,
 }
 
 impl Attribute {
@@ -58,8 +84,12 @@ impl TryFrom<&weedle::attribute::ExtendedAttribute<'_>> for Attribute {
                 "ByRef" => Ok(Attribute::ByRef),
                 "Enum" => Ok(Attribute::Enum),
                 "Error" => Ok(Attribute::Error),
+                "Source" => Ok(Attribute::Source),
+                "Static" => Ok(Attribute::Static),
                 "Threadsafe" => Ok(Attribute::Threadsafe),
+                "Trait" => Ok(Attribute::Trait),
                 "Custom" => Ok(Attribute::Custom),
+                "Utf16Strings" => Ok(Attribute::Utf16Strings),
                 _ => anyhow::bail!("ExtendedAttributeNoArgs not supported: {:?}", (attr.0).0),
             },
             // Matches assignment-style attributes like ["Throws=Error"]
@@ -68,13 +98,36 @@ impl TryFrom<&weedle::attribute::ExtendedAttribute<'_>> for Attribute {
                     "Name" => Ok(Attribute::Name(name_from_id_or_string(&identity.rhs))),
                     "Throws" => Ok(Attribute::Throws(name_from_id_or_string(&identity.rhs))),
                     "Self" => Ok(Attribute::SelfType(SelfType::try_from(&identity.rhs)?)),
-                    "External" => Ok(Attribute::External(name_from_id_or_string(&identity.rhs))),
+                    "Method" => Ok(Attribute::Method(name_from_id_or_string(&identity.rhs))),
+                    "Rename" => Ok(Attribute::Rename(name_from_id_or_string(&identity.rhs))),
+                    "External" => Ok(Attribute::External {
+                        crate_name: name_from_id_or_string(&identity.rhs),
+                        kind: ExternalKind::DataClass,
+                    }),
+                    "ExternalError" => Ok(Attribute::External {
+                        crate_name: name_from_id_or_string(&identity.rhs),
+                        kind: ExternalKind::Error,
+                    }),
+                    "ExternalCallbackInterface" => Ok(Attribute::External {
+                        crate_name: name_from_id_or_string(&identity.rhs),
+                        kind: ExternalKind::CallbackInterface,
+                    }),
                     _ => anyhow::bail!(
                         "Attribute identity Identifier not supported: {:?}",
                         identity.lhs_identifier.0
                     ),
                 }
             }
+            // Matches named argument lists like ["Instance=StringCache(string T)"]
+            weedle::attribute::ExtendedAttribute::NamedArgList(list) => {
+                match list.lhs_identifier.0 {
+                    "Instance" => Ok(Attribute::Instance(list.rhs_identifier.0.to_string())),
+                    _ => anyhow::bail!(
+                        "Attribute named argument list not supported: {:?}",
+                        list.lhs_identifier.0
+                    ),
+                }
+            }
             _ => anyhow::bail!("Attribute not supported: {:?}", weedle_attribute),
         }
     }
@@ -117,8 +170,47 @@ where
     Ok(attrs)
 }
 
+/// Attributes that can be attached to the `namespace` definition in the UDL: `[Utf16Strings]` to
+/// pass strings as UTF-16.
+#[derive(Debug, Clone, Hash, Default)]
+pub(super) struct NamespaceAttributes(Vec<Attribute>);
+
+impl NamespaceAttributes {
+    pub(super) fn has_utf16_strings(&self) -> bool {
+        self.0
+            .iter()
+            .any(|attr| matches!(attr, Attribute::Utf16Strings))
+    }
+}
+
+impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for NamespaceAttributes {
+    type Error = anyhow::Error;
+    fn try_from(
+        weedle_attributes: &weedle::attribute::ExtendedAttributeList<'_>,
+    ) -> Result<Self, Self::Error> {
+        let attrs = parse_attributes(weedle_attributes, |attr| match attr {
+            Attribute::Utf16Strings => Ok(()),
+            _ => bail!(format!("{attr:?} not supported for namespaces")),
+        })?;
+        Ok(Self(attrs))
+    }
+}
+
+impl<T: TryInto<NamespaceAttributes, Error = anyhow::Error>> TryFrom<Option<T>>
+    for NamespaceAttributes
+{
+    type Error = anyhow::Error;
+    fn try_from(value: Option<T>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(Default::default()),
+            Some(v) => v.try_into(),
+        }
+    }
+}
+
 /// Attributes that can be attached to an `enum` definition in the UDL.
-/// There's only one case here: using `[Error]` to mark an enum as an error class.
+/// There's only one case here: using `[Error]` to mark an enum as an error class, optionally with
+/// `[Source]`.
 #[derive(Debug, Clone, Hash, Default)]
 pub(super) struct EnumAttributes(Vec<Attribute>);
 
@@ -126,6 +218,10 @@ impl EnumAttributes {
     pub fn contains_error_attr(&self) -> bool {
         self.0.iter().any(|attr| attr.is_error())
     }
+
+    pub fn contains_source_attr(&self) -> bool {
+        self.0.iter().any(|attr| matches!(attr, Attribute::Source))
+    }
 }
 
 impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for EnumAttributes {
@@ -135,9 +231,14 @@ impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for EnumAttributes {
     ) -> Result<Self, Self::Error> {
         let attrs = parse_attributes(weedle_attributes, |attr| match attr {
             Attribute::Error => Ok(()),
+            Attribute::Source => Ok(()),
             _ => bail!(format!("{attr:?} not supported for enums")),
         })?;
-        Ok(Self(attrs))
+        let attrs = Self(attrs);
+        if attrs.contains_source_attr() && !attrs.contains_error_attr() {
+            bail!("`[Source]` is only supported on errors");
+        }
+        Ok(attrs)
     }
 }
 
@@ -167,6 +268,13 @@ impl FunctionAttributes {
             _ => None,
         })
     }
+
+    pub(super) fn get_method_of(&self) -> Option<&str> {
+        self.0.iter().find_map(|attr| match attr {
+            Attribute::Method(inner) => Some(inner.as_ref()),
+            _ => None,
+        })
+    }
 }
 
 impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for FunctionAttributes {
@@ -175,7 +283,7 @@ impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for FunctionAttribut
         weedle_attributes: &weedle::attribute::ExtendedAttributeList<'_>,
     ) -> Result<Self, Self::Error> {
         let attrs = parse_attributes(weedle_attributes, |attr| match attr {
-            Attribute::Throws(_) => Ok(()),
+            Attribute::Throws(_) | Attribute::Method(_) => Ok(()),
             _ => bail!(format!("{attr:?} not supported for functions")),
         })?;
         Ok(Self(attrs))
@@ -205,6 +313,10 @@ impl ArgumentAttributes {
     pub fn by_ref(&self) -> bool {
         self.0.iter().any(|attr| matches!(attr, Attribute::ByRef))
     }
+
+    pub fn get_rename(&self) -> Option<&str> {
+        get_rename(&self.0)
+    }
 }
 
 impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for ArgumentAttributes {
@@ -213,7 +325,7 @@ impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for ArgumentAttribut
         weedle_attributes: &weedle::attribute::ExtendedAttributeList<'_>,
     ) -> Result<Self, Self::Error> {
         let attrs = parse_attributes(weedle_attributes, |attr| match attr {
-            Attribute::ByRef => Ok(()),
+            Attribute::ByRef | Attribute::Rename(_) => Ok(()),
             _ => bail!(format!("{attr:?} not supported for arguments")),
         })?;
         Ok(Self(attrs))
@@ -232,6 +344,88 @@ impl<T: TryInto<ArgumentAttributes, Error = anyhow::Error>> TryFrom<Option<T>>
     }
 }
 
+/// Represents UDL attributes that might appear on a dictionary field, or on a field of an
+/// enum variant.
+///
+/// This supports the `[Rename]` attribute for fields whose name in the foreign language
+/// bindings differs from their Rust name.
+#[derive(Debug, Clone, Hash, Default)]
+pub(super) struct FieldAttributes(Vec<Attribute>);
+
+impl FieldAttributes {
+    pub fn get_rename(&self) -> Option<&str> {
+        get_rename(&self.0)
+    }
+}
+
+impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for FieldAttributes {
+    type Error = anyhow::Error;
+    fn try_from(
+        weedle_attributes: &weedle::attribute::ExtendedAttributeList<'_>,
+    ) -> Result<Self, Self::Error> {
+        let attrs = parse_attributes(weedle_attributes, |attr| match attr {
+            Attribute::Rename(_) => Ok(()),
+            _ => bail!(format!("{attr:?} not supported for fields")),
+        })?;
+        Ok(Self(attrs))
+    }
+}
+
+impl<T: TryInto<FieldAttributes, Error = anyhow::Error>> TryFrom<Option<T>> for FieldAttributes {
+    type Error = anyhow::Error;
+    fn try_from(value: Option<T>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(Default::default()),
+            Some(v) => v.try_into(),
+        }
+    }
+}
+
+/// Represents UDL attributes that might appear on the variant of an `[Enum] interface`.
+///
+/// This supports the `[Rename]` attribute for variants whose name in the foreign language
+/// bindings differs from their Rust name.
+#[derive(Debug, Clone, Hash, Default)]
+pub(super) struct VariantAttributes(Vec<Attribute>);
+
+impl VariantAttributes {
+    pub fn get_rename(&self) -> Option<&str> {
+        get_rename(&self.0)
+    }
+}
+
+impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for VariantAttributes {
+    type Error = anyhow::Error;
+    fn try_from(
+        weedle_attributes: &weedle::attribute::ExtendedAttributeList<'_>,
+    ) -> Result<Self, Self::Error> {
+        let attrs = parse_attributes(weedle_attributes, |attr| match attr {
+            Attribute::Rename(_) => Ok(()),
+            _ => bail!(format!("{attr:?} not supported for enum variants")),
+        })?;
+        Ok(Self(attrs))
+    }
+}
+
+impl<T: TryInto<VariantAttributes, Error = anyhow::Error>> TryFrom<Option<T>>
+    for VariantAttributes
+{
+    type Error = anyhow::Error;
+    fn try_from(value: Option<T>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(Default::default()),
+            Some(v) => v.try_into(),
+        }
+    }
+}
+
+fn get_rename(attrs: &[Attribute]) -> Option<&str> {
+    attrs.iter().find_map(|attr| match attr {
+        Attribute::Rename(name) => Some(name.as_ref()),
+        _ => None,
+    })
+}
+
 /// Represents UDL attributes that might appear on an `interface` definition.
 #[derive(Debug, Clone, Hash, Default)]
 pub(super) struct InterfaceAttributes(Vec<Attribute>);
@@ -245,11 +439,34 @@ impl InterfaceAttributes {
         self.0.iter().any(|attr| attr.is_error())
     }
 
+    pub fn contains_source_attr(&self) -> bool {
+        self.0.iter().any(|attr| matches!(attr, Attribute::Source))
+    }
+
     pub fn threadsafe(&self) -> bool {
         self.0
             .iter()
             .any(|attr| matches!(attr, Attribute::Threadsafe))
     }
+
+    /// The names of the instances of a generic interface, empty if the interface isn't generic.
+    pub fn instances(&self) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter_map(|attr| match attr {
+                Attribute::Instance(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn object_impl(&self) -> ObjectImpl {
+        if self.0.iter().any(|attr| matches!(attr, Attribute::Trait)) {
+            ObjectImpl::Trait
+        } else {
+            ObjectImpl::Struct
+        }
+    }
 }
 
 impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for InterfaceAttributes {
@@ -261,13 +478,23 @@ impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for InterfaceAttribu
             Attribute::Enum => Ok(()),
             Attribute::Error => Ok(()),
             Attribute::Threadsafe => Ok(()),
+            Attribute::Trait => Ok(()),
+            Attribute::Instance(_) => Ok(()),
+            Attribute::Source => Ok(()),
             _ => bail!(format!("{attr:?} not supported for interface definition")),
         })?;
-        // Can't be both `[Threadsafe]` and an `[Enum]`.
-        if attrs.len() > 1 {
+        let attrs = Self(attrs);
+        if attrs.contains_source_attr() && !attrs.contains_error_attr() {
+            bail!("`[Source]` is only supported on errors");
+        }
+        // Can't be both `[Threadsafe]` and an `[Enum]`, or a `[Trait]` and an `[Error]`, and
+        // generic interfaces can only have instances.
+        let instances = attrs.instances().len();
+        let others = attrs.0.len() - instances - usize::from(attrs.contains_source_attr());
+        if others > 1 || (instances > 0 && others > 0) {
             bail!("conflicting attributes on interface definition");
         }
-        Ok(Self(attrs))
+        Ok(attrs)
     }
 }
 
@@ -344,6 +571,10 @@ impl MethodAttributes {
             .iter()
             .any(|attr| matches!(attr, Attribute::SelfType(SelfType::ByArc)))
     }
+
+    pub(super) fn is_static(&self) -> bool {
+        self.0.iter().any(|attr| matches!(attr, Attribute::Static))
+    }
 }
 
 impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for MethodAttributes {
@@ -353,9 +584,17 @@ impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for MethodAttributes
     ) -> Result<Self, Self::Error> {
         let attrs = parse_attributes(weedle_attributes, |attr| match attr {
             Attribute::SelfType(_) => Ok(()),
+            Attribute::Static => Ok(()),
             Attribute::Throws(_) => Ok(()),
             _ => bail!(format!("{attr:?} not supported for methods")),
         })?;
+        if attrs.iter().any(|attr| matches!(attr, Attribute::Static))
+            && attrs
+                .iter()
+                .any(|attr| matches!(attr, Attribute::SelfType(_)))
+        {
+            bail!("Static methods don't have a `Self` type");
+        }
         Ok(Self(attrs))
     }
 }
@@ -397,7 +636,9 @@ impl TryFrom<&weedle::attribute::IdentifierOrString<'_>> for SelfType {
 
 /// Represents UDL attributes that might appear on a typedef
 ///
-/// This supports the `[External="crate_name"]` and `[Custom]` attributes for types.
+/// This supports the `[External="crate_name"]` (and its `ExternalError` and
+/// `ExternalCallbackInterface` variants) and `[Custom]` attributes for types, as well as
+/// `[Throws=ErrorName]` for the custom types whose conversion can fail with that error.
 #[derive(Debug, Clone, Hash, Default)]
 pub(super) struct TypedefAttributes(Vec<Attribute>);
 
@@ -406,17 +647,34 @@ impl TypedefAttributes {
         self.0
             .iter()
             .find_map(|attr| match attr {
-                Attribute::External(crate_name) => Some(crate_name.clone()),
+                Attribute::External { crate_name, .. } => Some(crate_name.clone()),
                 _ => None,
             })
             .expect("must have a crate name")
     }
 
+    pub(super) fn get_external_kind(&self) -> ExternalKind {
+        self.0
+            .iter()
+            .find_map(|attr| match attr {
+                Attribute::External { kind, .. } => Some(*kind),
+                _ => None,
+            })
+            .unwrap_or(ExternalKind::DataClass)
+    }
+
     pub(super) fn is_custom(&self) -> bool {
         self.0
             .iter()
             .any(|attr| matches!(attr, Attribute::Custom { .. }))
     }
+
+    pub(super) fn get_throws_err(&self) -> Option<&str> {
+        self.0.iter().find_map(|attr| match attr {
+            Attribute::Throws(inner) => Some(inner.as_ref()),
+            _ => None,
+        })
+    }
 }
 
 impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for TypedefAttributes {
@@ -425,10 +683,14 @@ impl TryFrom<&weedle::attribute::ExtendedAttributeList<'_>> for TypedefAttribute
         weedle_attributes: &weedle::attribute::ExtendedAttributeList<'_>,
     ) -> Result<Self, Self::Error> {
         let attrs = parse_attributes(weedle_attributes, |attr| match attr {
-            Attribute::External { .. } | Attribute::Custom => Ok(()),
+            Attribute::External { .. } | Attribute::Custom | Attribute::Throws(_) => Ok(()),
             _ => bail!(format!("{attr:?} not supported for typedefs")),
         })?;
-        Ok(Self(attrs))
+        let attrs = Self(attrs);
+        if attrs.get_throws_err().is_some() && !attrs.is_custom() {
+            bail!("`[Throws]` is only supported for `[Custom]` typedefs");
+        }
+        Ok(attrs)
     }
 }
 
@@ -475,6 +737,20 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_source() -> Result<()> {
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[Error, Source]").unwrap();
+        assert!(EnumAttributes::try_from(&node)?.contains_source_attr());
+        assert!(InterfaceAttributes::try_from(&node)?.contains_source_attr());
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[Source]").unwrap();
+        let err = EnumAttributes::try_from(&node).unwrap_err();
+        assert_eq!(err.to_string(), "`[Source]` is only supported on errors");
+        let err = InterfaceAttributes::try_from(&node).unwrap_err();
+        assert_eq!(err.to_string(), "`[Source]` is only supported on errors");
+        Ok(())
+    }
+
     #[test]
     fn test_name() -> Result<()> {
         let (_, node) = weedle::attribute::ExtendedAttribute::parse("Name=Value").unwrap();
@@ -510,6 +786,14 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_static() -> Result<()> {
+        let (_, node) = weedle::attribute::ExtendedAttribute::parse("Static").unwrap();
+        let attr = Attribute::try_from(&node)?;
+        assert!(matches!(attr, Attribute::Static));
+        Ok(())
+    }
+
     #[test]
     fn test_throws() -> Result<()> {
         let (_, node) = weedle::attribute::ExtendedAttribute::parse("Throws=Name").unwrap();
@@ -563,6 +847,20 @@ mod test {
         assert!(matches!(attrs.get_throws_err(), None));
     }
 
+    #[test]
+    fn test_method_of_attribute() {
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[Method=Point, Throws=Error]")
+                .unwrap();
+        let attrs = FunctionAttributes::try_from(&node).unwrap();
+        assert!(matches!(attrs.get_method_of(), Some("Point")));
+        assert!(matches!(attrs.get_throws_err(), Some("Error")));
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[]").unwrap();
+        let attrs = FunctionAttributes::try_from(&node).unwrap();
+        assert!(matches!(attrs.get_method_of(), None));
+    }
+
     #[test]
     fn test_other_attributes_not_supported_for_functions() {
         let (_, node) =
@@ -601,6 +899,19 @@ mod test {
         let attrs = MethodAttributes::try_from(&node).unwrap();
         assert!(attrs.get_self_by_arc());
         assert!(attrs.get_throws_err().is_none());
+        assert!(!attrs.is_static());
+
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[Static, Throws=Error]").unwrap();
+        let attrs = MethodAttributes::try_from(&node).unwrap();
+        assert!(attrs.is_static());
+        assert!(!attrs.get_self_by_arc());
+        assert!(attrs.get_throws_err().is_some());
+
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[Static, Self=ByArc]").unwrap();
+        let err = MethodAttributes::try_from(&node).unwrap_err();
+        assert_eq!(err.to_string(), "Static methods don't have a `Self` type");
     }
 
     #[test]
@@ -651,6 +962,26 @@ mod test {
         assert!(matches!(attrs.by_ref(), false));
     }
 
+    #[test]
+    fn test_rename_attribute() {
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[Rename=\"kind\", ByRef]").unwrap();
+        let attrs = ArgumentAttributes::try_from(&node).unwrap();
+        assert!(matches!(attrs.get_rename(), Some("kind")));
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[Rename=kind]").unwrap();
+        let attrs = FieldAttributes::try_from(&node).unwrap();
+        assert!(matches!(attrs.get_rename(), Some("kind")));
+        let attrs = VariantAttributes::try_from(&node).unwrap();
+        assert!(matches!(attrs.get_rename(), Some("kind")));
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[ByRef]").unwrap();
+        let err = FieldAttributes::try_from(&node).unwrap_err();
+        assert_eq!(err.to_string(), "ByRef not supported for fields");
+        let err = VariantAttributes::try_from(&node).unwrap_err();
+        assert_eq!(err.to_string(), "ByRef not supported for enum variants");
+    }
+
     #[test]
     fn test_other_attributes_not_supported_for_arguments() {
         let (_, node) =
@@ -696,6 +1027,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_trait_attribute() {
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[Trait]").unwrap();
+        let attrs = InterfaceAttributes::try_from(&node).unwrap();
+        assert_eq!(attrs.object_impl(), ObjectImpl::Trait);
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[]").unwrap();
+        let attrs = InterfaceAttributes::try_from(&node).unwrap();
+        assert_eq!(attrs.object_impl(), ObjectImpl::Struct);
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[Trait, Error]").unwrap();
+        let err = InterfaceAttributes::try_from(&node).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "conflicting attributes on interface definition"
+        );
+    }
+
+    #[test]
+    fn test_instance_attribute() {
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse(
+            "[Instance=StringCache(string T), Instance=CountCache(u32 T)]",
+        )
+        .unwrap();
+        let attrs = InterfaceAttributes::try_from(&node).unwrap();
+        assert_eq!(attrs.instances(), vec!["StringCache", "CountCache"]);
+        assert_eq!(attrs.object_impl(), ObjectImpl::Struct);
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[]").unwrap();
+        let attrs = InterfaceAttributes::try_from(&node).unwrap();
+        assert!(attrs.instances().is_empty());
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse(
+            "[Trait, Instance=StringCache(string T)]",
+        )
+        .unwrap();
+        let err = InterfaceAttributes::try_from(&node).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "conflicting attributes on interface definition"
+        );
+
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[Generic=StringCache(string T)]")
+                .unwrap();
+        let err = InterfaceAttributes::try_from(&node).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Attribute named argument list not supported: \"Generic\""
+        );
+    }
+
     #[test]
     fn test_other_attributes_not_supported_for_interfaces() {
         let (_, node) =
@@ -718,6 +1101,27 @@ mod test {
         let attrs = TypedefAttributes::try_from(&node).unwrap();
         assert!(!attrs.is_custom());
         assert_eq!(attrs.get_crate_name(), "crate_name");
+        assert_eq!(attrs.get_external_kind(), ExternalKind::DataClass);
+
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[ExternalError=crate_name]").unwrap();
+        let attrs = TypedefAttributes::try_from(&node).unwrap();
+        assert_eq!(attrs.get_crate_name(), "crate_name");
+        assert_eq!(attrs.get_external_kind(), ExternalKind::Error);
+
+        let (_, node) = weedle::attribute::ExtendedAttributeList::parse(
+            "[ExternalCallbackInterface=crate_name]",
+        )
+        .unwrap();
+        let attrs = TypedefAttributes::try_from(&node).unwrap();
+        assert_eq!(attrs.get_crate_name(), "crate_name");
+        assert_eq!(attrs.get_external_kind(), ExternalKind::CallbackInterface);
+
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[Custom, Throws=GuidError]").unwrap();
+        let attrs = TypedefAttributes::try_from(&node).unwrap();
+        assert!(attrs.is_custom());
+        assert_eq!(attrs.get_throws_err(), Some("GuidError"));
     }
 
     #[test]
@@ -742,5 +1146,14 @@ mod test {
         let (_, node) = weedle::attribute::ExtendedAttributeList::parse("[ByRef]").unwrap();
         let err = TypedefAttributes::try_from(&node).unwrap_err();
         assert_eq!(err.to_string(), "ByRef not supported for typedefs");
+
+        let (_, node) =
+            weedle::attribute::ExtendedAttributeList::parse("[External=crate_name, Throws=Oops]")
+                .unwrap();
+        let err = TypedefAttributes::try_from(&node).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`[Throws]` is only supported for `[Custom]` typedefs"
+        );
     }
 }
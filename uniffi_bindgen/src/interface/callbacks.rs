@@ -32,13 +32,29 @@
 //! assert_eq!(callback.methods()[0].name(), "hello");
 //! # Ok::<(), anyhow::Error>(())
 //! ```
+//!
+//! A callback interface of a single method can also be declared as a callback function, which
+//! the foreign-language code implements with a closure rather than with a class:
+//!
+//! ```
+//! # let ci = uniffi_bindgen::interface::ComponentInterface::from_webidl(r##"
+//! # namespace example {};
+//! callback StringFilter = boolean (string value);
+//! # "##)?;
+//! let callback = ci.get_callback_interface_definition("StringFilter").unwrap();
+//! assert!(callback.is_function());
+//! assert_eq!(callback.methods()[0].name(), "call");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
 
+use std::collections::BTreeSet;
 use std::hash::{Hash, Hasher};
 
 use anyhow::{bail, Result};
 
+use super::attributes::MethodAttributes;
 use super::ffi::{FFIArgument, FFIFunction, FFIType};
-use super::object::Method;
+use super::object::{Method, ObjectImpl};
 use super::types::{Type, TypeIterator};
 use super::{APIConverter, ComponentInterface};
 
@@ -46,7 +62,10 @@ use super::{APIConverter, ComponentInterface};
 pub struct CallbackInterface {
     pub(super) name: String,
     pub(super) methods: Vec<Method>,
+    // Whether this is a callback function, whose only method is `call`.
+    pub(super) function: bool,
     pub(super) ffi_init_callback: FFIFunction,
+    pub(super) ffi_unregister_callback: FFIFunction,
 }
 
 impl CallbackInterface {
@@ -54,7 +73,9 @@ impl CallbackInterface {
         CallbackInterface {
             name,
             methods: Default::default(),
+            function: false,
             ffi_init_callback: Default::default(),
+            ffi_unregister_callback: Default::default(),
         }
     }
 
@@ -70,17 +91,49 @@ impl CallbackInterface {
         self.methods.iter().collect()
     }
 
+    /// Whether this is a callback function, declared as `callback Name = ReturnType (arguments);`.
+    ///
+    /// Its only method is named `call`, and is the closure itself in the foreign languages and a
+    /// `Box<dyn Fn(...) + Send + Sync>` in Rust.
+    pub fn is_function(&self) -> bool {
+        self.function
+    }
+
+    /// The errors thrown by the methods, each of them once. The foreign-language code throws them
+    /// for Rust to receive them as the `Err` of the methods' results.
+    pub fn throws_types(&self) -> Vec<Type> {
+        self.methods
+            .iter()
+            .filter_map(Method::throws_type)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
     pub fn ffi_init_callback(&self) -> &FFIFunction {
         &self.ffi_init_callback
     }
 
+    /// The function unregistering a callback, with the registration returned by
+    /// [`Self::ffi_init_callback`], once the foreign objects of the callback are gone.
+    pub fn ffi_unregister_callback(&self) -> &FFIFunction {
+        &self.ffi_unregister_callback
+    }
+
     pub(super) fn derive_ffi_funcs(&mut self, ci_prefix: &str) {
         self.ffi_init_callback.name = format!("ffi_{ci_prefix}_{}_init_callback", self.name);
         self.ffi_init_callback.arguments = vec![FFIArgument {
             name: "callback_stub".to_string(),
             type_: FFIType::ForeignCallback,
         }];
-        self.ffi_init_callback.return_type = None;
+        self.ffi_init_callback.return_type = Some(FFIType::UInt16);
+        self.ffi_unregister_callback.name =
+            format!("ffi_{ci_prefix}_{}_unregister_callback", self.name);
+        self.ffi_unregister_callback.arguments = vec![FFIArgument {
+            name: "registration".to_string(),
+            type_: FFIType::UInt16,
+        }];
+        self.ffi_unregister_callback.return_type = None;
     }
 
     pub fn iter_types(&self) -> TypeIterator<'_> {
@@ -98,6 +151,7 @@ impl Hash for CallbackInterface {
         //    avoids a weird circular depenendency in the calculation.
         self.name.hash(state);
         self.methods.hash(state);
+        self.function.hash(state);
     }
 }
 
@@ -127,6 +181,27 @@ impl APIConverter<CallbackInterface> for weedle::CallbackInterfaceDefinition<'_>
     }
 }
 
+impl APIConverter<CallbackInterface> for weedle::CallbackDefinition<'_> {
+    fn convert(&self, ci: &mut ComponentInterface) -> Result<CallbackInterface> {
+        let attributes = MethodAttributes::try_from(self.attributes.as_ref())?;
+        let mut object = CallbackInterface::new(self.identifier.0.to_string());
+        object.function = true;
+        object.methods.push(Method {
+            name: "call".to_string(),
+            object_name: object.name.clone(),
+            object_impl: ObjectImpl::Struct,
+            return_type: ci.resolve_return_type_expression(&self.return_type)?,
+            arguments: self.arguments.body.list.convert(ci)?,
+            ffi_func: Default::default(),
+            throws: ci.resolve_throws_type(attributes.get_throws_err()),
+            throws_from_arguments: false,
+            attributes,
+            docstring: None,
+        });
+        Ok(object)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -173,4 +248,59 @@ mod test {
         assert_eq!(callbacks_two.methods()[0].name(), "two");
         assert_eq!(callbacks_two.methods()[1].name(), "too");
     }
+
+    #[test]
+    fn test_callback_functions() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Error]
+            enum Oops { "Oh" };
+            callback StringFilter = boolean (string value, u32 index);
+            [Throws=Oops]
+            callback Listener = void ();
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert_eq!(ci.callback_interface_definitions().len(), 2);
+
+        let filter = ci
+            .get_callback_interface_definition("StringFilter")
+            .unwrap();
+        assert!(filter.is_function());
+        assert_eq!(filter.methods().len(), 1);
+        let call = filter.methods()[0];
+        assert_eq!(call.name(), "call");
+        assert_eq!(call.arguments().len(), 2);
+        assert_eq!(call.return_type(), Some(&Type::Boolean));
+
+        let listener = ci.get_callback_interface_definition("Listener").unwrap();
+        assert!(listener.is_function());
+        assert_eq!(listener.methods()[0].return_type(), None);
+        assert_eq!(listener.throws_types(), vec![Type::Error("Oops".into())]);
+    }
+
+    #[test]
+    fn test_throws_types() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Error]
+            enum Oops { "Oh" };
+            [Error]
+            enum Ouch { "Aie" };
+            callback interface Throwing {
+                [Throws=Oops]
+                void one();
+                [Throws=Ouch]
+                u32 two();
+                [Throws=Oops]
+                string three();
+                void four();
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let callbacks = ci.get_callback_interface_definition("Throwing").unwrap();
+        assert_eq!(
+            callbacks.throws_types(),
+            vec![Type::Error("Oops".into()), Type::Error("Ouch".into())]
+        );
+    }
 }
@@ -0,0 +1,181 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Constant definitions for a `ComponentInterface`.
+//!
+//! This module converts `const` members of the namespace from UDL into [`Constant`] structures
+//! that can be added to a `ComponentInterface`. Constants only exist in the foreign-language
+//! bindings, there's nothing to call across the FFI to get them.
+//!
+//! A declaration in the UDL like this:
+//!
+//! ```
+//! # let ci = uniffi_bindgen::interface::ComponentInterface::from_webidl(r##"
+//! namespace example {
+//!   const u32 MAX_ITEMS = 100;
+//! };
+//! # "##)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Will result in a [`Constant`] member being added to the resulting [`ComponentInterface`]:
+//!
+//! ```
+//! # use uniffi_bindgen::interface::{Literal, Radix, Type};
+//! # let ci = uniffi_bindgen::interface::ComponentInterface::from_webidl(r##"
+//! # namespace example {
+//! #   const u32 MAX_ITEMS = 100;
+//! # };
+//! # "##)?;
+//! let constant = ci.get_constant_definition("MAX_ITEMS").unwrap();
+//! assert_eq!(constant.name(), "MAX_ITEMS");
+//! assert_eq!(constant.type_(), &Type::UInt32);
+//! assert_eq!(constant.value(), &Literal::UInt(100, Radix::Decimal, Type::UInt32));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{bail, Result};
+
+use super::literal::{convert_default_value, Literal};
+use super::types::Type;
+use super::{APIConverter, ComponentInterface};
+
+/// Represents a named value, available in the foreign-language bindings.
+///
+/// Only integers, floats, strings, booleans and durations can be constants.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Constant {
+    pub(super) name: String,
+    pub(super) type_: Type,
+    pub(super) value: Literal,
+}
+
+impl Constant {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub fn value(&self) -> &Literal {
+        &self.value
+    }
+}
+
+impl APIConverter<Constant> for weedle::namespace::ConstNamespaceMember<'_> {
+    fn convert(&self, ci: &mut ComponentInterface) -> Result<Constant> {
+        if self.attributes.is_some() {
+            bail!("constant attributes are not supported yet");
+        }
+        let name = self.identifier.0.to_string();
+        let type_ = ci.resolve_type_expression(&self.type_)?;
+        match type_ {
+            Type::UInt8
+            | Type::Int8
+            | Type::UInt16
+            | Type::Int16
+            | Type::UInt32
+            | Type::Int32
+            | Type::UInt64
+            | Type::Int64
+            | Type::Float32
+            | Type::Float64
+            | Type::Boolean
+            | Type::String
+            | Type::Duration => {}
+            _ => bail!("Constant `{name}` can't be of type {type_:?}"),
+        }
+        let value = convert_default_value(&self.value, &type_)?;
+        Ok(Constant { name, type_, value })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Radix;
+    use super::*;
+
+    #[test]
+    fn test_constants() {
+        const UDL: &str = r#"
+            namespace test {
+                const i8 MIN_LEVEL = -8;
+                const u64 MASK = 0xff;
+                const f64 RATIO = 1.5;
+                const boolean ENABLED = true;
+                const string GREETING = "hello";
+                const duration TIMEOUT = 2.25;
+                u32 count();
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert_eq!(ci.constant_definitions().len(), 6);
+        assert_eq!(ci.function_definitions().len(), 1);
+
+        let constant = ci.get_constant_definition("MIN_LEVEL").unwrap();
+        assert_eq!(constant.type_(), &Type::Int8);
+        assert_eq!(
+            constant.value(),
+            &Literal::Int(-8, Radix::Decimal, Type::Int8)
+        );
+        let constant = ci.get_constant_definition("MASK").unwrap();
+        assert_eq!(
+            constant.value(),
+            &Literal::UInt(0xff, Radix::Hexadecimal, Type::UInt64)
+        );
+        let constant = ci.get_constant_definition("RATIO").unwrap();
+        assert_eq!(
+            constant.value(),
+            &Literal::Float("1.5".into(), Type::Float64)
+        );
+        let constant = ci.get_constant_definition("ENABLED").unwrap();
+        assert_eq!(constant.value(), &Literal::Boolean(true));
+        let constant = ci.get_constant_definition("GREETING").unwrap();
+        assert_eq!(constant.value(), &Literal::String("hello".into()));
+        let constant = ci.get_constant_definition("TIMEOUT").unwrap();
+        assert_eq!(constant.value(), &Literal::Duration(2, 250_000_000));
+    }
+
+    #[test]
+    fn test_constants_must_have_a_simple_type() {
+        const UDL: &str = r#"
+            namespace test {
+                const sequence<u32> PRIMES = [];
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Constant `PRIMES` can't be of type Sequence(UInt32)"
+        );
+    }
+
+    #[test]
+    fn test_constant_values_must_match_their_type() {
+        const UDL: &str = r#"
+            namespace test {
+                const u32 COUNT = "many";
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No support for String(StringLit(\"many\")) literal yet"
+        );
+    }
+
+    #[test]
+    fn test_rejects_duplicate_constants() {
+        const UDL: &str = r#"
+            namespace test {
+                const u32 COUNT = 1;
+                const u32 COUNT = 2;
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
+        assert_eq!(err.to_string(), "duplicate constant definition: \"COUNT\"");
+    }
+}
@@ -0,0 +1,436 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Diagnostics for the errors found in UDL files.
+//!
+//! The weedle syntax nodes borrow their text from the UDL they were parsed from, so an error
+//! about a node can carry the span of that text, without threading positions through the parser.
+//! The errors keep their message, and once the parsing is over, the span is located in the UDL
+//! sources to report the file, line and column of the error:
+//!
+//! ```
+//! # use uniffi_bindgen::interface::{ComponentInterface, Diagnostic};
+//! let udl = "namespace example {\n    Strin hello();\n};\n";
+//! let err = ComponentInterface::from_webidl(udl).unwrap_err();
+//! let diagnostic = Diagnostic::from_error(&err, &[("example.udl", udl)]).unwrap();
+//! assert_eq!((diagnostic.line, diagnostic.column), (2, 5));
+//! assert_eq!(diagnostic.suggestion.as_deref(), Some("did you mean `string`?"));
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt;
+
+/// An error about a specific place of a UDL source, and possibly how to fix it.
+///
+/// It is displayed as the error it wraps, so that the messages don't depend on whether the
+/// errors are located.
+#[derive(Debug)]
+pub(super) struct SpannedError {
+    // The address and length of the text the error is about, which is only compared with the
+    // addresses of the sources and never read.
+    start: usize,
+    len: usize,
+    suggestion: Option<String>,
+    error: anyhow::Error,
+}
+
+impl fmt::Display for SpannedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl StdError for SpannedError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.chain().nth(1)
+    }
+}
+
+/// An error about `text`, which must be borrowed from the UDL being parsed.
+fn error_at(text: &str, message: impl fmt::Display) -> anyhow::Error {
+    spanned(text, None, anyhow::anyhow!("{message}"))
+}
+
+/// An error about `text`, with a suggestion of how to fix it, such as "did you mean `string`?".
+pub(super) fn error_with_suggestion(
+    text: &str,
+    message: impl fmt::Display,
+    suggestion: Option<String>,
+) -> anyhow::Error {
+    spanned(text, suggestion, anyhow::anyhow!("{message}"))
+}
+
+/// Locate `err` at `text` if it isn't about a more specific place already.
+fn locate(err: anyhow::Error, text: &str) -> anyhow::Error {
+    if span_of(&err).is_some() {
+        err
+    } else {
+        spanned(text, None, err)
+    }
+}
+
+/// Locate `err` at the name of the definition it was found in, if it isn't about a more specific
+/// place already.
+pub(super) fn locate_definition(
+    err: anyhow::Error,
+    defn: &weedle::Definition<'_>,
+) -> anyhow::Error {
+    use weedle::Definition;
+    let name = match defn {
+        Definition::Callback(d) => d.identifier.0,
+        Definition::CallbackInterface(d) => d.identifier.0,
+        Definition::Interface(d) => d.identifier.0,
+        Definition::InterfaceMixin(d) => d.identifier.0,
+        Definition::Namespace(d) => d.identifier.0,
+        Definition::Dictionary(d) => d.identifier.0,
+        Definition::PartialInterface(d) => d.identifier.0,
+        Definition::PartialInterfaceMixin(d) => d.identifier.0,
+        Definition::PartialDictionary(d) => d.identifier.0,
+        Definition::PartialNamespace(d) => d.identifier.0,
+        Definition::Enum(d) => d.identifier.0,
+        Definition::Typedef(d) => d.identifier.0,
+        Definition::IncludesStatement(d) => d.lhs_identifier.0,
+        Definition::Implements(d) => d.lhs_identifier.0,
+        Definition::Include(d) => d.path.0,
+    };
+    locate(err, name)
+}
+
+fn spanned(text: &str, suggestion: Option<String>, error: anyhow::Error) -> anyhow::Error {
+    anyhow::Error::new(SpannedError {
+        start: text.as_ptr() as usize,
+        len: text.len(),
+        suggestion,
+        error,
+    })
+}
+
+fn span_of(err: &anyhow::Error) -> Option<&SpannedError> {
+    err.chain().find_map(|e| e.downcast_ref::<SpannedError>())
+}
+
+/// The names of the builtin types, and of the WebIDL keywords naming types.
+const BUILTIN_TYPE_NAMES: &[&str] = &[
+    "boolean",
+    "float",
+    "double",
+    "string",
+    "char",
+    "u8",
+    "i8",
+    "u16",
+    "i16",
+    "u32",
+    "i32",
+    "u64",
+    "i64",
+    "f32",
+    "f64",
+    "timestamp",
+    "duration",
+    "offset_datetime",
+    "bigint",
+    "decimal",
+    "url",
+    "uuid",
+    "json",
+    "shared_slice",
+];
+
+/// The UDL types which the names of common Rust types are spelled as.
+const RUST_TYPE_NAMES: &[(&str, &str)] = &[
+    ("String", "string"),
+    ("str", "string"),
+    ("bool", "boolean"),
+    ("usize", "u64"),
+    ("isize", "i64"),
+    ("bytes", "sequence<u8>"),
+    ("Vec", "sequence<T>"),
+    ("HashMap", "record<K, V>"),
+    ("HashSet", "set<T>"),
+    ("Option", "T?"),
+    ("Result", "result<T, E>"),
+    ("SystemTime", "timestamp"),
+    ("Duration", "duration"),
+];
+
+/// The generic types of the UDL, which are keywords followed by `<`.
+const GENERIC_TYPE_NAMES: &[&str] = &[
+    "sequence", "record", "set", "result", "tuple", "iterator", "weak",
+];
+
+/// Suggest the type which an unknown type name was probably meant to be, among the builtin types
+/// and the types defined in the UDL.
+pub(super) fn suggest_type<'a>(
+    name: &str,
+    defined: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    if let Some((_, udl)) = RUST_TYPE_NAMES.iter().find(|(rust, _)| *rust == name) {
+        return Some(format!("did you mean `{udl}`?"));
+    }
+    let candidates: Vec<&str> = BUILTIN_TYPE_NAMES.iter().copied().chain(defined).collect();
+    closest(name, &candidates).map(|candidate| format!("did you mean `{candidate}`?"))
+}
+
+/// Find the unknown generic type in the text of a definition which failed to parse, such as
+/// `sequnce<u8>` or `Vec<u8>`, and suggest the UDL one.
+fn find_unknown_generic(text: &str) -> Option<(&str, Option<String>)> {
+    // Only look at the definition which failed to parse, which ends with the first `};`.
+    let text = &text[..text.find("};").unwrap_or(text.len())];
+    let mut start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        let is_ident = c.is_ascii_alphanumeric() || c == '_';
+        match (start, is_ident) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                start = None;
+                let name = &text[s..i];
+                let next = text[i..].trim_start();
+                if !next.starts_with('<') || GENERIC_TYPE_NAMES.contains(&name) {
+                    continue;
+                }
+                let replacement = RUST_TYPE_NAMES
+                    .iter()
+                    .find(|(rust, udl)| *rust == name && udl.contains('<'))
+                    .and_then(|(_, udl)| udl.split('<').next())
+                    .or_else(|| closest(name, GENERIC_TYPE_NAMES));
+                let suggestion = replacement.map(|replacement| {
+                    // Suggest the whole type, with its generic arguments, when they are simple.
+                    match next.find('>') {
+                        Some(end) if !next[1..end].contains('<') => {
+                            format!("did you mean `{replacement}{}`?", &next[..=end])
+                        }
+                        _ => format!("did you mean `{replacement}`?"),
+                    }
+                });
+                return Some((name, suggestion));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The candidate closest to `name`, if it is close enough to be a typo.
+fn closest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between two strings, ignoring the case.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The error for UDL which weedle stopped parsing at `remaining`.
+///
+/// Weedle gives up at the start of the definition it can't parse, so this points at an unknown
+/// generic type inside it when there is one, which is the most common mistake.
+pub(super) fn parse_error(remaining: &str) -> anyhow::Error {
+    let remaining = remaining.trim_start();
+    match find_unknown_generic(remaining) {
+        Some((name, suggestion)) => error_with_suggestion(
+            name,
+            format!("parse error: unknown generic type `{name}`"),
+            suggestion,
+        ),
+        None => {
+            let token = remaining
+                .split(|c: char| c.is_whitespace())
+                .next()
+                .unwrap_or(remaining);
+            error_at(token, "parse error")
+        }
+    }
+}
+
+/// An error located in a UDL file, as reported to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// The path of the UDL file, as given with its source.
+    pub path: String,
+    /// The line of the error, starting from 1.
+    pub line: usize,
+    /// The column of the error on its line, in characters starting from 1.
+    pub column: usize,
+    /// The line of the error, without its line break.
+    pub snippet: String,
+    /// The number of characters of the error on its line, at least 1.
+    pub width: usize,
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    /// Locate an error in the UDL sources it was found in, given as `(path, text)` pairs, if it is
+    /// about a specific place of one of them.
+    pub fn from_error(err: &anyhow::Error, sources: &[(&str, &str)]) -> Option<Self> {
+        let spanned = span_of(err)?;
+        let (path, text) = sources.iter().find(|(_, text)| {
+            let start = text.as_ptr() as usize;
+            (start..=start + text.len()).contains(&spanned.start)
+        })?;
+        let offset = spanned.start - text.as_ptr() as usize;
+        let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+        let snippet = text[line_start..line_end].trim_end_matches('\r');
+        let span_end = (offset + spanned.len).min(line_start + snippet.len());
+        Some(Diagnostic {
+            message: err.to_string(),
+            path: path.to_string(),
+            line: text[..offset].matches('\n').count() + 1,
+            column: text[line_start..offset].chars().count() + 1,
+            snippet: snippet.to_string(),
+            width: text[offset..span_end.max(offset)].chars().count().max(1),
+            suggestion: spanned.suggestion.clone(),
+        })
+    }
+}
+
+// Displayed like the errors of rustc, with the offending line and the error underlined.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "{gutter}--> {}:{}:{}", self.path, self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        let indent: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(f, "{gutter} | {indent}{}", "^".repeat(self.width))?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n{gutter} = help: {suggestion}")?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for Diagnostic {}
+
+#[cfg(test)]
+mod test {
+    use super::super::ComponentInterface;
+    use super::*;
+
+    fn diagnose(udl: &str) -> Diagnostic {
+        let err = ComponentInterface::from_webidl(udl).unwrap_err();
+        Diagnostic::from_error(&err, &[("test.udl", udl)]).unwrap()
+    }
+
+    #[test]
+    fn test_unknown_types() {
+        let udl = "namespace test {\n    void greet(Strng name);\n};\n";
+        let diagnostic = diagnose(udl);
+        assert_eq!(diagnostic.message, "unknown type reference: Strng");
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 16));
+        assert_eq!(diagnostic.snippet, "    void greet(Strng name);");
+        assert_eq!(diagnostic.width, 5);
+        assert_eq!(
+            diagnostic.suggestion.as_deref(),
+            Some("did you mean `string`?")
+        );
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: unknown type reference: Strng
+ --> test.udl:2:16
+  |
+2 |     void greet(Strng name);
+  |                ^^^^^
+  = help: did you mean `string`?"
+        );
+
+        let udl =
+            "namespace test {};\ndictionary Point { f64 x; };\ndictionary Line { Pont start; };\n";
+        let diagnostic = diagnose(udl);
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 19));
+        assert_eq!(
+            diagnostic.suggestion.as_deref(),
+            Some("did you mean `Point`?")
+        );
+
+        let udl = "namespace test {\n    void greet(String name);\n};\n";
+        assert_eq!(
+            diagnose(udl).suggestion.as_deref(),
+            Some("did you mean `string`?")
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let udl = "namespace test {\n    sequnce<u8> get_bytes();\n};\n";
+        let diagnostic = diagnose(udl);
+        assert_eq!(
+            diagnostic.message,
+            "parse error: unknown generic type `sequnce`"
+        );
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 5));
+        assert_eq!(
+            diagnostic.suggestion.as_deref(),
+            Some("did you mean `sequence<u8>`?")
+        );
+
+        let udl = "namespace test {\n    Vec<u8> get_bytes();\n};\n";
+        assert_eq!(
+            diagnose(udl).suggestion.as_deref(),
+            Some("did you mean `sequence<u8>`?")
+        );
+
+        // Without an unknown generic type, the error is at the definition weedle stopped at.
+        let udl = "namespace test {};\n\ndictionary Point {\n    f64 x\n};\n";
+        let diagnostic = diagnose(udl);
+        assert_eq!(diagnostic.message, "parse error");
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 1));
+        assert_eq!(diagnostic.width, 10);
+        assert_eq!(diagnostic.suggestion, None);
+    }
+
+    #[test]
+    fn test_errors_are_located_at_their_definition() {
+        let udl = "namespace test {};\ndictionary Base {};\ndictionary Item : Base { u8 kind; };\n";
+        let diagnostic = diagnose(udl);
+        assert_eq!(
+            diagnostic.message,
+            "dictionary inheritence is not supported"
+        );
+        assert_eq!((diagnostic.line, diagnostic.column), (3, 12));
+        assert_eq!(diagnostic.width, 4);
+
+        let udl = "namespace test {};\nnamespace again {};\n";
+        let diagnostic = diagnose(udl);
+        assert_eq!(diagnostic.message, "duplicate namespace definition");
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 11));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("sequence", "sequence"), 0);
+        assert_eq!(edit_distance("sequnce", "sequence"), 1);
+        assert_eq!(edit_distance("Strng", "string"), 1);
+        assert_eq!(closest("recrod", GENERIC_TYPE_NAMES), Some("record"));
+        assert_eq!(closest("frobnicate", GENERIC_TYPE_NAMES), None);
+    }
+}
@@ -78,6 +78,7 @@
 
 use anyhow::{bail, Result};
 
+use super::attributes::{FieldAttributes, VariantAttributes};
 use super::record::Field;
 use super::types::{Type, TypeIterator};
 use super::{APIConverter, ComponentInterface};
@@ -93,6 +94,9 @@ pub struct Enum {
     pub(super) variants: Vec<Variant>,
     // "Flat" enums do not have variants with associated data.
     pub(super) flat: bool,
+    // Whether the enum refers back to itself, resolved by the `ComponentInterface`.
+    pub(super) recursive: bool,
+    pub(super) docstring: Option<String>,
 }
 
 impl Enum {
@@ -100,6 +104,10 @@ impl Enum {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
     pub fn type_(&self) -> Type {
         // *sigh* at the clone here, the relationship between a ComponentInterace
         // and its contained types could use a bit of a cleanup.
@@ -114,6 +122,12 @@ impl Enum {
         self.flat
     }
 
+    /// Whether a variant of the enum contains the enum itself without going through a sequence
+    /// or a map, like an expression tree, so some of its fields are boxed.
+    pub fn is_recursive(&self) -> bool {
+        self.recursive
+    }
+
     pub fn iter_types(&self) -> TypeIterator<'_> {
         Box::new(self.variants.iter().flat_map(Variant::iter_types))
     }
@@ -122,10 +136,27 @@ impl Enum {
 impl From<uniffi_meta::EnumMetadata> for Enum {
     fn from(meta: uniffi_meta::EnumMetadata) -> Self {
         let flat = meta.variants.iter().all(|v| v.fields.is_empty());
+        // Like in Rust, variants without an explicit discriminant take the one following the
+        // discriminant of the previous variant.
+        let mut next_discriminant = 0;
+        let variants = meta
+            .variants
+            .into_iter()
+            .map(|v| {
+                let discriminant = v.discriminant.unwrap_or(next_discriminant);
+                next_discriminant = discriminant.wrapping_add(1);
+                Variant {
+                    discriminant,
+                    ..v.into()
+                }
+            })
+            .collect();
         Self {
             name: meta.name,
-            variants: meta.variants.into_iter().map(Into::into).collect(),
+            variants,
             flat,
+            recursive: false,
+            docstring: meta.docstring,
         }
     }
 }
@@ -142,15 +173,19 @@ impl APIConverter<Enum> for weedle::EnumDefinition<'_> {
                 .body
                 .list
                 .iter()
-                .map::<Result<_>, _>(|v| {
+                .enumerate()
+                .map::<Result<_>, _>(|(i, v)| {
                     Ok(Variant {
                         name: v.0.to_string(),
+                        discriminant: i as i64,
                         ..Default::default()
                     })
                 })
                 .collect::<Result<Vec<_>>>()?,
             // Enums declared using the `enum` syntax can never have variants with fields.
             flat: true,
+            recursive: false,
+            docstring: None,
         })
     }
 }
@@ -168,8 +203,12 @@ impl APIConverter<Enum> for weedle::InterfaceDefinition<'_> {
                 .members
                 .body
                 .iter()
-                .map::<Result<Variant>, _>(|member| match member {
-                    weedle::interface::InterfaceMember::Operation(t) => Ok(t.convert(ci)?),
+                .enumerate()
+                .map::<Result<Variant>, _>(|(i, member)| match member {
+                    weedle::interface::InterfaceMember::Operation(t) => Ok(Variant {
+                        discriminant: i as i64,
+                        ..t.convert(ci)?
+                    }),
                     _ => bail!(
                         "interface member type {:?} not supported in enum interface",
                         member
@@ -178,6 +217,8 @@ impl APIConverter<Enum> for weedle::InterfaceDefinition<'_> {
                 .collect::<Result<Vec<_>>>()?,
             // Enums declared using the `[Enum] interface` syntax might have variants with fields.
             flat: false,
+            recursive: false,
+            docstring: None,
         })
     }
 }
@@ -188,14 +229,28 @@ impl APIConverter<Enum> for weedle::InterfaceDefinition<'_> {
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct Variant {
     pub(super) name: String,
+    // The name given by a `[Rename=...]` attribute, which the bindings use instead of `name`.
+    pub(super) rename: Option<String>,
     pub(super) fields: Vec<Field>,
+    pub(super) discriminant: i64,
+    pub(super) docstring: Option<String>,
 }
 
 impl Variant {
+    /// The name of the variant in the foreign language bindings.
     pub fn name(&self) -> &str {
+        self.rename.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The name of the variant in Rust, which differs from `name()` when it's renamed.
+    pub fn rust_name(&self) -> &str {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
     pub fn fields(&self) -> &[Field] {
         &self.fields
     }
@@ -204,6 +259,15 @@ impl Variant {
         !self.fields.is_empty()
     }
 
+    /// The discriminant of the variant in the Rust enum.
+    ///
+    /// This is only known for enums defined with `#[derive(uniffi::Enum)]`. For enums declared in
+    /// UDL, this is the position of the variant, which matches the Rust discriminant unless the
+    /// Rust enum sets discriminants explicitly.
+    pub fn discriminant(&self) -> i64 {
+        self.discriminant
+    }
+
     pub fn iter_types(&self) -> TypeIterator<'_> {
         Box::new(self.fields.iter().flat_map(Field::iter_types))
     }
@@ -213,7 +277,11 @@ impl From<uniffi_meta::VariantMetadata> for Variant {
     fn from(meta: uniffi_meta::VariantMetadata) -> Self {
         Self {
             name: meta.name,
+            rename: meta.rename,
             fields: meta.fields.into_iter().map(Into::into).collect(),
+            // Variants without an explicit discriminant get theirs from the enum.
+            discriminant: meta.discriminant.unwrap_or_default(),
+            docstring: meta.docstring,
         }
     }
 }
@@ -242,8 +310,10 @@ impl APIConverter<Variant> for weedle::interface::OperationInterfaceMember<'_> {
                 _ => bail!("enum interface members must have plain identifers as names"),
             }
         };
+        let attributes = VariantAttributes::try_from(self.attributes.as_ref())?;
         Ok(Variant {
             name,
+            rename: attributes.get_rename().map(ToOwned::to_owned),
             fields: self
                 .args
                 .body
@@ -251,6 +321,9 @@ impl APIConverter<Variant> for weedle::interface::OperationInterfaceMember<'_> {
                 .iter()
                 .map(|arg| arg.convert(ci))
                 .collect::<Result<Vec<_>>>()?,
+            // We don't know the position of the variant at this point, the enum fills it in.
+            discriminant: 0,
+            docstring: None,
         })
     }
 }
@@ -267,21 +340,22 @@ impl APIConverter<Field> for weedle::argument::Argument<'_> {
 impl APIConverter<Field> for weedle::argument::SingleArgument<'_> {
     fn convert(&self, ci: &mut ComponentInterface) -> Result<Field> {
         let type_ = ci.resolve_type_expression(&self.type_)?;
-        if let Type::Object(_) = type_ {
+        if let Type::Object { .. } = type_ {
             bail!("Objects cannot currently be used in enum variant data");
         }
         if self.default.is_some() {
             bail!("enum interface variant fields must not have default values");
         }
-        if self.attributes.is_some() {
-            bail!("enum interface variant fields must not have attributes");
-        }
+        let attributes = FieldAttributes::try_from(self.attributes.as_ref())?;
         // TODO: maybe we should use our own `Field` type here with just name and type,
         // rather than appropriating record::Field..?
         Ok(Field {
             name: self.identifier.0.to_string(),
+            rename: attributes.get_rename().map(ToOwned::to_owned),
             type_,
             default: None,
+            boxed: false,
+            docstring: None,
         })
     }
 }
@@ -295,15 +369,12 @@ mod test {
     fn test_duplicate_variants() {
         const UDL: &str = r#"
             namespace test{};
-            // Weird, but currently allowed!
-            // We should probably disallow this...
             enum Testing { "one", "two", "one" };
         "#;
-        let ci = ComponentInterface::from_webidl(UDL).unwrap();
-        assert_eq!(ci.enum_definitions().count(), 1);
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
         assert_eq!(
-            ci.get_enum_definition("Testing").unwrap().variants().len(),
-            3
+            err.to_string(),
+            "`Testing` has several variants named `one`"
         );
     }
 
@@ -431,4 +502,48 @@ mod test {
             Some(FFIType::RustBuffer)
         ));
     }
+
+    #[test]
+    fn test_discriminants() {
+        const UDL: &str = r##"
+            namespace test{};
+            enum TestEnum { "one", "two", "three" };
+        "##;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let e = ci.get_enum_definition("TestEnum").unwrap();
+        assert_eq!(
+            e.variants()
+                .iter()
+                .map(|v| v.discriminant())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+
+        let variant = |name: &str, discriminant| uniffi_meta::VariantMetadata {
+            name: name.to_owned(),
+            rename: None,
+            fields: vec![],
+            discriminant,
+            docstring: None,
+        };
+        let e: Enum = uniffi_meta::EnumMetadata {
+            module_path: vec!["test".to_owned()],
+            name: "TestEnum".to_owned(),
+            variants: vec![
+                variant("One", None),
+                variant("Ten", Some(10)),
+                variant("Eleven", None),
+                variant("MinusOne", Some(-1)),
+            ],
+            docstring: None,
+        }
+        .into();
+        assert_eq!(
+            e.variants()
+                .iter()
+                .map(|v| v.discriminant())
+                .collect::<Vec<_>>(),
+            vec![0, 10, 11, -1]
+        );
+    }
 }
@@ -84,6 +84,7 @@
 
 use anyhow::Result;
 
+use super::attributes::{EnumAttributes, InterfaceAttributes};
 use super::enum_::{Enum, Variant};
 use super::types::{Type, TypeIterator};
 use super::{APIConverter, ComponentInterface};
@@ -98,6 +99,8 @@ use super::{APIConverter, ComponentInterface};
 pub struct Error {
     pub name: String,
     enum_: Enum,
+    // Whether the error sends the chain of its sources along with it, see `[Source]`.
+    has_source: bool,
 }
 
 impl Error {
@@ -105,6 +108,7 @@ impl Error {
         Self {
             name: enum_.name.clone(),
             enum_,
+            has_source: false,
         }
     }
 
@@ -130,6 +134,12 @@ impl Error {
         self.enum_.is_flat()
     }
 
+    /// Whether the error is declared with `[Source]`, so that the descriptions of the chain of
+    /// its [`source`](std::error::Error::source)s are sent along with it.
+    pub fn has_source(&self) -> bool {
+        self.has_source
+    }
+
     pub fn iter_types(&self) -> TypeIterator<'_> {
         self.wrapped_enum().iter_types()
     }
@@ -137,13 +147,21 @@ impl Error {
 
 impl APIConverter<Error> for weedle::EnumDefinition<'_> {
     fn convert(&self, ci: &mut ComponentInterface) -> Result<Error> {
-        Ok(Error::from_enum(APIConverter::<Enum>::convert(self, ci)?))
+        let attrs = EnumAttributes::try_from(self.attributes.as_ref())?;
+        Ok(Error {
+            has_source: attrs.contains_source_attr(),
+            ..Error::from_enum(APIConverter::<Enum>::convert(self, ci)?)
+        })
     }
 }
 
 impl APIConverter<Error> for weedle::InterfaceDefinition<'_> {
     fn convert(&self, ci: &mut ComponentInterface) -> Result<Error> {
-        Ok(Error::from_enum(APIConverter::<Enum>::convert(self, ci)?))
+        let attrs = InterfaceAttributes::try_from(self.attributes.as_ref())?;
+        Ok(Error {
+            has_source: attrs.contains_source_attr(),
+            ..Error::from_enum(APIConverter::<Enum>::convert(self, ci)?)
+        })
     }
 }
 
@@ -176,16 +194,13 @@ mod test {
     fn test_duplicate_variants() {
         const UDL: &str = r#"
             namespace test{};
-            // Weird, but currently allowed!
-            // We should probably disallow this...
             [Error]
             enum Testing { "one", "two", "one" };
         "#;
-        let ci = ComponentInterface::from_webidl(UDL).unwrap();
-        assert_eq!(ci.error_definitions().len(), 1);
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
         assert_eq!(
-            ci.get_error_definition("Testing").unwrap().variants().len(),
-            3
+            err.to_string(),
+            "`Testing` has several variants named `one`"
         );
     }
 
@@ -212,5 +227,20 @@ mod test {
             vec!("One", "Two")
         );
         assert!(!error.is_flat());
+        assert!(!error.has_source());
+    }
+
+    #[test]
+    fn test_source() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Error, Source]
+            enum Flat { "one" };
+            [Error, Source]
+            interface Rich { One(string reason); };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert!(ci.get_error_definition("Flat").unwrap().has_source());
+        assert!(ci.get_error_definition("Rich").unwrap().has_source());
     }
 }
@@ -12,6 +12,11 @@
 //! need to know about them. But as a developer working on UniFFI itself, you're likely
 //! to spend a lot of time thinking about how these low-level types are used to represent
 //! the higher-level "interface types" from the [`super::types::Type`] enum.
+
+use std::collections::HashSet;
+
+use super::{Argument, Type};
+
 /// Represents the restricted set of low-level types that can be used to construct
 /// the C-style FFI layer between a rust component and its foreign language bindings.
 ///
@@ -31,7 +36,8 @@ pub enum FFIType {
     Int64,
     Float32,
     Float64,
-    /// A `*const c_void` pointer to a rust-owned `Arc<T>`.
+    /// A `*const c_void` pointer to rust-owned memory, like the bytes of a shared slice. Objects,
+    /// their weak references, iterators and shared slices are passed as `UInt64` handles instead.
     /// If you've got one of these, you must call the appropriate rust function to free it.
     /// The templates will generate a unique `free` function for each T.
     /// The inner string references the name of the `T` type.
@@ -46,6 +52,10 @@ pub enum FFIType {
     /// A pointer to a single function in to the foreign language.
     /// This function contains all the machinery to make callbacks work on the foreign language side.
     ForeignCallback,
+    /// A `#[repr(C)]` struct mirroring a record made only of numbers and booleans, passed by value
+    /// instead of being serialized into a byte buffer.
+    /// The inner string is the name of the record.
+    Struct(String),
     // TODO: you can imagine a richer structural typesystem here, e.g. `Ref<String>` or something.
     // We don't need that yet and it's possible we never will, so it isn't here for now.
 }
@@ -73,6 +83,28 @@ impl FFIFunction {
     pub fn return_type(&self) -> Option<&FFIType> {
         self.return_type.as_ref()
     }
+
+    /// Pass the records which are C structs by value rather than in a `RustBuffer`, given the
+    /// arguments and the return type the function was derived from.
+    pub(super) fn pass_structs_by_value(
+        &mut self,
+        arguments: &[Argument],
+        return_type: Option<&Type>,
+        ffi_structs: &HashSet<String>,
+    ) {
+        let struct_type = |type_: &Type| match type_ {
+            Type::Record(name) if ffi_structs.contains(name) => Some(FFIType::Struct(name.clone())),
+            _ => None,
+        };
+        for (ffi_arg, arg) in self.arguments.iter_mut().zip(arguments) {
+            if let Some(type_) = struct_type(&arg.type_) {
+                ffi_arg.type_ = type_;
+            }
+        }
+        if let Some(type_) = return_type.and_then(struct_type) {
+            self.return_type = Some(type_);
+        }
+    }
 }
 
 /// Represents an argument to an FFI function.
@@ -0,0 +1,490 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Canonical formatting of UDL files.
+//!
+//! The weedle syntax tree drops the comments and the layout of the UDL, so the formatter works
+//! on the tokens of the UDL instead, comments included, and only decides the whitespace between
+//! them. The UDL is parsed first, so that only valid UDL is formatted, and the tokens of the
+//! result are checked to be the same as those of the input.
+//!
+//! The canonical format has:
+//!
+//!  * one definition per paragraph, separated by a blank line,
+//!  * one member, or enum value, per line, indented by [`FormatOptions::indent`] spaces,
+//!  * the attributes of the definitions and members on their own line before them,
+//!  * a single space between the tokens of a line, except inside `<...>`, after `(` and `[`,
+//!    and around the `=` of attributes,
+//!  * single blank lines kept between members, where the UDL had some.
+//!
+//! With [`FormatOptions::sort`], the definitions are also ordered, with the `include` directives
+//! first, then the namespace, then the other definitions by name. The comments before a
+//! definition move with it, except those at the top of the file, separated from the first
+//! definition by a blank line.
+
+use anyhow::{bail, ensure, Result};
+
+/// How to format UDL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The number of spaces per level of indentation.
+    pub indent: usize,
+    /// Whether to order the definitions.
+    pub sort: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            sort: false,
+        }
+    }
+}
+
+/// Format UDL in the canonical format.
+pub fn format_udl(udl: &str, options: FormatOptions) -> Result<String> {
+    super::parse_definitions(udl)?;
+    let tokens = tokenize(udl)?;
+    let mut items = split_items(&tokens);
+    if options.sort {
+        sort_items(&mut items);
+    }
+    let mut formatted = items
+        .iter()
+        .map(|item| format_item(item, options.indent))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+
+    // Sorting reorders the tokens, but formatting alone must only change the whitespace.
+    if !options.sort {
+        ensure!(
+            kinds(&tokenize(&formatted)?) == kinds(&tokens),
+            "formatting changed the tokens of the UDL, which is a bug of the formatter"
+        );
+    }
+    Ok(formatted)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind<'a> {
+    /// An identifier, keyword or number.
+    Word(&'a str),
+    /// A string literal, with its quotes.
+    Str(&'a str),
+    Punct(char),
+    /// A `//` or `/* */` comment, without the line break ending it.
+    Comment(&'a str),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: Kind<'a>,
+    /// The number of line breaks between the previous token and this one.
+    newlines: usize,
+}
+
+impl Token<'_> {
+    fn is_comment(&self) -> bool {
+        matches!(self.kind, Kind::Comment(_))
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.kind == Kind::Punct(c)
+    }
+}
+
+fn tokenize(udl: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut newlines = 0;
+    let mut rest = udl;
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_whitespace() {
+            newlines += usize::from(c == '\n');
+            rest = &rest[c.len_utf8()..];
+            continue;
+        } else if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            match rest.find("*/") {
+                Some(end) => end + 2,
+                None => bail!("unterminated comment"),
+            }
+        } else if c == '"' {
+            match rest[1..].find('"') {
+                Some(end) => end + 2,
+                None => bail!("unterminated string"),
+            }
+        } else if is_word_char(c) || (c == '-' && rest[1..].starts_with(is_word_char)) {
+            let start = c.len_utf8();
+            start
+                + rest[start..]
+                    .find(|c| !is_word_char(c))
+                    .unwrap_or(rest.len() - start)
+        } else if "{}[]()<>,;=:?".contains(c) {
+            1
+        } else {
+            bail!("unexpected character `{c}`")
+        };
+        let text = &rest[..len];
+        let kind = match c {
+            '/' => Kind::Comment(text.trim_end()),
+            '"' => Kind::Str(text),
+            _ if len == 1 && !is_word_char(c) => Kind::Punct(c),
+            _ => Kind::Word(text),
+        };
+        tokens.push(Token { kind, newlines });
+        newlines = 0;
+        rest = &rest[len..];
+    }
+    Ok(tokens)
+}
+
+fn kinds<'a>(tokens: &[Token<'a>]) -> Vec<Kind<'a>> {
+    tokens.iter().map(|t| t.kind).collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// A definition, with the comments before and after it, or a group of comments on their own.
+struct Item<'a> {
+    tokens: Vec<Token<'a>>,
+}
+
+impl Item<'_> {
+    fn is_comment(&self) -> bool {
+        self.tokens.iter().all(Token::is_comment)
+    }
+
+    /// The order of the definition: the `include` directives, the namespace, then the other
+    /// definitions by name.
+    fn sort_key(&self) -> (u8, &str) {
+        let mut tokens = self.tokens.iter().filter(|t| !t.is_comment()).peekable();
+        // Skip the attributes of the definition.
+        if tokens.peek().map_or(false, |t| t.is_punct('[')) {
+            tokens.find(|t| t.is_punct(']'));
+        }
+        let mut words = tokens.filter_map(|t| match t.kind {
+            Kind::Word(w) => Some(w),
+            Kind::Str(s) => Some(s),
+            _ => None,
+        });
+        match words.next() {
+            Some("include") => (0, words.next().unwrap_or_default()),
+            Some("namespace") => (1, words.next().unwrap_or_default()),
+            // The name of a typedef is at its end, after the type.
+            Some("typedef") => (2, words.last().unwrap_or_default()),
+            first => (
+                2,
+                std::iter::once(first.unwrap_or_default())
+                    .chain(words)
+                    .find(|w| {
+                        !matches!(
+                            *w,
+                            "callback" | "interface" | "partial" | "dictionary" | "enum" | "mixin"
+                        )
+                    })
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Split the tokens into the top-level definitions, which are formatted on their own.
+fn split_items<'a>(tokens: &[Token<'a>]) -> Vec<Item<'a>> {
+    let mut items: Vec<Item<'a>> = Vec::new();
+    let mut current = Vec::new();
+    let mut depth = 0usize;
+    for token in tokens {
+        if token.is_comment() && token.newlines == 0 && current.is_empty() {
+            // A comment at the end of the line of the previous definition belongs to it.
+            if let Some(previous) = items.last_mut() {
+                previous.tokens.push(*token);
+                continue;
+            }
+        }
+        if token.newlines >= 2 && !current.is_empty() && current.iter().all(Token::is_comment) {
+            // Comments separated from what follows by a blank line stand on their own.
+            items.push(Item {
+                tokens: std::mem::take(&mut current),
+            });
+        }
+        current.push(*token);
+        match token.kind {
+            Kind::Punct('{' | '(' | '[' | '<') => depth += 1,
+            Kind::Punct('}' | ')' | ']' | '>') => depth = depth.saturating_sub(1),
+            Kind::Punct(';') if depth == 0 => items.push(Item {
+                tokens: std::mem::take(&mut current),
+            }),
+            _ => {}
+        }
+    }
+    if !current.is_empty() {
+        items.push(Item { tokens: current });
+    }
+    items
+}
+
+/// Sort the definitions, keeping the comments at the top of the file at the top.
+fn sort_items(items: &mut Vec<Item<'_>>) {
+    let header = items.iter().take_while(|item| item.is_comment()).count();
+    // The other groups of comments move with the definition following them.
+    let mut definitions: Vec<Item<'_>> = Vec::new();
+    let mut comments = Vec::new();
+    for item in items.drain(header..) {
+        if item.is_comment() {
+            comments.push(item);
+        } else if comments.is_empty() {
+            definitions.push(item);
+        } else {
+            let mut tokens: Vec<_> = comments.drain(..).flat_map(|c| c.tokens).collect();
+            // Keep a blank line between the comments and the definition.
+            let mut rest = item.tokens;
+            rest[0].newlines = rest[0].newlines.max(2);
+            tokens.extend(rest);
+            definitions.push(Item { tokens });
+        }
+    }
+    definitions.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+    items.extend(definitions);
+    items.extend(comments);
+}
+
+/// Format a top-level definition, without the line break ending it.
+fn format_item(item: &Item<'_>, indent: usize) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        indent,
+        depth: 0,
+        nesting: Vec::new(),
+        line_start: true,
+        previous: None,
+    };
+    let is_callback_function = matches!(
+        item.tokens.iter().filter(|t| !t.is_comment()).take(2).map(|t| t.kind).collect::<Vec<_>>()[..],
+        [Kind::Word("callback"), Kind::Word(w)] if w != "interface"
+    );
+    for (i, token) in item.tokens.iter().enumerate() {
+        let next = item.tokens[i + 1..].iter().find(|t| !t.is_comment());
+        printer.token(token, next, is_callback_function);
+    }
+    printer.out.trim_end().to_string()
+}
+
+struct Printer<'a> {
+    out: String,
+    indent: usize,
+    /// The depth of `{}` blocks, which are indented.
+    depth: usize,
+    /// The `(`, `[` and `<` which are open on the current line.
+    nesting: Vec<char>,
+    line_start: bool,
+    previous: Option<Kind<'a>>,
+}
+
+impl<'a> Printer<'a> {
+    fn newline(&mut self) {
+        if !self.line_start {
+            self.out.push('\n');
+            self.line_start = true;
+        }
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.line_start {
+            self.out.push_str(&" ".repeat(self.depth * self.indent));
+            self.line_start = false;
+        } else if space {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+    }
+
+    /// Whether a word written now needs a space before it.
+    fn space_before_word(&self) -> bool {
+        match self.previous {
+            None => false,
+            Some(Kind::Punct('(' | '[' | '<')) => false,
+            Some(Kind::Punct('=')) => self.nesting.last() != Some(&'['),
+            _ => true,
+        }
+    }
+
+    fn token(&mut self, token: &Token<'a>, next: Option<&Token<'a>>, is_callback_function: bool) {
+        if token.newlines > 0 || self.out.is_empty() {
+            self.newline();
+            // Keep a blank line where there was one, but not at the start of a block.
+            if token.newlines >= 2
+                && self.line_start
+                && !self.out.is_empty()
+                && self.previous != Some(Kind::Punct('{'))
+            {
+                self.out.push('\n');
+            }
+        } else if token.is_comment() && self.line_start && !self.out.is_empty() {
+            // A comment at the end of a line stays there.
+            self.out.pop();
+            self.line_start = false;
+        }
+
+        match token.kind {
+            Kind::Comment(text) => {
+                self.write(text, true);
+                if text.starts_with("//") {
+                    self.newline();
+                }
+                // Comments don't change the spacing of the tokens around them.
+                return;
+            }
+            Kind::Word(text) | Kind::Str(text) => {
+                let space = self.space_before_word();
+                self.write(text, space);
+            }
+            Kind::Punct('{') => {
+                self.write("{", true);
+                if next.map_or(false, |next| !next.is_punct('}')) {
+                    self.depth += 1;
+                    self.newline();
+                }
+            }
+            Kind::Punct('}') => {
+                if self.previous != Some(Kind::Punct('{')) {
+                    self.newline();
+                    self.depth = self.depth.saturating_sub(1);
+                }
+                self.write("}", false);
+            }
+            Kind::Punct(';') => {
+                self.write(";", false);
+                self.newline();
+            }
+            Kind::Punct(',') => {
+                self.write(",", false);
+                // The values of enums are on their own line.
+                if self.nesting.is_empty() {
+                    self.newline();
+                }
+            }
+            Kind::Punct('(') => {
+                // Only the type of callback functions is separated from their arguments.
+                let space = is_callback_function && self.depth == 0 && self.nesting.is_empty();
+                self.write("(", space);
+                self.nesting.push('(');
+            }
+            Kind::Punct('[') => {
+                // The attributes of definitions and members are on their own line.
+                if self.nesting.is_empty() {
+                    self.newline();
+                }
+                let space = self.space_before_word();
+                self.write("[", space);
+                self.nesting.push('[');
+            }
+            Kind::Punct('<') => {
+                self.write("<", false);
+                self.nesting.push('<');
+            }
+            Kind::Punct(c @ (')' | ']' | '>')) => {
+                self.nesting.pop();
+                self.write(&c.to_string(), false);
+                if c == ']' && self.nesting.is_empty() {
+                    self.newline();
+                }
+            }
+            Kind::Punct('=') => {
+                let space = self.nesting.last() != Some(&'[');
+                self.write("=", space);
+            }
+            Kind::Punct('?') => self.write("?", false),
+            Kind::Punct(c) => self.write(&c.to_string(), true),
+        }
+        self.previous = Some(token.kind);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn format(udl: &str) -> String {
+        format_udl(udl, FormatOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        const UDL: &str = r#"
+// The example namespace.
+namespace  example { [Throws=Error] sequence< u8 >? get_bytes( [ByRef]string  key, optional u32 count = 3 ) ;
+  void clear(); };
+[Error] enum Error { "NotFound" , "Invalid" };   // The errors.
+dictionary Point: Shape {f64 x;
+
+
+  f64 y; record<string,Point?> others; };
+callback Filter=boolean(string value);
+[Custom]
+typedef string Guid;
+"#;
+        const EXPECTED: &str = r#"// The example namespace.
+namespace example {
+    [Throws=Error]
+    sequence<u8>? get_bytes([ByRef] string key, optional u32 count = 3);
+    void clear();
+};
+
+[Error]
+enum Error {
+    "NotFound",
+    "Invalid"
+}; // The errors.
+
+dictionary Point : Shape {
+    f64 x;
+
+    f64 y;
+    record<string, Point?> others;
+};
+
+callback Filter = boolean (string value);
+
+[Custom]
+typedef string Guid;
+"#;
+        assert_eq!(format(UDL), EXPECTED);
+        // Formatting is idempotent.
+        assert_eq!(format(EXPECTED), EXPECTED);
+    }
+
+    #[test]
+    fn test_format_comments() {
+        const UDL: &str = "/* License. */\n\n// Header.\n\nnamespace example {\n  // Hello.\n  /* Inline */ void hello(); // Trailing.\n};\ninterface Obj { constructor(); };\n";
+        assert_eq!(
+            format(UDL),
+            "/* License. */\n\n// Header.\n\nnamespace example {\n    // Hello.\n    /* Inline */ void hello(); // Trailing.\n};\n\ninterface Obj {\n    constructor();\n};\n",
+        );
+    }
+
+    #[test]
+    fn test_format_options() {
+        const UDL: &str = "// Header.\n\n// About Zeta.\ndictionary Zeta { u8 z; };\nenum Alpha { \"A\" };\nnamespace example {};\ninclude \"other.udl\";\n";
+        let options = FormatOptions {
+            indent: 2,
+            sort: true,
+        };
+        assert_eq!(
+            format_udl(UDL, options).unwrap(),
+            "// Header.\n\ninclude \"other.udl\";\n\nnamespace example {};\n\nenum Alpha {\n  \"A\"\n};\n\n// About Zeta.\ndictionary Zeta {\n  u8 z;\n};\n",
+        );
+    }
+
+    #[test]
+    fn test_format_invalid_udl() {
+        assert!(format_udl("namespace example {", FormatOptions::default()).is_err());
+    }
+}
@@ -51,6 +51,10 @@ use super::{APIConverter, ComponentInterface};
 /// and has a corresponding standalone function in the foreign language bindings.
 ///
 /// In the FFI, this will be a standalone function with appropriately lowered types.
+///
+/// Functions with the `[Method=...]` attribute are methods of a record or enum instead. They're
+/// still standalone functions in the FFI, which receive the record or enum as their first
+/// argument, but the foreign language bindings expose them as methods of its class.
 #[derive(Debug, Clone)]
 pub struct Function {
     pub(super) name: String,
@@ -58,6 +62,14 @@ pub struct Function {
     pub(super) return_type: Option<Type>,
     pub(super) ffi_func: FFIFunction,
     pub(super) attributes: FunctionAttributes,
+    // The error named by the `[Throws=...]` attribute, resolved by the `ComponentInterface`.
+    pub(super) throws: Option<Type>,
+    // Whether `throws` is the error of the custom types of the arguments rather than named by
+    // `[Throws=...]`, in which case the Rust function doesn't return a `Result`.
+    pub(super) throws_from_arguments: bool,
+    // The record or enum named by the `[Method=...]` attribute, resolved by the `ComponentInterface`.
+    pub(super) self_type: Option<Type>,
+    pub(super) docstring: Option<String>,
 }
 
 impl Function {
@@ -65,12 +77,32 @@ impl Function {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
     pub fn arguments(&self) -> Vec<&Argument> {
         self.arguments.iter().collect()
     }
 
+    pub fn has_default_arguments(&self) -> bool {
+        self.arguments.iter().any(|a| a.default.is_some())
+    }
+
+    // Methods of records and enums have a special implicit first argument for their receiver,
+    // hence `arguments` and `full_arguments` are different.
     pub fn full_arguments(&self) -> Vec<Argument> {
-        self.arguments.to_vec()
+        self.self_type
+            .iter()
+            .map(|self_type| Argument {
+                name: "receiver".to_string(),
+                type_: self_type.clone(),
+                by_ref: true,
+                optional: false,
+                default: None,
+            })
+            .chain(self.arguments.iter().cloned())
+            .collect()
     }
 
     pub fn return_type(&self) -> Option<&Type> {
@@ -81,8 +113,18 @@ impl Function {
         &self.ffi_func
     }
 
+    /// The record or enum this function is a method of, if any.
+    pub fn self_type(&self) -> Option<&Type> {
+        self.self_type.as_ref()
+    }
+
+    /// The name of the record or enum this function is a method of, if any.
+    pub fn self_type_name(&self) -> Option<&str> {
+        self.attributes.get_method_of()
+    }
+
     pub fn throws(&self) -> bool {
-        self.attributes.get_throws_err().is_some()
+        self.throws.is_some()
     }
 
     pub fn throws_name(&self) -> Option<&str> {
@@ -90,19 +132,26 @@ impl Function {
     }
 
     pub fn throws_type(&self) -> Option<Type> {
-        self.attributes
-            .get_throws_err()
-            .map(|name| Type::Error(name.to_owned()))
+        self.throws.clone()
+    }
+
+    /// Whether the function only throws the errors of the custom types of its arguments, so the
+    /// Rust function itself doesn't return a `Result`.
+    pub fn throws_from_arguments(&self) -> bool {
+        self.throws_from_arguments
     }
 
     pub fn derive_ffi_func(&mut self, ci_prefix: &str) -> Result<()> {
         // The name is already set if the function is defined through a proc-macro invocation
         // rather than in UDL. Don't overwrite it in that case.
         if self.ffi_func.name.is_empty() {
-            self.ffi_func.name = format!("{ci_prefix}_{}", self.name);
+            self.ffi_func.name = match self.self_type_name() {
+                Some(type_name) => format!("{ci_prefix}_{type_name}_{}", self.name),
+                None => format!("{ci_prefix}_{}", self.name),
+            };
         }
 
-        self.ffi_func.arguments = self.arguments.iter().map(|arg| arg.into()).collect();
+        self.ffi_func.arguments = self.full_arguments().iter().map(|arg| arg.into()).collect();
         self.ffi_func.return_type = self.return_type.as_ref().map(|rt| rt.into());
         Ok(())
     }
@@ -122,15 +171,12 @@ impl From<uniffi_meta::FnParamMetadata> for Argument {
 
 impl From<uniffi_meta::FnMetadata> for Function {
     fn from(meta: uniffi_meta::FnMetadata) -> Self {
-        let ffi_name = meta.ffi_symbol_name();
-
         let return_type = meta.return_type.map(|out| convert_type(&out));
         let arguments = meta.inputs.into_iter().map(Into::into).collect();
 
-        let ffi_func = FFIFunction {
-            name: ffi_name,
-            ..FFIFunction::default()
-        };
+        // The FFI function is named by `ComponentInterface::add_fn_meta`, which knows the
+        // `ffi_prefix` of the configuration.
+        let ffi_func = FFIFunction::default();
 
         Self {
             name: meta.name,
@@ -138,6 +184,10 @@ impl From<uniffi_meta::FnMetadata> for Function {
             return_type,
             ffi_func,
             attributes: Default::default(),
+            throws: None,
+            throws_from_arguments: false,
+            self_type: None,
+            docstring: meta.docstring,
         }
     }
 }
@@ -169,6 +219,16 @@ impl APIConverter<Function> for weedle::namespace::NamespaceMember<'_> {
 impl APIConverter<Function> for weedle::namespace::OperationNamespaceMember<'_> {
     fn convert(&self, ci: &mut ComponentInterface) -> Result<Function> {
         let return_type = ci.resolve_return_type_expression(&self.return_type)?;
+        let attributes = FunctionAttributes::try_from(self.attributes.as_ref())?;
+        let self_type = match attributes.get_method_of() {
+            None => None,
+            Some(type_name) => match ci.get_type(type_name) {
+                Some(type_ @ (Type::Record(_) | Type::Enum(_))) => Some(type_),
+                _ => bail!(
+                    "`[Method={type_name}]` functions can only be methods of dictionaries and enums"
+                ),
+            },
+        };
         Ok(Function {
             name: match self.identifier {
                 None => bail!("anonymous functions are not supported {:?}", self),
@@ -177,7 +237,11 @@ impl APIConverter<Function> for weedle::namespace::OperationNamespaceMember<'_>
             return_type,
             arguments: self.args.body.list.convert(ci)?,
             ffi_func: Default::default(),
-            attributes: FunctionAttributes::try_from(self.attributes.as_ref())?,
+            throws: ci.resolve_throws_type(attributes.get_throws_err()),
+            throws_from_arguments: false,
+            self_type,
+            attributes,
+            docstring: None,
         })
     }
 }
@@ -241,11 +305,16 @@ impl APIConverter<Argument> for weedle::argument::SingleArgument<'_> {
             None => None,
             Some(v) => Some(convert_default_value(&v.value, &type_)?),
         };
-        let by_ref = ArgumentAttributes::try_from(self.attributes.as_ref())?.by_ref();
+        let attributes = ArgumentAttributes::try_from(self.attributes.as_ref())?;
+        // Arguments are passed by position, so a renamed argument doesn't need its Rust name.
+        let name = attributes
+            .get_rename()
+            .unwrap_or(self.identifier.0)
+            .to_string();
         Ok(Argument {
-            name: self.identifier.0.to_string(),
+            name,
             type_,
-            by_ref,
+            by_ref: attributes.by_ref(),
             optional: self.optional.is_some(),
             default,
         })
@@ -0,0 +1,699 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! # Machine-readable description of component interfaces.
+//!
+//! The [`ComponentInterface`] is an implementation detail of the bindings generators, full of FFI
+//! details, which changes from one version of UniFFI to the next.  Tools like documentation sites
+//! or API linters get the interfaces as JSON instead, from `uniffi-bindgen print-interface`, with
+//! the types of this module: the definitions of the components once the UDL and the proc-macro
+//! metadata are merged, and the types resolved, without anything about the FFI.
+//!
+//! The JSON is stable: fields may be added, but changing or removing any bumps
+//! [`JSON_FORMAT_VERSION`].  Types and literals are objects with a `kind`, e.g.
+//! `{"kind": "sequence", "inner": {"kind": "u32"}}`, so that tools don't parse them from strings.
+//! The JSON can be read back with these types, which is how `uniffi-bindgen diff` compares
+//! interfaces.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::enum_::Variant;
+use super::{
+    Argument, CallbackInterface, ComponentInterface, Constant, Constructor, Enum, Error,
+    ExternalKind, Field, Function, Literal, Method, Object, ObjectImpl, Record, Type,
+};
+
+/// The version of the JSON format of the interfaces, bumped on incompatible changes.
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+/// The interfaces of a set of components, the top-level JSON value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfacesJson {
+    pub format_version: u32,
+    pub components: Vec<ComponentJson>,
+}
+
+impl InterfacesJson {
+    pub fn new<'a>(components: impl IntoIterator<Item = &'a ComponentInterface>) -> Self {
+        Self {
+            format_version: JSON_FORMAT_VERSION,
+            components: components.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentJson {
+    pub namespace: String,
+    pub functions: Vec<FunctionJson>,
+    pub objects: Vec<ObjectJson>,
+    pub records: Vec<RecordJson>,
+    pub enums: Vec<EnumJson>,
+    pub errors: Vec<EnumJson>,
+    pub callback_interfaces: Vec<CallbackInterfaceJson>,
+    pub constants: Vec<ConstantJson>,
+    pub custom_types: Vec<CustomTypeJson>,
+    pub external_types: Vec<ExternalTypeJson>,
+}
+
+impl From<&ComponentInterface> for ComponentJson {
+    fn from(ci: &ComponentInterface) -> Self {
+        Self {
+            namespace: ci.namespace().to_string(),
+            functions: ci
+                .function_definitions()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            objects: ci.object_definitions().iter().map(Into::into).collect(),
+            records: ci.record_definitions().map(Into::into).collect(),
+            enums: ci.enum_definitions().map(Into::into).collect(),
+            errors: ci.error_definitions().iter().map(Into::into).collect(),
+            callback_interfaces: ci
+                .callback_interface_definitions()
+                .iter()
+                .map(Into::into)
+                .collect(),
+            constants: ci.constant_definitions().iter().map(Into::into).collect(),
+            custom_types: ci
+                .iter_custom_types()
+                .map(|(name, builtin)| CustomTypeJson {
+                    name: name.clone(),
+                    builtin: builtin.into(),
+                })
+                .collect(),
+            external_types: ci
+                .iter_external_types()
+                .map(|(name, crate_name, kind)| ExternalTypeJson {
+                    name: name.clone(),
+                    crate_name: crate_name.clone(),
+                    kind: kind.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    pub arguments: Vec<ArgumentJson>,
+    pub return_type: Option<TypeJson>,
+    pub throws: Option<TypeJson>,
+}
+
+impl From<&Function> for FunctionJson {
+    fn from(func: &Function) -> Self {
+        Self {
+            name: func.name().to_string(),
+            docstring: func.docstring().map(ToString::to_string),
+            arguments: func.arguments().into_iter().map(Into::into).collect(),
+            return_type: func.return_type().map(Into::into),
+            throws: func.throws_type().as_ref().map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgumentJson {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: TypeJson,
+    pub by_ref: bool,
+    pub default: Option<LiteralJson>,
+}
+
+impl From<&Argument> for ArgumentJson {
+    fn from(arg: &Argument) -> Self {
+        Self {
+            name: arg.name().to_string(),
+            type_: arg.type_().into(),
+            by_ref: arg.by_ref(),
+            default: arg.default_value().map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    pub imp: ObjectImplJson,
+    pub constructors: Vec<ConstructorJson>,
+    pub methods: Vec<MethodJson>,
+}
+
+impl From<&Object> for ObjectJson {
+    fn from(obj: &Object) -> Self {
+        Self {
+            name: obj.name().to_string(),
+            docstring: obj.docstring().map(ToString::to_string),
+            imp: obj.imp().into(),
+            constructors: obj.constructors().into_iter().map(Into::into).collect(),
+            methods: obj.all_methods().into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectImplJson {
+    Struct,
+    Trait,
+}
+
+impl From<ObjectImpl> for ObjectImplJson {
+    fn from(imp: ObjectImpl) -> Self {
+        match imp {
+            ObjectImpl::Struct => Self::Struct,
+            ObjectImpl::Trait => Self::Trait,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstructorJson {
+    pub name: String,
+    pub arguments: Vec<ArgumentJson>,
+    pub throws: Option<TypeJson>,
+}
+
+impl From<&Constructor> for ConstructorJson {
+    fn from(cons: &Constructor) -> Self {
+        Self {
+            name: cons.name().to_string(),
+            arguments: cons.arguments().into_iter().map(Into::into).collect(),
+            throws: cons.throws_type().as_ref().map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    pub is_static: bool,
+    pub arguments: Vec<ArgumentJson>,
+    pub return_type: Option<TypeJson>,
+    pub throws: Option<TypeJson>,
+}
+
+impl From<&Method> for MethodJson {
+    fn from(meth: &Method) -> Self {
+        Self {
+            name: meth.name().to_string(),
+            docstring: meth.docstring().map(ToString::to_string),
+            is_static: meth.is_static(),
+            arguments: meth.arguments().into_iter().map(Into::into).collect(),
+            return_type: meth.return_type().map(Into::into),
+            throws: meth.throws_type().as_ref().map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    pub fields: Vec<FieldJson>,
+}
+
+impl From<&Record> for RecordJson {
+    fn from(rec: &Record) -> Self {
+        Self {
+            name: rec.name().to_string(),
+            docstring: rec.docstring().map(ToString::to_string),
+            fields: rec.fields().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: TypeJson,
+    pub default: Option<LiteralJson>,
+}
+
+impl From<&Field> for FieldJson {
+    fn from(field: &Field) -> Self {
+        Self {
+            name: field.name().to_string(),
+            docstring: field.docstring().map(ToString::to_string),
+            type_: field.type_().into(),
+            default: field.default_value().map(Into::into),
+        }
+    }
+}
+
+/// An enum, or an error, which is an enum thrown by functions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    /// Whether none of the variants have fields.
+    pub flat: bool,
+    pub variants: Vec<VariantJson>,
+}
+
+impl From<&Enum> for EnumJson {
+    fn from(enum_: &Enum) -> Self {
+        Self {
+            name: enum_.name().to_string(),
+            docstring: enum_.docstring().map(ToString::to_string),
+            flat: enum_.is_flat(),
+            variants: enum_.variants().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&Error> for EnumJson {
+    fn from(error: &Error) -> Self {
+        error.wrapped_enum().into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantJson {
+    pub name: String,
+    pub docstring: Option<String>,
+    pub discriminant: i64,
+    pub fields: Vec<FieldJson>,
+}
+
+impl From<&Variant> for VariantJson {
+    fn from(variant: &Variant) -> Self {
+        Self {
+            name: variant.name().to_string(),
+            docstring: variant.docstring().map(ToString::to_string),
+            discriminant: variant.discriminant(),
+            fields: variant.fields().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallbackInterfaceJson {
+    pub name: String,
+    /// Whether it's declared as a `callback` function, with a single method.
+    pub is_function: bool,
+    pub methods: Vec<MethodJson>,
+}
+
+impl From<&CallbackInterface> for CallbackInterfaceJson {
+    fn from(cbi: &CallbackInterface) -> Self {
+        Self {
+            name: cbi.name().to_string(),
+            is_function: cbi.is_function(),
+            methods: cbi.methods().into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstantJson {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: TypeJson,
+    pub value: LiteralJson,
+}
+
+impl From<&Constant> for ConstantJson {
+    fn from(constant: &Constant) -> Self {
+        Self {
+            name: constant.name().to_string(),
+            type_: constant.type_().into(),
+            value: constant.value().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomTypeJson {
+    pub name: String,
+    pub builtin: TypeJson,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalTypeJson {
+    pub name: String,
+    pub crate_name: String,
+    pub kind: ExternalKindJson,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalKindJson {
+    DataClass,
+    Error,
+    CallbackInterface,
+}
+
+impl From<ExternalKind> for ExternalKindJson {
+    fn from(kind: ExternalKind) -> Self {
+        match kind {
+            ExternalKind::DataClass => Self::DataClass,
+            ExternalKind::Error => Self::Error,
+            ExternalKind::CallbackInterface => Self::CallbackInterface,
+        }
+    }
+}
+
+/// A type, named like in the UDL where it can be.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeJson {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+    Boolean,
+    Char,
+    String,
+    Timestamp,
+    Duration,
+    #[serde(rename = "offset_datetime")]
+    OffsetDateTime,
+    #[serde(rename = "bigint")]
+    BigInt,
+    Decimal,
+    Url,
+    Uuid,
+    Json,
+    SharedSlice,
+    Object {
+        name: String,
+        imp: ObjectImplJson,
+    },
+    Record {
+        name: String,
+    },
+    Enum {
+        name: String,
+    },
+    Error {
+        name: String,
+    },
+    CallbackInterface {
+        name: String,
+    },
+    Optional {
+        inner: Box<TypeJson>,
+    },
+    Sequence {
+        inner: Box<TypeJson>,
+    },
+    Set {
+        inner: Box<TypeJson>,
+    },
+    Map {
+        key: Box<TypeJson>,
+        value: Box<TypeJson>,
+    },
+    Tuple {
+        elements: Vec<TypeJson>,
+    },
+    Result {
+        ok: Box<TypeJson>,
+        err: Box<TypeJson>,
+    },
+    Weak {
+        inner: Box<TypeJson>,
+    },
+    Iterator {
+        inner: Box<TypeJson>,
+    },
+    External {
+        name: String,
+        crate_name: String,
+        external_kind: ExternalKindJson,
+    },
+    Custom {
+        name: String,
+        builtin: Box<TypeJson>,
+    },
+}
+
+impl From<&Type> for TypeJson {
+    fn from(type_: &Type) -> Self {
+        let boxed = |t: &Type| Box::new(Self::from(t));
+        match type_ {
+            Type::UInt8 => Self::U8,
+            Type::Int8 => Self::I8,
+            Type::UInt16 => Self::U16,
+            Type::Int16 => Self::I16,
+            Type::UInt32 => Self::U32,
+            Type::Int32 => Self::I32,
+            Type::UInt64 => Self::U64,
+            Type::Int64 => Self::I64,
+            Type::Float32 => Self::F32,
+            Type::Float64 => Self::F64,
+            Type::Boolean => Self::Boolean,
+            Type::Char => Self::Char,
+            Type::String => Self::String,
+            Type::Timestamp => Self::Timestamp,
+            Type::Duration => Self::Duration,
+            Type::OffsetDateTime => Self::OffsetDateTime,
+            Type::BigInt => Self::BigInt,
+            Type::Decimal => Self::Decimal,
+            Type::Url => Self::Url,
+            Type::Uuid => Self::Uuid,
+            Type::Json => Self::Json,
+            Type::SharedSlice => Self::SharedSlice,
+            Type::Object { name, imp } => Self::Object {
+                name: name.clone(),
+                imp: (*imp).into(),
+            },
+            Type::Record(name) => Self::Record { name: name.clone() },
+            Type::Enum(name) => Self::Enum { name: name.clone() },
+            Type::Error(name) => Self::Error { name: name.clone() },
+            Type::CallbackInterface(name) => Self::CallbackInterface { name: name.clone() },
+            Type::Optional(inner) => Self::Optional {
+                inner: boxed(inner),
+            },
+            Type::Sequence(inner) => Self::Sequence {
+                inner: boxed(inner),
+            },
+            Type::Set(inner) => Self::Set {
+                inner: boxed(inner),
+            },
+            Type::Map(key, value) => Self::Map {
+                key: boxed(key),
+                value: boxed(value),
+            },
+            Type::Tuple(elements) => Self::Tuple {
+                elements: elements.iter().map(Into::into).collect(),
+            },
+            Type::Result(ok, err) => Self::Result {
+                ok: boxed(ok),
+                err: boxed(err),
+            },
+            Type::Weak(inner) => Self::Weak {
+                inner: boxed(inner),
+            },
+            Type::Iterator(inner) => Self::Iterator {
+                inner: boxed(inner),
+            },
+            Type::External {
+                name,
+                crate_name,
+                kind,
+            } => Self::External {
+                name: name.clone(),
+                crate_name: crate_name.clone(),
+                external_kind: (*kind).into(),
+            },
+            Type::Custom { name, builtin } => Self::Custom {
+                name: name.clone(),
+                builtin: boxed(builtin),
+            },
+            Type::Unresolved { name } => {
+                unreachable!("Type `{name}` must be resolved before it's described")
+            }
+        }
+    }
+}
+
+/// The type as it's written in the UDL, e.g. `record<DOMString, sequence<u32>?>`.
+impl fmt::Display for TypeJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::U8 => write!(f, "u8"),
+            Self::I8 => write!(f, "i8"),
+            Self::U16 => write!(f, "u16"),
+            Self::I16 => write!(f, "i16"),
+            Self::U32 => write!(f, "u32"),
+            Self::I32 => write!(f, "i32"),
+            Self::U64 => write!(f, "u64"),
+            Self::I64 => write!(f, "i64"),
+            Self::F32 => write!(f, "f32"),
+            Self::F64 => write!(f, "f64"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Char => write!(f, "char"),
+            Self::String => write!(f, "string"),
+            Self::Timestamp => write!(f, "timestamp"),
+            Self::Duration => write!(f, "duration"),
+            Self::OffsetDateTime => write!(f, "offset_datetime"),
+            Self::BigInt => write!(f, "bigint"),
+            Self::Decimal => write!(f, "decimal"),
+            Self::Url => write!(f, "url"),
+            Self::Uuid => write!(f, "uuid"),
+            Self::Json => write!(f, "json"),
+            Self::SharedSlice => write!(f, "shared_slice"),
+            Self::Object { name, .. }
+            | Self::Record { name }
+            | Self::Enum { name }
+            | Self::Error { name }
+            | Self::CallbackInterface { name }
+            | Self::External { name, .. }
+            | Self::Custom { name, .. } => write!(f, "{name}"),
+            Self::Optional { inner } => write!(f, "{inner}?"),
+            Self::Sequence { inner } => write!(f, "sequence<{inner}>"),
+            Self::Set { inner } => write!(f, "set<{inner}>"),
+            Self::Map { key, value } => write!(f, "record<{key}, {value}>"),
+            Self::Tuple { elements } => {
+                let elements: Vec<_> = elements.iter().map(ToString::to_string).collect();
+                write!(f, "tuple<{}>", elements.join(", "))
+            }
+            Self::Result { ok, err } => write!(f, "result<{ok}, {err}>"),
+            Self::Weak { inner } => write!(f, "weak<{inner}>"),
+            Self::Iterator { inner } => write!(f, "iterator<{inner}>"),
+        }
+    }
+}
+
+/// A literal value, of defaults and constants.  Floats are kept as written in the UDL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LiteralJson {
+    Boolean {
+        value: bool,
+    },
+    String {
+        value: String,
+    },
+    Int {
+        value: i64,
+    },
+    #[serde(rename = "uint")]
+    UInt {
+        value: u64,
+    },
+    Float {
+        value: String,
+    },
+    Enum {
+        variant: String,
+    },
+    Duration {
+        secs: u64,
+        nanos: u32,
+    },
+    EmptySequence,
+    EmptyMap,
+    EmptyRecord,
+    Null,
+}
+
+impl From<&Literal> for LiteralJson {
+    fn from(literal: &Literal) -> Self {
+        match literal {
+            Literal::Boolean(value) => Self::Boolean { value: *value },
+            Literal::String(value) => Self::String {
+                value: value.clone(),
+            },
+            Literal::Int(value, ..) => Self::Int { value: *value },
+            Literal::UInt(value, ..) => Self::UInt { value: *value },
+            Literal::Float(value, _) => Self::Float {
+                value: value.clone(),
+            },
+            Literal::Enum(variant, _) | Literal::EmptyVariant(variant, _) => Self::Enum {
+                variant: variant.clone(),
+            },
+            Literal::Duration(secs, nanos) => Self::Duration {
+                secs: *secs,
+                nanos: *nanos,
+            },
+            Literal::EmptySequence => Self::EmptySequence,
+            Literal::EmptyMap => Self::EmptyMap,
+            Literal::EmptyRecord(_) => Self::EmptyRecord,
+            Literal::Null => Self::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_interface_json() {
+        const UDL: &str = r#"
+            namespace test {
+                [Throws=Oops]
+                sequence<u32>? list(string name, optional u8 count = 3);
+            };
+            [Error]
+            enum Oops { "Bad" };
+            dictionary Point {
+                f64 x = 0.5;
+                record<DOMString, Point?> others;
+            };
+            interface Canvas {
+                constructor();
+                void draw(Point point);
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let json = serde_json::to_value(InterfacesJson::new([&ci])).unwrap();
+        assert_eq!(json["format_version"], JSON_FORMAT_VERSION);
+        let component = &json["components"][0];
+        assert_eq!(component["namespace"], "test");
+
+        assert_eq!(
+            component["functions"][0],
+            serde_json::json!({
+                "name": "list",
+                "docstring": null,
+                "arguments": [
+                    {"name": "name", "type": {"kind": "string"}, "by_ref": false, "default": null},
+                    {
+                        "name": "count",
+                        "type": {"kind": "u8"},
+                        "by_ref": false,
+                        "default": {"kind": "uint", "value": 3},
+                    },
+                ],
+                "return_type": {"kind": "optional", "inner": {"kind": "sequence", "inner": {"kind": "u32"}}},
+                "throws": {"kind": "error", "name": "Oops"},
+            })
+        );
+        assert_eq!(component["errors"][0]["variants"][0]["name"], "Bad");
+        assert_eq!(
+            component["records"][0]["fields"][1]["type"],
+            serde_json::json!({
+                "kind": "map",
+                "key": {"kind": "string"},
+                "value": {"kind": "optional", "inner": {"kind": "record", "name": "Point"}},
+            })
+        );
+        assert_eq!(
+            component["records"][0]["fields"][0]["default"],
+            serde_json::json!({"kind": "float", "value": "0.5"})
+        );
+        assert_eq!(component["objects"][0]["imp"], "struct");
+        assert_eq!(component["objects"][0]["methods"][0]["name"], "draw");
+    }
+}
@@ -28,8 +28,16 @@ pub enum Literal {
     // will have to do extra work.
     Float(String, Type),
     Enum(String, Type),
+    // A duration, in whole seconds and the nanoseconds in addition to them, like Rust's `Duration`.
+    // The UDL writes it as a number of seconds.
+    Duration(u64, u32),
+    // A variant without fields of an enum which isn't flat, whose other variants may have some.
+    // UDL defaults start as `Enum` literals, and are turned into this once the enum is known.
+    EmptyVariant(String, Type),
     EmptySequence,
     EmptyMap,
+    // A record where every field takes its default value.
+    EmptyRecord(Type),
     Null,
 }
 
@@ -102,6 +110,31 @@ pub(super) fn convert_default_value(
         })
     }
 
+    // Splitting the decimal representation keeps every nanosecond, which a float wouldn't.
+    fn convert_duration(default_value: &weedle::literal::DefaultValue<'_>) -> Result<Literal> {
+        let string = match default_value {
+            weedle::literal::DefaultValue::Integer(weedle::literal::IntegerLit::Dec(v)) => v.0,
+            // weedle parses "0" as an octal literal.
+            weedle::literal::DefaultValue::Integer(weedle::literal::IntegerLit::Oct(v))
+                if v.0 == "0" =>
+            {
+                v.0
+            }
+            weedle::literal::DefaultValue::Float(weedle::literal::FloatLit::Value(v)) => v.0,
+            _ => bail!("A duration must be a decimal number of seconds, not {default_value:?}"),
+        };
+        let (secs, fraction) = string.split_once('.').unwrap_or((string, ""));
+        if secs.starts_with('-') {
+            bail!("A duration can't be negative, like {string}");
+        }
+        if fraction.len() > 9 || fraction.contains(['e', 'E']) || secs.contains(['e', 'E']) {
+            bail!("A duration must be written as seconds with at most 9 decimals, not {string}");
+        }
+        let secs = if secs.is_empty() { 0 } else { secs.parse()? };
+        let nanos = format!("{fraction:0<9}").parse()?;
+        Ok(Literal::Duration(secs, nanos))
+    }
+
     Ok(match (default_value, type_) {
         (weedle::literal::DefaultValue::Boolean(b), Type::Boolean) => Literal::Boolean(b.0),
         (weedle::literal::DefaultValue::String(s), Type::String) => {
@@ -111,10 +144,17 @@ pub(super) fn convert_default_value(
             // trying to break default values with weird escapes and quotes.
             Literal::String(s.0.to_string())
         }
-        (weedle::literal::DefaultValue::EmptyArray(_), Type::Sequence(_)) => Literal::EmptySequence,
+        (weedle::literal::DefaultValue::EmptyArray(_), Type::Sequence(_) | Type::Set(_)) => {
+            Literal::EmptySequence
+        }
+        (weedle::literal::DefaultValue::EmptyDictionary(_), Type::Map(_, _)) => Literal::EmptyMap,
+        (weedle::literal::DefaultValue::EmptyDictionary(_), Type::Record(_)) => {
+            Literal::EmptyRecord(type_.clone())
+        }
         (weedle::literal::DefaultValue::String(s), Type::Enum(_)) => {
             Literal::Enum(s.0.to_string(), type_.clone())
         }
+        (_, Type::Duration) => convert_duration(default_value)?,
         (weedle::literal::DefaultValue::Null(_), Type::Optional(_)) => Literal::Null,
         (_, Type::Optional(inner_type)) => convert_default_value(default_value, inner_type)?,
 
@@ -163,10 +203,40 @@ mod test {
             parse_and_convert("[]", Type::Sequence(Box::new(Type::String)))?,
             Literal::EmptySequence
         ));
+        assert!(matches!(
+            parse_and_convert("[]", Type::Set(Box::new(Type::String)))?,
+            Literal::EmptySequence
+        ));
+        assert!(matches!(
+            parse_and_convert(
+                "{}",
+                Type::Map(Box::new(Type::String), Box::new(Type::String))
+            )?,
+            Literal::EmptyMap
+        ));
+        assert!(
+            matches!(parse_and_convert("{}", Type::Record("R".into()))?, Literal::EmptyRecord(Type::Record(r)) if r == "R")
+        );
         assert!(matches!(
             parse_and_convert("null", Type::Optional(Box::new(Type::String)))?,
             Literal::Null
         ));
+        assert!(matches!(
+            parse_and_convert("0", Type::Duration)?,
+            Literal::Duration(0, 0)
+        ));
+        assert!(matches!(
+            parse_and_convert("90", Type::Duration)?,
+            Literal::Duration(90, 0)
+        ));
+        assert!(matches!(
+            parse_and_convert("1.000000001", Type::Duration)?,
+            Literal::Duration(1, 1)
+        ));
+        assert!(matches!(
+            parse_and_convert(".5", Type::Duration)?,
+            Literal::Duration(0, 500_000_000)
+        ));
         Ok(())
     }
     #[test]
@@ -181,5 +251,17 @@ mod test {
             .unwrap_err()
             .to_string()
             .starts_with("No support for"));
+        assert_eq!(
+            parse_and_convert("-1.5", Type::Duration)
+                .unwrap_err()
+                .to_string(),
+            "A duration can't be negative, like -1.5"
+        );
+        assert_eq!(
+            parse_and_convert("1e3", Type::Duration)
+                .unwrap_err()
+                .to_string(),
+            "A duration must be written as seconds with at most 9 decimals, not 1e3"
+        );
     }
 }
@@ -45,39 +45,51 @@
 //!   * Error messages and general developer experience leave a lot to be desired.
 
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashSet},
     convert::TryFrom,
     hash::{Hash, Hasher},
     iter,
 };
 
 use anyhow::{bail, ensure, Result};
+use heck::{ToShoutySnakeCase, ToSnakeCase};
 
 pub mod types;
-pub use types::Type;
+pub use types::{ExternalKind, Type};
 use types::{TypeIterator, TypeUniverse};
 
 mod attributes;
 mod callbacks;
 pub use callbacks::CallbackInterface;
+mod constant;
+mod diagnostics;
+pub use constant::Constant;
+pub use diagnostics::Diagnostic;
 mod enum_;
 pub use enum_::Enum;
 mod error;
 pub use error::Error;
+mod format;
+pub use format::{format_udl, FormatOptions};
 mod function;
 pub use function::{Argument, Function};
+pub mod json;
 mod literal;
 pub use literal::{Literal, Radix};
 mod namespace;
 pub use namespace::Namespace;
 mod object;
-pub use object::{Constructor, Method, Object};
+pub use object::{Constructor, Method, Object, ObjectImpl};
 mod record;
 pub use record::{Field, Record};
 
 pub mod ffi;
 pub use ffi::{FFIArgument, FFIFunction, FFIType};
-use uniffi_meta::{FnMetadata, MethodMetadata, ObjectMetadata};
+use uniffi_meta::{FnMetadata, HookKind, HookMetadata, MethodMetadata, ObjectMetadata};
+
+/// The version of the conventions of the FFI between the scaffolding and the bindings, like the
+/// layout of `RustBuffer` and `RustCallStatus`, which must be bumped whenever they change.
+pub const UNIFFI_CONTRACT_VERSION: u32 = 2;
 
 /// The main public interface for this module, representing the complete details of an interface exposed
 /// by a rust component and the details of consuming it via an extern-C FFI layer.
@@ -94,6 +106,17 @@ pub struct ComponentInterface {
     namespace: String,
     /// The internal unique prefix used to namespace FFI symbols
     ffi_namespace: String,
+    /// The `ffi_prefix` of the configuration, which starts the names of all the FFI symbols.
+    ffi_prefix: Option<String>,
+    /// The texts of the UDL file and of the files it includes, which the scaffolding embeds in the
+    /// library, see [`udl_metadata`](Self::udl_metadata).
+    udl_sources: Vec<String>,
+    /// The checksum of the interface defined in the UDL, which is part of `ffi_namespace`.
+    ffi_checksum: u16,
+    /// Whether values are serialized with the compact encoding, see `uniffi::FfiConverter`.
+    compact_encoding: bool,
+    /// Whether the strings are passed as UTF-16, see `uniffi::Utf16String`.
+    utf16_strings: bool,
     /// The high-level API provided by the component.
     enums: BTreeMap<String, Enum>,
     records: BTreeMap<String, Record>,
@@ -101,40 +124,72 @@ pub struct ComponentInterface {
     objects: Vec<Object>,
     callback_interfaces: Vec<CallbackInterface>,
     errors: Vec<Error>,
+    constants: Vec<Constant>,
+    /// The errors of the custom types declared with `[Custom, Throws=...]`, by type name.
+    custom_type_errors: BTreeMap<String, Type>,
+    /// The FFI functions of the `#[uniffi::init]` and `#[uniffi::shutdown]` hooks.
+    init_hooks: Vec<FFIFunction>,
+    shutdown_hooks: Vec<FFIFunction>,
 }
 
 impl ComponentInterface {
     /// Parse a `ComponentInterface` from a string containing a WebIDL definition.
     pub fn from_webidl(idl: &str) -> Result<Self> {
+        let mut ci = Self::from_definitions(parse_definitions(idl)?)?;
+        ci.udl_sources = vec![idl.to_string()];
+        Ok(ci)
+    }
+
+    /// Parse a `ComponentInterface` from WebIDL definitions split across several strings.
+    ///
+    /// This is used for UDL files that pull in other files with `include "other.udl";`.
+    /// The caller is responsible for resolving those directives and passing in the
+    /// contents of every included file; the directives themselves are then ignored.
+    pub fn from_webidl_sources<S: AsRef<str>>(sources: &[S]) -> Result<Self> {
+        let mut defns = Vec::new();
+        for idl in sources {
+            defns.extend(
+                parse_definitions(idl.as_ref())?
+                    .into_iter()
+                    .filter(|d| !matches!(d, weedle::Definition::Include(_))),
+            );
+        }
+        let mut ci = Self::from_definitions(defns)?;
+        ci.udl_sources = sources.iter().map(|idl| idl.as_ref().to_string()).collect();
+        Ok(ci)
+    }
+
+    fn from_definitions(defns: weedle::Definitions<'_>) -> Result<Self> {
         let mut ci = Self {
             uniffi_version: env!("CARGO_PKG_VERSION").to_string(),
             ..Default::default()
         };
-        // There's some lifetime thing with the errors returned from weedle::Definitions::parse
-        // that my own lifetime is too short to worry about figuring out; unwrap and move on.
-
-        // Note we use `weedle::Definitions::parse` instead of `weedle::parse` so
-        // on parse errors we can see how far weedle got, which helps locate the problem.
-        use weedle::Parse; // this trait must be in scope for parse to work.
-        let (remaining, defns) = weedle::Definitions::parse(idl.trim()).unwrap();
-        if !remaining.is_empty() {
-            println!("Error parsing the IDL. Text remaining to be parsed is:");
-            println!("{remaining}");
-            bail!("parse error");
-        }
         // Unconditionally add the String type, which is used by the panic handling
         ci.types.add_known_type(&Type::String)?;
         // We process the WebIDL definitions in two passes.
         // First, go through and look for all the named types.
-        ci.types.add_type_definitions_from(defns.as_slice())?;
+        // The errors are located at the name of the definition they are found in, unless they
+        // are about a more specific place of it.
+        for defn in &defns {
+            ci.types
+                .add_type_definitions_from(std::slice::from_ref(defn))
+                .map_err(|err| diagnostics::locate_definition(err, defn))?;
+        }
         // With those names resolved, we can build a complete representation of the API.
-        APIBuilder::process(&defns, &mut ci)?;
+        for defn in &defns {
+            defn.process(&mut ci)
+                .map_err(|err| diagnostics::locate_definition(err, defn))?;
+        }
+        ci.resolve_default_values()?;
+        ci.resolve_argument_errors()?;
+        ci.resolve_recursive_types()?;
 
         // The FFI namespace must not be computed on the fly because it could otherwise be
         // influenced by things added later from proc-macro metadata. Those have their own
         // namespacing mechanism.
         assert!(!ci.namespace.is_empty());
-        ci.ffi_namespace = format!("{}_{:x}", ci.namespace, ci.checksum());
+        ci.ffi_checksum = ci.checksum();
+        ci.ffi_namespace = format!("{}_{:x}", ci.namespace, ci.ffi_checksum);
 
         // The following two methods will be called later anyways, but we call them here because
         // it's convenient for UDL-only tests.
@@ -153,6 +208,32 @@ impl ComponentInterface {
         self.namespace.as_str()
     }
 
+    /// Whether values are serialized with the compact encoding, see
+    /// [`set_compact_encoding`](Self::set_compact_encoding).
+    ///
+    /// The encoding is part of the [`contract_version`](Self::contract_version) and of the
+    /// [`udl_metadata`](Self::udl_metadata), so that the bindings and the scaffolding can't
+    /// disagree on it.
+    pub fn compact_encoding(&self) -> bool {
+        self.compact_encoding
+    }
+
+    /// The encoding of the values, see [`compact_encoding`](Self::compact_encoding).
+    pub fn value_encoding(&self) -> uniffi_meta::ValueEncoding {
+        if self.compact_encoding {
+            uniffi_meta::ValueEncoding::Compact
+        } else {
+            uniffi_meta::ValueEncoding::Standard
+        }
+    }
+
+    /// Whether the strings lowered and lifted on their own are passed as UTF-16 rather than UTF-8,
+    /// declared with the `[Utf16Strings]` attribute of the namespace, see `uniffi::Utf16String`.
+    /// The strings serialized inside other values are always UTF-8.
+    pub fn utf16_strings(&self) -> bool {
+        self.utf16_strings
+    }
+
     /// Get the definitions for every Enum type in the interface.
     pub fn enum_definitions(&self) -> impl Iterator<Item = &Enum> {
         self.enums.values()
@@ -173,15 +254,60 @@ impl ComponentInterface {
         self.records.get(name)
     }
 
-    /// Get the definitions for every Function in the interface.
-    pub fn function_definitions(&self) -> &[Function] {
+    /// The FFI functions of the `#[uniffi::init]` hooks, which the bindings call when they load
+    /// the library.
+    pub fn init_hooks(&self) -> &[FFIFunction] {
+        &self.init_hooks
+    }
+
+    /// The FFI functions of the `#[uniffi::shutdown]` hooks, which the bindings call when the
+    /// process exits.
+    pub fn shutdown_hooks(&self) -> &[FFIFunction] {
+        &self.shutdown_hooks
+    }
+
+    /// Get the definitions for every top-level Function in the interface.
+    ///
+    /// This doesn't include the methods of records and enums, see [`Self::methods_of`].
+    pub fn function_definitions(&self) -> Vec<&Function> {
+        self.functions
+            .iter()
+            .filter(|f| f.self_type.is_none())
+            .collect()
+    }
+
+    /// Get the definitions for every Function in the interface, including the methods of
+    /// records and enums.
+    ///
+    /// These are all standalone functions in the FFI and the Rust scaffolding.
+    pub fn all_function_definitions(&self) -> &[Function] {
         &self.functions
     }
 
-    /// Get a Function definition by name, or None if no such Function is defined.
+    /// Get the methods of the given record or enum type.
+    pub fn methods_of(&self, self_type: &Type) -> Vec<&Function> {
+        self.functions
+            .iter()
+            .filter(|f| f.self_type.as_ref() == Some(self_type))
+            .collect()
+    }
+
+    /// Get a top-level Function definition by name, or None if no such Function is defined.
     pub fn get_function_definition(&self, name: &str) -> Option<&Function> {
         // TODO: probably we could store these internally in a HashMap to make this easier?
-        self.functions.iter().find(|f| f.name == name)
+        self.functions
+            .iter()
+            .find(|f| f.name == name && f.self_type.is_none())
+    }
+
+    /// Get the definitions for every Constant in the interface.
+    pub fn constant_definitions(&self) -> &[Constant] {
+        &self.constants
+    }
+
+    /// Get a Constant definition by name, or None if no such Constant is defined.
+    pub fn get_constant_definition(&self, name: &str) -> Option<&Constant> {
+        self.constants.iter().find(|c| c.name == name)
     }
 
     /// Get the definitions for every Object type in the interface.
@@ -206,6 +332,16 @@ impl ComponentInterface {
         self.callback_interfaces.iter().find(|o| o.name == name)
     }
 
+    /// Whether the type is a callback function, rather than a callback interface.
+    pub fn is_callback_function(&self, type_: &Type) -> bool {
+        match type_ {
+            Type::CallbackInterface(name) => self
+                .get_callback_interface_definition(name)
+                .map_or(false, CallbackInterface::is_function),
+            _ => false,
+        }
+    }
+
     /// Get the definitions for every Error type in the interface.
     pub fn error_definitions(&self) -> &[Error] {
         &self.errors
@@ -232,14 +368,23 @@ impl ComponentInterface {
             .iter()
             .flat_map(|cb| cb.methods())
             .any(|m| m.throws_type() == Some(error.type_()));
+        // or to lift the results holding them
+        let used_in_result = self
+            .types
+            .iter_known_types()
+            .any(|t| matches!(t, Type::Result(_, err) if **err == error.type_()));
 
-        fielded || used_in_callback_interface
+        fielded || used_in_callback_interface || used_in_result
     }
 
     /// Get details about all `Type::External` types
-    pub fn iter_external_types(&self) -> impl Iterator<Item = (&String, &String)> {
+    pub fn iter_external_types(&self) -> impl Iterator<Item = (&String, &String, ExternalKind)> {
         self.types.iter_known_types().filter_map(|t| match t {
-            Type::External { name, crate_name } => Some((name, crate_name)),
+            Type::External {
+                name,
+                crate_name,
+                kind,
+            } => Some((name, crate_name, *kind)),
             _ => None,
         })
     }
@@ -257,6 +402,21 @@ impl ComponentInterface {
         self.types.iter_known_types()
     }
 
+    /// Iterate over the iterators returned by functions and methods, which each need their own
+    /// FFI functions.
+    pub fn iter_iterator_types(&self) -> impl Iterator<Item = &Type> {
+        self.types
+            .iter_known_types()
+            .filter(|t| matches!(t, Type::Iterator(_)))
+    }
+
+    /// Whether functions or methods return shared slices, which need their own FFI functions.
+    pub fn uses_shared_slices(&self) -> bool {
+        self.types
+            .iter_known_types()
+            .any(|t| t == &Type::SharedSlice)
+    }
+
     /// Get a specific type
     pub fn get_type(&self, name: &str) -> Option<Type> {
         self.types.get_type_definition(name)
@@ -277,7 +437,7 @@ impl ComponentInterface {
     /// tightly with the host GC, and hence need to perform manual destruction of objects.
     pub fn item_contains_object_references(&self, item: &Type) -> bool {
         self.iter_types_in_item(item)
-            .any(|t| matches!(t, Type::Object(_)))
+            .any(|t| matches!(t, Type::Object { .. }))
     }
 
     /// Check whether the given item contains any (possibly nested) unsigned types
@@ -286,6 +446,15 @@ impl ComponentInterface {
             .any(|t| matches!(t, Type::UInt8 | Type::UInt16 | Type::UInt32 | Type::UInt64))
     }
 
+    /// Check whether the given item contains any (possibly nested) tuples.
+    ///
+    /// Some languages can't derive equality or hashing for tuples, so the bindings for records
+    /// and enums containing them need to go without.
+    pub fn item_contains_tuples(&self, item: &Type) -> bool {
+        self.iter_types_in_item(item)
+            .any(|t| matches!(t, Type::Tuple(_)))
+    }
+
     /// Check whether the interface contains any optional types
     pub fn contains_optional_types(&self) -> bool {
         self.types
@@ -307,6 +476,13 @@ impl ComponentInterface {
             .any(|t| matches!(t, Type::Map(_, _)))
     }
 
+    /// Check whether the interface contains any result types
+    pub fn contains_result_types(&self) -> bool {
+        self.types
+            .iter_known_types()
+            .any(|t| matches!(t, Type::Result(_, _)))
+    }
+
     /// Calculate a numeric checksum for this ComponentInterface.
     ///
     /// The checksum can be used to guard against accidentally using foreign-language bindings
@@ -333,6 +509,62 @@ impl ComponentInterface {
         uniffi_meta::checksum(self)
     }
 
+    /// Start the names of all the FFI functions of the interface with `prefix`, the `ffi_prefix`
+    /// of the `uniffi.toml` configuration, so that they don't collide with the ones of other
+    /// libraries built with uniffi when they are statically linked into the same binary.
+    ///
+    /// This must be called before adding the proc-macro metadata, whose FFI functions are named
+    /// with the prefix when they are added.
+    pub fn set_ffi_prefix(&mut self, prefix: &str) -> Result<()> {
+        uniffi_meta::check_ffi_prefix(prefix).map_err(anyhow::Error::msg)?;
+        self.ffi_prefix = Some(prefix.to_string());
+        self.ffi_namespace = format!("{prefix}_{}_{:x}", self.namespace, self.ffi_checksum);
+        // The functions of the UDL were named after the namespace without the prefix.
+        for func in self.functions.iter_mut() {
+            func.ffi_func.name.clear();
+        }
+        for obj in self.objects.iter_mut() {
+            obj.ffi_func_free.name.clear();
+            obj.ffi_func_clone.name.clear();
+            for meth in obj.methods.iter_mut() {
+                meth.ffi_func.name.clear();
+            }
+        }
+        self.derive_ffi_funcs()
+    }
+
+    /// Serialize the values with the compact encoding if `compact`, the `compact_encoding` of the
+    /// `uniffi.toml` configuration, see `uniffi::FfiConverter`.
+    pub fn set_compact_encoding(&mut self, compact: bool) {
+        self.compact_encoding = compact;
+    }
+
+    /// The `ffi_prefix` of the configuration, see [`set_ffi_prefix`](Self::set_ffi_prefix).
+    pub fn ffi_prefix(&self) -> Option<&str> {
+        self.ffi_prefix.as_deref()
+    }
+
+    /// The UDL definitions of the interface, which the scaffolding embeds in the library, among
+    /// the metadata of the proc-macros, so that the bindings can be generated from the library
+    /// alone, see `uniffi-bindgen generate --library`.
+    pub fn udl_metadata(&self) -> uniffi_meta::UdlMetadata {
+        uniffi_meta::UdlMetadata {
+            namespace: self.namespace.clone(),
+            uniffi_version: self.uniffi_version.clone(),
+            ffi_prefix: self.ffi_prefix.clone(),
+            value_encoding: self.value_encoding(),
+            sources: self.udl_sources.clone(),
+        }
+    }
+
+    /// The name of the static holding the [`udl_metadata`](Self::udl_metadata) in the library.
+    pub fn udl_metadata_symbol_name(&self) -> String {
+        match self.ffi_prefix() {
+            Some(prefix) => format!("UNIFFI_META_{prefix}_UDL_{}", self.namespace()),
+            None => format!("UNIFFI_META_UDL_{}", self.namespace()),
+        }
+    }
+
     /// The namespace to use in FFI-level function definitions.
     ///
     /// The value returned by this method is used as a prefix to namespace all UDL-defined FFI
@@ -350,6 +582,53 @@ impl ComponentInterface {
         &self.ffi_namespace
     }
 
+    /// The version of the contract between the scaffolding and the bindings, which must be the
+    /// same on both sides for the bindings to use the library.
+    ///
+    /// Its 8 high bits are [`UNIFFI_CONTRACT_VERSION`], its next 8 bits are the encoding of the
+    /// values, `1` for the compact one and `0` for the standard one, and its 16 low bits are the
+    /// checksum which is part of the names of the FFI functions. Like that checksum, it's settled
+    /// once the UDL is parsed, so that the proc-macro metadata, which only the bindings see,
+    /// doesn't change it.
+    pub fn contract_version(&self) -> u32 {
+        assert!(!self.ffi_namespace.is_empty());
+        (UNIFFI_CONTRACT_VERSION << 24)
+            | (u32::from(self.compact_encoding) << 16)
+            | u32::from(self.ffi_checksum)
+    }
+
+    /// Builtin FFI function returning the [`contract_version`](Self::contract_version) of the
+    /// scaffolding, which the bindings check as soon as they load the library.
+    ///
+    /// Unlike the names of the other FFI functions, its name doesn't contain the checksum, so that
+    /// the bindings find it in any version of the library. It can't fail, so it takes no
+    /// `RustCallStatus`, and it isn't part of the FFI function definitions, since the bindings
+    /// call it before declaring the others.
+    pub fn ffi_contract_version_name(&self) -> String {
+        match self.ffi_prefix() {
+            Some(prefix) => format!("uniffi_{prefix}_{}_contract_version", self.namespace()),
+            None => format!("uniffi_{}_contract_version", self.namespace()),
+        }
+    }
+
+    /// Builtin FFI function for installing a callback which receives the records of the `log`
+    /// crate, see `uniffi/src/ffi/foreignlog.rs`.
+    ///
+    /// Its callback argument has a type of its own, so it isn't part of the FFI function
+    /// definitions which all bindings declare. Only the bindings forwarding logs declare it.
+    pub fn ffi_install_log_forwarder_name(&self) -> String {
+        format!("ffi_{}_install_log_forwarder", self.ffi_namespace())
+    }
+
+    /// Builtin FFI function for registering the functions allocating and freeing the bytes of the
+    /// `RustBuffer`s, see `uniffi/src/ffi/bufferalloc.rs`.
+    ///
+    /// Like the log forwarder, its argument has a type of its own, so it isn't part of the FFI
+    /// function definitions. It's only for the embedders calling it from their own code.
+    pub fn ffi_rustbuffer_set_allocator_name(&self) -> String {
+        format!("ffi_{}_rustbuffer_set_allocator", self.ffi_namespace())
+    }
+
     /// Builtin FFI function for allocating a new `RustBuffer`.
     /// This is needed so that the foreign language bindings can create buffers in which to pass
     /// complex data types across the FFI.
@@ -412,6 +691,108 @@ impl ComponentInterface {
         }
     }
 
+    /// Builtin FFI function for getting the counters of the `RustBuffer`s, see
+    /// `uniffi/src/ffi/bufferlimits.rs`.
+    /// It returns them as `u64`s in a `RustBuffer`.
+    pub fn ffi_rustbuffer_stats(&self) -> FFIFunction {
+        FFIFunction {
+            name: format!("ffi_{}_rustbuffer_stats", self.ffi_namespace()),
+            arguments: vec![],
+            return_type: Some(FFIType::RustBuffer),
+        }
+    }
+
+    /// Builtin FFI function for describing the object handles and `RustBuffer`s which weren't
+    /// freed yet, see `uniffi/src/ffi/leakdetection.rs`.
+    /// It returns the description as a string in a `RustBuffer`.
+    pub fn ffi_dump_live_allocations(&self) -> FFIFunction {
+        FFIFunction {
+            name: format!("ffi_{}_dump_live_allocations", self.ffi_namespace()),
+            arguments: vec![],
+            return_type: Some(FFIType::RustBuffer),
+        }
+    }
+
+    /// Builtin FFI function for getting the next item of an iterator returned by Rust.
+    /// It returns the item as an optional value in a `RustBuffer`, `None` marking the end.
+    pub fn ffi_iterator_next(&self, iterator: &Type) -> FFIFunction {
+        FFIFunction {
+            name: format!(
+                "ffi_{}_{}_next",
+                self.ffi_namespace(),
+                iterator.canonical_name().to_snake_case()
+            ),
+            arguments: vec![FFIArgument {
+                name: "handle".to_string(),
+                type_: iterator.into(),
+            }],
+            return_type: Some(FFIType::RustBuffer),
+        }
+    }
+
+    /// The type of the items returned by `ffi_iterator_next`, which the bindings lift.
+    pub fn ffi_iterator_next_type(&self, iterator: &Type) -> Type {
+        match iterator {
+            Type::Iterator(t) => Type::Optional(t.clone()),
+            _ => unreachable!("only iterators have items"),
+        }
+    }
+
+    /// Builtin FFI function for freeing an iterator returned by Rust.
+    pub fn ffi_iterator_free(&self, iterator: &Type) -> FFIFunction {
+        FFIFunction {
+            name: format!(
+                "ffi_{}_{}_free",
+                self.ffi_namespace(),
+                iterator.canonical_name().to_snake_case()
+            ),
+            arguments: vec![FFIArgument {
+                name: "handle".to_string(),
+                type_: iterator.into(),
+            }],
+            return_type: None,
+        }
+    }
+
+    /// Builtin FFI function for getting the address of the bytes of a shared slice returned by
+    /// Rust, which stay valid until it's freed.
+    pub fn ffi_shared_slice_data(&self) -> FFIFunction {
+        FFIFunction {
+            name: format!("ffi_{}_shared_slice_data", self.ffi_namespace()),
+            arguments: vec![FFIArgument {
+                name: "handle".to_string(),
+                type_: (&Type::SharedSlice).into(),
+            }],
+            // The address of the bytes, rather than a handle.
+            return_type: Some(FFIType::RustArcPtr(Type::SharedSlice.canonical_name())),
+        }
+    }
+
+    /// Builtin FFI function for getting the number of bytes of a shared slice returned by Rust.
+    pub fn ffi_shared_slice_len(&self) -> FFIFunction {
+        FFIFunction {
+            name: format!("ffi_{}_shared_slice_len", self.ffi_namespace()),
+            arguments: vec![FFIArgument {
+                name: "handle".to_string(),
+                type_: (&Type::SharedSlice).into(),
+            }],
+            return_type: Some(FFIType::UInt64),
+        }
+    }
+
+    /// Builtin FFI function for releasing a shared slice returned by Rust, after which its bytes
+    /// can't be read.
+    pub fn ffi_shared_slice_free(&self) -> FFIFunction {
+        FFIFunction {
+            name: format!("ffi_{}_shared_slice_free", self.ffi_namespace()),
+            arguments: vec![FFIArgument {
+                name: "handle".to_string(),
+                type_: (&Type::SharedSlice).into(),
+            }],
+            return_type: None,
+        }
+    }
+
     /// List the definitions of all FFI functions in the interface.
     ///
     /// The set of FFI functions is derived automatically from the set of higher-level types
@@ -420,6 +801,9 @@ impl ComponentInterface {
         self.iter_user_ffi_function_definitions()
             .cloned()
             .chain(self.iter_rust_buffer_ffi_function_definitions())
+            .chain(iter::once(self.ffi_dump_live_allocations()))
+            .chain(self.iter_iterator_ffi_function_definitions())
+            .chain(self.iter_shared_slice_ffi_function_definitions())
     }
 
     /// List all FFI functions definitions for user-defined interfaces
@@ -428,6 +812,7 @@ impl ComponentInterface {
     ///   - Top-level functions
     ///   - Object methods
     ///   - Callback interfaces
+    ///   - Init and shutdown hooks
     pub fn iter_user_ffi_function_definitions(&self) -> impl Iterator<Item = &FFIFunction> + '_ {
         iter::empty()
             .chain(
@@ -438,9 +823,11 @@ impl ComponentInterface {
             .chain(
                 self.callback_interfaces
                     .iter()
-                    .map(|cb| cb.ffi_init_callback()),
+                    .flat_map(|cb| [cb.ffi_init_callback(), cb.ffi_unregister_callback()]),
             )
             .chain(self.functions.iter().map(|f| &f.ffi_func))
+            .chain(self.init_hooks.iter())
+            .chain(self.shutdown_hooks.iter())
     }
 
     /// List all FFI functions definitions for RustBuffer functionality
@@ -450,10 +837,31 @@ impl ComponentInterface {
             self.ffi_rustbuffer_from_bytes(),
             self.ffi_rustbuffer_free(),
             self.ffi_rustbuffer_reserve(),
+            self.ffi_rustbuffer_stats(),
         ]
         .into_iter()
     }
 
+    /// List all FFI functions definitions for the iterators returned by Rust
+    pub fn iter_iterator_ffi_function_definitions(&self) -> impl Iterator<Item = FFIFunction> + '_ {
+        self.iter_iterator_types()
+            .flat_map(|t| [self.ffi_iterator_next(t), self.ffi_iterator_free(t)])
+    }
+
+    /// List all FFI functions definitions for the shared slices returned by Rust, if any
+    pub fn iter_shared_slice_ffi_function_definitions(&self) -> impl Iterator<Item = FFIFunction> {
+        let functions = if self.uses_shared_slices() {
+            vec![
+                self.ffi_shared_slice_data(),
+                self.ffi_shared_slice_len(),
+                self.ffi_shared_slice_free(),
+            ]
+        } else {
+            vec![]
+        };
+        functions.into_iter()
+    }
+
     //
     // Private methods for building a ComponentInterface.
     //
@@ -491,12 +899,28 @@ impl ComponentInterface {
         })
     }
 
+    /// Resolve the error named by a `[Throws=...]` attribute into a `Type`.
+    ///
+    /// This is an error of this component, unless the name was declared as an external error.
+    fn resolve_throws_type(&self, name: Option<&str>) -> Option<Type> {
+        name.map(|name| match self.types.get_type_definition(name) {
+            Some(
+                type_ @ Type::External {
+                    kind: ExternalKind::Error,
+                    ..
+                },
+            ) => type_,
+            _ => Type::Error(name.to_owned()),
+        })
+    }
+
     /// Called by `APIBuilder` impls to add a newly-parsed namespace definition to the `ComponentInterface`.
     fn add_namespace_definition(&mut self, defn: Namespace) -> Result<()> {
         if !self.namespace.is_empty() {
             bail!("duplicate namespace definition");
         }
         self.namespace = defn.name;
+        self.utf16_strings = defn.utf16_strings;
         Ok(())
     }
 
@@ -547,10 +971,19 @@ impl ComponentInterface {
     fn add_function_impl(&mut self, defn: Function) -> Result<()> {
         // Since functions are not a first-class type, we have to check for duplicates here
         // rather than relying on the type-finding pass to catch them.
-        if self.functions.iter().any(|f| f.name == defn.name) {
-            bail!("duplicate function definition: \"{}\"", defn.name);
+        if self
+            .functions
+            .iter()
+            .any(|f| f.name == defn.name && f.self_type_name() == defn.self_type_name())
+        {
+            match defn.self_type_name() {
+                Some(type_name) => {
+                    bail!("duplicate method definition: \"{type_name}.{}\"", defn.name)
+                }
+                None => bail!("duplicate function definition: \"{}\"", defn.name),
+            }
         }
-        if !matches!(self.types.get_type_definition(defn.name()), None) {
+        if defn.self_type.is_none() && self.types.get_type_definition(defn.name()).is_some() {
             bail!("Conflicting type definition for \"{}\"", defn.name());
         }
         self.functions.push(defn);
@@ -558,6 +991,15 @@ impl ComponentInterface {
         Ok(())
     }
 
+    /// Called by `APIBuilder` impls to add a newly-parsed constant definition to the `ComponentInterface`.
+    fn add_constant_definition(&mut self, defn: Constant) -> Result<()> {
+        if self.constants.iter().any(|c| c.name == defn.name) {
+            bail!("duplicate constant definition: \"{}\"", defn.name);
+        }
+        self.constants.push(defn);
+        Ok(())
+    }
+
     /// Called by `APIBuilder` impls to add a newly-parsed function definition to the `ComponentInterface`.
     fn add_function_definition(&mut self, defn: Function) -> Result<()> {
         for arg in &defn.arguments {
@@ -571,18 +1013,38 @@ impl ComponentInterface {
     }
 
     pub(super) fn add_fn_meta(&mut self, meta: FnMetadata) -> Result<()> {
-        self.add_function_impl(meta.into())
+        let ffi_name = meta.ffi_symbol_name(self.ffi_prefix());
+        let mut defn: Function = meta.into();
+        defn.ffi_func.name = ffi_name;
+        self.add_function_impl(defn)
     }
 
     pub(super) fn add_method_meta(&mut self, meta: MethodMetadata) {
+        let ffi_name = meta.ffi_symbol_name(self.ffi_prefix.as_deref());
         let object = get_or_insert_object(&mut self.objects, &meta.self_name);
-        let defn: Method = meta.into();
+        let mut defn: Method = meta.into();
+        defn.ffi_func.name = ffi_name;
         object.methods.push(defn);
     }
 
     pub(super) fn add_object_free_fn(&mut self, meta: ObjectMetadata) {
+        let ffi_prefix = self.ffi_prefix.as_deref();
         let object = get_or_insert_object(&mut self.objects, &meta.name);
-        object.ffi_func_free.name = meta.free_ffi_symbol_name();
+        object.ffi_func_free.name = meta.free_ffi_symbol_name(ffi_prefix);
+        object.ffi_func_clone.name = meta.clone_ffi_symbol_name(ffi_prefix);
+        object.docstring = meta.docstring;
+    }
+
+    pub(super) fn add_hook_meta(&mut self, meta: HookMetadata) {
+        let ffi_func = FFIFunction {
+            name: meta.ffi_symbol_name(self.ffi_prefix()),
+            arguments: vec![],
+            return_type: None,
+        };
+        match meta.kind {
+            HookKind::Init => self.init_hooks.push(ffi_func),
+            HookKind::Shutdown => self.shutdown_hooks.push(ffi_func),
+        }
     }
 
     /// Called by `APIBuilder` impls to add a newly-parsed object definition to the `ComponentInterface`.
@@ -619,10 +1081,21 @@ impl ComponentInterface {
                 Type::Sequence(inner) => {
                     handle_unresolved_in(inner, f)?;
                 }
-                Type::Map(k, v) => {
+                Type::Set(inner) => {
+                    handle_unresolved_in(inner, f)?;
+                }
+                Type::Iterator(inner) => {
+                    handle_unresolved_in(inner, f)?;
+                }
+                Type::Map(k, v) | Type::Result(k, v) => {
                     handle_unresolved_in(k, f.clone())?;
                     handle_unresolved_in(v, f)?;
                 }
+                Type::Tuple(elements) => {
+                    for element in elements {
+                        handle_unresolved_in(element, f.clone())?;
+                    }
+                }
                 _ => {}
             }
 
@@ -704,12 +1177,60 @@ impl ComponentInterface {
             }
         }
 
+        // Renaming can't give the same name to several fields or variants.
+        let field_lists = self.records.values().map(|r| (r.name(), &r.fields)).chain(
+            self.enums
+                .values()
+                .chain(self.errors.iter().map(|e| e.wrapped_enum()))
+                .flat_map(|e| e.variants.iter().map(|v| (v.name(), &v.fields))),
+        );
+        for (name, fields) in field_lists {
+            let mut field_names = HashSet::new();
+            for field in fields {
+                ensure!(
+                    field_names.insert(field.name()),
+                    "`{name}` has several fields named `{}`",
+                    field.name()
+                );
+            }
+        }
+        for e in self
+            .enums
+            .values()
+            .chain(self.errors.iter().map(|e| e.wrapped_enum()))
+        {
+            let mut variant_names = HashSet::new();
+            for variant in &e.variants {
+                ensure!(
+                    variant_names.insert(variant.name()),
+                    "`{}` has several variants named `{}`",
+                    e.name(),
+                    variant.name()
+                );
+            }
+        }
+
         for ty in self.iter_types() {
             match ty {
-                Type::Object(name) => {
+                Type::Object { name, imp } => {
+                    let object = self.objects.iter().find(|o| o.name == *name);
+                    ensure!(object.is_some(), "Object `{name}` has no definition");
+                    // Exported signatures use `Arc<dyn Trait>` for `[Trait]` interfaces, and only
+                    // for them.
+                    match (object.unwrap().imp, imp) {
+                        (ObjectImpl::Struct, ObjectImpl::Trait) => {
+                            bail!("`Arc<dyn {name}>` is used, but `{name}` isn't a `[Trait]` interface")
+                        }
+                        (ObjectImpl::Trait, ObjectImpl::Struct) => {
+                            bail!("`Arc<{name}>` is used, but `{name}` is a `[Trait]` interface")
+                        }
+                        _ => {}
+                    }
+                }
+                Type::CallbackInterface(name) => {
                     ensure!(
-                        self.objects.iter().any(|o| o.name == *name),
-                        "Object `{name}` has no definition",
+                        self.get_callback_interface_definition(name).is_some(),
+                        "Callback interface `{name}` has no definition",
                     );
                 }
                 Type::Record(name) => {
@@ -731,58 +1252,405 @@ impl ComponentInterface {
             }
         }
 
+        // Iterators are cursors over the items Rust produces on demand, so they can only be
+        // returned by functions and methods, and not be passed around in any other way.
+        for ty in self.iter_types() {
+            ensure!(
+                !ty.iter_types()
+                    .skip(1)
+                    .any(|t| matches!(t, Type::Iterator(_))),
+                "Iterators can't be nested in other types, like `{ty:?}`",
+            );
+        }
+        // Likewise, shared slices are views of bytes owned by Rust until the foreign-language code
+        // releases them.
+        for ty in self.iter_types() {
+            ensure!(
+                !ty.iter_types().skip(1).any(|t| t == &Type::SharedSlice),
+                "Shared slices can't be nested in other types, like `{ty:?}`",
+            );
+        }
+        let callback_methods = self
+            .callback_interfaces
+            .iter()
+            .flat_map(|cb| cb.methods.iter());
+        let passed_types = self
+            .functions
+            .iter()
+            .flat_map(|f| f.arguments.iter())
+            .chain(self.objects.iter().flat_map(|o| {
+                o.constructors
+                    .iter()
+                    .flat_map(|c| c.arguments.iter())
+                    .chain(o.methods.iter().flat_map(|m| m.arguments.iter()))
+            }))
+            .chain(callback_methods.clone().flat_map(|m| m.arguments.iter()))
+            .map(|a| &a.type_)
+            .chain(callback_methods.filter_map(|m| m.return_type.as_ref()))
+            .chain(
+                self.records
+                    .values()
+                    .flat_map(|r| r.fields.iter())
+                    .map(|f| &f.type_),
+            )
+            .chain(
+                self.enums
+                    .values()
+                    .chain(self.errors.iter().map(|e| e.wrapped_enum()))
+                    .flat_map(|e| e.variants.iter())
+                    .flat_map(|v| v.fields.iter())
+                    .map(|f| &f.type_),
+            );
+        for ty in passed_types {
+            ensure!(
+                !matches!(ty, Type::Iterator(_)),
+                "Iterators can only be returned by functions and methods, not passed like `{ty:?}`",
+            );
+            ensure!(
+                ty != &Type::SharedSlice,
+                "Shared slices can only be returned by functions and methods, not passed",
+            );
+        }
+
+        // A record can only be defaulted to `{}` if each of its fields has a default.
+        let arguments = self
+            .functions
+            .iter()
+            .flat_map(|f| f.arguments.iter())
+            .chain(self.objects.iter().flat_map(|o| {
+                o.constructors
+                    .iter()
+                    .flat_map(|c| c.arguments.iter())
+                    .chain(o.methods.iter().flat_map(|m| m.arguments.iter()))
+            }));
+        let default_values = arguments.filter_map(|a| a.default.as_ref()).chain(
+            self.records
+                .values()
+                .flat_map(|r| r.fields.iter().filter_map(|f| f.default.as_ref())),
+        );
+        for default_value in default_values {
+            if let Literal::EmptyRecord(Type::Record(name)) = default_value {
+                // Missing records were already reported above.
+                let record = &self.records[name];
+                ensure!(
+                    record.fields.iter().all(|f| f.default.is_some()),
+                    "Record `{name}` can only default to `{{}}` if all of its fields have a default value",
+                );
+            }
+        }
+
         Ok(())
     }
 
-    /// Automatically derive the low-level FFI functions from the high-level types in the interface.
+    /// Check the enum variants which arguments and fields default to, turning the defaults to the
+    /// variants of enums which aren't flat into `Literal::EmptyVariant`.
     ///
-    /// This should only be called after the high-level types have been completed defined, otherwise
-    /// the resulting set will be missing some entries.
-    pub fn derive_ffi_funcs(&mut self) -> Result<()> {
-        let ci_prefix = self.ffi_namespace().to_owned();
-        for func in self.functions.iter_mut() {
-            func.derive_ffi_func(&ci_prefix)?;
-        }
-        for obj in self.objects.iter_mut() {
-            obj.derive_ffi_funcs(&ci_prefix)?;
-        }
-        for callback in self.callback_interfaces.iter_mut() {
-            callback.derive_ffi_funcs(&ci_prefix);
+    /// Enums which aren't defined yet are skipped, as they may come from proc-macro metadata,
+    /// so this is called again once it has been added.
+    pub(super) fn resolve_default_values(&mut self) -> Result<()> {
+        let enums = &self.enums;
+        let arguments = self
+            .functions
+            .iter_mut()
+            .flat_map(|f| f.arguments.iter_mut())
+            .chain(self.objects.iter_mut().flat_map(|o| {
+                o.constructors
+                    .iter_mut()
+                    .flat_map(|c| c.arguments.iter_mut())
+                    .chain(o.methods.iter_mut().flat_map(|m| m.arguments.iter_mut()))
+            }));
+        let default_values = arguments.filter_map(|a| a.default.as_mut()).chain(
+            self.records
+                .values_mut()
+                .flat_map(|r| r.fields.iter_mut().filter_map(|f| f.default.as_mut())),
+        );
+        for default_value in default_values {
+            let (variant_name, enum_) = match &*default_value {
+                Literal::Enum(variant_name, Type::Enum(name)) => match enums.get(name) {
+                    Some(enum_) => (variant_name.clone(), enum_),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            // The backends render variant names in their own case, so any case names a variant.
+            // Renamed variants can be named by either their Rust or their foreign name.
+            let variant = enum_.variants().iter().find(|v| {
+                [v.name(), v.rust_name()]
+                    .iter()
+                    .any(|name| name.to_shouty_snake_case() == variant_name.to_shouty_snake_case())
+            });
+            let variant = match variant {
+                Some(variant) => variant,
+                None => bail!("Enum `{}` has no variant `{variant_name}`", enum_.name()),
+            };
+            if enum_.is_flat() {
+                // The backends render the default with the variant's foreign name.
+                *default_value = Literal::Enum(variant.name().to_owned(), enum_.type_());
+            } else {
+                ensure!(
+                    !variant.has_fields(),
+                    "Variant `{variant_name}` of enum `{}` has fields, so it can't be a default value",
+                    enum_.name(),
+                );
+                *default_value = Literal::EmptyVariant(variant.name().to_owned(), enum_.type_());
+            }
         }
+
         Ok(())
     }
-}
 
-/// `ComponentInterface` structs can be hashed, but this is mostly a convenient way to
-/// produce a checksum of their contents. They're not really intended to live in a hashtable.
-impl Hash for ComponentInterface {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        // We can't hash `self.types`, but its contents are implied by the other fields
-        // anyway, so it's safe to ignore it.
-        self.uniffi_version.hash(state);
-        self.namespace.hash(state);
-        self.enums.hash(state);
-        self.records.hash(state);
-        self.functions.hash(state);
-        self.objects.hash(state);
-        self.callback_interfaces.hash(state);
-        self.errors.hash(state);
-    }
-}
+    /// Make the functions, methods and constructors which don't declare an error throw the error
+    /// of the custom types of their arguments, so that failing to convert an argument raises it
+    /// rather than panicking.
+    ///
+    /// This only applies to the UDL definitions, as the scaffolding of the proc-macros is
+    /// generated without knowing about the errors of custom types.
+    fn resolve_argument_errors(&mut self) -> Result<()> {
+        let custom_type_errors = &self.custom_type_errors;
+        let argument_errors = |name: &str, arguments: &[Argument]| -> Result<Option<Type>> {
+            let mut errors = arguments
+                .iter()
+                .flat_map(Argument::iter_types)
+                .filter_map(|t| match t {
+                    Type::Custom {
+                        name: type_name, ..
+                    } => custom_type_errors.get(type_name),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>()
+                .into_iter();
+            let error_name = |error: &Type| match error {
+                Type::Error(name) | Type::External { name, .. } => name.clone(),
+                _ => error.canonical_name(),
+            };
+            match (errors.next(), errors.next()) {
+                (Some(a), Some(b)) => bail!(
+                    "`{name}` takes custom types with different errors, `{}` and `{}`, so it must declare which one it throws",
+                    error_name(a),
+                    error_name(b),
+                ),
+                (error, _) => Ok(error.cloned()),
+            }
+        };
 
-fn get_or_insert_object<'a>(objects: &'a mut Vec<Object>, name: &str) -> &'a mut Object {
-    // The find-based way of writing this currently runs into a borrow checker
-    // error, so we use position
-    match objects.iter_mut().position(|o| o.name == name) {
-        Some(idx) => &mut objects[idx],
-        None => {
-            objects.push(Object::new(name.to_owned()));
-            objects.last_mut().unwrap()
+        for func in self.functions.iter_mut() {
+            if func.throws.is_none() {
+                func.throws = argument_errors(&func.name, &func.arguments)?;
+                func.throws_from_arguments = func.throws.is_some();
+            }
+        }
+        for obj in self.objects.iter_mut() {
+            for cons in obj.constructors.iter_mut() {
+                if cons.throws.is_none() {
+                    cons.throws = argument_errors(&cons.name, &cons.arguments)?;
+                    cons.throws_from_arguments = cons.throws.is_some();
+                }
+            }
+            for meth in obj.methods.iter_mut() {
+                if meth.throws.is_none() {
+                    meth.throws = argument_errors(&meth.name, &meth.arguments)?;
+                    meth.throws_from_arguments = meth.throws.is_some();
+                }
+            }
         }
+        Ok(())
     }
-}
 
-/// Stateful iterator for yielding all types contained in a given type.
+    /// Find the records and enums which contain themselves, and box the fields through which
+    /// they do.
+    ///
+    /// Sequences and maps are already an indirection, so only the fields referring to a record or
+    /// enum directly, or as an optional value, need to be boxed for the Rust types to have a size.
+    /// This is called again once proc-macro metadata has been added.
+    pub(super) fn resolve_recursive_types(&mut self) -> Result<()> {
+        // The records and enums each record or enum refers to without any indirection.
+        fn direct_references<'a>(type_: &'a Type, names: &mut Vec<&'a str>) {
+            match type_ {
+                Type::Record(name) | Type::Enum(name) => names.push(name),
+                Type::Optional(inner) => direct_references(inner, names),
+                Type::Tuple(elements) => {
+                    for element in elements {
+                        direct_references(element, names)
+                    }
+                }
+                Type::Result(ok, err) => {
+                    direct_references(ok, names);
+                    direct_references(err, names);
+                }
+                _ => {}
+            }
+        }
+        let fields_of = |name: &str| -> Vec<&Field> {
+            match (self.records.get(name), self.enums.get(name)) {
+                (Some(rec), _) => rec.fields.iter().collect(),
+                (_, Some(enum_)) => enum_.variants.iter().flat_map(|v| &v.fields).collect(),
+                _ => Vec::new(),
+            }
+        };
+        let refers_to = |from: &str, to: &str| -> bool {
+            let mut seen = HashSet::new();
+            let mut pending = vec![from.to_owned()];
+            while let Some(name) = pending.pop() {
+                if name == to {
+                    return true;
+                }
+                if seen.insert(name.clone()) {
+                    let mut names = Vec::new();
+                    for field in fields_of(&name) {
+                        direct_references(&field.type_, &mut names);
+                    }
+                    pending.extend(names.into_iter().map(ToOwned::to_owned));
+                }
+            }
+            false
+        };
+
+        // The positions of the fields to box, by the name of their record or enum. Variants can
+        // have fields of the same name, so fields are identified by their position in
+        // `fields_of`, which follows the order of the variants.
+        let mut boxed_fields = BTreeMap::<String, BTreeSet<usize>>::new();
+        for name in self.records.keys().chain(self.enums.keys()) {
+            for (index, field) in fields_of(name).into_iter().enumerate() {
+                let target = match &field.type_ {
+                    Type::Optional(inner) => inner.as_ref(),
+                    type_ => type_,
+                };
+                match target {
+                    Type::Record(target) | Type::Enum(target) if refers_to(target, name) => {
+                        boxed_fields.entry(name.clone()).or_default().insert(index);
+                    }
+                    _ => {
+                        let mut names = Vec::new();
+                        direct_references(&field.type_, &mut names);
+                        if names.into_iter().any(|target| refers_to(target, name)) {
+                            bail!(
+                                "`{name}` contains itself through the `{}` field, which can't be boxed; use a sequence instead",
+                                field.name
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        for rec in self.records.values_mut() {
+            let boxed = boxed_fields.get(&rec.name);
+            rec.recursive = boxed.is_some();
+            for (index, field) in rec.fields.iter_mut().enumerate() {
+                field.boxed = boxed.map_or(false, |indices| indices.contains(&index));
+            }
+        }
+        for enum_ in self.enums.values_mut() {
+            let boxed = boxed_fields.get(&enum_.name);
+            enum_.recursive = boxed.is_some();
+            let fields = enum_.variants.iter_mut().flat_map(|v| v.fields.iter_mut());
+            for (index, field) in fields.enumerate() {
+                field.boxed = boxed.map_or(false, |indices| indices.contains(&index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Automatically derive the low-level FFI functions from the high-level types in the interface.
+    ///
+    /// This should only be called after the high-level types have been completed defined, otherwise
+    /// the resulting set will be missing some entries.
+    pub fn derive_ffi_funcs(&mut self) -> Result<()> {
+        let ci_prefix = self.ffi_namespace().to_owned();
+        for func in self.functions.iter_mut() {
+            func.derive_ffi_func(&ci_prefix)?;
+        }
+        // Only the objects used through weak references get the FFI functions for them.
+        let weakly_referenced: HashSet<_> = self
+            .types
+            .iter_known_types()
+            .filter_map(|t| match t {
+                Type::Weak(inner) => Some(inner.canonical_name()),
+                _ => None,
+            })
+            .collect();
+        for obj in self.objects.iter_mut() {
+            obj.referenced_weakly = weakly_referenced.contains(&obj.type_().canonical_name());
+            obj.derive_ffi_funcs(&ci_prefix)?;
+        }
+        for callback in self.callback_interfaces.iter_mut() {
+            callback.derive_ffi_funcs(&ci_prefix);
+        }
+        self.pass_structs_by_value();
+        Ok(())
+    }
+
+    // The records which are C structs are passed by value as arguments and return values, the
+    // other uses of them, like in sequences or callback interfaces, still serialize them.
+    fn pass_structs_by_value(&mut self) {
+        let ffi_structs: HashSet<_> = self
+            .records
+            .values()
+            .filter(|rec| rec.is_ffi_struct())
+            .map(|rec| rec.name.clone())
+            .collect();
+        if ffi_structs.is_empty() {
+            return;
+        }
+        for func in self.functions.iter_mut() {
+            let arguments = func.full_arguments();
+            func.ffi_func.pass_structs_by_value(
+                &arguments,
+                func.return_type.as_ref(),
+                &ffi_structs,
+            );
+        }
+        for obj in self.objects.iter_mut() {
+            for cons in obj.constructors.iter_mut() {
+                cons.ffi_func
+                    .pass_structs_by_value(&cons.arguments, None, &ffi_structs);
+            }
+            for meth in obj.methods.iter_mut() {
+                let arguments = meth.full_arguments();
+                meth.ffi_func.pass_structs_by_value(
+                    &arguments,
+                    meth.return_type.as_ref(),
+                    &ffi_structs,
+                );
+            }
+        }
+    }
+}
+
+/// `ComponentInterface` structs can be hashed, but this is mostly a convenient way to
+/// produce a checksum of their contents. They're not really intended to live in a hashtable.
+impl Hash for ComponentInterface {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // We can't hash `self.types`, but its contents are implied by the other fields
+        // anyway, so it's safe to ignore it.
+        self.uniffi_version.hash(state);
+        self.namespace.hash(state);
+        self.compact_encoding.hash(state);
+        self.utf16_strings.hash(state);
+        self.enums.hash(state);
+        self.records.hash(state);
+        self.functions.hash(state);
+        self.objects.hash(state);
+        self.callback_interfaces.hash(state);
+        self.errors.hash(state);
+        self.constants.hash(state);
+        self.custom_type_errors.hash(state);
+    }
+}
+
+fn get_or_insert_object<'a>(objects: &'a mut Vec<Object>, name: &str) -> &'a mut Object {
+    // The find-based way of writing this currently runs into a borrow checker
+    // error, so we use position
+    match objects.iter_mut().position(|o| o.name == name) {
+        Some(idx) => &mut objects[idx],
+        None => {
+            objects.push(Object::new(name.to_owned()));
+            objects.last_mut().unwrap()
+        }
+    }
+}
+
+/// Stateful iterator for yielding all types contained in a given type.
 ///
 /// This struct is the implementation of [`ComponentInterface::iter_types_in_item`] and should be
 /// considered an opaque implementation detail. It's a separate struct because I couldn't
@@ -827,7 +1695,7 @@ impl<'a> RecursiveTypeIterator<'a> {
             Type::Record(nm)
             | Type::Enum(nm)
             | Type::Error(nm)
-            | Type::Object(nm)
+            | Type::Object { name: nm, .. }
             | Type::CallbackInterface(nm) => {
                 if !self.seen.contains(nm.as_str()) {
                     self.pending.push(type_);
@@ -853,7 +1721,9 @@ impl<'a> RecursiveTypeIterator<'a> {
                 Type::Record(nm) => self.ci.get_record_definition(nm).map(Record::iter_types),
                 Type::Enum(nm) => self.ci.get_enum_definition(nm).map(Enum::iter_types),
                 Type::Error(nm) => self.ci.get_error_definition(nm).map(Error::iter_types),
-                Type::Object(nm) => self.ci.get_object_definition(nm).map(Object::iter_types),
+                Type::Object { name, .. } => {
+                    self.ci.get_object_definition(name).map(Object::iter_types)
+                }
                 Type::CallbackInterface(nm) => self
                     .ci
                     .get_callback_interface_definition(nm)
@@ -885,6 +1755,36 @@ impl<'a> Iterator for RecursiveTypeIterator<'a> {
     }
 }
 
+/// Parse a string containing WebIDL into a list of weedle definitions.
+fn parse_definitions(idl: &str) -> Result<weedle::Definitions<'_>> {
+    // Note we use `weedle::Definitions::parse` instead of `weedle::parse` so
+    // on parse errors we can see how far weedle got, which helps locate the problem.
+    use weedle::Parse; // this trait must be in scope for parse to work.
+    let (remaining, defns) = match weedle::Definitions::parse(idl.trim()) {
+        Ok(parsed) => parsed,
+        Err(weedle::Err::Error(err) | weedle::Err::Failure(err)) => {
+            return Err(diagnostics::parse_error(err.input))
+        }
+        Err(weedle::Err::Incomplete(_)) => bail!("parse error: unexpected end of the UDL"),
+    };
+    if !remaining.is_empty() {
+        return Err(diagnostics::parse_error(remaining));
+    }
+    Ok(defns)
+}
+
+/// Get the paths named by the `include "...";` directives in a string containing WebIDL,
+/// in the order in which they appear.
+pub fn udl_includes(idl: &str) -> Result<Vec<String>> {
+    Ok(parse_definitions(idl)?
+        .iter()
+        .filter_map(|d| match d {
+            weedle::Definition::Include(d) => Some(d.path.0.to_string()),
+            _ => None,
+        })
+        .collect())
+}
+
 /// Trait to help build a `ComponentInterface` from WedIDL syntax nodes.
 ///
 /// This trait does structural matching on the various weedle AST nodes and
@@ -935,6 +1835,10 @@ impl APIBuilder for weedle::Definition<'_> {
                 } else if attrs.contains_error_attr() {
                     let e = d.convert(ci)?;
                     ci.add_error_definition(e);
+                } else if !attrs.instances().is_empty() {
+                    for obj in object::convert_generic_interface(d, ci)? {
+                        ci.add_object_definition(obj);
+                    }
                 } else {
                     let obj = d.convert(ci)?;
                     ci.add_object_definition(obj);
@@ -944,8 +1848,23 @@ impl APIBuilder for weedle::Definition<'_> {
                 let obj = d.convert(ci)?;
                 ci.add_callback_interface_definition(obj);
             }
-            // everything needed for typedefs is done in finder.rs.
-            weedle::Definition::Typedef(_) => {}
+            weedle::Definition::Callback(d) => {
+                let obj = d.convert(ci)?;
+                ci.add_callback_interface_definition(obj);
+            }
+            // everything needed for typedefs is done in finder.rs, but the errors of custom types.
+            weedle::Definition::Typedef(d) => {
+                let attrs = attributes::TypedefAttributes::try_from(d.attributes.as_ref())?;
+                if let Some(error_name) = attrs.get_throws_err() {
+                    let error = ci.resolve_throws_type(Some(error_name)).unwrap();
+                    ci.custom_type_errors
+                        .insert(d.identifier.0.to_string(), error);
+                }
+            }
+            weedle::Definition::Include(d) => bail!(
+                "`include \"{}\"` can only be used in a UDL file processed by uniffi-bindgen",
+                d.path.0
+            ),
             _ => bail!("don't know how to deal with {:?}", self),
         }
         Ok(())
@@ -990,9 +1909,13 @@ fn convert_type(s: &uniffi_meta::Type) -> Type {
         Ty::F32 => Type::Float32,
         Ty::F64 => Type::Float64,
         Ty::Bool => Type::Boolean,
+        Ty::Char => Type::Char,
         Ty::String => Type::String,
         Ty::Option { inner_type } => Type::Optional(convert_type(inner_type).into()),
         Ty::Vec { inner_type } => Type::Sequence(convert_type(inner_type).into()),
+        Ty::HashSet { inner_type } | Ty::BTreeSet { inner_type } => {
+            Type::Set(convert_type(inner_type).into())
+        }
         Ty::HashMap {
             key_type,
             value_type,
@@ -1000,7 +1923,18 @@ fn convert_type(s: &uniffi_meta::Type) -> Type {
             convert_type(key_type).into(),
             convert_type(value_type).into(),
         ),
-        Ty::ArcObject { object_name } => Type::Object(object_name.clone()),
+        Ty::Tuple { element_types } => {
+            Type::Tuple(element_types.iter().map(convert_type).collect())
+        }
+        Ty::ArcObject { object_name } => Type::Object {
+            name: object_name.clone(),
+            imp: ObjectImpl::Struct,
+        },
+        Ty::ArcTraitObject { trait_name } => Type::Object {
+            name: trait_name.clone(),
+            imp: ObjectImpl::Trait,
+        },
+        Ty::BoxTraitObject { trait_name } => Type::CallbackInterface(trait_name.clone()),
         Ty::Unresolved { name } => Type::Unresolved { name: name.clone() },
     }
 }
@@ -1060,6 +1994,135 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_contract_version() {
+        let mut ci = ComponentInterface::from_webidl(UDL1).unwrap();
+        let version = ci.contract_version();
+        assert_eq!(version >> 24, UNIFFI_CONTRACT_VERSION);
+        assert_eq!((version >> 16) & 0xFF, 0);
+        assert_eq!(version & 0xFFFF, u32::from(ci.checksum()));
+        assert_eq!(
+            ci.ffi_contract_version_name(),
+            "uniffi_foobar_contract_version"
+        );
+        // Like the names of the FFI functions, it doesn't change with the definitions added later.
+        ci.enums.clear();
+        assert_ne!(version & 0xFFFF, u32::from(ci.checksum()));
+        assert_eq!(ci.contract_version(), version);
+
+        let mut ci = ComponentInterface::from_webidl("namespace foobar{};").unwrap();
+        ci.set_compact_encoding(true);
+        assert_eq!((ci.contract_version() >> 16) & 0xFF, 1);
+    }
+
+    #[test]
+    fn test_ffi_prefix() {
+        const UDL: &str = r#"
+            namespace hello {
+                u64 world();
+            };
+            interface Greeter {
+                constructor();
+                string greet();
+            };
+        "#;
+        let mut ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let checksum = ci.checksum();
+        ci.set_ffi_prefix("acme").unwrap();
+        assert_eq!(ci.ffi_namespace(), format!("acme_hello_{checksum:x}"));
+        assert_eq!(
+            ci.ffi_contract_version_name(),
+            "uniffi_acme_hello_contract_version"
+        );
+        let names: Vec<_> = ci
+            .iter_ffi_function_definitions()
+            .map(|func| func.name)
+            .collect();
+        assert!(names.contains(&format!("acme_hello_{checksum:x}_world")));
+        assert!(names.contains(&format!("acme_hello_{checksum:x}_Greeter_greet")));
+        assert!(names.contains(&format!("ffi_acme_hello_{checksum:x}_Greeter_object_free")));
+        assert!(names.contains(&format!("ffi_acme_hello_{checksum:x}_rustbuffer_alloc")));
+        assert!(names
+            .iter()
+            .all(|name| name.starts_with("acme_") || name.starts_with("ffi_acme_")));
+
+        // The functions exported with the proc-macros get the prefix when they are added.
+        ci.add_fn_meta(FnMetadata {
+            module_path: vec!["hello".to_string()],
+            name: "exported".to_string(),
+            inputs: vec![],
+            return_type: None,
+            docstring: None,
+        })
+        .unwrap();
+        let exported = ci.get_function_definition("exported").unwrap();
+        assert!(exported
+            .ffi_func()
+            .name()
+            .starts_with("_uniffi_acme_hello_exported_"));
+
+        let err = ci.set_ffi_prefix("1acme").unwrap_err();
+        assert!(err.to_string().contains("must be made of ASCII letters"));
+    }
+
+    #[test]
+    fn test_udl_metadata_rebuilds_the_interface() {
+        const UDL_MAIN: &str = r#"
+            include "types.udl";
+            namespace test {
+                Point origin();
+            };
+        "#;
+        const UDL_TYPES: &str = r#"
+            dictionary Point {
+                double x;
+                double y;
+            };
+        "#;
+        let mut ci = ComponentInterface::from_webidl_sources(&[UDL_MAIN, UDL_TYPES]).unwrap();
+        assert_eq!(ci.udl_metadata_symbol_name(), "UNIFFI_META_UDL_test");
+        ci.set_ffi_prefix("acme").unwrap();
+        assert_eq!(ci.udl_metadata_symbol_name(), "UNIFFI_META_acme_UDL_test");
+
+        let udl = ci.udl_metadata();
+        assert_eq!(udl.namespace, "test");
+        assert_eq!(udl.ffi_prefix.as_deref(), Some("acme"));
+        assert_eq!(udl.value_encoding, uniffi_meta::ValueEncoding::Standard);
+        let rebuilt = ComponentInterface::from_webidl_sources(&udl.sources).unwrap();
+        assert_eq!(rebuilt.checksum(), ci.checksum());
+        assert!(rebuilt.get_record_definition("Point").is_some());
+    }
+
+    #[test]
+    fn test_include_directives() {
+        const UDL_MAIN: &str = r#"
+            include "types.udl";
+            namespace test {
+                Point origin();
+            };
+        "#;
+        const UDL_TYPES: &str = r#"
+            dictionary Point {
+                i32 x;
+                i32 y;
+            };
+        "#;
+        assert_eq!(udl_includes(UDL_MAIN).unwrap(), vec!["types.udl"]);
+        assert!(udl_includes(UDL_TYPES).unwrap().is_empty());
+
+        let ci = ComponentInterface::from_webidl_sources(&[UDL_MAIN, UDL_TYPES]).unwrap();
+        assert_eq!(ci.namespace(), "test");
+        assert!(ci.get_record_definition("Point").is_some());
+        assert_eq!(ci.function_definitions().len(), 1);
+
+        // Without a UDL file to resolve them against, includes are an error.
+        let err = ComponentInterface::from_webidl(UDL_MAIN).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`include \"types.udl\"` can only be used in a UDL file processed by uniffi-bindgen"
+        );
+    }
+
     #[test]
     fn test_duplicate_type_names_are_an_error() {
         const UDL: &str = r#"
@@ -1075,7 +2138,7 @@ mod test {
         assert_eq!(
             err.to_string(),
             "Conflicting type definition for `Testing`! \
-             existing definition: Object(\"Testing\"), \
+             existing definition: Object { name: \"Testing\", imp: Struct }, \
              new definition: Record(\"Testing\")"
         );
 
@@ -1133,6 +2196,397 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_empty_record_defaults_need_field_defaults() {
+        const UDL: &str = r#"
+            namespace test{
+                void testing(optional Options options = {});
+            };
+            dictionary Options {
+                boolean verbose = false;
+                u32 retries = 3;
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert!(matches!(
+            ci.get_function_definition("testing").unwrap().arguments()[0].default_value(),
+            Some(Literal::EmptyRecord(Type::Record(name))) if name == "Options"
+        ));
+
+        const UDL2: &str = r#"
+            namespace test{
+                void testing(optional Options options = {});
+            };
+            dictionary Options {
+                boolean verbose = false;
+                u32 retries;
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Record `Options` can only default to `{}` if all of its fields have a default value"
+        );
+    }
+
+    #[test]
+    fn test_enum_defaults_name_a_variant() {
+        const UDL: &str = r#"
+            namespace test{};
+            enum Level { "Low", "High" };
+            [Enum]
+            interface Retry {
+                Never();
+                Times(u32 count);
+            };
+            dictionary Options {
+                Level level = "High";
+                Retry retry = "Never";
+                Options? fallback = null;
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let fields = ci.get_record_definition("Options").unwrap().fields();
+        assert!(matches!(
+            fields[0].default_value(),
+            Some(Literal::Enum(variant, Type::Enum(name))) if variant == "High" && name == "Level"
+        ));
+        assert!(matches!(
+            fields[1].default_value(),
+            Some(Literal::EmptyVariant(variant, Type::Enum(name))) if variant == "Never" && name == "Retry"
+        ));
+
+        const UDL2: &str = r#"
+            namespace test{};
+            enum Level { "Low", "High" };
+            dictionary Options {
+                Level level = "Medium";
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(err.to_string(), "Enum `Level` has no variant `Medium`");
+
+        const UDL3: &str = r#"
+            namespace test{};
+            [Enum]
+            interface Retry {
+                Never();
+                Times(u32 count);
+            };
+            dictionary Options {
+                Retry retry = "Times";
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Variant `Times` of enum `Retry` has fields, so it can't be a default value"
+        );
+    }
+
+    #[test]
+    fn test_iterators_can_only_be_returned() {
+        const UDL: &str = r#"
+            namespace test{
+                iterator<string> words(string text);
+            };
+            interface Dictionary {
+                iterator<u32> lengths();
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert_eq!(ci.iter_iterator_types().count(), 2);
+        let iterator = Type::Iterator(Box::new(Type::String));
+        assert!(ci
+            .iter_ffi_function_definitions()
+            .any(|f| f.name() == ci.ffi_iterator_next(&iterator).name()));
+
+        const UDL2: &str = r#"
+            namespace test{
+                void count(iterator<string> words);
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Iterators can only be returned by functions and methods, not passed like `Iterator(String)`"
+        );
+
+        const UDL3: &str = r#"
+            namespace test{
+                sequence<iterator<string>> split(string text);
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Iterators can't be nested in other types, like `Sequence(Iterator(String))`"
+        );
+    }
+
+    #[test]
+    fn test_shared_slices() {
+        const UDL: &str = r#"
+            namespace test{
+                shared_slice load(string path);
+            };
+            interface Dataset {
+                shared_slice column(u32 index);
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert!(ci.uses_shared_slices());
+        assert_eq!(ci.iter_shared_slice_ffi_function_definitions().count(), 3);
+        assert!(ci
+            .iter_ffi_function_definitions()
+            .any(|f| f.name() == ci.ffi_shared_slice_free().name()));
+
+        let ci = ComponentInterface::from_webidl("namespace test{};").unwrap();
+        assert!(!ci.uses_shared_slices());
+        assert_eq!(ci.iter_shared_slice_ffi_function_definitions().count(), 0);
+
+        const UDL2: &str = r#"
+            namespace test{
+                void store(shared_slice data);
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Shared slices can only be returned by functions and methods, not passed"
+        );
+
+        const UDL3: &str = r#"
+            namespace test{
+                shared_slice? load(string path);
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Shared slices can't be nested in other types, like `Optional(SharedSlice)`"
+        );
+    }
+
+    #[test]
+    fn test_renamed_fields_variants_and_arguments() {
+        const UDL: &str = r#"
+            namespace test{
+                void paint([Rename=surface] Item item, optional Shade shade = "Dark");
+            };
+            dictionary Item {
+                [Rename="kind"] string type;
+            };
+            [Enum]
+            interface Shade {
+                [Rename=Dark] Black();
+                White();
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let field = &ci.get_record_definition("Item").unwrap().fields()[0];
+        assert_eq!(field.name(), "kind");
+        assert_eq!(field.rust_name(), "type");
+        let variant = &ci.get_enum_definition("Shade").unwrap().variants()[0];
+        assert_eq!(variant.name(), "Dark");
+        assert_eq!(variant.rust_name(), "Black");
+        let func = ci.get_function_definition("paint").unwrap();
+        assert_eq!(func.arguments()[0].name(), "surface");
+        assert!(matches!(
+            func.arguments()[1].default_value(),
+            Some(Literal::EmptyVariant(name, _)) if name == "Dark"
+        ));
+
+        const UDL2: &str = r#"
+            namespace test{};
+            dictionary Item {
+                [Rename=kind] string type;
+                string kind;
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(err.to_string(), "`Item` has several fields named `kind`");
+    }
+
+    #[test]
+    fn test_custom_type_errors() {
+        const UDL: &str = r#"
+            namespace test{
+                void check(sequence<Guid> guids);
+                [Throws=OtherError]
+                void try_check(Guid guid);
+                void unchecked(string guid);
+            };
+            [Custom, Throws=GuidError]
+            typedef string Guid;
+            [Error]
+            enum GuidError { "TooShort" };
+            [Error]
+            enum OtherError { "Oops" };
+            interface Store {
+                constructor(Guid? guid);
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let check = ci.get_function_definition("check").unwrap();
+        assert!(matches!(check.throws_type(), Some(Type::Error(name)) if name == "GuidError"));
+        assert!(check.throws_from_arguments());
+        let try_check = ci.get_function_definition("try_check").unwrap();
+        assert!(matches!(try_check.throws_type(), Some(Type::Error(name)) if name == "OtherError"));
+        assert!(!try_check.throws_from_arguments());
+        assert!(ci
+            .get_function_definition("unchecked")
+            .unwrap()
+            .throws_type()
+            .is_none());
+        let cons = &ci.get_object_definition("Store").unwrap().constructors()[0];
+        assert!(cons.throws_from_arguments());
+
+        const UDL2: &str = r#"
+            namespace test{
+                void check(Guid guid, Url url);
+            };
+            [Custom, Throws=GuidError]
+            typedef string Guid;
+            [Custom, Throws=UrlError]
+            typedef string Url;
+            [Error]
+            enum GuidError { "TooShort" };
+            [Error]
+            enum UrlError { "Malformed" };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`check` takes custom types with different errors, `GuidError` and `UrlError`, so it must declare which one it throws"
+        );
+    }
+
+    #[test]
+    fn test_recursive_types() {
+        const UDL: &str = r#"
+            namespace test{};
+            dictionary Node {
+                string name;
+                Node? parent;
+                sequence<Node> children;
+            };
+            [Enum]
+            interface Expr {
+                Num(i64 value);
+                Neg(Expr value);
+                Add(Expr left, Expr right);
+                Let(Binding binding);
+            };
+            dictionary Binding {
+                string name;
+                Expr value;
+            };
+            dictionary Leaf {
+                Node node;
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let boxed =
+            |fields: &[Field]| -> Vec<bool> { fields.iter().map(Field::is_boxed).collect() };
+        let node = ci.get_record_definition("Node").unwrap();
+        assert!(node.is_recursive());
+        assert_eq!(boxed(node.fields()), vec![false, true, false]);
+        let expr = ci.get_enum_definition("Expr").unwrap();
+        assert!(expr.is_recursive());
+        let variants = expr.variants();
+        assert_eq!(boxed(variants[0].fields()), vec![false]);
+        assert_eq!(boxed(variants[1].fields()), vec![true]);
+        assert_eq!(boxed(variants[2].fields()), vec![true, true]);
+        assert_eq!(boxed(variants[3].fields()), vec![true]);
+        let binding = ci.get_record_definition("Binding").unwrap();
+        assert!(binding.is_recursive());
+        assert_eq!(boxed(binding.fields()), vec![false, true]);
+        let leaf = ci.get_record_definition("Leaf").unwrap();
+        assert!(!leaf.is_recursive());
+        assert_eq!(boxed(leaf.fields()), vec![false]);
+
+        const UDL2: &str = r#"
+            namespace test{};
+            dictionary Pair {
+                tuple<Pair, string>? next;
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Pair` contains itself through the `next` field, which can't be boxed; use a sequence instead"
+        );
+    }
+
+    #[test]
+    fn test_methods_of_records_and_enums() {
+        const UDL: &str = r#"
+            namespace test{
+                [Method=Point]
+                double distance(Point other);
+                [Method=Color]
+                Color inverse();
+                double distance(Point from, Point to);
+            };
+            dictionary Point {
+                double x;
+                double y;
+            };
+            enum Color { "Black", "White" };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert_eq!(ci.function_definitions().len(), 1);
+        assert_eq!(ci.all_function_definitions().len(), 3);
+        assert!(ci.get_function_definition("inverse").is_none());
+
+        let point = Type::Record("Point".into());
+        let methods = ci.methods_of(&point);
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].name(), "distance");
+        assert_eq!(methods[0].self_type_name(), Some("Point"));
+        assert_eq!(methods[0].arguments().len(), 1);
+        assert_eq!(methods[0].full_arguments().len(), 2);
+        assert_eq!(methods[0].full_arguments()[0].type_(), &point);
+        assert_eq!(methods[0].ffi_func().arguments().len(), 2);
+        assert_ne!(
+            methods[0].ffi_func().name(),
+            ci.get_function_definition("distance")
+                .unwrap()
+                .ffi_func()
+                .name()
+        );
+        assert_eq!(ci.methods_of(&Type::Enum("Color".into())).len(), 1);
+
+        const UDL2: &str = r#"
+            namespace test{
+                [Method=Counter]
+                u32 value();
+            };
+            interface Counter {};
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`[Method=Counter]` functions can only be methods of dictionaries and enums"
+        );
+
+        const UDL3: &str = r#"
+            namespace test{
+                [Method=Color]
+                Color inverse();
+                [Method=Color]
+                Color inverse(boolean twice);
+            };
+            enum Color { "Black", "White" };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "duplicate method definition: \"Color.inverse\""
+        );
+    }
+
     #[test]
     fn test_contains_optional_types() {
         let mut ci = ComponentInterface {
@@ -1196,7 +2650,8 @@ mod test {
             };
         "#;
         let ci = ComponentInterface::from_webidl(UDL).unwrap();
-        assert!(!ci.item_contains_unsigned_types(&Type::Object("Testing".into())));
+        let obj = ci.get_object_definition("Testing").unwrap();
+        assert!(!ci.item_contains_unsigned_types(&obj.type_()));
     }
 
     #[test]
@@ -1214,6 +2669,7 @@ mod test {
             };
         "#;
         let ci = ComponentInterface::from_webidl(UDL).unwrap();
-        assert!(ci.item_contains_unsigned_types(&Type::Object("TestObj".into())));
+        let obj = ci.get_object_definition("TestObj").unwrap();
+        assert!(ci.item_contains_unsigned_types(&obj.type_()));
     }
 }
@@ -61,28 +61,38 @@
 //! Yeah, it's a bit of an awkward fit syntactically, but it's enough
 //! to get us up and running for a first version of this tool.
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 
+use super::attributes::NamespaceAttributes;
 use super::{APIBuilder, APIConverter, ComponentInterface};
 
-/// A namespace is currently just a name, but might hold more metadata about
-/// the component in future.
+/// A namespace is the name of the component, and how its values are passed.
 ///
 #[derive(Debug, Clone, Hash)]
 pub struct Namespace {
     pub(super) name: String,
+    /// Whether the namespace has the `[Utf16Strings]` attribute, see `uniffi::Utf16String`.
+    pub(super) utf16_strings: bool,
 }
 
 impl APIBuilder for weedle::NamespaceDefinition<'_> {
     fn process(&self, ci: &mut ComponentInterface) -> Result<()> {
-        if self.attributes.is_some() {
-            bail!("namespace attributes are not supported yet");
-        }
+        let attributes = NamespaceAttributes::try_from(self.attributes.as_ref())?;
         ci.add_namespace_definition(Namespace {
             name: self.identifier.0.to_string(),
+            utf16_strings: attributes.has_utf16_strings(),
         })?;
-        for func in self.members.body.convert(ci)? {
-            ci.add_function_definition(func)?;
+        for member in &self.members.body {
+            match member {
+                weedle::namespace::NamespaceMember::Const(c) => {
+                    let constant = c.convert(ci)?;
+                    ci.add_constant_definition(constant)?;
+                }
+                _ => {
+                    let func = member.convert(ci)?;
+                    ci.add_function_definition(func)?;
+                }
+            }
         }
         Ok(())
     }
@@ -117,6 +127,17 @@ mod test {
         assert!(ci.get_function_definition("potato").is_none());
     }
 
+    #[test]
+    fn test_namespace_attributes() {
+        let ci = ComponentInterface::from_webidl("namespace foobar{};").unwrap();
+        assert!(!ci.utf16_strings());
+        let utf16 = ComponentInterface::from_webidl("[Utf16Strings] namespace foobar{};").unwrap();
+        assert!(utf16.utf16_strings());
+
+        let err = ComponentInterface::from_webidl("[Trait] namespace foobar{};").unwrap_err();
+        assert_eq!(err.to_string(), "Trait not supported for namespaces");
+    }
+
     #[test]
     fn test_rejects_duplicate_namespaces() {
         const UDL: &str = r#"
@@ -84,25 +84,43 @@ use super::{APIConverter, ComponentInterface};
 /// binding code is expected to stitch these functions back together into an appropriate class
 /// definition (or that language's equivalent thereof).
 ///
+/// By default an object is implemented in Rust by a struct of the same name. Interfaces marked
+/// with the `[Trait]` attribute are implemented by a trait of the same name instead, and their
+/// instances are `Arc<dyn Trait>`s, so any implementation of the trait can be handed out.
+///
 /// TODO:
 ///  - maybe "Class" would be a better name than "Object" here?
 #[derive(Debug, Clone)]
 pub struct Object {
     pub(super) name: String,
+    pub(super) imp: ObjectImpl,
     pub(super) constructors: Vec<Constructor>,
     pub(super) methods: Vec<Method>,
     pub(super) ffi_func_free: FFIFunction,
+    pub(super) ffi_func_clone: FFIFunction,
+    pub(super) referenced_weakly: bool,
+    pub(super) ffi_func_weak_free: FFIFunction,
+    pub(super) ffi_func_weak_clone: FFIFunction,
+    pub(super) ffi_func_weak_upgrade: FFIFunction,
     pub(super) uses_deprecated_threadsafe_attribute: bool,
+    pub(super) docstring: Option<String>,
 }
 
 impl Object {
     pub(super) fn new(name: String) -> Object {
         Object {
             name,
+            imp: ObjectImpl::Struct,
             constructors: Default::default(),
             methods: Default::default(),
             ffi_func_free: Default::default(),
+            ffi_func_clone: Default::default(),
+            referenced_weakly: false,
+            ffi_func_weak_free: Default::default(),
+            ffi_func_weak_clone: Default::default(),
+            ffi_func_weak_upgrade: Default::default(),
             uses_deprecated_threadsafe_attribute: false,
+            docstring: None,
         }
     }
 
@@ -110,8 +128,24 @@ impl Object {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
+    pub fn imp(&self) -> ObjectImpl {
+        self.imp
+    }
+
+    /// The Rust type implementing the object, `r#Name` or `dyn r#Name`.
+    pub fn rust_name(&self) -> String {
+        self.imp.rust_name_for(&self.name)
+    }
+
     pub fn type_(&self) -> Type {
-        Type::Object(self.name.clone())
+        Type::Object {
+            name: self.name.clone(),
+            imp: self.imp,
+        }
     }
 
     pub fn constructors(&self) -> Vec<&Constructor> {
@@ -131,10 +165,27 @@ impl Object {
             .collect()
     }
 
+    /// The methods called on an instance of the object.
     pub fn methods(&self) -> Vec<&Method> {
+        self.methods.iter().filter(|m| !m.is_static()).collect()
+    }
+
+    /// The methods called on the object type itself, without an instance.
+    pub fn static_methods(&self) -> Vec<&Method> {
+        self.methods.iter().filter(|m| m.is_static()).collect()
+    }
+
+    /// Both the instance and the static methods, as needed by the scaffolding.
+    pub fn all_methods(&self) -> Vec<&Method> {
         self.methods.iter().collect()
     }
 
+    /// Whether the object has an instance or static method with this name, which the bindings
+    /// can't use for the members they generate themselves, like `destroy`.
+    pub fn has_method(&self, name: &str) -> bool {
+        self.methods.iter().any(|m| m.name() == name)
+    }
+
     pub fn get_method(&self, name: &str) -> Method {
         let matches: Vec<_> = self.methods.iter().filter(|m| m.name() == name).collect();
         match matches.len() {
@@ -147,12 +198,57 @@ impl Object {
         &self.ffi_func_free
     }
 
+    /// The FFI function giving the foreign-language code a new reference to an object, which it
+    /// passes on to Rust when writing the object into a `RustBuffer`, as Rust takes ownership of
+    /// the objects it reads.
+    pub fn ffi_object_clone(&self) -> &FFIFunction {
+        &self.ffi_func_clone
+    }
+
+    /// Whether the object is used through weak references, `weak<Name>` in the UDL.
+    ///
+    /// Only such objects have the FFI functions for managing weak references.
+    pub fn is_referenced_weakly(&self) -> bool {
+        self.referenced_weakly
+    }
+
+    pub fn ffi_weak_free(&self) -> &FFIFunction {
+        &self.ffi_func_weak_free
+    }
+
+    pub fn ffi_weak_clone(&self) -> &FFIFunction {
+        &self.ffi_func_weak_clone
+    }
+
+    /// The FFI function returning the object behind a weak reference, as a `RustBuffer` holding
+    /// an optional object, which is empty if the object has already been dropped.
+    pub fn ffi_weak_upgrade(&self) -> &FFIFunction {
+        &self.ffi_func_weak_upgrade
+    }
+
+    /// The type returned by `ffi_weak_upgrade()`, as seen by the foreign-language code.
+    pub fn weak_upgrade_type(&self) -> Type {
+        Type::Optional(Box::new(self.type_()))
+    }
+
     pub fn uses_deprecated_threadsafe_attribute(&self) -> bool {
         self.uses_deprecated_threadsafe_attribute
     }
 
     pub fn iter_ffi_function_definitions(&self) -> impl Iterator<Item = &FFIFunction> {
+        let weak_ffi_funcs = [
+            &self.ffi_func_weak_free,
+            &self.ffi_func_weak_clone,
+            &self.ffi_func_weak_upgrade,
+        ];
+        let referenced_weakly = self.referenced_weakly;
         iter::once(&self.ffi_func_free)
+            .chain(iter::once(&self.ffi_func_clone))
+            .chain(
+                weak_ffi_funcs
+                    .into_iter()
+                    .filter(move |_| referenced_weakly),
+            )
             .chain(self.constructors.iter().map(|f| &f.ffi_func))
             .chain(self.methods.iter().map(|f| &f.ffi_func))
     }
@@ -163,12 +259,41 @@ impl Object {
         if self.ffi_func_free.name().is_empty() {
             self.ffi_func_free.name = format!("ffi_{ci_prefix}_{}_object_free", self.name);
         }
+        // Objects are passed as handles to their `Arc`s.
         self.ffi_func_free.arguments = vec![FFIArgument {
-            name: "ptr".to_string(),
-            type_: FFIType::RustArcPtr(self.name().to_string()),
+            name: "handle".to_string(),
+            type_: FFIType::UInt64,
         }];
         self.ffi_func_free.return_type = None;
 
+        if self.ffi_func_clone.name().is_empty() {
+            self.ffi_func_clone.name = format!("ffi_{ci_prefix}_{}_object_clone", self.name);
+        }
+        self.ffi_func_clone.arguments = vec![FFIArgument {
+            name: "handle".to_string(),
+            type_: FFIType::UInt64,
+        }];
+        self.ffi_func_clone.return_type = Some(FFIType::UInt64);
+
+        if self.referenced_weakly {
+            self.ffi_func_weak_free.name = format!("ffi_{ci_prefix}_{}_weak_free", self.name);
+            self.ffi_func_weak_clone.name = format!("ffi_{ci_prefix}_{}_weak_clone", self.name);
+            self.ffi_func_weak_upgrade.name = format!("ffi_{ci_prefix}_{}_weak_upgrade", self.name);
+            for func in [
+                &mut self.ffi_func_weak_free,
+                &mut self.ffi_func_weak_clone,
+                &mut self.ffi_func_weak_upgrade,
+            ] {
+                func.arguments = vec![FFIArgument {
+                    name: "handle".to_string(),
+                    type_: FFIType::UInt64,
+                }];
+            }
+            self.ffi_func_weak_free.return_type = None;
+            self.ffi_func_weak_clone.return_type = Some(FFIType::UInt64);
+            self.ffi_func_weak_upgrade.return_type = Some(FFIType::RustBuffer);
+        }
+
         for cons in self.constructors.iter_mut() {
             cons.derive_ffi_func(ci_prefix, &self.name);
         }
@@ -199,6 +324,7 @@ impl Hash for Object {
         //    hash value we're trying to calculate here, so excluding it
         //    avoids a weird circular depenendency in the calculation.
         self.name.hash(state);
+        self.imp.hash(state);
         self.constructors.hash(state);
         self.methods.hash(state);
     }
@@ -215,6 +341,7 @@ impl APIConverter<Object> for weedle::InterfaceDefinition<'_> {
             None => Default::default(),
         };
         object.uses_deprecated_threadsafe_attribute = attributes.threadsafe();
+        object.imp = attributes.object_impl();
         // Convert each member into a constructor or method, guarding against duplicate names.
         let mut member_names = HashSet::new();
         for member in &self.members.body {
@@ -232,15 +359,102 @@ impl APIConverter<Object> for weedle::InterfaceDefinition<'_> {
                         bail!("Duplicate interface member name: \"{}\"", method.name())
                     }
                     method.object_name = object.name.clone();
+                    method.object_impl = object.imp;
                     object.methods.push(method);
                 }
                 _ => bail!("no support for interface member type {:?} yet", member),
             }
         }
+        if object.imp == ObjectImpl::Trait {
+            // Instances of a trait interface are made by Rust code returning them, and its
+            // methods need a `self` to dispatch on.
+            if !object.constructors.is_empty() {
+                bail!("Trait interface `{}` can't have constructors", object.name);
+            }
+            if let Some(meth) = object.methods.iter().find(|m| m.is_static()) {
+                bail!(
+                    "Trait interface `{}` can't have the static method `{}`",
+                    object.name,
+                    meth.name
+                );
+            }
+        }
         Ok(object)
     }
 }
 
+/// Convert a generic interface into an [`Object`] for each of its instances.
+///
+/// A generic interface is declared with an `[Instance=Name(Type T, ...)]` attribute for each
+/// instance, giving the types of its type parameters. Each instance is then converted like a
+/// regular interface named after it, with the type parameters defined as these types while doing
+/// so. On the Rust side, the instances are expected to be type aliases like
+/// `type Name = Interface<Type>;`.
+pub(super) fn convert_generic_interface(
+    defn: &weedle::InterfaceDefinition<'_>,
+    ci: &mut ComponentInterface,
+) -> Result<Vec<Object>> {
+    let mut type_params: Option<Vec<String>> = None;
+    let mut objects = Vec::new();
+    let attrs = defn.attributes.iter().flat_map(|attrs| &attrs.body.list);
+    for attr in attrs {
+        let instance = match attr {
+            weedle::attribute::ExtendedAttribute::NamedArgList(instance) => instance,
+            _ => continue,
+        };
+        let args: Vec<Argument> = instance.args.body.list.convert(ci)?;
+        if args.iter().any(|arg| arg.optional || arg.default.is_some()) {
+            bail!(
+                "The type parameters of `{}` can't have default values",
+                defn.identifier.0
+            );
+        }
+        let names: Vec<String> = args.iter().map(|arg| arg.name.clone()).collect();
+        match &type_params {
+            None => type_params = Some(names),
+            Some(params) if *params != names => bail!(
+                "All the instances of `{}` must have the same type parameters",
+                defn.identifier.0
+            ),
+            Some(_) => {}
+        }
+
+        for arg in &args {
+            ci.types.add_type_definition(&arg.name, arg.type_.clone())?;
+        }
+        let object: Result<Object> = defn.convert(ci);
+        for arg in &args {
+            ci.types.remove_type_definition(&arg.name);
+        }
+        let mut object = object?;
+        object.name = instance.rhs_identifier.0.to_string();
+        for meth in object.methods.iter_mut() {
+            meth.object_name = object.name.clone();
+        }
+        objects.push(object);
+    }
+    Ok(objects)
+}
+
+/// How an object is implemented on the Rust side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ObjectImpl {
+    /// A struct, with the instances being `Arc<Struct>`s.
+    Struct,
+    /// A trait, with the instances being `Arc<dyn Trait>`s.
+    Trait,
+}
+
+impl ObjectImpl {
+    /// The Rust type implementing an object named `name`.
+    pub fn rust_name_for(&self, name: &str) -> String {
+        match self {
+            ObjectImpl::Struct => format!("r#{name}"),
+            ObjectImpl::Trait => format!("dyn r#{name}"),
+        }
+    }
+}
+
 // Represents a constructor for an object type.
 //
 // In the FFI, this will be a function that returns a pointer to an instance
@@ -251,6 +465,11 @@ pub struct Constructor {
     pub(super) arguments: Vec<Argument>,
     pub(super) ffi_func: FFIFunction,
     pub(super) attributes: ConstructorAttributes,
+    // The error named by the `[Throws=...]` attribute, resolved by the `ComponentInterface`.
+    pub(super) throws: Option<Type>,
+    // Whether `throws` is the error of the custom types of the arguments rather than named by
+    // `[Throws=...]`, in which case the Rust function doesn't return a `Result`.
+    pub(super) throws_from_arguments: bool,
 }
 
 impl Constructor {
@@ -262,6 +481,10 @@ impl Constructor {
         self.arguments.iter().collect()
     }
 
+    pub fn has_default_arguments(&self) -> bool {
+        self.arguments.iter().any(|a| a.default.is_some())
+    }
+
     pub fn full_arguments(&self) -> Vec<Argument> {
         self.arguments.to_vec()
     }
@@ -271,7 +494,7 @@ impl Constructor {
     }
 
     pub fn throws(&self) -> bool {
-        self.attributes.get_throws_err().is_some()
+        self.throws.is_some()
     }
 
     pub fn throws_name(&self) -> Option<&str> {
@@ -279,9 +502,13 @@ impl Constructor {
     }
 
     pub fn throws_type(&self) -> Option<Type> {
-        self.attributes
-            .get_throws_err()
-            .map(|name| Type::Error(name.to_owned()))
+        self.throws.clone()
+    }
+
+    /// Whether this only throws the errors of the custom types of its arguments, so the Rust
+    /// function itself doesn't return a `Result`.
+    pub fn throws_from_arguments(&self) -> bool {
+        self.throws_from_arguments
     }
 
     pub fn is_primary_constructor(&self) -> bool {
@@ -291,7 +518,7 @@ impl Constructor {
     fn derive_ffi_func(&mut self, ci_prefix: &str, obj_name: &str) {
         self.ffi_func.name = format!("{ci_prefix}_{obj_name}_{}", self.name);
         self.ffi_func.arguments = self.arguments.iter().map(Into::into).collect();
-        self.ffi_func.return_type = Some(FFIType::RustArcPtr(obj_name.to_string()));
+        self.ffi_func.return_type = Some(FFIType::UInt64);
     }
 
     pub fn iter_types(&self) -> TypeIterator<'_> {
@@ -320,6 +547,8 @@ impl Default for Constructor {
             arguments: Vec::new(),
             ffi_func: Default::default(),
             attributes: Default::default(),
+            throws: None,
+            throws_from_arguments: false,
         }
     }
 }
@@ -334,6 +563,8 @@ impl APIConverter<Constructor> for weedle::interface::ConstructorInterfaceMember
             name: String::from(attributes.get_name().unwrap_or("new")),
             arguments: self.args.body.list.convert(ci)?,
             ffi_func: Default::default(),
+            throws: ci.resolve_throws_type(attributes.get_throws_err()),
+            throws_from_arguments: false,
             attributes,
         })
     }
@@ -341,16 +572,26 @@ impl APIConverter<Constructor> for weedle::interface::ConstructorInterfaceMember
 
 // Represents an instance method for an object type.
 //
-// The FFI will represent this as a function whose first/self argument is a
-// `FFIType::RustArcPtr` to the instance.
+// The FFI will represent this as a function whose first/self argument is the
+// `FFIType::UInt64` handle of the instance.
+//
+// A static method is an associated function of the object type instead, which the FFI
+// represents as a plain function.
 #[derive(Debug, Clone)]
 pub struct Method {
     pub(super) name: String,
     pub(super) object_name: String,
+    pub(super) object_impl: ObjectImpl,
     pub(super) return_type: Option<Type>,
     pub(super) arguments: Vec<Argument>,
     pub(super) ffi_func: FFIFunction,
     pub(super) attributes: MethodAttributes,
+    // The error named by the `[Throws=...]` attribute, resolved by the `ComponentInterface`.
+    pub(super) throws: Option<Type>,
+    // Whether `throws` is the error of the custom types of the arguments rather than named by
+    // `[Throws=...]`, in which case the Rust function doesn't return a `Result`.
+    pub(super) throws_from_arguments: bool,
+    pub(super) docstring: Option<String>,
 }
 
 impl Method {
@@ -358,18 +599,32 @@ impl Method {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
     pub fn arguments(&self) -> Vec<&Argument> {
         self.arguments.iter().collect()
     }
 
+    pub fn has_default_arguments(&self) -> bool {
+        self.arguments.iter().any(|a| a.default.is_some())
+    }
+
     // Methods have a special implicit first argument for the object instance,
     // hence `arguments` and `full_arguments` are different.
     pub fn full_arguments(&self) -> Vec<Argument> {
+        if self.is_static() {
+            return self.arguments.to_vec();
+        }
         vec![Argument {
             name: "ptr".to_string(),
             // TODO: ideally we'd get this via `ci.resolve_type_expression` so that it
             // is contained in the proper `TypeUniverse`, but this works for now.
-            type_: Type::Object(self.object_name.clone()),
+            type_: Type::Object {
+                name: self.object_name.clone(),
+                imp: self.object_impl,
+            },
             by_ref: !self.attributes.get_self_by_arc(),
             optional: false,
             default: None,
@@ -388,7 +643,7 @@ impl Method {
     }
 
     pub fn throws(&self) -> bool {
-        self.attributes.get_throws_err().is_some()
+        self.throws.is_some()
     }
 
     pub fn throws_name(&self) -> Option<&str> {
@@ -396,15 +651,23 @@ impl Method {
     }
 
     pub fn throws_type(&self) -> Option<Type> {
-        self.attributes
-            .get_throws_err()
-            .map(|name| Type::Error(name.to_owned()))
+        self.throws.clone()
+    }
+
+    /// Whether this only throws the errors of the custom types of its arguments, so the Rust
+    /// function itself doesn't return a `Result`.
+    pub fn throws_from_arguments(&self) -> bool {
+        self.throws_from_arguments
     }
 
     pub fn takes_self_by_arc(&self) -> bool {
         self.attributes.get_self_by_arc()
     }
 
+    pub fn is_static(&self) -> bool {
+        self.attributes.is_static()
+    }
+
     pub fn derive_ffi_func(&mut self, ci_prefix: &str, obj_prefix: &str) -> Result<()> {
         // The name is already set if the function is defined through a proc-macro invocation
         // rather than in UDL. Don't overwrite it in that case.
@@ -429,23 +692,24 @@ impl Method {
 
 impl From<uniffi_meta::MethodMetadata> for Method {
     fn from(meta: uniffi_meta::MethodMetadata) -> Self {
-        let ffi_name = meta.ffi_symbol_name();
-
         let return_type = meta.return_type.map(|out| convert_type(&out));
         let arguments = meta.inputs.into_iter().map(Into::into).collect();
 
-        let ffi_func = FFIFunction {
-            name: ffi_name,
-            ..FFIFunction::default()
-        };
+        // The FFI function is named by `ComponentInterface::add_method_meta`, which knows the
+        // `ffi_prefix` of the configuration.
+        let ffi_func = FFIFunction::default();
 
         Self {
             name: meta.name,
             object_name: meta.self_name,
+            object_impl: ObjectImpl::Struct,
             arguments,
             return_type,
             ffi_func,
             attributes: Default::default(),
+            throws: None,
+            throws_from_arguments: false,
+            docstring: meta.docstring,
         }
     }
 }
@@ -460,6 +724,7 @@ impl Hash for Method {
         //    avoids a weird circular depenendency in the calculation.
         self.name.hash(state);
         self.object_name.hash(state);
+        self.object_impl.hash(state);
         self.arguments.hash(state);
         self.return_type.hash(state);
         self.attributes.hash(state);
@@ -475,6 +740,7 @@ impl APIConverter<Method> for weedle::interface::OperationInterfaceMember<'_> {
             bail!("method modifiers are not supported")
         }
         let return_type = ci.resolve_return_type_expression(&self.return_type)?;
+        let attributes = MethodAttributes::try_from(self.attributes.as_ref())?;
         Ok(Method {
             name: match self.identifier {
                 None => bail!("anonymous methods are not supported {:?}", self),
@@ -486,12 +752,16 @@ impl APIConverter<Method> for weedle::interface::OperationInterfaceMember<'_> {
                     name
                 }
             },
-            // We don't know the name of the containing `Object` at this point, fill it in later.
+            // We don't know the containing `Object` at this point, fill it in later.
             object_name: Default::default(),
+            object_impl: ObjectImpl::Struct,
             arguments: self.args.body.list.convert(ci)?,
             return_type,
             ffi_func: Default::default(),
-            attributes: MethodAttributes::try_from(self.attributes.as_ref())?,
+            throws: ci.resolve_throws_type(attributes.get_throws_err()),
+            throws_from_arguments: false,
+            attributes,
+            docstring: None,
         })
     }
 }
@@ -582,6 +852,210 @@ mod test {
         assert_eq!(cons.ffi_func.arguments.len(), 0);
     }
 
+    #[test]
+    fn test_static_methods() {
+        const UDL: &str = r#"
+            namespace test{};
+            interface Testing {
+                constructor();
+                u32 count();
+                [Static]
+                Testing from_parts(string name, u32 count);
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let obj = ci.get_object_definition("Testing").unwrap();
+        assert_eq!(obj.methods().len(), 1);
+        assert_eq!(obj.static_methods().len(), 1);
+        assert!(obj.has_method("count"));
+        assert!(obj.has_method("from_parts"));
+        assert!(!obj.has_method("destroy"));
+
+        let meth = obj.methods()[0];
+        assert_eq!(meth.name(), "count");
+        assert!(!meth.is_static());
+        assert_eq!(meth.ffi_func().arguments().len(), 1);
+
+        let meth = obj.static_methods()[0];
+        assert_eq!(meth.name(), "from_parts");
+        assert!(meth.is_static());
+        assert_eq!(meth.full_arguments().len(), 2);
+        assert_eq!(meth.ffi_func().arguments().len(), 2);
+        assert_eq!(meth.ffi_func().arguments()[0].name(), "name");
+    }
+
+    #[test]
+    fn test_trait_interfaces() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Trait]
+            interface Testing {
+                u32 count();
+            };
+            interface Other {};
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let obj = ci.get_object_definition("Testing").unwrap();
+        assert_eq!(obj.imp(), ObjectImpl::Trait);
+        assert_eq!(obj.rust_name(), "dyn r#Testing");
+        assert_eq!(
+            obj.type_(),
+            Type::Object {
+                name: "Testing".into(),
+                imp: ObjectImpl::Trait
+            }
+        );
+        let meth = obj.get_method("count");
+        assert_eq!(meth.full_arguments()[0].type_(), &obj.type_());
+
+        let obj = ci.get_object_definition("Other").unwrap();
+        assert_eq!(obj.imp(), ObjectImpl::Struct);
+        assert_eq!(obj.rust_name(), "r#Other");
+    }
+
+    #[test]
+    fn test_weak_references() {
+        const UDL: &str = r#"
+            namespace test{};
+            interface Node {
+                weak<Node>? parent();
+            };
+            interface Other {};
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        let obj = ci.get_object_definition("Node").unwrap();
+        assert!(obj.is_referenced_weakly());
+        assert!(obj
+            .ffi_weak_upgrade()
+            .name()
+            .ends_with("_Node_weak_upgrade"));
+        assert_eq!(
+            obj.ffi_weak_upgrade().return_type(),
+            Some(&FFIType::RustBuffer)
+        );
+        assert_eq!(obj.iter_ffi_function_definitions().count(), 6);
+
+        let obj = ci.get_object_definition("Other").unwrap();
+        assert!(!obj.is_referenced_weakly());
+        assert_eq!(obj.iter_ffi_function_definitions().count(), 2);
+
+        const UDL2: &str = r#"
+            namespace test{};
+            [Trait]
+            interface Node {
+                weak<Node> parent();
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "weak references to the [Trait] interface `Node` are not supported yet"
+        );
+    }
+
+    #[test]
+    fn test_trait_interfaces_cant_be_constructed() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Trait]
+            interface Testing {
+                constructor();
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Trait interface `Testing` can't have constructors"
+        );
+
+        const UDL2: &str = r#"
+            namespace test{};
+            [Trait]
+            interface Testing {
+                [Static]
+                Testing make();
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Trait interface `Testing` can't have the static method `make`"
+        );
+    }
+
+    #[test]
+    fn test_generic_interfaces() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Instance=StringCache(string T), Instance=CountCache(u32 T)]
+            interface Cache {
+                constructor();
+                T? get(string key);
+                void set(string key, T value);
+            };
+            interface Other {
+                void take(StringCache cache);
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert!(ci.get_object_definition("Cache").is_none());
+        assert_eq!(ci.object_definitions().len(), 3);
+
+        let obj = ci.get_object_definition("StringCache").unwrap();
+        let meth = obj.get_method("get");
+        assert_eq!(
+            meth.return_type(),
+            Some(&Type::Optional(Box::new(Type::String)))
+        );
+        assert_eq!(meth.full_arguments()[0].type_(), &obj.type_());
+        assert_eq!(
+            meth.ffi_func().name(),
+            format!("{}_StringCache_get", ci.ffi_namespace())
+        );
+
+        let obj = ci.get_object_definition("CountCache").unwrap();
+        let meth = obj.get_method("set");
+        assert_eq!(meth.arguments()[1].type_(), &Type::UInt32);
+
+        let obj = ci.get_object_definition("Other").unwrap();
+        assert_eq!(
+            obj.get_method("take").arguments()[0].type_(),
+            &Type::Object {
+                name: "StringCache".into(),
+                imp: ObjectImpl::Struct
+            }
+        );
+    }
+
+    #[test]
+    fn test_generic_interfaces_type_parameters() {
+        const UDL: &str = r#"
+            namespace test{};
+            [Instance=StringCache(string T), Instance=CountCache(u32 T)]
+            interface Cache {
+                void set(string key, T value);
+            };
+            interface Other {
+                void take(T value);
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL).unwrap_err();
+        assert_eq!(err.to_string(), "unknown type reference: T");
+
+        const UDL2: &str = r#"
+            namespace test{};
+            [Instance=StringCache(string T), Instance=CountCache(u32 U)]
+            interface Cache {
+                void set(string key, T value);
+            };
+        "#;
+        let err = ComponentInterface::from_webidl(UDL2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "All the instances of `Cache` must have the same type parameters"
+        );
+    }
+
     #[test]
     fn test_the_name_new_is_reserved_for_constructors() {
         const UDL: &str = r#"
@@ -46,6 +46,7 @@
 
 use anyhow::{bail, Result};
 
+use super::attributes::FieldAttributes;
 use super::types::{Type, TypeIterator};
 use super::{
     convert_type,
@@ -58,10 +59,18 @@ use super::{APIConverter, ComponentInterface};
 /// In the FFI these are represented as a byte buffer, which one side explicitly
 /// serializes the data into and the other serializes it out of. So I guess they're
 /// kind of like "pass by clone" values.
+///
+/// The records declared in the UDL whose fields are all numbers or booleans are passed as
+/// `#[repr(C)]` structs instead, see [`Record::is_ffi_struct`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Record {
     pub(super) name: String,
     pub(super) fields: Vec<Field>,
+    // Whether the record refers back to itself, resolved by the `ComponentInterface`.
+    pub(super) recursive: bool,
+    // Whether the record is passed as a C struct rather than serialized.
+    pub(super) ffi_struct: bool,
+    pub(super) docstring: Option<String>,
 }
 
 impl Record {
@@ -69,6 +78,10 @@ impl Record {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
     pub fn type_(&self) -> Type {
         // *sigh* at the clone here, the relationship between a ComponentInterace
         // and its contained types could use a bit of a cleanup.
@@ -79,6 +92,25 @@ impl Record {
         &self.fields
     }
 
+    /// Whether the record contains itself without going through a sequence or a map, like a
+    /// tree node holding its optional parent, so some of its fields are boxed.
+    pub fn is_recursive(&self) -> bool {
+        self.recursive
+    }
+
+    /// Whether the record is passed by value as a `#[repr(C)]` struct of its fields as arguments
+    /// and return values, rather than serialized into a `RustBuffer`.
+    ///
+    /// That's the case of the records declared in the UDL with at least one field, whose fields
+    /// are all numbers or booleans. Records defined through proc-macros are always serialized.
+    pub fn is_ffi_struct(&self) -> bool {
+        self.ffi_struct
+    }
+
+    pub fn has_default_fields(&self) -> bool {
+        self.fields.iter().any(|f| f.default.is_some())
+    }
+
     pub fn iter_types(&self) -> TypeIterator<'_> {
         Box::new(self.fields.iter().flat_map(Field::iter_types))
     }
@@ -89,6 +121,9 @@ impl From<uniffi_meta::RecordMetadata> for Record {
         Self {
             name: meta.name,
             fields: meta.fields.into_iter().map(Into::into).collect(),
+            recursive: false,
+            ffi_struct: false,
+            docstring: meta.docstring,
         }
     }
 }
@@ -101,9 +136,30 @@ impl APIConverter<Record> for weedle::DictionaryDefinition<'_> {
         if self.inheritance.is_some() {
             bail!("dictionary inheritence is not supported");
         }
+        let fields: Vec<Field> = self.members.body.convert(ci)?;
+        let ffi_struct = !fields.is_empty()
+            && fields.iter().all(|field| {
+                matches!(
+                    field.type_,
+                    Type::UInt8
+                        | Type::Int8
+                        | Type::UInt16
+                        | Type::Int16
+                        | Type::UInt32
+                        | Type::Int32
+                        | Type::UInt64
+                        | Type::Int64
+                        | Type::Float32
+                        | Type::Float64
+                        | Type::Boolean
+                )
+            });
         Ok(Record {
             name: self.identifier.0.to_string(),
-            fields: self.members.body.convert(ci)?,
+            fields,
+            recursive: false,
+            ffi_struct,
+            docstring: None,
         })
     }
 }
@@ -112,15 +168,30 @@ impl APIConverter<Record> for weedle::DictionaryDefinition<'_> {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Field {
     pub(super) name: String,
+    // The name given by a `[Rename=...]` attribute, which the bindings use instead of `name`.
+    pub(super) rename: Option<String>,
     pub(super) type_: Type,
     pub(super) default: Option<Literal>,
+    // Whether the field refers back to its record or enum, resolved by the `ComponentInterface`.
+    pub(super) boxed: bool,
+    pub(super) docstring: Option<String>,
 }
 
 impl Field {
+    /// The name of the field in the foreign language bindings.
     pub fn name(&self) -> &str {
+        self.rename.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The name of the field in Rust, which differs from `name()` when it's renamed.
+    pub fn rust_name(&self) -> &str {
         &self.name
     }
 
+    pub fn docstring(&self) -> Option<&str> {
+        self.docstring.as_deref()
+    }
+
     pub fn type_(&self) -> &Type {
         &self.type_
     }
@@ -129,6 +200,12 @@ impl Field {
         self.default.as_ref()
     }
 
+    /// Whether the field refers back to its record or enum without going through a sequence or
+    /// a map, so it's a `Box<T>` in Rust, or an `Option<Box<T>>` for an optional field.
+    pub fn is_boxed(&self) -> bool {
+        self.boxed
+    }
+
     pub fn iter_types(&self) -> TypeIterator<'_> {
         self.type_.iter_types()
     }
@@ -138,17 +215,18 @@ impl From<uniffi_meta::FieldMetadata> for Field {
     fn from(meta: uniffi_meta::FieldMetadata) -> Self {
         Self {
             name: meta.name,
+            rename: meta.rename,
             type_: convert_type(&meta.ty),
             default: None,
+            boxed: false,
+            docstring: meta.docstring,
         }
     }
 }
 
 impl APIConverter<Field> for weedle::dictionary::DictionaryMember<'_> {
     fn convert(&self, ci: &mut ComponentInterface) -> Result<Field> {
-        if self.attributes.is_some() {
-            bail!("dictionary member attributes are not supported yet");
-        }
+        let attributes = FieldAttributes::try_from(self.attributes.as_ref())?;
         let type_ = ci.resolve_type_expression(&self.type_)?;
         let default = match self.default {
             None => None,
@@ -156,14 +234,18 @@ impl APIConverter<Field> for weedle::dictionary::DictionaryMember<'_> {
         };
         Ok(Field {
             name: self.identifier.0.to_string(),
+            rename: attributes.get_rename().map(ToOwned::to_owned),
             type_,
             default,
+            boxed: false,
+            docstring: None,
         })
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::super::ffi::FFIType;
     use super::super::literal::Radix;
     use super::*;
 
@@ -239,4 +321,39 @@ mod test {
             .any(|t| t.canonical_name() == "Optionalstring"));
         assert!(ci.iter_types().any(|t| t.canonical_name() == "TypeTesting"));
     }
+
+    #[test]
+    fn test_records_of_numbers_are_ffi_structs() {
+        const UDL: &str = r#"
+            namespace test{
+                Point translate(Point point, Named named);
+            };
+            dictionary Point {
+                f32 x;
+                f32 y;
+                boolean visible;
+            };
+            dictionary Named {
+                string name;
+                Point point;
+            };
+            dictionary Empty {};
+        "#;
+        let ci = ComponentInterface::from_webidl(UDL).unwrap();
+        assert!(ci.get_record_definition("Point").unwrap().is_ffi_struct());
+        assert!(!ci.get_record_definition("Named").unwrap().is_ffi_struct());
+        assert!(!ci.get_record_definition("Empty").unwrap().is_ffi_struct());
+
+        let func = ci.get_function_definition("translate").unwrap();
+        let ffi_func = func.ffi_func();
+        assert_eq!(
+            ffi_func.arguments()[0].type_(),
+            FFIType::Struct("Point".into())
+        );
+        assert_eq!(ffi_func.arguments()[1].type_(), FFIType::RustBuffer);
+        assert_eq!(
+            ffi_func.return_type(),
+            Some(&FFIType::Struct("Point".into()))
+        );
+    }
 }
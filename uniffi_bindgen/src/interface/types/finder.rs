@@ -50,6 +50,7 @@ impl TypeFinder for weedle::Definition<'_> {
             weedle::Definition::Enum(d) => d.add_type_definitions_to(types),
             weedle::Definition::Typedef(d) => d.add_type_definitions_to(types),
             weedle::Definition::CallbackInterface(d) => d.add_type_definitions_to(types),
+            weedle::Definition::Callback(d) => d.add_type_definitions_to(types),
             _ => Ok(()),
         }
     }
@@ -58,13 +59,23 @@ impl TypeFinder for weedle::Definition<'_> {
 impl TypeFinder for weedle::InterfaceDefinition<'_> {
     fn add_type_definitions_to(&self, types: &mut TypeUniverse) -> Result<()> {
         let name = self.identifier.0.to_string();
+        let attrs = InterfaceAttributes::try_from(self.attributes.as_ref())?;
         // Some enum types are defined using an `interface` with a special attribute.
-        if InterfaceAttributes::try_from(self.attributes.as_ref())?.contains_enum_attr() {
+        if attrs.contains_enum_attr() {
             types.add_type_definition(self.identifier.0, Type::Enum(name))
-        } else if InterfaceAttributes::try_from(self.attributes.as_ref())?.contains_error_attr() {
+        } else if attrs.contains_error_attr() {
             types.add_type_definition(self.identifier.0, Type::Error(name))
+        } else if !attrs.instances().is_empty() {
+            // A generic interface only defines its instances.
+            for instance in attrs.instances() {
+                let name = instance.to_string();
+                let imp = attrs.object_impl();
+                types.add_type_definition(instance, Type::Object { name, imp })?;
+            }
+            Ok(())
         } else {
-            types.add_type_definition(self.identifier.0, Type::Object(name))
+            let imp = attrs.object_impl();
+            types.add_type_definition(self.identifier.0, Type::Object { name, imp })
         }
     }
 }
@@ -116,6 +127,7 @@ impl TypeFinder for weedle::TypedefDefinition<'_> {
                 Type::External {
                     name: name.to_string(),
                     crate_name: attrs.get_crate_name(),
+                    kind: attrs.get_external_kind(),
                 },
             )
         }
@@ -132,8 +144,18 @@ impl TypeFinder for weedle::CallbackInterfaceDefinition<'_> {
     }
 }
 
+// Callback functions are callback interfaces with a single method.
+impl TypeFinder for weedle::CallbackDefinition<'_> {
+    fn add_type_definitions_to(&self, types: &mut TypeUniverse) -> Result<()> {
+        let name = self.identifier.0.to_string();
+        types.add_type_definition(self.identifier.0, Type::CallbackInterface(name))
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use super::super::super::ObjectImpl;
+    use super::super::ExternalKind;
     use super::*;
 
     // A helper to take valid UDL and a closure to check what's in it.
@@ -200,7 +222,7 @@ mod test {
         "#,
             |types| {
                 assert!(
-                    matches!(types.get_type_definition("TestObject").unwrap(), Type::Object(nm) if nm == "TestObject")
+                    matches!(types.get_type_definition("TestObject").unwrap(), Type::Object { name, imp: ObjectImpl::Struct } if name == "TestObject")
                 );
             },
         );
@@ -210,14 +232,28 @@ mod test {
             [External="crate-name"]
             typedef extern ExternalType;
 
+            [ExternalError="crate-name"]
+            typedef extern ExternalError;
+
+            [ExternalCallbackInterface="crate-name"]
+            typedef extern ExternalCallbackInterface;
+
             [Custom]
             typedef string CustomType;
         "#,
             |types| {
                 assert!(
-                    matches!(types.get_type_definition("ExternalType").unwrap(), Type::External { name, crate_name }
+                    matches!(types.get_type_definition("ExternalType").unwrap(), Type::External { name, crate_name, kind: ExternalKind::DataClass }
                                                                                  if name == "ExternalType" && crate_name == "crate-name")
                 );
+                assert!(
+                    matches!(types.get_type_definition("ExternalError").unwrap(), Type::External { name, kind: ExternalKind::Error, .. }
+                                                                                  if name == "ExternalError")
+                );
+                assert!(
+                    matches!(types.get_type_definition("ExternalCallbackInterface").unwrap(), Type::External { name, kind: ExternalKind::CallbackInterface, .. }
+                                                                                              if name == "ExternalCallbackInterface")
+                );
                 assert!(
                     matches!(types.get_type_definition("CustomType").unwrap(), Type::Custom { name, builtin }
                                                                                      if name == "CustomType" && builtin == Box::new(Type::String))
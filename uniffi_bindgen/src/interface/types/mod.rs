@@ -27,6 +27,7 @@ use anyhow::{bail, Result};
 use heck::ToUpperCamelCase;
 
 use super::ffi::FFIType;
+use super::object::ObjectImpl;
 
 mod finder;
 pub(super) use finder::TypeFinder;
@@ -50,11 +51,23 @@ pub enum Type {
     Float32,
     Float64,
     Boolean,
+    Char,
     String,
     Timestamp,
     Duration,
+    OffsetDateTime,
+    BigInt,
+    Decimal,
+    Url,
+    Uuid,
+    Json,
+    // A view of bytes owned by Rust, which can only be returned by functions and methods.
+    SharedSlice,
     // Types defined in the component API, each of which has a string name.
-    Object(String),
+    Object {
+        name: String,
+        imp: ObjectImpl,
+    },
     Record(String),
     Enum(String),
     Error(String),
@@ -62,14 +75,31 @@ pub enum Type {
     // Structurally recursive types.
     Optional(Box<Type>),
     Sequence(Box<Type>),
+    Set(Box<Type>),
     Map(Box<Type>, Box<Type>),
+    Tuple(Vec<Type>),
+    // The success and error types of a result, the latter being an `Error`.
+    Result(Box<Type>, Box<Type>),
+    // A weak reference to an object, which may be gone when the foreign-language code upgrades it.
+    Weak(Box<Type>),
+    // A cursor over values produced lazily by Rust, which can only be returned by functions and methods.
+    Iterator(Box<Type>),
     // An FfiConverter we `use` from an external crate
-    External { name: String, crate_name: String },
+    External {
+        name: String,
+        crate_name: String,
+        kind: ExternalKind,
+    },
     // Custom type on the scaffolding side
-    Custom { name: String, builtin: Box<Type> },
+    Custom {
+        name: String,
+        builtin: Box<Type>,
+    },
     // An unresolved user-defined type inside a proc-macro exported function
     // signature. Must be replaced by another type before bindings generation.
-    Unresolved { name: String },
+    Unresolved {
+        name: String,
+    },
 }
 
 impl Type {
@@ -94,19 +124,27 @@ impl Type {
             Type::Float64 => "f64".into(),
             Type::String => "string".into(),
             Type::Boolean => "bool".into(),
+            Type::Char => "char".into(),
             // API defined types.
             // Note that these all get unique names, and the parser ensures that the names do not
             // conflict with a builtin type. We add a prefix to the name to guard against pathological
             // cases like a record named `SequenceRecord` interfering with `sequence<Record>`.
             // However, types that support importing all end up with the same prefix of "Type", so
             // that the import handling code knows how to find the remote reference.
-            Type::Object(nm) => format!("Type{nm}"),
+            Type::Object { name, .. } => format!("Type{name}"),
             Type::Error(nm) => format!("Type{nm}"),
             Type::Enum(nm) => format!("Type{nm}"),
             Type::Record(nm) => format!("Type{nm}"),
             Type::CallbackInterface(nm) => format!("CallbackInterface{nm}"),
             Type::Timestamp => "Timestamp".into(),
             Type::Duration => "Duration".into(),
+            Type::OffsetDateTime => "OffsetDateTime".into(),
+            Type::BigInt => "BigInt".into(),
+            Type::Decimal => "Decimal".into(),
+            Type::Url => "Url".into(),
+            Type::Uuid => "Uuid".into(),
+            Type::Json => "Json".into(),
+            Type::SharedSlice => "SharedSlice".into(),
             // Recursive types.
             // These add a prefix to the name of the underlying type.
             // The component API definition cannot give names to recursive types, so as long as the
@@ -114,12 +152,35 @@ impl Type {
             // acccidentally generating name collisions.
             Type::Optional(t) => format!("Optional{}", t.canonical_name()),
             Type::Sequence(t) => format!("Sequence{}", t.canonical_name()),
+            Type::Set(t) => format!("Set{}", t.canonical_name()),
             Type::Map(k, v) => format!(
                 "Map{}{}",
                 k.canonical_name().to_upper_camel_case(),
                 v.canonical_name().to_upper_camel_case()
             ),
+            // The arity is part of the name, so that nested tuples can't collide.
+            Type::Tuple(elements) => format!(
+                "Tuple{}{}",
+                elements.len(),
+                elements
+                    .iter()
+                    .map(|t| t.canonical_name().to_upper_camel_case())
+                    .collect::<String>()
+            ),
+            Type::Result(ok, err) => format!(
+                "Result{}{}",
+                ok.canonical_name().to_upper_camel_case(),
+                err.canonical_name().to_upper_camel_case()
+            ),
+            Type::Weak(t) => format!("Weak{}", t.canonical_name()),
+            Type::Iterator(t) => format!("Iterator{}", t.canonical_name()),
             // A type that exists externally.
+            // External callback interfaces keep the prefix they have in the crate defining them.
+            Type::External {
+                name,
+                kind: ExternalKind::CallbackInterface,
+                ..
+            } => format!("CallbackInterface{name}"),
             Type::External { name, .. } | Type::Custom { name, .. } => format!("Type{name}"),
             Type::Unresolved { name } => {
                 unreachable!("Type `{name}` must be resolved before calling canonical_name")
@@ -133,14 +194,33 @@ impl Type {
 
     pub fn iter_types(&self) -> TypeIterator<'_> {
         let nested_types = match self {
-            Type::Optional(t) | Type::Sequence(t) => t.iter_types(),
-            Type::Map(k, v) => Box::new(k.iter_types().chain(v.iter_types())),
+            Type::Optional(t)
+            | Type::Sequence(t)
+            | Type::Set(t)
+            | Type::Weak(t)
+            | Type::Iterator(t) => t.iter_types(),
+            Type::Map(k, v) | Type::Result(k, v) => Box::new(k.iter_types().chain(v.iter_types())),
+            Type::Tuple(elements) => Box::new(elements.iter().flat_map(Type::iter_types)),
             _ => Box::new(iter::empty()),
         };
         Box::new(std::iter::once(self).chain(nested_types))
     }
 }
 
+/// The kind of thing an external type is in the crate defining it.
+///
+/// It tells how values of the type cross the FFI, and where the bindings find their
+/// `FfiConverter`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum ExternalKind {
+    /// A record, enum or custom type, serialized into a `RustBuffer`.
+    DataClass,
+    /// An error, serialized into a `RustBuffer` and thrown by the functions using it.
+    Error,
+    /// A callback interface, passed as a handle into the callbacks of its crate.
+    CallbackInterface,
+}
+
 /// When passing data across the FFI, each `Type` value will be lowered into a corresponding
 /// `FFIType` value. This conversion tells you which one.
 ///
@@ -162,22 +242,43 @@ impl From<&Type> for FFIType {
             Type::Float64 => FFIType::Float64,
             // Booleans lower into an Int8, to work around a bug in JNA.
             Type::Boolean => FFIType::Int8,
+            // Chars are Unicode scalar values, which fit in 21 bits.
+            Type::Char => FFIType::UInt32,
             // Strings are always owned rust values.
             // We might add a separate type for borrowed strings in future.
             Type::String => FFIType::RustBuffer,
-            // Objects are pointers to an Arc<>
-            Type::Object(name) => FFIType::RustArcPtr(name.to_owned()),
+            // Objects are handles to an Arc<>, checked whenever they're used.
+            Type::Object { .. } => FFIType::UInt64,
+            // Weak references are handles to a Weak<>, checked like the ones of the objects.
+            Type::Weak(_) => FFIType::UInt64,
+            // Iterators are handles to the cursor over their items, checked like the ones of the objects.
+            Type::Iterator(_) => FFIType::UInt64,
+            // Shared slices are handles to the `uniffi::SharedSlice`, checked like the ones of the objects.
+            Type::SharedSlice => FFIType::UInt64,
             // Callback interfaces are passed as opaque integer handles.
-            Type::CallbackInterface(_) => FFIType::UInt64,
+            Type::CallbackInterface(_)
+            | Type::External {
+                kind: ExternalKind::CallbackInterface,
+                ..
+            } => FFIType::UInt64,
             // Other types are serialized into a bytebuffer and deserialized on the other side.
             Type::Enum(_)
             | Type::Error(_)
             | Type::Record(_)
             | Type::Optional(_)
             | Type::Sequence(_)
+            | Type::Set(_)
             | Type::Map(_, _)
+            | Type::Tuple(_)
+            | Type::Result(_, _)
             | Type::Timestamp
             | Type::Duration
+            | Type::OffsetDateTime
+            | Type::BigInt
+            | Type::Decimal
+            | Type::Url
+            | Type::Uuid
+            | Type::Json
             | Type::External { .. } => FFIType::RustBuffer,
             Type::Custom { builtin, .. } => FFIType::from(builtin.as_ref()),
             Type::Unresolved { name } => {
@@ -259,11 +360,24 @@ impl TypeUniverse {
         }
     }
 
+    /// Remove the definition of a named [Type], as added by `add_type_definition()`.
+    ///
+    /// This is used for the type parameters of generic interfaces, which are only
+    /// defined while converting each of their instances.
+    pub(super) fn remove_type_definition(&mut self, name: &str) {
+        self.type_definitions.remove(name);
+    }
+
     /// Get the [Type] corresponding to a given name, if any.
     pub(super) fn get_type_definition(&self, name: &str) -> Option<Type> {
         self.type_definitions.get(name).cloned()
     }
 
+    /// Iterate over the names of the defined types, to suggest them in errors.
+    pub(super) fn type_definition_names(&self) -> impl Iterator<Item = &str> {
+        self.type_definitions.keys().map(String::as_str)
+    }
+
     /// Get the [Type] corresponding to a given WebIDL type node.
     ///
     /// If the node is a structural type (e.g. a sequence) then this will also add
@@ -290,10 +404,20 @@ impl TypeUniverse {
             match type_ {
                 Type::Optional(t) => self.add_known_type(t)?,
                 Type::Sequence(t) => self.add_known_type(t)?,
-                Type::Map(k, v) => {
+                Type::Set(t) => self.add_known_type(t)?,
+                // Upgrading a weak reference gives an optional object.
+                Type::Weak(t) => self.add_known_type(&Type::Optional(t.clone()))?,
+                // The next item of an iterator is optional, `None` marking its end.
+                Type::Iterator(t) => self.add_known_type(&Type::Optional(t.clone()))?,
+                Type::Map(k, v) | Type::Result(k, v) => {
                     self.add_known_type(k)?;
                     self.add_known_type(v)?;
                 }
+                Type::Tuple(elements) => {
+                    for t in elements {
+                        self.add_known_type(t)?;
+                    }
+                }
                 _ => {}
             }
         }
@@ -323,9 +447,10 @@ mod test_type {
         assert_eq!(Type::UInt8.canonical_name(), "u8");
         assert_eq!(Type::String.canonical_name(), "string");
         assert_eq!(
-            Type::Optional(Box::new(Type::Sequence(Box::new(Type::Object(
-                "Example".into()
-            )))))
+            Type::Optional(Box::new(Type::Sequence(Box::new(Type::Object {
+                name: "Example".into(),
+                imp: ObjectImpl::Struct,
+            }))))
             .canonical_name(),
             "OptionalSequenceTypeExample"
         );
@@ -14,7 +14,8 @@
 
 use anyhow::{bail, Result};
 
-use super::{Type, TypeUniverse};
+use super::super::diagnostics;
+use super::{ObjectImpl, Type, TypeUniverse};
 
 /// Trait to help resolving an UDL type node to a [`Type`].
 ///
@@ -61,6 +62,11 @@ impl TypeResolver for weedle::types::NonAnyType<'_> {
             weedle::types::NonAnyType::FloatingPoint(t) => t.resolve_type_expression(types),
             weedle::types::NonAnyType::Sequence(t) => t.resolve_type_expression(types),
             weedle::types::NonAnyType::RecordType(t) => t.resolve_type_expression(types),
+            weedle::types::NonAnyType::TupleType(t) => t.resolve_type_expression(types),
+            weedle::types::NonAnyType::SetType(t) => t.resolve_type_expression(types),
+            weedle::types::NonAnyType::ResultType(t) => t.resolve_type_expression(types),
+            weedle::types::NonAnyType::WeakType(t) => t.resolve_type_expression(types),
+            weedle::types::NonAnyType::IteratorType(t) => t.resolve_type_expression(types),
             _ => bail!("no support for type {:?}", self),
         }
     }
@@ -125,6 +131,61 @@ impl TypeResolver for weedle::types::SequenceType<'_> {
     }
 }
 
+impl TypeResolver for weedle::types::SetType<'_> {
+    fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
+        let t = self.generics.body.as_ref().resolve_type_expression(types)?;
+        let ty = Type::Set(Box::new(t));
+        types.add_known_type(&ty)?;
+        Ok(ty)
+    }
+}
+
+impl TypeResolver for weedle::types::ResultType<'_> {
+    fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
+        let ok_type = self.generics.body.0.resolve_type_expression(types)?;
+        let err_type = self.generics.body.2.resolve_type_expression(types)?;
+        // The error is lifted and lowered like the errors thrown by functions.
+        if !matches!(err_type, Type::Error(_)) {
+            bail!("the error type of a result must be an [Error] enum, not {err_type:?}");
+        }
+        let ty = Type::Result(Box::new(ok_type), Box::new(err_type));
+        types.add_known_type(&ty)?;
+        Ok(ty)
+    }
+}
+
+impl TypeResolver for weedle::types::WeakType<'_> {
+    fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
+        let t = self.generics.body.as_ref().resolve_type_expression(types)?;
+        match &t {
+            Type::Object {
+                imp: ObjectImpl::Struct,
+                ..
+            } => {}
+            // `Weak<dyn Trait>` is a fat pointer, like `Arc<dyn Trait>`, and isn't supported yet.
+            Type::Object { name, .. } => {
+                bail!("weak references to the [Trait] interface `{name}` are not supported yet")
+            }
+            _ => bail!("weak references must point to an interface, not {t:?}"),
+        }
+        let ty = Type::Weak(Box::new(t));
+        types.add_known_type(&ty)?;
+        Ok(ty)
+    }
+}
+
+impl TypeResolver for weedle::types::IteratorType<'_> {
+    fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
+        let t = self.generics.body.as_ref().resolve_type_expression(types)?;
+        if matches!(t, Type::Optional(_)) {
+            bail!("iterators can't have optional items, as `None` marks their end");
+        }
+        let ty = Type::Iterator(Box::new(t));
+        types.add_known_type(&ty)?;
+        Ok(ty)
+    }
+}
+
 impl TypeResolver for weedle::types::RecordKeyType<'_> {
     fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
         use weedle::types::RecordKeyType::*;
@@ -152,6 +213,23 @@ impl TypeResolver for weedle::types::RecordType<'_> {
     }
 }
 
+impl TypeResolver for weedle::types::TupleType<'_> {
+    fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
+        let elements = &self.generics.body.list;
+        // Kotlin has no tuple type, tuples map to its `Pair` and `Triple` classes.
+        if !(2..=3).contains(&elements.len()) {
+            bail!("tuples must have 2 or 3 elements, not {}", elements.len());
+        }
+        let elements = elements
+            .iter()
+            .map(|t| t.resolve_type_expression(types))
+            .collect::<Result<Vec<_>>>()?;
+        let tuple = Type::Tuple(elements);
+        types.add_known_type(&tuple)?;
+        Ok(tuple)
+    }
+}
+
 impl TypeResolver for weedle::common::Identifier<'_> {
     fn resolve_type_expression(&self, types: &mut TypeUniverse) -> Result<Type> {
         match resolve_builtin_type(self.0) {
@@ -164,7 +242,11 @@ impl TypeResolver for weedle::common::Identifier<'_> {
                     types.add_known_type(&type_)?;
                     Ok(type_)
                 }
-                None => bail!("unknown type reference: {}", self.0),
+                None => Err(diagnostics::error_with_suggestion(
+                    self.0,
+                    format!("unknown type reference: {}", self.0),
+                    diagnostics::suggest_type(self.0, types.type_definition_names()),
+                )),
             },
         }
     }
@@ -204,6 +286,7 @@ impl TypeResolver for weedle::types::DoubleType {
 pub(in super::super) fn resolve_builtin_type(name: &str) -> Option<Type> {
     match name {
         "string" => Some(Type::String),
+        "char" => Some(Type::Char),
         "u8" => Some(Type::UInt8),
         "i8" => Some(Type::Int8),
         "u16" => Some(Type::UInt16),
@@ -216,6 +299,13 @@ pub(in super::super) fn resolve_builtin_type(name: &str) -> Option<Type> {
         "f64" => Some(Type::Float64),
         "timestamp" => Some(Type::Timestamp),
         "duration" => Some(Type::Duration),
+        "offset_datetime" => Some(Type::OffsetDateTime),
+        "bigint" => Some(Type::BigInt),
+        "decimal" => Some(Type::Decimal),
+        "url" => Some(Type::Url),
+        "uuid" => Some(Type::Uuid),
+        "json" => Some(Type::Json),
+        "shared_slice" => Some(Type::SharedSlice),
         _ => None,
     }
 }
@@ -243,6 +333,35 @@ mod test {
         assert_eq!(t.canonical_name(), "OptionalTypeTestRecord");
         assert_eq!(types.iter_known_types().count(), 2);
 
+        let (_, expr) = weedle::types::Type::parse("char").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t, Type::Char);
+        assert_eq!(t.ffi_type(), crate::interface::FFIType::UInt32);
+
+        let (_, expr) = weedle::types::Type::parse("offset_datetime?").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "OptionalOffsetDateTime");
+        assert_eq!(
+            Type::OffsetDateTime.ffi_type(),
+            crate::interface::FFIType::RustBuffer
+        );
+
+        let (_, expr) = weedle::types::Type::parse("sequence<decimal>").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "SequenceDecimal");
+
+        let (_, expr) = weedle::types::Type::parse("record<DOMString, uuid>").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "MapStringUuid");
+
+        let (_, expr) = weedle::types::Type::parse("url").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t, Type::Url);
+
+        let (_, expr) = weedle::types::Type::parse("json?").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "OptionalJson");
+
         Ok(())
     }
 
@@ -278,6 +397,100 @@ mod test {
             .any(|t| t.canonical_name() == "string"));
     }
 
+    #[test]
+    fn test_resolving_set_type_adds_inner_type() {
+        let mut types = TypeUniverse::default();
+        let (_, expr) = weedle::types::Type::parse("set<string>?").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "OptionalSetstring");
+        assert_eq!(types.iter_known_types().count(), 3);
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "Setstring"));
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "string"));
+    }
+
+    #[test]
+    fn test_resolving_result_type_requires_an_error() {
+        let mut types = TypeUniverse::default();
+        types
+            .add_type_definition("TestError", Type::Error("TestError".into()))
+            .unwrap();
+        types
+            .add_type_definition("TestRecord", Type::Record("TestRecord".into()))
+            .unwrap();
+        let (_, expr) = weedle::types::Type::parse("result<u32, TestError>").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "ResultU32TypeTestError");
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "ResultU32TypeTestError"));
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "u32"));
+
+        let (_, expr) = weedle::types::Type::parse("result<u32, TestRecord>").unwrap();
+        let err = types.resolve_type_expression(expr).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the error type of a result must be an [Error] enum, not Record(\"TestRecord\")"
+        );
+    }
+
+    #[test]
+    fn test_resolving_weak_type_requires_an_object() {
+        let mut types = TypeUniverse::default();
+        types
+            .add_type_definition(
+                "Node",
+                Type::Object {
+                    name: "Node".into(),
+                    imp: ObjectImpl::Struct,
+                },
+            )
+            .unwrap();
+        types
+            .add_type_definition("TestRecord", Type::Record("TestRecord".into()))
+            .unwrap();
+        let (_, expr) = weedle::types::Type::parse("weak<Node>").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "WeakTypeNode");
+        assert_eq!(t.ffi_type(), crate::interface::FFIType::UInt64);
+        // The bindings need the optional object returned when upgrading the reference.
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "OptionalTypeNode"));
+
+        let (_, expr) = weedle::types::Type::parse("weak<TestRecord>").unwrap();
+        let err = types.resolve_type_expression(expr).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "weak references must point to an interface, not Record(\"TestRecord\")"
+        );
+    }
+
+    #[test]
+    fn test_resolving_iterator_type_adds_optional_item_type() {
+        let mut types = TypeUniverse::default();
+        let (_, expr) = weedle::types::Type::parse("iterator<u32>").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "Iteratoru32");
+        assert_eq!(t.ffi_type(), crate::interface::FFIType::UInt64);
+        // The bindings need the optional item returned when advancing the iterator.
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "Optionalu32"));
+
+        let (_, expr) = weedle::types::Type::parse("iterator<u32?>").unwrap();
+        let err = types.resolve_type_expression(expr).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "iterators can't have optional items, as `None` marks their end"
+        );
+    }
+
     #[test]
     fn test_resolving_map_type_adds_string_and_inner_type() {
         let mut types = TypeUniverse::default();
@@ -316,6 +529,25 @@ mod test {
             .any(|t| t.canonical_name() == "f32"));
     }
 
+    #[test]
+    fn test_resolving_tuple_type_adds_element_types() {
+        let mut types = TypeUniverse::default();
+        let (_, expr) = weedle::types::Type::parse("tuple<u32, sequence<string>>").unwrap();
+        let t = types.resolve_type_expression(expr).unwrap();
+        assert_eq!(t.canonical_name(), "Tuple2U32Sequencestring");
+        assert_eq!(types.iter_known_types().count(), 4);
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "u32"));
+        assert!(types
+            .iter_known_types()
+            .any(|t| t.canonical_name() == "Sequencestring"));
+
+        let (_, expr) = weedle::types::Type::parse("tuple<u32, u32, u32, u32>").unwrap();
+        let err = types.resolve_type_expression(expr).unwrap_err();
+        assert_eq!(err.to_string(), "tuples must have 2 or 3 elements, not 4");
+    }
+
     #[test]
     fn test_error_on_unknown_type() -> Result<()> {
         let mut types = TypeUniverse::default();
@@ -101,13 +101,22 @@ use fs_err::{self as fs, File};
 use serde::{Deserialize, Serialize};
 use std::io::prelude::*;
 use std::io::ErrorKind;
-use std::{collections::HashMap, env, process::Command, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    process::Command,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
 
 pub mod backend;
 pub mod bindings;
 pub mod interface;
 pub mod macro_metadata;
 pub mod scaffolding;
+mod workspace;
 
 pub use interface::ComponentInterface;
 use scaffolding::RustScaffolding;
@@ -256,7 +265,8 @@ pub fn generate_external_bindings(
 
     let crate_root = guess_crate_root(udl_file.as_ref())?;
     let out_dir = get_out_dir(udl_file.as_ref(), out_dir_override)?;
-    let component = parse_udl(udl_file.as_ref()).context("Error parsing UDL")?;
+    let (component, _) =
+        load_component(udl_file.as_ref(), config_file_override).context("Error parsing UDL")?;
     let bindings_config = load_bindings_config(&component, crate_root, config_file_override)?;
     binding_generator.write_bindings(component, bindings_config, &out_dir)
 }
@@ -269,17 +279,14 @@ pub fn generate_component_scaffolding(
     out_dir_override: Option<&Utf8Path>,
     format_code: bool,
 ) -> Result<()> {
-    let component = parse_udl(udl_file)?;
-    let _config = get_config(
-        &component,
-        guess_crate_root(udl_file)?,
-        config_file_override,
-    );
+    let (component, config) = load_component(udl_file, config_file_override)?;
     let file_stem = udl_file.file_stem().context("not a file")?;
     let filename = format!("{file_stem}.uniffi.rs");
     let out_path = get_out_dir(udl_file, out_dir_override)?.join(filename);
     let mut f = File::create(&out_path)?;
-    write!(f, "{}", RustScaffolding::new(&component)).context("Failed to write output file")?;
+    // The JNI glue of the Kotlin bindings is part of the scaffolding, when they use it.
+    let scaffolding = RustScaffolding::new(&component, config.bindings.kotlin_jni_class_name());
+    write!(f, "{}", scaffolding).context("Failed to write output file")?;
     if format_code {
         format_code_with_rustfmt(&out_path)?;
     }
@@ -288,21 +295,26 @@ pub fn generate_component_scaffolding(
 
 // Generate the bindings in the target languages that call the scaffolding
 // Rust code.
+//
+// When the component is compiled into a library combining several components, see
+// `uniffi_reexport_scaffolding!`, `cdylib_name_override` is the name of that library, which
+// the bindings load instead of the one of the configuration.
 pub fn generate_bindings(
     udl_file: &Utf8Path,
     config_file_override: Option<&Utf8Path>,
     target_languages: Vec<&str>,
     out_dir_override: Option<&Utf8Path>,
     library_file: Option<&Utf8Path>,
+    cdylib_name_override: Option<&str>,
     try_format_code: bool,
 ) -> Result<()> {
-    let mut component = parse_udl(udl_file)?;
+    let (mut component, mut config) = load_component(udl_file, config_file_override)?;
     if let Some(library_file) = library_file {
         macro_metadata::add_to_ci_from_library(&mut component, library_file)?;
     }
-    let crate_root = &guess_crate_root(udl_file)?;
-
-    let config = get_config(&component, crate_root, config_file_override)?;
+    if let Some(cdylib_name) = cdylib_name_override {
+        config.bindings = config.bindings.with_cdylib_name(cdylib_name);
+    }
     let out_dir = get_out_dir(udl_file, out_dir_override)?;
     for language in target_languages {
         bindings::write_bindings(
@@ -317,6 +329,392 @@ pub fn generate_bindings(
     Ok(())
 }
 
+// Generate the bindings of all the components of a compiled library, from the UDL definitions
+// and the proc-macro metadata which their scaffolding embeds in it, without their crates.
+//
+// The bindings load that library, e.g. `foo` for `libfoo.so`, unless `cdylib_name_override`
+// is given.
+pub fn generate_bindings_from_library(
+    library_file: &Utf8Path,
+    config_file_override: Option<&Utf8Path>,
+    target_languages: Vec<&str>,
+    out_dir: &Utf8Path,
+    cdylib_name_override: Option<&str>,
+    try_format_code: bool,
+) -> Result<()> {
+    let cdylib_name = match cdylib_name_override {
+        Some(cdylib_name) => cdylib_name,
+        None => library_cdylib_name(library_file)?,
+    };
+    fs::create_dir_all(out_dir)?;
+    for component in macro_metadata::components_from_library(library_file)? {
+        let mut config = get_config(&component, None, config_file_override)?;
+        config.bindings = config.bindings.with_cdylib_name(cdylib_name);
+        for language in &target_languages {
+            bindings::write_bindings(
+                &config.bindings,
+                &component,
+                out_dir,
+                (*language).try_into()?,
+                try_format_code,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the bindings in the output directory are the ones [`generate_bindings`] would write,
+/// failing with the list of the stale files otherwise.
+///
+/// The bindings are generated into a temporary directory, and compared with the files of the
+/// output directory, which may also contain other files.
+pub fn check_bindings(
+    udl_files: &[Utf8PathBuf],
+    config_file_override: Option<&Utf8Path>,
+    target_languages: Vec<&str>,
+    out_dir_override: Option<&Utf8Path>,
+    library_file: Option<&Utf8Path>,
+    cdylib_name_override: Option<&str>,
+    try_format_code: bool,
+) -> Result<()> {
+    let mut stale = Vec::new();
+    for udl_file in udl_files {
+        let out_dir = get_out_dir(udl_file, out_dir_override)?;
+        stale.extend(stale_files(&out_dir, |tmp_dir| {
+            generate_bindings(
+                udl_file,
+                config_file_override,
+                target_languages.clone(),
+                Some(tmp_dir),
+                library_file,
+                cdylib_name_override,
+                try_format_code,
+            )
+        })?);
+    }
+    report_stale_files(stale)
+}
+
+/// Like [`check_bindings`], for the bindings of [`generate_bindings_from_library`].
+pub fn check_bindings_from_library(
+    library_file: &Utf8Path,
+    config_file_override: Option<&Utf8Path>,
+    target_languages: Vec<&str>,
+    out_dir: &Utf8Path,
+    cdylib_name_override: Option<&str>,
+    try_format_code: bool,
+) -> Result<()> {
+    let stale = stale_files(out_dir, |tmp_dir| {
+        generate_bindings_from_library(
+            library_file,
+            config_file_override,
+            target_languages,
+            tmp_dir,
+            cdylib_name_override,
+            try_format_code,
+        )
+    })?;
+    report_stale_files(stale)
+}
+
+// The files which `generate` writes to the directory it's given, but which are missing or
+// different in `out_dir`, with the reason.
+fn stale_files(
+    out_dir: &Utf8Path,
+    generate: impl FnOnce(&Utf8Path) -> Result<()>,
+) -> Result<Vec<(Utf8PathBuf, String)>> {
+    let tmp_dir = TempDir::new()?;
+    generate(&tmp_dir.0)?;
+    let mut stale = Vec::new();
+    for generated in list_files(&tmp_dir.0)? {
+        let path = out_dir.join(generated.strip_prefix(&tmp_dir.0)?);
+        let expected = fs::read(&generated)?;
+        let reason = match std::fs::read(&path) {
+            Ok(actual) if actual == expected => continue,
+            Ok(actual) => {
+                let line = String::from_utf8_lossy(&actual)
+                    .lines()
+                    .zip(String::from_utf8_lossy(&expected).lines())
+                    .take_while(|(actual, expected)| actual == expected)
+                    .count();
+                format!("differs from line {}", line + 1)
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => "missing".to_string(),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {path}")),
+        };
+        stale.push((path, reason));
+    }
+    Ok(stale)
+}
+
+fn report_stale_files(stale: Vec<(Utf8PathBuf, String)>) -> Result<()> {
+    if stale.is_empty() {
+        return Ok(());
+    }
+    let files: Vec<_> = stale
+        .iter()
+        .map(|(path, reason)| format!("  {path}: {reason}"))
+        .collect();
+    bail!(
+        "Generated bindings out of date, run `uniffi-bindgen generate` to update them:\n{}",
+        files.join("\n")
+    )
+}
+
+// The files of a directory and its subdirectories, in order.
+fn list_files(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let mut files = Vec::new();
+    for entry in dir.read_dir_utf8()? {
+        let path = entry?.into_path();
+        if path.is_dir() {
+            files.extend(list_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// A directory of the system's temporary directory, removed when dropped.
+struct TempDir(Utf8PathBuf);
+
+impl TempDir {
+    fn new() -> Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "uniffi-bindgen-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = Utf8PathBuf::try_from(env::temp_dir().join(name))?;
+        fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// How often `watch()` checks the files for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+// Run `generate`, then run it again each time one of the files of `watched_files` changes, until
+// the process is interrupted.  The errors are reported without stopping, since the files are
+// likely being edited.
+fn watch(
+    watched_files: impl Fn() -> Vec<Utf8PathBuf>,
+    generate: impl Fn() -> Result<()>,
+) -> Result<()> {
+    let snapshot = || -> Vec<_> {
+        watched_files()
+            .into_iter()
+            .map(|path| {
+                let modified = path.metadata().and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect()
+    };
+    let mut last = None;
+    loop {
+        let mut current = snapshot();
+        if last.as_ref() != Some(&current) {
+            if last.is_some() {
+                // Wait for the files to be written completely, e.g. a library being linked.
+                loop {
+                    thread::sleep(WATCH_INTERVAL);
+                    let next = snapshot();
+                    if next == current {
+                        break;
+                    }
+                    current = next;
+                }
+                eprintln!("Files changed, generating the bindings again");
+            }
+            match generate() {
+                Ok(()) => eprintln!("Bindings generated, watching for changes"),
+                Err(err) => eprintln!("Error: {err:?}"),
+            }
+            last = Some(current);
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+// The files the bindings are generated from: the UDL files with the files they include, the
+// config, and the library.  The ones missing are watched too, for when they're created.
+fn watched_files(
+    udl_files: &[Utf8PathBuf],
+    config_file_override: Option<&Utf8Path>,
+    library_file: Option<&Utf8Path>,
+) -> Vec<Utf8PathBuf> {
+    let mut files = Vec::new();
+    for udl_file in udl_files {
+        files.push(udl_file.clone());
+        // On errors, like a missing included file, the files read so far are still watched.
+        let mut sources = Vec::new();
+        let _ = read_udl_sources(udl_file, &mut HashSet::new(), &mut sources);
+        files.extend(sources.into_iter().map(|(path, _)| path));
+        if config_file_override.is_none() {
+            if let Ok(crate_root) = guess_crate_root(udl_file) {
+                files.push(crate_root.join("uniffi.toml"));
+            }
+        }
+    }
+    files.extend(config_file_override.map(Utf8Path::to_owned));
+    files.extend(library_file.map(Utf8Path::to_owned));
+    files.sort();
+    files.dedup();
+    files
+}
+
+// The name under which the bindings load a library: its file name, without the extension nor
+// the `lib` prefix.
+fn library_cdylib_name(library_file: &Utf8Path) -> Result<&str> {
+    let file_stem = library_file
+        .file_stem()
+        .with_context(|| format!("{library_file} is not a library file"))?;
+    Ok(file_stem.strip_prefix("lib").unwrap_or(file_stem))
+}
+
+/// Package the component as an Android library module, containing its Kotlin bindings and the
+/// cdylib compiled for each of the requested `abis`.
+pub fn generate_android_library(
+    udl_file: &Utf8Path,
+    config_file_override: Option<&Utf8Path>,
+    abis: Vec<&str>,
+    out_dir: &Utf8Path,
+    release: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let (component, config) = load_component(udl_file, config_file_override)?;
+    let crate_root = guess_crate_root(udl_file)?;
+    fs::create_dir_all(out_dir)?;
+    bindings::write_android_library(
+        &config.bindings,
+        &component,
+        crate_root,
+        out_dir,
+        &abis,
+        release,
+        try_format_code,
+    )
+}
+
+pub fn generate_python_package(
+    udl_file: &Utf8Path,
+    config_file_override: Option<&Utf8Path>,
+    out_dir: &Utf8Path,
+    release: bool,
+    build_wheel: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let (component, config) = load_component(udl_file, config_file_override)?;
+    let crate_root = guess_crate_root(udl_file)?;
+    fs::create_dir_all(out_dir)?;
+    bindings::write_python_package(
+        &config.bindings,
+        &component,
+        crate_root,
+        out_dir,
+        release,
+        build_wheel,
+        try_format_code,
+    )
+}
+
+pub fn generate_ruby_gem(
+    udl_file: &Utf8Path,
+    config_file_override: Option<&Utf8Path>,
+    out_dir: &Utf8Path,
+    release: bool,
+    build_gem: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let (component, config) = load_component(udl_file, config_file_override)?;
+    let crate_root = guess_crate_root(udl_file)?;
+    fs::create_dir_all(out_dir)?;
+    bindings::write_ruby_gem(
+        &config.bindings,
+        &component,
+        crate_root,
+        out_dir,
+        release,
+        build_gem,
+        try_format_code,
+    )
+}
+
+/// Build the UniFFI components of the cargo workspace of `manifest_path`, or of the current
+/// directory, for the host, and generate their bindings from their UDL files and their library.
+///
+/// Only the components of the `packages` named are built if there are any. The bindings are
+/// generated for `target_languages`, or for the `languages` set in the
+/// `[package.metadata.uniffi.bindings]` table of the manifest of each component if empty. They
+/// are written to a subdirectory named after the package in `out_dir_override` if given, or next
+/// to the UDL files otherwise.
+pub fn generate_workspace_bindings(
+    manifest_path: Option<&Utf8Path>,
+    packages: &[String],
+    target_languages: Vec<&str>,
+    out_dir_override: Option<&Utf8Path>,
+    release: bool,
+    try_format_code: bool,
+) -> Result<()> {
+    let components = workspace::find_components(manifest_path, packages)?;
+    let names: Vec<&str> = components.iter().map(|c| c.package.as_str()).collect();
+    let libraries = bindings::cdylib::build_cdylibs(manifest_path, &names, release)?;
+    for component in &components {
+        let package = &component.package;
+        let languages = if target_languages.is_empty() {
+            component.languages.iter().map(String::as_str).collect()
+        } else {
+            target_languages.clone()
+        };
+        if languages.is_empty() {
+            bail!(
+                "No language to generate the bindings of {package} for, pass `--language` or set \
+                 `languages` in its `[package.metadata.uniffi.bindings]`"
+            );
+        }
+        let library = libraries
+            .get(&component.package_id)
+            .with_context(|| format!("No cdylib was built for {package}"))?;
+        let out_dir = out_dir_override.map(|out_dir| out_dir.join(package));
+        if component.udl_files.is_empty() {
+            // The component only uses the proc-macros, its bindings come from its library.
+            let config = component.crate_root.join("uniffi.toml");
+            let config = if config.exists() { Some(config) } else { None };
+            generate_bindings_from_library(
+                library,
+                config.as_deref(),
+                languages,
+                &out_dir.unwrap_or_else(|| component.crate_root.join("src")),
+                None,
+                try_format_code,
+            )?;
+        } else {
+            for udl_file in &component.udl_files {
+                generate_bindings(
+                    udl_file,
+                    None,
+                    languages.clone(),
+                    out_dir.as_deref(),
+                    Some(library),
+                    None,
+                    try_format_code,
+                )?;
+            }
+        }
+        eprintln!("Generated the bindings of {package}");
+    }
+    Ok(())
+}
+
 pub fn dump_json(library_path: &Utf8Path) -> Result<String> {
     let metadata = macro_metadata::extract_from_library(library_path)?;
     Ok(serde_json::to_string_pretty(&metadata)?)
@@ -327,6 +725,103 @@ pub fn print_json(library_path: &Utf8Path) -> Result<()> {
     Ok(())
 }
 
+/// Describe the interfaces of components as JSON, see [`interface::json`].
+///
+/// The components are the ones of the UDL files, with the items exported with the proc-macros
+/// found in `library_file`, if given.
+pub fn interfaces_json(
+    udl_files: &[Utf8PathBuf],
+    config_file_override: Option<&Utf8Path>,
+    library_file: Option<&Utf8Path>,
+) -> Result<String> {
+    let mut components = Vec::new();
+    for udl_file in udl_files {
+        let (mut component, _) = load_component(udl_file, config_file_override)?;
+        if let Some(library_file) = library_file {
+            macro_metadata::add_to_ci_from_library(&mut component, library_file)?;
+        }
+        components.push(component);
+    }
+    Ok(serde_json::to_string_pretty(
+        &interface::json::InterfacesJson::new(&components),
+    )?)
+}
+
+/// Like [`interfaces_json`], for all the components of a compiled library, from the definitions
+/// its scaffolding embeds.
+pub fn interfaces_json_from_library(library_file: &Utf8Path) -> Result<String> {
+    let components = macro_metadata::components_from_library(library_file)?;
+    Ok(serde_json::to_string_pretty(
+        &interface::json::InterfacesJson::new(&components),
+    )?)
+}
+
+/// Compare two versions of the interfaces of components, see [`bindings::diff`].
+///
+/// Each version is a UDL file, a JSON file printed by `uniffi-bindgen print-interface`, or a
+/// compiled library.
+pub fn diff_interfaces(old: &Utf8Path, new: &Utf8Path) -> Result<bindings::diff::DiffReport> {
+    Ok(bindings::diff::DiffReport::new(
+        &load_interfaces(old)?,
+        &load_interfaces(new)?,
+    ))
+}
+
+fn load_interfaces(path: &Utf8Path) -> Result<interface::json::InterfacesJson> {
+    match path.extension() {
+        Some("udl") => {
+            let (component, _) = load_component(path, None)?;
+            Ok(interface::json::InterfacesJson::new([&component]))
+        }
+        Some("json") => {
+            let json: interface::json::InterfacesJson =
+                serde_json::from_str(&fs::read_to_string(path)?)
+                    .with_context(|| format!("Failed to read the interfaces from {path}"))?;
+            if json.format_version != interface::json::JSON_FORMAT_VERSION {
+                bail!(
+                    "{path} has version {} of the JSON format, but uniffi-bindgen reads version {}",
+                    json.format_version,
+                    interface::json::JSON_FORMAT_VERSION
+                );
+            }
+            Ok(json)
+        }
+        _ => Ok(interface::json::InterfacesJson::new(
+            &macro_metadata::components_from_library(path)?,
+        )),
+    }
+}
+
+/// Format UDL files in place, in the canonical format of [`interface::format_udl`].
+///
+/// With `check`, the files are left untouched, and this fails if any of them isn't formatted.
+pub fn format_udl_files(
+    udl_files: &[Utf8PathBuf],
+    options: interface::FormatOptions,
+    check: bool,
+) -> Result<()> {
+    let mut unformatted = Vec::new();
+    for udl_file in udl_files {
+        let udl = fs::read_to_string(udl_file)
+            .with_context(|| format!("Failed to read UDL from {udl_file}"))?;
+        let formatted = interface::format_udl(&udl, options)
+            .map_err(|err| diagnose(err, &[(udl_file.as_str(), &udl)]))
+            .with_context(|| format!("Failed to format UDL from {udl_file}"))?;
+        if formatted == udl {
+            continue;
+        }
+        if check {
+            unformatted.push(udl_file.as_str());
+        } else {
+            fs::write(udl_file, formatted)?;
+        }
+    }
+    if !unformatted.is_empty() {
+        bail!("UDL files not formatted: {}", unformatted.join(", "));
+    }
+    Ok(())
+}
+
 /// Guess the root directory of the crate from the path of its UDL file.
 ///
 /// For now, we assume that the UDL file is in `./src/something.udl` relative
@@ -344,16 +839,19 @@ pub fn guess_crate_root(udl_file: &Utf8Path) -> Result<&Utf8Path> {
     Ok(path_guess)
 }
 
+// Without a `config_file_override`, the config is the `uniffi.toml` file of the crate, if any.
+// Components loaded from a compiled library have no crate at hand, only their defaults.
 fn get_config(
     component: &ComponentInterface,
-    crate_root: &Utf8Path,
+    crate_root: Option<&Utf8Path>,
     config_file_override: Option<&Utf8Path>,
 ) -> Result<Config> {
     let default_config: Config = component.into();
 
-    let config_file = match config_file_override {
-        Some(cfg) => Some(cfg.to_owned()),
-        None => crate_root.join("uniffi.toml").canonicalize_utf8().ok(),
+    let config_file = match (config_file_override, crate_root) {
+        (Some(cfg), _) => Some(cfg.to_owned()),
+        (None, Some(crate_root)) => crate_root.join("uniffi.toml").canonicalize_utf8().ok(),
+        (None, None) => None,
     };
 
     match config_file {
@@ -368,6 +866,25 @@ fn get_config(
     }
 }
 
+/// Parse a UDL file, and load the configuration of its component, whose `ffi_prefix` is then
+/// applied to the names of the FFI functions, and whose `compact_encoding` to the values.
+fn load_component(
+    udl_file: &Utf8Path,
+    config_file_override: Option<&Utf8Path>,
+) -> Result<(ComponentInterface, Config)> {
+    let mut component = parse_udl(udl_file)?;
+    let config = get_config(
+        &component,
+        Some(guess_crate_root(udl_file)?),
+        config_file_override,
+    )?;
+    if let Some(prefix) = &config.ffi_prefix {
+        component.set_ffi_prefix(prefix)?;
+    }
+    component.set_compact_encoding(config.compact_encoding.unwrap_or(false));
+    Ok((component, config))
+}
+
 fn get_out_dir(udl_file: &Utf8Path, out_dir_override: Option<&Utf8Path>) -> Result<Utf8PathBuf> {
     Ok(match out_dir_override {
         Some(s) => {
@@ -383,9 +900,53 @@ fn get_out_dir(udl_file: &Utf8Path, out_dir_override: Option<&Utf8Path>) -> Resu
 }
 
 fn parse_udl(udl_file: &Utf8Path) -> Result<ComponentInterface> {
+    let mut sources = Vec::new();
+    read_udl_sources(udl_file, &mut HashSet::new(), &mut sources)?;
+    let sources: Vec<(&str, &str)> = sources
+        .iter()
+        .map(|(path, udl)| (path.as_str(), udl.as_str()))
+        .collect();
+    let texts: Vec<&str> = sources.iter().map(|(_, udl)| *udl).collect();
+    ComponentInterface::from_webidl_sources(&texts)
+        .map_err(|err| diagnose(err, &sources))
+        .context("Failed to parse UDL")
+}
+
+/// Report where an error is in the UDL sources, given as `(path, text)` pairs, if it's about a
+/// specific place of them.
+fn diagnose(err: anyhow::Error, sources: &[(&str, &str)]) -> anyhow::Error {
+    match interface::Diagnostic::from_error(&err, sources) {
+        Some(diagnostic) => diagnostic.into(),
+        None => err,
+    }
+}
+
+/// Read a UDL file along with every file it pulls in via `include "...";` directives.
+///
+/// Included paths are relative to the file containing the directive. Each file is only
+/// read once, so including the same file from several places (or circularly) is harmless.
+fn read_udl_sources(
+    udl_file: &Utf8Path,
+    seen: &mut HashSet<Utf8PathBuf>,
+    sources: &mut Vec<(Utf8PathBuf, String)>,
+) -> Result<()> {
+    let canonical_path = udl_file
+        .canonicalize_utf8()
+        .with_context(|| format!("Failed to read UDL from {udl_file}"))?;
+    if !seen.insert(canonical_path) {
+        return Ok(());
+    }
     let udl = fs::read_to_string(udl_file)
         .with_context(|| format!("Failed to read UDL from {udl_file}"))?;
-    ComponentInterface::from_webidl(&udl).context("Failed to parse UDL")
+    let includes = interface::udl_includes(&udl)
+        .map_err(|err| diagnose(err, &[(udl_file.as_str(), &udl)]))
+        .with_context(|| format!("Failed to parse UDL from {udl_file}"))?;
+    sources.push((udl_file.to_owned(), udl));
+    let parent = udl_file.parent().unwrap_or_else(|| Utf8Path::new(""));
+    for include in includes {
+        read_udl_sources(&parent.join(include), seen, sources)?;
+    }
+    Ok(())
 }
 
 fn format_code_with_rustfmt(path: &Utf8Path) -> Result<()> {
@@ -404,6 +965,12 @@ fn format_code_with_rustfmt(path: &Utf8Path) -> Result<()> {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct Config {
+    /// Starts the names of all the FFI symbols of the component, see
+    /// [`ComponentInterface::set_ffi_prefix`].
+    ffi_prefix: Option<String>,
+    /// Whether the values of the component are serialized with the compact encoding, see
+    /// [`ComponentInterface::set_compact_encoding`].
+    compact_encoding: Option<bool>,
     #[serde(default)]
     bindings: bindings::Config,
 }
@@ -411,6 +978,8 @@ struct Config {
 impl From<&ComponentInterface> for Config {
     fn from(ci: &ComponentInterface) -> Self {
         Config {
+            ffi_prefix: None,
+            compact_encoding: None,
             bindings: ci.into(),
         }
     }
@@ -423,6 +992,8 @@ pub trait MergeWith {
 impl MergeWith for Config {
     fn merge_with(&self, other: &Self) -> Self {
         Config {
+            ffi_prefix: self.ffi_prefix.merge_with(&other.ffi_prefix),
+            compact_encoding: self.compact_encoding.merge_with(&other.compact_encoding),
             bindings: self.bindings.merge_with(&other.bindings),
         }
     }
@@ -485,8 +1056,60 @@ enum Commands {
         #[clap(long)]
         lib_file: Option<Utf8PathBuf>,
 
-        /// Path to the UDL file.
-        udl_file: Utf8PathBuf,
+        /// Name of the library the bindings load, overriding the `cdylib_name` of the config.
+        /// Used to load several components compiled into a single library.
+        #[clap(long)]
+        cdylib_name: Option<String>,
+
+        /// Generate the bindings of all the components of a native lib (cdylib or staticlib),
+        /// from the definitions its scaffolding embeds, instead of from UDL files.
+        #[clap(long, conflicts_with_all = &["udl-files", "lib-file"], requires = "out-dir")]
+        library: Option<Utf8PathBuf>,
+
+        /// Keep running, and generate the bindings again each time the UDL files, the config or
+        /// the library change.
+        #[clap(long)]
+        watch: bool,
+
+        /// Path(s) to the UDL file(s), one per component.
+        #[clap(required_unless_present = "library")]
+        udl_files: Vec<Utf8PathBuf>,
+    },
+
+    /// Check that the generated bindings are up to date, failing otherwise
+    Check {
+        /// Foreign language(s) of the bindings to check.
+        #[clap(long, short, possible_values = &["kotlin", "python", "swift", "ruby"])]
+        language: Vec<String>,
+
+        /// Directory of the generated files to check. Default is same folder as .udl file.
+        #[clap(long, short)]
+        out_dir: Option<Utf8PathBuf>,
+
+        /// The bindings were generated with `--no-format`.
+        #[clap(long, short)]
+        no_format: bool,
+
+        /// Path to the optional uniffi config file. If not provided, uniffi-bindgen will try to guess it from the UDL's file location.
+        #[clap(long, short)]
+        config: Option<Utf8PathBuf>,
+
+        /// Extract proc-macro metadata from a native lib (cdylib or staticlib) for this crate.
+        #[clap(long)]
+        lib_file: Option<Utf8PathBuf>,
+
+        /// Name of the library the bindings load, overriding the `cdylib_name` of the config.
+        #[clap(long)]
+        cdylib_name: Option<String>,
+
+        /// Check the bindings of all the components of a native lib (cdylib or staticlib),
+        /// generated from the definitions its scaffolding embeds.
+        #[clap(long, conflicts_with_all = &["udl-files", "lib-file"], requires = "out-dir")]
+        library: Option<Utf8PathBuf>,
+
+        /// Path(s) to the UDL file(s), one per component.
+        #[clap(required_unless_present = "library")]
+        udl_files: Vec<Utf8PathBuf>,
     },
 
     /// Generate Rust scaffolding code
@@ -507,11 +1130,249 @@ enum Commands {
         udl_file: Utf8PathBuf,
     },
 
+    /// Format UDL files in place
+    Fmt {
+        /// Check that the files are formatted, without changing them.
+        #[clap(long)]
+        check: bool,
+
+        /// Order the definitions: `include` directives, then the namespace, then the others by name.
+        #[clap(long)]
+        sort: bool,
+
+        /// Number of spaces per level of indentation.
+        #[clap(long, default_value_t = 4)]
+        indent: usize,
+
+        /// Path(s) to the UDL file(s).
+        #[clap(required = true)]
+        udl_files: Vec<Utf8PathBuf>,
+    },
+
     /// Print the JSON representation of the interface from a dynamic library
     PrintJson {
         /// Path to the library file (.so, .dll, .dylib, or .a)
         path: Utf8PathBuf,
     },
+
+    /// Print the interfaces of components, with their types resolved, in a machine-readable format
+    PrintInterface {
+        /// Output format.
+        #[clap(long, possible_values = &["json"], default_value = "json")]
+        format: String,
+
+        /// Path to the optional uniffi config file. If not provided, uniffi-bindgen will try to guess it from the UDL's file location.
+        #[clap(long, short)]
+        config: Option<Utf8PathBuf>,
+
+        /// Extract proc-macro metadata from a native lib (cdylib or staticlib) for this crate.
+        #[clap(long)]
+        lib_file: Option<Utf8PathBuf>,
+
+        /// Print the interfaces of all the components of a native lib (cdylib or staticlib),
+        /// from the definitions its scaffolding embeds, instead of from UDL files.
+        #[clap(long, conflicts_with_all = &["udl-files", "lib-file"])]
+        library: Option<Utf8PathBuf>,
+
+        /// Path(s) to the UDL file(s), one per component.
+        #[clap(required_unless_present = "library")]
+        udl_files: Vec<Utf8PathBuf>,
+    },
+
+    /// Compare two versions of an interface, and report the breaking and additive changes,
+    /// failing if some are breaking
+    Diff {
+        /// Language(s) whose breaking changes fail the command. Default is all of them.
+        #[clap(long, short, possible_values = &["kotlin", "python", "swift", "ruby"])]
+        language: Vec<String>,
+
+        /// Output format.
+        #[clap(long, possible_values = &["text", "json"], default_value = "text")]
+        format: String,
+
+        /// The old version: a UDL file, a JSON file printed by `print-interface`, or a native lib.
+        old: Utf8PathBuf,
+
+        /// The new version, like the old one.
+        new: Utf8PathBuf,
+    },
+
+    /// Kotlin-specific tooling
+    Kotlin {
+        #[clap(subcommand)]
+        command: KotlinCommands,
+    },
+
+    /// Python-specific tooling
+    Python {
+        #[clap(subcommand)]
+        command: PythonCommands,
+    },
+
+    /// Ruby-specific tooling
+    Ruby {
+        #[clap(subcommand)]
+        command: RubyCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum KotlinCommands {
+    /// Build the cdylib for Android and package it, with its Kotlin bindings, as a Gradle
+    /// library module producing an AAR
+    Aar {
+        /// Android ABI(s) to build the library for. Defaults to all supported ABIs.
+        #[clap(long, possible_values = bindings::kotlin::DEFAULT_ABIS)]
+        abi: Vec<String>,
+
+        /// Directory in which to write the Gradle module.
+        #[clap(long, short)]
+        out_dir: Utf8PathBuf,
+
+        /// Build the library with the release profile.
+        #[clap(long)]
+        release: bool,
+
+        /// Do not try to format the generated bindings.
+        #[clap(long, short)]
+        no_format: bool,
+
+        /// Path to the optional uniffi config file. If not provided, uniffi-bindgen will try to guess it from the UDL's file location.
+        #[clap(long, short)]
+        config: Option<Utf8PathBuf>,
+
+        /// Path to the UDL file.
+        udl_file: Utf8PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum PythonCommands {
+    /// Build the cdylib for the host and package it, with its Python bindings, as a
+    /// pip-installable Python package
+    Package {
+        /// Directory in which to write the package.
+        #[clap(long, short)]
+        out_dir: Utf8PathBuf,
+
+        /// Build the library with the release profile.
+        #[clap(long)]
+        release: bool,
+
+        /// Also build a wheel of the package, in the `dist` subdirectory, using pip.
+        #[clap(long)]
+        wheel: bool,
+
+        /// Do not try to format the generated bindings.
+        #[clap(long, short)]
+        no_format: bool,
+
+        /// Path to the optional uniffi config file. If not provided, uniffi-bindgen will try to guess it from the UDL's file location.
+        #[clap(long, short)]
+        config: Option<Utf8PathBuf>,
+
+        /// Path to the UDL file.
+        udl_file: Utf8PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum RubyCommands {
+    /// Build the cdylib for the host and package it, with its Ruby bindings, as a gem
+    Gem {
+        /// Directory in which to write the gem.
+        #[clap(long, short)]
+        out_dir: Utf8PathBuf,
+
+        /// Build the library with the release profile.
+        #[clap(long)]
+        release: bool,
+
+        /// Also build the `.gem` file, in the output directory, using `gem build`.
+        #[clap(long)]
+        build: bool,
+
+        /// Do not try to format the generated bindings.
+        #[clap(long, short)]
+        no_format: bool,
+
+        /// Path to the optional uniffi config file. If not provided, uniffi-bindgen will try to guess it from the UDL's file location.
+        #[clap(long, short)]
+        config: Option<Utf8PathBuf>,
+
+        /// Path to the UDL file.
+        udl_file: Utf8PathBuf,
+    },
+}
+
+/// Build the UniFFI components of a cargo workspace and generate their bindings
+#[derive(Parser)]
+#[clap(name = "cargo", bin_name = "cargo")]
+#[clap(version = clap::crate_version!())]
+#[clap(propagate_version = true)]
+enum CargoCli {
+    /// Build the UniFFI components of a cargo workspace and generate their bindings
+    Uniffi {
+        #[clap(subcommand)]
+        command: CargoUniffiCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum CargoUniffiCommands {
+    /// Build the cdylib of each UniFFI component of the workspace for the host, and generate its
+    /// bindings
+    Build {
+        /// Foreign language(s) for which to build bindings. Defaults to the `languages` of the
+        /// `[package.metadata.uniffi.bindings]` table of the manifest of each component.
+        #[clap(long, short, possible_values = &["kotlin", "python", "swift", "ruby"])]
+        language: Vec<String>,
+
+        /// Directory in which to write the generated files, in a subdirectory per package.
+        /// Default is same folder as the .udl files.
+        #[clap(long, short)]
+        out_dir: Option<Utf8PathBuf>,
+
+        /// Package(s) to build and generate the bindings of. Defaults to all the UniFFI
+        /// components of the workspace.
+        #[clap(long, short)]
+        package: Vec<String>,
+
+        /// Build the libraries with the release profile.
+        #[clap(long)]
+        release: bool,
+
+        /// Do not try to format the generated bindings.
+        #[clap(long, short)]
+        no_format: bool,
+
+        /// Path to the Cargo.toml of the workspace. Default is the one of the current directory.
+        #[clap(long)]
+        manifest_path: Option<Utf8PathBuf>,
+    },
+}
+
+/// The entry point of `cargo uniffi`, the cargo subcommand installed as the `cargo-uniffi`
+/// binary.
+pub fn run_cargo_main() -> Result<()> {
+    let CargoCli::Uniffi { command } = CargoCli::parse();
+    match command {
+        CargoUniffiCommands::Build {
+            language,
+            out_dir,
+            package,
+            release,
+            no_format,
+            manifest_path,
+        } => generate_workspace_bindings(
+            manifest_path.as_deref(),
+            &package,
+            language.iter().map(String::as_str).collect(),
+            out_dir.as_deref(),
+            release,
+            !no_format,
+        ),
+    }
 }
 
 pub fn run_main() -> Result<()> {
@@ -523,15 +1384,75 @@ pub fn run_main() -> Result<()> {
             no_format,
             config,
             lib_file,
-            udl_file,
-        } => generate_bindings(
-            udl_file,
-            config.as_deref(),
-            language.iter().map(String::as_str).collect(),
-            out_dir.as_deref(),
-            lib_file.as_deref(),
-            !no_format,
-        ),
+            cdylib_name,
+            library,
+            watch: watch_files,
+            udl_files,
+        } => {
+            let generate = || match library {
+                Some(library) => generate_bindings_from_library(
+                    library,
+                    config.as_deref(),
+                    language.iter().map(String::as_str).collect(),
+                    out_dir.as_deref().context("--library requires --out-dir")?,
+                    cdylib_name.as_deref(),
+                    !no_format,
+                ),
+                None => udl_files.iter().try_for_each(|udl_file| {
+                    generate_bindings(
+                        udl_file,
+                        config.as_deref(),
+                        language.iter().map(String::as_str).collect(),
+                        out_dir.as_deref(),
+                        lib_file.as_deref(),
+                        cdylib_name.as_deref(),
+                        !no_format,
+                    )
+                }),
+            };
+            if *watch_files {
+                watch(
+                    || {
+                        watched_files(
+                            udl_files,
+                            config.as_deref(),
+                            library.as_deref().or(lib_file.as_deref()),
+                        )
+                    },
+                    generate,
+                )
+            } else {
+                generate()
+            }
+        }
+        Commands::Check {
+            language,
+            out_dir,
+            no_format,
+            config,
+            lib_file,
+            cdylib_name,
+            library,
+            udl_files,
+        } => match library {
+            Some(library) => check_bindings_from_library(
+                library,
+                config.as_deref(),
+                language.iter().map(String::as_str).collect(),
+                out_dir.as_deref().context("--library requires --out-dir")?,
+                cdylib_name.as_deref(),
+                !no_format,
+            ),
+            None => check_bindings(
+                udl_files,
+                config.as_deref(),
+                language.iter().map(String::as_str).collect(),
+                out_dir.as_deref(),
+                lib_file.as_deref(),
+                cdylib_name.as_deref(),
+                !no_format,
+            ),
+        },
         Commands::Scaffolding {
             out_dir,
             config,
@@ -543,7 +1464,119 @@ pub fn run_main() -> Result<()> {
             out_dir.as_deref(),
             !no_format,
         ),
+        Commands::Fmt {
+            check,
+            sort,
+            indent,
+            udl_files,
+        } => format_udl_files(
+            udl_files,
+            interface::FormatOptions {
+                indent: *indent,
+                sort: *sort,
+            },
+            *check,
+        ),
         Commands::PrintJson { path } => print_json(path),
+        Commands::PrintInterface {
+            // JSON is the only format so far.
+            format: _,
+            config,
+            lib_file,
+            library,
+            udl_files,
+        } => {
+            let json = match library {
+                Some(library) => interfaces_json_from_library(library)?,
+                None => interfaces_json(udl_files, config.as_deref(), lib_file.as_deref())?,
+            };
+            println!("{json}");
+            Ok(())
+        }
+        Commands::Diff {
+            language,
+            format,
+            old,
+            new,
+        } => {
+            let report = diff_interfaces(old, new)?;
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+                _ => println!("{report}"),
+            }
+            let languages = language
+                .iter()
+                .map(|language| language.as_str().try_into())
+                .collect::<Result<Vec<bindings::TargetLanguage>>>()?;
+            let breaking: Vec<_> = report
+                .breaking_languages()
+                .into_iter()
+                .filter(|language| languages.is_empty() || languages.contains(language))
+                .map(|language| language.to_string())
+                .collect();
+            if !breaking.is_empty() {
+                bail!("Breaking changes for {}", breaking.join(", "));
+            }
+            Ok(())
+        }
+        Commands::Kotlin {
+            command:
+                KotlinCommands::Aar {
+                    abi,
+                    out_dir,
+                    release,
+                    no_format,
+                    config,
+                    udl_file,
+                },
+        } => generate_android_library(
+            udl_file,
+            config.as_deref(),
+            if abi.is_empty() {
+                bindings::kotlin::DEFAULT_ABIS.to_vec()
+            } else {
+                abi.iter().map(String::as_str).collect()
+            },
+            out_dir,
+            *release,
+            !no_format,
+        ),
+        Commands::Python {
+            command:
+                PythonCommands::Package {
+                    out_dir,
+                    release,
+                    wheel,
+                    no_format,
+                    config,
+                    udl_file,
+                },
+        } => generate_python_package(
+            udl_file,
+            config.as_deref(),
+            out_dir,
+            *release,
+            *wheel,
+            !no_format,
+        ),
+        Commands::Ruby {
+            command:
+                RubyCommands::Gem {
+                    out_dir,
+                    release,
+                    build,
+                    no_format,
+                    config,
+                    udl_file,
+                },
+        } => generate_ruby_gem(
+            udl_file,
+            config.as_deref(),
+            out_dir,
+            *release,
+            *build,
+            !no_format,
+        ),
     }?;
     Ok(())
 }
@@ -580,4 +1613,57 @@ mod test {
         let not_a_crate_root = &this_crate_root.join("./src/templates");
         assert!(guess_crate_root(&not_a_crate_root.join("./src/example.udl")).is_err());
     }
+
+    #[test]
+    fn test_watched_files() {
+        let crate_root = TempDir::new().unwrap();
+        let crate_root = &crate_root.0;
+        fs::create_dir_all(crate_root.join("src")).unwrap();
+        fs::write(crate_root.join("Cargo.toml"), "").unwrap();
+        let udl_file = crate_root.join("src/main.udl");
+        fs::write(&udl_file, "include \"types.udl\";\nnamespace main {};\n").unwrap();
+        fs::write(crate_root.join("src/types.udl"), "").unwrap();
+        assert_eq!(
+            watched_files(std::slice::from_ref(&udl_file), None, None),
+            vec![
+                udl_file,
+                crate_root.join("src/types.udl"),
+                crate_root.join("uniffi.toml"),
+            ]
+        );
+
+        let config = Utf8Path::new("custom.toml");
+        let library = Utf8Path::new("libfoo.so");
+        assert_eq!(
+            watched_files(&[], Some(config), Some(library)),
+            vec![config.to_owned(), library.to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_stale_files() {
+        let generate = |dir: &Utf8Path| -> Result<()> {
+            fs::create_dir_all(dir.join("sub"))?;
+            fs::write(dir.join("a.kt"), "one\ntwo\nthree\n")?;
+            fs::write(dir.join("sub/b.kt"), "four\n")?;
+            Ok(())
+        };
+        let out_dir = TempDir::new().unwrap();
+        generate(&out_dir.0).unwrap();
+        fs::write(out_dir.0.join("other.txt"), "not generated").unwrap();
+        assert!(stale_files(&out_dir.0, generate).unwrap().is_empty());
+
+        fs::write(out_dir.0.join("a.kt"), "one\n2\nthree\n").unwrap();
+        fs::remove_file(out_dir.0.join("sub/b.kt")).unwrap();
+        let stale = stale_files(&out_dir.0, generate).unwrap();
+        assert_eq!(
+            stale,
+            vec![
+                (out_dir.0.join("a.kt"), "differs from line 2".to_string()),
+                (out_dir.0.join("sub/b.kt"), "missing".to_string()),
+            ]
+        );
+        let err = report_stale_files(stale).unwrap_err().to_string();
+        assert!(err.contains("a.kt: differs from line 2"));
+    }
 }
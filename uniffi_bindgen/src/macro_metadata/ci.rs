@@ -3,8 +3,8 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use crate::interface::{ComponentInterface, Enum, Record, Type};
-use anyhow::anyhow;
-use uniffi_meta::Metadata;
+use anyhow::{anyhow, bail};
+use uniffi_meta::{FnParamMetadata, Metadata};
 
 /// Add Metadata items to the ComponentInterface
 ///
@@ -19,29 +19,17 @@ pub fn add_to_ci(
     metadata_items: Vec<Metadata>,
 ) -> anyhow::Result<()> {
     for item in metadata_items {
-        let (item_desc, crate_name) = match &item {
-            Metadata::Func(meta) => (
-                format!("function `{}`", meta.name),
-                meta.module_path.first().unwrap(),
-            ),
-            Metadata::Method(meta) => (
-                format!("method `{}.{}`", meta.self_name, meta.name),
-                meta.module_path.first().unwrap(),
-            ),
-            Metadata::Record(meta) => (
-                format!("record `{}`", meta.name),
-                meta.module_path.first().unwrap(),
-            ),
-            Metadata::Enum(meta) => (
-                format!("enum `{}`", meta.name),
-                meta.module_path.first().unwrap(),
-            ),
-            Metadata::Object(meta) => (
-                format!("object `{}`", meta.name),
-                meta.module_path.first().unwrap(),
-            ),
+        let item_desc = match &item {
+            Metadata::Func(meta) => format!("function `{}`", meta.name),
+            Metadata::Method(meta) => format!("method `{}.{}`", meta.self_name, meta.name),
+            Metadata::Record(meta) => format!("record `{}`", meta.name),
+            Metadata::Enum(meta) => format!("enum `{}`", meta.name),
+            Metadata::Object(meta) => format!("object `{}`", meta.name),
+            Metadata::Udl(meta) => format!("UDL of `{}`", meta.namespace),
+            Metadata::Hook(meta) => format!("hook `{}`", meta.name),
         };
 
+        let crate_name = crate_name(&item);
         let ns = iface.namespace();
         if crate_name != ns {
             return Err(anyhow!("Found {item_desc} from crate `{crate_name}`.")
@@ -51,6 +39,22 @@ pub fn add_to_ci(
                 .context("Mixing symbols from multiple crates is not supported yet."));
         }
 
+        // The exported functions lower and lift their strings through the implementation of
+        // `String`, which doesn't know about the namespace.
+        if iface.utf16_strings() {
+            let has_strings = match &item {
+                Metadata::Func(meta) => passes_strings(&meta.inputs, &meta.return_type),
+                Metadata::Method(meta) => passes_strings(&meta.inputs, &meta.return_type),
+                _ => false,
+            };
+            if has_strings {
+                bail!(
+                    "The {item_desc} takes or returns a string, which the `[Utf16Strings]` \
+                     attribute of the namespace doesn't support for exported functions yet"
+                );
+            }
+        }
+
         match item {
             Metadata::Func(meta) => {
                 iface.add_fn_meta(meta)?;
@@ -77,12 +81,45 @@ pub fn add_to_ci(
             Metadata::Object(meta) => {
                 iface.add_object_free_fn(meta);
             }
+            Metadata::Hook(meta) => {
+                iface.add_hook_meta(meta);
+            }
+            Metadata::Udl(_) => {
+                bail!("The {item_desc} is the source of the interface, not one of its items")
+            }
         }
     }
 
     iface.resolve_types()?;
+    iface.resolve_default_values()?;
+    iface.resolve_recursive_types()?;
     iface.derive_ffi_funcs()?;
     iface.check_consistency()?;
 
     Ok(())
 }
+
+/// The name of the crate defining a metadata item.
+///
+/// The UDL of a component doesn't know its crate, which is named after its namespace when it
+/// also has proc-macro items.
+pub(super) fn crate_name(item: &Metadata) -> &str {
+    let module_path = match item {
+        Metadata::Func(meta) => &meta.module_path,
+        Metadata::Method(meta) => &meta.module_path,
+        Metadata::Record(meta) => &meta.module_path,
+        Metadata::Enum(meta) => &meta.module_path,
+        Metadata::Object(meta) => &meta.module_path,
+        Metadata::Hook(meta) => &meta.module_path,
+        Metadata::Udl(meta) => return &meta.namespace,
+    };
+    module_path.first().unwrap()
+}
+
+fn passes_strings(inputs: &[FnParamMetadata], return_type: &Option<uniffi_meta::Type>) -> bool {
+    inputs
+        .iter()
+        .map(|input| &input.ty)
+        .chain(return_type)
+        .any(|ty| *ty == uniffi_meta::Type::String)
+}
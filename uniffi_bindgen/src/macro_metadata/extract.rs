@@ -165,10 +165,16 @@ impl ExtractedItems {
         // always know the end position, because goblin reports the symbol size as 0 for PE and
         // MachO files.
         //
-        // This works fine, because bincode knows when the serialized data is terminated and will
-        // just ignore the trailing data.
+        // This works fine, because the header of the record gives its length, and the trailing
+        // data is ignored.
         let data = &file_data[offset..];
-        self.items.push(bincode::deserialize::<Metadata>(data)?);
+        // Records of kinds added by newer versions of uniffi are skipped.
+        if let Some(item) = uniffi_meta::deserialize_metadata(data)
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("Failed to read the metadata symbol {name}"))?
+        {
+            self.items.push(item);
+        }
         self.names.insert(name.to_string());
         Ok(())
     }
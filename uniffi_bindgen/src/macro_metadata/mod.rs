@@ -3,7 +3,9 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use super::ComponentInterface;
+use anyhow::bail;
 use camino::Utf8Path;
+use uniffi_meta::{Metadata, ValueEncoding};
 
 mod ci;
 mod extract;
@@ -11,9 +13,62 @@ mod extract;
 pub use ci::add_to_ci;
 pub use extract::extract_from_library;
 
+/// Add the Metadata items of a library to the ComponentInterface
+///
+/// A library can combine several components, see `uniffi_reexport_scaffolding!`, so only the
+/// items of the crate of the component, named after its namespace, are added: the ones of the
+/// other components are theirs.
 pub fn add_to_ci_from_library(
     iface: &mut ComponentInterface,
     library_path: &Utf8Path,
 ) -> anyhow::Result<()> {
-    add_to_ci(iface, extract_from_library(library_path)?)
+    let items = extract_from_library(library_path)?
+        .into_iter()
+        .filter(|item| ci::crate_name(item) == iface.namespace())
+        .filter(|item| !matches!(item, Metadata::Udl(_)))
+        .collect();
+    add_to_ci(iface, items)
+}
+
+/// Build the ComponentInterfaces of all the components of a library, from the UDL definitions
+/// which their scaffolding embeds in it, along with the Metadata items of their proc-macros.
+pub fn components_from_library(library_path: &Utf8Path) -> anyhow::Result<Vec<ComponentInterface>> {
+    let (udls, items): (Vec<_>, Vec<_>) = extract_from_library(library_path)?
+        .into_iter()
+        .partition(|item| matches!(item, Metadata::Udl(_)));
+    if udls.is_empty() {
+        bail!(
+            "No UniFFI component found in {library_path}, its scaffolding must be generated by \
+             uniffi {}",
+            crate::BINDGEN_VERSION
+        );
+    }
+    udls.into_iter()
+        .map(|udl| {
+            let udl = match udl {
+                Metadata::Udl(udl) => udl,
+                _ => unreachable!(),
+            };
+            if udl.uniffi_version != crate::BINDGEN_VERSION {
+                bail!(
+                    "The scaffolding of `{}` was generated by uniffi {}, but this is uniffi {}",
+                    udl.namespace,
+                    udl.uniffi_version,
+                    crate::BINDGEN_VERSION
+                );
+            }
+            let mut iface = ComponentInterface::from_webidl_sources(&udl.sources)?;
+            iface.set_compact_encoding(udl.value_encoding == ValueEncoding::Compact);
+            if let Some(prefix) = &udl.ffi_prefix {
+                iface.set_ffi_prefix(prefix)?;
+            }
+            let items = items
+                .iter()
+                .filter(|item| ci::crate_name(item) == udl.namespace)
+                .cloned()
+                .collect();
+            add_to_ci(&mut iface, items)?;
+            Ok(iface)
+        })
+        .collect()
 }
@@ -14,12 +14,28 @@ use heck::ToSnakeCase;
 pub struct RustScaffolding<'a> {
     ci: &'a ComponentInterface,
     uniffi_version: &'static str,
+    // The serialized `ci.udl_metadata()`, like the metadata of the proc-macros.
+    udl_metadata: Vec<u8>,
+    // The JVM class declaring the `external` functions of the Kotlin bindings, when they use the
+    // `jni` FFI backend, for which the scaffolding implements them.
+    jni_class_name: Option<String>,
+    // The suffix of the methods of `uniffi::FfiConverter` which use the encoding of the component,
+    // see `UNIFFI_COMPACT_ENCODING`.
+    encoding_suffix: &'static str,
 }
 impl<'a> RustScaffolding<'a> {
-    pub fn new(ci: &'a ComponentInterface) -> Self {
+    pub fn new(ci: &'a ComponentInterface, jni_class_name: Option<String>) -> Self {
+        let udl_metadata = uniffi_meta::Metadata::from(ci.udl_metadata());
         Self {
             ci,
             uniffi_version: crate::BINDGEN_VERSION,
+            udl_metadata: uniffi_meta::serialize_metadata(&udl_metadata),
+            jni_class_name,
+            encoding_suffix: if ci.compact_encoding() {
+                "_compact"
+            } else {
+                ""
+            },
         }
     }
 }
@@ -39,21 +55,58 @@ mod filters {
             Type::Float32 => "f32".into(),
             Type::Float64 => "f64".into(),
             Type::Boolean => "bool".into(),
+            Type::Char => "char".into(),
             Type::String => "String".into(),
             Type::Timestamp => "std::time::SystemTime".into(),
             Type::Duration => "std::time::Duration".into(),
+            Type::OffsetDateTime => {
+                "uniffi::deps::chrono::DateTime<uniffi::deps::chrono::FixedOffset>".into()
+            }
+            Type::BigInt => "uniffi::deps::num_bigint::BigInt".into(),
+            Type::Decimal => "uniffi::deps::rust_decimal::Decimal".into(),
+            Type::Url => "uniffi::deps::url::Url".into(),
+            Type::Uuid => "uniffi::deps::uuid::Uuid".into(),
+            Type::Json => "uniffi::deps::serde_json::Value".into(),
+            Type::SharedSlice => "uniffi::SharedSlice".into(),
             Type::Enum(name) | Type::Record(name) | Type::Error(name) => format!("r#{name}"),
-            Type::Object(name) => format!("std::sync::Arc<r#{name}>"),
+            Type::Object { name, imp } => format!("std::sync::Arc<{}>", imp.rust_name_for(name)),
             Type::CallbackInterface(name) => format!("Box<dyn r#{name}>"),
             Type::Optional(t) => format!("std::option::Option<{}>", type_rs(t)?),
             Type::Sequence(t) => format!("std::vec::Vec<{}>", type_rs(t)?),
+            Type::Set(t) => format!("std::collections::HashSet<{}>", type_rs(t)?),
             Type::Map(k, v) => format!(
                 "std::collections::HashMap<{}, {}>",
                 type_rs(k)?,
                 type_rs(v)?
             ),
+            Type::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(type_rs)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ")
+            ),
+            Type::Result(ok, err) => {
+                format!("std::result::Result<{}, {}>", type_rs(ok)?, type_rs(err)?)
+            }
+            Type::Weak(t) => match t.as_ref() {
+                Type::Object { name, imp } => {
+                    format!("std::sync::Weak<{}>", imp.rust_name_for(name))
+                }
+                _ => unreachable!("weak references only point to objects"),
+            },
+            Type::Iterator(t) => {
+                format!("Box<dyn Iterator<Item = {}> + Send>", type_rs(t)?)
+            }
             Type::Custom { name, .. } => format!("r#{name}"),
-            Type::External { .. } => panic!("External types coming to a uniffi near you soon!"),
+            // External types are `use`d by the crate, like the types it defines itself.
+            Type::External {
+                name,
+                kind: ExternalKind::CallbackInterface,
+                ..
+            } => format!("Box<dyn r#{name}>"),
+            Type::External { name, .. } => format!("r#{name}"),
             Type::Unresolved { .. } => {
                 unreachable!("UDL scaffolding code never contains unresolved types")
             }
@@ -76,9 +129,188 @@ mod filters {
             FFIType::RustBuffer => "uniffi::RustBuffer".into(),
             FFIType::ForeignBytes => "uniffi::ForeignBytes".into(),
             FFIType::ForeignCallback => "uniffi::ForeignCallback".into(),
+            FFIType::Struct(name) => format!("FfiStruct{name}"),
         })
     }
 
+    /// The name of the JNI glue of an FFI function, under which the JVM looks up the `external`
+    /// function `jni_{ffi_name}` declared by the class `class_name`.
+    pub fn jni_symbol(
+        ffi_name: impl AsRef<str>,
+        class_name: impl AsRef<str>,
+    ) -> Result<String, askama::Error> {
+        Ok(format!(
+            "Java_{}_{}",
+            jni_mangle(class_name.as_ref()),
+            jni_mangle(&format!("jni_{}", ffi_name.as_ref()))
+        ))
+    }
+
+    // Escape a name for a JNI symbol: the dots separating the packages become `_`, and the other
+    // characters which aren't alphanumeric ASCII are escaped, `_` as `_1` and the others as `_0`
+    // followed by their UTF-16 code units in hexadecimal.
+    fn jni_mangle(name: &str) -> String {
+        let mut mangled = String::new();
+        for c in name.chars() {
+            match c {
+                '.' => mangled.push('_'),
+                '_' => mangled.push_str("_1"),
+                c if c.is_ascii_alphanumeric() => mangled.push(c),
+                c => {
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        mangled.push_str(&format!("_0{unit:04x}"));
+                    }
+                }
+            }
+        }
+        mangled
+    }
+
+    /// The parameters of the JNI glue of an FFI function, after the `JNIEnv` and the object.
+    ///
+    /// JNI only passes numbers: the `RustBuffer`s and `ForeignBytes` are passed as their fields,
+    /// and the pointers as `jlong`s. The structs are read from the scratch memory of the thread,
+    /// see `uniffi::ffi::jni`, and the callbacks are replaced by the ones of the glue.
+    pub fn jni_arg_list_decl(func: &FFIFunction) -> Result<String, askama::Error> {
+        let mut params = vec![
+            "_env: uniffi::ffi::jni::JniEnv".to_string(),
+            "_this: uniffi::ffi::jni::JniObject".to_string(),
+        ];
+        for arg in func.arguments() {
+            let name = arg.name();
+            match arg.type_() {
+                FFIType::RustBuffer => params.extend([
+                    format!("{name}_capacity: i32"),
+                    format!("{name}_len: i32"),
+                    format!("{name}_data: i64"),
+                ]),
+                FFIType::ForeignBytes => {
+                    params.extend([format!("{name}_len: i32"), format!("{name}_data: i64")])
+                }
+                FFIType::ForeignCallback | FFIType::Struct(_) => {}
+                FFIType::RustArcPtr(_) => params.push(format!("r#{name}: i64")),
+                type_ => match jni_signed(&type_) {
+                    Some(signed) => params.push(format!("r#{name}: {signed}")),
+                    None => params.push(format!("r#{name}: {}", type_ffi(&type_)?)),
+                },
+            }
+        }
+        Ok(params.join(", "))
+    }
+
+    /// The return type of the JNI glue of an FFI function, with its arrow, if it returns a value.
+    pub fn jni_return_type(func: &FFIFunction) -> Result<String, askama::Error> {
+        Ok(match func.return_type() {
+            None | Some(FFIType::RustBuffer | FFIType::Struct(_)) => String::new(),
+            Some(FFIType::RustArcPtr(_)) => " -> i64".into(),
+            Some(type_) => match jni_signed(type_) {
+                Some(signed) => format!(" -> {signed}"),
+                None => format!(" -> {}", type_ffi(type_)?),
+            },
+        })
+    }
+
+    // JNI only has signed integers, so the unsigned ones are passed as the signed integers of
+    // the same size, and cast back.
+    fn jni_signed(type_: &FFIType) -> Option<&'static str> {
+        match type_ {
+            FFIType::UInt8 => Some("i8"),
+            FFIType::UInt16 => Some("i16"),
+            FFIType::UInt32 => Some("i32"),
+            FFIType::UInt64 => Some("i64"),
+            _ => None,
+        }
+    }
+
+    /// The body of the JNI glue of an FFI function, which calls it with its arguments rebuilt
+    /// from the parameters of the glue, then writes its call status, and its return value when
+    /// JNI can't return it, to the scratch memory of the thread.
+    ///
+    /// The structs are read from the scratch memory after the return value, in the order of the
+    /// arguments, like the Kotlin bindings write them.
+    pub fn jni_call(func: &FFIFunction) -> Result<String, askama::Error> {
+        let mut offset = "uniffi::ffi::jni::SCRATCH_RETURN_OFFSET".to_string();
+        match func.return_type() {
+            Some(FFIType::RustBuffer) => {
+                offset.push_str(" + std::mem::size_of::<uniffi::RustBuffer>()")
+            }
+            Some(type_ @ FFIType::Struct(_)) => {
+                offset.push_str(&format!(" + {}::JNI_SIZE", type_ffi(type_)?))
+            }
+            _ => (),
+        }
+        let mut args = Vec::new();
+        for arg in func.arguments() {
+            let name = arg.name();
+            args.push(match arg.type_() {
+                FFIType::RustBuffer => format!(
+                    "uniffi::RustBuffer::from_raw_parts({name}_data as usize as *mut u8, {name}_len, {name}_capacity)"
+                ),
+                FFIType::ForeignBytes => format!(
+                    "uniffi::ForeignBytes::from_raw_parts({name}_data as usize as *const u8, {name}_len)"
+                ),
+                FFIType::RustArcPtr(_) => {
+                    format!("r#{name} as usize as *const std::os::raw::c_void")
+                }
+                FFIType::ForeignCallback => format!(
+                    "{{ {}.resolve(_env, _this); {} }}",
+                    jni_callback_method(func.name())?,
+                    jni_callback(func.name())?
+                ),
+                type_ @ FFIType::Struct(_) => {
+                    let read = format!("{}::jni_read(scratch, {offset})", type_ffi(&type_)?);
+                    offset.push_str(&format!(" + {}::JNI_SIZE", type_ffi(&type_)?));
+                    read
+                }
+                type_ => match jni_signed(&type_) {
+                    Some(_) => format!("r#{name} as {}", type_ffi(&type_)?),
+                    None => format!("r#{name}"),
+                },
+            });
+        }
+        args.push("&mut call_status".into());
+        let call = format!("r#{}({})", func.name(), args.join(", "));
+        let result = match func.return_type() {
+            Some(FFIType::RustBuffer) => {
+                "uniffi::ffi::jni::write_return_buffer(scratch, uniffi_result)".into()
+            }
+            Some(FFIType::Struct(_)) => {
+                "uniffi_result.jni_write(scratch, uniffi::ffi::jni::SCRATCH_RETURN_OFFSET)".into()
+            }
+            Some(FFIType::RustArcPtr(_)) => "uniffi_result as usize as i64".into(),
+            Some(type_) => match jni_signed(type_) {
+                Some(signed) => format!("uniffi_result as {signed}"),
+                None => "uniffi_result".into(),
+            },
+            None => String::new(),
+        };
+        let mut statements = vec![
+            "let scratch = SCRATCH.with(uniffi::ffi::jni::JniScratch::as_ptr);".to_string(),
+            "let mut call_status = uniffi::RustCallStatus::default();".into(),
+        ];
+        if func.return_type().is_some() {
+            statements.push(format!("let uniffi_result = {call};"));
+        } else {
+            statements.push(format!("{call};"));
+        }
+        statements.push("uniffi::ffi::jni::write_call_status(scratch, call_status);".into());
+        if !result.is_empty() {
+            statements.push(result);
+        }
+        Ok(statements.join("\n            "))
+    }
+
+    /// The `ForeignCallback` which the JNI glue installs in place of the one of the bindings, for
+    /// the FFI function initializing a callback interface.
+    pub fn jni_callback(init_fn_name: &str) -> Result<String, askama::Error> {
+        Ok(format!("r#{init_fn_name}_foreign_callback"))
+    }
+
+    /// The static method of the bindings which `jni_callback` calls.
+    pub fn jni_callback_method(init_fn_name: &str) -> Result<String, askama::Error> {
+        Ok(format!("r#{}_METHOD", init_fn_name.to_uppercase()))
+    }
+
     /// Get the name of the FfiConverter implementation for this type
     ///
     /// - For primitives / standard types this is the type itself.
@@ -89,8 +321,23 @@ mod filters {
             // Timestamp/Duraration are handled by standard types
             Type::Timestamp => "std::time::SystemTime".into(),
             Type::Duration => "std::time::Duration".into(),
+            // Offset date-times, big integers, decimals, URLs, UUIDs and JSON values are handled by
+            // the types of the crates behind the `chrono`, `num-bigint`, `rust_decimal`, `url`,
+            // `uuid` and `serde_json` features
+            Type::OffsetDateTime => {
+                "uniffi::deps::chrono::DateTime<uniffi::deps::chrono::FixedOffset>".into()
+            }
+            Type::BigInt => "uniffi::deps::num_bigint::BigInt".into(),
+            Type::Decimal => "uniffi::deps::rust_decimal::Decimal".into(),
+            Type::Url => "uniffi::deps::url::Url".into(),
+            Type::Uuid => "uniffi::deps::uuid::Uuid".into(),
+            Type::Json => "uniffi::deps::serde_json::Value".into(),
+            Type::SharedSlice => "uniffi::SharedSlice".into(),
             // Object is handled by Arc<T>
-            Type::Object(name) => format!("std::sync::Arc<r#{name}>"),
+            Type::Object {
+                name,
+                imp: ObjectImpl::Struct,
+            } => format!("std::sync::Arc<r#{name}>"),
             // Other user-defined types are handled by a unit-struct that we generate.  The
             // FfiConverter implementation for this can be found in one of the scaffolding template code.
             //
@@ -98,7 +345,17 @@ mod filters {
             //
             // CallbackInterface is handled by special case code on both the scaffolding and
             // bindings side.  It's not a unit-struct, but the same name generation code works.
-            Type::Enum(_) | Type::Record(_) | Type::Error(_) | Type::CallbackInterface(_) => {
+            //
+            // Trait objects can't go through the `Arc<T>` implementation, as `Arc<dyn Trait>` is a
+            // fat pointer.
+            Type::Enum(_)
+            | Type::Record(_)
+            | Type::Error(_)
+            | Type::CallbackInterface(_)
+            | Type::Object {
+                imp: ObjectImpl::Trait,
+                ..
+            } => {
                 format!("FfiConverter{}", type_.canonical_name())
             }
             // Wrapper types are implemented by generics that wrap the FfiConverter implementation of the
@@ -107,16 +364,46 @@ mod filters {
                 format!("std::option::Option<{}>", ffi_converter_name(inner)?)
             }
             Type::Sequence(inner) => format!("std::vec::Vec<{}>", ffi_converter_name(inner)?),
+            Type::Set(inner) => {
+                format!("std::collections::HashSet<{}>", ffi_converter_name(inner)?)
+            }
             Type::Map(k, v) => format!(
                 "std::collections::HashMap<{}, {}>",
                 ffi_converter_name(k)?,
                 ffi_converter_name(v)?
             ),
-            // External and Wrapped bytes have FfiConverters with a predictable name based on the type name.
-            Type::Custom { name, .. } | Type::External { name, .. } => {
-                format!("FfiConverterType{name}")
+            Type::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(ffi_converter_name)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ")
+            ),
+            Type::Result(ok, err) => format!(
+                "std::result::Result<{}, {}>",
+                ffi_converter_name(ok)?,
+                ffi_converter_name(err)?
+            ),
+            // Weak references are handled by Weak<T>, like objects by Arc<T>.
+            Type::Weak(inner) => match inner.as_ref() {
+                Type::Object {
+                    name,
+                    imp: ObjectImpl::Struct,
+                } => format!("std::sync::Weak<r#{name}>"),
+                _ => unreachable!("weak references only point to [Struct] objects"),
+            },
+            Type::Iterator(t) => {
+                format!("uniffi::FfiConverterIterator<{}>", ffi_converter_name(t)?)
             }
-            // Primitive types / strings are implemented by their rust type
+            // External and Wrapped bytes have FfiConverters with a predictable name based on the type name.
+            Type::Custom { name, .. } => format!("FfiConverterType{name}"),
+            Type::External {
+                kind: ExternalKind::CallbackInterface,
+                ..
+            } => type_rs(type_)?,
+            Type::External { .. } => format!("FfiConverter{}", type_.canonical_name()),
+            // Primitive types are implemented by their rust type
             Type::Int8 => "i8".into(),
             Type::UInt8 => "u8".into(),
             Type::Int16 => "i16".into(),
@@ -127,8 +414,10 @@ mod filters {
             Type::UInt64 => "u64".into(),
             Type::Float32 => "f32".into(),
             Type::Float64 => "f64".into(),
-            Type::String => "String".into(),
+            // Strings are implemented by a type alias of the scaffolding, see `Utf16String`.
+            Type::String => "UniffiStringConverter".into(),
             Type::Boolean => "bool".into(),
+            Type::Char => "char".into(),
             Type::Unresolved { .. } => {
                 unreachable!("UDL scaffolding code never contains unresolved types")
             }
@@ -137,7 +426,8 @@ mod filters {
 
     // Map a type to Rust code that specifies the FfiConverter implementation.
     //
-    // This outputs something like `<TheFfiConverterStruct as FfiConverter>`
+    // This outputs something like `<TheFfiConverterStruct as FfiConverter>`, whose methods are
+    // called with the `encoding_suffix` of the component.
     pub fn ffi_converter(type_: &Type) -> Result<String, askama::Error> {
         Ok(format!(
             "<{} as uniffi::FfiConverter>",
@@ -145,8 +435,35 @@ mod filters {
         ))
     }
 
+    // Map a field to Rust code that specifies the FfiConverter implementation of its value.
+    //
+    // This is the one of its type, except for the boxed fields of recursive records and enums.
+    pub fn field_ffi_converter(field: &Field) -> Result<String, askama::Error> {
+        let name = match field.type_() {
+            Type::Optional(inner) if field.is_boxed() => format!(
+                "std::option::Option<std::boxed::Box<{}>>",
+                ffi_converter_name(inner)?
+            ),
+            type_ if field.is_boxed() => {
+                format!("std::boxed::Box<{}>", ffi_converter_name(type_)?)
+            }
+            type_ => ffi_converter_name(type_)?,
+        };
+        Ok(format!("<{name} as uniffi::FfiConverter>"))
+    }
+
     // Turns a `crate-name` into the `crate_name` the .rs code needs to specify.
     pub fn crate_name_rs(nm: &str) -> Result<String, askama::Error> {
         Ok(nm.to_string().to_snake_case())
     }
+
+    // A byte string literal of the bytes, in which the text, like the one of the UDL, stays readable.
+    pub fn byte_string(bytes: &[u8]) -> Result<String, askama::Error> {
+        let escaped: String = bytes
+            .iter()
+            .flat_map(|b| std::ascii::escape_default(*b))
+            .map(char::from)
+            .collect();
+        Ok(format!("b\"{escaped}\""))
+    }
 }
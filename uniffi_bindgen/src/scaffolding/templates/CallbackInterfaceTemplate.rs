@@ -2,13 +2,23 @@
 // For each Callback Interface definition, we assume that there is a corresponding trait defined in Rust client code.
 // If the UDL callback interface and Rust trait's methods don't match, the Rust compiler will complain.
 // We generate:
-//  * an init function to accept that `ForeignCallback` from the foreign language, and stores it.
+//  * an init function to accept that `ForeignCallback` from the foreign language, and stores it
+//    in a slot, whose registration the foreign language stores in the handles it passes to Rust.
+//  * an unregister function, which frees the slot of a `ForeignCallback` once the foreign objects
+//    of its handles are gone.
 //  * a holder for a `ForeignCallback`, of type `uniffi::ForeignCallbackInternals`.
 //  * a proxy `struct` which implements the `trait` that the Callback Interface corresponds to. This
 //    is the object that client code interacts with.
 //    - for each method, arguments will be packed into a `RustBuffer` and sent over the `ForeignCallback` to be
 //      unpacked and called. The return value is packed into another `RustBuffer` and sent back to Rust.
-//    - a `Drop` `impl`, which tells the foreign language to forget about the real callback object.
+//    - each call is recorded as in progress on its thread while it runs, see
+//      `uniffi::ForeignCallbackGuard`, which catches Rust and foreign code calling each other endlessly.
+//    - a `Drop` `impl`, which tells the foreign language to forget about the real callback object,
+//      unless its callback was unregistered.
+//
+// Callback functions have no trait: their proxy has an inherent `call` method instead, which the
+// closure that client code receives calls. That closure's type is declared here, as an alias named
+// after the callback function.
 #}
 {% let trait_name = cbi.name() -%}
 {% let trait_impl = cbi.type_().borrow()|ffi_converter_name -%}
@@ -20,9 +30,19 @@ static {{ foreign_callback_internals }}: uniffi::ForeignCallbackInternals = unif
 
 #[doc(hidden)]
 #[no_mangle]
-pub extern "C" fn {{ cbi.ffi_init_callback().name() }}(callback: uniffi::ForeignCallback, _: &mut uniffi::RustCallStatus) {
-    {{ foreign_callback_internals }}.set_callback(callback);
-    // The call status should be initialized to CALL_SUCCESS, so no need to modify it.
+pub extern "C" fn {{ cbi.ffi_init_callback().name() }}(callback: uniffi::ForeignCallback, call_status: &mut uniffi::RustCallStatus) -> u16 {
+    // Returns the registration which the bindings store in the handles they pass to Rust.
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        {{ foreign_callback_internals }}
+            .set_callback(callback)
+            .unwrap_or_else(|err| uniffi::internal_error(err.to_string()))
+    })
+}
+
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ cbi.ffi_unregister_callback().name() }}(registration: u16, call_status: &mut uniffi::RustCallStatus) {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {{ foreign_callback_internals }}.unset_callback(registration))
 }
 
 // Make an implementation which will shell out to the foreign language.
@@ -34,29 +54,57 @@ struct {{ trait_impl }} {
 
 impl Drop for {{ trait_impl }} {
     fn drop(&mut self) {
-        let callback = {{ foreign_callback_internals }}.get_callback().unwrap();
-        let mut rbuf = uniffi::RustBuffer::new();
-        unsafe { callback(self.handle, uniffi::IDX_CALLBACK_FREE, Default::default(), &mut rbuf) };
+        // The foreign objects of an unregistered callback are gone already.
+        if let Some(callback) = {{ foreign_callback_internals }}.get_callback(self.handle) {
+            let mut rbuf = uniffi::RustBuffer::new();
+            unsafe { callback(self.handle, uniffi::IDX_CALLBACK_FREE, Default::default(), &mut rbuf) };
+        }
     }
 }
 
 uniffi::deps::static_assertions::assert_impl_all!({{ trait_impl }}: Send);
+{%- for error_type in cbi.throws_types() %}
+// The errors thrown by the foreign-language code are received as they are, but the error type must
+// also represent the unexpected ones, like exceptions of other types.
+uniffi::deps::static_assertions::assert_impl_all!({{ error_type|type_rs }}: From<uniffi::UnexpectedUniFFICallbackError>);
+{%- endfor %}
+{%- if cbi.is_function() %}
+{%- for meth in cbi.methods() %}
+
+pub type r#{{ trait_name }} = Box<dyn Fn(
+    {%- for arg in meth.arguments() %}{{ arg.type_().borrow()|type_rs }}{% if !loop.last %}, {% endif %}{% endfor -%}
+    ){% call rs::callback_return_type(meth) %} + Send + Sync>;
+{%- endfor %}
+{%- endif %}
 
+{% if cbi.is_function() -%}
+impl {{ trait_impl }} {
+{%- else -%}
 impl r#{{ trait_name }} for {{ trait_impl }} {
+{%- endif %}
     {%- for meth in cbi.methods() %}
 
     {#- Method declaration #}
     fn r#{{ meth.name() -}}
     ({% call rs::arg_list_decl_with_prefix("&self", meth) %})
-    {%- match (meth.return_type(), meth.throws_type()) %}
-    {%- when (Some(return_type), None) %} -> {{ return_type.borrow()|type_rs }}
-    {%- when (Some(return_type), Some(err)) %} -> ::std::result::Result<{{ return_type.borrow()|type_rs }}, {{ err|type_rs }}>
-    {%- when (None, Some(err)) %} -> ::std::result::Result<(), {{ err|type_rs }}>
-    {% else -%}
-    {%- endmatch -%} {
+    {%- call rs::callback_return_type(meth) -%} {
     {#- Method body #}
         uniffi::deps::log::debug!("{{ cbi.name() }}.{{ meth.name() }}");
 
+    {#- Recording the call, which fails if it's nested too deeply into other ones. #}
+        let _call_guard = match uniffi::ForeignCallbackGuard::enter("{{ cbi.name() }}", "{{ meth.name() }}", self.handle) {
+            Ok(guard) => guard,
+            {%- match meth.throws_type() %}
+            {%- when Some(error_type) %}
+            Err(err) => {
+                let e: {{ error_type|type_rs }} = uniffi::UnexpectedUniFFICallbackError::from_reason(err.to_string()).into();
+                return Err(e);
+            }
+            {%- else %}
+            Err(err) => panic!("{}", err),
+            {%- endmatch %}
+        };
+
     {#- Packing args into a RustBuffer #}
         {% if meth.arguments().len() == 0 -%}
         let args_buf = Vec::new();
@@ -64,12 +112,23 @@ impl r#{{ trait_name }} for {{ trait_impl }} {
         let mut args_buf = Vec::new();
         {% endif -%}
         {%- for arg in meth.arguments() %}
-        {{ arg.type_().borrow()|ffi_converter }}::write(r#{{ arg.name() }}, &mut args_buf);
+        {{ arg.type_().borrow()|ffi_converter }}::write{{ encoding_suffix }}(r#{{ arg.name() }}, &mut args_buf);
         {%- endfor -%}
         let args_rbuf = uniffi::RustBuffer::from_vec(args_buf);
 
     {#- Calling into foreign code. #}
-        let callback = {{ foreign_callback_internals }}.get_callback().unwrap();
+        let callback = match {{ foreign_callback_internals }}.get_callback(self.handle) {
+            Some(callback) => callback,
+            {%- match meth.throws_type() %}
+            {%- when Some(error_type) %}
+            None => {
+                let e: {{ error_type|type_rs }} = uniffi::UnexpectedUniFFICallbackError::from_reason(String::from("The callback of {{ cbi.name() }} was unregistered")).into();
+                return Err(e);
+            }
+            {%- else %}
+            None => panic!("The callback of {{ cbi.name() }} was unregistered"),
+            {%- endmatch %}
+        };
 
         unsafe {
             // SAFETY:
@@ -89,7 +148,7 @@ impl r#{{ trait_name }} for {{ trait_impl }} {
                         {%- when Some(return_type) -%}
                         let vec = ret_rbuf.destroy_into_vec();
                         let mut ret_buf = vec.as_slice();
-                        {{ return_type|ffi_converter }}::try_read(&mut ret_buf).unwrap()
+                        {{ return_type|ffi_converter }}::try_read{{ encoding_suffix }}(&mut ret_buf).unwrap()
                         {%- else %}
                         uniffi::RustBuffer::destroy(ret_rbuf);
                         {%- endmatch %}
@@ -106,7 +165,7 @@ impl r#{{ trait_name }} for {{ trait_impl }} {
                     {% when Some(error_type) -%}
                     let vec = ret_rbuf.destroy_into_vec();
                     let mut ret_buf = vec.as_slice();
-                    Err({{ error_type|ffi_converter }}::try_read(&mut ret_buf).unwrap())
+                    Err({{ error_type|ffi_converter }}::try_read{{ encoding_suffix }}(&mut ret_buf).unwrap())
                     {%- else -%}
                     panic!("Callback return -2, but throws_type() is None");
                     {%- endmatch %}
@@ -127,7 +186,7 @@ impl r#{{ trait_name }} for {{ trait_impl }} {
                 {%- when Some(error_type) -%}
                 -1 => {
                     let reason = if !ret_rbuf.is_empty() {
-                        match {{ Type::String.borrow()|ffi_converter }}::try_lift(ret_rbuf) {
+                        match {{ Type::String.borrow()|ffi_converter }}::try_lift{{ encoding_suffix }}(ret_rbuf) {
                             Ok(s) => s,
                             Err(e) => {
                                 uniffi::deps::log::error!("{{ trait_name }} Error reading ret_buf: {e}");
@@ -144,7 +203,7 @@ impl r#{{ trait_name }} for {{ trait_impl }} {
                 {%- else %}
                 -1 => {
                     if !ret_rbuf.is_empty() {
-                        let reason = match {{ Type::String.borrow()|ffi_converter }}::try_lift(ret_rbuf) {
+                        let reason = match {{ Type::String.borrow()|ffi_converter }}::try_lift{{ encoding_suffix }}(ret_rbuf) {
                             Ok(s) => s,
                             Err(_) => {
                                 String::from("[Error reading reason]")
@@ -165,8 +224,13 @@ impl r#{{ trait_name }} for {{ trait_impl }} {
 }
 
 unsafe impl uniffi::FfiConverter for {{ trait_impl }} {
-    // This RustType allows for rust code that inputs this type as a Box<dyn CallbackInterfaceTrait> param
+    // This RustType allows for rust code that inputs this type as a Box<dyn CallbackInterfaceTrait> param,
+    // or as the closure of a callback function.
+    {%- if cbi.is_function() %}
+    type RustType = r#{{ trait_name }};
+    {%- else %}
     type RustType = Box<dyn r#{{ trait_name }}>;
+    {%- endif %}
     type FfiType = u64;
 
     // Lower and write are tricky to implement because we have a dyn trait as our type.  There's
@@ -187,7 +251,18 @@ unsafe impl uniffi::FfiConverter for {{ trait_impl }} {
     }
 
     fn try_lift(v: Self::FfiType) -> uniffi::deps::anyhow::Result<Self::RustType> {
+        {%- if cbi.is_function() %}
+        {%- for meth in cbi.methods() %}
+        let callback = Self { handle: v };
+        Ok(Box::new(move |
+            {%- for arg in meth.arguments() %}r#{{ arg.name() }}: {{ arg.type_().borrow()|type_rs }}{% if !loop.last %}, {% endif %}{% endfor -%}
+            | callback.r#{{ meth.name() }}(
+            {%- for arg in meth.arguments() %}r#{{ arg.name() }}{% if !loop.last %}, {% endif %}{% endfor -%}
+            )))
+        {%- endfor %}
+        {%- else %}
         Ok(Box::new(Self { handle: v }))
+        {%- endif %}
     }
 
     fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<Self::RustType> {
@@ -195,4 +270,34 @@ unsafe impl uniffi::FfiConverter for {{ trait_impl }} {
         uniffi::check_remaining(buf, 8)?;
         <Self as uniffi::FfiConverter>::try_lift(buf.get_u64())
     }
+    {% call rs::compact_methods() %}
+}
+
+{%- if !cbi.is_function() %}
+
+// Let the functions exported with `#[uniffi::export]`, and the crates using the callback interface
+// with an `[ExternalCallbackInterface]` typedef, take `Box<dyn Trait>` arguments, with the
+// FfiConverter above.
+#[doc(hidden)]
+unsafe impl uniffi::FfiConverter for Box<dyn r#{{ trait_name }}> {
+    type RustType = Self;
+    type FfiType = u64;
+
+    fn lower(obj: Self::RustType) -> Self::FfiType {
+        <{{ trait_impl }} as uniffi::FfiConverter>::lower(obj)
+    }
+
+    fn write(obj: Self::RustType, buf: &mut std::vec::Vec<u8>) {
+        <{{ trait_impl }} as uniffi::FfiConverter>::write(obj, buf)
+    }
+
+    fn try_lift(v: Self::FfiType) -> uniffi::deps::anyhow::Result<Self::RustType> {
+        <{{ trait_impl }} as uniffi::FfiConverter>::try_lift(v)
+    }
+
+    fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<Self::RustType> {
+        <{{ trait_impl }} as uniffi::FfiConverter>::try_read(buf)
+    }
+    {% call rs::compact_methods() %}
 }
+{%- endif %}
@@ -5,6 +5,10 @@
 //
 // We define a unit-struct to implement the trait to sidestep Rust's orphan rule (ADR-0006). It's
 // public so other crates can refer to it via an `[External='crate'] typedef`
+//
+// The variants and fields are serialized with the encoding of this component, even by the `_compact`
+// methods of the converter, since the bindings of the other components use ours, see
+// `uniffi::FfiConverter`.
 #}
 
 #[doc(hidden)]
@@ -15,13 +19,12 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
     type RustType = r#{{ e.name() }};
 
     fn write(obj: Self::RustType, buf: &mut std::vec::Vec<u8>) {
-        use uniffi::deps::bytes::BufMut;
         match obj {
             {%- for variant in e.variants() %}
-            r#{{ e.name() }}::r#{{ variant.name() }} { {% for field in variant.fields() %}r#{{ field.name() }}, {%- endfor %} } => {
-                buf.put_i32({{ loop.index }});
+            r#{{ e.name() }}::r#{{ variant.rust_name() }} { {% for field in variant.fields() %}r#{{ field.rust_name() }}, {%- endfor %} } => {
+                uniffi::write_variant{{ encoding_suffix }}(buf, {{ loop.index }});
                 {% for field in variant.fields() -%}
-                {{ field.type_().borrow()|ffi_converter }}::write(r#{{ field.name() }}, buf);
+                {{ field|field_ffi_converter }}::write{{ encoding_suffix }}(r#{{ field.rust_name() }}, buf);
                 {%- endfor %}
             },
             {%- endfor %}
@@ -29,17 +32,16 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
     }
 
     fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<r#{{ e.name() }}> {
-        use uniffi::deps::bytes::Buf;
-        uniffi::check_remaining(buf, 4)?;
-        Ok(match buf.get_i32() {
+        Ok(match uniffi::read_variant{{ encoding_suffix }}(buf)? {
             {%- for variant in e.variants() %}
-            {{ loop.index }} => r#{{ e.name() }}::r#{{ variant.name() }}{% if variant.has_fields() %} {
+            {{ loop.index }} => r#{{ e.name() }}::r#{{ variant.rust_name() }}{% if variant.has_fields() %} {
                 {% for field in variant.fields() %}
-                r#{{ field.name() }}: {{ field.type_().borrow()|ffi_converter }}::try_read(buf)?,
+                r#{{ field.rust_name() }}: {{ field|field_ffi_converter }}::try_read{{ encoding_suffix }}(buf)?,
                 {%- endfor %}
             }{% endif %},
             {%- endfor %}
             v => uniffi::deps::anyhow::bail!("Invalid {{ e.name() }} enum value: {}", v),
         })
     }
+    {% call rs::rust_buffer_compact_methods() %}
 }
@@ -4,7 +4,14 @@
 // compile if the provided struct has a different shape to the one declared in the UDL.
 //
 // We define a unit-struct to implement the trait to sidestep Rust's orphan rule (ADR-0006). It's
-// public so other crates can refer to it via an `[External='crate'] typedef`
+// public so other crates can refer to it via an `[ExternalError='crate'] typedef`
+//
+// The variants and fields are serialized with the encoding of this component, even by the `_compact`
+// methods of the converter, since the bindings of the other components use ours, see
+// `uniffi::FfiConverter`.
+//
+// The errors declared with `[Source]` record the chain of their sources when they're the error of
+// the call, and the sources are sent after the error, see `uniffi::lower_error`.
 #}
 
 #[doc(hidden)]
@@ -21,13 +28,12 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
 
 
     fn write(obj: r#{{ e.name() }}, buf: &mut std::vec::Vec<u8>) {
-        use uniffi::deps::bytes::BufMut;
         let msg = obj.to_string();
         match obj {
             {%- for variant in e.variants() %}
-            r#{{ e.name() }}::r#{{ variant.name() }}{..} => {
-                buf.put_i32({{ loop.index }});
-                <String as uniffi::FfiConverter>::write(msg, buf);
+            r#{{ e.name() }}::r#{{ variant.rust_name() }}{..} => {
+                uniffi::write_variant{{ encoding_suffix }}(buf, {{ loop.index }});
+                <String as uniffi::FfiConverter>::write{{ encoding_suffix }}(msg, buf);
             },
             {%- endfor %}
         };
@@ -35,11 +41,9 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
 
     {%- if ci.should_generate_error_read(e) %}
     fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<r#{{ e.name() }}> {
-        use uniffi::deps::bytes::Buf;
-        uniffi::check_remaining(buf, 4)?;
-        Ok(match buf.get_i32() {
+        Ok(match uniffi::read_variant{{ encoding_suffix }}(buf)? {
             {%- for variant in e.variants() %}
-            {{ loop.index }} => r#{{ e.name() }}::r#{{ variant.name() }}{ },
+            {{ loop.index }} => r#{{ e.name() }}::r#{{ variant.rust_name() }}{ },
             {%- endfor %}
             v => uniffi::deps::anyhow::bail!("Invalid {{ e.name() }} enum value: {}", v),
         })
@@ -60,13 +64,12 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
     // syntax to match the variant while ignoring its fields.
 
     fn write(obj: r#{{ e.name() }}, buf: &mut std::vec::Vec<u8>) {
-        use uniffi::deps::bytes::BufMut;
         match obj {
             {%- for variant in e.variants() %}
-            r#{{ e.name() }}::r#{{ variant.name() }}{% if variant.has_fields() %} { {% for field in variant.fields() %}r#{{ field.name() }}, {%- endfor %} }{% else %}{..}{% endif %} => {
-                buf.put_i32({{ loop.index }});
+            r#{{ e.name() }}::r#{{ variant.rust_name() }}{% if variant.has_fields() %} { {% for field in variant.fields() %}r#{{ field.rust_name() }}, {%- endfor %} }{% else %}{..}{% endif %} => {
+                uniffi::write_variant{{ encoding_suffix }}(buf, {{ loop.index }});
                 {% for field in variant.fields() -%}
-                {{ field.type_().borrow()|ffi_converter }}::write(r#{{ field.name() }}, buf);
+                {{ field.type_().borrow()|ffi_converter }}::write{{ encoding_suffix }}(r#{{ field.rust_name() }}, buf);
                 {%- endfor %}
             },
             {%- endfor %}
@@ -76,13 +79,11 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
     fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<r#{{ e.name() }}> {
         // Note: no need to call should_generate_error_read here, since it is always true for
         // non-flat errors
-        use uniffi::deps::bytes::Buf;
-        uniffi::check_remaining(buf, 4)?;
-        Ok(match buf.get_i32() {
+        Ok(match uniffi::read_variant{{ encoding_suffix }}(buf)? {
             {%- for variant in e.variants() %}
-            {{ loop.index }} => r#{{ e.name() }}::r#{{ variant.name() }}{% if variant.has_fields() %} {
+            {{ loop.index }} => r#{{ e.name() }}::r#{{ variant.rust_name() }}{% if variant.has_fields() %} {
                 {% for field in variant.fields() %}
-                r#{{ field.name() }}: {{ field.type_().borrow()|ffi_converter }}::try_read(buf)?,
+                r#{{ field.rust_name() }}: {{ field.type_().borrow()|ffi_converter }}::try_read{{ encoding_suffix }}(buf)?,
                 {%- endfor %}
             }{% endif %},
             {%- endfor %}
@@ -90,6 +91,13 @@ impl uniffi::RustBufferFfiConverter for {{ e.type_().borrow()|ffi_converter_name
         })
     }
     {% endif %}
+    {% call rs::rust_buffer_compact_methods() %}
 }
 
-impl uniffi::FfiError for {{ e.type_().borrow()|ffi_converter_name }} { }
+impl uniffi::FfiError for {{ e.type_().borrow()|ffi_converter_name }} {
+    {%- if e.has_source() %}
+    fn record_sources(obj: &r#{{ e.name() }}) {
+        uniffi::record_error_sources(obj);
+    }
+    {%- endif %}
+}
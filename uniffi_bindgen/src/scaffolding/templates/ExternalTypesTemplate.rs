@@ -1,13 +1,20 @@
 // Support for external types.
 
 // Types with an external `FfiConverter`...
-{% for (name, crate_name) in ci.iter_external_types() %}
+// Callback interfaces are lifted by the `FfiConverter` of their `Box<dyn Trait>`, so that their
+// proxy doesn't have to be public.
+{% for (name, crate_name, kind) in ci.iter_external_types() %}
+{%- match kind %}
+{%- when ExternalKind::CallbackInterface %}
+{%- else %}
 // `{{ name }}` is defined in `{{ crate_name }}`
 use {{ crate_name|crate_name_rs }}::FfiConverterType{{ name }};
+{%- endmatch %}
 {% endfor %}
 
 // For custom scaffolding types we need to generate an FfiConverterType based on the
-// UniffiCustomTypeConverter implementation that the library supplies
+// UniffiCustomTypeConverter implementation that the library supplies. Like the records, the
+// builtin values are serialized with the encoding of this component.
 {% for (name, builtin) in ci.iter_custom_types() %}
 {% if loop.first %}
 
@@ -30,19 +37,20 @@ unsafe impl uniffi::FfiConverter for FfiConverterType{{ name }} {
     type FfiType = {{ FFIType::from(builtin).borrow()|type_ffi }};
 
     fn lower(obj: {{ name }} ) -> Self::FfiType {
-        <{{ builtin|type_rs }} as uniffi::FfiConverter>::lower(<{{ name }} as UniffiCustomTypeConverter>::from_custom(obj))
+        {{ builtin|ffi_converter }}::lower{{ encoding_suffix }}(<{{ name }} as UniffiCustomTypeConverter>::from_custom(obj))
     }
 
     fn try_lift(v: Self::FfiType) -> uniffi::Result<{{ name }}> {
-        <r#{{ name }} as UniffiCustomTypeConverter>::into_custom(<{{ builtin|type_rs }} as uniffi::FfiConverter>::try_lift(v)?)
+        <r#{{ name }} as UniffiCustomTypeConverter>::into_custom({{ builtin|ffi_converter }}::try_lift{{ encoding_suffix }}(v)?)
     }
 
     fn write(obj: {{ name }}, buf: &mut Vec<u8>) {
-        <{{ builtin|type_rs }} as uniffi::FfiConverter>::write(<{{ name }} as UniffiCustomTypeConverter>::from_custom(obj), buf);
+        {{ builtin|ffi_converter }}::write{{ encoding_suffix }}(<{{ name }} as UniffiCustomTypeConverter>::from_custom(obj), buf);
     }
 
     fn try_read(buf: &mut &[u8]) -> uniffi::Result<r#{{ name }}> {
-        <{{ name }} as UniffiCustomTypeConverter>::into_custom(<{{ builtin|type_rs }} as uniffi::FfiConverter>::try_read(buf)?)
+        <{{ name }} as UniffiCustomTypeConverter>::into_custom({{ builtin|ffi_converter }}::try_read{{ encoding_suffix }}(buf)?)
     }
+    {% call rs::compact_methods() %}
 }
 {%- endfor -%}
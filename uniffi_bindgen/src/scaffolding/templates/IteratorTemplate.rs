@@ -0,0 +1,33 @@
+{#
+// The iterators returned by Rust are `uniffi::FfiIterator`s, which the foreign-language code
+// advances one item at a time, and frees once it's done with them. It refers to them by handles in
+// `uniffi::OBJECT_HANDLES`, which are checked like the ones of the objects. Advancing an iterator
+// from a callback called while it produces an item fails, rather than deadlocking.
+#}
+{%- let ffi_next = ci.ffi_iterator_next(ty) %}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_next.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
+    uniffi::call_with_lowered_result::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        let iterator = match uniffi::OBJECT_HANDLES.get::<uniffi::FfiIterator<<{{ item_type|ffi_converter_name }} as uniffi::FfiConverter>::RustType>>(handle) {
+            Ok(iterator) => iterator,
+            Err(err) => return uniffi::internal_error(format!("Failed to advance the iterator: {err}")),
+        };
+        match iterator.next() {
+            Ok(item) => Ok(<std::option::Option<{{ item_type|ffi_converter_name }}> as uniffi::FfiConverter>::try_lower{{ encoding_suffix }}(item)?),
+            Err(err) => uniffi::internal_error(err.to_string()),
+        }
+    })
+}
+
+{% let ffi_free = ci.ffi_iterator_free(ty) -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_free.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        {#- an iterator being advanced on another thread is only dropped once it's done. #}
+        if let Err(err) = uniffi::OBJECT_HANDLES.remove::<uniffi::FfiIterator<<{{ item_type|ffi_converter_name }} as uniffi::FfiConverter>::RustType>>(handle) {
+            uniffi::internal_error(format!("Failed to free the iterator: {err}"))
+        }
+    })
+}
@@ -0,0 +1,106 @@
+// The JNI glue of the Kotlin bindings using the `jni` FFI backend.
+//
+// Each FFI function gets a function exported under the name of the `external` function which
+// `{{ jni_class_name }}` declares for it, which the JVM calls directly, rather than through the
+// reflection of JNA. It takes the arguments of the FFI function as numbers, and calls it through its
+// symbol, so that the functions of `#[uniffi::export]` are found too. The rest goes through the
+// scratch memory of the thread, see `uniffi::ffi::jni`.
+#[allow(non_snake_case, non_upper_case_globals, clippy::too_many_arguments)]
+mod uniffi_jni {
+    use super::*;
+
+    extern "C" {
+        fn r#{{ ci.ffi_contract_version_name() }}() -> u32;
+        fn r#{{ ci.ffi_install_log_forwarder_name() }}(callback: uniffi::ForeignLogCallback, max_level: i32, call_status: &mut uniffi::RustCallStatus) -> i8;
+        {%- for func in ci.iter_ffi_function_definitions() %}
+        fn r#{{ func.name() }}({% call rs::arg_list_ffi_decl(func) %}){% match func.return_type() %}{% when Some with (return_type) %} -> {{ return_type|type_ffi }}{% when None %}{% endmatch %};
+        {%- endfor %}
+    }
+
+    thread_local! {
+        static SCRATCH: uniffi::ffi::jni::JniScratch = const { uniffi::ffi::jni::JniScratch::new() };
+    }
+
+    #[doc(hidden)]
+    #[no_mangle]
+    pub extern "system" fn {{ "uniffi_scratch"|jni_symbol(jni_class_name) }}(_env: uniffi::ffi::jni::JniEnv, _this: uniffi::ffi::jni::JniObject, size: i32) -> uniffi::ffi::jni::JniObject {
+        SCRATCH.with(|scratch| unsafe { scratch.buffer(_env, size) })
+    }
+
+    #[doc(hidden)]
+    #[no_mangle]
+    pub extern "system" fn {{ "uniffi_bytes"|jni_symbol(jni_class_name) }}(_env: uniffi::ffi::jni::JniEnv, _this: uniffi::ffi::jni::JniObject, data: i64, len: i64) -> uniffi::ffi::jni::JniObject {
+        unsafe { uniffi::ffi::jni::new_direct_byte_buffer(_env, data as usize as *mut u8, len as usize) }
+    }
+
+    #[doc(hidden)]
+    #[no_mangle]
+    pub extern "system" fn {{ ci.ffi_contract_version_name()|jni_symbol(jni_class_name) }}(_env: uniffi::ffi::jni::JniEnv, _this: uniffi::ffi::jni::JniObject) -> i32 {
+        unsafe { r#{{ ci.ffi_contract_version_name() }}() as i32 }
+    }
+
+    // The log forwarder, which calls `uniffiJniLog(level, target, targetLen, message, messageLen)`.
+    static LOG_METHOD: uniffi::ffi::jni::JniStaticMethod = uniffi::ffi::jni::JniStaticMethod::new("uniffiJniLog\0", "(IJIJI)V\0");
+
+    unsafe extern "C" fn log_callback(level: i32, target: *const u8, target_len: i32, message: *const u8, message_len: i32) {
+        uniffi::ffi::jni::call_log_callback(&LOG_METHOD, level, target, target_len, message, message_len)
+    }
+
+    #[doc(hidden)]
+    #[no_mangle]
+    pub extern "system" fn {{ ci.ffi_install_log_forwarder_name()|jni_symbol(jni_class_name) }}(_env: uniffi::ffi::jni::JniEnv, _this: uniffi::ffi::jni::JniObject, max_level: i32) -> i8 {
+        unsafe {
+            LOG_METHOD.resolve(_env, _this);
+            let scratch = SCRATCH.with(uniffi::ffi::jni::JniScratch::as_ptr);
+            let mut call_status = uniffi::RustCallStatus::default();
+            let uniffi_result = r#{{ ci.ffi_install_log_forwarder_name() }}(log_callback, max_level, &mut call_status);
+            uniffi::ffi::jni::write_call_status(scratch, call_status);
+            uniffi_result
+        }
+    }
+    {%- for cbi in ci.callback_interface_definitions() %}
+    {%- let init_fn = cbi.ffi_init_callback() %}
+
+    // The callback of `{{ cbi.name() }}`, which calls `jni_callback_{{ init_fn.name() }}(handle,
+    // method, argsCapacity, argsLen, argsData)`.
+    static {{ init_fn.name()|jni_callback_method }}: uniffi::ffi::jni::JniStaticMethod = uniffi::ffi::jni::JniStaticMethod::new("jni_callback_{{ init_fn.name() }}\0", "(JIIIJ)I\0");
+
+    unsafe extern "C" fn {{ init_fn.name()|jni_callback }}(handle: u64, method: u32, args: uniffi::RustBuffer, buf_ptr: *mut uniffi::RustBuffer) -> std::os::raw::c_int {
+        SCRATCH.with(|scratch| uniffi::ffi::jni::call_foreign_callback(&{{ init_fn.name()|jni_callback_method }}, scratch, handle, method, args, buf_ptr))
+    }
+    {%- endfor %}
+    {%- for rec in ci.record_definitions() %}
+    {%- if rec.is_ffi_struct() %}
+    {%- let ffi_struct = FFIType::Struct(rec.name().to_string()) %}
+
+    // The fields of the struct are in slots of the scratch memory, in their order.
+    impl {{ ffi_struct|type_ffi }} {
+        pub(super) const JNI_SIZE: usize = {{ rec.fields().len() }} * uniffi::ffi::jni::SCRATCH_SLOT_SIZE;
+
+        pub(super) unsafe fn jni_read(scratch: *mut u8, offset: usize) -> Self {
+            Self {
+                {%- for field in rec.fields() %}
+                r#{{ field.rust_name() }}: uniffi::ffi::jni::read_slot(scratch, offset, {{ loop.index0 }}),
+                {%- endfor %}
+            }
+        }
+
+        pub(super) unsafe fn jni_write(self, scratch: *mut u8, offset: usize) {
+            {%- for field in rec.fields() %}
+            uniffi::ffi::jni::write_slot(scratch, offset, {{ loop.index0 }}, self.r#{{ field.rust_name() }});
+            {%- endfor %}
+        }
+    }
+    {%- endif %}
+    {%- endfor %}
+    {%- for func in ci.iter_ffi_function_definitions() %}
+
+    #[doc(hidden)]
+    #[no_mangle]
+    pub extern "system" fn {{ func.name()|jni_symbol(jni_class_name) }}({{ func|jni_arg_list_decl }}){{ func|jni_return_type }} {
+        unsafe {
+            {{ func|jni_call }}
+        }
+    }
+    {%- endfor %}
+}
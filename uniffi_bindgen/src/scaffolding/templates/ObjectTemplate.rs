@@ -1,10 +1,13 @@
 // For each Object definition, we assume the caller has provided an appropriately-shaped `struct T`
-// with an `impl` for each method on the object. We create an `Arc<T>` for "safely" handing out
-// references to these structs to foreign language code, and we provide a `pub extern "C"` function
-// corresponding to each method.
+// with an `impl` for each method on the object. We create an `Arc<T>` for safely handing out
+// references to these structs to foreign language code, which gets a handle to it in
+// `uniffi::OBJECT_HANDLES`, and we provide a `pub extern "C"` function corresponding to each method.
 //
-// (Note that "safely" is in "scare quotes" - that's because we use functions on an `Arc` that
-// that are inherently unsafe, but the code we generate is safe in practice.)
+// For `[Trait]` interfaces, we assume a `trait T` instead, and hand out `Arc<dyn T>`s. As these
+// can't be stored in the map as they are, the handles refer to an `Arc<Arc<dyn T>>` instead.
+//
+// The handles are checked whenever they're used, so that a stale or corrupted handle is reported
+// as an error rather than being dereferenced.
 //
 // If the caller's implementation of the struct does not match with the methods or types specified
 // in the UDL, then the rust compiler will complain with a (hopefully at least somewhat helpful!)
@@ -29,24 +32,92 @@ fn uniffi_note_threadsafe_deprecation_{{ obj.name() }}() {}
 // if they are not, but unfortunately it fails with an unactionably obscure error message.
 // By asserting the requirement explicitly, we help Rust produce a more scrutable error message
 // and thus help the user debug why the requirement isn't being met.
-uniffi::deps::static_assertions::assert_impl_all!(r#{{ obj.name() }}: Sync, Send);
+uniffi::deps::static_assertions::assert_impl_all!({{ obj.rust_name() }}: Sync, Send);
 
 {% let ffi_free = obj.ffi_object_free() -%}
 #[doc(hidden)]
 #[no_mangle]
-pub extern "C" fn {{ ffi_free.name() }}(ptr: *const std::os::raw::c_void, call_status: &mut uniffi::RustCallStatus) {
-    uniffi::call_with_output(call_status, || {
-        assert!(!ptr.is_null());
-        {#- turn it into an Arc and explicitly drop it. #}
-        drop(unsafe { std::sync::Arc::from_raw(ptr as *const r#{{ obj.name() }}) })
+pub extern "C" fn {{ ffi_free.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        {#- the reference owned by the handle is dropped with it. #}
+        {%- match obj.imp() %}
+        {%- when ObjectImpl::Struct %}
+        let result = uniffi::OBJECT_HANDLES.remove::<r#{{ obj.name() }}>(handle);
+        {%- when ObjectImpl::Trait %}
+        let result = uniffi::OBJECT_HANDLES.remove::<std::sync::Arc<dyn r#{{ obj.name() }}>>(handle);
+        {%- endmatch %}
+        if let Err(err) = result {
+            uniffi::internal_error(format!("Failed to free {{ obj.name() }}: {err}"))
+        }
     })
 }
 
+{% let ffi_clone = obj.ffi_object_clone() -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_clone.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) -> u64 {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        {#- the foreign-language code owns the new handle, referring to the same object. #}
+        {%- match obj.imp() %}
+        {%- when ObjectImpl::Struct %}
+        let result = uniffi::OBJECT_HANDLES.clone_handle::<r#{{ obj.name() }}>(handle);
+        {%- when ObjectImpl::Trait %}
+        let result = uniffi::OBJECT_HANDLES.clone_handle::<std::sync::Arc<dyn r#{{ obj.name() }}>>(handle);
+        {%- endmatch %}
+        result.unwrap_or_else(|err| uniffi::internal_error(format!("Failed to clone {{ obj.name() }}: {err}")))
+    })
+}
+
+{%- if obj.is_referenced_weakly() %}
+
+// The weak references handed out to the foreign-language code are handles to `Weak<T>`s, for
+// which it can ask the object, if it's still alive. They're checked like the handles of the objects.
+{% let ffi_weak_free = obj.ffi_weak_free() -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_weak_free.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        if let Err(err) = uniffi::OBJECT_HANDLES.remove::<std::sync::Weak<r#{{ obj.name() }}>>(handle) {
+            uniffi::internal_error(format!("Failed to free the weak reference to {{ obj.name() }}: {err}"))
+        }
+    })
+}
+
+{% let ffi_weak_clone = obj.ffi_weak_clone() -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_weak_clone.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) -> u64 {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        uniffi::OBJECT_HANDLES.clone_handle::<std::sync::Weak<r#{{ obj.name() }}>>(handle)
+            .unwrap_or_else(|err| uniffi::internal_error(format!("Failed to clone the weak reference to {{ obj.name() }}: {err}")))
+    })
+}
+
+{% let ffi_weak_upgrade = obj.ffi_weak_upgrade() -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_weak_upgrade.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        match <std::sync::Weak<r#{{ obj.name() }}> as uniffi::FfiConverter>::try_lift(handle) {
+            Ok(weak) => <std::option::Option<std::sync::Arc<r#{{ obj.name() }}>> as uniffi::FfiConverter>::lower{{ encoding_suffix }}(weak.upgrade()),
+            Err(err) => uniffi::internal_error(format!("Failed to upgrade the weak reference to {{ obj.name() }}: {err}")),
+        }
+    })
+}
+{%- endif %}
+
+{%- if obj.imp() == ObjectImpl::Trait %}
+
+// The handles of the `Arc<dyn Trait>`s refer to an `Arc` of their own, freed by `{{ ffi_free.name() }}()`.
+#[doc(hidden)]
+pub type {{ obj.type_().borrow()|ffi_converter_name }} = uniffi::FfiConverterArcDyn<dyn r#{{ obj.name() }}>;
+{%- endif %}
+
 {%- for cons in obj.constructors() %}
     #[doc(hidden)]
     #[no_mangle]
     pub extern "C" fn r#{{ cons.ffi_func().name() }}(
-        {%- call rs::arg_list_ffi_decl(cons.ffi_func()) %}) -> *const std::os::raw::c_void /* *const {{ obj.name() }} */ {
+        {%- call rs::arg_list_ffi_decl(cons.ffi_func()) %}) -> u64 /* handle of {{ obj.name() }} */ {
         uniffi::deps::log::debug!("{{ cons.ffi_func().name() }}");
         {% if obj.uses_deprecated_threadsafe_attribute() %}
         uniffi_note_threadsafe_deprecation_{{ obj.name() }}();
@@ -58,7 +129,7 @@ pub extern "C" fn {{ ffi_free.name() }}(ptr: *const std::os::raw::c_void, call_s
     }
 {%- endfor %}
 
-{%- for meth in obj.methods() %}
+{%- for meth in obj.all_methods() %}
     #[doc(hidden)]
     #[no_mangle]
     #[allow(clippy::let_unit_value)] // Sometimes we generate code that binds `_retval` to `()`.
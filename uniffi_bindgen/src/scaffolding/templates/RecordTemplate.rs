@@ -6,10 +6,73 @@
 //
 // We define a unit-struct to implement the trait to sidestep Rust's orphan rule (ADR-0006). It's
 // public so other crates can refer to it via an `[External='crate'] typedef`
+//
+// The fields are serialized with the encoding of this component, even by the `_compact` methods of
+// the converter, since the bindings of the other components use ours, see `uniffi::FfiConverter`.
 #}
 
 #[doc(hidden)]
 pub struct {{ rec.type_().borrow()|ffi_converter_name }};
+{%- if rec.is_ffi_struct() %}
+{%- let ffi_struct = FFIType::Struct(rec.name().to_string()) %}
+
+// The record is made only of numbers and booleans, so it's passed as a C struct of the FFI values
+// of its fields, rather than serialized. It's still serialized inside other values.
+#[doc(hidden)]
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+#[allow(non_snake_case)]
+pub struct {{ ffi_struct|type_ffi }} {
+    {%- for field in rec.fields() %}
+    pub r#{{ field.rust_name() }}: {{ FFIType::from(field.type_()).borrow()|type_ffi }},
+    {%- endfor %}
+}
+
+// The value returned alongside an error, made of the default values of the fields.
+#[doc(hidden)]
+impl uniffi::ffi::ffidefault::FfiDefault for {{ ffi_struct|type_ffi }} {
+    fn ffi_default() -> Self {
+        Self::default()
+    }
+}
+
+#[doc(hidden)]
+unsafe impl uniffi::FfiConverter for {{ rec.type_().borrow()|ffi_converter_name }} {
+    type RustType = r#{{ rec.name() }};
+    type FfiType = {{ ffi_struct|type_ffi }};
+
+    fn lower(obj: r#{{ rec.name() }}) -> Self::FfiType {
+        {{ ffi_struct|type_ffi }} {
+            {%- for field in rec.fields() %}
+            r#{{ field.rust_name() }}: {{ field|field_ffi_converter }}::lower{{ encoding_suffix }}(obj.r#{{ field.rust_name() }}),
+            {%- endfor %}
+        }
+    }
+
+    fn try_lift(v: Self::FfiType) -> uniffi::deps::anyhow::Result<r#{{ rec.name() }}> {
+        Ok(r#{{ rec.name() }} {
+            {%- for field in rec.fields() %}
+            r#{{ field.rust_name() }}: {{ field|field_ffi_converter }}::try_lift{{ encoding_suffix }}(v.r#{{ field.rust_name() }})?,
+            {%- endfor %}
+        })
+    }
+
+    fn write(obj: r#{{ rec.name() }}, buf: &mut std::vec::Vec<u8>) {
+        {%- for field in rec.fields() %}
+        {{ field|field_ffi_converter }}::write{{ encoding_suffix }}(obj.r#{{ field.rust_name() }}, buf);
+        {%- endfor %}
+    }
+
+    fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<r#{{ rec.name() }}> {
+        Ok(r#{{ rec.name() }} {
+            {%- for field in rec.fields() %}
+            r#{{ field.rust_name() }}: {{ field|field_ffi_converter }}::try_read{{ encoding_suffix }}(buf)?,
+            {%- endfor %}
+        })
+    }
+    {% call rs::compact_methods() %}
+}
+{%- else %}
 
 #[doc(hidden)]
 impl uniffi::RustBufferFfiConverter for {{ rec.type_().borrow()|ffi_converter_name }} {
@@ -19,15 +82,17 @@ impl uniffi::RustBufferFfiConverter for {{ rec.type_().borrow()|ffi_converter_na
         // If the provided struct doesn't match the fields declared in the UDL, then
         // the generated code here will fail to compile with somewhat helpful error.
         {%- for field in rec.fields() %}
-        {{ field.type_().borrow()|ffi_converter }}::write(obj.r#{{ field.name() }}, buf);
+        {{ field|field_ffi_converter }}::write{{ encoding_suffix }}(obj.r#{{ field.rust_name() }}, buf);
         {%- endfor %}
     }
 
     fn try_read(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<r#{{ rec.name() }}> {
         Ok(r#{{ rec.name() }} {
             {%- for field in rec.fields() %}
-                r#{{ field.name() }}: {{ field.type_().borrow()|ffi_converter }}::try_read(buf)?,
+                r#{{ field.rust_name() }}: {{ field|field_ffi_converter }}::try_read{{ encoding_suffix }}(buf)?,
             {%- endfor %}
         })
     }
+    {% call rs::rust_buffer_compact_methods() %}
 }
+{%- endif %}
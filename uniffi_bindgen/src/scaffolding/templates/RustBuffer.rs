@@ -1,3 +1,11 @@
+// The version of the contract with the bindings, which they check when loading the library, see
+// `ComponentInterface::contract_version` in `uniffi_bindgen`.
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ci.ffi_contract_version_name() }}() -> u32 {
+    {{ ci.contract_version() }}
+}
+
 // Everybody gets basic buffer support, since it's needed for passing complex types over the FFI.
 //
 // See `uniffi/src/ffi/rustbuffer.rs` for documentation on these functions
@@ -5,23 +13,56 @@
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub extern "C" fn {{ ci.ffi_rustbuffer_alloc().name() }}(size: i32, call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
-    uniffi::ffi::uniffi_rustbuffer_alloc(size, call_status)
+    uniffi::ffi::uniffi_rustbuffer_alloc::<UNIFFI_COMPACT_ENCODING>(size, call_status)
 }
 
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub unsafe extern "C" fn {{ ci.ffi_rustbuffer_from_bytes().name() }}(bytes: uniffi::ForeignBytes, call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
-    uniffi::ffi::uniffi_rustbuffer_from_bytes(bytes, call_status)
+    uniffi::ffi::uniffi_rustbuffer_from_bytes::<UNIFFI_COMPACT_ENCODING>(bytes, call_status)
 }
 
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub unsafe extern "C" fn {{ ci.ffi_rustbuffer_free().name() }}(buf: uniffi::RustBuffer, call_status: &mut uniffi::RustCallStatus) {
-    uniffi::ffi::uniffi_rustbuffer_free(buf, call_status)
+    uniffi::ffi::uniffi_rustbuffer_free::<UNIFFI_COMPACT_ENCODING>(buf, call_status)
 }
 
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub unsafe extern "C" fn {{ ci.ffi_rustbuffer_reserve().name() }}(buf: uniffi::RustBuffer, additional: i32, call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
-    uniffi::ffi::uniffi_rustbuffer_reserve(buf, additional, call_status)
+    uniffi::ffi::uniffi_rustbuffer_reserve::<UNIFFI_COMPACT_ENCODING>(buf, additional, call_status)
+}
+
+// See `uniffi/src/ffi/bufferlimits.rs` for documentation on this function.
+#[no_mangle]
+pub extern "C" fn {{ ci.ffi_rustbuffer_stats().name() }}(call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
+    uniffi::ffi::uniffi_rustbuffer_stats::<UNIFFI_COMPACT_ENCODING>(call_status)
+}
+
+// See `uniffi/src/ffi/bufferalloc.rs` for documentation on this function.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn {{ ci.ffi_rustbuffer_set_allocator_name() }}(allocator: uniffi::RustBufferAllocator, call_status: &mut uniffi::RustCallStatus) -> i8 {
+    uniffi::ffi::uniffi_rustbuffer_set_allocator::<UNIFFI_COMPACT_ENCODING>(allocator, call_status)
+}
+
+// See `uniffi/src/ffi/foreignlog.rs` for documentation on this function.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub extern "C" fn {{ ci.ffi_install_log_forwarder_name() }}(callback: uniffi::ForeignLogCallback, max_level: i32, call_status: &mut uniffi::RustCallStatus) -> i8 {
+    uniffi::ffi::uniffi_install_log_forwarder::<UNIFFI_COMPACT_ENCODING>(callback, max_level, call_status)
+}
+
+// See `uniffi/src/ffi/leakdetection.rs` for documentation on this function.
+#[no_mangle]
+pub extern "C" fn {{ ci.ffi_dump_live_allocations().name() }}(call_status: &mut uniffi::RustCallStatus) -> uniffi::RustBuffer {
+    {%- if ci.utf16_strings() %}
+    // The bindings lift the description like the other strings of the component.
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        <UniffiStringConverter as uniffi::FfiConverter>::lower{{ encoding_suffix }}(uniffi::ffi::dump_live_allocations())
+    })
+    {%- else %}
+    uniffi::ffi::uniffi_dump_live_allocations::<UNIFFI_COMPACT_ENCODING>(call_status)
+    {%- endif %}
 }
@@ -0,0 +1,35 @@
+{#
+// The shared slices returned by Rust are `uniffi::SharedSlice`s, whose bytes the foreign-language
+// code reads in place until it releases them. It refers to them by handles in
+// `uniffi::OBJECT_HANDLES`, which are checked like the ones of the objects.
+#}
+{%- let ffi_data = ci.ffi_shared_slice_data() %}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_data.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) -> *const std::os::raw::c_void {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        uniffi::shared_slice_data(handle)
+            .unwrap_or_else(|err| uniffi::internal_error(format!("Failed to read the shared slice: {err}")))
+    })
+}
+
+{% let ffi_len = ci.ffi_shared_slice_len() -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_len.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) -> u64 {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        uniffi::shared_slice_len(handle)
+            .unwrap_or_else(|err| uniffi::internal_error(format!("Failed to read the shared slice: {err}")))
+    })
+}
+
+{% let ffi_free = ci.ffi_shared_slice_free() -%}
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn {{ ffi_free.name() }}(handle: u64, call_status: &mut uniffi::RustCallStatus) {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        if let Err(err) = uniffi::shared_slice_free(handle) {
+            uniffi::internal_error(format!("Failed to free the shared slice: {err}"))
+        }
+    })
+}
@@ -0,0 +1,5 @@
+// The UDL definitions of the component, among the metadata of the proc-macros, from which
+// `uniffi-bindgen generate --library` generates the bindings without the sources of the crate.
+#[doc(hidden)]
+#[no_mangle]
+pub static {{ ci.udl_metadata_symbol_name() }}: [u8; {{ udl_metadata.len() }}] = *{{ udl_metadata|byte_string }};
@@ -8,22 +8,28 @@ r#{{ func.name() }}({% call _arg_list_rs_call(func) -%})
 
 {%- macro _arg_list_rs_call(func) %}
     {%- for arg in func.full_arguments() %}
-        match {{- arg.type_().borrow()|ffi_converter }}::try_lift(r#{{ arg.name() }}) {
+        match {{- arg.type_().borrow()|ffi_converter }}::try_lift{{ encoding_suffix }}(r#{{ arg.name() }}) {
         {%- if arg.by_ref() %}
+        {%- match arg.type_() %}
+        {%- when Type::Object { name, imp: ObjectImpl::Trait } %}
+            {#- `&Arc<dyn Trait>` would be unsized rather than dereferenced to `&dyn Trait`. #}
+            Ok(ref val) => &**val,
+        {%- else %}
             Ok(ref val) => val,
+        {%- endmatch %}
         {%- else %}
             Ok(val) => val,
         {%- endif %}
 
         {#- If this function returns an error, we attempt to downcast errors doing arg
             conversions to this error. If the downcast fails or the function doesn't
-            return an error, we just panic.
+            return an error, we fail the call with an internal error.
         -#}
         {%- match func.throws_type() -%}
         {%- when Some with (e) %}
-            Err(err) => return Err(uniffi::lower_anyhow_error_or_panic::<{{ e|ffi_converter_name }}>(err, "{{ arg.name() }}")),
+            Err(err) => return Err(uniffi::lower_anyhow_error_or_internal_error::<{{ e|ffi_converter_name }}>(err, "{{ arg.name() }}").into()),
         {%- else %}
-            Err(err) => panic!("Failed to convert arg '{}': {}", "{{ arg.name() }}", err),
+            Err(err) => return uniffi::internal_error(format!("Failed to convert arg '{}': {}", "{{ arg.name() }}", err)),
         {%- endmatch %}
         }
         {%- if !loop.last %},{% endif %}
@@ -50,69 +56,128 @@ r#{{ func.name() }}({% call _arg_list_rs_call(func) -%})
     {%- endif %}
 {%- endmacro -%}
 
+{#- The return type of a callback method, a `Result` when it throws. #}
+{% macro callback_return_type(meth) %}
+    {%- match (meth.return_type(), meth.throws_type()) %}
+    {%- when (Some(return_type), None) %} -> {{ return_type.borrow()|type_rs }}
+    {%- when (Some(return_type), Some(err)) %} -> ::std::result::Result<{{ return_type.borrow()|type_rs }}, {{ err|type_rs }}>
+    {%- when (None, Some(err)) %} -> ::std::result::Result<(), {{ err|type_rs }}>
+    {% else -%}
+    {%- endmatch -%}
+{%- endmacro -%}
+
 {% macro return_signature(func) %}{% match func.ffi_func().return_type() %}{% when Some with (return_type) %} -> {% call return_type_func(func) %}{%- else -%}{%- endmatch -%}{%- endmacro -%}
 
 {% macro return_type_func(func) %}{% match func.ffi_func().return_type() %}{% when Some with (return_type) %}{{ return_type|type_ffi }}{%- else -%}(){%- endmatch -%}{%- endmacro -%}
 
-{% macro ret(func) %}{% match func.return_type() %}{% when Some with (return_type) %}{{ return_type|ffi_converter }}::lower(_retval){% else %}_retval{% endmatch %}{% endmacro %}
+{% macro ret(func) %}{% match func.return_type() %}{% when Some with (return_type) %}{% call lower_return(return_type, "_retval") %}{% else %}_retval{% endmatch %}{% endmacro %}
+
+{#- Iterators are returned as any `impl Iterator`, which their converter lowers boxed. The call fails if the return value goes over the `RustBuffer` limits. #}
+{% macro lower_return(return_type, retval) %}{% match return_type %}{% when Type::Iterator(item_type) %}{{ return_type|ffi_converter }}::try_lower{{ encoding_suffix }}(Box::new({{ retval }}))?{% else %}{{ return_type|ffi_converter }}::try_lower{{ encoding_suffix }}({{ retval }})?{% endmatch %}{% endmacro %}
+
+{#- Functions which only throw the errors of the custom types of their arguments don't return a `Result`. #}
+{% macro lower_err(func, e) %}{% if !func.throws_from_arguments() %}.map_err(Into::into).map_err(uniffi::lower_error{{ encoding_suffix }}::<{{ e|ffi_converter_name }}>)?{% endif %}{% endmacro %}
 
 {% macro construct(obj, cons) %}
     r#{{- obj.name() }}::{% call to_rs_call(cons) -%}
 {% endmacro %}
 
 {% macro to_rs_constructor_call(obj, cons) %}
+uniffi::instrument_call("{{ cons.ffi_func().name() }}", call_status, |call_status| {
 {% match cons.throws_type() %}
 {% when Some with (e) %}
-    uniffi::call_with_result(call_status, || {
-        let _new = {% call construct(obj, cons) %}.map_err(Into::into).map_err({{ e|ffi_converter }}::lower)?;
+    uniffi::call_with_lowered_result::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+        let _new = {% call construct(obj, cons) %}{% call lower_err(cons, e) %};
         let _arc = std::sync::Arc::new(_new);
-        Ok({{ obj.type_().borrow()|ffi_converter }}::lower(_arc))
+        Ok({{ obj.type_().borrow()|ffi_converter }}::lower{{ encoding_suffix }}(_arc))
     })
 {% else %}
-    uniffi::call_with_output(call_status, || {
+    uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
         let _new = {% call construct(obj, cons) %};
         let _arc = std::sync::Arc::new(_new);
-        {{ obj.type_().borrow()|ffi_converter }}::lower(_arc)
+        {{ obj.type_().borrow()|ffi_converter }}::lower{{ encoding_suffix }}(_arc)
     })
 {% endmatch %}
+})
 {% endmacro %}
 
 {% macro to_rs_method_call(obj, meth) -%}
+uniffi::instrument_call("{{ meth.ffi_func().name() }}", call_status, |call_status| {
 {% match meth.throws_type() -%}
 {% when Some with (e) -%}
-uniffi::call_with_result(call_status, || {
-    let _retval =  r#{{ obj.name() }}::{% call to_rs_call(meth) %}.map_err(Into::into).map_err({{ e|ffi_converter }}::lower)?;
+uniffi::call_with_lowered_result::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+    let _retval =  r#{{ obj.name() }}::{% call to_rs_call(meth) %}{% call lower_err(meth, e) %};
     Ok({% call ret(meth) %})
 })
 {% else %}
-uniffi::call_with_output(call_status, || {
-    {% match meth.return_type() -%}
-    {% when Some with (return_type) -%}
+{% match meth.return_type() -%}
+{% when Some with (return_type) -%}
+uniffi::call_with_lowered_result::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
     let retval = r#{{ obj.name() }}::{% call to_rs_call(meth) %};
-    {{ return_type|ffi_converter }}::lower(retval)
-    {% else -%}
+    Ok({% call lower_return(return_type, "retval") %})
+})
+{% else -%}
+uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
     r#{{ obj.name() }}::{% call to_rs_call(meth) %}
-    {% endmatch -%}
 })
 {% endmatch -%}
+{% endmatch -%}
+})
 {% endmacro -%}
 
+{#- Methods of records and enums are associated functions, which get their receiver first. #}
+{% macro self_type_path(func) %}{% match func.self_type_name() %}{% when Some with (type_name) %}r#{{ type_name }}::{% else %}{% endmatch %}{% endmacro %}
+
 {% macro to_rs_function_call(func) %}
+uniffi::instrument_call("{{ func.ffi_func().name() }}", call_status, |call_status| {
 {% match func.throws_type() %}
 {% when Some with (e) %}
-uniffi::call_with_result(call_status, || {
-    let _retval = {% call to_rs_call(func) %}.map_err(Into::into).map_err({{ e|ffi_converter }}::lower)?;
+uniffi::call_with_lowered_result::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+    let _retval = {% call self_type_path(func) %}{% call to_rs_call(func) %}{% call lower_err(func, e) %};
     Ok({% call ret(func) %})
 })
 {% else %}
-uniffi::call_with_output(call_status, || {
-    {% match func.return_type() -%}
-    {% when Some with (return_type) -%}
-    {{ return_type|ffi_converter }}::lower({% call to_rs_call(func) %})
-    {% else -%}
+{% match func.return_type() -%}
+{% when Some with (return_type) -%}
+uniffi::call_with_lowered_result::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
+    let retval = {% call self_type_path(func) %}{% call to_rs_call(func) %};
+    Ok({% call lower_return(return_type, "retval") %})
+})
+{% else -%}
+uniffi::call_with_output::<UNIFFI_COMPACT_ENCODING, _, _>(call_status, || {
     {% if func.full_arguments().is_empty() %}#[allow(clippy::redundant_closure)]{% endif %}
-    {% call to_rs_call(func) %}
-    {% endmatch -%}
+    {% call self_type_path(func) %}{% call to_rs_call(func) %}
 })
+{% endmatch -%}
 {% endmatch %}
+})
 {% endmacro %}
+
+{#- The `_compact` methods of the converters of the component, which use the methods of its encoding, see `uniffi::FfiConverter`. #}
+{% macro compact_methods() %}
+    fn lower_compact(obj: Self::RustType) -> Self::FfiType {
+        <Self as uniffi::FfiConverter>::lower(obj)
+    }
+
+    fn try_lift_compact(v: Self::FfiType) -> uniffi::deps::anyhow::Result<Self::RustType> {
+        <Self as uniffi::FfiConverter>::try_lift(v)
+    }
+
+    fn write_compact(obj: Self::RustType, buf: &mut std::vec::Vec<u8>) {
+        <Self as uniffi::FfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<Self::RustType> {
+        <Self as uniffi::FfiConverter>::try_read(buf)
+    }
+{%- endmacro %}
+
+{% macro rust_buffer_compact_methods() %}
+    fn write_compact(obj: Self::RustType, buf: &mut std::vec::Vec<u8>) {
+        <Self as uniffi::RustBufferFfiConverter>::write(obj, buf)
+    }
+
+    fn try_read_compact(buf: &mut &[u8]) -> uniffi::deps::anyhow::Result<Self::RustType> {
+        <Self as uniffi::RustBufferFfiConverter>::try_read(buf)
+    }
+{%- endmacro %}
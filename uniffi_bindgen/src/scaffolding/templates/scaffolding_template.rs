@@ -8,6 +8,18 @@
 // seems to show that single line as context for the user.
 uniffi::assert_compatible_version!("{{ uniffi_version }}"); // Please check that you depend on version {{ uniffi_version }} of the `uniffi` crate.
 
+// The encoding of the values of the component, which is the one of its bindings: the values are
+// serialized with it in the FFI functions and the converters of the component, see
+// `uniffi::FfiConverter`. It's the `compact_encoding` setting of `uniffi.toml`, which the
+// proc-macros read too.
+#[doc(hidden)]
+pub(crate) const UNIFFI_COMPACT_ENCODING: bool = {{ ci.compact_encoding() }};
+
+// The `FfiConverter` of the strings, which are passed as UTF-8 or, with the `[Utf16Strings]`
+// attribute of the namespace, as UTF-16.
+#[allow(dead_code)]
+type UniffiStringConverter = {% if ci.utf16_strings() %}uniffi::Utf16String{% else %}String{% endif %};
+
 {% for ty in ci.iter_types() %}
 {%- match ty %}
 {%- when Type::Map with (k, v) -%}
@@ -19,6 +31,8 @@ uniffi::deps::static_assertions::assert_impl_all!({{ k|type_rs }}: ::std::cmp::E
 
 {% include "RustBuffer.rs" %}
 
+{% include "UdlMetadata.rs" %}
+
 // Error definitions, corresponding to `error` in the UDL.
 {% for e in ci.error_definitions() %}
 {% include "ErrorTemplate.rs" %}
@@ -34,8 +48,9 @@ uniffi::deps::static_assertions::assert_impl_all!({{ k|type_rs }}: ::std::cmp::E
 {% include "RecordTemplate.rs" %}
 {% endfor %}
 
-// Top level functions, corresponding to UDL `namespace` functions.
-{%- for func in ci.function_definitions() %}
+// Top level functions, corresponding to UDL `namespace` functions, including the methods of
+// records and enums.
+{%- for func in ci.all_function_definitions() %}
 {% include "TopLevelFunctionTemplate.rs" %}
 {% endfor -%}
 
@@ -44,6 +59,21 @@ uniffi::deps::static_assertions::assert_impl_all!({{ k|type_rs }}: ::std::cmp::E
 {% include "ObjectTemplate.rs" %}
 {% endfor %}
 
+// Iterators returned by functions and methods, corresponding to `iterator` in the UDL.
+{% for ty in ci.iter_iterator_types() %}
+{%- match ty %}
+{%- when Type::Iterator(item_type) %}
+{% include "IteratorTemplate.rs" %}
+{%- else %}
+{%- endmatch %}
+{% endfor %}
+
+{%- if ci.uses_shared_slices() %}
+
+// Shared slices returned by functions and methods, corresponding to `shared_slice` in the UDL.
+{% include "SharedSliceTemplate.rs" %}
+{%- endif %}
+
 // Callback Interface definitions, corresponding to UDL `callback interface` definitions.
 {% for cbi in ci.callback_interface_definitions() %}
 {% include "CallbackInterfaceTemplate.rs" %}
@@ -51,6 +81,12 @@ uniffi::deps::static_assertions::assert_impl_all!({{ k|type_rs }}: ::std::cmp::E
 
 // External and Wrapped types
 {% include "ExternalTypesTemplate.rs" %}
+{%- match jni_class_name %}
+{%- when Some with (jni_class_name) %}
+
+{% include "JniTemplate.rs" %}
+{%- when None %}
+{%- endmatch %}
 
 // The `reexport_uniffi_scaffolding` macro
 {% include "ReexportUniFFIScaffolding.rs" %}
@@ -0,0 +1,202 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Find the UniFFI components of a cargo workspace, for `cargo uniffi`.
+//!
+//! A package of the workspace is a component when it depends on `uniffi` and has a cdylib
+//! target. Its UDL files are the ones in its `src` directory, like for the scaffolding, and the
+//! languages to generate its bindings for can be set in its manifest:
+//!
+//! ```toml
+//! [package.metadata.uniffi.bindings]
+//! languages = ["kotlin", "swift"]
+//! ```
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Deserialize;
+
+/// A UniFFI component of the workspace.
+#[derive(Debug)]
+pub(crate) struct WorkspaceComponent {
+    /// The name of the package.
+    pub(crate) package: String,
+    /// The id of the package, which cargo identifies its build artifacts with.
+    pub(crate) package_id: String,
+    pub(crate) crate_root: Utf8PathBuf,
+    /// The UDL files of the component, empty when it only uses the proc-macros.
+    pub(crate) udl_files: Vec<Utf8PathBuf>,
+    /// The languages set in `[package.metadata.uniffi.bindings]`.
+    pub(crate) languages: Vec<String>,
+}
+
+// The parts of the output of `cargo metadata` we need.
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    id: String,
+    manifest_path: Utf8PathBuf,
+    dependencies: Vec<Dependency>,
+    targets: Vec<Target>,
+    #[serde(default)]
+    metadata: Option<PackageMetadata>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    crate_types: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PackageMetadata {
+    uniffi: Option<UniffiMetadata>,
+}
+
+#[derive(Deserialize)]
+struct UniffiMetadata {
+    bindings: Option<BindingsMetadata>,
+}
+
+#[derive(Deserialize)]
+struct BindingsMetadata {
+    #[serde(default)]
+    languages: Vec<String>,
+}
+
+/// Find the components of the workspace of `manifest_path`, or of the current directory.
+///
+/// Only the `packages` named are returned if there are any, which fails if one of them isn't a
+/// component.
+pub(crate) fn find_components(
+    manifest_path: Option<&Utf8Path>,
+    packages: &[String],
+) -> Result<Vec<WorkspaceComponent>> {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("metadata")
+        .arg("--format-version=1")
+        .arg("--no-deps");
+    if let Some(manifest_path) = manifest_path {
+        cmd.arg("--manifest-path").arg(manifest_path);
+    }
+    let output = cmd.output().context("Failed to run cargo metadata")?;
+    if !output.status.success() {
+        bail!(
+            "cargo metadata failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let metadata: Metadata = serde_json::from_slice(&output.stdout)?;
+
+    let mut components = Vec::new();
+    // With `--no-deps`, the packages are the members of the workspace.
+    for package in metadata.packages {
+        if !packages.is_empty() && !packages.contains(&package.name) {
+            continue;
+        }
+        let is_component = package.dependencies.iter().any(|dep| dep.name == "uniffi")
+            && package
+                .targets
+                .iter()
+                .any(|target| target.crate_types.iter().any(|t| t == "cdylib"));
+        if !is_component {
+            if packages.contains(&package.name) {
+                bail!(
+                    "{} is not a UniFFI component, it must depend on `uniffi` and build a cdylib",
+                    package.name
+                );
+            }
+            continue;
+        }
+        let crate_root = package
+            .manifest_path
+            .parent()
+            .context("The manifest has no parent directory")?
+            .to_owned();
+        components.push(WorkspaceComponent {
+            udl_files: udl_files(&crate_root)?,
+            languages: package
+                .metadata
+                .and_then(|metadata| metadata.uniffi?.bindings)
+                .map(|bindings| bindings.languages)
+                .unwrap_or_default(),
+            package: package.name,
+            package_id: package.id,
+            crate_root,
+        });
+    }
+
+    for package in packages {
+        if !components.iter().any(|c| &c.package == package) {
+            bail!("No package named {package} in the workspace");
+        }
+    }
+    if components.is_empty() {
+        bail!("No UniFFI component in the workspace");
+    }
+    Ok(components)
+}
+
+// The UDL files in the `src` directory of the crate, sorted for a stable order.
+fn udl_files(crate_root: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let pattern = crate_root.join("src").join("*.udl");
+    let mut udl_files = glob::glob(pattern.as_str())?
+        .map(|path| Ok(Utf8PathBuf::try_from(path?)?))
+        .collect::<Result<Vec<_>>>()?;
+    udl_files.sort();
+    Ok(udl_files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn workspace_manifest() -> Utf8PathBuf {
+        Utf8Path::new(env!("CARGO_MANIFEST_DIR")).join("../Cargo.toml")
+    }
+
+    #[test]
+    fn test_find_components() -> Result<()> {
+        let components = find_components(
+            Some(&workspace_manifest()),
+            &["uniffi-example-arithmetic".to_owned()],
+        )?;
+        assert_eq!(components.len(), 1);
+        let component = &components[0];
+        assert_eq!(component.package, "uniffi-example-arithmetic");
+        assert!(component.crate_root.ends_with("examples/arithmetic"));
+        assert_eq!(
+            component.udl_files,
+            vec![component.crate_root.join("src/arithmetic.udl")]
+        );
+        assert!(component.languages.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_components_errors() {
+        let manifest = workspace_manifest();
+        let err = find_components(Some(&manifest), &["uniffi_bindgen".to_owned()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "uniffi_bindgen is not a UniFFI component, it must depend on `uniffi` and build a cdylib"
+        );
+        let err = find_components(Some(&manifest), &["no-such-package".to_owned()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No package named no-such-package in the workspace"
+        );
+    }
+}
@@ -25,6 +25,12 @@ pub fn generate_scaffolding(udl_file: impl AsRef<Utf8Path>) -> Result<()> {
     let udl_file = udl_file.as_ref();
 
     println!("cargo:rerun-if-changed={udl_file}");
+    // The scaffolding includes the JNI glue of the Kotlin bindings when the `uniffi.toml` file of
+    // the crate asks for it. Cargo always reruns the build script for missing files, so only
+    // watch it when there is one.
+    if Utf8Path::new("uniffi.toml").is_file() {
+        println!("cargo:rerun-if-changed=uniffi.toml");
+    }
     // The UNIFFI_TESTS_DISABLE_EXTENSIONS variable disables some bindings, but it is evaluated
     // at *build* time, so we need to rebuild when it changes.
     println!("cargo:rerun-if-env-changed=UNIFFI_TESTS_DISABLE_EXTENSIONS");
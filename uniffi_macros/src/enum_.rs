@@ -5,10 +5,18 @@ use uniffi_meta::{EnumMetadata, FieldMetadata, VariantMetadata};
 
 use crate::{
     export::metadata::convert::convert_type,
-    util::{assert_type_eq, create_metadata_static_var, try_read_field},
+    util::{
+        assert_type_eq, create_metadata_static_var, encoding_method, extract_docstring,
+        extract_rename, ffi_converter, try_read_field, UniffiToml,
+    },
 };
 
-pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream {
+pub fn expand_enum(
+    input: DeriveInput,
+    module_path: Vec<String>,
+    uniffi_toml: &UniffiToml,
+) -> TokenStream {
+    let compact = uniffi_toml.compact_encoding;
     let variants = match input.data {
         Data::Enum(e) => Some(e.variants),
         _ => None,
@@ -18,15 +26,17 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
 
     let (write_impl, try_read_impl) = match &variants {
         Some(variants) => {
+            let write_variant = encoding_method("write_variant", compact);
+            let read_variant = encoding_method("read_variant", compact);
             let write_match_arms = variants.iter().enumerate().map(|(i, v)| {
                 let v_ident = &v.ident;
                 let fields = v.fields.iter().map(|f| &f.ident);
                 let idx = Index::from(i + 1);
-                let write_fields = v.fields.iter().map(write_field);
+                let write_fields = v.fields.iter().map(|f| write_field(f, compact));
 
                 quote! {
                     Self::#v_ident { #(#fields),* } => {
-                        ::uniffi::deps::bytes::BufMut::put_i32(buf, #idx);
+                        ::uniffi::#write_variant(buf, #idx);
                         #(#write_fields)*
                     }
                 }
@@ -38,7 +48,7 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
             let try_read_match_arms = variants.iter().enumerate().map(|(i, v)| {
                 let idx = Index::from(i + 1);
                 let v_ident = &v.ident;
-                let try_read_fields = v.fields.iter().map(try_read_field);
+                let try_read_fields = v.fields.iter().map(|f| try_read_field(f, compact));
 
                 quote! {
                     #idx => Self::#v_ident { #(#try_read_fields)* },
@@ -46,9 +56,7 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
             });
             let error_format_string = format!("Invalid {ident} enum value: {{}}");
             let try_read_impl = quote! {
-                ::uniffi::check_remaining(buf, 4)?;
-
-                Ok(match ::uniffi::deps::bytes::Buf::get_i32(buf) {
+                Ok(match ::uniffi::#read_variant(buf)? {
                     #(#try_read_match_arms)*
                     v => ::uniffi::deps::anyhow::bail!(#error_format_string, v),
                 })
@@ -63,8 +71,12 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
     };
 
     let meta_static_var = if let Some(variants) = variants {
-        match enum_metadata(ident, variants, module_path) {
-            Ok(metadata) => create_metadata_static_var(ident, metadata.into()),
+        match enum_metadata(ident, variants, &input.attrs, module_path) {
+            Ok(metadata) => create_metadata_static_var(
+                ident,
+                metadata.into(),
+                uniffi_toml.ffi_prefix.as_deref(),
+            ),
             Err(e) => e.into_compile_error(),
         }
     } else {
@@ -75,6 +87,8 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
     let type_assertion = assert_type_eq(ident, quote! { crate::uniffi_types::#ident });
 
     quote! {
+        // The values are serialized with the encoding of the component in both encodings, see
+        // `uniffi::FfiConverter`.
         impl ::uniffi::RustBufferFfiConverter for #ident {
             type RustType = Self;
 
@@ -85,6 +99,14 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
             fn try_read(buf: &mut &[::std::primitive::u8]) -> ::uniffi::deps::anyhow::Result<Self> {
                 #try_read_impl
             }
+
+            fn write_compact(obj: Self, buf: &mut ::std::vec::Vec<u8>) {
+                <Self as ::uniffi::RustBufferFfiConverter>::write(obj, buf)
+            }
+
+            fn try_read_compact(buf: &mut &[::std::primitive::u8]) -> ::uniffi::deps::anyhow::Result<Self> {
+                <Self as ::uniffi::RustBufferFfiConverter>::try_read(buf)
+            }
         }
 
         #meta_static_var
@@ -95,6 +117,7 @@ pub fn expand_enum(input: DeriveInput, module_path: Vec<String>) -> TokenStream
 fn enum_metadata(
     ident: &Ident,
     variants: Punctuated<Variant, Token![,]>,
+    attrs: &[syn::Attribute],
     module_path: Vec<String>,
 ) -> syn::Result<EnumMetadata> {
     let name = ident.to_string();
@@ -107,6 +130,7 @@ fn enum_metadata(
         module_path,
         name,
         variants,
+        docstring: extract_docstring(attrs),
     })
 }
 
@@ -118,7 +142,37 @@ fn variant_metadata(v: &Variant) -> syn::Result<VariantMetadata> {
         .map(|f| field_metadata(f, v))
         .collect::<syn::Result<_>>()?;
 
-    Ok(VariantMetadata { name, fields })
+    let discriminant = v
+        .discriminant
+        .as_ref()
+        .map(|(_, expr)| discriminant_value(expr))
+        .transpose()?;
+
+    Ok(VariantMetadata {
+        name,
+        rename: extract_rename(&v.attrs)?,
+        fields,
+        discriminant,
+        docstring: extract_docstring(&v.attrs),
+    })
+}
+
+fn discriminant_value(expr: &syn::Expr) -> syn::Result<i64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.base10_parse(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => discriminant_value(expr).map(|value| -value),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "UniFFI only supports integer literals as enum discriminants",
+        )),
+    }
 }
 
 fn field_metadata(f: &Field, v: &Variant) -> syn::Result<FieldMetadata> {
@@ -135,15 +189,18 @@ fn field_metadata(f: &Field, v: &Variant) -> syn::Result<FieldMetadata> {
 
     Ok(FieldMetadata {
         name,
+        rename: extract_rename(&f.attrs)?,
         ty: convert_type(&f.ty)?,
+        docstring: extract_docstring(&f.attrs),
     })
 }
 
-pub fn write_field(f: &Field) -> TokenStream {
+pub fn write_field(f: &Field, compact: bool) -> TokenStream {
     let ident = &f.ident;
-    let ty = &f.ty;
+    let ffi_converter = ffi_converter(&f.ty);
+    let write = encoding_method("write", compact);
 
     quote! {
-        <#ty as ::uniffi::FfiConverter>::write(#ident, buf);
+        #ffi_converter::#write(#ident, buf);
     }
 }
@@ -15,7 +15,7 @@ pub use self::metadata::gen_metadata;
 use self::scaffolding::{gen_fn_scaffolding, gen_method_scaffolding};
 use crate::{
     export::metadata::convert::convert_type,
-    util::{assert_type_eq, create_metadata_static_var},
+    util::{assert_type_eq, create_metadata_static_var, UniffiToml},
 };
 
 // TODO(jplatte): Ensure no generics, no async, …
@@ -37,13 +37,20 @@ pub struct Method {
     metadata: MethodMetadata,
 }
 
-pub fn expand_export(metadata: ExportItem, mod_path: &[String]) -> TokenStream {
+pub fn expand_export(
+    metadata: ExportItem,
+    mod_path: &[String],
+    uniffi_toml: &UniffiToml,
+) -> TokenStream {
+    let ffi_prefix = uniffi_toml.ffi_prefix.as_deref();
+    let compact = uniffi_toml.compact_encoding;
     match metadata {
         ExportItem::Function { sig, metadata } => {
             let checksum = checksum(&metadata);
-            let scaffolding = gen_fn_scaffolding(&sig, mod_path, checksum);
+            let scaffolding = gen_fn_scaffolding(&sig, ffi_prefix, compact, mod_path, checksum);
             let type_assertions = fn_type_assertions(&sig);
-            let meta_static_var = create_metadata_static_var(&sig.ident, metadata.into());
+            let meta_static_var =
+                create_metadata_static_var(&sig.ident, metadata.into(), ffi_prefix);
 
             quote! {
                 #scaffolding
@@ -62,12 +69,19 @@ pub fn expand_export(metadata: ExportItem, mod_path: &[String]) -> TokenStream {
                         syn::Error::into_compile_error,
                         |Method { item, metadata }| {
                             let checksum = checksum(&metadata);
-                            let scaffolding =
-                                gen_method_scaffolding(&item.sig, mod_path, checksum, &self_ident);
+                            let scaffolding = gen_method_scaffolding(
+                                &item.sig,
+                                ffi_prefix,
+                                compact,
+                                mod_path,
+                                checksum,
+                                &self_ident,
+                            );
                             let type_assertions = fn_type_assertions(&item.sig);
                             let meta_static_var = create_metadata_static_var(
                                 &format_ident!("{}_{}", metadata.self_name, item.sig.ident),
                                 metadata.into(),
+                                ffi_prefix,
                             );
 
                             quote! {
@@ -107,6 +121,7 @@ fn fn_type_assertions(sig: &syn::Signature) -> TokenStream {
             Type::F32 => quote! { ::std::primitive::f32 },
             Type::F64 => quote! { ::std::primitive::f64 },
             Type::Bool => quote! { ::std::primitive::bool },
+            Type::Char => quote! { ::std::primitive::char },
             Type::String => quote! { ::std::string::String },
             Type::Option { inner_type } => {
                 let inner = convert_type_back(inner_type);
@@ -116,6 +131,14 @@ fn fn_type_assertions(sig: &syn::Signature) -> TokenStream {
                 let inner = convert_type_back(inner_type);
                 quote! { ::std::vec::Vec<#inner> }
             }
+            Type::HashSet { inner_type } => {
+                let inner = convert_type_back(inner_type);
+                quote! { ::std::collections::HashSet<#inner> }
+            }
+            Type::BTreeSet { inner_type } => {
+                let inner = convert_type_back(inner_type);
+                quote! { ::std::collections::BTreeSet<#inner> }
+            }
             Type::HashMap {
                 key_type,
                 value_type,
@@ -124,10 +147,22 @@ fn fn_type_assertions(sig: &syn::Signature) -> TokenStream {
                 let value = convert_type_back(value_type);
                 quote! { ::std::collections::HashMap<#key, #value> }
             }
+            Type::Tuple { element_types } => {
+                let elements = element_types.iter().map(convert_type_back);
+                quote! { ( #(#elements),* ) }
+            }
             Type::ArcObject { object_name } => {
                 let object_ident = format_ident!("{object_name}");
                 quote! { ::std::sync::Arc<crate::uniffi_types::#object_ident> }
             }
+            Type::ArcTraitObject { trait_name } => {
+                let trait_ident = format_ident!("{trait_name}");
+                quote! { ::std::sync::Arc<dyn crate::uniffi_types::#trait_ident> }
+            }
+            Type::BoxTraitObject { trait_name } => {
+                let trait_ident = format_ident!("{trait_name}");
+                quote! { ::std::boxed::Box<dyn crate::uniffi_types::#trait_ident> }
+            }
             Type::Unresolved { name } => {
                 let ident = format_ident!("{name}");
                 quote! { crate::uniffi_types::#ident }
@@ -14,7 +14,7 @@ use self::{function::gen_fn_metadata, impl_::gen_impl_metadata};
 
 pub fn gen_metadata(item: syn::Item, mod_path: &[String]) -> syn::Result<ExportItem> {
     match item {
-        syn::Item::Fn(item) => gen_fn_metadata(item.sig, mod_path),
+        syn::Item::Fn(item) => gen_fn_metadata(item.sig, &item.attrs, mod_path),
         syn::Item::Impl(item) => gen_impl_metadata(item, mod_path),
         // FIXME: Support const / static?
         _ => Err(syn::Error::new(
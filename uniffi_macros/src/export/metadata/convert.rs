@@ -45,6 +45,9 @@ pub(super) fn return_type_metadata(ty: &syn::ReturnType) -> syn::Result<Option<T
 }
 
 pub(crate) fn convert_type(ty: &syn::Type) -> syn::Result<Type> {
+    if let Some(tuple) = type_as_tuple(ty) {
+        return convert_tuple_type(tuple);
+    }
     let type_path = type_as_type_path(ty)?;
 
     if type_path.qself.is_some() {
@@ -110,6 +113,7 @@ fn convert_bare_type_name(ident: &Ident) -> Type {
         "f32" => Type::F32,
         "f64" => Type::F64,
         "bool" => Type::Bool,
+        "char" => Type::Char,
         "String" => Type::String,
         _ => Type::Unresolved { name },
     }
@@ -118,12 +122,22 @@ fn convert_bare_type_name(ident: &Ident) -> Type {
 fn convert_generic_type1(ident: &Ident, arg: &syn::GenericArgument) -> syn::Result<Type> {
     let arg = arg_as_type(arg)?;
     match ident.to_string().as_str() {
-        "Arc" => Ok(Type::ArcObject {
-            object_name: type_as_type_path(arg)?
-                .path
-                .get_ident()
-                .ok_or_else(|| type_not_supported(arg))?
-                .to_string(),
+        "Arc" => Ok(match type_as_trait_object(arg) {
+            Some(trait_object) => Type::ArcTraitObject {
+                trait_name: trait_object_name(trait_object)?,
+            },
+            None => Type::ArcObject {
+                object_name: type_as_type_path(arg)?
+                    .path
+                    .get_ident()
+                    .ok_or_else(|| type_not_supported(arg))?
+                    .to_string(),
+            },
+        }),
+        "Box" => Ok(Type::BoxTraitObject {
+            trait_name: trait_object_name(
+                type_as_trait_object(arg).ok_or_else(|| type_not_supported(arg))?,
+            )?,
         }),
         "Option" => Ok(Type::Option {
             inner_type: convert_type(arg)?.into(),
@@ -131,6 +145,12 @@ fn convert_generic_type1(ident: &Ident, arg: &syn::GenericArgument) -> syn::Resu
         "Vec" => Ok(Type::Vec {
             inner_type: convert_type(arg)?.into(),
         }),
+        "HashSet" => Ok(Type::HashSet {
+            inner_type: convert_type(arg)?.into(),
+        }),
+        "BTreeSet" => Ok(Type::BTreeSet {
+            inner_type: convert_type(arg)?.into(),
+        }),
         _ => Err(type_not_supported(ident)),
     }
 }
@@ -152,6 +172,31 @@ fn convert_generic_type2(
     }
 }
 
+fn convert_tuple_type(tuple: &syn::TypeTuple) -> syn::Result<Type> {
+    if !(2..=3).contains(&tuple.elems.len()) {
+        return Err(syn::Error::new_spanned(
+            tuple,
+            "only tuples of 2 or 3 elements are currently supported by uniffi::export",
+        ));
+    }
+    Ok(Type::Tuple {
+        element_types: tuple
+            .elems
+            .iter()
+            .map(convert_type)
+            .collect::<syn::Result<_>>()?,
+    })
+}
+
+fn type_as_tuple(ty: &syn::Type) -> Option<&syn::TypeTuple> {
+    match ty {
+        syn::Type::Group(g) => type_as_tuple(&g.elem),
+        syn::Type::Paren(p) => type_as_tuple(&p.elem),
+        syn::Type::Tuple(t) => Some(t),
+        _ => None,
+    }
+}
+
 pub(super) fn type_as_type_path(ty: &syn::Type) -> syn::Result<&syn::TypePath> {
     match ty {
         syn::Type::Group(g) => type_as_type_path(&g.elem),
@@ -161,6 +206,32 @@ pub(super) fn type_as_type_path(ty: &syn::Type) -> syn::Result<&syn::TypePath> {
     }
 }
 
+pub(crate) fn type_as_trait_object(ty: &syn::Type) -> Option<&syn::TypeTraitObject> {
+    match ty {
+        syn::Type::Group(g) => type_as_trait_object(&g.elem),
+        syn::Type::Paren(p) => type_as_trait_object(&p.elem),
+        syn::Type::TraitObject(t) => Some(t),
+        _ => None,
+    }
+}
+
+// The name of the trait of `dyn Trait`, which can't have other bounds as the scaffolding only
+// supports `dyn Trait` itself.
+fn trait_object_name(trait_object: &syn::TypeTraitObject) -> syn::Result<String> {
+    let mut bounds = trait_object.bounds.iter();
+    match (bounds.next(), bounds.next()) {
+        (Some(syn::TypeParamBound::Trait(bound)), None) => bound
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .ok_or_else(|| type_not_supported(trait_object)),
+        _ => Err(syn::Error::new_spanned(
+            trait_object,
+            "only `dyn Trait`, without other bounds, is currently supported by uniffi::export",
+        )),
+    }
+}
+
 fn arg_as_type(arg: &syn::GenericArgument) -> syn::Result<&syn::Type> {
     match arg {
         syn::GenericArgument::Type(t) => Ok(t),
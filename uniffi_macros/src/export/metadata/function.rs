@@ -5,10 +5,14 @@
 use uniffi_meta::FnMetadata;
 
 use super::convert::{fn_param_metadata, return_type_metadata};
-use crate::export::ExportItem;
+use crate::{export::ExportItem, util::extract_docstring};
 
-pub(super) fn gen_fn_metadata(sig: syn::Signature, mod_path: &[String]) -> syn::Result<ExportItem> {
-    let metadata = fn_metadata(&sig, mod_path)?;
+pub(super) fn gen_fn_metadata(
+    sig: syn::Signature,
+    attrs: &[syn::Attribute],
+    mod_path: &[String],
+) -> syn::Result<ExportItem> {
+    let metadata = fn_metadata(&sig, attrs, mod_path)?;
 
     Ok(ExportItem::Function {
         sig: Box::new(sig),
@@ -16,11 +20,16 @@ pub(super) fn gen_fn_metadata(sig: syn::Signature, mod_path: &[String]) -> syn::
     })
 }
 
-fn fn_metadata(sig: &syn::Signature, mod_path: &[String]) -> syn::Result<FnMetadata> {
+fn fn_metadata(
+    sig: &syn::Signature,
+    attrs: &[syn::Attribute],
+    mod_path: &[String],
+) -> syn::Result<FnMetadata> {
     Ok(FnMetadata {
         module_path: mod_path.to_owned(),
         name: sig.ident.to_string(),
         inputs: fn_param_metadata(&sig.inputs)?,
         return_type: return_type_metadata(&sig.output)?,
+        docstring: extract_docstring(attrs),
     })
 }
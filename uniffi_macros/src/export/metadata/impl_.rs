@@ -5,7 +5,10 @@
 use uniffi_meta::MethodMetadata;
 
 use super::convert::{fn_param_metadata, return_type_metadata, type_as_type_path};
-use crate::export::{ExportItem, Method};
+use crate::{
+    export::{ExportItem, Method},
+    util::extract_docstring,
+};
 
 pub(super) fn gen_impl_metadata(
     item: syn::ItemImpl,
@@ -80,5 +83,6 @@ fn method_metadata(
         name: f.sig.ident.to_string(),
         inputs: fn_param_metadata(&f.sig.inputs)?,
         return_type: return_type_metadata(&f.sig.output)?,
+        docstring: extract_docstring(&f.attrs),
     })
 }
@@ -6,8 +6,12 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 use syn::{FnArg, Pat, ReturnType, Signature};
 
+use crate::util::{encoding_method, ffi_converter};
+
 pub(super) fn gen_fn_scaffolding(
     sig: &Signature,
+    ffi_prefix: Option<&str>,
+    compact: bool,
     mod_path: &[String],
     checksum: u16,
 ) -> TokenStream {
@@ -15,23 +19,25 @@ pub(super) fn gen_fn_scaffolding(
     let name_s = name.to_string();
 
     let ffi_ident = Ident::new(
-        &uniffi_meta::fn_ffi_symbol_name(mod_path, &name_s, checksum),
+        &uniffi_meta::fn_ffi_symbol_name(ffi_prefix, mod_path, &name_s, checksum),
         Span::call_site(),
     );
 
     const ERROR_MSG: &str =
         "uniffi::export must be used on the impl block, not its containing fn's";
-    let (params, args): (Vec<_>, Vec<_>) = collect_params(&sig.inputs, ERROR_MSG).unzip();
+    let (params, args): (Vec<_>, Vec<_>) = collect_params(&sig.inputs, compact, ERROR_MSG).unzip();
 
     let fn_call = quote! {
         #name(#(#args),*)
     };
 
-    gen_ffi_function(sig, ffi_ident, &params, fn_call)
+    gen_ffi_function(sig, compact, ffi_ident, &params, fn_call)
 }
 
 pub(super) fn gen_method_scaffolding(
     sig: &syn::Signature,
+    ffi_prefix: Option<&str>,
+    compact: bool,
     mod_path: &[String],
     checksum: u16,
     self_ident: &Ident,
@@ -41,7 +47,7 @@ pub(super) fn gen_method_scaffolding(
 
     let ffi_name = format!("impl_{self_ident}_{name_s}");
     let ffi_ident = Ident::new(
-        &uniffi_meta::fn_ffi_symbol_name(mod_path, &ffi_name, checksum),
+        &uniffi_meta::fn_ffi_symbol_name(ffi_prefix, mod_path, &ffi_name, checksum),
         Span::call_site(),
     );
 
@@ -58,11 +64,17 @@ pub(super) fn gen_method_scaffolding(
             params_args.0.push(quote! { this: #ffi_converter::FfiType });
 
             let remaining_args = sig.inputs.iter().skip(1);
-            params_args.extend(collect_params(remaining_args, RECEIVER_ERROR));
+            params_args.extend(collect_params(remaining_args, compact, RECEIVER_ERROR));
 
             quote! {
-                #ffi_converter::try_lift(this).unwrap_or_else(|err| {
-                    ::std::panic!("Failed to convert arg 'self': {}", err)
+                (match #ffi_converter::try_lift(this) {
+                    ::std::result::Result::Ok(val) => val,
+                    ::std::result::Result::Err(err) => {
+                        return ::uniffi::internal_error(::std::format!(
+                            "Failed to convert arg 'self': {}",
+                            err
+                        ))
+                    }
                 }).
             }
         }
@@ -71,7 +83,7 @@ pub(super) fn gen_method_scaffolding(
                 syn::Error::new_spanned(sig, "associated functions are not currently supported")
                     .into_compile_error(),
             );
-            params_args.extend(collect_params(&sig.inputs, RECEIVER_ERROR));
+            params_args.extend(collect_params(&sig.inputs, compact, RECEIVER_ERROR));
             quote! { #self_ident:: }
         }
     };
@@ -83,7 +95,7 @@ pub(super) fn gen_method_scaffolding(
         #fn_call_prefix #name(#(#args),*)
     };
 
-    gen_ffi_function(sig, ffi_ident, &params, fn_call)
+    gen_ffi_function(sig, compact, ffi_ident, &params, fn_call)
 }
 
 fn is_receiver(fn_arg: &FnArg) -> bool {
@@ -95,6 +107,7 @@ fn is_receiver(fn_arg: &FnArg) -> bool {
 
 fn collect_params<'a>(
     inputs: impl IntoIterator<Item = &'a FnArg> + 'a,
+    compact: bool,
     receiver_error_msg: &'static str,
 ) -> impl Iterator<Item = (TokenStream, TokenStream)> + 'a {
     fn receiver_error(
@@ -106,7 +119,7 @@ fn collect_params<'a>(
         (param, arg)
     }
 
-    inputs.into_iter().enumerate().map(|(i, arg)| {
+    inputs.into_iter().enumerate().map(move |(i, arg)| {
         let (ty, name) = match arg {
             FnArg::Receiver(r) => {
                 return receiver_error(r, receiver_error_msg);
@@ -125,16 +138,21 @@ fn collect_params<'a>(
         };
 
         let arg_n = format_ident!("arg{i}");
-        let param = quote! { #arg_n: <#ty as ::uniffi::FfiConverter>::FfiType };
+        let ffi_converter = ffi_converter(ty);
+        let try_lift = encoding_method("try_lift", compact);
+        let param = quote! { #arg_n: #ffi_converter::FfiType };
 
         let panic_fmt = match name {
             Some(name) => format!("Failed to convert arg '{name}': {{}}"),
             None => format!("Failed to convert arg #{i}: {{}}"),
         };
         let arg = quote! {
-            <#ty as ::uniffi::FfiConverter>::try_lift(#arg_n).unwrap_or_else(|err| {
-                ::std::panic!(#panic_fmt, err)
-            })
+            match #ffi_converter::#try_lift(#arg_n) {
+                ::std::result::Result::Ok(val) => val,
+                ::std::result::Result::Err(err) => {
+                    return ::uniffi::internal_error(::std::format!(#panic_fmt, err))
+                }
+            }
         };
 
         (param, arg)
@@ -143,27 +161,35 @@ fn collect_params<'a>(
 
 fn gen_ffi_function(
     sig: &syn::Signature,
+    compact: bool,
     ffi_ident: Ident,
     params: &[TokenStream],
     rust_fn_call: TokenStream,
 ) -> TokenStream {
     let name = &sig.ident;
     let name_s = name.to_string();
+    let ffi_name_s = ffi_ident.to_string();
 
     // FIXME(jplatte): Use an extra trait implemented for `T: FfiConverter` as
     // well as `()` so no different codegen is needed?
-    let (output, return_expr);
+    let (output, call);
     match &sig.output {
         ReturnType::Default => {
             output = None;
-            return_expr = rust_fn_call;
+            call = quote! {
+                ::uniffi::call_with_output::<#compact, _, _>(call_status, || #rust_fn_call)
+            };
         }
         ReturnType::Type(_, ty) => {
+            let ffi_converter = ffi_converter(ty);
+            let try_lower = encoding_method("try_lower", compact);
             output = Some(quote! {
-                -> <#ty as ::uniffi::FfiConverter>::FfiType
+                -> #ffi_converter::FfiType
             });
-            return_expr = quote! {
-                <#ty as ::uniffi::FfiConverter>::lower(#rust_fn_call)
+            call = quote! {
+                ::uniffi::call_with_lowered_result::<#compact, _, _>(call_status, || {
+                    ::std::result::Result::Ok(#ffi_converter::#try_lower(#rust_fn_call)?)
+                })
             };
         }
     }
@@ -176,9 +202,7 @@ fn gen_ffi_function(
             call_status: &mut ::uniffi::RustCallStatus,
         ) #output {
             ::uniffi::deps::log::debug!(#name_s);
-            ::uniffi::call_with_output(call_status, || {
-                #return_expr
-            })
+            ::uniffi::instrument_call(#ffi_name_s, call_status, |call_status| #call)
         }
     }
 }
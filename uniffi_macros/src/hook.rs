@@ -0,0 +1,74 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ReturnType;
+use uniffi_meta::{HookKind, HookMetadata};
+
+use crate::util::{create_metadata_static_var, UniffiToml};
+
+pub fn expand_hook(
+    item: syn::ItemFn,
+    kind: HookKind,
+    mod_path: Vec<String>,
+    uniffi_toml: &UniffiToml,
+) -> syn::Result<TokenStream> {
+    let (attr_name, kind_name) = match kind {
+        HookKind::Init => ("uniffi::init", "init"),
+        HookKind::Shutdown => ("uniffi::shutdown", "shutdown"),
+    };
+    let sig = &item.sig;
+    if !sig.inputs.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.inputs,
+            format!("{attr_name} functions can't take arguments"),
+        ));
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        return Err(syn::Error::new_spanned(
+            ty,
+            format!("{attr_name} functions can't return a value"),
+        ));
+    }
+    if sig.asyncness.is_some() || !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            sig,
+            format!("{attr_name} functions can't be async or generic"),
+        ));
+    }
+
+    let name = &sig.ident;
+    let metadata = HookMetadata {
+        module_path: mod_path,
+        name: name.to_string(),
+        kind,
+    };
+    let ffi_prefix = uniffi_toml.ffi_prefix.as_deref();
+    let ffi_name = metadata.ffi_symbol_name(ffi_prefix);
+    let ffi_ident = Ident::new(&ffi_name, Span::call_site());
+    let meta_static_var = create_metadata_static_var(
+        &format_ident!("{kind_name}_{name}"),
+        metadata.into(),
+        ffi_prefix,
+    );
+
+    let compact = uniffi_toml.compact_encoding;
+
+    // The library may be loaded more than once, e.g. by a Python module which is reloaded, but
+    // the hook only runs the first time.
+    Ok(quote! {
+        #[doc(hidden)]
+        #[no_mangle]
+        pub extern "C" fn #ffi_ident(call_status: &mut ::uniffi::RustCallStatus) {
+            static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+            ::uniffi::deps::log::debug!(#ffi_name);
+            ::uniffi::instrument_call(#ffi_name, call_status, |call_status| {
+                ::uniffi::call_with_output::<#compact, _, _>(call_status, || ONCE.call_once(#name))
+            })
+        }
+
+        #meta_static_var
+    })
+}
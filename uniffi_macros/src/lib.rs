@@ -16,13 +16,15 @@ use util::rewrite_self_type;
 
 mod enum_;
 mod export;
+mod hook;
 mod object;
 mod record;
 mod test;
 mod util;
 
 use self::{
-    enum_::expand_enum, export::expand_export, object::expand_object, record::expand_record,
+    enum_::expand_enum, export::expand_export, hook::expand_hook, object::expand_object,
+    record::expand_record,
 };
 
 /// A macro to build testcases for a component's generated bindings.
@@ -50,6 +52,7 @@ pub fn export(_attr: TokenStream, input: TokenStream) -> TokenStream {
 
     let gen_output = || {
         let mod_path = util::mod_path()?;
+        let uniffi_toml = util::uniffi_toml()?;
         let mut item = syn::parse(input)?;
 
         // If the input is an `impl` block, rewrite any uses of the `Self` type
@@ -59,37 +62,93 @@ pub fn export(_attr: TokenStream, input: TokenStream) -> TokenStream {
         rewrite_self_type(&mut item);
 
         let metadata = export::gen_metadata(item, &mod_path)?;
-        Ok(expand_export(metadata, &mod_path))
+        Ok(expand_export(metadata, &mod_path, &uniffi_toml))
     };
     let output = gen_output().unwrap_or_else(syn::Error::into_compile_error);
+    let uniffi_toml_dependency = util::uniffi_toml_dependency();
 
     quote! {
         #input2
         #output
+        #uniffi_toml_dependency
     }
     .into()
 }
 
-#[proc_macro_derive(Record)]
+/// Mark a function without arguments nor return value, e.g. setting up a logger, to be called once
+/// by the bindings when they load the library, before any other call into it.
+#[proc_macro_attribute]
+pub fn init(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    expand_hook_attr(uniffi_meta::HookKind::Init, input)
+}
+
+/// Mark a function without arguments nor return value, e.g. flushing a cache, to be called once by
+/// the bindings when the process exits.
+#[proc_macro_attribute]
+pub fn shutdown(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    expand_hook_attr(uniffi_meta::HookKind::Shutdown, input)
+}
+
+fn expand_hook_attr(kind: uniffi_meta::HookKind, input: TokenStream) -> TokenStream {
+    let input2 = proc_macro2::TokenStream::from(input.clone());
+
+    let gen_output = || {
+        let mod_path = util::mod_path()?;
+        let uniffi_toml = util::uniffi_toml()?;
+        let item = syn::parse(input)?;
+        expand_hook(item, kind, mod_path, &uniffi_toml)
+    };
+    let output = gen_output().unwrap_or_else(syn::Error::into_compile_error);
+    let uniffi_toml_dependency = util::uniffi_toml_dependency();
+
+    quote! {
+        #input2
+        #output
+        #uniffi_toml_dependency
+    }
+    .into()
+}
+
+#[proc_macro_derive(Record, attributes(uniffi))]
 pub fn derive_record(input: TokenStream) -> TokenStream {
     let mod_path = match util::mod_path() {
         Ok(p) => p,
         Err(e) => return e.into_compile_error().into(),
     };
+    let uniffi_toml = match util::uniffi_toml() {
+        Ok(t) => t,
+        Err(e) => return e.into_compile_error().into(),
+    };
     let input = parse_macro_input!(input);
 
-    expand_record(input, mod_path).into()
+    let output = expand_record(input, mod_path, &uniffi_toml);
+    let uniffi_toml_dependency = util::uniffi_toml_dependency();
+    quote! {
+        #output
+        #uniffi_toml_dependency
+    }
+    .into()
 }
 
-#[proc_macro_derive(Enum)]
+#[proc_macro_derive(Enum, attributes(uniffi))]
 pub fn derive_enum(input: TokenStream) -> TokenStream {
     let mod_path = match util::mod_path() {
         Ok(p) => p,
         Err(e) => return e.into_compile_error().into(),
     };
+    let uniffi_toml = match util::uniffi_toml() {
+        Ok(t) => t,
+        Err(e) => return e.into_compile_error().into(),
+    };
     let input = parse_macro_input!(input);
 
-    expand_enum(input, mod_path).into()
+    let output = expand_enum(input, mod_path, &uniffi_toml);
+    let uniffi_toml_dependency = util::uniffi_toml_dependency();
+    quote! {
+        #output
+        #uniffi_toml_dependency
+    }
+    .into()
 }
 
 #[proc_macro_derive(Object)]
@@ -98,9 +157,19 @@ pub fn derive_object(input: TokenStream) -> TokenStream {
         Ok(p) => p,
         Err(e) => return e.into_compile_error().into(),
     };
+    let uniffi_toml = match util::uniffi_toml() {
+        Ok(t) => t,
+        Err(e) => return e.into_compile_error().into(),
+    };
     let input = parse_macro_input!(input);
 
-    expand_object(input, mod_path).into()
+    let output = expand_object(input, mod_path, &uniffi_toml);
+    let uniffi_toml_dependency = util::uniffi_toml_dependency();
+    quote! {
+        #output
+        #uniffi_toml_dependency
+    }
+    .into()
 }
 
 /// A helper macro to include generated component scaffolding.
@@ -1,35 +1,112 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
-use syn::DeriveInput;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Data, DeriveInput};
 use uniffi_meta::ObjectMetadata;
 
-use crate::util::{assert_type_eq, create_metadata_static_var};
+use crate::util::{assert_type_eq, create_metadata_static_var, extract_docstring, UniffiToml};
 
-pub fn expand_object(input: DeriveInput, module_path: Vec<String>) -> TokenStream {
+pub fn expand_object(
+    input: DeriveInput,
+    module_path: Vec<String>,
+    uniffi_toml: &UniffiToml,
+) -> TokenStream {
+    let ffi_prefix = uniffi_toml.ffi_prefix.as_deref();
     let ident = &input.ident;
     let name = ident.to_string();
-    let metadata = ObjectMetadata { module_path, name };
-    let free_fn_ident = Ident::new(&metadata.free_ffi_symbol_name(), Span::call_site());
-    let meta_static_var = create_metadata_static_var(ident, metadata.into());
+    let metadata = ObjectMetadata {
+        module_path,
+        name: name.clone(),
+        docstring: extract_docstring(&input.attrs),
+    };
+    let free_fn_ident = Ident::new(
+        &metadata.free_ffi_symbol_name(ffi_prefix),
+        Span::call_site(),
+    );
+    let clone_fn_ident = Ident::new(
+        &metadata.clone_ffi_symbol_name(ffi_prefix),
+        Span::call_site(),
+    );
+    let meta_static_var = create_metadata_static_var(ident, metadata.into(), ffi_prefix);
     let type_assertion = assert_type_eq(ident, quote! { crate::uniffi_types::#ident });
+    let send_sync_assertion = assert_fields_send_sync(&input);
+    let compact = uniffi_toml.compact_encoding;
 
     quote! {
         #[doc(hidden)]
         #[no_mangle]
         pub extern "C" fn #free_fn_ident(
-            ptr: *const ::std::ffi::c_void,
+            handle: u64,
             call_status: &mut ::uniffi::RustCallStatus
         ) {
-            uniffi::call_with_output(call_status, || {
-                assert!(!ptr.is_null());
-                let ptr = ptr.cast::<#ident>();
-                unsafe {
-                    ::std::sync::Arc::decrement_strong_count(ptr);
+            #[allow(unused_imports)]
+            use ::uniffi::object_checks::{ObjectIsNotSendSync as _, ObjectIsSendSync as _};
+
+            uniffi::call_with_output::<#compact, _, _>(call_status, || {
+                let object = &&::uniffi::object_checks::ObjectFields::<#ident>(
+                    ::std::marker::PhantomData,
+                );
+                if let Err(err) = object.free_handle(handle) {
+                    ::uniffi::internal_error(::std::format!("Failed to free {}: {}", #name, err))
                 }
             });
         }
 
+        #[doc(hidden)]
+        #[no_mangle]
+        pub extern "C" fn #clone_fn_ident(
+            handle: u64,
+            call_status: &mut ::uniffi::RustCallStatus
+        ) -> u64 {
+            #[allow(unused_imports)]
+            use ::uniffi::object_checks::{ObjectIsNotSendSync as _, ObjectIsSendSync as _};
+
+            uniffi::call_with_output::<#compact, _, _>(call_status, || {
+                let object = &&::uniffi::object_checks::ObjectFields::<#ident>(
+                    ::std::marker::PhantomData,
+                );
+                object.clone_handle(handle).unwrap_or_else(|err| {
+                    ::uniffi::internal_error(::std::format!("Failed to clone {}: {}", #name, err))
+                })
+            })
+        }
+
         #meta_static_var
         #type_assertion
+        #send_sync_assertion
+    }
+}
+
+/// Check that the object is `Send + Sync`, with an error spanned on each field which isn't
+/// otherwise, see `uniffi::object_checks`.
+fn assert_fields_send_sync(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let field_types: Vec<_> = match &input.data {
+        Data::Struct(s) => s.fields.iter().map(|f| &f.ty).collect(),
+        Data::Enum(e) => e
+            .variants
+            .iter()
+            .flat_map(|v| v.fields.iter().map(|f| &f.ty))
+            .collect(),
+        Data::Union(u) => u.fields.named.iter().map(|f| &f.ty).collect(),
+    };
+    let field_checks = field_types.into_iter().map(|ty| {
+        quote_spanned! {ty.span()=>
+            fields.field_must_be_send_and_sync::<#ty>();
+        }
+    });
+
+    quote! {
+        const _: () = {
+            #[allow(unused_imports)]
+            use ::uniffi::object_checks::{ObjectIsNotSendSync as _, ObjectIsSendSync as _};
+
+            #[allow(dead_code, unused_variables)]
+            fn uniffi_object_must_be_send_and_sync() {
+                let fields = &&::uniffi::object_checks::ObjectFields::<#ident>(
+                    ::std::marker::PhantomData,
+                );
+                #(#field_checks)*
+            }
+        };
     }
 }
@@ -5,10 +5,18 @@ use uniffi_meta::{FieldMetadata, RecordMetadata};
 
 use crate::{
     export::metadata::convert::convert_type,
-    util::{assert_type_eq, create_metadata_static_var, try_read_field},
+    util::{
+        assert_type_eq, create_metadata_static_var, encoding_method, extract_docstring,
+        extract_rename, ffi_converter, try_read_field, UniffiToml,
+    },
 };
 
-pub fn expand_record(input: DeriveInput, module_path: Vec<String>) -> TokenStream {
+pub fn expand_record(
+    input: DeriveInput,
+    module_path: Vec<String>,
+    uniffi_toml: &UniffiToml,
+) -> TokenStream {
+    let compact = uniffi_toml.compact_encoding;
     let fields = match input.data {
         Data::Struct(s) => Some(s.fields),
         _ => None,
@@ -18,8 +26,8 @@ pub fn expand_record(input: DeriveInput, module_path: Vec<String>) -> TokenStrea
 
     let (write_impl, try_read_fields) = match &fields {
         Some(fields) => (
-            fields.iter().map(write_field).collect(),
-            fields.iter().map(try_read_field).collect(),
+            fields.iter().map(|f| write_field(f, compact)).collect(),
+            fields.iter().map(|f| try_read_field(f, compact)).collect(),
         ),
         None => {
             let unimplemented = quote! { ::std::unimplemented!() };
@@ -28,8 +36,12 @@ pub fn expand_record(input: DeriveInput, module_path: Vec<String>) -> TokenStrea
     };
 
     let meta_static_var = if let Some(fields) = fields {
-        match record_metadata(ident, fields, module_path) {
-            Ok(metadata) => create_metadata_static_var(ident, metadata.into()),
+        match record_metadata(ident, fields, &input.attrs, module_path) {
+            Ok(metadata) => create_metadata_static_var(
+                ident,
+                metadata.into(),
+                uniffi_toml.ffi_prefix.as_deref(),
+            ),
             Err(e) => e.into_compile_error(),
         }
     } else {
@@ -43,6 +55,8 @@ pub fn expand_record(input: DeriveInput, module_path: Vec<String>) -> TokenStrea
     let type_assertion = assert_type_eq(ident, quote! { crate::uniffi_types::#ident });
 
     quote! {
+        // The values are serialized with the encoding of the component in both encodings, see
+        // `uniffi::FfiConverter`.
         impl ::uniffi::RustBufferFfiConverter for #ident {
             type RustType = Self;
 
@@ -53,6 +67,14 @@ pub fn expand_record(input: DeriveInput, module_path: Vec<String>) -> TokenStrea
             fn try_read(buf: &mut &[::std::primitive::u8]) -> ::uniffi::deps::anyhow::Result<Self> {
                 Ok(Self { #try_read_fields })
             }
+
+            fn write_compact(obj: Self, buf: &mut ::std::vec::Vec<u8>) {
+                <Self as ::uniffi::RustBufferFfiConverter>::write(obj, buf)
+            }
+
+            fn try_read_compact(buf: &mut &[::std::primitive::u8]) -> ::uniffi::deps::anyhow::Result<Self> {
+                <Self as ::uniffi::RustBufferFfiConverter>::try_read(buf)
+            }
         }
 
         #meta_static_var
@@ -63,6 +85,7 @@ pub fn expand_record(input: DeriveInput, module_path: Vec<String>) -> TokenStrea
 fn record_metadata(
     ident: &Ident,
     fields: Fields,
+    attrs: &[syn::Attribute],
     module_path: Vec<String>,
 ) -> syn::Result<RecordMetadata> {
     let name = ident.to_string();
@@ -85,6 +108,7 @@ fn record_metadata(
         module_path,
         name,
         fields,
+        docstring: extract_docstring(attrs),
     })
 }
 
@@ -93,15 +117,18 @@ fn field_metadata(f: &Field) -> syn::Result<FieldMetadata> {
 
     Ok(FieldMetadata {
         name,
+        rename: extract_rename(&f.attrs)?,
         ty: convert_type(&f.ty)?,
+        docstring: extract_docstring(&f.attrs),
     })
 }
 
-pub fn write_field(f: &Field) -> TokenStream {
+pub fn write_field(f: &Field, compact: bool) -> TokenStream {
     let ident = &f.ident;
-    let ty = &f.ty;
+    let ffi_converter = ffi_converter(&f.ty);
+    let write = encoding_method("write", compact);
 
     quote! {
-        <#ty as ::uniffi::FfiConverter>::write(obj.#ident, buf);
+        #ffi_converter::#write(obj.#ident, buf);
     }
 }
@@ -7,6 +7,8 @@ use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{spanned::Spanned, visit_mut::VisitMut, Item, Type};
 use uniffi_meta::Metadata;
 
+use crate::export::metadata::convert::convert_type;
+
 #[cfg(not(feature = "nightly"))]
 pub fn mod_path() -> syn::Result<Vec<String>> {
     // Without the nightly feature and TokenStream::expand_expr, just return the crate name
@@ -56,6 +58,56 @@ pub fn mod_path() -> syn::Result<Vec<String>> {
         .map_err(|e| syn::Error::new(Span::call_site(), e))
 }
 
+// The `uniffi.toml` of the crate being compiled. The proc-macro library can be reused across
+// crates, e.g. by rust-analyzer, so the path is looked up for each expansion, rather than cached.
+fn uniffi_toml_path() -> syn::Result<std::path::PathBuf> {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or_else(|| syn::Error::new(Span::call_site(), "`CARGO_MANIFEST_DIR` is not set"))?;
+    Ok(std::path::Path::new(&manifest_dir).join("uniffi.toml"))
+}
+
+/// The settings of the `uniffi.toml` of the crate which change the code generated by the macros.
+#[derive(Default, serde::Deserialize)]
+pub struct UniffiToml {
+    /// Starts the names of the FFI symbols, see `uniffi_meta::fn_ffi_symbol_name`.
+    pub ffi_prefix: Option<String>,
+    /// Whether the values are serialized with the compact encoding, see `uniffi::FfiConverter`.
+    #[serde(default)]
+    pub compact_encoding: bool,
+}
+
+/// Read the `uniffi.toml` of the crate, if it has one.
+pub fn uniffi_toml() -> syn::Result<UniffiToml> {
+    use fs_err as fs;
+
+    let path = uniffi_toml_path()?;
+    if !path.exists() {
+        return Ok(UniffiToml::default());
+    }
+    let read = || -> Result<UniffiToml, String> {
+        let uniffi_toml_bytes = fs::read(&path).map_err(|e| e.to_string())?;
+        let uniffi_toml = toml::from_slice::<UniffiToml>(&uniffi_toml_bytes)
+            .map_err(|e| format!("Failed to parse `uniffi.toml`: {e}"))?;
+        if let Some(prefix) = &uniffi_toml.ffi_prefix {
+            uniffi_meta::check_ffi_prefix(prefix)?;
+        }
+        Ok(uniffi_toml)
+    };
+    read().map_err(|e| syn::Error::new(Span::call_site(), e))
+}
+
+/// Make the crate depend on its `uniffi.toml`, if it has one, so that it's compiled again when the
+/// settings change.
+pub fn uniffi_toml_dependency() -> TokenStream {
+    match uniffi_toml_path() {
+        Ok(path) if path.exists() => {
+            let path = path.to_string_lossy();
+            quote! { const _: &[u8] = ::core::include_bytes!(#path); }
+        }
+        _ => TokenStream::new(),
+    }
+}
+
 #[cfg(feature = "nightly")]
 pub fn mod_path() -> syn::Result<Vec<String>> {
     use proc_macro::TokenStream;
@@ -123,19 +175,102 @@ pub fn rewrite_self_type(item: &mut Item) {
     }
 }
 
-pub fn try_read_field(f: &syn::Field) -> TokenStream {
+pub fn try_read_field(f: &syn::Field, compact: bool) -> TokenStream {
     let ident = &f.ident;
-    let ty = &f.ty;
+    let ffi_converter = ffi_converter(&f.ty);
+    let try_read = encoding_method("try_read", compact);
 
     quote! {
-        #ident: <#ty as ::uniffi::FfiConverter>::try_read(buf)?,
+        #ident: #ffi_converter::#try_read(buf)?,
+    }
+}
+
+/// The `FfiConverter` implementation of `ty`, which is `ty` itself except for `Arc<dyn Trait>`.
+/// Only `uniffi` can implement the trait for it, with `FfiConverterArcDyn`.
+pub fn ffi_converter(ty: &Type) -> TokenStream {
+    let converter = match convert_type(ty) {
+        Ok(uniffi_meta::Type::ArcTraitObject { trait_name }) => {
+            let trait_ident = format_ident!("{trait_name}");
+            quote! { ::uniffi::FfiConverterArcDyn<dyn crate::uniffi_types::#trait_ident> }
+        }
+        _ => quote! { #ty },
+    };
+    quote! { <#converter as ::uniffi::FfiConverter> }
+}
+
+/// The method `name` of `uniffi::FfiConverter`, or the `uniffi` function, which uses the encoding
+/// of the component: the one with a `_compact` suffix for the compact encoding.
+pub fn encoding_method(name: &str, compact: bool) -> Ident {
+    if compact {
+        format_ident!("{name}_compact")
+    } else {
+        format_ident!("{name}")
+    }
+}
+
+/// Collect the `///` documentation of an item, as written in the source.
+pub fn extract_docstring(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(s),
+                ..
+            })) => Some(s.value()),
+            _ => None,
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
     }
+    // `/// text` becomes `#[doc = " text"]`, so strip the space which follows the slashes.
+    let docstring = lines
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(docstring.trim().to_owned())
 }
 
-pub fn create_metadata_static_var(name: &Ident, val: Metadata) -> TokenStream {
-    let data: Vec<u8> = bincode::serialize(&val).expect("Error serializing metadata item");
+/// Get the name set by a `#[uniffi(rename = "...")]` attribute on a field or variant.
+pub fn extract_rename(attrs: &[syn::Attribute]) -> syn::Result<Option<String>> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("uniffi")) {
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected `uniffi(...)`")),
+        };
+        for nested in list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(name),
+                    ..
+                })) if path.is_ident("rename") => rename = Some(name.value()),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "the only supported UniFFI attribute is `rename = \"...\"`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(rename)
+}
+
+pub fn create_metadata_static_var(
+    name: &Ident,
+    val: Metadata,
+    ffi_prefix: Option<&str>,
+) -> TokenStream {
+    let data = uniffi_meta::serialize_metadata(&val);
     let count = data.len();
-    let var_name = format_ident!("UNIFFI_META_{}", name);
+    let var_name = match ffi_prefix {
+        Some(prefix) => format_ident!("UNIFFI_META_{}_{}", prefix, name),
+        None => format_ident!("UNIFFI_META_{}", name),
+    };
 
     quote! {
         #[no_mangle]
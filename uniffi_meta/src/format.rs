@@ -0,0 +1,209 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The format of the metadata items embedded in the libraries, see
+//! `docs/manual/src/internals/metadata_format.md`.
+//!
+//! Every item is a record made of a fixed-size header followed by its payload:
+//!
+//! | Bytes  | Content                                                          |
+//! |--------|------------------------------------------------------------------|
+//! | 0..8   | [`METADATA_MAGIC`]                                               |
+//! | 8..10  | The [`METADATA_FORMAT_VERSION`] of the record, little-endian u16 |
+//! | 10..12 | The kind of the item, see [`MetadataKind`], little-endian u16    |
+//! | 12..16 | The length of the payload in bytes, little-endian u32            |
+//! | 16..   | The payload, the `bincode` 1.3 serialization of the item         |
+//!
+//! Since the header gives the length of the payload, readers skip the records of the kinds they
+//! don't know, which newer versions of uniffi may add without changing the format version.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::Metadata;
+
+/// The first bytes of every metadata record.
+pub const METADATA_MAGIC: [u8; 8] = *b"UNIFFIMD";
+
+/// The version of the format of the metadata records, bumped on every change to the header or
+/// to the layout of the payload of an existing kind. Readers reject the records of a newer version.
+pub const METADATA_FORMAT_VERSION: u16 = 1;
+
+/// The length of the header of a metadata record, before its payload.
+pub const METADATA_HEADER_LEN: usize = 16;
+
+/// The kind of a metadata record, which is its variant of [`Metadata`].
+///
+/// The values are part of the format: new kinds get new values, existing ones are never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MetadataKind {
+    Func = 0,
+    Method = 1,
+    Record = 2,
+    Enum = 3,
+    Object = 4,
+    Udl = 5,
+    Hook = 6,
+}
+
+impl MetadataKind {
+    /// The kind of the value of a record header, if known to this version.
+    pub fn from_u16(value: u16) -> Option<Self> {
+        Some(match value {
+            0 => Self::Func,
+            1 => Self::Method,
+            2 => Self::Record,
+            3 => Self::Enum,
+            4 => Self::Object,
+            5 => Self::Udl,
+            6 => Self::Hook,
+            _ => return None,
+        })
+    }
+}
+
+/// The header of a metadata record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MetadataHeader {
+    pub format_version: u16,
+    /// The kind of the item, see [`MetadataKind::from_u16`].
+    pub kind: u16,
+    pub payload_len: u32,
+}
+
+impl MetadataHeader {
+    /// Parse the header at the start of `data`, which must start with [`METADATA_MAGIC`].
+    ///
+    /// The format version isn't checked, so that tools can report the one of a record they can't
+    /// read.
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.len() < METADATA_HEADER_LEN {
+            return Err("The metadata record is truncated".to_string());
+        }
+        if data[..8] != METADATA_MAGIC {
+            return Err("The data is not a uniffi metadata record".to_string());
+        }
+        Ok(Self {
+            format_version: u16::from_le_bytes([data[8], data[9]]),
+            kind: u16::from_le_bytes([data[10], data[11]]),
+            payload_len: u32::from_le_bytes([data[12], data[13], data[14], data[15]]),
+        })
+    }
+
+    /// The length of the whole record, header included.
+    pub fn record_len(&self) -> usize {
+        METADATA_HEADER_LEN + self.payload_len as usize
+    }
+}
+
+/// Serialize a metadata item as a record of the current [`METADATA_FORMAT_VERSION`].
+pub fn serialize_metadata(item: &Metadata) -> Vec<u8> {
+    let (kind, payload) = match item {
+        Metadata::Func(meta) => (MetadataKind::Func, serialize_payload(meta)),
+        Metadata::Method(meta) => (MetadataKind::Method, serialize_payload(meta)),
+        Metadata::Record(meta) => (MetadataKind::Record, serialize_payload(meta)),
+        Metadata::Enum(meta) => (MetadataKind::Enum, serialize_payload(meta)),
+        Metadata::Object(meta) => (MetadataKind::Object, serialize_payload(meta)),
+        Metadata::Udl(meta) => (MetadataKind::Udl, serialize_payload(meta)),
+        Metadata::Hook(meta) => (MetadataKind::Hook, serialize_payload(meta)),
+    };
+    let payload_len = u32::try_from(payload.len()).expect("Metadata item too large");
+
+    let mut data = Vec::with_capacity(METADATA_HEADER_LEN + payload.len());
+    data.extend_from_slice(&METADATA_MAGIC);
+    data.extend_from_slice(&METADATA_FORMAT_VERSION.to_le_bytes());
+    data.extend_from_slice(&(kind as u16).to_le_bytes());
+    data.extend_from_slice(&payload_len.to_le_bytes());
+    data.extend_from_slice(&payload);
+    data
+}
+
+fn serialize_payload<T: Serialize>(meta: &T) -> Vec<u8> {
+    bincode::serialize(meta).expect("Error serializing metadata item")
+}
+
+/// Deserialize the metadata record at the start of `data`, which may go on past its end.
+///
+/// Returns `None` for the records of a kind unknown to this version, which the caller skips,
+/// and an error for the ones of a newer [`METADATA_FORMAT_VERSION`].
+pub fn deserialize_metadata(data: &[u8]) -> Result<Option<Metadata>, String> {
+    let header = MetadataHeader::parse(data)?;
+    if header.format_version > METADATA_FORMAT_VERSION {
+        return Err(format!(
+            "The metadata format version {} is newer than the version {METADATA_FORMAT_VERSION} \
+             supported by this version of uniffi, which must be upgraded",
+            header.format_version
+        ));
+    }
+    let payload = data
+        .get(METADATA_HEADER_LEN..header.record_len())
+        .ok_or_else(|| "The metadata record is truncated".to_string())?;
+    let kind = match MetadataKind::from_u16(header.kind) {
+        Some(kind) => kind,
+        None => return Ok(None),
+    };
+    Ok(Some(match kind {
+        MetadataKind::Func => Metadata::Func(deserialize_payload(payload)?),
+        MetadataKind::Method => Metadata::Method(deserialize_payload(payload)?),
+        MetadataKind::Record => Metadata::Record(deserialize_payload(payload)?),
+        MetadataKind::Enum => Metadata::Enum(deserialize_payload(payload)?),
+        MetadataKind::Object => Metadata::Object(deserialize_payload(payload)?),
+        MetadataKind::Udl => Metadata::Udl(deserialize_payload(payload)?),
+        MetadataKind::Hook => Metadata::Hook(deserialize_payload(payload)?),
+    }))
+}
+
+fn deserialize_payload<T: DeserializeOwned>(payload: &[u8]) -> Result<T, String> {
+    bincode::deserialize(payload).map_err(|e| format!("Invalid metadata record: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ObjectMetadata;
+
+    fn object() -> Metadata {
+        Metadata::Object(ObjectMetadata {
+            module_path: vec!["crate_name".to_string()],
+            name: "Object".to_string(),
+            docstring: None,
+        })
+    }
+
+    #[test]
+    fn test_round_trip_with_trailing_data() {
+        let mut data = serialize_metadata(&object());
+        let header = MetadataHeader::parse(&data).unwrap();
+        assert_eq!(header.format_version, METADATA_FORMAT_VERSION);
+        assert_eq!(header.kind, MetadataKind::Object as u16);
+        assert_eq!(header.record_len(), data.len());
+
+        data.extend_from_slice(b"trailing data");
+        match deserialize_metadata(&data).unwrap() {
+            Some(Metadata::Object(meta)) => assert_eq!(meta.name, "Object"),
+            other => panic!("unexpected metadata: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_kinds_are_skipped() {
+        let mut data = serialize_metadata(&object());
+        data[10..12].copy_from_slice(&1000u16.to_le_bytes());
+        assert!(deserialize_metadata(&data).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_newer_versions_and_foreign_data_are_rejected() {
+        let mut data = serialize_metadata(&object());
+        data[8..10].copy_from_slice(&(METADATA_FORMAT_VERSION + 1).to_le_bytes());
+        let err = deserialize_metadata(&data).unwrap_err();
+        assert!(err.contains("must be upgraded"));
+
+        let err = deserialize_metadata(b"not a metadata record").unwrap_err();
+        assert!(err.contains("not a uniffi metadata record"));
+        let data = serialize_metadata(&object());
+        let err = deserialize_metadata(&data[..data.len() - 1]).unwrap_err();
+        assert!(err.contains("truncated"));
+    }
+}
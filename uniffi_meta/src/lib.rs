@@ -9,17 +9,24 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+mod format;
+pub use format::{
+    deserialize_metadata, serialize_metadata, MetadataHeader, MetadataKind,
+    METADATA_FORMAT_VERSION, METADATA_HEADER_LEN, METADATA_MAGIC,
+};
+
 #[derive(Clone, Debug, Hash, Deserialize, Serialize)]
 pub struct FnMetadata {
     pub module_path: Vec<String>,
     pub name: String,
     pub inputs: Vec<FnParamMetadata>,
     pub return_type: Option<Type>,
+    pub docstring: Option<String>,
 }
 
 impl FnMetadata {
-    pub fn ffi_symbol_name(&self) -> String {
-        fn_ffi_symbol_name(&self.module_path, &self.name, checksum(self))
+    pub fn ffi_symbol_name(&self, ffi_prefix: Option<&str>) -> String {
+        fn_ffi_symbol_name(ffi_prefix, &self.module_path, &self.name, checksum(self))
     }
 }
 
@@ -30,12 +37,13 @@ pub struct MethodMetadata {
     pub name: String,
     pub inputs: Vec<FnParamMetadata>,
     pub return_type: Option<Type>,
+    pub docstring: Option<String>,
 }
 
 impl MethodMetadata {
-    pub fn ffi_symbol_name(&self) -> String {
+    pub fn ffi_symbol_name(&self, ffi_prefix: Option<&str>) -> String {
         let full_name = format!("impl_{}_{}", self.self_name, self.name);
-        fn_ffi_symbol_name(&self.module_path, &full_name, checksum(self))
+        fn_ffi_symbol_name(ffi_prefix, &self.module_path, &full_name, checksum(self))
     }
 }
 
@@ -59,6 +67,7 @@ pub enum Type {
     F32,
     F64,
     Bool,
+    Char,
     String,
     Option {
         inner_type: Box<Type>,
@@ -66,13 +75,30 @@ pub enum Type {
     Vec {
         inner_type: Box<Type>,
     },
+    HashSet {
+        inner_type: Box<Type>,
+    },
+    BTreeSet {
+        inner_type: Box<Type>,
+    },
     HashMap {
         key_type: Box<Type>,
         value_type: Box<Type>,
     },
+    Tuple {
+        element_types: Vec<Type>,
+    },
     ArcObject {
         object_name: String,
     },
+    /// `Arc<dyn Trait>`, of a `[Trait]` interface.
+    ArcTraitObject {
+        trait_name: String,
+    },
+    /// `Box<dyn Trait>`, of a callback interface.
+    BoxTraitObject {
+        trait_name: String,
+    },
     Unresolved {
         name: String,
     },
@@ -83,13 +109,17 @@ pub struct RecordMetadata {
     pub module_path: Vec<String>,
     pub name: String,
     pub fields: Vec<FieldMetadata>,
+    pub docstring: Option<String>,
 }
 
 #[derive(Clone, Debug, Hash, Deserialize, Serialize)]
 pub struct FieldMetadata {
     pub name: String,
+    /// The name set with `#[uniffi(rename = "...")]`, used by the bindings instead of `name`.
+    pub rename: Option<String>,
     #[serde(rename = "type")]
     pub ty: Type,
+    pub docstring: Option<String>,
 }
 
 #[derive(Clone, Debug, Hash, Deserialize, Serialize)]
@@ -97,30 +127,96 @@ pub struct EnumMetadata {
     pub module_path: Vec<String>,
     pub name: String,
     pub variants: Vec<VariantMetadata>,
+    pub docstring: Option<String>,
 }
 
 #[derive(Clone, Debug, Hash, Deserialize, Serialize)]
 pub struct VariantMetadata {
     pub name: String,
+    /// The name set with `#[uniffi(rename = "...")]`, used by the bindings instead of `name`.
+    pub rename: Option<String>,
     pub fields: Vec<FieldMetadata>,
+    pub discriminant: Option<i64>,
+    pub docstring: Option<String>,
 }
 
 #[derive(Clone, Debug, Hash, Deserialize, Serialize)]
 pub struct ObjectMetadata {
     pub module_path: Vec<String>,
     pub name: String,
+    pub docstring: Option<String>,
 }
 
 impl ObjectMetadata {
     /// FFI symbol name for the `free` function for this object.
     ///
     /// This function is used to free the memory used by this object.
-    pub fn free_ffi_symbol_name(&self) -> String {
+    pub fn free_ffi_symbol_name(&self, ffi_prefix: Option<&str>) -> String {
         let free_name = format!("object_free_{}", self.name);
-        fn_ffi_symbol_name(&self.module_path, &free_name, checksum(self))
+        fn_ffi_symbol_name(ffi_prefix, &self.module_path, &free_name, checksum(self))
+    }
+
+    /// FFI symbol name for the `clone` function for this object.
+    ///
+    /// This function is used to give the foreign-language code a new reference to this object.
+    pub fn clone_ffi_symbol_name(&self, ffi_prefix: Option<&str>) -> String {
+        let clone_name = format!("object_clone_{}", self.name);
+        fn_ffi_symbol_name(ffi_prefix, &self.module_path, &clone_name, checksum(self))
+    }
+}
+
+/// A function called once by the bindings, marked with `#[uniffi::init]` or
+/// `#[uniffi::shutdown]`.
+#[derive(Clone, Debug, Hash, Deserialize, Serialize)]
+pub struct HookMetadata {
+    pub module_path: Vec<String>,
+    pub name: String,
+    pub kind: HookKind,
+}
+
+impl HookMetadata {
+    pub fn ffi_symbol_name(&self, ffi_prefix: Option<&str>) -> String {
+        let kind = match self.kind {
+            HookKind::Init => "init",
+            HookKind::Shutdown => "shutdown",
+        };
+        let full_name = format!("{kind}_{}", self.name);
+        fn_ffi_symbol_name(ffi_prefix, &self.module_path, &full_name, checksum(self))
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum HookKind {
+    /// Called when the bindings load the library, before any other call into it.
+    Init,
+    /// Called when the process exits.
+    Shutdown,
+}
+
+/// The UDL definitions of a component, which its scaffolding embeds in the library so that the
+/// bindings can be generated from the library alone.
+#[derive(Clone, Debug, Hash, Deserialize, Serialize)]
+pub struct UdlMetadata {
+    pub namespace: String,
+    /// The version of uniffi which generated the scaffolding, which must be the one generating the
+    /// bindings for the checksums to match.
+    pub uniffi_version: String,
+    /// The `ffi_prefix` of the configuration, see [`fn_ffi_symbol_name`].
+    pub ffi_prefix: Option<String>,
+    /// How the component serializes its values.
+    pub value_encoding: ValueEncoding,
+    /// The text of the UDL file, followed by the ones of the files it includes.
+    pub sources: Vec<String>,
+}
+
+/// How a component serializes the values in its `RustBuffer`s, chosen with the
+/// `compact_encoding` setting of its `uniffi.toml`, see `uniffi::FfiConverter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum ValueEncoding {
+    Standard,
+    Compact,
+}
+
 /// Returns the last 16 bits of the value's hash as computed with [`DefaultHasher`].
 ///
 /// To be used as a checksum of FFI symbols, as a safeguard against different UniFFI versions being
@@ -131,12 +227,44 @@ pub fn checksum<T: Hash>(val: &T) -> u16 {
     (hasher.finish() & 0x000000000000FFFF) as u16
 }
 
-pub fn fn_ffi_symbol_name(mod_path: &[String], name: &str, checksum: u16) -> String {
+/// The name of the FFI symbol of an exported function.
+///
+/// `ffi_prefix` is the `ffi_prefix` of the `uniffi.toml` of the crate, which keeps the symbols
+/// of several libraries built with uniffi apart when they are linked into the same binary.
+pub fn fn_ffi_symbol_name(
+    ffi_prefix: Option<&str>,
+    mod_path: &[String],
+    name: &str,
+    checksum: u16,
+) -> String {
     let mod_path = mod_path.join("__");
-    format!("_uniffi_{mod_path}_{name}_{checksum:x}")
+    match ffi_prefix {
+        Some(prefix) => format!("_uniffi_{prefix}_{mod_path}_{name}_{checksum:x}"),
+        None => format!("_uniffi_{mod_path}_{name}_{checksum:x}"),
+    }
+}
+
+/// Check that an `ffi_prefix` can start the names of C symbols: it must be made of ASCII
+/// letters, digits and underscores, and not start with a digit.
+pub fn check_ffi_prefix(prefix: &str) -> Result<(), String> {
+    let is_valid = prefix.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "The FFI prefix `{prefix}` must be made of ASCII letters, digits and underscores, \
+             and not start with a digit"
+        ))
+    }
 }
 
 /// Enum covering all the possible metadata types
+///
+/// The items are embedded in the libraries as records serialized with [`serialize_metadata`],
+/// where each variant has its own [`MetadataKind`].
 #[derive(Clone, Debug, Hash, Deserialize, Serialize)]
 pub enum Metadata {
     Func(FnMetadata),
@@ -144,6 +272,8 @@ pub enum Metadata {
     Record(RecordMetadata),
     Enum(EnumMetadata),
     Object(ObjectMetadata),
+    Udl(UdlMetadata),
+    Hook(HookMetadata),
 }
 
 impl From<FnMetadata> for Metadata {
@@ -175,3 +305,15 @@ impl From<ObjectMetadata> for Metadata {
         Self::Object(v)
     }
 }
+
+impl From<UdlMetadata> for Metadata {
+    fn from(v: UdlMetadata) -> Self {
+        Self::Udl(v)
+    }
+}
+
+impl From<HookMetadata> for Metadata {
+    fn from(v: HookMetadata) -> Self {
+        Self::Hook(v)
+    }
+}
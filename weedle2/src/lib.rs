@@ -221,6 +221,12 @@ ast_types! {
             rhs_identifier: Identifier<'a>,
             semi_colon: term!(;),
         }),
+        /// Parses `include "path";`
+        Include(struct IncludeDefinition<'a> {
+            include: term!(include),
+            path: StringLit<'a>,
+            semi_colon: term!(;),
+        }),
     }
 }
 
@@ -239,6 +245,12 @@ mod test {
         rhs_identifier.0 == "second";
     });
 
+    test!(should_parse_include { r#"include "other.udl";"# =>
+        "";
+        IncludeDefinition;
+        path.0 == "other.udl";
+    });
+
     test!(should_parse_typedef { "typedef short Short;" =>
         "";
         TypedefDefinition;
@@ -1,7 +1,8 @@
 use crate::argument::ArgumentList;
 use crate::attribute::ExtendedAttributeList;
 use crate::common::{Identifier, Parenthesized};
-use crate::types::{AttributedType, ReturnType};
+use crate::literal::DefaultValue;
+use crate::types::{AttributedType, ReturnType, Type};
 
 /// Parses namespace members declaration
 pub type NamespaceMembers<'a> = Vec<NamespaceMember<'a>>;
@@ -28,6 +29,19 @@ ast_types! {
             identifier: Identifier<'a>,
             semi_colon: term!(;),
         }),
+        /// Parses `[attribute]? const type identifier = value;`
+        ///
+        /// Unlike the const members of interfaces, the type can be any type and the value any
+        /// default value.
+        Const(struct ConstNamespaceMember<'a> {
+            attributes: Option<ExtendedAttributeList<'a>>,
+            const_: term!(const),
+            type_: Type<'a>,
+            identifier: Identifier<'a>,
+            assign: term!(=),
+            value: DefaultValue<'a>,
+            semi_colon: term!(;),
+        }),
     }
 }
 
@@ -49,4 +63,17 @@ mod test {
         attributes.is_none();
         identifier.is_none();
     });
+
+    test!(should_parse_const_namespace_member { "const u32 MAX_ITEMS = 100;" =>
+        "";
+        ConstNamespaceMember;
+        attributes.is_none();
+        identifier.0 == "MAX_ITEMS";
+    });
+
+    test!(should_parse_string_const_namespace_member { "const string GREETING = \"hello\";" =>
+        "";
+        ConstNamespaceMember;
+        identifier.0 == "GREETING";
+    });
 }
@@ -249,6 +249,21 @@ generate_terms_for_names! {
     /// Represents the terminal symbol `record`
     Record => "record",
 
+    /// Represents the terminal symbol `tuple`
+    Tuple => "tuple",
+
+    /// Represents the terminal symbol `set`
+    Set => "set",
+
+    /// Represents the terminal symbol `result`
+    Result => "result",
+
+    /// Represents the terminal symbol `weak`
+    Weak => "weak",
+
+    /// Represents the terminal symbol `iterator`
+    Iterator => "iterator",
+
     /// Represents the terminal symbol `ArrayBuffer`
     ArrayBuffer => "ArrayBuffer",
 
@@ -303,6 +318,9 @@ generate_terms_for_names! {
     /// Represents the terminal symbol `implements`
     Implements => "implements",
 
+    /// Represents the terminal symbol `include`
+    Include => "include",
+
     /// Represents the terminal symbol `legacycaller`
     LegacyCaller => "legacycaller",
 
@@ -504,6 +522,21 @@ macro_rules! term {
     (record) => {
         $crate::term::Record
     };
+    (tuple) => {
+        $crate::term::Tuple
+    };
+    (set) => {
+        $crate::term::Set
+    };
+    (result) => {
+        $crate::term::Result
+    };
+    (weak) => {
+        $crate::term::Weak
+    };
+    (iterator) => {
+        $crate::term::Iterator
+    };
     (ArrayBuffer) => {
         $crate::term::ArrayBuffer
     };
@@ -558,6 +591,9 @@ macro_rules! term {
     (implements) => {
         $crate::term::Implements
     };
+    (include) => {
+        $crate::term::Include
+    };
     (legacycaller) => {
         $crate::term::LegacyCaller
     };
@@ -679,6 +715,11 @@ mod test {
         unsigned, Unsigned, "unsigned";
         undefined, Undefined, "undefined";
         record, Record, "record";
+        tuple, Tuple, "tuple";
+        set, Set, "set";
+        result, Result, "result";
+        weak, Weak, "weak";
+        iterator, Iterator, "iterator";
         arraybuffer, ArrayBuffer, "ArrayBuffer";
         dataview, DataView, "DataView";
         int8array, Int8Array, "Int8Array";
@@ -693,6 +734,7 @@ mod test {
         promise, Promise, "Promise";
         error, Error, "Error";
         implements, Implements, "implements";
+        include, Include, "include";
         legacycaller, LegacyCaller, "legacycaller";
         constructor, Constructor, "constructor";
     ];
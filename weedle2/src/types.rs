@@ -1,5 +1,5 @@
 use crate::attribute::ExtendedAttributeList;
-use crate::common::{Generics, Identifier, Parenthesized, Punctuated};
+use crate::common::{Generics, Identifier, Parenthesized, Punctuated, PunctuatedNonEmpty};
 use crate::term;
 use crate::Parse;
 
@@ -47,6 +47,11 @@ ast_types! {
         BufferSource(MayBeNull<term!(BufferSource)>),
         FrozenArrayType(MayBeNull<FrozenArrayType<'a>>),
         RecordType(MayBeNull<RecordType<'a>>),
+        TupleType(MayBeNull<TupleType<'a>>),
+        SetType(MayBeNull<SetType<'a>>),
+        ResultType(MayBeNull<ResultType<'a>>),
+        WeakType(MayBeNull<WeakType<'a>>),
+        IteratorType(MayBeNull<IteratorType<'a>>),
         Identifier(MayBeNull<Identifier<'a>>),
     }
 
@@ -123,6 +128,36 @@ ast_types! {
         generics: Generics<(Box<RecordKeyType<'a>>, term!(,), Box<Type<'a>>)>,
     }
 
+    /// Parses `tuple<Type, Type, ...>`
+    struct TupleType<'a> {
+        tuple: term!(tuple),
+        generics: Generics<PunctuatedNonEmpty<Type<'a>, term!(,)>>,
+    }
+
+    /// Parses `set<Type>`
+    struct SetType<'a> {
+        set: term!(set),
+        generics: Generics<Box<Type<'a>>>,
+    }
+
+    /// Parses `result<Type, Type>`
+    struct ResultType<'a> {
+        result: term!(result),
+        generics: Generics<(Box<Type<'a>>, term!(,), Box<Type<'a>>)>,
+    }
+
+    /// Parses `weak<Type>`
+    struct WeakType<'a> {
+        weak: term!(weak),
+        generics: Generics<Box<Type<'a>>>,
+    }
+
+    /// Parses `iterator<Type>`
+    struct IteratorType<'a> {
+        iterator: term!(iterator),
+        generics: Generics<Box<Type<'a>>>,
+    }
+
     /// Parses one of the string types `ByteString|DOMString|USVString` or any other type.
     enum RecordKeyType<'a> {
         Byte(term!(ByteString)),
@@ -230,6 +265,11 @@ mod test {
             BufferSource == "BufferSource",
             FrozenArrayType == "FrozenArray<short>",
             RecordType == "record<DOMString, short>",
+            TupleType == "tuple<short, DOMString>",
+            SetType == "set<short>",
+            ResultType == "result<short, DOMString>",
+            WeakType == "weak<Node>",
+            IteratorType == "iterator<short>",
             Identifier == "mango"
         }
     );
@@ -314,6 +354,32 @@ mod test {
         SequenceType;
     });
 
+    test!(should_parse_tuple_type { "tuple<short, sequence<DOMString>, boolean>" =>
+        "";
+        TupleType;
+        generics.body.list.len() == 3;
+    });
+
+    test!(should_parse_set_type { "set<DOMString>" =>
+        "";
+        SetType;
+    });
+
+    test!(should_parse_result_type { "result<DOMString, Error>" =>
+        "";
+        ResultType;
+    });
+
+    test!(should_parse_weak_type { "weak<Node>" =>
+        "";
+        WeakType;
+    });
+
+    test!(should_parse_iterator_type { "iterator<short>" =>
+        "";
+        IteratorType;
+    });
+
     test_variants!(
         SingleType {
             Any == "any",